@@ -0,0 +1,348 @@
+//! Keeps a tool loop's history within the model's context window: long tool
+//! outputs are truncated as they arrive, and older tool results are elided
+//! once the conversation nears the limit. The full text stays readable
+//! through the `tool_output` tool.
+
+use super::token_estimate::{estimate_message_tokens, estimate_tokens, provider_token_factor};
+use crate::config::ContextConfig;
+use crate::llm::types::{ContentBlock, MessageRole, ProviderMessage};
+use crate::tools::{ToolOutputStore, ToolSpec};
+use std::path::Path;
+
+/// Rough characters per token, matching [`estimate_tokens`].
+const CHARS_PER_TOKEN: u64 = 4;
+/// Context window assumed for models missing from [`MODEL_CONTEXT_LIMITS`].
+const DEFAULT_CONTEXT_TOKENS: u64 = 32_768;
+/// Tool results shorter than this are never elided; the notice would save
+/// little.
+const MIN_ELIDED_CHARS: usize = 400;
+const ELIDED_PREFIX: &str = "[elided ";
+
+/// Known context windows, matched against the model name (minus any
+/// `vendor/` prefix) by prefix. More specific names come first.
+const MODEL_CONTEXT_LIMITS: &[(&str, u64)] = &[
+    ("claude", 200_000),
+    ("gpt-4.1", 1_047_576),
+    ("gpt-4o", 128_000),
+    ("gpt-4-turbo", 128_000),
+    ("gpt-4", 8_192),
+    ("gpt-3.5", 16_385),
+    ("gpt-5", 400_000),
+    ("o1", 200_000),
+    ("o3", 200_000),
+    ("o4", 200_000),
+    ("gemini", 1_048_576),
+    ("llama3.1", 131_072),
+    ("llama3.2", 131_072),
+    ("llama3.3", 131_072),
+    ("llama3", 8_192),
+    ("deepseek", 65_536),
+    ("mistral-large", 131_072),
+    ("mistral", 32_768),
+    ("qwen", 32_768),
+];
+
+/// Context window of `model` in tokens: the configured override, else the
+/// built-in table, else [`DEFAULT_CONTEXT_TOKENS`].
+pub fn model_context_limit(config: &ContextConfig, model: &str) -> u64 {
+    if let Some(limit) = config.model_limits.get(model) {
+        return *limit;
+    }
+    let name = model
+        .rsplit('/')
+        .next()
+        .unwrap_or(model)
+        .to_ascii_lowercase();
+    MODEL_CONTEXT_LIMITS
+        .iter()
+        .find(|(prefix, _)| name.starts_with(prefix))
+        .map_or(DEFAULT_CONTEXT_TOKENS, |(_, limit)| *limit)
+}
+
+/// Output token limit of `model` from [`ContextConfig::max_output_tokens`],
+/// using the longest matching prefix. `None` when no entry matches.
+pub fn model_output_limit(config: &ContextConfig, model: &str) -> Option<u32> {
+    config
+        .max_output_tokens
+        .iter()
+        .filter(|(prefix, _)| model.starts_with(prefix.as_str()))
+        .max_by_key(|(prefix, _)| prefix.len())
+        .map(|(_, limit)| *limit)
+}
+
+/// Context budget for one tool-loop run.
+pub struct ContextWindow {
+    limit: u64,
+    elide_at: u64,
+    max_tool_output_chars: usize,
+    token_factor: f64,
+    outputs: ToolOutputStore,
+}
+
+impl ContextWindow {
+    pub fn new(
+        config: &ContextConfig,
+        model: &str,
+        provider_name: &str,
+        workspace_dir: &Path,
+    ) -> Self {
+        let limit = model_context_limit(config, model);
+        #[allow(
+            clippy::cast_possible_truncation,
+            clippy::cast_precision_loss,
+            clippy::cast_sign_loss
+        )]
+        let elide_at = (limit as f64 * config.elide_ratio.clamp(0.1, 1.0)) as u64;
+        // A single output may take at most a quarter of the window.
+        let quarter_window_chars =
+            usize::try_from(limit * CHARS_PER_TOKEN / 4).unwrap_or(usize::MAX);
+
+        Self {
+            limit,
+            elide_at,
+            max_tool_output_chars: config.max_tool_output_chars.min(quarter_window_chars),
+            token_factor: provider_token_factor(provider_name),
+            outputs: ToolOutputStore::new(workspace_dir),
+        }
+    }
+
+    pub fn limit(&self) -> u64 {
+        self.limit
+    }
+
+    /// Estimated tokens for a request with this prompt, tools and history.
+    pub fn estimate(
+        &self,
+        system_prompt: &str,
+        tools: &[ToolSpec],
+        messages: &[ProviderMessage],
+    ) -> u64 {
+        let tools_json = serde_json::to_string(tools).unwrap_or_default();
+        self.scaled(
+            estimate_tokens(system_prompt)
+                + estimate_tokens(&tools_json)
+                + estimate_message_tokens(messages),
+        )
+    }
+
+    /// Tool output as it should be sent to the model. Outputs over the
+    /// limit keep their start and end, and the full text is saved under a
+    /// handle named in the notice.
+    pub async fn clip_tool_output(&self, content: String) -> String {
+        let total = content.chars().count();
+        if total <= self.max_tool_output_chars {
+            return content;
+        }
+
+        let tail_chars = self.max_tool_output_chars / 4;
+        let head_chars = self.max_tool_output_chars - tail_chars;
+        let head: String = content.chars().take(head_chars).collect();
+        let tail: String = content.chars().skip(total - tail_chars).collect();
+        let omitted = total - head_chars - tail_chars;
+
+        let retrieval = match self.outputs.save(&content).await {
+            Ok(handle) => format!("call tool_output with handle \"{handle}\" to read it"),
+            Err(error) => {
+                tracing::warn!(%error, "failed to save truncated tool output");
+                "the full output could not be saved".to_string()
+            }
+        };
+        format!(
+            "{head}\n\n[output truncated: {omitted} of {total} characters omitted; {retrieval}]\n\n{tail}"
+        )
+    }
+
+    /// Elide older tool results, oldest first, until the request estimate is
+    /// under the elision threshold. Results the model has not yet seen (after
+    /// the last assistant message) are kept. Returns how many were elided.
+    pub async fn fit(
+        &self,
+        system_prompt: &str,
+        tools: &[ToolSpec],
+        messages: &mut [ProviderMessage],
+    ) -> usize {
+        let mut estimate = self.estimate(system_prompt, tools, messages);
+        if estimate <= self.elide_at {
+            return 0;
+        }
+
+        let seen = messages
+            .iter()
+            .rposition(|message| message.role == MessageRole::Assistant)
+            .unwrap_or(0);
+        let mut elided = 0;
+        for message in &mut messages[..seen] {
+            for block in &mut message.content {
+                if estimate <= self.elide_at {
+                    return elided;
+                }
+                let ContentBlock::ToolResult { content, .. } = block else {
+                    continue;
+                };
+                if content.len() < MIN_ELIDED_CHARS || content.starts_with(ELIDED_PREFIX) {
+                    continue;
+                }
+
+                let notice = self.elision_notice(content).await;
+                let saved = estimate_tokens(content).saturating_sub(estimate_tokens(&notice));
+                estimate = estimate.saturating_sub(self.scaled(saved));
+                *content = notice;
+                elided += 1;
+            }
+        }
+
+        if estimate > self.limit {
+            tracing::warn!(
+                estimate,
+                limit = self.limit,
+                "tool loop history still exceeds the context window after elision"
+            );
+        }
+        elided
+    }
+
+    async fn elision_notice(&self, content: &str) -> String {
+        let chars = content.chars().count();
+        match self.outputs.save(content).await {
+            Ok(handle) => format!(
+                "{ELIDED_PREFIX}{chars} characters of earlier tool output to fit the context window; call tool_output with handle \"{handle}\" to read it]"
+            ),
+            Err(error) => {
+                tracing::warn!(%error, "failed to save elided tool output");
+                format!(
+                    "{ELIDED_PREFIX}{chars} characters of earlier tool output to fit the context window]"
+                )
+            }
+        }
+    }
+
+    #[allow(
+        clippy::cast_possible_truncation,
+        clippy::cast_precision_loss,
+        clippy::cast_sign_loss
+    )]
+    fn scaled(&self, tokens: u64) -> u64 {
+        (tokens as f64 * self.token_factor).ceil() as u64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn window(dir: &Path, limit: u64, max_tool_output_chars: usize) -> ContextWindow {
+        let config = ContextConfig {
+            model_limits: [("test-model".to_string(), limit)].into(),
+            elide_ratio: 0.5,
+            max_tool_output_chars,
+            ..ContextConfig::default()
+        };
+        ContextWindow::new(&config, "test-model", "anthropic", dir)
+    }
+
+    fn assistant(text: &str) -> ProviderMessage {
+        ProviderMessage {
+            role: MessageRole::Assistant,
+            content: vec![ContentBlock::Text {
+                text: text.to_string(),
+            }],
+        }
+    }
+
+    fn handle_in(text: &str) -> &str {
+        let start = text.find("handle \"").unwrap() + "handle \"".len();
+        let len = text[start..].find('"').unwrap();
+        &text[start..start + len]
+    }
+
+    #[test]
+    fn output_limit_uses_the_longest_matching_prefix() {
+        let mut config = ContextConfig::default();
+        assert_eq!(
+            model_output_limit(&config, "claude-opus-4-20250514"),
+            Some(32_000)
+        );
+        assert_eq!(model_output_limit(&config, "gpt-4o"), None);
+
+        config.max_output_tokens = [
+            ("claude".to_string(), 4_096),
+            ("claude-opus-4-1".to_string(), 16_000),
+        ]
+        .into();
+        assert_eq!(
+            model_output_limit(&config, "claude-opus-4-1-20250805"),
+            Some(16_000)
+        );
+        assert_eq!(
+            model_output_limit(&config, "claude-opus-4-20250514"),
+            Some(4_096)
+        );
+    }
+
+    #[test]
+    fn limits_come_from_config_then_table() {
+        let config = ContextConfig {
+            model_limits: [("llama3:8b".to_string(), 4_096)].into(),
+            ..ContextConfig::default()
+        };
+        assert_eq!(model_context_limit(&config, "llama3:8b"), 4_096);
+        assert_eq!(
+            model_context_limit(&config, "claude-sonnet-4-20250514"),
+            200_000
+        );
+        assert_eq!(model_context_limit(&config, "openai/gpt-4o-mini"), 128_000);
+        assert_eq!(model_context_limit(&config, "llama3.1:70b"), 131_072);
+        assert_eq!(
+            model_context_limit(&config, "unknown-model"),
+            DEFAULT_CONTEXT_TOKENS
+        );
+    }
+
+    #[tokio::test]
+    async fn long_output_keeps_head_and_tail_with_a_handle() {
+        let dir = tempfile::tempdir().unwrap();
+        let window = window(dir.path(), 100_000, 100);
+        let output = format!("{}{}", "a".repeat(150), "z".repeat(50));
+
+        let clipped = window.clip_tool_output(output.clone()).await;
+
+        assert!(clipped.starts_with(&"a".repeat(75)));
+        assert!(clipped.ends_with(&"z".repeat(25)));
+        assert!(clipped.contains("100 of 200 characters omitted"));
+        let saved = window.outputs.load(handle_in(&clipped)).await.unwrap();
+        assert_eq!(saved.as_deref(), Some(output.as_str()));
+
+        let short = window.clip_tool_output("ok".to_string()).await;
+        assert_eq!(short, "ok");
+    }
+
+    #[tokio::test]
+    async fn fit_elides_oldest_seen_results_first() {
+        let dir = tempfile::tempdir().unwrap();
+        let window = window(dir.path(), 1_000, 16_000);
+        let big = "x".repeat(1_200);
+        let mut messages = vec![
+            ProviderMessage::user("go"),
+            assistant("calling"),
+            ProviderMessage::tool_result("t1", big.clone(), false),
+            assistant("calling again"),
+            ProviderMessage::tool_result("t2", big.clone(), false),
+            assistant("and again"),
+            ProviderMessage::tool_result("t3", big.clone(), false),
+        ];
+
+        let elided = window.fit("system", &[], &mut messages).await;
+
+        let content = |index: usize| match &messages[index].content[0] {
+            ContentBlock::ToolResult { content, .. } => content.clone(),
+            _ => unreachable!(),
+        };
+        assert_eq!(elided, 2);
+        assert!(content(2).starts_with(ELIDED_PREFIX));
+        assert!(content(4).starts_with(ELIDED_PREFIX));
+        assert_eq!(content(6), big);
+        let saved = window.outputs.load(handle_in(&content(2))).await.unwrap();
+        assert_eq!(saved.as_deref(), Some(big.as_str()));
+        assert!(window.estimate("system", &[], &messages) <= 500);
+    }
+}
@@ -1,4 +1,5 @@
 use crate::memory::{Memory, MemoryRecallItem, RecallQuery};
+use crate::runtime::observability::{ObserverEvent, global_observer};
 use crate::security::external_content::{
     ExternalAction, decide_external_action, detect_injection_signals, sanitize_marker_collision,
     wrap_external_content,
@@ -6,6 +7,7 @@ use crate::security::external_content::{
 use crate::security::policy::TenantPolicyContext;
 use anyhow::Result;
 use std::fmt::Write;
+use std::time::Instant;
 
 fn sanitize_external_fragment_for_context(slot_key: &str, value: &str) -> String {
     if !value.contains("digest_sha256=") {
@@ -73,7 +75,15 @@ pub(super) async fn build_context_with_policy(
 
     // Pull relevant memories for this message
     let query = build_context_recall_query(entity_id, user_msg, policy_context)?;
-    let entries = mem.recall_scoped(query).await?;
+    let started = Instant::now();
+    let recalled = mem.recall_scoped(query).await;
+    global_observer().record_event(&ObserverEvent::MemoryRecall {
+        entity_id: entity_id.to_string(),
+        results: recalled.as_ref().map_or(0, Vec::len),
+        duration: started.elapsed(),
+        success: recalled.is_ok(),
+    });
+    let entries = recalled?;
     let mut replayable_entries = Vec::with_capacity(entries.len());
     for entry in entries {
         if allow_context_replay_item(mem, &entry).await {
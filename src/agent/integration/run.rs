@@ -1,5 +1,6 @@
 use crate::config::Config;
 use crate::memory::Memory;
+use crate::plugins::skills::load_skill_tools;
 use crate::tools::middleware::default_middleware_chain;
 use crate::tools::{self, ToolRegistry};
 use std::sync::Arc;
@@ -8,8 +9,9 @@ use std::sync::Arc;
 ///
 /// Session code (`session.rs`) delegates here so that both the main-session
 /// path and the integration-test path share one tool-initialisation routine.
-pub(super) fn init_tools(_config: &Config, mem: &Arc<dyn Memory>) -> Arc<ToolRegistry> {
-    let tools = tools::all_tools(Arc::clone(mem));
+pub(super) fn init_tools(config: &Config, mem: &Arc<dyn Memory>) -> Arc<ToolRegistry> {
+    let mut tools = tools::all_tools(Arc::clone(mem));
+    tools.extend(load_skill_tools(&config.workspace_dir, &config.skills));
     let middleware = default_middleware_chain();
     let mut registry = ToolRegistry::new(middleware);
     for tool in tools {
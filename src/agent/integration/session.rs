@@ -12,28 +12,35 @@ use super::verify_repair::{
 
 use crate::agent::{LoopStopReason, PromptHook, ToolLoop, ToolLoopResult, ToolLoopRunParams};
 use crate::config::Config;
-use crate::llm::ProviderMessage;
-use crate::llm::{CliStreamSink, StreamSink};
+use crate::llm::{CliStreamSink, StreamSink, ThinkingLevel};
+use crate::llm::{ContentBlock, MessageRole, ProviderMessage};
 use crate::memory::{
     self, Memory, MemoryEventInput, MemoryEventType, MemoryLayer, MemoryProvenance, MemorySource,
     PrivacyLevel, SourceKind,
 };
 use crate::persona::person_identity::resolve_person_id;
-use crate::planner::{ExecutionReport, Plan, PlanExecutor, PlanParser, StepStatus, ToolStepRunner};
-use crate::runtime::observability::traits::AutonomyLifecycleSignal;
-use crate::runtime::observability::{NoopObserver, Observer};
+use crate::planner::{
+    ExecutionPolicy, ExecutionReport, Plan, PlanExecutor, PlanParser, PlanPolicyContext, PlanStore,
+    PromptStepRunner, StepStatus, ToolStepRunner,
+};
+use crate::runtime::observability::trace::{SpanContext, TurnGuard, in_span};
+use crate::runtime::observability::traits::{AutonomyLifecycleSignal, ObserverMetric};
+use crate::runtime::observability::{Observer, ObserverEvent, global_observer};
 use crate::security::SecurityPolicy;
 use crate::security::policy::{AutonomyLevel, EntityRateLimiter, TenantPolicyContext};
 use crate::security::writeback_guard::enforce_agent_autosave_write_policy;
 use crate::tools::middleware::ExecutionContext;
 use crate::utils::text::truncate_with_ellipsis;
 use anyhow::{Context, Result};
+use std::path::Path;
 use std::sync::Arc;
+use uuid::Uuid;
 
 pub(super) struct MainSessionRuntimeOptions<'a> {
     execution_context_override: Option<ExecutionContext>,
     stream_sink: Option<Arc<dyn StreamSink>>,
     conversation_history: &'a [ProviderMessage],
+    image_content: &'a [ContentBlock],
     hooks: &'a [Arc<dyn PromptHook>],
 }
 
@@ -74,6 +81,7 @@ pub(super) async fn execute_main_session_turn_with_metrics(
         execution_context_override: None,
         stream_sink: Some(Arc::new(CliStreamSink::new()) as Arc<dyn StreamSink>),
         conversation_history,
+        image_content: &[],
         hooks: &[],
     };
     execute_main_session_turn_with_policy_outcome(
@@ -166,6 +174,7 @@ async fn execute_main_session_turn_with_policy(
         execution_context_override: None,
         stream_sink: Some(Arc::new(CliStreamSink::new()) as Arc<dyn StreamSink>),
         conversation_history: &[],
+        image_content: &[],
         hooks: &[],
     };
     execute_main_session_turn_with_policy_outcome(
@@ -206,6 +215,7 @@ fn build_main_session_execution_context(
         allowed_tools: None,
         rate_limiter: Arc::clone(&params.rate_limiter),
         tenant_context: write_context.policy_context.clone(),
+        approval: None,
     }
 }
 
@@ -357,8 +367,21 @@ async fn try_execute_with_planner(
         return None;
     }
 
-    let runner = ToolStepRunner::new(Arc::clone(&params.registry), ctx.clone());
-    let report = match PlanExecutor::execute(&mut plan, &runner).await {
+    let runner = PromptStepRunner::new(
+        ToolStepRunner::new(Arc::clone(&params.registry), ctx.clone()),
+        params.answer_provider,
+        model_name,
+        temperature,
+    );
+    plan.id = Uuid::new_v4().to_string();
+    let store = open_plan_store(params.plan_store_dir, &plan, ctx).await;
+    let execution = match store.as_ref() {
+        Some(store) => {
+            PlanExecutor::execute_persisted(&mut plan, &runner, &params.plan_policy, store).await
+        }
+        None => PlanExecutor::execute_with_policy(&mut plan, &runner, &params.plan_policy).await,
+    };
+    let report = match execution {
         Ok(report) => report,
         Err(error) => {
             tracing::warn!(error = %error, "plan execution failed; falling back to direct tool loop");
@@ -373,6 +396,24 @@ async fn try_execute_with_planner(
     }
 }
 
+/// Persist the plan so it can be inspected and resumed after a restart.
+/// Persistence failures are logged and the plan runs unpersisted.
+async fn open_plan_store(dir: &Path, plan: &Plan, ctx: &ExecutionContext) -> Option<PlanStore> {
+    let store = match PlanStore::open(dir).await {
+        Ok(store) => store,
+        Err(error) => {
+            tracing::warn!(error = %error, "failed to open plan store; plan will not be persisted");
+            return None;
+        }
+    };
+    let policy = PlanPolicyContext::from_execution_context(ctx);
+    if let Err(error) = store.create(plan, &ctx.entity_id, &policy).await {
+        tracing::warn!(error = %error, "failed to persist plan; plan will not be persisted");
+        return None;
+    }
+    Some(store)
+}
+
 #[allow(clippy::too_many_arguments)]
 async fn execute_turn_with_plan_or_tool_loop(
     params: &MainSessionTurnParams<'_>,
@@ -381,10 +422,12 @@ async fn execute_turn_with_plan_or_tool_loop(
     clamped_temperature: f64,
     ctx: &ExecutionContext,
     conversation_history: &[ProviderMessage],
+    image_content: &[ContentBlock],
     stream_sink: Option<Arc<dyn StreamSink>>,
     hooks: &[Arc<dyn PromptHook>],
 ) -> Result<ToolLoopResult> {
-    let planner_response = if should_attempt_planner(user_message) {
+    // Plan steps only carry text, so turns with images go to the tool loop.
+    let planner_response = if image_content.is_empty() && should_attempt_planner(user_message) {
         try_execute_with_planner(
             params,
             enriched,
@@ -399,13 +442,26 @@ async fn execute_turn_with_plan_or_tool_loop(
 
     if let Some(planned_response) = planner_response {
         tracing::info!(entity_id = %ctx.entity_id, "planner path selected for main session turn");
+        let transcript = vec![
+            ProviderMessage::user(enriched),
+            ProviderMessage {
+                role: MessageRole::Assistant,
+                content: vec![ContentBlock::Text {
+                    text: planned_response.clone(),
+                }],
+            },
+        ];
         return Ok(ToolLoopResult {
             final_text: planned_response,
             tool_calls: Vec::new(),
             attachments: Vec::new(),
             iterations: 0,
             tokens_used: None,
+            input_tokens: None,
+            output_tokens: None,
             stop_reason: LoopStopReason::Completed,
+            output_truncated: false,
+            transcript,
         });
     }
 
@@ -413,15 +469,18 @@ async fn execute_turn_with_plan_or_tool_loop(
         Arc::clone(&params.registry),
         params.max_tool_iterations,
         params.repeated_tool_call_streak_limit,
-    );
+    )
+    .with_context_config(params.context.clone());
     let tool_result = tool_loop
         .run(ToolLoopRunParams {
             provider: params.answer_provider,
             system_prompt: params.system_prompt,
             user_message: enriched,
-            image_content: &[],
+            image_content,
             model: params.model_name,
             temperature: clamped_temperature,
+            thinking: params.thinking,
+            request_options: params.request_options,
             ctx,
             stream_sink,
             conversation_history,
@@ -482,7 +541,7 @@ pub async fn run_main_session_turn_for_integration_with_policy(
         policy_context,
         user_message,
     } = params;
-    let observer: Arc<dyn Observer> = Arc::new(NoopObserver);
+    let observer = global_observer();
     let registry = super::run::init_tools(config, &mem);
     let person_id = resolve_person_id(config);
     let params = MainSessionTurnParams {
@@ -499,6 +558,11 @@ pub async fn run_main_session_turn_for_integration_with_policy(
             config.autonomy.max_actions_per_hour,
             config.autonomy.max_actions_per_entity_per_hour,
         )),
+        plan_policy: ExecutionPolicy::from_config(&config.planner),
+        plan_store_dir: &config.workspace_dir,
+        context: &config.context,
+        thinking: ThinkingLevel::Off,
+        request_options: None,
     };
 
     execute_main_session_turn_with_policy(
@@ -537,41 +601,70 @@ pub async fn run_main_session_turn_for_runtime_with_policy(
         execution_context,
         stream_sink,
         conversation_history,
+        image_content,
         hooks,
+        thinking,
+        request_options,
     } = runtime_options;
-    let observer: Arc<dyn Observer> = Arc::new(NoopObserver);
-    let person_id = resolve_person_id(config);
-    let session_params = MainSessionTurnParams {
-        answer_provider,
-        reflect_provider,
-        person_id: &person_id,
-        system_prompt,
-        model_name,
-        temperature,
-        registry,
-        max_tool_iterations,
-        repeated_tool_call_streak_limit,
-        rate_limiter: Arc::clone(&execution_context.rate_limiter),
-    };
-    let runtime_options = MainSessionRuntimeOptions {
-        execution_context_override: Some(execution_context),
-        stream_sink,
-        conversation_history,
-        hooks,
-    };
+    let observer = global_observer();
+    // Everything recorded during the turn nests under this span.
+    in_span(SpanContext::for_turn(), async {
+        observer.record_event(&ObserverEvent::AgentStart {
+            provider: answer_provider.name().to_string(),
+            model: model_name.to_string(),
+            entity_id: entity_id.to_string(),
+        });
+        let turn = TurnGuard::start(Arc::clone(&observer));
+        let person_id = resolve_person_id(config);
+        let session_params = MainSessionTurnParams {
+            answer_provider,
+            reflect_provider,
+            person_id: &person_id,
+            system_prompt,
+            model_name,
+            temperature,
+            registry,
+            max_tool_iterations,
+            repeated_tool_call_streak_limit,
+            rate_limiter: Arc::clone(&execution_context.rate_limiter),
+            plan_policy: ExecutionPolicy::from_config(&config.planner),
+            plan_store_dir: &config.workspace_dir,
+            context: &config.context,
+            thinking,
+            request_options,
+        };
+        let runtime_options = MainSessionRuntimeOptions {
+            execution_context_override: Some(execution_context),
+            stream_sink,
+            conversation_history,
+            image_content,
+            hooks,
+        };
 
-    execute_main_session_turn_with_policy_outcome(
-        config,
-        security,
-        mem,
-        &session_params,
-        user_message,
-        RuntimeMemoryWriteContext::for_entity_with_policy(entity_id, policy_context),
-        &observer,
-        &runtime_options,
-    )
+        let result = execute_main_session_turn_with_policy_outcome(
+            config,
+            security,
+            mem,
+            &session_params,
+            user_message,
+            RuntimeMemoryWriteContext::for_entity_with_policy(entity_id, policy_context),
+            &observer,
+            &runtime_options,
+        )
+        .await
+        .map(|outcome| outcome.tool_result);
+
+        let tokens_used = result.as_ref().ok().and_then(|result| result.tokens_used);
+        let success = result
+            .as_ref()
+            .is_ok_and(|result| !matches!(result.stop_reason, LoopStopReason::Error(_)));
+        turn.finish(tokens_used, success);
+        if let Some(tokens) = tokens_used {
+            observer.record_metric(&ObserverMetric::TokensUsed(tokens));
+        }
+        result
+    })
     .await
-    .map(|outcome| outcome.tool_result)
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -618,6 +711,7 @@ pub(super) async fn execute_main_session_turn_with_accounting(
         clamped_temperature,
         execution_context,
         runtime_options.conversation_history,
+        runtime_options.image_content,
         runtime_options.stream_sink.clone(),
         runtime_options.hooks,
     )
@@ -1,13 +1,16 @@
-use crate::config::Config;
+use crate::config::{Config, ContextConfig};
 use crate::llm::Provider;
-use crate::llm::{ProviderMessage, StreamSink};
+use crate::llm::{ChatOptions, ThinkingLevel};
+use crate::llm::{ContentBlock, ProviderMessage, StreamSink};
 use crate::memory::Memory;
 use crate::persona::person_identity::person_entity_id;
+use crate::planner::ExecutionPolicy;
 use crate::security::SecurityPolicy;
 use crate::security::policy::{EntityRateLimiter, TenantPolicyContext};
 use crate::tools::{ExecutionContext, ToolRegistry};
 use crate::{agent::PromptHook, agent::ToolLoopResult};
 use anyhow::Result;
+use std::path::Path;
 use std::sync::Arc;
 
 pub(super) const PERSONA_PER_TURN_CALL_BUDGET: u8 = 2;
@@ -74,6 +77,12 @@ pub(super) struct MainSessionTurnParams<'a> {
     pub(super) max_tool_iterations: u32,
     pub(super) repeated_tool_call_streak_limit: u32,
     pub(super) rate_limiter: Arc<EntityRateLimiter>,
+    pub(super) plan_policy: ExecutionPolicy,
+    /// Workspace directory holding the plan store.
+    pub(super) plan_store_dir: &'a Path,
+    pub(super) context: &'a ContextConfig,
+    pub(super) thinking: ThinkingLevel,
+    pub(super) request_options: Option<&'a ChatOptions>,
 }
 
 pub struct IntegrationTurnParams<'a> {
@@ -97,7 +106,14 @@ pub struct IntegrationRuntimeTurnOptions<'a> {
     pub execution_context: ExecutionContext,
     pub stream_sink: Option<Arc<dyn StreamSink>>,
     pub conversation_history: &'a [ProviderMessage],
+    /// Image blocks sent alongside `user_message`.
+    pub image_content: &'a [ContentBlock],
     pub hooks: &'a [Arc<dyn PromptHook>],
+    /// The conversation's `/think` level.
+    pub thinking: ThinkingLevel,
+    /// Generation options requested by an API client (`max_tokens`, `stop`,
+    /// sampling); `None` uses the defaults.
+    pub request_options: Option<&'a ChatOptions>,
 }
 
 #[derive(Debug, Clone)]
@@ -1,9 +1,11 @@
+pub mod context_window;
 pub mod hooks;
 pub mod hooks_leak;
 pub mod integration;
 pub mod token_estimate;
 pub mod tool_loop;
 
+pub use context_window::{ContextWindow, model_context_limit, model_output_limit};
 pub use hooks::{HookDecision, PromptHook};
 pub use hooks_leak::LeakDetectionHook;
 pub use integration::{
@@ -28,6 +28,7 @@ pub fn estimate_message_tokens(messages: &[ProviderMessage]) -> u64 {
                         estimate_tokens(name) + estimate_tokens(&input_str)
                     }
                     ContentBlock::ToolResult { content, .. } => estimate_tokens(content),
+                    ContentBlock::Thinking { thinking, .. } => estimate_tokens(thinking),
                     ContentBlock::Image { .. } => 256, // fixed estimate for images
                 })
                 .sum();
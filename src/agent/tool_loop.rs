@@ -1,11 +1,19 @@
+use super::context_window::{ContextWindow, model_output_limit};
 use super::hooks::{HookDecision, PromptHook};
+use crate::config::ContextConfig;
+use crate::llm::ThinkingLevel;
+use crate::llm::options::ChatOptions;
 use crate::llm::streaming::{StreamCollector, StreamSink};
 use crate::llm::traits::Provider;
-use crate::llm::types::{ContentBlock, MessageRole, ProviderMessage, ProviderResponse};
+use crate::llm::types::{ContentBlock, MessageRole, ProviderMessage, ProviderResponse, StopReason};
+use crate::runtime::observability::{ObserverEvent, global_observer};
 use crate::tools::{ExecutionContext, OutputAttachment, ToolRegistry, ToolResult, ToolSpec};
 use futures_util::StreamExt;
 use serde::{Deserialize, Serialize};
+use std::future::Future;
+use std::pin::Pin;
 use std::sync::Arc;
+use std::time::Instant;
 
 // ── Constants ────────────────────────────────────────────────────────────────
 
@@ -13,6 +21,8 @@ use std::sync::Arc;
 pub(crate) const TOOL_LOOP_HARD_CAP: u32 = 25;
 /// Stop early when the model repeats the exact same tool call and result.
 const DEFAULT_REPEATED_TOOL_CALL_STREAK_LIMIT: u32 = 3;
+/// Most parallel-safe tool calls from one response that run at the same time.
+const MAX_PARALLEL_TOOL_CALLS: usize = 4;
 
 /// Injected into the system prompt when tool specs are present to prevent
 /// the model from obeying instructions embedded in tool result content.
@@ -34,6 +44,7 @@ pub struct ToolLoop {
     pub(crate) registry: Arc<ToolRegistry>,
     pub(crate) max_iterations: u32,
     pub(crate) repeated_tool_call_streak_limit: u32,
+    pub(crate) context: ContextConfig,
 }
 
 /// Parameters for a single [`ToolLoop::run`] invocation.
@@ -44,6 +55,12 @@ pub struct ToolLoopRunParams<'a> {
     pub image_content: &'a [ContentBlock],
     pub model: &'a str,
     pub temperature: f64,
+    /// The conversation's `/think` level.
+    pub thinking: ThinkingLevel,
+    /// Generation options requested by the caller (e.g. an API client).
+    /// `temperature` and `thinking` above take precedence, and `max_tokens`
+    /// is capped at the model's configured output limit.
+    pub request_options: Option<&'a ChatOptions>,
     pub ctx: &'a ExecutionContext,
     pub stream_sink: Option<Arc<dyn StreamSink>>,
     pub conversation_history: &'a [ProviderMessage],
@@ -83,7 +100,16 @@ pub struct ToolLoopResult {
     pub attachments: Vec<OutputAttachment>,
     pub iterations: u32,
     pub tokens_used: Option<u64>,
+    /// Prompt and completion tokens summed over every LLM call, when the
+    /// provider reported them.
+    pub input_tokens: Option<u64>,
+    pub output_tokens: Option<u64>,
     pub stop_reason: LoopStopReason,
+    /// The final reply was cut off by the output token limit.
+    pub output_truncated: bool,
+    /// Messages added during this run, starting with the user message and
+    /// including every assistant reply and tool result.
+    pub transcript: Vec<ProviderMessage>,
 }
 
 // ── Internal types ───────────────────────────────────────────────────────────
@@ -93,7 +119,7 @@ struct ChatOnceInput<'a> {
     messages: &'a [ProviderMessage],
     tools: &'a [ToolSpec],
     model: &'a str,
-    temperature: f64,
+    options: &'a ChatOptions,
     stream_sink: Option<&'a Arc<dyn StreamSink>>,
 }
 
@@ -101,20 +127,19 @@ struct ChatOnceInput<'a> {
 struct LoopState {
     tool_calls: Vec<ToolCallRecord>,
     attachments: Vec<OutputAttachment>,
-    total_tokens: u64,
+    input_tokens: u64,
+    output_tokens: u64,
     has_token_info: bool,
     iteration: u32,
     repeated_tool_call_streak: u32,
     last_tool_call_signature: Option<ToolCallSignature>,
+    context: ContextWindow,
 }
 
 impl LoopState {
     fn tokens_used(&self) -> Option<u64> {
-        if self.has_token_info {
-            Some(self.total_tokens)
-        } else {
-            None
-        }
+        self.has_token_info
+            .then_some(self.input_tokens + self.output_tokens)
     }
 }
 
@@ -139,6 +164,16 @@ impl ToolCallSignature {
     }
 }
 
+/// A tool-use block from a provider response.
+struct ToolCall<'a> {
+    id: &'a str,
+    name: &'a str,
+    input: &'a serde_json::Value,
+}
+
+type ToolExecution<'a> =
+    Pin<Box<dyn Future<Output = Result<ToolResult, LoopStopReason>> + Send + 'a>>;
+
 /// Outcome of processing one batch of tool-use blocks.
 enum ToolBatchOutcome {
     /// All tool calls executed; continue the loop.
@@ -167,53 +202,94 @@ impl ToolLoop {
             registry,
             max_iterations: max_iterations.min(TOOL_LOOP_HARD_CAP),
             repeated_tool_call_streak_limit: repeated_tool_call_streak_limit.max(1),
+            context: ContextConfig::default(),
         }
     }
 
+    /// Use `config` for context window limits and tool output truncation.
+    #[must_use]
+    pub fn with_context_config(mut self, config: ContextConfig) -> Self {
+        self.context = config;
+        self
+    }
+
     /// Run the tool loop to completion.
     ///
     /// Sends the user message to the provider, executes any tool calls the
     /// model requests, and repeats until the model stops requesting tools,
     /// the iteration limit is reached, or a hook blocks execution.
     pub async fn run(&self, params: ToolLoopRunParams<'_>) -> anyhow::Result<ToolLoopResult> {
-        let tools = self.registry.specs_for_context(params.ctx);
-        let system_prompt =
-            augment_prompt_with_trust_boundary(params.system_prompt, !tools.is_empty());
-
         let mut messages = build_initial_messages(
             params.conversation_history,
             params.user_message,
             params.image_content,
         );
+        let mut result = self.run_turns(&params, &mut messages).await?;
+        result.transcript = messages.split_off(params.conversation_history.len());
+        Ok(result)
+    }
+
+    async fn run_turns(
+        &self,
+        params: &ToolLoopRunParams<'_>,
+        messages: &mut Vec<ProviderMessage>,
+    ) -> anyhow::Result<ToolLoopResult> {
+        let tools = self.registry.specs_for_context(params.ctx);
+        let system_prompt =
+            augment_prompt_with_trust_boundary(params.system_prompt, !tools.is_empty());
+        let mut options = ChatOptions {
+            temperature: params.temperature,
+            thinking: params.thinking,
+            ..params.request_options.cloned().unwrap_or_default()
+        };
+        if let Some(limit) = model_output_limit(&self.context, params.model) {
+            options.max_tokens = Some(options.max_tokens.map_or(limit, |max| max.min(limit)));
+        }
 
         let mut state = LoopState {
             tool_calls: Vec::new(),
             attachments: Vec::new(),
-            total_tokens: 0,
+            input_tokens: 0,
+            output_tokens: 0,
             has_token_info: false,
             iteration: 0,
             repeated_tool_call_streak: 0,
             last_tool_call_signature: None,
+            context: ContextWindow::new(
+                &self.context,
+                params.model,
+                params.provider.name(),
+                &params.ctx.workspace_dir,
+            ),
         };
 
         loop {
             if state.iteration >= self.max_iterations {
                 return Ok(build_result(
-                    extract_last_text(&messages),
+                    extract_last_text(messages),
                     state,
                     LoopStopReason::MaxIterations,
                 ));
             }
 
+            let elided = state.context.fit(&system_prompt, &tools, messages).await;
+            if elided > 0 {
+                tracing::debug!(
+                    elided,
+                    limit = state.context.limit(),
+                    "elided older tool results to fit the context window"
+                );
+            }
+
             let response = self
                 .chat_once(
                     params.provider,
                     ChatOnceInput {
                         system_prompt: &system_prompt,
-                        messages: &messages,
+                        messages,
                         tools: &tools,
                         model: params.model,
-                        temperature: params.temperature,
+                        options: &options,
                         stream_sink: params.stream_sink.as_ref(),
                     },
                 )
@@ -224,14 +300,15 @@ impl ToolLoop {
                 Err(e) => {
                     let msg = e.to_string();
                     if let Some(stop) = classify_execute_error(&msg) {
-                        return Ok(build_result(extract_last_text(&messages), state, stop));
+                        return Ok(build_result(extract_last_text(messages), state, stop));
                     }
                     return Err(e);
                 }
             };
 
-            if let Some(tokens) = response.total_tokens() {
-                state.total_tokens += tokens;
+            if let (Some(input), Some(output)) = (response.input_tokens, response.output_tokens) {
+                state.input_tokens += input;
+                state.output_tokens += output;
                 state.has_token_info = true;
             }
 
@@ -240,31 +317,31 @@ impl ToolLoop {
 
             if response.has_tool_use() {
                 match self
-                    .execute_tool_blocks(
-                        &response,
-                        &mut messages,
-                        &mut state,
-                        params.hooks,
-                        params.ctx,
-                    )
+                    .execute_tool_blocks(&response, messages, &mut state, params.hooks, params.ctx)
                     .await
                 {
                     ToolBatchOutcome::Continue => {}
                     ToolBatchOutcome::Stop(reason) => {
-                        return Ok(build_result(extract_last_text(&messages), state, reason));
+                        return Ok(build_result(extract_last_text(messages), state, reason));
                     }
                 }
             } else {
-                let final_text = extract_last_text(&messages);
+                let final_text = extract_last_text(messages);
                 for hook in params.hooks {
                     hook.on_completion(&final_text, params.ctx).await;
                 }
-                return Ok(build_result(final_text, state, LoopStopReason::Completed));
+                let mut result = build_result(final_text, state, LoopStopReason::Completed);
+                result.output_truncated = response.stop_reason == Some(StopReason::MaxTokens);
+                return Ok(result);
             }
         }
     }
 
     /// Execute every tool-use block from a single provider response.
+    ///
+    /// Consecutive calls to parallel-safe tools run concurrently (at most
+    /// [`MAX_PARALLEL_TOOL_CALLS`] at a time); every other call runs on its
+    /// own. Hooks and results are always processed in block order.
     async fn execute_tool_blocks(
         &self,
         response: &ProviderResponse,
@@ -273,68 +350,152 @@ impl ToolLoop {
         hooks: &[Arc<dyn PromptHook>],
         ctx: &ExecutionContext,
     ) -> ToolBatchOutcome {
-        let tool_blocks = response.tool_use_blocks();
-
-        for block in &tool_blocks {
-            let ContentBlock::ToolUse { id, name, input } = block else {
-                continue;
-            };
-
-            // Run pre-execution hooks.
-            for hook in hooks {
-                if let HookDecision::Block(reason) = hook.on_tool_call(name, input, ctx).await {
-                    return ToolBatchOutcome::Stop(LoopStopReason::HookBlocked(reason));
+        let calls: Vec<ToolCall<'_>> = response
+            .tool_use_blocks()
+            .into_iter()
+            .filter_map(|block| match block {
+                ContentBlock::ToolUse { id, name, input } => Some(ToolCall { id, name, input }),
+                _ => None,
+            })
+            .collect();
+
+        let mut start = 0;
+        while start < calls.len() {
+            let end = self.batch_end(&calls, start);
+            let batch = &calls[start..end];
+            start = end;
+
+            // Run pre-execution hooks; a block stops the batch at that call.
+            let mut runnable = batch.len();
+            let mut blocked = None;
+            'hooks: for (index, call) in batch.iter().enumerate() {
+                for hook in hooks {
+                    if let HookDecision::Block(reason) =
+                        hook.on_tool_call(call.name, call.input, ctx).await
+                    {
+                        runnable = index;
+                        blocked = Some(reason);
+                        break 'hooks;
+                    }
                 }
             }
 
-            // Execute via registry.
-            let result = match self.registry.execute(name, input.clone(), ctx).await {
-                Ok(r) => r,
-                Err(e) => {
-                    let msg = e.to_string();
-                    if let Some(stop) = classify_execute_error(&msg) {
-                        return ToolBatchOutcome::Stop(stop);
-                    }
-                    ToolResult {
-                        success: false,
-                        output: String::new(),
-                        error: Some(msg),
-                        attachments: Vec::new(),
-                    }
+            let executions: Vec<ToolExecution<'_>> = batch[..runnable]
+                .iter()
+                .map(|call| Box::pin(self.execute_call(call, ctx)) as ToolExecution<'_>)
+                .collect();
+            let results: Vec<_> = futures_util::stream::iter(executions)
+                .buffered(MAX_PARALLEL_TOOL_CALLS)
+                .collect()
+                .await;
+
+            for (call, result) in batch.iter().zip(results) {
+                let result = match result {
+                    Ok(result) => result,
+                    Err(stop) => return ToolBatchOutcome::Stop(stop),
+                };
+                if let Some(stop) = self
+                    .record_tool_result(call, result, messages, state, hooks, ctx)
+                    .await
+                {
+                    return ToolBatchOutcome::Stop(stop);
                 }
-            };
+            }
 
-            // Run post-execution hooks.
-            for hook in hooks {
-                hook.on_tool_result(name, &result, ctx).await;
+            if let Some(reason) = blocked {
+                return ToolBatchOutcome::Stop(LoopStopReason::HookBlocked(reason));
             }
+        }
 
-            // Record results.
-            state.attachments.extend(result.attachments.clone());
-            state.tool_calls.push(ToolCallRecord {
-                tool_name: name.clone(),
-                args: input.clone(),
-                result: result.clone(),
-                iteration: state.iteration,
-            });
-
-            if has_repeated_tool_call_streak(
-                state,
-                name,
-                input,
-                &result,
-                self.repeated_tool_call_streak_limit,
-            ) {
-                return ToolBatchOutcome::Stop(LoopStopReason::HookBlocked(
-                    "repeated_identical_tool_call".to_string(),
-                ));
+        ToolBatchOutcome::Continue
+    }
+
+    /// End (exclusive) of the batch starting at `start`: a run of
+    /// parallel-safe calls, or a single call otherwise.
+    fn batch_end(&self, calls: &[ToolCall<'_>], start: usize) -> usize {
+        let parallel_safe = |call: &ToolCall<'_>| self.registry.is_parallel_safe(call.name);
+        if !parallel_safe(&calls[start]) {
+            return start + 1;
+        }
+        calls[start..]
+            .iter()
+            .position(|call| !parallel_safe(call))
+            .map_or(calls.len(), |offset| start + offset)
+    }
+
+    /// Execute one call via the registry. Errors that should end the loop
+    /// become `Err`; any other failure is returned as an unsuccessful result.
+    async fn execute_call(
+        &self,
+        call: &ToolCall<'_>,
+        ctx: &ExecutionContext,
+    ) -> Result<ToolResult, LoopStopReason> {
+        match self
+            .registry
+            .execute(call.name, call.input.clone(), ctx)
+            .await
+        {
+            Ok(result) => Ok(result),
+            Err(e) => {
+                let msg = e.to_string();
+                if let Some(stop) = classify_execute_error(&msg) {
+                    return Err(stop);
+                }
+                Ok(ToolResult {
+                    success: false,
+                    output: String::new(),
+                    error: Some(msg),
+                    attachments: Vec::new(),
+                })
             }
+        }
+    }
 
-            let content = format_tool_result_content(&result);
-            messages.push(ProviderMessage::tool_result(id, content, !result.success));
+    /// Run post-execution hooks and append the result to the loop state and
+    /// conversation. Returns a stop reason when the call repeats too often.
+    async fn record_tool_result(
+        &self,
+        call: &ToolCall<'_>,
+        result: ToolResult,
+        messages: &mut Vec<ProviderMessage>,
+        state: &mut LoopState,
+        hooks: &[Arc<dyn PromptHook>],
+        ctx: &ExecutionContext,
+    ) -> Option<LoopStopReason> {
+        for hook in hooks {
+            hook.on_tool_result(call.name, &result, ctx).await;
         }
 
-        ToolBatchOutcome::Continue
+        state.attachments.extend(result.attachments.clone());
+        state.tool_calls.push(ToolCallRecord {
+            tool_name: call.name.to_string(),
+            args: call.input.clone(),
+            result: result.clone(),
+            iteration: state.iteration,
+        });
+
+        if has_repeated_tool_call_streak(
+            state,
+            call.name,
+            call.input,
+            &result,
+            self.repeated_tool_call_streak_limit,
+        ) {
+            return Some(LoopStopReason::HookBlocked(
+                "repeated_identical_tool_call".to_string(),
+            ));
+        }
+
+        let content = state
+            .context
+            .clip_tool_output(format_tool_result_content(&result))
+            .await;
+        messages.push(ProviderMessage::tool_result(
+            call.id,
+            content,
+            !result.success,
+        ));
+        None
     }
 
     /// Single LLM round-trip, using streaming or non-streaming based on sink.
@@ -342,6 +503,26 @@ impl ToolLoop {
         &self,
         provider: &dyn Provider,
         input: ChatOnceInput<'_>,
+    ) -> anyhow::Result<ProviderResponse> {
+        let model = input.model.to_string();
+        let started = Instant::now();
+        let result = Self::request(provider, input).await;
+
+        let response = result.as_ref().ok();
+        global_observer().record_event(&ObserverEvent::ProviderRequest {
+            provider: provider.name().to_string(),
+            model: response.and_then(|r| r.model.clone()).unwrap_or(model),
+            duration: started.elapsed(),
+            success: response.is_some(),
+            input_tokens: response.and_then(|r| r.input_tokens),
+            output_tokens: response.and_then(|r| r.output_tokens),
+        });
+        result
+    }
+
+    async fn request(
+        provider: &dyn Provider,
+        input: ChatOnceInput<'_>,
     ) -> anyhow::Result<ProviderResponse> {
         let system = Some(input.system_prompt);
 
@@ -352,7 +533,7 @@ impl ToolLoop {
                     input.messages,
                     input.tools,
                     input.model,
-                    input.temperature,
+                    input.options,
                 )
                 .await?;
 
@@ -370,7 +551,7 @@ impl ToolLoop {
                     input.messages,
                     input.tools,
                     input.model,
-                    input.temperature,
+                    input.options,
                 )
                 .await
         }
@@ -476,7 +657,11 @@ fn build_result(
         attachments: state.attachments,
         iterations: state.iteration,
         tokens_used,
+        input_tokens: state.has_token_info.then_some(state.input_tokens),
+        output_tokens: state.has_token_info.then_some(state.output_tokens),
         stop_reason,
+        output_truncated: false,
+        transcript: Vec::new(),
     }
 }
 
@@ -691,16 +876,27 @@ mod tests {
         assert!(!is_action_limit_message("connection timeout"));
     }
 
+    fn test_context_window() -> ContextWindow {
+        ContextWindow::new(
+            &ContextConfig::default(),
+            "test-model",
+            "test",
+            &std::env::temp_dir(),
+        )
+    }
+
     #[test]
     fn build_result_constructs_correctly() {
         let state = LoopState {
             tool_calls: vec![],
             attachments: vec![],
-            total_tokens: 1000,
+            input_tokens: 800,
+            output_tokens: 200,
             has_token_info: true,
             iteration: 3,
             repeated_tool_call_streak: 0,
             last_tool_call_signature: None,
+            context: test_context_window(),
         };
         let result = build_result("done".to_string(), state, LoopStopReason::Completed);
         assert_eq!(result.final_text, "done");
@@ -708,6 +904,8 @@ mod tests {
         assert!(result.attachments.is_empty());
         assert_eq!(result.iterations, 3);
         assert_eq!(result.tokens_used, Some(1000));
+        assert_eq!(result.input_tokens, Some(800));
+        assert_eq!(result.output_tokens, Some(200));
         assert_eq!(result.stop_reason, LoopStopReason::Completed);
     }
 
@@ -716,11 +914,13 @@ mod tests {
         let state = LoopState {
             tool_calls: vec![],
             attachments: vec![],
-            total_tokens: 0,
+            input_tokens: 0,
+            output_tokens: 0,
             has_token_info: false,
             iteration: 1,
             repeated_tool_call_streak: 0,
             last_tool_call_signature: None,
+            context: test_context_window(),
         };
         let result = build_result("text".to_string(), state, LoopStopReason::Completed);
         assert_eq!(result.tokens_used, None);
@@ -769,11 +969,13 @@ mod tests {
         let mut state = LoopState {
             tool_calls: Vec::new(),
             attachments: Vec::new(),
-            total_tokens: 0,
+            input_tokens: 0,
+            output_tokens: 0,
             has_token_info: false,
             iteration: 1,
             repeated_tool_call_streak: 0,
             last_tool_call_signature: None,
+            context: test_context_window(),
         };
         let args = serde_json::json!({"command":"ls -a"});
         let result = ToolResult {
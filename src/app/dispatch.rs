@@ -1,20 +1,24 @@
 use crate::cli::commands::{
-    ChannelCommands, Cli, Commands, CronCommands, IntegrationCommands, ServiceCommands,
-    SkillCommands,
+    ChannelCommands, Cli, Commands, CronCommands, IntegrationCommands, McpCommands, PlanCommands,
+    ServiceCommands, SkillCommands, UsageCommands,
 };
 use anyhow::{Result, bail};
+use std::io::IsTerminal;
 use std::sync::Arc;
 use tracing::info;
 
 use crate::Config;
 use crate::app::status::render_status;
+use crate::config::DEFAULT_MODEL;
 
 /// Run the AI agent loop via the integrated main-session API.
 ///
 /// 1. Creates an LLM provider via the resilient factory with OAuth recovery.
 /// 2. Creates memory via `memory::factory::create_memory`.
 /// 3. Builds the tool registry from `tools::all_tools(memory)`.
-/// 4. Runs an integrated main-session turn and prints the result.
+/// 4. Runs an integrated main-session turn and prints the result, prompting on
+///    the terminal before supervised tool calls.
+#[allow(clippy::too_many_lines)]
 async fn run_agent(
     config: Arc<Config>,
     message: Option<String>,
@@ -47,8 +51,13 @@ async fn run_agent(
     );
 
     // 3. Build tool registry
-    let tools = crate::tools::all_tools(Arc::clone(&memory));
-    let mut registry = crate::tools::ToolRegistry::default();
+    let mut tools = crate::tools::all_tools(Arc::clone(&memory));
+    tools.extend(crate::plugins::skills::load_skill_tools(
+        &config.workspace_dir,
+        &config.skills,
+    ));
+    let mut registry =
+        crate::tools::ToolRegistry::new(crate::tools::middleware::default_middleware_chain());
     for tool in tools {
         registry.register(tool);
     }
@@ -56,7 +65,14 @@ async fn run_agent(
 
     // 4. Build runtime context and run the integrated main session turn
     let security = Arc::new(crate::security::SecurityPolicy::default());
-    let ctx = crate::tools::ExecutionContext::from_security(Arc::clone(&security));
+    let mut ctx = crate::tools::ExecutionContext::from_security(Arc::clone(&security));
+    if std::io::stdin().is_terminal() {
+        let permissions = crate::security::PermissionStore::load(&config.workspace_dir);
+        ctx.approval = Some(Arc::new(
+            crate::security::ApprovalGate::new(Arc::new(crate::security::CliApprovalBroker))
+                .with_permissions(Arc::new(permissions)),
+        ));
+    }
     let entity_id = ctx.entity_id.clone();
     let policy_context = ctx.tenant_context.clone();
     let tool_descs = crate::tools::tool_descriptions();
@@ -90,11 +106,30 @@ async fn run_agent(
             execution_context: ctx,
             stream_sink: None,
             conversation_history: &[],
+            image_content: &[],
             hooks: &[],
+            thinking: crate::llm::ThinkingLevel::Off,
+            request_options: None,
         },
     )
     .await?;
 
+    if let Some(usage) =
+        crate::runtime::usage::UsageRecorder::open_if_enabled(&config, Arc::clone(&security)).await
+    {
+        usage
+            .record_turn(crate::runtime::usage::TurnUsage {
+                entity_id: &entity_id,
+                channel: "cli",
+                session_id: None,
+                provider: provider.name(),
+                model: &model,
+                input_tokens: result.input_tokens,
+                output_tokens: result.output_tokens,
+            })
+            .await;
+    }
+
     println!("{}", result.final_text);
 
     if let Some(tokens) = result.tokens_used {
@@ -108,6 +143,24 @@ async fn run_agent(
     Ok(())
 }
 
+fn set_skill_enabled(config: &Config, name: &str, enabled: bool) -> Result<()> {
+    let name = normalize_non_empty_arg(name, "skill name")?;
+    let installed = crate::plugins::skills::load_skills(&config.workspace_dir);
+    if !installed.iter().any(|skill| skill.name == name) {
+        bail!("Skill '{name}' is not installed");
+    }
+
+    let mut updated = config.clone();
+    let state = if enabled { "enabled" } else { "disabled" };
+    if updated.skills.set_skill_enabled(&name, enabled) {
+        updated.save()?;
+        println!("Skill '{name}' {state}");
+    } else {
+        println!("Skill '{name}' is already {state}");
+    }
+    Ok(())
+}
+
 fn validate_cli_temperature(temperature: f64) -> Result<()> {
     if !temperature.is_finite() {
         bail!("--temperature must be a finite number in [0.0, 2.0]");
@@ -151,11 +204,13 @@ fn resolve_agent_model_name(config: &Config, model_override: Option<&str>) -> Re
         }
     }
 
-    Ok("anthropic/claude-sonnet-4-20250514".to_string())
+    Ok(DEFAULT_MODEL.to_string())
 }
 
 #[allow(clippy::too_many_lines)]
 pub async fn dispatch(cli: Cli, config: Arc<Config>) -> Result<()> {
+    crate::runtime::observability::init_global_observer(&config.observability);
+
     // Onboard runs quick setup by default, or the interactive wizard with --interactive
     if let Commands::Onboard {
         interactive,
@@ -235,7 +290,9 @@ pub async fn dispatch(cli: Cli, config: Arc<Config>) -> Result<()> {
             } else {
                 info!("Starting AsteronIris Gateway on {host}:{port}");
             }
-            crate::transport::gateway::run_gateway(&host, port, Arc::clone(&config)).await
+            // No channel listeners run here, so webhook-fed channels are
+            // left to the daemon.
+            crate::transport::gateway::run_gateway(&host, port, Arc::clone(&config), false).await
         }
 
         Commands::Daemon { port, host } => {
@@ -303,6 +360,42 @@ pub async fn dispatch(cli: Cli, config: Arc<Config>) -> Result<()> {
             crate::platform::cron::handle_command(cmd, &config).await
         }
 
+        Commands::Plan { plan_command } => {
+            let cmd = match plan_command {
+                PlanCommands::List => crate::planner::PlanCommand::List,
+                PlanCommands::Show { id } => crate::planner::PlanCommand::Show { id },
+                PlanCommands::Resume { id } => crate::planner::PlanCommand::Resume { id },
+                PlanCommands::Cancel { id } => crate::planner::PlanCommand::Cancel { id },
+            };
+            crate::planner::handle_command(cmd, &config).await
+        }
+
+        Commands::Usage { usage_command } => {
+            let cmd = match usage_command {
+                UsageCommands::Summary {
+                    by,
+                    since,
+                    until,
+                    format,
+                } => crate::runtime::usage::UsageCommand::Summary {
+                    by,
+                    since,
+                    until,
+                    format,
+                },
+                UsageCommands::Export {
+                    since,
+                    until,
+                    format,
+                } => crate::runtime::usage::UsageCommand::Export {
+                    since,
+                    until,
+                    format,
+                },
+            };
+            crate::runtime::usage::handle_command(cmd, &config).await
+        }
+
         Commands::Service { service_command } => {
             let cmd = match service_command {
                 ServiceCommands::Install => crate::platform::service::ServiceCommand::Install,
@@ -338,6 +431,9 @@ pub async fn dispatch(cli: Cli, config: Arc<Config>) -> Result<()> {
                 if config.channels_config.matrix.is_some() {
                     println!("  - Matrix");
                 }
+                if config.channels_config.mattermost.is_some() {
+                    println!("  - Mattermost");
+                }
                 if config.channels_config.email.is_some() {
                     println!("  - Email");
                 }
@@ -379,7 +475,19 @@ pub async fn dispatch(cli: Cli, config: Arc<Config>) -> Result<()> {
                 } else {
                     println!("Installed skills:");
                     for skill in &skills {
-                        println!("  - {}: {}", skill.name, skill.description);
+                        let status = if config.skills.is_skill_enabled(&skill.name) {
+                            ""
+                        } else {
+                            " (disabled)"
+                        };
+                        println!("  - {}{status}: {}", skill.name, skill.description);
+                        for tool in &skill.tools {
+                            println!(
+                                "      tool {} [{}]",
+                                crate::plugins::skills::skill_tool_name(&skill.name, &tool.name),
+                                tool.kind
+                            );
+                        }
                     }
                 }
                 Ok(())
@@ -394,6 +502,17 @@ pub async fn dispatch(cli: Cli, config: Arc<Config>) -> Result<()> {
                     "Skill remove not yet implemented for '{name}' — delete from workspace/skills/"
                 )
             }
+            SkillCommands::Enable { name } => set_skill_enabled(&config, &name, true),
+            SkillCommands::Disable { name } => set_skill_enabled(&config, &name, false),
+        },
+
+        Commands::Mcp { mcp_command } => match mcp_command {
+            #[cfg(feature = "mcp")]
+            McpCommands::Serve => crate::plugins::mcp::server::serve_stdio(&config).await,
+            #[cfg(not(feature = "mcp"))]
+            McpCommands::Serve => {
+                bail!("MCP server support is not compiled in — rebuild with `--features mcp`")
+            }
         },
     }
 }
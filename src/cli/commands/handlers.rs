@@ -1,11 +1,14 @@
 use super::types::{Command, CommandResult};
+use crate::llm::ThinkingLevel;
 
-pub fn handle_command(command: &Command) -> CommandResult {
+/// Run `command` for a conversation; `/think` updates that conversation's
+/// `thinking` level.
+pub fn handle_command(command: &Command, thinking: &mut ThinkingLevel) -> CommandResult {
     match command {
         Command::Status => handle_status(),
         Command::New => handle_new(),
         Command::Compact => handle_compact(),
-        Command::Think { level } => handle_think(level.as_deref()),
+        Command::Think { level } => handle_think(level.as_deref(), thinking),
         Command::Verbose => handle_verbose(),
         Command::Usage => handle_usage(),
         Command::Help => handle_help(),
@@ -24,10 +27,28 @@ fn handle_compact() -> CommandResult {
     CommandResult::visible("Session compacted.")
 }
 
-fn handle_think(level: Option<&str>) -> CommandResult {
-    match level {
-        Some(l) => CommandResult::ephemeral(format!("Thinking level set to: {l}")),
-        None => CommandResult::ephemeral("Thinking level toggled."),
+/// Set the extended-thinking level; without an argument, toggle between
+/// off and medium.
+fn handle_think(level: Option<&str>, thinking: &mut ThinkingLevel) -> CommandResult {
+    let level = match level {
+        Some(value) => match ThinkingLevel::parse(value) {
+            Some(level) => level,
+            None => {
+                return CommandResult::ephemeral(format!(
+                    "Unknown thinking level '{value}'. Use off, low, medium or high."
+                ));
+            }
+        },
+        None if *thinking == ThinkingLevel::Off => ThinkingLevel::Medium,
+        None => ThinkingLevel::Off,
+    };
+    *thinking = level;
+    match level.budget_tokens() {
+        Some(budget) => CommandResult::ephemeral(format!(
+            "Thinking level set to {} (budget {budget} tokens).",
+            level.as_str()
+        )),
+        None => CommandResult::ephemeral("Thinking turned off."),
     }
 }
 
@@ -36,7 +57,7 @@ fn handle_verbose() -> CommandResult {
 }
 
 fn handle_usage() -> CommandResult {
-    CommandResult::visible("Usage tracking not yet configured.")
+    CommandResult::visible("Run `asteroniris usage summary` to see token usage and cost.")
 }
 
 fn handle_help() -> CommandResult {
@@ -44,9 +65,43 @@ fn handle_help() -> CommandResult {
         "/status  -- Show current status\n\
          /new     -- Start a new session\n\
          /compact -- Summarize session history\n\
-         /think   -- Toggle thinking mode (e.g. /think high)\n\
+         /think   -- Set thinking level: off, low, medium, high (no level toggles)\n\
          /verbose -- Toggle verbose output\n\
          /usage   -- Show token usage statistics\n\
          /help    -- Show this help message",
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn think_updates_only_the_given_conversation() {
+        let mut first = ThinkingLevel::Off;
+        let mut second = ThinkingLevel::Off;
+
+        handle_command(&Command::Think { level: None }, &mut first);
+        assert_eq!(first, ThinkingLevel::Medium);
+        assert_eq!(second, ThinkingLevel::Off);
+
+        handle_command(
+            &Command::Think {
+                level: Some("high".into()),
+            },
+            &mut second,
+        );
+        assert_eq!(second, ThinkingLevel::High);
+        handle_command(&Command::Think { level: None }, &mut first);
+        assert_eq!(first, ThinkingLevel::Off);
+
+        let reply = handle_command(
+            &Command::Think {
+                level: Some("extreme".into()),
+            },
+            &mut second,
+        );
+        assert!(reply.text.contains("Unknown thinking level"));
+        assert_eq!(second, ThinkingLevel::High);
+    }
+}
@@ -8,8 +8,8 @@ pub mod types;
 pub use handlers::handle_command;
 pub use parser::parse_command;
 pub use subcommands::{
-    AuthCommands, ChannelCommands, CronCommands, IntegrationCommands, ServiceCommands,
-    SkillCommands,
+    AuthCommands, ChannelCommands, CronCommands, IntegrationCommands, McpCommands, PlanCommands,
+    ServiceCommands, SkillCommands, UsageCommands,
 };
 pub use types::{Command, CommandResult};
 
@@ -136,6 +136,18 @@ pub enum Commands {
         cron_command: CronCommands,
     },
 
+    /// Inspect, resume and cancel persisted plans
+    Plan {
+        #[command(subcommand)]
+        plan_command: PlanCommands,
+    },
+
+    /// Report token usage and estimated cost
+    Usage {
+        #[command(subcommand)]
+        usage_command: UsageCommands,
+    },
+
     /// Manage channels (telegram, discord, slack)
     Channel {
         #[command(subcommand)]
@@ -159,6 +171,12 @@ pub enum Commands {
         #[command(subcommand)]
         skill_command: SkillCommands,
     },
+
+    /// Expose tools to other agents via the Model Context Protocol
+    Mcp {
+        #[command(subcommand)]
+        mcp_command: McpCommands,
+    },
 }
 
 #[cfg(test)]
@@ -194,4 +212,48 @@ mod tests {
             other => panic!("expected eval command, got {other:?}"),
         }
     }
+
+    #[test]
+    fn parse_plan_resume_command() {
+        let cli = Cli::parse_from(["asteroniris", "plan", "resume", "plan-42"]);
+        match cli.command {
+            Commands::Plan {
+                plan_command: super::PlanCommands::Resume { id },
+            } => assert_eq!(id, "plan-42"),
+            other => panic!("expected plan resume command, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn parse_usage_summary_with_defaults() {
+        let cli = Cli::parse_from(["asteroniris", "usage", "summary", "--since", "2026-09-01"]);
+        match cli.command {
+            Commands::Usage {
+                usage_command:
+                    super::UsageCommands::Summary {
+                        by,
+                        since,
+                        until,
+                        format,
+                    },
+            } => {
+                assert_eq!(by, "model");
+                assert_eq!(since.as_deref(), Some("2026-09-01"));
+                assert!(until.is_none());
+                assert_eq!(format, "table");
+            }
+            other => panic!("expected usage summary command, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn parse_mcp_serve_command() {
+        let cli = Cli::parse_from(["asteroniris", "mcp", "serve"]);
+        assert!(matches!(
+            cli.command,
+            Commands::Mcp {
+                mcp_command: super::McpCommands::Serve
+            }
+        ));
+    }
 }
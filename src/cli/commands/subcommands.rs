@@ -54,6 +54,23 @@ pub enum SkillCommands {
         /// Skill name to remove
         name: String,
     },
+    /// Enable a disabled skill (prompt text and tools)
+    Enable {
+        /// Skill name to enable
+        name: String,
+    },
+    /// Disable a skill without uninstalling it
+    Disable {
+        /// Skill name to disable
+        name: String,
+    },
+}
+
+/// MCP server subcommands
+#[derive(Subcommand, Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub enum McpCommands {
+    /// Serve registered tools to MCP clients over stdio
+    Serve,
 }
 
 /// Cron subcommands
@@ -75,6 +92,60 @@ pub enum CronCommands {
     },
 }
 
+/// Plan subcommands
+#[derive(Subcommand, Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub enum PlanCommands {
+    /// List persisted plans
+    List,
+    /// Show a plan with per-step status and output
+    Show {
+        /// Plan ID
+        id: String,
+    },
+    /// Resume a plan from its last completed step
+    Resume {
+        /// Plan ID
+        id: String,
+    },
+    /// Cancel a running plan
+    Cancel {
+        /// Plan ID
+        id: String,
+    },
+}
+
+/// Usage reporting subcommands
+#[derive(Subcommand, Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub enum UsageCommands {
+    /// Show token and cost totals broken down by one dimension
+    Summary {
+        /// Breakdown: entity, channel, session, model or day
+        #[arg(long, default_value = "model")]
+        by: String,
+        /// Only turns at or after this date or RFC 3339 time
+        #[arg(long)]
+        since: Option<String>,
+        /// Only turns before this date or RFC 3339 time
+        #[arg(long)]
+        until: Option<String>,
+        /// Output format: table, csv or json
+        #[arg(long, default_value = "table")]
+        format: String,
+    },
+    /// Export every recorded turn
+    Export {
+        /// Only turns at or after this date or RFC 3339 time
+        #[arg(long)]
+        since: Option<String>,
+        /// Only turns before this date or RFC 3339 time
+        #[arg(long)]
+        until: Option<String>,
+        /// Output format: csv or json
+        #[arg(long, default_value = "csv")]
+        format: String,
+    },
+}
+
 /// Auth profile subcommands
 #[derive(Subcommand, Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub enum AuthCommands {
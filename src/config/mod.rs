@@ -2,9 +2,11 @@ pub mod hot_reload;
 pub mod schema;
 
 pub use schema::{
-    AutonomyConfig, BrowserConfig, ChannelsConfig, ComposioConfig, Config, DiscordConfig,
-    EmailConfig, GatewayConfig, GatewayDefenseMode, HeartbeatConfig, IMessageConfig,
-    IdentityConfig, MatrixConfig, McpConfig, MediaConfig, MemoryConfig, ObservabilityConfig,
-    PersonaConfig, ReliabilityConfig, RuntimeConfig, RuntimeKind, SecretsConfig, SlackConfig,
-    TasteConfig, TelegramConfig, ToolsConfig, TunnelConfig, WebhookConfig,
+    AutonomyConfig, BrowserConfig, ChannelsConfig, ComposioConfig, Config, ContextConfig,
+    DEFAULT_MODEL, DiscordConfig, EmailConfig, EmailFolderRule, GatewayConfig, GatewayDefenseMode,
+    HeartbeatConfig, IMessageConfig, IdentityConfig, MatrixAutoJoin, MatrixConfig,
+    MattermostConfig, McpConfig, MediaConfig, MemoryConfig, ObservabilityConfig, PersonaConfig,
+    PlannerConfig, ProcessConfig, ReliabilityConfig, RouteRule, RoutingConfig, RuntimeConfig,
+    RuntimeKind, SecretsConfig, SkillsConfig, SlackConfig, TasteConfig, TelegramConfig,
+    ToolsConfig, TunnelConfig, UsageConfig, WebhookConfig,
 };
@@ -14,6 +14,7 @@ pub struct ChannelsConfig {
     pub whatsapp: Option<WhatsAppConfig>,
     pub email: Option<EmailConfig>,
     pub irc: Option<IrcConfig>,
+    pub mattermost: Option<MattermostConfig>,
 }
 
 impl Default for ChannelsConfig {
@@ -29,13 +30,14 @@ impl Default for ChannelsConfig {
             whatsapp: None,
             email: None,
             irc: None,
+            mattermost: None,
         }
     }
 }
 
 impl ChannelsConfig {
     #[must_use]
-    pub fn configured_channel_flags(&self) -> [(&'static str, bool); 9] {
+    pub fn configured_channel_flags(&self) -> [(&'static str, bool); 10] {
         [
             ("Telegram", self.telegram.is_some()),
             ("Discord", self.discord.is_some()),
@@ -46,12 +48,13 @@ impl ChannelsConfig {
             ("WhatsApp", self.whatsapp.is_some()),
             ("Email", self.email.is_some()),
             ("IRC", self.irc.is_some()),
+            ("Mattermost", self.mattermost.is_some()),
         ]
     }
 
     #[must_use]
     pub fn active_channel_names(&self) -> Vec<&'static str> {
-        let mut active = Vec::with_capacity(10);
+        let mut active = Vec::with_capacity(11);
         active.push("CLI");
         for (name, configured) in self.configured_channel_flags() {
             if configured {
@@ -66,6 +69,14 @@ impl ChannelsConfig {
 pub struct TelegramConfig {
     pub bot_token: String,
     pub allowed_users: Vec<String>,
+    /// Public HTTPS URL of the gateway's `/telegram/webhook`; registered
+    /// with `setWebhook` instead of long-polling `getUpdates`.
+    #[serde(default)]
+    pub webhook_url: Option<String>,
+    /// Sent by Telegram as `X-Telegram-Bot-Api-Secret-Token` and checked on
+    /// every webhook request.
+    #[serde(default)]
+    pub webhook_secret: Option<String>,
     #[serde(default, deserialize_with = "deserialize_autonomy_level_opt")]
     pub autonomy_level: Option<AutonomyLevel>,
     #[serde(default)]
@@ -97,7 +108,14 @@ pub struct DiscordConfig {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SlackConfig {
     pub bot_token: String,
+    /// App-level token (`xapp-…`) with `connections:write`; enables Socket
+    /// Mode. Without it, events arrive through the gateway's `/slack/events`.
     pub app_token: Option<String>,
+    /// Verifies Events API requests on the gateway's `/slack/events`.
+    #[serde(default)]
+    pub signing_secret: Option<String>,
+    /// Channel where every message is answered; elsewhere the bot answers
+    /// mentions, direct messages and slash commands.
     pub channel_id: Option<String>,
     #[serde(default)]
     pub allowed_users: Vec<String>,
@@ -107,6 +125,24 @@ pub struct SlackConfig {
     pub tool_allowlist: Option<Vec<String>>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MattermostConfig {
+    /// Server URL, e.g. `https://mattermost.example.com`.
+    pub url: String,
+    /// Bot account (or personal) access token.
+    pub bot_token: String,
+    /// Channel where every message is answered; elsewhere the bot answers
+    /// mentions and direct messages.
+    pub channel_id: Option<String>,
+    /// Mattermost usernames or user IDs.
+    #[serde(default)]
+    pub allowed_users: Vec<String>,
+    #[serde(default, deserialize_with = "deserialize_autonomy_level_opt")]
+    pub autonomy_level: Option<AutonomyLevel>,
+    #[serde(default)]
+    pub tool_allowlist: Option<Vec<String>>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WebhookConfig {
     pub port: u16,
@@ -130,14 +166,41 @@ pub struct IMessageConfig {
 pub struct MatrixConfig {
     pub homeserver: String,
     pub access_token: String,
+    /// Home room: every message is answered and it is the default target
+    /// for outbound messages. In other joined rooms the bot answers direct
+    /// messages and mentions.
+    #[serde(default)]
     pub room_id: String,
     pub allowed_users: Vec<String>,
+    /// Which room invites are accepted automatically.
+    #[serde(default)]
+    pub auto_join: MatrixAutoJoin,
+    /// Decrypt and encrypt messages in end-to-end encrypted rooms. Needs the
+    /// `matrix-e2e` feature and a device-bound access token.
+    #[serde(default = "default_true")]
+    pub e2e: bool,
+    /// Encrypts the Olm/Megolm device store under `state/matrix/`.
+    #[serde(default)]
+    pub store_passphrase: Option<String>,
     #[serde(default, deserialize_with = "deserialize_autonomy_level_opt")]
     pub autonomy_level: Option<AutonomyLevel>,
     #[serde(default)]
     pub tool_allowlist: Option<Vec<String>>,
 }
 
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum MatrixAutoJoin {
+    /// Leave invites pending.
+    Off,
+    /// Join rooms when the inviter is listed in `allowed_users` by name;
+    /// a `*` entry does not count.
+    #[default]
+    Allowed,
+    /// Join every room the bot is invited to.
+    All,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WhatsAppConfig {
     pub access_token: String,
@@ -189,10 +252,37 @@ pub struct EmailConfig {
     pub username: String,
     pub password: String,
     pub from_address: String,
+    /// Poll interval when `idle` is off or the server lacks IMAP IDLE.
     #[serde(default = "default_email_poll_interval")]
     pub poll_interval_secs: u64,
     #[serde(default)]
     pub allowed_senders: Vec<String>,
+    /// Wait for new mail with IMAP IDLE instead of polling.
+    #[serde(default = "default_true")]
+    pub idle: bool,
+    /// Folders to watch, each with its own routing; only `imap_folder`
+    /// when empty.
+    #[serde(default)]
+    pub folders: Vec<EmailFolderRule>,
+    /// Largest message fetched from IMAP; bigger ones are refused.
+    #[serde(default = "default_email_max_message_bytes")]
+    pub max_message_bytes: usize,
+}
+
+/// Routing for one watched IMAP folder.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct EmailFolderRule {
+    pub folder: String,
+    /// Senders answered from this folder; `allowed_senders` when unset.
+    #[serde(default)]
+    pub allowed_senders: Option<Vec<String>>,
+    /// Reply from this address (e.g. the support alias delivered here)
+    /// instead of `from_address`.
+    #[serde(default)]
+    pub from_address: Option<String>,
+    /// Move answered messages to this folder; they stay in place when unset.
+    #[serde(default)]
+    pub move_to: Option<String>,
 }
 
 impl Default for EmailConfig {
@@ -209,6 +299,9 @@ impl Default for EmailConfig {
             from_address: String::new(),
             poll_interval_secs: default_email_poll_interval(),
             allowed_senders: Vec::new(),
+            idle: true,
+            folders: Vec::new(),
+            max_message_bytes: default_email_max_message_bytes(),
         }
     }
 }
@@ -248,6 +341,9 @@ fn default_email_imap_folder() -> String {
 fn default_email_poll_interval() -> u64 {
     60
 }
+fn default_email_max_message_bytes() -> usize {
+    25 * 1024 * 1024
+}
 fn default_true() -> bool {
     true
 }
@@ -276,4 +372,31 @@ mod tests {
         assert_eq!(telegram.autonomy_level, Some(AutonomyLevel::ReadOnly));
         assert_eq!(telegram.tool_allowlist, Some(vec!["file_read".to_string()]));
     }
+
+    #[test]
+    fn email_config_parses_folder_rules() {
+        let email: EmailConfig = toml::from_str(
+            r#"
+imap_host = "imap.example.org"
+smtp_host = "smtp.example.org"
+username = "bot"
+password = "secret"
+from_address = "bot@example.org"
+
+[[folders]]
+folder = "Support"
+allowed_senders = ["*"]
+from_address = "support@example.org"
+move_to = "Support/Done"
+"#,
+        )
+        .unwrap();
+        assert!(email.idle);
+        assert_eq!(email.folders.len(), 1);
+        assert_eq!(email.folders[0].move_to.as_deref(), Some("Support/Done"));
+        assert_eq!(
+            email.folders[0].allowed_senders.as_deref(),
+            Some(&["*".to_string()][..])
+        );
+    }
 }
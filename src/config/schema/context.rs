@@ -0,0 +1,71 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContextConfig {
+    /// Context window in tokens per model, overriding the built-in table.
+    /// Keys are model names as passed to the provider.
+    #[serde(default)]
+    pub model_limits: HashMap<String, u64>,
+    /// Fraction of the context window at which older tool results are
+    /// elided from the tool loop's history.
+    #[serde(default = "default_elide_ratio")]
+    pub elide_ratio: f64,
+    /// Longest tool output (in characters) sent to the model as-is; longer
+    /// outputs are truncated and kept in full for `tool_output`.
+    #[serde(default = "default_max_tool_output_chars")]
+    pub max_tool_output_chars: usize,
+    /// Output token limit per model, matched by the longest key that
+    /// prefixes the model name. The tool loop requests this many output
+    /// tokens; models without an entry use the provider's default. Setting
+    /// the table replaces the built-in entries.
+    #[serde(default = "default_max_output_tokens")]
+    pub max_output_tokens: HashMap<String, u32>,
+}
+
+fn default_elide_ratio() -> f64 {
+    0.75
+}
+fn default_max_tool_output_chars() -> usize {
+    16_000
+}
+fn default_max_output_tokens() -> HashMap<String, u32> {
+    [
+        ("claude-opus-4", 32_000),
+        ("claude-sonnet-4", 64_000),
+        ("claude-3-7-sonnet", 64_000),
+        ("claude-3-5-sonnet", 8_192),
+        ("claude-3-5-haiku", 8_192),
+        ("claude-3-opus", 4_096),
+        ("claude-3-sonnet", 4_096),
+        ("claude-3-haiku", 4_096),
+    ]
+    .into_iter()
+    .map(|(model, limit)| (model.to_string(), limit))
+    .collect()
+}
+
+impl Default for ContextConfig {
+    fn default() -> Self {
+        Self {
+            model_limits: HashMap::new(),
+            elide_ratio: default_elide_ratio(),
+            max_tool_output_chars: default_max_tool_output_chars(),
+            max_output_tokens: default_max_output_tokens(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn context_config_partial_toml_uses_defaults() {
+        let cfg: ContextConfig = toml::from_str("[model_limits]\n\"llama3:8b\" = 8192").unwrap();
+        assert_eq!(cfg.model_limits.get("llama3:8b"), Some(&8192));
+        assert!((cfg.elide_ratio - 0.75).abs() < f64::EPSILON);
+        assert_eq!(cfg.max_tool_output_chars, 16_000);
+        assert_eq!(cfg.max_output_tokens.get("claude-sonnet-4"), Some(&64_000));
+    }
+}
@@ -93,6 +93,8 @@ impl Config {
                 decrypt_secret_string(&mut slack.bot_token, &store, self.secrets.encrypt)?;
             needs_persist |=
                 decrypt_secret_option(&mut slack.app_token, &store, self.secrets.encrypt)?;
+            needs_persist |=
+                decrypt_secret_option(&mut slack.signing_secret, &store, self.secrets.encrypt)?;
         }
         if let Some(webhook) = self.channels_config.webhook.as_mut() {
             needs_persist |=
@@ -101,6 +103,8 @@ impl Config {
         if let Some(matrix) = self.channels_config.matrix.as_mut() {
             needs_persist |=
                 decrypt_secret_string(&mut matrix.access_token, &store, self.secrets.encrypt)?;
+            needs_persist |=
+                decrypt_secret_option(&mut matrix.store_passphrase, &store, self.secrets.encrypt)?;
         }
         if let Some(whatsapp) = self.channels_config.whatsapp.as_mut() {
             needs_persist |=
@@ -118,6 +122,10 @@ impl Config {
             needs_persist |=
                 decrypt_secret_option(&mut irc.sasl_password, &store, self.secrets.encrypt)?;
         }
+        if let Some(mattermost) = self.channels_config.mattermost.as_mut() {
+            needs_persist |=
+                decrypt_secret_string(&mut mattermost.bot_token, &store, self.secrets.encrypt)?;
+        }
         if let Some(cloudflare) = self.tunnel.cloudflare.as_mut() {
             needs_persist |=
                 decrypt_secret_string(&mut cloudflare.token, &store, self.secrets.encrypt)?;
@@ -147,12 +155,14 @@ impl Config {
         if let Some(slack) = self.channels_config.slack.as_mut() {
             encrypt_secret_string(&mut slack.bot_token, &store)?;
             encrypt_secret_option(&mut slack.app_token, &store)?;
+            encrypt_secret_option(&mut slack.signing_secret, &store)?;
         }
         if let Some(webhook) = self.channels_config.webhook.as_mut() {
             encrypt_secret_option(&mut webhook.secret, &store)?;
         }
         if let Some(matrix) = self.channels_config.matrix.as_mut() {
             encrypt_secret_string(&mut matrix.access_token, &store)?;
+            encrypt_secret_option(&mut matrix.store_passphrase, &store)?;
         }
         if let Some(whatsapp) = self.channels_config.whatsapp.as_mut() {
             encrypt_secret_string(&mut whatsapp.access_token, &store)?;
@@ -164,6 +174,9 @@ impl Config {
             encrypt_secret_option(&mut irc.nickserv_password, &store)?;
             encrypt_secret_option(&mut irc.sasl_password, &store)?;
         }
+        if let Some(mattermost) = self.channels_config.mattermost.as_mut() {
+            encrypt_secret_string(&mut mattermost.bot_token, &store)?;
+        }
         if let Some(cloudflare) = self.tunnel.cloudflare.as_mut() {
             encrypt_secret_string(&mut cloudflare.token, &store)?;
         }
@@ -7,6 +7,6 @@ mod test_env;
 mod types;
 
 pub use types::{
-    BrowserConfig, ComposioConfig, Config, HeartbeatConfig, IdentityConfig, PersonaConfig,
-    ReliabilityConfig, RuntimeConfig, RuntimeKind, SecretsConfig,
+    BrowserConfig, ComposioConfig, Config, DEFAULT_MODEL, HeartbeatConfig, IdentityConfig,
+    PersonaConfig, ReliabilityConfig, RuntimeConfig, RuntimeKind, SecretsConfig,
 };
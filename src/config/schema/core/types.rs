@@ -1,13 +1,18 @@
 use crate::config::schema::{
-    AutonomyConfig, ChannelsConfig, GatewayConfig, McpConfig, MemoryConfig, ObservabilityConfig,
-    TasteConfig, ToolsConfig, TunnelConfig,
+    AutonomyConfig, ChannelsConfig, ContextConfig, GatewayConfig, McpConfig, MemoryConfig,
+    ObservabilityConfig, PlannerConfig, ProcessConfig, RoutingConfig, SkillsConfig, TasteConfig,
+    ToolsConfig, TunnelConfig, UsageConfig,
 };
 use crate::media::types::MediaConfig;
+use crate::session::SessionConfig;
 use anyhow::Result;
 use directories::UserDirs;
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 
+/// Model used when `default_model` is not configured.
+pub const DEFAULT_MODEL: &str = "anthropic/claude-sonnet-4-20250514";
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
     #[serde(skip)]
@@ -53,7 +58,26 @@ pub struct Config {
     #[serde(default)]
     pub mcp: McpConfig,
     #[serde(default)]
+    pub skills: SkillsConfig,
+    #[serde(default)]
+    pub planner: PlannerConfig,
+    #[serde(default)]
     pub taste: TasteConfig,
+    /// Per-conversation chat history for channels and the gateway.
+    #[serde(default)]
+    pub session: SessionConfig,
+    /// Conversation branches and cortex reflection in the channel runtime.
+    #[serde(default)]
+    pub process: ProcessConfig,
+    /// Context window limits and tool output truncation for agent turns.
+    #[serde(default)]
+    pub context: ContextConfig,
+    /// Per-turn model selection by message features and remaining budget.
+    #[serde(default)]
+    pub routing: RoutingConfig,
+    /// Token and cost accounting per entity, channel, session and model.
+    #[serde(default)]
+    pub usage: UsageConfig,
     #[serde(default = "default_locale")]
     pub locale: String,
 }
@@ -290,7 +314,7 @@ impl Default for Config {
             config_path: asteroniris_dir.join("config.toml"),
             api_key: None,
             default_provider: Some("openrouter".to_string()),
-            default_model: Some("anthropic/claude-sonnet-4-20250514".to_string()),
+            default_model: Some(DEFAULT_MODEL.to_string()),
             default_temperature: 0.7,
             observability: ObservabilityConfig::default(),
             autonomy: AutonomyConfig::default(),
@@ -309,7 +333,14 @@ impl Default for Config {
             identity: IdentityConfig::default(),
             tools: ToolsConfig::default(),
             mcp: McpConfig::default(),
+            skills: SkillsConfig::default(),
+            planner: PlannerConfig::default(),
             taste: TasteConfig::default(),
+            session: SessionConfig::default(),
+            process: ProcessConfig::default(),
+            context: ContextConfig::default(),
+            routing: RoutingConfig::default(),
+            usage: UsageConfig::default(),
             locale: default_locale(),
         }
     }
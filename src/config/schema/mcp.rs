@@ -16,6 +16,30 @@ pub struct McpConfig {
     pub import_json: Option<String>,
     #[serde(default)]
     pub servers: Vec<McpServerConfig>,
+    #[serde(default)]
+    pub serve: McpServeConfig,
+}
+
+/// Server mode: exposes the local tool registry to external MCP clients.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct McpServeConfig {
+    /// Mount the streamable HTTP/SSE transport on the gateway at `/mcp`.
+    #[serde(default)]
+    pub gateway: bool,
+    /// Restrict exposed tools to this list. Empty exposes every registered tool.
+    #[serde(default)]
+    pub allowed_tools: Vec<String>,
+}
+
+impl McpServeConfig {
+    #[must_use]
+    pub fn allowed_tool_set(&self) -> Option<HashSet<String>> {
+        if self.allowed_tools.is_empty() {
+            None
+        } else {
+            Some(self.allowed_tools.iter().cloned().collect())
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -130,6 +154,24 @@ mod tests {
         assert!(!cfg.enabled);
         assert!(cfg.import_json.is_none());
         assert!(cfg.servers.is_empty());
+        assert!(!cfg.serve.gateway);
+        assert!(cfg.serve.allowed_tool_set().is_none());
+    }
+
+    #[test]
+    fn serve_config_parses_allowed_tools() {
+        let cfg: McpConfig = toml::from_str(
+            r#"
+            [serve]
+            gateway = true
+            allowed_tools = ["file_read", "memory_recall"]
+            "#,
+        )
+        .unwrap();
+        assert!(cfg.serve.gateway);
+        let allowed = cfg.serve.allowed_tool_set().unwrap();
+        assert!(allowed.contains("file_read"));
+        assert!(!allowed.contains("shell"));
     }
 
     #[test]
@@ -137,6 +179,7 @@ mod tests {
         let cfg = McpConfig {
             enabled: true,
             import_json: None,
+            serve: McpServeConfig::default(),
             servers: vec![McpServerConfig {
                 name: String::new(),
                 transport: McpTransport::Stdio {
@@ -167,6 +210,7 @@ mod tests {
         let cfg = McpConfig {
             enabled: true,
             import_json: None,
+            serve: McpServeConfig::default(),
             servers: vec![server.clone(), server],
         };
         let errors = cfg.validate();
@@ -178,6 +222,7 @@ mod tests {
         let cfg = McpConfig {
             enabled: true,
             import_json: None,
+            serve: McpServeConfig::default(),
             servers: vec![
                 McpServerConfig {
                     name: "on".to_string(),
@@ -1,31 +1,43 @@
 mod autonomy;
 mod channels;
+mod context;
 mod core;
 mod gateway;
 mod mcp;
 mod memory;
 mod observability;
+mod planner;
+mod process;
+mod routing;
+mod skills;
 mod taste;
 mod tools;
 mod tunnel;
+mod usage;
 
 pub use crate::media::types::MediaConfig;
 pub use autonomy::{AutonomyConfig, AutonomyRolloutStage};
 #[allow(unused_imports)]
 pub use autonomy::{AutonomyRolloutConfig, TemperatureBand, TemperatureBandsConfig};
 pub use channels::{
-    ChannelsConfig, DiscordConfig, EmailConfig, IMessageConfig, IrcConfig, MatrixConfig,
-    SlackConfig, TelegramConfig, WebhookConfig, WhatsAppConfig,
+    ChannelsConfig, DiscordConfig, EmailConfig, EmailFolderRule, IMessageConfig, IrcConfig,
+    MatrixAutoJoin, MatrixConfig, MattermostConfig, SlackConfig, TelegramConfig, WebhookConfig,
+    WhatsAppConfig,
 };
+pub use context::ContextConfig;
 pub use core::{
-    BrowserConfig, ComposioConfig, Config, HeartbeatConfig, IdentityConfig, PersonaConfig,
-    ReliabilityConfig, RuntimeConfig, RuntimeKind, SecretsConfig,
+    BrowserConfig, ComposioConfig, Config, DEFAULT_MODEL, HeartbeatConfig, IdentityConfig,
+    PersonaConfig, ReliabilityConfig, RuntimeConfig, RuntimeKind, SecretsConfig,
 };
 pub use gateway::{GatewayConfig, GatewayDefenseMode};
 #[allow(unused_imports)]
-pub use mcp::{McpConfig, McpServerConfig, McpTransport};
+pub use mcp::{McpConfig, McpServeConfig, McpServerConfig, McpTransport};
 pub use memory::MemoryConfig;
 pub use observability::ObservabilityConfig;
+pub use planner::PlannerConfig;
+pub use process::ProcessConfig;
+pub use routing::{RouteRule, RoutingConfig};
+pub use skills::SkillsConfig;
 pub use taste::TasteConfig;
 #[allow(unused_imports)]
 pub use tools::{ToolEntry, ToolsConfig};
@@ -33,3 +45,4 @@ pub use tunnel::{
     CloudflareTunnelConfig, CustomTunnelConfig, NgrokTunnelConfig, TailscaleTunnelConfig,
     TunnelConfig,
 };
+pub use usage::UsageConfig;
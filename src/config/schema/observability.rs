@@ -3,12 +3,40 @@ use serde::{Deserialize, Serialize};
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ObservabilityConfig {
     pub backend: String,
+    /// Address for the daemon's standalone Prometheus `/metrics` listener,
+    /// e.g. `127.0.0.1:9464`. The listener has no authentication, so only
+    /// loopback addresses are accepted unless `metrics_allow_public` is set.
+    /// The gateway serves `/metrics` behind its own auth regardless.
+    #[serde(default)]
+    pub metrics_listen: Option<String>,
+    /// Let `metrics_listen` bind a non-loopback address.
+    #[serde(default)]
+    pub metrics_allow_public: bool,
+    /// OTLP/HTTP collector base URL for the `otel` backend; spans go to
+    /// `<endpoint>/v1/traces` and metrics to `<endpoint>/v1/metrics`.
+    #[serde(default = "default_otlp_endpoint")]
+    pub otlp_endpoint: String,
+    /// `service.name` resource attribute attached to exported telemetry.
+    #[serde(default = "default_otlp_service_name")]
+    pub otlp_service_name: String,
+}
+
+fn default_otlp_endpoint() -> String {
+    "http://127.0.0.1:4318".into()
+}
+
+fn default_otlp_service_name() -> String {
+    "asteroniris".into()
 }
 
 impl Default for ObservabilityConfig {
     fn default() -> Self {
         Self {
             backend: "none".into(),
+            metrics_listen: None,
+            metrics_allow_public: false,
+            otlp_endpoint: default_otlp_endpoint(),
+            otlp_service_name: default_otlp_service_name(),
         }
     }
 }
@@ -21,15 +49,23 @@ mod tests {
     fn default_observability_config() {
         let config = ObservabilityConfig::default();
         assert_eq!(config.backend, "none");
+        assert_eq!(config.otlp_endpoint, "http://127.0.0.1:4318");
     }
 
     #[test]
     fn observability_config_toml_round_trip() {
         let original = ObservabilityConfig {
             backend: "prometheus".into(),
+            metrics_listen: Some("127.0.0.1:9464".into()),
+            metrics_allow_public: false,
+            otlp_endpoint: "http://collector:4318".into(),
+            otlp_service_name: "iris-test".into(),
         };
         let toml = toml::to_string(&original).unwrap();
         let decoded: ObservabilityConfig = toml::from_str(&toml).unwrap();
         assert_eq!(decoded.backend, original.backend);
+        assert_eq!(decoded.metrics_listen, original.metrics_listen);
+        assert_eq!(decoded.otlp_endpoint, original.otlp_endpoint);
+        assert_eq!(decoded.otlp_service_name, original.otlp_service_name);
     }
 }
@@ -0,0 +1,78 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlannerConfig {
+    /// Maximum number of independent plan steps executed at the same time.
+    #[serde(default = "default_max_parallelism")]
+    pub max_parallelism: usize,
+    /// Per-step timeout in seconds (0 disables the timeout).
+    #[serde(default = "default_step_timeout_secs")]
+    pub step_timeout_secs: u64,
+    /// Retries for a failed step before its dependents are skipped.
+    #[serde(default)]
+    pub max_retries: u32,
+    /// Base delay between retries; multiplied by the attempt number.
+    #[serde(default = "default_retry_backoff_ms")]
+    pub retry_backoff_ms: u64,
+    /// Upper bound in seconds for step timeouts, including the ones a plan
+    /// sets on its own steps.
+    #[serde(default = "default_max_step_timeout_secs")]
+    pub max_step_timeout_secs: u64,
+    /// Upper bound for step retries, including the ones a plan sets on its
+    /// own steps.
+    #[serde(default = "default_max_step_retries")]
+    pub max_step_retries: u32,
+}
+
+fn default_max_parallelism() -> usize {
+    4
+}
+fn default_step_timeout_secs() -> u64 {
+    300
+}
+fn default_retry_backoff_ms() -> u64 {
+    500
+}
+fn default_max_step_timeout_secs() -> u64 {
+    3_600
+}
+fn default_max_step_retries() -> u32 {
+    5
+}
+
+impl Default for PlannerConfig {
+    fn default() -> Self {
+        Self {
+            max_parallelism: default_max_parallelism(),
+            step_timeout_secs: default_step_timeout_secs(),
+            max_retries: 0,
+            retry_backoff_ms: default_retry_backoff_ms(),
+            max_step_timeout_secs: default_max_step_timeout_secs(),
+            max_step_retries: default_max_step_retries(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn planner_config_defaults() {
+        let cfg = PlannerConfig::default();
+        assert_eq!(cfg.max_parallelism, 4);
+        assert_eq!(cfg.step_timeout_secs, 300);
+        assert_eq!(cfg.max_retries, 0);
+        assert_eq!(cfg.retry_backoff_ms, 500);
+        assert_eq!(cfg.max_step_timeout_secs, 3_600);
+        assert_eq!(cfg.max_step_retries, 5);
+    }
+
+    #[test]
+    fn planner_config_partial_toml_uses_defaults() {
+        let cfg: PlannerConfig = toml::from_str("max_parallelism = 2\nmax_retries = 1").unwrap();
+        assert_eq!(cfg.max_parallelism, 2);
+        assert_eq!(cfg.max_retries, 1);
+        assert_eq!(cfg.step_timeout_secs, 300);
+    }
+}
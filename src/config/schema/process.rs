@@ -0,0 +1,66 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProcessConfig {
+    /// Close a conversation branch after this many seconds without a message;
+    /// its history is reloaded from the session store on the next message.
+    #[serde(default = "default_branch_idle_secs")]
+    pub branch_idle_secs: u64,
+    /// Context window used to decide when a branch's history is compacted.
+    #[serde(default = "default_max_context_tokens")]
+    pub max_context_tokens: u64,
+    /// How often the cortex refreshes the bulletin injected into branch
+    /// prompts (0 disables the cortex).
+    #[serde(default = "default_cortex_interval_secs")]
+    pub cortex_interval_secs: u64,
+    /// Channel turns that may run at once across all conversations.
+    #[serde(default = "default_max_concurrent_turns")]
+    pub max_concurrent_turns: usize,
+    /// Channel messages that may be waiting or running at once; messages
+    /// past this are turned away with a busy reply.
+    #[serde(default = "default_max_pending_messages")]
+    pub max_pending_messages: usize,
+}
+
+fn default_branch_idle_secs() -> u64 {
+    1800
+}
+fn default_max_context_tokens() -> u64 {
+    128_000
+}
+fn default_cortex_interval_secs() -> u64 {
+    900
+}
+fn default_max_concurrent_turns() -> usize {
+    8
+}
+fn default_max_pending_messages() -> usize {
+    256
+}
+
+impl Default for ProcessConfig {
+    fn default() -> Self {
+        Self {
+            branch_idle_secs: default_branch_idle_secs(),
+            max_context_tokens: default_max_context_tokens(),
+            cortex_interval_secs: default_cortex_interval_secs(),
+            max_concurrent_turns: default_max_concurrent_turns(),
+            max_pending_messages: default_max_pending_messages(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn process_config_partial_toml_uses_defaults() {
+        let cfg: ProcessConfig = toml::from_str("branch_idle_secs = 60").unwrap();
+        assert_eq!(cfg.branch_idle_secs, 60);
+        assert_eq!(cfg.max_context_tokens, 128_000);
+        assert_eq!(cfg.cortex_interval_secs, 900);
+        assert_eq!(cfg.max_concurrent_turns, 8);
+        assert_eq!(cfg.max_pending_messages, 256);
+    }
+}
@@ -0,0 +1,95 @@
+use serde::{Deserialize, Serialize};
+
+/// Per-turn model selection for channel messages. Rules are tried in order
+/// and the first whose conditions all hold picks the model; turns no rule
+/// matches use `default_provider`/`default_model`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RoutingConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default)]
+    pub rules: Vec<RouteRule>,
+    /// Once less than this percentage of `autonomy.max_cost_per_day_cents`
+    /// remains, turns go to the cheapest model (priced by `[usage]`) among
+    /// the default and the rules whose other conditions match.
+    #[serde(default = "default_low_budget_percent")]
+    pub low_budget_percent: u8,
+}
+
+/// One routing rule. Unset conditions always hold.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RouteRule {
+    /// Shown in logs when the rule picks a model.
+    #[serde(default)]
+    pub name: Option<String>,
+    /// Provider to send the turn to; the default provider when unset.
+    #[serde(default)]
+    pub provider: Option<String>,
+    pub model: String,
+    /// Message length in characters, inclusive.
+    #[serde(default)]
+    pub min_chars: Option<usize>,
+    #[serde(default)]
+    pub max_chars: Option<usize>,
+    /// Whether the message carries image attachments.
+    #[serde(default)]
+    pub has_images: Option<bool>,
+    /// Whether tools are offered to the turn.
+    #[serde(default)]
+    pub needs_tools: Option<bool>,
+    /// Channel names (`telegram`, `slack`, ...) the rule applies to; any
+    /// channel when empty.
+    #[serde(default)]
+    pub channels: Vec<String>,
+    /// The rule is skipped once less than this percentage of the daily cost
+    /// budget remains, so later (cheaper) rules take over.
+    #[serde(default)]
+    pub min_budget_remaining_percent: Option<u8>,
+    /// On a low budget, let a priced model replace this rule's model even
+    /// when it has no known price. Unpriced models are kept otherwise,
+    /// since there is no telling whether the switch saves anything.
+    #[serde(default)]
+    pub replace_when_unpriced: bool,
+}
+
+fn default_low_budget_percent() -> u8 {
+    10
+}
+
+impl Default for RoutingConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            rules: Vec::new(),
+            low_budget_percent: default_low_budget_percent(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn routing_config_parses_rules_with_defaults() {
+        let cfg: RoutingConfig = toml::from_str(
+            r#"
+enabled = true
+
+[[rules]]
+name = "small talk"
+model = "claude-3-5-haiku-20241022"
+max_chars = 40
+needs_tools = false
+"#,
+        )
+        .unwrap();
+        assert!(cfg.enabled);
+        assert_eq!(cfg.low_budget_percent, 10);
+        assert_eq!(cfg.rules.len(), 1);
+        assert_eq!(cfg.rules[0].max_chars, Some(40));
+        assert!(cfg.rules[0].provider.is_none());
+        assert!(cfg.rules[0].channels.is_empty());
+        assert!(!cfg.rules[0].replace_when_unpriced);
+    }
+}
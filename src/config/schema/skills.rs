@@ -0,0 +1,74 @@
+use serde::{Deserialize, Serialize};
+
+fn default_true() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SkillsConfig {
+    /// Register tools declared in `SKILL.toml` manifests into the tool registry.
+    #[serde(default = "default_true")]
+    pub tools_enabled: bool,
+    /// Skills that are installed but not loaded (no prompt text, no tools).
+    #[serde(default)]
+    pub disabled: Vec<String>,
+}
+
+impl Default for SkillsConfig {
+    fn default() -> Self {
+        Self {
+            tools_enabled: true,
+            disabled: Vec::new(),
+        }
+    }
+}
+
+impl SkillsConfig {
+    #[must_use]
+    pub fn is_skill_enabled(&self, name: &str) -> bool {
+        !self.disabled.iter().any(|disabled| disabled == name)
+    }
+
+    /// Enable or disable a skill. Returns whether the setting changed.
+    pub fn set_skill_enabled(&mut self, name: &str, enabled: bool) -> bool {
+        if enabled == self.is_skill_enabled(name) {
+            return false;
+        }
+        if enabled {
+            self.disabled.retain(|disabled| disabled != name);
+        } else {
+            self.disabled.push(name.to_string());
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_skills_config_enables_everything() {
+        let cfg = SkillsConfig::default();
+        assert!(cfg.tools_enabled);
+        assert!(cfg.is_skill_enabled("weather"));
+    }
+
+    #[test]
+    fn skills_config_deserialize_disabled_list() {
+        let cfg: SkillsConfig = toml::from_str(r#"disabled = ["weather"]"#).unwrap();
+        assert!(cfg.tools_enabled);
+        assert!(!cfg.is_skill_enabled("weather"));
+        assert!(cfg.is_skill_enabled("deploy"));
+    }
+
+    #[test]
+    fn set_skill_enabled_toggles_once() {
+        let mut cfg = SkillsConfig::default();
+        assert!(cfg.set_skill_enabled("weather", false));
+        assert!(!cfg.set_skill_enabled("weather", false));
+        assert_eq!(cfg.disabled, vec!["weather".to_string()]);
+        assert!(cfg.set_skill_enabled("weather", true));
+        assert!(cfg.disabled.is_empty());
+    }
+}
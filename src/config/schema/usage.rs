@@ -0,0 +1,65 @@
+use crate::runtime::usage::ModelPricing;
+use serde::{Deserialize, Serialize};
+
+/// Per-turn usage accounting in `<workspace>/usage/usage.db`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UsageConfig {
+    /// Record provider tokens and estimated cost for every agent turn.
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    /// Refuse further actions once today's estimated spend reaches
+    /// `autonomy.max_cost_per_day_cents`. Off by default: spend is only
+    /// reported and used for alerts and routing.
+    #[serde(default)]
+    pub enforce_budget: bool,
+    /// Log a warning the first time each of these percentages of
+    /// `autonomy.max_cost_per_day_cents` is spent in a day.
+    #[serde(default = "default_alert_percents")]
+    pub alert_percents: Vec<u8>,
+    /// Prices for models missing from the built-in table, or overriding it.
+    /// Also used by `[routing]` to compare models.
+    #[serde(default)]
+    pub pricing: Vec<ModelPricing>,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+fn default_alert_percents() -> Vec<u8> {
+    vec![80, 100]
+}
+
+impl Default for UsageConfig {
+    fn default() -> Self {
+        Self {
+            enabled: default_enabled(),
+            enforce_budget: false,
+            alert_percents: default_alert_percents(),
+            pricing: Vec::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn usage_config_parses_custom_pricing() {
+        let cfg: UsageConfig = toml::from_str(
+            r#"
+alert_percents = [50]
+
+[[pricing]]
+model_pattern = "llama3"
+input_cost_per_million = 0.0
+output_cost_per_million = 0.0
+"#,
+        )
+        .unwrap();
+        assert!(cfg.enabled);
+        assert!(!cfg.enforce_budget);
+        assert_eq!(cfg.alert_percents, vec![50]);
+        assert_eq!(cfg.pricing[0].model_pattern, "llama3");
+    }
+}
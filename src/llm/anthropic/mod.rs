@@ -2,7 +2,9 @@ use crate::llm::types::{
     ContentBlock, ImageSource, MessageRole, ProviderMessage, ProviderResponse, StopReason,
 };
 use crate::llm::{
-    build_provider_client, scrub_secret_patterns,
+    build_provider_client,
+    options::{ChatOptions, OptionSupport, ResponseFormat},
+    scrub_secret_patterns,
     sse::{SseBuffer, parse_event_data_pairs},
     streaming::ProviderStream,
     tool_convert::{ToolFields, map_tools_optional},
@@ -13,13 +15,19 @@ use reqwest::Client;
 use std::future::Future;
 use std::pin::Pin;
 
+mod models;
 mod types;
 use types::{
-    AnthropicImageSource, AnthropicToolDef, ChatRequest, ChatResponse, InputContentBlock, Message,
-    MessageContent, ResponseContentBlock, StreamContentBlockDelta, StreamContentBlockStart,
-    StreamContentBlockType, StreamDelta, StreamMessageDelta, StreamMessageStart,
+    AnthropicImageSource, AnthropicToolDef, CacheControl, ChatRequest, ChatResponse,
+    InputContentBlock, Message, MessageContent, ResponseContentBlock, StreamContentBlockDelta,
+    StreamContentBlockStart, StreamContentBlockType, StreamDelta, StreamMessageDelta,
+    StreamMessageStart, SystemBlock, ThinkingConfig, ToolChoice, Usage,
 };
 
+/// Tool the model is forced to call when a response format is requested;
+/// its input is the structured reply.
+const STRUCTURED_OUTPUT_TOOL: &str = "structured_output";
+
 pub struct AnthropicProvider {
     /// Pre-computed auth: `("Authorization", "Bearer <token>")` or `("x-api-key", "<key>")`.
     cached_auth: Option<(&'static str, String)>,
@@ -61,21 +69,174 @@ impl AnthropicProvider {
         model: &str,
         temperature: f64,
     ) -> ChatRequest {
+        let messages = vec![Message {
+            role: "user",
+            content: MessageContent::Text(message.to_string()),
+        }];
+        let options = ChatOptions::new(temperature);
+        Self::chat_request(
+            model,
+            system_prompt.map(ToString::to_string),
+            messages,
+            None,
+            &options,
+            Self::thinking_budget(model, &options),
+        )
+    }
+
+    fn build_tools_request(
+        system_prompt: Option<&str>,
+        messages: &[ProviderMessage],
+        tools: &[ToolSpec],
+        model: &str,
+        options: &ChatOptions,
+    ) -> ChatRequest {
+        let thinking = Self::thinking_budget(model, options);
+        let anthropic_messages = messages
+            .iter()
+            .map(|message| Self::provider_message_to_message(message, thinking.is_some()))
+            .collect();
+        let anthropic_tools = map_tools_optional(tools, |tool| {
+            let fields = ToolFields::from_tool_with_description(
+                tool,
+                scrub_secret_patterns(&tool.description).into_owned(),
+            );
+
+            AnthropicToolDef {
+                name: fields.name,
+                description: fields.description,
+                input_schema: fields.parameters,
+                cache_control: None,
+            }
+        });
+
+        Self::chat_request(
+            model,
+            system_prompt.map(|system| scrub_secret_patterns(system).into_owned()),
+            anthropic_messages,
+            anthropic_tools,
+            options,
+            thinking,
+        )
+    }
+
+    /// Request shared by every entry point. `max_tokens` is the caller's cap
+    /// (the tool loop sets it from `[context] max_output_tokens`), the system prompt and the last tool
+    /// definition carry cache breakpoints so the stable prefix is cached
+    /// across turns, and a thinking budget forces the temperature the API
+    /// requires with it. A response format becomes a forced
+    /// [`STRUCTURED_OUTPUT_TOOL`] call.
+    fn chat_request(
+        model: &str,
+        system: Option<String>,
+        messages: Vec<Message>,
+        mut tools: Option<Vec<AnthropicToolDef>>,
+        options: &ChatOptions,
+        thinking_budget: Option<u32>,
+    ) -> ChatRequest {
+        if let Some(last) = tools.as_mut().and_then(|tools| tools.last_mut()) {
+            last.cache_control = Some(CacheControl::ephemeral());
+        }
+        let tool_choice = options.response_format.as_ref().map(|format| {
+            tools
+                .get_or_insert_default()
+                .push(Self::structured_output_tool(format));
+            ToolChoice {
+                kind: "tool",
+                name: STRUCTURED_OUTPUT_TOOL.to_string(),
+            }
+        });
+        let system = system.filter(|text| !text.is_empty()).map(|text| {
+            vec![SystemBlock {
+                kind: "text",
+                text,
+                cache_control: Some(CacheControl::ephemeral()),
+            }]
+        });
+        let temperature = if thinking_budget.is_some() {
+            Some(1.0)
+        } else if options.top_p.is_some() {
+            None
+        } else {
+            Some(options.temperature)
+        };
+
         ChatRequest {
             model: model.to_string(),
-            max_tokens: 4096,
-            system: system_prompt.map(ToString::to_string),
-            messages: vec![Message {
-                role: "user",
-                content: MessageContent::Text(message.to_string()),
-            }],
-            tools: None,
+            max_tokens: Self::max_tokens(options),
+            system,
+            messages,
+            tools,
+            tool_choice,
             temperature,
+            top_p: options.top_p,
+            stop_sequences: options.stop_sequences.clone(),
+            thinking: thinking_budget.map(|budget_tokens| ThinkingConfig {
+                kind: "enabled",
+                budget_tokens,
+            }),
             stream: None,
         }
     }
 
-    fn provider_message_to_message(provider_message: &ProviderMessage) -> Message {
+    /// The API requires an output limit; callers that set none get
+    /// [`models::DEFAULT_MAX_OUTPUT_TOKENS`].
+    fn max_tokens(options: &ChatOptions) -> u32 {
+        options
+            .max_tokens
+            .unwrap_or(models::DEFAULT_MAX_OUTPUT_TOKENS)
+    }
+
+    /// Thinking budget for `model` at the conversation's `/think` level.
+    /// Forced tool calls cannot be combined with thinking, so requests with
+    /// a response format get none.
+    fn thinking_budget(model: &str, options: &ChatOptions) -> Option<u32> {
+        if options.response_format.is_some() {
+            return None;
+        }
+        let requested = options.thinking.budget_tokens()?;
+        models::thinking_budget(model, requested, Self::max_tokens(options))
+    }
+
+    fn structured_output_tool(format: &ResponseFormat) -> AnthropicToolDef {
+        let input_schema = match format {
+            ResponseFormat::Json => serde_json::json!({"type": "object"}),
+            ResponseFormat::JsonSchema { schema, .. } => schema.clone(),
+        };
+        AnthropicToolDef {
+            name: STRUCTURED_OUTPUT_TOOL.to_string(),
+            description: "Respond with the requested JSON as this tool's input.".to_string(),
+            input_schema,
+            cache_control: None,
+        }
+    }
+
+    /// Turn the forced [`STRUCTURED_OUTPUT_TOOL`] call back into the JSON
+    /// text reply the caller asked for.
+    fn unwrap_structured_output(response: &mut ProviderResponse) {
+        let Some(json) = response
+            .content_blocks
+            .iter()
+            .find_map(|block| match block {
+                ContentBlock::ToolUse { name, input, .. } if name == STRUCTURED_OUTPUT_TOOL => {
+                    Some(input.to_string())
+                }
+                _ => None,
+            })
+        else {
+            return;
+        };
+        response.content_blocks = vec![ContentBlock::Text { text: json.clone() }];
+        response.text = json;
+        response.stop_reason = Some(StopReason::EndTurn);
+    }
+
+    /// Thinking blocks are only sent back, signed, while thinking is enabled;
+    /// the API rejects them otherwise.
+    fn provider_message_to_message(
+        provider_message: &ProviderMessage,
+        include_thinking: bool,
+    ) -> Message {
         let role = match provider_message.role {
             MessageRole::User | MessageRole::System => "user",
             MessageRole::Assistant => "assistant",
@@ -91,24 +252,33 @@ impl AnthropicProvider {
         let blocks = provider_message
             .content
             .iter()
-            .map(|block| match block {
-                ContentBlock::Text { text } => InputContentBlock::Text {
+            .filter_map(|block| match block {
+                ContentBlock::Text { text } => Some(InputContentBlock::Text {
                     text: scrub_secret_patterns(text).into_owned(),
-                },
-                ContentBlock::ToolUse { id, name, input } => InputContentBlock::ToolUse {
+                }),
+                ContentBlock::Thinking {
+                    thinking,
+                    signature,
+                } => (include_thinking && !signature.is_empty()).then(|| {
+                    InputContentBlock::Thinking {
+                        thinking: thinking.clone(),
+                        signature: signature.clone(),
+                    }
+                }),
+                ContentBlock::ToolUse { id, name, input } => Some(InputContentBlock::ToolUse {
                     id: id.clone(),
                     name: name.clone(),
                     input: input.clone(),
-                },
+                }),
                 ContentBlock::ToolResult {
                     tool_use_id,
                     content,
                     is_error,
-                } => InputContentBlock::ToolResult {
+                } => Some(InputContentBlock::ToolResult {
                     tool_use_id: tool_use_id.clone(),
                     content: scrub_secret_patterns(content).into_owned(),
                     is_error: if *is_error { Some(true) } else { None },
-                },
+                }),
                 ContentBlock::Image { source } => {
                     let anthropic_source = match source {
                         ImageSource::Base64 { media_type, data } => AnthropicImageSource::Base64 {
@@ -117,9 +287,9 @@ impl AnthropicProvider {
                         },
                         ImageSource::Url { url } => AnthropicImageSource::Url { url: url.clone() },
                     };
-                    InputContentBlock::Image {
+                    Some(InputContentBlock::Image {
                         source: anthropic_source,
-                    }
+                    })
                 }
             })
             .collect();
@@ -146,6 +316,13 @@ impl AnthropicProvider {
                 ResponseContentBlock::Text { text } => {
                     Some(ContentBlock::Text { text: text.clone() })
                 }
+                ResponseContentBlock::Thinking {
+                    thinking,
+                    signature,
+                } => Some(ContentBlock::Thinking {
+                    thinking: thinking.clone(),
+                    signature: signature.clone(),
+                }),
                 ResponseContentBlock::ToolUse { id, name, input } => Some(ContentBlock::ToolUse {
                     id: id.clone(),
                     name: name.clone(),
@@ -170,6 +347,16 @@ impl AnthropicProvider {
         if text.is_empty() { None } else { Some(text) }
     }
 
+    fn log_cache_usage(usage: &Usage) {
+        if usage.cache_creation_input_tokens > 0 || usage.cache_read_input_tokens > 0 {
+            tracing::debug!(
+                cache_creation_input_tokens = usage.cache_creation_input_tokens,
+                cache_read_input_tokens = usage.cache_read_input_tokens,
+                "anthropic prompt cache usage"
+            );
+        }
+    }
+
     fn extract_text(chat_response: &ChatResponse) -> anyhow::Result<String> {
         Self::text_from_content_blocks(&Self::parse_content_blocks(&chat_response.content))
             .ok_or_else(|| anyhow::anyhow!("No response from Anthropic"))
@@ -247,6 +434,7 @@ impl AnthropicProvider {
             "message_start" => {
                 if let Ok(msg) = serde_json::from_str::<StreamMessageStart>(data) {
                     if let Some(usage) = msg.message.usage {
+                        Self::log_cache_usage(&usage);
                         *input_tokens = Some(usage.input_tokens);
                     }
                     events.push(StreamEvent::ResponseStart {
@@ -270,6 +458,11 @@ impl AnthropicProvider {
                                 events.push(StreamEvent::TextDelta { text });
                             }
                         }
+                        StreamContentBlockType::Thinking { thinking } => {
+                            if !thinking.is_empty() {
+                                events.push(StreamEvent::ThinkingDelta { thinking });
+                            }
+                        }
                         StreamContentBlockType::Unknown => {}
                     }
                 }
@@ -288,6 +481,12 @@ impl AnthropicProvider {
                                 input_json_delta: partial_json,
                             });
                         }
+                        StreamDelta::ThinkingDelta { thinking } => {
+                            events.push(StreamEvent::ThinkingDelta { thinking });
+                        }
+                        StreamDelta::SignatureDelta { signature } => {
+                            events.push(StreamEvent::ThinkingSignature { signature });
+                        }
                         StreamDelta::Unknown => {}
                     }
                 }
@@ -316,36 +515,22 @@ impl AnthropicProvider {
         messages: &[ProviderMessage],
         tools: &[ToolSpec],
         model: &str,
-        temperature: f64,
+        options: &ChatOptions,
     ) -> anyhow::Result<ProviderStream> {
         use futures_util::StreamExt;
 
-        let anthropic_messages: Vec<Message> = messages
-            .iter()
-            .map(Self::provider_message_to_message)
-            .collect();
-        let anthropic_tools = map_tools_optional(tools, |tool| {
-            let fields = ToolFields::from_tool_with_description(
-                tool,
-                scrub_secret_patterns(&tool.description).into_owned(),
-            );
-
-            AnthropicToolDef {
-                name: fields.name,
-                description: fields.description,
-                input_schema: fields.parameters,
-            }
-        });
+        // The structured output tool's input is only usable once complete.
+        if options.response_format.is_some() {
+            let response = self
+                .chat_with_tools(system_prompt, messages, tools, model, options)
+                .await?;
+            return Ok(Box::pin(futures_util::stream::iter(
+                crate::llm::streaming::resp_to_events(response),
+            )));
+        }
 
-        let request = ChatRequest {
-            model: model.to_string(),
-            max_tokens: 4096,
-            system: system_prompt.map(|system| scrub_secret_patterns(system).into_owned()),
-            messages: anthropic_messages,
-            tools: anthropic_tools,
-            temperature,
-            stream: Some(true),
-        };
+        let mut request = Self::build_tools_request(system_prompt, messages, tools, model, options);
+        request.stream = Some(true);
 
         let response = self.call_api_streaming(&request).await?;
         let mut byte_stream = response.bytes_stream();
@@ -388,6 +573,10 @@ impl Provider for AnthropicProvider {
             tool_calling: true,
             streaming: true,
             vision: true,
+            options: OptionSupport {
+                seed: false,
+                ..OptionSupport::ALL
+            },
         }
     }
 
@@ -419,6 +608,7 @@ impl Provider for AnthropicProvider {
                 .await?;
             let text = Self::extract_text(&chat_response)?;
             let mut provider_response = if let Some(usage) = chat_response.usage {
+                Self::log_cache_usage(&usage);
                 ProviderResponse::with_usage(text, usage.input_tokens, usage.output_tokens)
             } else {
                 ProviderResponse::text_only(text)
@@ -436,41 +626,17 @@ impl Provider for AnthropicProvider {
         messages: &'a [ProviderMessage],
         tools: &'a [ToolSpec],
         model: &'a str,
-        temperature: f64,
+        options: &'a ChatOptions,
     ) -> Pin<Box<dyn Future<Output = anyhow::Result<ProviderResponse>> + Send + 'a>> {
         Box::pin(async move {
-            let anthropic_messages = messages
-                .iter()
-                .map(Self::provider_message_to_message)
-                .collect();
-            let anthropic_tools = map_tools_optional(tools, |tool| {
-                let fields = ToolFields::from_tool_with_description(
-                    tool,
-                    scrub_secret_patterns(&tool.description).into_owned(),
-                );
-
-                AnthropicToolDef {
-                    name: fields.name,
-                    description: fields.description,
-                    input_schema: fields.parameters,
-                }
-            });
-
-            let request = ChatRequest {
-                model: model.to_string(),
-                max_tokens: 4096,
-                system: system_prompt.map(|system| scrub_secret_patterns(system).into_owned()),
-                messages: anthropic_messages,
-                tools: anthropic_tools,
-                temperature,
-                stream: None,
-            };
+            let request = Self::build_tools_request(system_prompt, messages, tools, model, options);
             let chat_response = self.call_api_with_request(&request).await?;
 
             let content_blocks = Self::parse_content_blocks(&chat_response.content);
             let text = Self::text_from_content_blocks(&content_blocks).unwrap_or_default();
 
             let mut provider_response = if let Some(usage) = chat_response.usage {
+                Self::log_cache_usage(&usage);
                 ProviderResponse::with_usage(text, usage.input_tokens, usage.output_tokens)
             } else {
                 ProviderResponse::text_only(text)
@@ -482,6 +648,9 @@ impl Provider for AnthropicProvider {
             if let Some(api_model) = chat_response.model {
                 provider_response = provider_response.with_model(api_model);
             }
+            if options.response_format.is_some() {
+                Self::unwrap_structured_output(&mut provider_response);
+            }
 
             Ok(provider_response)
         })
@@ -493,10 +662,10 @@ impl Provider for AnthropicProvider {
         messages: &'a [ProviderMessage],
         tools: &'a [ToolSpec],
         model: &'a str,
-        temperature: f64,
+        options: &'a ChatOptions,
     ) -> Pin<Box<dyn Future<Output = anyhow::Result<ProviderStream>> + Send + 'a>> {
         Box::pin(async move {
-            self.chat_with_tools_stream_impl(system_prompt, messages, tools, model, temperature)
+            self.chat_with_tools_stream_impl(system_prompt, messages, tools, model, options)
                 .await
         })
     }
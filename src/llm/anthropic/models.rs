@@ -0,0 +1,36 @@
+//! Thinking support and budgets for Claude models. Output limits per model
+//! are configured in `[context] max_output_tokens`.
+
+/// Output limit for requests that do not set `max_tokens`.
+pub(super) const DEFAULT_MAX_OUTPUT_TOKENS: u32 = 8_192;
+/// Smallest thinking budget the API accepts.
+pub(super) const MIN_THINKING_BUDGET: u32 = 1_024;
+/// Output tokens kept free for the answer itself when thinking is enabled.
+const ANSWER_RESERVE_TOKENS: u32 = 4_096;
+
+/// Models that predate extended thinking.
+const NO_THINKING_PREFIXES: &[&str] = &[
+    "claude-3-5",
+    "claude-3-opus",
+    "claude-3-sonnet",
+    "claude-3-haiku",
+    "claude-2",
+    "claude-instant",
+];
+
+pub(super) fn supports_thinking(model: &str) -> bool {
+    !NO_THINKING_PREFIXES
+        .iter()
+        .any(|prefix| model.starts_with(prefix))
+}
+
+/// Thinking budget for `model` at `requested` tokens, capped so the answer
+/// still fits in `max_tokens`. `None` when the model cannot think or the
+/// cap leaves less than the API minimum.
+pub(super) fn thinking_budget(model: &str, requested: u32, max_tokens: u32) -> Option<u32> {
+    if !supports_thinking(model) {
+        return None;
+    }
+    let budget = requested.min(max_tokens.saturating_sub(ANSWER_RESERVE_TOKENS));
+    (budget >= MIN_THINKING_BUDGET).then_some(budget)
+}
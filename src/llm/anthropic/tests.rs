@@ -1,4 +1,6 @@
 use super::*;
+use crate::llm::ThinkingLevel;
+use crate::llm::options::ResponseFormat;
 use crate::llm::sse::parse_event_data_pairs;
 use crate::llm::types::{ImageSource, MessageRole, ProviderMessage, StopReason};
 
@@ -112,7 +114,11 @@ fn chat_request_serializes_without_system() {
             content: MessageContent::Text("hello".to_string()),
         }],
         tools: None,
-        temperature: 0.7,
+        tool_choice: None,
+        temperature: Some(0.7),
+        top_p: None,
+        stop_sequences: Vec::new(),
+        thinking: None,
         stream: None,
     };
     let json = serde_json::to_string(&req).unwrap();
@@ -130,17 +136,25 @@ fn chat_request_serializes_with_system() {
     let req = ChatRequest {
         model: "claude-3-opus".to_string(),
         max_tokens: 4096,
-        system: Some("You are AsteronIris".to_string()),
+        system: Some(vec![SystemBlock {
+            kind: "text",
+            text: "You are AsteronIris".to_string(),
+            cache_control: None,
+        }]),
         messages: vec![Message {
             role: "user",
             content: MessageContent::Text("hello".to_string()),
         }],
         tools: None,
-        temperature: 0.7,
+        tool_choice: None,
+        temperature: Some(0.7),
+        top_p: None,
+        stop_sequences: Vec::new(),
+        thinking: None,
         stream: None,
     };
     let json = serde_json::to_string(&req).unwrap();
-    assert!(json.contains("\"system\":\"You are AsteronIris\""));
+    assert!(json.contains("\"system\":[{\"type\":\"text\",\"text\":\"You are AsteronIris\"}]"));
 }
 
 #[test]
@@ -163,8 +177,13 @@ fn chat_request_serializes_with_tools() {
                 },
                 "required": ["command"]
             }),
+            cache_control: None,
         }]),
-        temperature: 0.7,
+        tool_choice: None,
+        temperature: Some(0.7),
+        top_p: None,
+        stop_sequences: Vec::new(),
+        thinking: None,
         stream: None,
     };
 
@@ -256,8 +275,8 @@ fn provider_message_to_message_supports_tool_result_and_mixed_assistant_blocks()
     };
     let tool_result = ProviderMessage::tool_result("toolu_1", "src", false);
 
-    let assistant_message = AnthropicProvider::provider_message_to_message(&assistant);
-    let tool_result_message = AnthropicProvider::provider_message_to_message(&tool_result);
+    let assistant_message = AnthropicProvider::provider_message_to_message(&assistant, false);
+    let tool_result_message = AnthropicProvider::provider_message_to_message(&tool_result, false);
 
     let assistant_json = serde_json::to_value(&assistant_message).unwrap();
     let tool_result_json = serde_json::to_value(&tool_result_message).unwrap();
@@ -284,7 +303,7 @@ fn provider_message_to_message_maps_image_block() {
             },
         ],
     };
-    let mapped = AnthropicProvider::provider_message_to_message(&msg);
+    let mapped = AnthropicProvider::provider_message_to_message(&msg, false);
     assert_eq!(mapped.role, "user");
 
     let json = serde_json::to_value(&mapped.content).unwrap();
@@ -354,10 +373,257 @@ fn temperature_range_serializes() {
             system: None,
             messages: vec![],
             tools: None,
-            temperature: temp,
+            tool_choice: None,
+            temperature: Some(temp),
+            top_p: None,
+            stop_sequences: Vec::new(),
+            thinking: None,
             stream: None,
         };
         let json = serde_json::to_string(&req).unwrap();
         assert!(json.contains(&format!("{temp}")));
     }
 }
+
+#[test]
+fn max_tokens_come_from_the_caller_or_the_default() {
+    let request = AnthropicProvider::build_request(None, "hi", "claude-sonnet-4-20250514", 0.7);
+    assert_eq!(request.max_tokens, 8_192);
+    let request = AnthropicProvider::build_tools_request(
+        None,
+        &[ProviderMessage::user("hi")],
+        &[],
+        "claude-sonnet-4-20250514",
+        &ChatOptions::new(0.7).with_max_tokens(64_000),
+    );
+    assert_eq!(request.max_tokens, 64_000);
+}
+
+#[test]
+fn tools_request_marks_system_and_last_tool_for_caching() {
+    let tools = vec![
+        ToolSpec {
+            name: "shell".to_string(),
+            description: "Run a shell command".to_string(),
+            parameters: serde_json::json!({"type": "object"}),
+        },
+        ToolSpec {
+            name: "file_read".to_string(),
+            description: "Read a file".to_string(),
+            parameters: serde_json::json!({"type": "object"}),
+        },
+    ];
+    let request = AnthropicProvider::build_tools_request(
+        Some("You are AsteronIris"),
+        &[ProviderMessage::user("hello")],
+        &tools,
+        "claude-3-5-haiku-20241022",
+        &ChatOptions::new(0.7),
+    );
+
+    let json = serde_json::to_value(&request).unwrap();
+    assert_eq!(json["system"][0]["text"], "You are AsteronIris");
+    assert_eq!(json["system"][0]["cache_control"]["type"], "ephemeral");
+    assert!(json["tools"][0].get("cache_control").is_none());
+    assert_eq!(json["tools"][1]["cache_control"]["type"], "ephemeral");
+    assert!(json.get("thinking").is_none());
+}
+
+#[test]
+fn chat_request_with_thinking_forces_temperature() {
+    let request = AnthropicProvider::chat_request(
+        "claude-opus-4-20250514",
+        None,
+        vec![],
+        None,
+        &ChatOptions::new(0.2).with_max_tokens(32_000),
+        Some(16_384),
+    );
+
+    let json = serde_json::to_value(&request).unwrap();
+    assert_eq!(json["thinking"]["type"], "enabled");
+    assert_eq!(json["thinking"]["budget_tokens"], 16_384);
+    assert_eq!(json["temperature"], 1.0);
+    assert_eq!(json["max_tokens"], 32_000);
+}
+
+#[test]
+fn chat_options_map_to_request_fields() {
+    let options = ChatOptions::new(0.2)
+        .with_stop_sequences(vec!["STOP".to_string()])
+        .with_top_p(0.9);
+    let request = AnthropicProvider::build_tools_request(
+        None,
+        &[ProviderMessage::user("hi")],
+        &[],
+        "claude-3-5-haiku-20241022",
+        &options,
+    );
+
+    let json = serde_json::to_value(&request).unwrap();
+    assert_eq!(json["max_tokens"], 8_192);
+    assert_eq!(json["stop_sequences"][0], "STOP");
+    assert_eq!(json["top_p"], 0.9);
+    assert!(json.get("temperature").is_none());
+
+    let request = AnthropicProvider::build_tools_request(
+        None,
+        &[ProviderMessage::user("hi")],
+        &[],
+        "claude-3-5-haiku-20241022",
+        &ChatOptions::new(0.2).with_max_tokens(256),
+    );
+    assert_eq!(request.max_tokens, 256);
+}
+
+#[test]
+fn response_format_forces_structured_output_tool() {
+    let schema = serde_json::json!({"type": "object", "properties": {"city": {"type": "string"}}});
+    let options = ChatOptions::new(0.2)
+        .with_response_format(ResponseFormat::json_schema("place", schema.clone()));
+    let request = AnthropicProvider::build_tools_request(
+        None,
+        &[ProviderMessage::user("Where is the Eiffel Tower?")],
+        &[],
+        "claude-sonnet-4-20250514",
+        &options,
+    );
+
+    let json = serde_json::to_value(&request).unwrap();
+    assert_eq!(json["tools"][0]["name"], STRUCTURED_OUTPUT_TOOL);
+    assert_eq!(json["tools"][0]["input_schema"], schema);
+    assert_eq!(json["tool_choice"]["type"], "tool");
+    assert_eq!(json["tool_choice"]["name"], STRUCTURED_OUTPUT_TOOL);
+    assert_eq!(
+        AnthropicProvider::thinking_budget("claude-sonnet-4-20250514", &options),
+        None
+    );
+
+    let mut response = ProviderResponse::text_only(String::new());
+    response.content_blocks = vec![ContentBlock::ToolUse {
+        id: "toolu_1".to_string(),
+        name: STRUCTURED_OUTPUT_TOOL.to_string(),
+        input: serde_json::json!({"city": "Paris"}),
+    }];
+    response.stop_reason = Some(StopReason::ToolUse);
+    AnthropicProvider::unwrap_structured_output(&mut response);
+
+    assert_eq!(response.text, r#"{"city":"Paris"}"#);
+    assert_eq!(response.stop_reason, Some(StopReason::EndTurn));
+    assert!(matches!(
+        response.content_blocks.as_slice(),
+        [ContentBlock::Text { text }] if text == r#"{"city":"Paris"}"#
+    ));
+}
+
+#[test]
+fn thinking_level_comes_from_the_request_options() {
+    let model = "claude-sonnet-4-20250514";
+    let off = ChatOptions::new(0.2).with_max_tokens(64_000);
+    assert_eq!(AnthropicProvider::thinking_budget(model, &off), None);
+
+    let high = off.clone().with_thinking(ThinkingLevel::High);
+    assert_eq!(
+        AnthropicProvider::thinking_budget(model, &high),
+        Some(32_768)
+    );
+    let request = AnthropicProvider::build_tools_request(
+        None,
+        &[ProviderMessage::user("hi")],
+        &[],
+        model,
+        &high,
+    );
+    assert_eq!(request.max_tokens, 64_000);
+    assert!(request.thinking.is_some());
+
+    // Without a caller limit the default leaves room for a small budget only.
+    let low = ChatOptions::new(0.2).with_thinking(ThinkingLevel::Low);
+    assert_eq!(AnthropicProvider::thinking_budget(model, &low), Some(4_096));
+}
+
+#[test]
+fn thinking_budget_respects_model_support_and_output_limit() {
+    assert_eq!(
+        models::thinking_budget("claude-sonnet-4-20250514", 16_384, 64_000),
+        Some(16_384)
+    );
+    assert_eq!(
+        models::thinking_budget("claude-opus-4-20250514", 32_768, 32_000),
+        Some(27_904)
+    );
+    assert_eq!(
+        models::thinking_budget("claude-3-5-sonnet-20241022", 4_096, 8_192),
+        None
+    );
+    assert_eq!(models::thinking_budget("claude-future", 4_096, 4_096), None);
+}
+
+#[test]
+fn thinking_blocks_round_trip_only_when_enabled_and_signed() {
+    let json = r#"{
+        "content":[
+            {"type":"thinking","thinking":"Let me check.","signature":"sig-1"},
+            {"type":"text","text":"Done."}
+        ],
+        "stop_reason":"end_turn",
+        "usage":{"input_tokens":10,"output_tokens":5,"cache_read_input_tokens":8}
+    }"#;
+    let resp: ChatResponse = serde_json::from_str(json).unwrap();
+    assert_eq!(resp.usage.as_ref().unwrap().cache_read_input_tokens, 8);
+    let blocks = AnthropicProvider::parse_content_blocks(&resp.content);
+    assert!(matches!(
+        &blocks[0],
+        ContentBlock::Thinking { thinking, signature }
+        if thinking == "Let me check." && signature == "sig-1"
+    ));
+    assert_eq!(
+        AnthropicProvider::text_from_content_blocks(&blocks).as_deref(),
+        Some("Done.")
+    );
+
+    let assistant = ProviderMessage {
+        role: MessageRole::Assistant,
+        content: blocks,
+    };
+    let enabled = serde_json::to_value(
+        AnthropicProvider::provider_message_to_message(&assistant, true).content,
+    )
+    .unwrap();
+    assert_eq!(enabled[0]["type"], "thinking");
+    assert_eq!(enabled[0]["signature"], "sig-1");
+
+    let disabled = serde_json::to_value(
+        AnthropicProvider::provider_message_to_message(&assistant, false).content,
+    )
+    .unwrap();
+    assert_eq!(disabled.as_array().unwrap().len(), 1);
+    assert_eq!(disabled[0]["type"], "text");
+}
+
+#[test]
+fn stream_maps_thinking_and_signature_deltas() {
+    let mut input_tokens = None;
+    let mut output_tokens = None;
+    let thinking = AnthropicProvider::stream_events_from_sse(
+        "content_block_delta",
+        r#"{"index":0,"delta":{"type":"thinking_delta","thinking":"Hmm"}}"#,
+        &mut input_tokens,
+        &mut output_tokens,
+    );
+    let signature = AnthropicProvider::stream_events_from_sse(
+        "content_block_delta",
+        r#"{"index":0,"delta":{"type":"signature_delta","signature":"sig-1"}}"#,
+        &mut input_tokens,
+        &mut output_tokens,
+    );
+
+    assert!(matches!(
+        thinking.as_slice(),
+        [crate::llm::streaming::StreamEvent::ThinkingDelta { thinking }] if thinking == "Hmm"
+    ));
+    assert!(matches!(
+        signature.as_slice(),
+        [crate::llm::streaming::StreamEvent::ThinkingSignature { signature }] if signature == "sig-1"
+    ));
+}
@@ -5,15 +5,63 @@ pub(super) struct ChatRequest {
     pub(super) model: String,
     pub(super) max_tokens: u32,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub(super) system: Option<String>,
+    pub(super) system: Option<Vec<SystemBlock>>,
     pub(super) messages: Vec<Message>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub(super) tools: Option<Vec<AnthropicToolDef>>,
-    pub(super) temperature: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(super) tool_choice: Option<ToolChoice>,
+    /// Left out when `top_p` is set; newer models reject both together.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(super) temperature: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(super) top_p: Option<f64>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub(super) stop_sequences: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(super) thinking: Option<ThinkingConfig>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub(super) stream: Option<bool>,
 }
 
+/// A system prompt block; the only kind the API accepts is text.
+#[derive(Debug, Serialize)]
+pub(super) struct SystemBlock {
+    #[serde(rename = "type")]
+    pub(super) kind: &'static str,
+    pub(super) text: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(super) cache_control: Option<CacheControl>,
+}
+
+/// Marks the end of a prompt prefix the API should cache.
+#[derive(Debug, Clone, Copy, Serialize)]
+pub(super) struct CacheControl {
+    #[serde(rename = "type")]
+    pub(super) kind: &'static str,
+}
+
+impl CacheControl {
+    pub(super) const fn ephemeral() -> Self {
+        Self { kind: "ephemeral" }
+    }
+}
+
+/// Forces the model to call the named tool.
+#[derive(Debug, Serialize)]
+pub(super) struct ToolChoice {
+    #[serde(rename = "type")]
+    pub(super) kind: &'static str,
+    pub(super) name: String,
+}
+
+#[derive(Debug, Serialize)]
+pub(super) struct ThinkingConfig {
+    #[serde(rename = "type")]
+    pub(super) kind: &'static str,
+    pub(super) budget_tokens: u32,
+}
+
 #[derive(Debug, Serialize)]
 pub(super) struct Message {
     pub(super) role: &'static str,
@@ -33,6 +81,10 @@ pub(super) enum InputContentBlock {
     Text {
         text: String,
     },
+    Thinking {
+        thinking: String,
+        signature: String,
+    },
     ToolUse {
         id: String,
         name: String,
@@ -61,6 +113,8 @@ pub(super) struct AnthropicToolDef {
     pub(super) name: String,
     pub(super) description: String,
     pub(super) input_schema: serde_json::Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(super) cache_control: Option<CacheControl>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -75,6 +129,10 @@ pub(super) struct ChatResponse {
 pub(super) struct Usage {
     pub(super) input_tokens: u64,
     pub(super) output_tokens: u64,
+    #[serde(default)]
+    pub(super) cache_creation_input_tokens: u64,
+    #[serde(default)]
+    pub(super) cache_read_input_tokens: u64,
 }
 
 #[derive(Debug, Deserialize)]
@@ -83,6 +141,11 @@ pub(super) enum ResponseContentBlock {
     Text {
         text: String,
     },
+    Thinking {
+        thinking: String,
+        #[serde(default)]
+        signature: String,
+    },
     ToolUse {
         id: String,
         name: String,
@@ -115,6 +178,10 @@ pub(super) enum StreamContentBlockType {
     Text {
         text: String,
     },
+    Thinking {
+        #[serde(default)]
+        thinking: String,
+    },
     ToolUse {
         id: String,
         name: String,
@@ -138,6 +205,12 @@ pub(super) enum StreamDelta {
     InputJsonDelta {
         partial_json: String,
     },
+    ThinkingDelta {
+        thinking: String,
+    },
+    SignatureDelta {
+        signature: String,
+    },
     #[serde(other)]
     Unknown,
 }
@@ -6,8 +6,9 @@ mod types;
 use crate::llm::{
     build_provider_client,
     fallback_tools::{augment_system_prompt_with_tools, build_fallback_response},
+    options::{ChatOptions, OptionSupport},
     sanitize_api_error,
-    traits::{Provider, messages_to_text},
+    traits::{Provider, ProviderCapabilities, messages_to_text},
     types::{ProviderMessage, ProviderResponse},
 };
 use crate::tools::ToolSpec;
@@ -190,7 +191,7 @@ impl OpenAiCompatibleProvider {
         system_prompt: Option<&str>,
         message: &str,
         model: &str,
-        temperature: f64,
+        options: &ChatOptions,
     ) -> anyhow::Result<ProviderResponse> {
         if self.api_key.is_none() {
             anyhow::bail!(
@@ -214,11 +215,7 @@ impl OpenAiCompatibleProvider {
             content: message.to_string(),
         });
 
-        let request = ChatRequest {
-            model: model.to_string(),
-            messages,
-            temperature,
-        };
+        let request = ChatRequest::new(model, messages, options);
 
         if self.prefer_responses_api {
             return self.chat_via_responses(system_prompt, message, model).await;
@@ -268,6 +265,18 @@ impl Provider for OpenAiCompatibleProvider {
         &self.name
     }
 
+    /// Options go out on the chat completions request; the Responses API
+    /// fallback sends only the prompt.
+    fn capabilities(&self) -> ProviderCapabilities {
+        ProviderCapabilities {
+            options: OptionSupport {
+                thinking: false,
+                ..OptionSupport::ALL
+            },
+            ..ProviderCapabilities::default()
+        }
+    }
+
     fn chat_with_system<'a>(
         &'a self,
         system_prompt: Option<&'a str>,
@@ -276,9 +285,14 @@ impl Provider for OpenAiCompatibleProvider {
         temperature: f64,
     ) -> Pin<Box<dyn Future<Output = anyhow::Result<String>> + Send + 'a>> {
         Box::pin(async move {
-            self.chat_with_system_internal(system_prompt, message, model, temperature)
-                .await
-                .map(|response| response.text)
+            self.chat_with_system_internal(
+                system_prompt,
+                message,
+                model,
+                &ChatOptions::new(temperature),
+            )
+            .await
+            .map(|response| response.text)
         })
     }
 
@@ -290,8 +304,13 @@ impl Provider for OpenAiCompatibleProvider {
         temperature: f64,
     ) -> Pin<Box<dyn Future<Output = anyhow::Result<ProviderResponse>> + Send + 'a>> {
         Box::pin(async move {
-            self.chat_with_system_internal(system_prompt, message, model, temperature)
-                .await
+            self.chat_with_system_internal(
+                system_prompt,
+                message,
+                model,
+                &ChatOptions::new(temperature),
+            )
+            .await
         })
     }
 
@@ -301,13 +320,13 @@ impl Provider for OpenAiCompatibleProvider {
         messages: &'a [ProviderMessage],
         tools: &'a [ToolSpec],
         model: &'a str,
-        temperature: f64,
+        options: &'a ChatOptions,
     ) -> Pin<Box<dyn Future<Output = anyhow::Result<ProviderResponse>> + Send + 'a>> {
         Box::pin(async move {
             let (augmented_prompt, text) =
                 Self::prepare_fallback_input(system_prompt, messages, tools);
             let response = self
-                .chat_with_system_full(Some(&augmented_prompt), &text, model, temperature)
+                .chat_with_system_internal(Some(&augmented_prompt), &text, model, options)
                 .await?;
             Ok(build_fallback_response(response, tools))
         })
@@ -363,9 +382,9 @@ mod tests {
 
     #[test]
     fn request_serializes_correctly() {
-        let req = ChatRequest {
-            model: "llama-3.3-70b".to_string(),
-            messages: vec![
+        let req = ChatRequest::new(
+            "llama-3.3-70b",
+            vec![
                 Message {
                     role: "system",
                     content: "You are AsteronIris".to_string(),
@@ -375,12 +394,31 @@ mod tests {
                     content: "hello".to_string(),
                 },
             ],
-            temperature: 0.7,
-        };
+            &ChatOptions::new(0.7),
+        );
         let json = serde_json::to_string(&req).unwrap();
         assert!(json.contains("llama-3.3-70b"));
         assert!(json.contains("system"));
         assert!(json.contains("user"));
+        assert!(!json.contains("max_tokens"));
+        assert!(!json.contains("response_format"));
+    }
+
+    #[test]
+    fn request_carries_chat_options() {
+        let options = ChatOptions::new(0.2)
+            .with_max_tokens(64)
+            .with_stop_sequences(vec!["\n\n".to_string()])
+            .with_seed(3)
+            .with_response_format(crate::llm::ResponseFormat::Json);
+        let req = ChatRequest::new("llama-3.3-70b", Vec::new(), &options);
+        let json = serde_json::to_value(&req).unwrap();
+
+        assert_eq!(json["max_tokens"], 64);
+        assert_eq!(json["stop"][0], "\n\n");
+        assert_eq!(json["seed"], 3);
+        assert_eq!(json["response_format"]["type"], "json_object");
+        assert!(json.get("top_p").is_none());
     }
 
     #[test]
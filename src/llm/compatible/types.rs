@@ -1,3 +1,4 @@
+use crate::llm::options::{ChatOptions, ResponseFormat};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
@@ -6,6 +7,34 @@ pub(super) struct ChatRequest {
     pub(super) model: String,
     pub(super) messages: Vec<Message>,
     pub(super) temperature: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(super) max_tokens: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(super) stop: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(super) top_p: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(super) seed: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(super) response_format: Option<Value>,
+}
+
+impl ChatRequest {
+    pub(super) fn new(model: &str, messages: Vec<Message>, options: &ChatOptions) -> Self {
+        Self {
+            model: model.to_string(),
+            messages,
+            temperature: options.temperature,
+            max_tokens: options.max_tokens,
+            stop: (!options.stop_sequences.is_empty()).then(|| options.stop_sequences.clone()),
+            top_p: options.top_p,
+            seed: options.seed,
+            response_format: options
+                .response_format
+                .as_ref()
+                .map(ResponseFormat::to_openai),
+        }
+    }
 }
 
 #[derive(Debug, Serialize)]
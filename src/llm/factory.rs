@@ -285,6 +285,21 @@ pub fn create_provider_with_oauth_recovery(
     create_provider_with_runtime_recovery(config, name, api_key)
 }
 
+/// Provider names a resilient provider tries, in order: the primary followed
+/// by the configured fallbacks, without duplicates.
+pub fn provider_chain(
+    primary_name: &str,
+    reliability: &crate::config::ReliabilityConfig,
+) -> Vec<String> {
+    let mut chain = vec![primary_name.to_string()];
+    for fallback in &reliability.fallback_providers {
+        if !chain.contains(fallback) {
+            chain.push(fallback.clone());
+        }
+    }
+    chain
+}
+
 pub fn create_resilient_provider_with_resolver<F>(
     primary_name: &str,
     reliability: &crate::config::ReliabilityConfig,
@@ -383,6 +398,22 @@ pub fn create_resilient_provider(
 mod tests {
     use super::*;
 
+    #[test]
+    fn provider_chain_lists_primary_then_unique_fallbacks() {
+        let reliability = crate::config::ReliabilityConfig {
+            fallback_providers: vec![
+                "openai".to_string(),
+                "anthropic".to_string(),
+                "openai".to_string(),
+            ],
+            ..crate::config::ReliabilityConfig::default()
+        };
+        assert_eq!(
+            provider_chain("anthropic", &reliability),
+            vec!["anthropic", "openai"]
+        );
+    }
+
     #[test]
     fn resolve_api_key_explicit_takes_precedence() {
         let key = resolve_api_key("anthropic", Some("sk-explicit"));
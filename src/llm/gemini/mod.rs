@@ -4,7 +4,9 @@
 //! - Google Cloud ADC (`GOOGLE_APPLICATION_CREDENTIALS`)
 
 use crate::llm::{
-    build_provider_client, sanitize_api_error, scrub_secret_patterns,
+    build_provider_client,
+    options::{ChatOptions, OptionSupport},
+    sanitize_api_error, scrub_secret_patterns,
     sse::{SseBuffer, parse_data_lines},
     streaming::ProviderStream,
     tool_convert::{ToolFields, map_tools_optional},
@@ -146,10 +148,7 @@ impl GeminiProvider {
             }],
             system_instruction,
             tools: None,
-            generation_config: GenerationConfig {
-                temperature,
-                max_output_tokens: 8192,
-            },
+            generation_config: GenerationConfig::new(temperature),
         }
     }
 
@@ -254,8 +253,10 @@ impl GeminiProvider {
         let parts = provider_message
             .content
             .iter()
-            .map(|block| match block {
-                ContentBlock::Text { text } => Part::text(scrub_secret_patterns(text).into_owned()),
+            .filter_map(|block| match block {
+                ContentBlock::Text { text } => {
+                    Some(Part::text(scrub_secret_patterns(text).into_owned()))
+                }
                 ContentBlock::ToolUse { id, name, input } => {
                     let args = if input.is_object() {
                         input.clone()
@@ -264,11 +265,11 @@ impl GeminiProvider {
                         wrapped.insert("input".to_string(), input.clone());
                         Value::Object(wrapped)
                     };
-                    Part::function_call(GeminiFunctionCall {
+                    Some(Part::function_call(GeminiFunctionCall {
                         name: name.clone(),
                         args,
                         id: Some(id.clone()),
-                    })
+                    }))
                 }
                 ContentBlock::ToolResult {
                     tool_use_id,
@@ -279,16 +280,16 @@ impl GeminiProvider {
                         .get(tool_use_id)
                         .cloned()
                         .unwrap_or_else(|| "tool".to_string());
-                    Part::function_response(GeminiFunctionResponse {
+                    Some(Part::function_response(GeminiFunctionResponse {
                         name: tool_name,
                         response: serde_json::json!({
                             "tool_use_id": tool_use_id,
                             "content": scrub_secret_patterns(content).into_owned(),
                             "is_error": is_error,
                         }),
-                    })
+                    }))
                 }
-                ContentBlock::Image { source } => match source {
+                ContentBlock::Image { source } => Some(match source {
                     ImageSource::Base64 { media_type, data } => {
                         Part::inline_data(GeminiInlineData {
                             mime_type: media_type.clone(),
@@ -299,7 +300,9 @@ impl GeminiProvider {
                         mime_type: String::new(),
                         file_uri: url.clone(),
                     }),
-                },
+                }),
+                // Thinking is specific to the provider that produced it.
+                ContentBlock::Thinking { .. } => None,
             })
             .collect();
 
@@ -313,7 +316,7 @@ impl GeminiProvider {
         system_prompt: Option<&str>,
         messages: &[ProviderMessage],
         tools: &[ToolSpec],
-        temperature: f64,
+        options: &ChatOptions,
     ) -> GenerateContentRequest {
         let tool_id_to_name = messages
             .iter()
@@ -322,7 +325,8 @@ impl GeminiProvider {
                 ContentBlock::ToolUse { id, name, .. } => Some((id.clone(), name.clone())),
                 ContentBlock::Text { .. }
                 | ContentBlock::ToolResult { .. }
-                | ContentBlock::Image { .. } => None,
+                | ContentBlock::Image { .. }
+                | ContentBlock::Thinking { .. } => None,
             })
             .collect::<HashMap<_, _>>();
 
@@ -336,10 +340,7 @@ impl GeminiProvider {
                 parts: vec![Part::text(scrub_secret_patterns(system).into_owned())],
             }),
             tools: Self::build_gemini_tools(tools),
-            generation_config: GenerationConfig {
-                temperature,
-                max_output_tokens: 8192,
-            },
+            generation_config: GenerationConfig::from_options(options),
         }
     }
 
@@ -441,12 +442,12 @@ impl GeminiProvider {
         messages: &[ProviderMessage],
         tools: &[ToolSpec],
         model: &str,
-        temperature: f64,
+        options: &ChatOptions,
     ) -> anyhow::Result<ProviderStream> {
         use crate::llm::streaming::StreamEvent;
         use futures_util::StreamExt;
 
-        let request = Self::build_tools_request(system_prompt, messages, tools, temperature);
+        let request = Self::build_tools_request(system_prompt, messages, tools, options);
 
         let response = self.call_api_streaming(model, &request).await?;
         let mut byte_stream = response.bytes_stream();
@@ -611,6 +612,10 @@ impl Provider for GeminiProvider {
             tool_calling: true,
             streaming: true,
             vision: true,
+            options: OptionSupport {
+                thinking: false,
+                ..OptionSupport::ALL
+            },
         }
     }
 
@@ -663,10 +668,10 @@ impl Provider for GeminiProvider {
         messages: &'a [ProviderMessage],
         tools: &'a [ToolSpec],
         model: &'a str,
-        temperature: f64,
+        options: &'a ChatOptions,
     ) -> Pin<Box<dyn Future<Output = anyhow::Result<ProviderResponse>> + Send + 'a>> {
         Box::pin(async move {
-            let request = Self::build_tools_request(system_prompt, messages, tools, temperature);
+            let request = Self::build_tools_request(system_prompt, messages, tools, options);
             let result = self.call_api_with_request(model, &request).await?;
 
             let candidate = result
@@ -716,10 +721,10 @@ impl Provider for GeminiProvider {
         messages: &'a [ProviderMessage],
         tools: &'a [ToolSpec],
         model: &'a str,
-        temperature: f64,
+        options: &'a ChatOptions,
     ) -> Pin<Box<dyn Future<Output = anyhow::Result<ProviderStream>> + Send + 'a>> {
         Box::pin(async move {
-            self.chat_with_tools_stream_impl(system_prompt, messages, tools, model, temperature)
+            self.chat_with_tools_stream_impl(system_prompt, messages, tools, model, options)
                 .await
         })
     }
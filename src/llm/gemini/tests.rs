@@ -1,5 +1,6 @@
 use super::types::CandidateContent;
 use super::*;
+use crate::llm::options::ResponseFormat;
 use crate::llm::traits::Provider;
 use crate::tools::ToolSpec;
 
@@ -69,10 +70,7 @@ fn request_serialization() {
             parts: vec![Part::text("You are helpful".to_string())],
         }),
         tools: None,
-        generation_config: GenerationConfig {
-            temperature: 0.7,
-            max_output_tokens: 8192,
-        },
+        generation_config: GenerationConfig::new(0.7),
     };
 
     let json = serde_json::to_string(&request).unwrap();
@@ -80,6 +78,30 @@ fn request_serialization() {
     assert!(json.contains("\"text\":\"Hello\""));
     assert!(json.contains("\"temperature\":0.7"));
     assert!(json.contains("\"maxOutputTokens\":8192"));
+    assert!(!json.contains("stopSequences"));
+    assert!(!json.contains("responseMimeType"));
+}
+
+#[test]
+fn chat_options_map_to_generation_config() {
+    let schema = serde_json::json!({"type": "object", "properties": {"city": {"type": "string"}}});
+    let options = ChatOptions::new(0.2)
+        .with_max_tokens(512)
+        .with_stop_sequences(vec!["END".to_string()])
+        .with_top_p(0.9)
+        .with_seed(42)
+        .with_response_format(ResponseFormat::json_schema("place", schema.clone()));
+
+    let request =
+        GeminiProvider::build_tools_request(None, &[ProviderMessage::user("hi")], &[], &options);
+    let config = serde_json::to_value(&request).unwrap()["generationConfig"].clone();
+
+    assert_eq!(config["maxOutputTokens"], 512);
+    assert_eq!(config["stopSequences"], serde_json::json!(["END"]));
+    assert_eq!(config["topP"], 0.9);
+    assert_eq!(config["seed"], 42);
+    assert_eq!(config["responseMimeType"], "application/json");
+    assert_eq!(config["responseJsonSchema"], schema);
 }
 
 #[test]
@@ -125,7 +147,7 @@ fn gemini_tools_serialize_as_function_declarations() {
         None,
         &[ProviderMessage::user("list files")],
         &tools,
-        0.1,
+        &ChatOptions::new(0.1),
     );
     let value = serde_json::to_value(&request).unwrap();
 
@@ -1,3 +1,4 @@
+use crate::llm::options::{ChatOptions, ResponseFormat};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
@@ -81,6 +82,45 @@ pub(super) struct GenerationConfig {
     pub(super) temperature: f64,
     #[serde(rename = "maxOutputTokens")]
     pub(super) max_output_tokens: u32,
+    #[serde(rename = "stopSequences", skip_serializing_if = "Vec::is_empty")]
+    pub(super) stop_sequences: Vec<String>,
+    #[serde(rename = "topP", skip_serializing_if = "Option::is_none")]
+    pub(super) top_p: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(super) seed: Option<u64>,
+    #[serde(rename = "responseMimeType", skip_serializing_if = "Option::is_none")]
+    pub(super) response_mime_type: Option<&'static str>,
+    #[serde(rename = "responseJsonSchema", skip_serializing_if = "Option::is_none")]
+    pub(super) response_json_schema: Option<Value>,
+}
+
+impl GenerationConfig {
+    const DEFAULT_MAX_OUTPUT_TOKENS: u32 = 8192;
+
+    pub(super) fn new(temperature: f64) -> Self {
+        Self::from_options(&ChatOptions::new(temperature))
+    }
+
+    pub(super) fn from_options(options: &ChatOptions) -> Self {
+        let (response_mime_type, response_json_schema) = match &options.response_format {
+            None => (None, None),
+            Some(ResponseFormat::Json) => (Some("application/json"), None),
+            Some(ResponseFormat::JsonSchema { schema, .. }) => {
+                (Some("application/json"), Some(schema.clone()))
+            }
+        };
+        Self {
+            temperature: options.temperature,
+            max_output_tokens: options
+                .max_tokens
+                .unwrap_or(Self::DEFAULT_MAX_OUTPUT_TOKENS),
+            stop_sequences: options.stop_sequences.clone(),
+            top_p: options.top_p,
+            seed: options.seed,
+            response_mime_type,
+            response_json_schema,
+        }
+    }
 }
 
 #[derive(Debug, Deserialize)]
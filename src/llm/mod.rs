@@ -4,9 +4,11 @@ pub mod cooldown;
 pub mod fallback_tools;
 pub mod http_client;
 pub mod leak_detect;
+pub mod options;
 pub mod scrub;
 pub mod sse;
 pub mod streaming;
+pub mod thinking;
 pub mod tool_convert;
 pub mod traits;
 pub mod types;
@@ -16,6 +18,7 @@ pub mod factory;
 pub mod manager;
 pub mod oauth_recovery;
 pub mod reliable;
+pub mod router;
 
 // ── Provider implementations ────────────────────────────────────────────────
 pub mod anthropic;
@@ -34,12 +37,14 @@ pub use fallback_tools::{
 };
 pub use http_client::{build_provider_client, build_provider_client_with_timeout};
 pub use leak_detect::{DetectedLeak, LeakEncoding, scan_for_leaks};
+pub use options::{ChatOptions, OptionSupport, ResponseFormat};
 pub use scrub::{api_error, sanitize_api_error, scrub_secret_patterns};
 pub use sse::{SseBuffer, parse_data_lines, parse_data_lines_without_done, parse_event_data_pairs};
 pub use streaming::{
     ChannelStreamSink, CliStreamSink, NullStreamSink, ProviderStream, StreamCollector, StreamEvent,
     StreamSink,
 };
+pub use thinking::ThinkingLevel;
 pub use tool_convert::{ToolFields, map_tools_optional};
 pub use traits::{Provider, ProviderCapabilities, messages_to_text};
 pub use types::{
@@ -1,3 +1,4 @@
+use super::options::ChatOptions;
 use super::scrub::sanitize_api_error;
 use super::streaming::ProviderStream;
 use super::traits::{Provider, ProviderCapabilities};
@@ -6,9 +7,9 @@ use crate::tools::ToolSpec;
 use anyhow::Result;
 use std::future::Future;
 use std::pin::Pin;
-use std::sync::Arc;
+use std::sync::{Arc, PoisonError, RwLock};
 use std::time::{Duration, Instant};
-use tokio::sync::{Mutex, RwLock};
+use tokio::sync::Mutex;
 
 type RecoverFn = dyn Fn(&str) -> Result<bool> + Send + Sync;
 type RebuildFn = dyn Fn(&str) -> Result<Arc<dyn Provider>> + Send + Sync;
@@ -65,6 +66,14 @@ impl OAuthRecoveryProvider {
         }
     }
 
+    /// The provider currently in use; swapped after a successful recovery.
+    fn current(&self) -> Arc<dyn Provider> {
+        self.inner
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
     fn is_auth_error(err: &anyhow::Error) -> bool {
         if let Some(reqwest_err) = err.downcast_ref::<reqwest::Error>()
             && let Some(status) = reqwest_err.status()
@@ -106,7 +115,7 @@ impl OAuthRecoveryProvider {
         let rebuild_fn = Arc::clone(&self.rebuild);
         let rebuilt_provider =
             tokio::task::spawn_blocking(move || (rebuild_fn)(&provider_name)).await??;
-        *self.inner.write().await = rebuilt_provider;
+        *self.inner.write().unwrap_or_else(PoisonError::into_inner) = rebuilt_provider;
 
         let mut state = self.state.lock().await;
         state.last_failed_at = None;
@@ -129,14 +138,19 @@ impl Provider for OAuthRecoveryProvider {
     }
 
     fn capabilities(&self) -> ProviderCapabilities {
-        // Read lock would need async; return defaults since the inner provider
-        // capabilities are only used through the inner provider calls.
-        ProviderCapabilities::default()
+        self.current().capabilities()
+    }
+
+    fn list_models(&self) -> Pin<Box<dyn Future<Output = Result<Vec<String>>> + Send + '_>> {
+        Box::pin(async move {
+            let provider = self.current();
+            provider.list_models().await
+        })
     }
 
     fn warmup(&self) -> Pin<Box<dyn Future<Output = Result<()>> + Send + '_>> {
         Box::pin(async move {
-            let provider = self.inner.read().await.clone();
+            let provider = self.current();
             provider.warmup().await
         })
     }
@@ -149,7 +163,7 @@ impl Provider for OAuthRecoveryProvider {
         temperature: f64,
     ) -> Pin<Box<dyn Future<Output = Result<String>> + Send + 'a>> {
         Box::pin(async move {
-            let provider = self.inner.read().await.clone();
+            let provider = self.current();
             let first_attempt = provider
                 .chat_with_system(system_prompt, message, model, temperature)
                 .await;
@@ -164,7 +178,7 @@ impl Provider for OAuthRecoveryProvider {
 
             match self.attempt_recovery().await {
                 Ok(true) => {
-                    let provider = self.inner.read().await.clone();
+                    let provider = self.current();
                     provider
                         .chat_with_system(system_prompt, message, model, temperature)
                         .await
@@ -186,7 +200,7 @@ impl Provider for OAuthRecoveryProvider {
         temperature: f64,
     ) -> Pin<Box<dyn Future<Output = Result<ProviderResponse>> + Send + 'a>> {
         Box::pin(async move {
-            let provider = self.inner.read().await.clone();
+            let provider = self.current();
             let first_attempt = provider
                 .chat_with_system_full(system_prompt, message, model, temperature)
                 .await;
@@ -201,7 +215,7 @@ impl Provider for OAuthRecoveryProvider {
 
             match self.attempt_recovery().await {
                 Ok(true) => {
-                    let provider = self.inner.read().await.clone();
+                    let provider = self.current();
                     provider
                         .chat_with_system_full(system_prompt, message, model, temperature)
                         .await
@@ -221,12 +235,12 @@ impl Provider for OAuthRecoveryProvider {
         messages: &'a [ProviderMessage],
         tools: &'a [ToolSpec],
         model: &'a str,
-        temperature: f64,
+        options: &'a ChatOptions,
     ) -> Pin<Box<dyn Future<Output = Result<ProviderResponse>> + Send + 'a>> {
         Box::pin(async move {
-            let provider = self.inner.read().await.clone();
+            let provider = self.current();
             let first_attempt = provider
-                .chat_with_tools(system_prompt, messages, tools, model, temperature)
+                .chat_with_tools(system_prompt, messages, tools, model, options)
                 .await;
 
             let Err(first_error) = first_attempt else {
@@ -239,9 +253,9 @@ impl Provider for OAuthRecoveryProvider {
 
             match self.attempt_recovery().await {
                 Ok(true) => {
-                    let provider = self.inner.read().await.clone();
+                    let provider = self.current();
                     provider
-                        .chat_with_tools(system_prompt, messages, tools, model, temperature)
+                        .chat_with_tools(system_prompt, messages, tools, model, options)
                         .await
                 }
                 Ok(false) => Err(first_error),
@@ -259,12 +273,12 @@ impl Provider for OAuthRecoveryProvider {
         messages: &'a [ProviderMessage],
         tools: &'a [ToolSpec],
         model: &'a str,
-        temperature: f64,
+        options: &'a ChatOptions,
     ) -> Pin<Box<dyn Future<Output = Result<ProviderStream>> + Send + 'a>> {
         Box::pin(async move {
-            let provider = self.inner.read().await.clone();
+            let provider = self.current();
             let first_attempt = provider
-                .chat_with_tools_stream(system_prompt, messages, tools, model, temperature)
+                .chat_with_tools_stream(system_prompt, messages, tools, model, options)
                 .await;
 
             let Err(first_error) = first_attempt else {
@@ -277,9 +291,9 @@ impl Provider for OAuthRecoveryProvider {
 
             match self.attempt_recovery().await {
                 Ok(true) => {
-                    let provider = self.inner.read().await.clone();
+                    let provider = self.current();
                     provider
-                        .chat_with_tools_stream(system_prompt, messages, tools, model, temperature)
+                        .chat_with_tools_stream(system_prompt, messages, tools, model, options)
                         .await
                 }
                 Ok(false) => Err(first_error),
@@ -332,6 +346,27 @@ mod tests {
         ) -> Pin<Box<dyn Future<Output = Result<String>> + Send + 'a>> {
             Box::pin(async move { Ok("ok".to_string()) })
         }
+
+        fn capabilities(&self) -> ProviderCapabilities {
+            ProviderCapabilities {
+                streaming: true,
+                options: crate::llm::OptionSupport::ALL,
+                ..ProviderCapabilities::default()
+            }
+        }
+    }
+
+    #[test]
+    fn capabilities_come_from_the_inner_provider() {
+        let provider = OAuthRecoveryProvider::new(
+            "ok",
+            Arc::new(OkProvider),
+            Arc::new(|_: &str| Ok(false)),
+            Arc::new(|_: &str| Ok(Arc::new(OkProvider) as Arc<dyn Provider>)),
+        );
+        let capabilities = provider.capabilities();
+        assert!(capabilities.streaming);
+        assert_eq!(capabilities.options, crate::llm::OptionSupport::ALL);
     }
 
     #[tokio::test]
@@ -0,0 +1,620 @@
+use crate::llm::{
+    build_provider_client_with_timeout,
+    fallback_tools::{augment_system_prompt_with_tools, build_fallback_response},
+    options::{ChatOptions, OptionSupport},
+    scrub_secret_patterns,
+    streaming::{ProviderStream, StreamEvent},
+    tool_convert::{ToolFields, map_tools_optional},
+    traits::{Provider, ProviderCapabilities, messages_to_text},
+    types::{
+        ContentBlock, ImageSource, MessageRole, ProviderMessage, ProviderResponse, StopReason,
+    },
+};
+use crate::tools::ToolSpec;
+use reqwest::Client;
+use serde_json::{Map, Value};
+use std::collections::{HashMap, HashSet};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Mutex, PoisonError};
+
+mod types;
+use types::{
+    ChatRequest, ChatResponse, Message, OllamaFunctionDef, OllamaToolDef, Options, StreamError,
+    TagsResponse, ToolCall, ToolCallFunction, format_for,
+};
+
+/// Error Ollama returns when a model's template has no tool support.
+const TOOLS_UNSUPPORTED: &str = "does not support tools";
+
+pub struct OllamaProvider {
+    base_url: String,
+    client: Client,
+    /// Models that rejected native tool calls; they get the prompt-based
+    /// fallback for the rest of the process.
+    tools_unsupported: Mutex<HashSet<String>>,
+}
+
+/// Progress through a streamed `/api/chat` response.
+#[derive(Debug, Default)]
+struct StreamState {
+    started: bool,
+    saw_tool_call: bool,
+}
+
+impl OllamaProvider {
+    pub fn new(base_url: Option<&str>) -> Self {
+        Self {
+            base_url: base_url
+                .unwrap_or("http://localhost:11434")
+                .trim_end_matches('/')
+                .to_string(),
+            client: build_provider_client_with_timeout(300),
+            tools_unsupported: Mutex::new(HashSet::new()),
+        }
+    }
+
+    fn build_request(
+        system_prompt: Option<&str>,
+        message: &str,
+        model: &str,
+        options: &ChatOptions,
+    ) -> ChatRequest {
+        let mut messages = Vec::new();
+
+        if let Some(sys) = system_prompt {
+            messages.push(Message::text("system", sys));
+        }
+
+        messages.push(Message::text("user", message));
+
+        ChatRequest {
+            model: model.to_string(),
+            messages,
+            tools: None,
+            stream: false,
+            format: format_for(options.response_format.as_ref()),
+            options: Options::new(options),
+        }
+    }
+
+    fn build_tools_request(
+        system_prompt: Option<&str>,
+        messages: &[ProviderMessage],
+        tools: &[ToolSpec],
+        model: &str,
+        options: &ChatOptions,
+    ) -> ChatRequest {
+        let tool_id_to_name = messages
+            .iter()
+            .flat_map(|message| message.content.iter())
+            .filter_map(|block| match block {
+                ContentBlock::ToolUse { id, name, .. } => Some((id.clone(), name.clone())),
+                ContentBlock::Text { .. }
+                | ContentBlock::ToolResult { .. }
+                | ContentBlock::Image { .. }
+                | ContentBlock::Thinking { .. } => None,
+            })
+            .collect::<HashMap<_, _>>();
+
+        let mut ollama_messages = Vec::new();
+        if let Some(system) = system_prompt {
+            ollama_messages.push(Message::text(
+                "system",
+                scrub_secret_patterns(system).into_owned(),
+            ));
+        }
+        for message in messages {
+            Self::push_provider_message(&mut ollama_messages, message, &tool_id_to_name);
+        }
+
+        let tools = map_tools_optional(tools, |tool| {
+            let fields = ToolFields::from_tool_with_description(
+                tool,
+                scrub_secret_patterns(&tool.description).into_owned(),
+            );
+
+            OllamaToolDef {
+                kind: "function",
+                function: OllamaFunctionDef {
+                    name: fields.name,
+                    description: fields.description,
+                    parameters: fields.parameters,
+                },
+            }
+        });
+
+        ChatRequest {
+            model: model.to_string(),
+            messages: ollama_messages,
+            tools,
+            stream: false,
+            format: format_for(options.response_format.as_ref()),
+            options: Options::new(options),
+        }
+    }
+
+    /// Append `provider_message` in Ollama's shape. Each tool result becomes
+    /// its own `tool` message naming the tool it answers, ahead of any text
+    /// in the same turn.
+    fn push_provider_message(
+        out: &mut Vec<Message>,
+        provider_message: &ProviderMessage,
+        tool_id_to_name: &HashMap<String, String>,
+    ) {
+        let role = match provider_message.role {
+            MessageRole::User => "user",
+            MessageRole::Assistant => "assistant",
+            MessageRole::System => "system",
+        };
+        let mut message = Message::text(role, String::new());
+        let mut text_parts = Vec::new();
+
+        for block in &provider_message.content {
+            match block {
+                ContentBlock::Text { text } => {
+                    text_parts.push(scrub_secret_patterns(text).into_owned());
+                }
+                ContentBlock::ToolUse { name, input, .. } => {
+                    message.tool_calls.push(ToolCall {
+                        function: ToolCallFunction {
+                            name: name.clone(),
+                            arguments: input.clone(),
+                        },
+                    });
+                }
+                ContentBlock::ToolResult {
+                    tool_use_id,
+                    content,
+                    is_error,
+                } => {
+                    let content = scrub_secret_patterns(content);
+                    let mut result = Message::text(
+                        "tool",
+                        if *is_error {
+                            format!("Error: {content}")
+                        } else {
+                            content.into_owned()
+                        },
+                    );
+                    result.tool_name = tool_id_to_name.get(tool_use_id).cloned();
+                    out.push(result);
+                }
+                ContentBlock::Image { source } => match source {
+                    ImageSource::Base64 { data, .. } => message.images.push(data.clone()),
+                    // Ollama only accepts inline image data.
+                    ImageSource::Url { url } => text_parts.push(format!("[image: {url}]")),
+                },
+                // Thinking is specific to the provider that produced it.
+                ContentBlock::Thinking { .. } => {}
+            }
+        }
+
+        message.content = text_parts.join("\n");
+        if !message.content.is_empty()
+            || !message.images.is_empty()
+            || !message.tool_calls.is_empty()
+        {
+            out.push(message);
+        }
+    }
+
+    fn tool_call_id() -> String {
+        format!(
+            "ollama_call_{}",
+            &uuid::Uuid::new_v4().simple().to_string()[..12]
+        )
+    }
+
+    /// Tool input as an object, wrapping anything else the model produced.
+    fn tool_input(arguments: &Value) -> Value {
+        match arguments {
+            Value::Object(_) => arguments.clone(),
+            Value::Null => Value::Object(Map::new()),
+            other => serde_json::json!({ "input": other }),
+        }
+    }
+
+    fn map_stop_reason(done_reason: Option<&str>, has_tool_calls: bool) -> StopReason {
+        if has_tool_calls {
+            return StopReason::ToolUse;
+        }
+        match done_reason {
+            Some("length") => StopReason::MaxTokens,
+            _ => StopReason::EndTurn,
+        }
+    }
+
+    fn parse_response(chat_response: ChatResponse) -> ProviderResponse {
+        let ChatResponse {
+            message,
+            done_reason,
+            prompt_eval_count,
+            eval_count,
+            model,
+            ..
+        } = chat_response;
+
+        let mut content_blocks = Vec::new();
+        if !message.thinking.is_empty() {
+            content_blocks.push(ContentBlock::Thinking {
+                thinking: message.thinking,
+                signature: String::new(),
+            });
+        }
+        if !message.content.is_empty() {
+            content_blocks.push(ContentBlock::Text {
+                text: message.content.clone(),
+            });
+        }
+        let has_tool_calls = !message.tool_calls.is_empty();
+        for call in message.tool_calls {
+            content_blocks.push(ContentBlock::ToolUse {
+                id: Self::tool_call_id(),
+                input: Self::tool_input(&call.function.arguments),
+                name: call.function.name,
+            });
+        }
+
+        let mut provider_response = match (prompt_eval_count, eval_count) {
+            (Some(input_tokens), Some(output_tokens)) => {
+                ProviderResponse::with_usage(message.content, input_tokens, output_tokens)
+            }
+            _ => ProviderResponse::text_only(message.content),
+        };
+        provider_response.content_blocks = content_blocks;
+        provider_response.stop_reason = Some(Self::map_stop_reason(
+            done_reason.as_deref(),
+            has_tool_calls,
+        ));
+        if let Some(api_model) = model {
+            provider_response = provider_response.with_model(api_model);
+        }
+        provider_response
+    }
+
+    /// Stream events for one NDJSON line of a streamed response.
+    fn stream_events_from_line(
+        line: &str,
+        state: &mut StreamState,
+    ) -> anyhow::Result<Vec<StreamEvent>> {
+        let chunk = match serde_json::from_str::<ChatResponse>(line) {
+            Ok(chunk) => chunk,
+            Err(parse_error) => {
+                if let Ok(error) = serde_json::from_str::<StreamError>(line) {
+                    anyhow::bail!(
+                        "Ollama API error: {}",
+                        crate::llm::scrub::sanitize_api_error(&error.error)
+                    );
+                }
+                tracing::debug!(%parse_error, "skipping unparseable Ollama stream line");
+                return Ok(Vec::new());
+            }
+        };
+
+        let mut events = Vec::new();
+        if !state.started {
+            state.started = true;
+            events.push(StreamEvent::ResponseStart {
+                model: chunk.model.clone(),
+            });
+        }
+        if !chunk.message.thinking.is_empty() {
+            events.push(StreamEvent::ThinkingDelta {
+                thinking: chunk.message.thinking,
+            });
+        }
+        if !chunk.message.content.is_empty() {
+            events.push(StreamEvent::TextDelta {
+                text: chunk.message.content,
+            });
+        }
+        for call in chunk.message.tool_calls {
+            state.saw_tool_call = true;
+            events.push(StreamEvent::ToolCallComplete {
+                id: Self::tool_call_id(),
+                input: Self::tool_input(&call.function.arguments),
+                name: call.function.name,
+            });
+        }
+        if chunk.done {
+            events.push(StreamEvent::Done {
+                stop_reason: Some(Self::map_stop_reason(
+                    chunk.done_reason.as_deref(),
+                    state.saw_tool_call,
+                )),
+                input_tokens: chunk.prompt_eval_count,
+                output_tokens: chunk.eval_count,
+            });
+        }
+        Ok(events)
+    }
+
+    async fn send(&self, request: &ChatRequest) -> anyhow::Result<reqwest::Response> {
+        let url = format!("{}/api/chat", self.base_url);
+
+        let response = self.client.post(&url).json(request).send().await?;
+
+        if !response.status().is_success() {
+            let err = crate::llm::scrub::api_error("Ollama", response).await;
+            anyhow::bail!("{err}. Is Ollama running? (brew install ollama && ollama serve)");
+        }
+
+        Ok(response)
+    }
+
+    async fn call_api(
+        &self,
+        system_prompt: Option<&str>,
+        message: &str,
+        model: &str,
+        options: &ChatOptions,
+    ) -> anyhow::Result<ChatResponse> {
+        let request = Self::build_request(system_prompt, message, model, options);
+        let response = self.send(&request).await?;
+        response.json().await.map_err(anyhow::Error::msg)
+    }
+
+    async fn chat_text(
+        &self,
+        system_prompt: Option<&str>,
+        message: &str,
+        model: &str,
+        options: &ChatOptions,
+    ) -> anyhow::Result<ProviderResponse> {
+        let chat_response = self
+            .call_api(system_prompt, message, model, options)
+            .await?;
+        let text = chat_response.message.content;
+        let mut provider_response =
+            match (chat_response.prompt_eval_count, chat_response.eval_count) {
+                (Some(input_tokens), Some(output_tokens)) => {
+                    ProviderResponse::with_usage(text, input_tokens, output_tokens)
+                }
+                _ => ProviderResponse::text_only(text),
+            };
+        if let Some(api_model) = chat_response.model {
+            provider_response = provider_response.with_model(api_model);
+        }
+        Ok(provider_response)
+    }
+
+    /// Whether `model` should get native tool calls; models that rejected
+    /// them once keep using the prompt-based fallback.
+    fn uses_native_tools(&self, model: &str, tools: &[ToolSpec]) -> bool {
+        tools.is_empty()
+            || !self
+                .tools_unsupported
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .contains(model)
+    }
+
+    /// Record a tool rejection for `model`; `true` when `error` was one.
+    fn note_tools_unsupported(&self, model: &str, error: &anyhow::Error) -> bool {
+        if !error.to_string().contains(TOOLS_UNSUPPORTED) {
+            return false;
+        }
+        tracing::info!(
+            model,
+            "Ollama model has no native tool support; using prompt-based tools"
+        );
+        self.tools_unsupported
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(model.to_string());
+        true
+    }
+
+    async fn chat_with_fallback_tools(
+        &self,
+        system_prompt: Option<&str>,
+        messages: &[ProviderMessage],
+        tools: &[ToolSpec],
+        model: &str,
+        options: &ChatOptions,
+    ) -> anyhow::Result<ProviderResponse> {
+        let (augmented_prompt, text) = Self::prepare_fallback_input(system_prompt, messages, tools);
+        let response = self
+            .chat_text(Some(&augmented_prompt), &text, model, options)
+            .await?;
+        Ok(build_fallback_response(response, tools))
+    }
+
+    fn prepare_fallback_input(
+        system_prompt: Option<&str>,
+        messages: &[ProviderMessage],
+        tools: &[ToolSpec],
+    ) -> (String, String) {
+        let augmented_prompt = augment_system_prompt_with_tools(system_prompt.unwrap_or(""), tools);
+        let text = messages_to_text(messages);
+        (augmented_prompt, text)
+    }
+
+    async fn chat_with_tools_stream_impl(
+        &self,
+        system_prompt: Option<&str>,
+        messages: &[ProviderMessage],
+        tools: &[ToolSpec],
+        model: &str,
+        options: &ChatOptions,
+    ) -> anyhow::Result<ProviderStream> {
+        use futures_util::StreamExt;
+
+        if !self.uses_native_tools(model, tools) {
+            let response = self
+                .chat_with_fallback_tools(system_prompt, messages, tools, model, options)
+                .await?;
+            return Ok(Box::pin(futures_util::stream::iter(
+                crate::llm::streaming::resp_to_events(response),
+            )));
+        }
+
+        let mut request = Self::build_tools_request(system_prompt, messages, tools, model, options);
+        request.stream = true;
+        let response = match self.send(&request).await {
+            Ok(response) => response,
+            Err(error) if self.note_tools_unsupported(model, &error) => {
+                let response = self
+                    .chat_with_fallback_tools(system_prompt, messages, tools, model, options)
+                    .await?;
+                return Ok(Box::pin(futures_util::stream::iter(
+                    crate::llm::streaming::resp_to_events(response),
+                )));
+            }
+            Err(error) => return Err(error),
+        };
+        let mut byte_stream = response.bytes_stream();
+
+        let stream = async_stream::try_stream! {
+            let mut buffer = String::new();
+            let mut state = StreamState::default();
+
+            while let Some(chunk_result) = byte_stream.next().await {
+                let chunk = chunk_result?;
+                buffer.push_str(&String::from_utf8_lossy(&chunk));
+
+                while let Some(newline) = buffer.find('\n') {
+                    let line: String = buffer.drain(..=newline).collect();
+                    let line = line.trim();
+                    if line.is_empty() {
+                        continue;
+                    }
+                    for event in Self::stream_events_from_line(line, &mut state)? {
+                        yield event;
+                    }
+                }
+            }
+
+            let line = buffer.trim();
+            if !line.is_empty() {
+                for event in Self::stream_events_from_line(line, &mut state)? {
+                    yield event;
+                }
+            }
+        };
+
+        Ok(Box::pin(stream))
+    }
+}
+
+impl Provider for OllamaProvider {
+    fn name(&self) -> &str {
+        "ollama"
+    }
+
+    /// Names of the models pulled into this Ollama server (`/api/tags`).
+    fn list_models(
+        &self,
+    ) -> Pin<Box<dyn Future<Output = anyhow::Result<Vec<String>>> + Send + '_>> {
+        Box::pin(async move {
+            let url = format!("{}/api/tags", self.base_url);
+            let response = self.client.get(&url).send().await?;
+            if !response.status().is_success() {
+                return Err(crate::llm::scrub::api_error("Ollama", response).await);
+            }
+            let tags: TagsResponse = response.json().await?;
+            let mut names: Vec<String> = tags.models.into_iter().map(|model| model.name).collect();
+            names.sort();
+            Ok(names)
+        })
+    }
+
+    fn capabilities(&self) -> ProviderCapabilities {
+        ProviderCapabilities {
+            tool_calling: true,
+            streaming: true,
+            vision: true,
+            options: OptionSupport {
+                thinking: false,
+                ..OptionSupport::ALL
+            },
+        }
+    }
+
+    fn chat_with_system<'a>(
+        &'a self,
+        system_prompt: Option<&'a str>,
+        message: &'a str,
+        model: &'a str,
+        temperature: f64,
+    ) -> Pin<Box<dyn Future<Output = anyhow::Result<String>> + Send + 'a>> {
+        Box::pin(async move {
+            let chat_response = self
+                .call_api(
+                    system_prompt,
+                    message,
+                    model,
+                    &ChatOptions::new(temperature),
+                )
+                .await?;
+            Ok(chat_response.message.content)
+        })
+    }
+
+    fn chat_with_system_full<'a>(
+        &'a self,
+        system_prompt: Option<&'a str>,
+        message: &'a str,
+        model: &'a str,
+        temperature: f64,
+    ) -> Pin<Box<dyn Future<Output = anyhow::Result<ProviderResponse>> + Send + 'a>> {
+        Box::pin(async move {
+            self.chat_text(
+                system_prompt,
+                message,
+                model,
+                &ChatOptions::new(temperature),
+            )
+            .await
+        })
+    }
+
+    fn chat_with_tools<'a>(
+        &'a self,
+        system_prompt: Option<&'a str>,
+        messages: &'a [ProviderMessage],
+        tools: &'a [ToolSpec],
+        model: &'a str,
+        options: &'a ChatOptions,
+    ) -> Pin<Box<dyn Future<Output = anyhow::Result<ProviderResponse>> + Send + 'a>> {
+        Box::pin(async move {
+            if !self.uses_native_tools(model, tools) {
+                return self
+                    .chat_with_fallback_tools(system_prompt, messages, tools, model, options)
+                    .await;
+            }
+
+            let request = Self::build_tools_request(system_prompt, messages, tools, model, options);
+            match self.send(&request).await {
+                Ok(response) => {
+                    let chat_response: ChatResponse =
+                        response.json().await.map_err(anyhow::Error::msg)?;
+                    Ok(Self::parse_response(chat_response))
+                }
+                Err(error) if self.note_tools_unsupported(model, &error) => {
+                    self.chat_with_fallback_tools(system_prompt, messages, tools, model, options)
+                        .await
+                }
+                Err(error) => Err(error),
+            }
+        })
+    }
+
+    fn chat_with_tools_stream<'a>(
+        &'a self,
+        system_prompt: Option<&'a str>,
+        messages: &'a [ProviderMessage],
+        tools: &'a [ToolSpec],
+        model: &'a str,
+        options: &'a ChatOptions,
+    ) -> Pin<Box<dyn Future<Output = anyhow::Result<ProviderStream>> + Send + 'a>> {
+        Box::pin(async move {
+            self.chat_with_tools_stream_impl(system_prompt, messages, tools, model, options)
+                .await
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests;
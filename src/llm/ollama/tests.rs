@@ -0,0 +1,294 @@
+use super::*;
+use crate::llm::options::ResponseFormat;
+use crate::llm::traits::Provider;
+use crate::llm::types::{ContentBlock, MessageRole, ProviderMessage};
+
+#[test]
+fn default_url() {
+    let p = OllamaProvider::new(None);
+    assert_eq!(p.base_url, "http://localhost:11434");
+}
+
+#[test]
+fn custom_url_trailing_slash() {
+    let p = OllamaProvider::new(Some("http://192.168.1.100:11434/"));
+    assert_eq!(p.base_url, "http://192.168.1.100:11434");
+}
+
+#[test]
+fn custom_url_no_trailing_slash() {
+    let p = OllamaProvider::new(Some("http://myserver:11434"));
+    assert_eq!(p.base_url, "http://myserver:11434");
+}
+
+#[test]
+fn empty_url_uses_empty() {
+    let p = OllamaProvider::new(Some(""));
+    assert_eq!(p.base_url, "");
+}
+
+#[test]
+fn request_serializes_with_system() {
+    let req = ChatRequest {
+        model: "llama3".to_string(),
+        messages: vec![
+            Message::text("system", "You are AsteronIris"),
+            Message::text("user", "hello"),
+        ],
+        tools: None,
+        stream: false,
+        format: None,
+        options: Options::new(&ChatOptions::new(0.7)),
+    };
+    let json = serde_json::to_string(&req).unwrap();
+    assert!(json.contains("\"stream\":false"));
+    assert!(json.contains("llama3"));
+    assert!(json.contains("system"));
+    assert!(json.contains("\"temperature\":0.7"));
+    assert!(!json.contains("num_predict"));
+    assert!(!json.contains("format"));
+}
+
+#[test]
+fn request_serializes_without_system() {
+    let req = ChatRequest {
+        model: "mistral".to_string(),
+        messages: vec![Message::text("user", "test")],
+        tools: None,
+        stream: false,
+        format: None,
+        options: Options::new(&ChatOptions::new(0.0)),
+    };
+    let json = serde_json::to_string(&req).unwrap();
+    assert!(!json.contains("\"role\":\"system\""));
+    assert!(json.contains("mistral"));
+}
+
+#[test]
+fn response_deserializes() {
+    let json = r#"{"message":{"role":"assistant","content":"Hello from Ollama!"}}"#;
+    let resp: ChatResponse = serde_json::from_str(json).unwrap();
+    assert_eq!(resp.message.content, "Hello from Ollama!");
+}
+
+#[test]
+fn response_with_empty_content() {
+    let json = r#"{"message":{"role":"assistant","content":""}}"#;
+    let resp: ChatResponse = serde_json::from_str(json).unwrap();
+    assert!(resp.message.content.is_empty());
+}
+
+#[test]
+fn response_with_multiline() {
+    let json = r#"{"message":{"role":"assistant","content":"line1\nline2\nline3"}}"#;
+    let resp: ChatResponse = serde_json::from_str(json).unwrap();
+    assert!(resp.message.content.contains("line1"));
+}
+
+#[test]
+fn fallback_input_includes_tool_schema_in_augmented_prompt() {
+    let messages = vec![ProviderMessage {
+        role: MessageRole::User,
+        content: vec![ContentBlock::Text {
+            text: "list files".to_string(),
+        }],
+    }];
+    let tools = vec![ToolSpec {
+        name: "shell".to_string(),
+        description: "Execute shell command".to_string(),
+        parameters: serde_json::json!({
+            "type": "object",
+            "properties": {
+                "command": {"type": "string"}
+            }
+        }),
+    }];
+
+    let (prompt, text) =
+        OllamaProvider::prepare_fallback_input(Some("You are helpful"), &messages, &tools);
+
+    assert!(prompt.contains("## Available Tools"));
+    assert!(prompt.contains("shell: Execute shell command"));
+    assert!(text.contains("User: list files"));
+}
+
+#[test]
+fn supports_tool_calling_streaming_and_vision() {
+    let provider = OllamaProvider::new(None);
+    assert!(provider.supports_tool_calling());
+    assert!(provider.supports_streaming());
+    assert!(provider.supports_vision());
+}
+
+#[test]
+fn name_returns_ollama() {
+    let provider = OllamaProvider::new(None);
+    assert_eq!(provider.name(), "ollama");
+}
+
+fn shell_tool() -> ToolSpec {
+    ToolSpec {
+        name: "shell".to_string(),
+        description: "Execute shell command".to_string(),
+        parameters: serde_json::json!({"type": "object"}),
+    }
+}
+
+#[test]
+fn tools_request_maps_tool_calls_results_and_images() {
+    let messages = vec![
+        ProviderMessage::user_with_image(
+            "what is this?",
+            ImageSource::base64("image/png", "iVBOR"),
+        ),
+        ProviderMessage {
+            role: MessageRole::Assistant,
+            content: vec![ContentBlock::ToolUse {
+                id: "ollama_call_1".to_string(),
+                name: "shell".to_string(),
+                input: serde_json::json!({"command": "ls"}),
+            }],
+        },
+        ProviderMessage::tool_result("ollama_call_1", "src", false),
+    ];
+
+    let request = OllamaProvider::build_tools_request(
+        Some("You are helpful"),
+        &messages,
+        &[shell_tool()],
+        "llama3.2",
+        &ChatOptions::new(0.7),
+    );
+    let json = serde_json::to_value(&request).unwrap();
+
+    assert_eq!(json["tools"][0]["type"], "function");
+    assert_eq!(json["tools"][0]["function"]["name"], "shell");
+    assert_eq!(json["messages"][0]["role"], "system");
+    assert_eq!(json["messages"][1]["content"], "what is this?");
+    assert_eq!(json["messages"][1]["images"][0], "iVBOR");
+    assert_eq!(json["messages"][2]["role"], "assistant");
+    assert_eq!(
+        json["messages"][2]["tool_calls"][0]["function"]["arguments"]["command"],
+        "ls"
+    );
+    assert_eq!(json["messages"][3]["role"], "tool");
+    assert_eq!(json["messages"][3]["tool_name"], "shell");
+    assert_eq!(json["messages"][3]["content"], "src");
+}
+
+#[test]
+fn chat_options_map_to_ollama_options_and_format() {
+    let options = ChatOptions::new(0.2)
+        .with_max_tokens(256)
+        .with_stop_sequences(vec!["</answer>".to_string()])
+        .with_seed(7)
+        .with_response_format(ResponseFormat::Json);
+
+    let request = OllamaProvider::build_tools_request(
+        None,
+        &[ProviderMessage::user("hi")],
+        &[],
+        "llama3.2",
+        &options,
+    );
+    let json = serde_json::to_value(&request).unwrap();
+
+    assert_eq!(json["format"], "json");
+    assert_eq!(json["options"]["num_predict"], 256);
+    assert_eq!(json["options"]["stop"][0], "</answer>");
+    assert_eq!(json["options"]["seed"], 7);
+    assert!(json["options"].get("top_p").is_none());
+
+    let schema = serde_json::json!({"type": "object"});
+    let options =
+        options.with_response_format(ResponseFormat::json_schema("reply", schema.clone()));
+    let request = OllamaProvider::build_request(None, "hi", "llama3.2", &options);
+    assert_eq!(serde_json::to_value(&request).unwrap()["format"], schema);
+}
+
+#[test]
+fn tool_call_response_maps_to_tool_use_blocks() {
+    let json = r#"{
+        "model":"llama3.2",
+        "message":{"role":"assistant","content":"","tool_calls":[
+            {"function":{"name":"shell","arguments":{"command":"ls"}}}
+        ]},
+        "done":true,
+        "done_reason":"stop",
+        "prompt_eval_count":12,
+        "eval_count":4
+    }"#;
+    let response = OllamaProvider::parse_response(serde_json::from_str(json).unwrap());
+
+    assert_eq!(response.stop_reason, Some(StopReason::ToolUse));
+    assert_eq!(response.input_tokens, Some(12));
+    assert!(matches!(
+        response.content_blocks.as_slice(),
+        [ContentBlock::ToolUse { id, name, input }]
+        if id.starts_with("ollama_call_") && name == "shell" && input["command"] == "ls"
+    ));
+}
+
+#[test]
+fn stream_lines_map_to_events() {
+    let mut state = StreamState::default();
+    let lines = [
+        r#"{"model":"qwen3","message":{"role":"assistant","content":"","thinking":"hmm"},"done":false}"#,
+        r#"{"model":"qwen3","message":{"role":"assistant","content":"Hi"},"done":false}"#,
+        r#"{"model":"qwen3","message":{"role":"assistant","content":"","tool_calls":[{"function":{"name":"shell","arguments":{}}}]},"done":false}"#,
+        r#"{"model":"qwen3","message":{"role":"assistant","content":""},"done":true,"done_reason":"stop","prompt_eval_count":5,"eval_count":3}"#,
+    ];
+    let events: Vec<StreamEvent> = lines
+        .iter()
+        .flat_map(|line| OllamaProvider::stream_events_from_line(line, &mut state).unwrap())
+        .collect();
+
+    assert!(
+        matches!(&events[0], StreamEvent::ResponseStart { model } if model.as_deref() == Some("qwen3"))
+    );
+    assert!(matches!(&events[1], StreamEvent::ThinkingDelta { thinking } if thinking == "hmm"));
+    assert!(matches!(&events[2], StreamEvent::TextDelta { text } if text == "Hi"));
+    assert!(matches!(&events[3], StreamEvent::ToolCallComplete { name, .. } if name == "shell"));
+    assert!(matches!(
+        &events[4],
+        StreamEvent::Done {
+            stop_reason: Some(StopReason::ToolUse),
+            input_tokens: Some(5),
+            output_tokens: Some(3),
+        }
+    ));
+
+    let error =
+        OllamaProvider::stream_events_from_line(r#"{"error":"model not found"}"#, &mut state);
+    assert!(error.unwrap_err().to_string().contains("model not found"));
+}
+
+#[test]
+fn models_that_reject_tools_fall_back_to_prompting() {
+    let provider = OllamaProvider::new(None);
+    let tools = [shell_tool()];
+    assert!(provider.uses_native_tools("gemma:2b", &tools));
+
+    let other = anyhow::anyhow!("Ollama API error (400 Bad Request): connection refused");
+    assert!(!provider.note_tools_unsupported("gemma:2b", &other));
+    let rejected = anyhow::anyhow!(
+        "Ollama API error (400 Bad Request): registry.ollama.ai/library/gemma:2b does not support tools"
+    );
+    assert!(provider.note_tools_unsupported("gemma:2b", &rejected));
+
+    assert!(!provider.uses_native_tools("gemma:2b", &tools));
+    assert!(provider.uses_native_tools("gemma:2b", &[]));
+    assert!(provider.uses_native_tools("llama3.2", &tools));
+}
+
+#[test]
+fn tags_response_deserializes() {
+    let json = r#"{"models":[{"name":"llama3.2:latest","size":2019393189},{"name":"qwen3:8b"}]}"#;
+    let tags: TagsResponse = serde_json::from_str(json).unwrap();
+    let names: Vec<&str> = tags
+        .models
+        .iter()
+        .map(|model| model.name.as_str())
+        .collect();
+    assert_eq!(names, ["llama3.2:latest", "qwen3:8b"]);
+}
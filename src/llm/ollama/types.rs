@@ -0,0 +1,139 @@
+use crate::llm::options::{ChatOptions, ResponseFormat};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+#[derive(Debug, Serialize)]
+pub(super) struct ChatRequest {
+    pub(super) model: String,
+    pub(super) messages: Vec<Message>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(super) tools: Option<Vec<OllamaToolDef>>,
+    pub(super) stream: bool,
+    /// `"json"` or a JSON Schema the reply must match.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(super) format: Option<Value>,
+    pub(super) options: Options,
+}
+
+#[derive(Debug, Serialize)]
+pub(super) struct Message {
+    pub(super) role: &'static str,
+    pub(super) content: String,
+    /// Base64-encoded images, for vision models.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub(super) images: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub(super) tool_calls: Vec<ToolCall>,
+    /// Name of the tool a `tool` message answers.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(super) tool_name: Option<String>,
+}
+
+impl Message {
+    pub(super) fn text(role: &'static str, content: impl Into<String>) -> Self {
+        Self {
+            role,
+            content: content.into(),
+            images: Vec::new(),
+            tool_calls: Vec::new(),
+            tool_name: None,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub(super) struct Options {
+    pub(super) temperature: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(super) num_predict: Option<u32>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub(super) stop: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(super) top_p: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(super) seed: Option<u64>,
+}
+
+impl Options {
+    pub(super) fn new(options: &ChatOptions) -> Self {
+        Self {
+            temperature: options.temperature,
+            num_predict: options.max_tokens,
+            stop: options.stop_sequences.clone(),
+            top_p: options.top_p,
+            seed: options.seed,
+        }
+    }
+}
+
+pub(super) fn format_for(response_format: Option<&ResponseFormat>) -> Option<Value> {
+    match response_format? {
+        ResponseFormat::Json => Some(Value::String("json".to_string())),
+        ResponseFormat::JsonSchema { schema, .. } => Some(schema.clone()),
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub(super) struct OllamaToolDef {
+    #[serde(rename = "type")]
+    pub(super) kind: &'static str,
+    pub(super) function: OllamaFunctionDef,
+}
+
+#[derive(Debug, Serialize)]
+pub(super) struct OllamaFunctionDef {
+    pub(super) name: String,
+    pub(super) description: String,
+    pub(super) parameters: serde_json::Value,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(super) struct ToolCall {
+    pub(super) function: ToolCallFunction,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(super) struct ToolCallFunction {
+    pub(super) name: String,
+    #[serde(default)]
+    pub(super) arguments: serde_json::Value,
+}
+
+/// A full response, or one NDJSON line of a streamed one.
+#[derive(Debug, Deserialize)]
+pub(super) struct ChatResponse {
+    pub(super) message: ResponseMessage,
+    #[serde(default)]
+    pub(super) done: bool,
+    pub(super) done_reason: Option<String>,
+    pub(super) prompt_eval_count: Option<u64>,
+    pub(super) eval_count: Option<u64>,
+    pub(super) model: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub(super) struct ResponseMessage {
+    #[serde(default)]
+    pub(super) content: String,
+    /// Reasoning text from thinking models, kept apart from `content`.
+    #[serde(default)]
+    pub(super) thinking: String,
+    #[serde(default)]
+    pub(super) tool_calls: Vec<ToolCall>,
+}
+
+#[derive(Debug, Deserialize)]
+pub(super) struct StreamError {
+    pub(super) error: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub(super) struct TagsResponse {
+    #[serde(default)]
+    pub(super) models: Vec<TagModel>,
+}
+
+#[derive(Debug, Deserialize)]
+pub(super) struct TagModel {
+    pub(super) name: String,
+}
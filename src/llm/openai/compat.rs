@@ -7,6 +7,7 @@ use crate::llm::types::{
     ContentBlock, ImageSource, MessageRole, ProviderMessage, ProviderResponse, StopReason,
 };
 use crate::llm::{
+    options::{ChatOptions, ResponseFormat},
     scrub_secret_patterns,
     sse::{SseBuffer, parse_data_lines_without_done},
     streaming::{ProviderStream, StreamEvent},
@@ -46,6 +47,12 @@ pub(in crate::llm) fn build_request(
         model: model.to_string(),
         messages,
         temperature,
+        max_tokens: None,
+        max_completion_tokens: None,
+        stop: None,
+        top_p: None,
+        seed: None,
+        response_format: None,
         tools: None,
         stream: None,
         stream_options: None,
@@ -107,6 +114,8 @@ pub(in crate::llm) fn map_provider_message(provider_message: &ProviderMessage) -
                     image_url: ImageUrlContent { url },
                 });
             }
+            // Thinking is specific to the provider that produced it.
+            ContentBlock::Thinking { .. } => {}
         }
     }
 
@@ -198,12 +207,21 @@ pub(in crate::llm) fn build_tools_request(
     messages: &[ProviderMessage],
     tools: &[ToolSpec],
     model: &str,
-    temperature: f64,
+    options: &ChatOptions,
 ) -> ChatRequest {
     ChatRequest {
         model: model.to_string(),
         messages: build_messages(system_prompt, messages),
-        temperature,
+        temperature: options.temperature,
+        max_tokens: options.max_tokens,
+        max_completion_tokens: None,
+        stop: (!options.stop_sequences.is_empty()).then(|| options.stop_sequences.clone()),
+        top_p: options.top_p,
+        seed: options.seed,
+        response_format: options
+            .response_format
+            .as_ref()
+            .map(ResponseFormat::to_openai),
         tools: build_openai_tools(tools),
         stream: None,
         stream_options: None,
@@ -215,17 +233,14 @@ pub(in crate::llm) fn build_stream_request(
     messages: &[ProviderMessage],
     tools: &[ToolSpec],
     model: &str,
-    temperature: f64,
+    options: &ChatOptions,
 ) -> ChatRequest {
     ChatRequest {
-        model: model.to_string(),
-        messages: build_messages(system_prompt, messages),
-        temperature,
-        tools: build_openai_tools(tools),
         stream: Some(true),
         stream_options: Some(StreamOptions {
             include_usage: true,
         }),
+        ..build_tools_request(system_prompt, messages, tools, model, options)
     }
 }
 
@@ -6,6 +6,7 @@ use crate::llm::types::{ContentBlock, StopReason};
 use crate::llm::types::{ProviderMessage, ProviderResponse};
 use crate::llm::{
     build_provider_client,
+    options::{ChatOptions, OptionSupport},
     streaming::ProviderStream,
     traits::{Provider, ProviderCapabilities},
 };
@@ -57,9 +58,12 @@ impl OpenAiProvider {
         messages: &[ProviderMessage],
         tools: &[ToolSpec],
         model: &str,
-        temperature: f64,
+        options: &ChatOptions,
     ) -> ChatRequest {
-        openai_compat::build_tools_request(system_prompt, messages, tools, model, temperature)
+        let mut request =
+            openai_compat::build_tools_request(system_prompt, messages, tools, model, options);
+        request.max_completion_tokens = request.max_tokens.take();
+        request
     }
 
     fn extract_text(chat_response: &ChatResponse) -> anyhow::Result<String> {
@@ -126,10 +130,11 @@ impl OpenAiProvider {
         messages: &[ProviderMessage],
         tools: &[ToolSpec],
         model: &str,
-        temperature: f64,
+        options: &ChatOptions,
     ) -> anyhow::Result<ProviderStream> {
-        let request =
-            openai_compat::build_stream_request(system_prompt, messages, tools, model, temperature);
+        let mut request =
+            openai_compat::build_stream_request(system_prompt, messages, tools, model, options);
+        request.max_completion_tokens = request.max_tokens.take();
         let response = self.call_api_streaming(&request).await?;
         Ok(openai_compat::sse_response_to_provider_stream(response))
     }
@@ -145,6 +150,10 @@ impl Provider for OpenAiProvider {
             tool_calling: true,
             streaming: true,
             vision: true,
+            options: OptionSupport {
+                thinking: false,
+                ..OptionSupport::ALL
+            },
         }
     }
 
@@ -185,11 +194,10 @@ impl Provider for OpenAiProvider {
         messages: &'a [ProviderMessage],
         tools: &'a [ToolSpec],
         model: &'a str,
-        temperature: f64,
+        options: &'a ChatOptions,
     ) -> Pin<Box<dyn Future<Output = anyhow::Result<ProviderResponse>> + Send + 'a>> {
         Box::pin(async move {
-            let request =
-                Self::build_tools_request(system_prompt, messages, tools, model, temperature);
+            let request = Self::build_tools_request(system_prompt, messages, tools, model, options);
             let chat_response = self.call_api_with_request(&request).await?;
             openai_compat::build_tool_provider_response(chat_response, "OpenAI")
         })
@@ -201,10 +209,10 @@ impl Provider for OpenAiProvider {
         messages: &'a [ProviderMessage],
         tools: &'a [ToolSpec],
         model: &'a str,
-        temperature: f64,
+        options: &'a ChatOptions,
     ) -> Pin<Box<dyn Future<Output = anyhow::Result<ProviderStream>> + Send + 'a>> {
         Box::pin(async move {
-            self.chat_with_tools_stream_impl(system_prompt, messages, tools, model, temperature)
+            self.chat_with_tools_stream_impl(system_prompt, messages, tools, model, options)
                 .await
         })
     }
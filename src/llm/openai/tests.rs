@@ -1,4 +1,5 @@
 use super::*;
+use crate::llm::options::{ChatOptions, ResponseFormat};
 use crate::llm::sse::parse_data_lines_without_done;
 use crate::llm::traits::Provider;
 use crate::llm::types::{ContentBlock, ImageSource, MessageRole, ProviderMessage, StopReason};
@@ -120,7 +121,13 @@ fn tools_request_serializes_in_openai_function_format() {
         }),
     }];
 
-    let req = OpenAiProvider::build_tools_request(None, &messages, &tools, "gpt-4o", 0.2);
+    let req = OpenAiProvider::build_tools_request(
+        None,
+        &messages,
+        &tools,
+        "gpt-4o",
+        &ChatOptions::new(0.2),
+    );
     let json = serde_json::to_value(&req).unwrap();
 
     assert_eq!(json["tools"][0]["type"], "function");
@@ -134,10 +141,34 @@ fn tools_request_serializes_in_openai_function_format() {
 
 #[test]
 fn request_without_tools_omits_tools_field() {
-    let req = OpenAiProvider::build_tools_request(None, &[], &[], "gpt-4o", 0.1);
+    let req = OpenAiProvider::build_tools_request(None, &[], &[], "gpt-4o", &ChatOptions::new(0.1));
     let json = serde_json::to_value(&req).unwrap();
 
     assert!(json.get("tools").is_none());
+    assert!(json.get("max_completion_tokens").is_none());
+    assert!(json.get("response_format").is_none());
+}
+
+#[test]
+fn chat_options_map_to_request_fields() {
+    let schema = serde_json::json!({"type": "object", "properties": {"city": {"type": "string"}}});
+    let options = ChatOptions::new(0.1)
+        .with_max_tokens(300)
+        .with_stop_sequences(vec!["END".to_string()])
+        .with_top_p(0.5)
+        .with_seed(11)
+        .with_response_format(ResponseFormat::json_schema("place", schema.clone()));
+    let req = OpenAiProvider::build_tools_request(None, &[], &[], "o4-mini", &options);
+    let json = serde_json::to_value(&req).unwrap();
+
+    assert_eq!(json["max_completion_tokens"], 300);
+    assert!(json.get("max_tokens").is_none());
+    assert_eq!(json["stop"][0], "END");
+    assert_eq!(json["top_p"], 0.5);
+    assert_eq!(json["seed"], 11);
+    assert_eq!(json["response_format"]["type"], "json_schema");
+    assert_eq!(json["response_format"]["json_schema"]["name"], "place");
+    assert_eq!(json["response_format"]["json_schema"]["schema"], schema);
 }
 
 #[test]
@@ -7,6 +7,20 @@ pub(in crate::llm) struct ChatRequest {
     pub(in crate::llm) messages: Vec<Message>,
     pub(in crate::llm) temperature: f64,
     #[serde(skip_serializing_if = "Option::is_none")]
+    pub(in crate::llm) max_tokens: Option<u32>,
+    /// Replacement for `max_tokens` on the `OpenAI` API; reasoning models
+    /// reject the older field.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(in crate::llm) max_completion_tokens: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(in crate::llm) stop: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(in crate::llm) top_p: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(in crate::llm) seed: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(in crate::llm) response_format: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub(in crate::llm) tools: Option<Vec<OpenAiTool>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub(in crate::llm) stream: Option<bool>,
@@ -8,6 +8,7 @@ use super::openai::{
 use crate::llm::sse::parse_data_lines_without_done;
 use crate::llm::{
     build_provider_client,
+    options::{ChatOptions, OptionSupport},
     streaming::ProviderStream,
     traits::{Provider, ProviderCapabilities},
     types::{ProviderMessage, ProviderResponse},
@@ -65,9 +66,9 @@ impl OpenRouterProvider {
         messages: &[ProviderMessage],
         tools: &[ToolSpec],
         model: &str,
-        temperature: f64,
+        options: &ChatOptions,
     ) -> ChatRequest {
-        openai_compat::build_tools_request(system_prompt, messages, tools, model, temperature)
+        openai_compat::build_tools_request(system_prompt, messages, tools, model, options)
     }
 
     async fn call_api_with_request(&self, request: &ChatRequest) -> anyhow::Result<ChatResponse> {
@@ -106,10 +107,10 @@ impl OpenRouterProvider {
         messages: &[ProviderMessage],
         tools: &[ToolSpec],
         model: &str,
-        temperature: f64,
+        options: &ChatOptions,
     ) -> anyhow::Result<ProviderStream> {
         let request =
-            openai_compat::build_stream_request(system_prompt, messages, tools, model, temperature);
+            openai_compat::build_stream_request(system_prompt, messages, tools, model, options);
         let response = self.call_api_streaming(&request).await?;
         Ok(openai_compat::sse_response_to_provider_stream(response))
     }
@@ -136,6 +137,10 @@ impl Provider for OpenRouterProvider {
             tool_calling: true,
             streaming: true,
             vision: true,
+            options: OptionSupport {
+                thinking: false,
+                ..OptionSupport::ALL
+            },
         }
     }
 
@@ -191,11 +196,10 @@ impl Provider for OpenRouterProvider {
         messages: &'a [ProviderMessage],
         tools: &'a [ToolSpec],
         model: &'a str,
-        temperature: f64,
+        options: &'a ChatOptions,
     ) -> Pin<Box<dyn Future<Output = anyhow::Result<ProviderResponse>> + Send + 'a>> {
         Box::pin(async move {
-            let request =
-                Self::build_tools_request(system_prompt, messages, tools, model, temperature);
+            let request = Self::build_tools_request(system_prompt, messages, tools, model, options);
             let chat_response = self.call_api_with_request(&request).await?;
             openai_compat::build_tool_provider_response(chat_response, "OpenRouter")
         })
@@ -207,10 +211,10 @@ impl Provider for OpenRouterProvider {
         messages: &'a [ProviderMessage],
         tools: &'a [ToolSpec],
         model: &'a str,
-        temperature: f64,
+        options: &'a ChatOptions,
     ) -> Pin<Box<dyn Future<Output = anyhow::Result<ProviderStream>> + Send + 'a>> {
         Box::pin(async move {
-            self.chat_with_tools_stream_impl(system_prompt, messages, tools, model, temperature)
+            self.chat_with_tools_stream_impl(system_prompt, messages, tools, model, options)
                 .await
         })
     }
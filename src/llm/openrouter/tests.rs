@@ -1,4 +1,5 @@
 use super::*;
+use crate::llm::options::ResponseFormat;
 use crate::llm::traits::Provider;
 use crate::llm::types::{ContentBlock, ImageSource, MessageRole, ProviderMessage};
 
@@ -17,10 +18,15 @@ fn tools_request_serializes_in_openai_function_format() {
         }),
     }];
 
+    let options = ChatOptions::new(0.3)
+        .with_max_tokens(128)
+        .with_response_format(ResponseFormat::Json);
     let request =
-        OpenRouterProvider::build_tools_request(None, &messages, &tools, "gpt-4o-mini", 0.3);
+        OpenRouterProvider::build_tools_request(None, &messages, &tools, "gpt-4o-mini", &options);
     let json = serde_json::to_value(&request).unwrap();
 
+    assert_eq!(json["max_tokens"], 128);
+    assert_eq!(json["response_format"]["type"], "json_object");
     assert_eq!(json["tools"][0]["type"], "function");
     assert_eq!(json["tools"][0]["function"]["name"], "shell");
     assert_eq!(json["tools"][0]["function"]["parameters"]["type"], "object");
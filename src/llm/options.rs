@@ -0,0 +1,194 @@
+//! Per-call generation options for [`Provider::chat_with_tools`] and its
+//! streaming variant.
+//!
+//! [`Provider::chat_with_tools`]: super::traits::Provider::chat_with_tools
+
+use super::thinking::ThinkingLevel;
+use serde_json::Value;
+
+/// Sampling and output controls for one request. Only `temperature` is
+/// always honoured; providers report which of the rest they apply through
+/// [`ProviderCapabilities::options`](super::traits::ProviderCapabilities::options).
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChatOptions {
+    pub temperature: f64,
+    /// Output token cap; `None` keeps the provider's per-model default.
+    pub max_tokens: Option<u32>,
+    /// Generation stops before any of these strings.
+    pub stop_sequences: Vec<String>,
+    pub top_p: Option<f64>,
+    /// Best-effort deterministic sampling.
+    pub seed: Option<u64>,
+    /// Constrain the answer to JSON, optionally matching a schema.
+    pub response_format: Option<ResponseFormat>,
+    /// Extended-thinking level of the conversation making the request.
+    pub thinking: ThinkingLevel,
+}
+
+impl ChatOptions {
+    pub fn new(temperature: f64) -> Self {
+        Self {
+            temperature,
+            max_tokens: None,
+            stop_sequences: Vec::new(),
+            top_p: None,
+            seed: None,
+            response_format: None,
+            thinking: ThinkingLevel::Off,
+        }
+    }
+
+    #[must_use]
+    pub fn with_max_tokens(mut self, max_tokens: u32) -> Self {
+        self.max_tokens = Some(max_tokens);
+        self
+    }
+
+    #[must_use]
+    pub fn with_stop_sequences(mut self, stop_sequences: Vec<String>) -> Self {
+        self.stop_sequences = stop_sequences;
+        self
+    }
+
+    #[must_use]
+    pub fn with_top_p(mut self, top_p: f64) -> Self {
+        self.top_p = Some(top_p);
+        self
+    }
+
+    #[must_use]
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    #[must_use]
+    pub fn with_response_format(mut self, response_format: ResponseFormat) -> Self {
+        self.response_format = Some(response_format);
+        self
+    }
+
+    #[must_use]
+    pub fn with_thinking(mut self, thinking: ThinkingLevel) -> Self {
+        self.thinking = thinking;
+        self
+    }
+
+    /// Names of the options set here that a provider with `support` ignores.
+    pub fn unsupported(&self, support: OptionSupport) -> Vec<&'static str> {
+        let mut ignored = Vec::new();
+        if self.max_tokens.is_some() && !support.max_tokens {
+            ignored.push("max_tokens");
+        }
+        if !self.stop_sequences.is_empty() && !support.stop_sequences {
+            ignored.push("stop_sequences");
+        }
+        if self.top_p.is_some() && !support.top_p {
+            ignored.push("top_p");
+        }
+        if self.seed.is_some() && !support.seed {
+            ignored.push("seed");
+        }
+        if self.response_format.is_some() && !support.response_format {
+            ignored.push("response_format");
+        }
+        if self.thinking != ThinkingLevel::Off && !support.thinking {
+            ignored.push("thinking");
+        }
+        ignored
+    }
+}
+
+impl Default for ChatOptions {
+    fn default() -> Self {
+        Self::new(0.7)
+    }
+}
+
+/// Structured output request.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ResponseFormat {
+    /// Any JSON object.
+    Json,
+    /// JSON matching `schema` (a JSON Schema object); `name` identifies it
+    /// to providers that require one.
+    JsonSchema { name: String, schema: Value },
+}
+
+impl ResponseFormat {
+    pub fn json_schema(name: impl Into<String>, schema: Value) -> Self {
+        Self::JsonSchema {
+            name: name.into(),
+            schema,
+        }
+    }
+
+    /// The `response_format` value of the `OpenAI` chat completions API,
+    /// also accepted by `OpenRouter` and most OpenAI-compatible APIs.
+    pub fn to_openai(&self) -> Value {
+        match self {
+            Self::Json => serde_json::json!({ "type": "json_object" }),
+            Self::JsonSchema { name, schema } => serde_json::json!({
+                "type": "json_schema",
+                "json_schema": { "name": name, "schema": schema, "strict": true }
+            }),
+        }
+    }
+}
+
+/// Which [`ChatOptions`] fields beyond `temperature` a provider applies.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[allow(clippy::struct_excessive_bools)]
+pub struct OptionSupport {
+    pub max_tokens: bool,
+    pub stop_sequences: bool,
+    pub top_p: bool,
+    pub seed: bool,
+    pub response_format: bool,
+    pub thinking: bool,
+}
+
+impl OptionSupport {
+    pub const ALL: Self = Self {
+        max_tokens: true,
+        stop_sequences: true,
+        top_p: true,
+        seed: true,
+        response_format: true,
+        thinking: true,
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unsupported_lists_only_set_options_the_provider_ignores() {
+        let options = ChatOptions::new(0.2)
+            .with_max_tokens(256)
+            .with_seed(7)
+            .with_response_format(ResponseFormat::Json);
+        let support = OptionSupport {
+            max_tokens: true,
+            ..OptionSupport::default()
+        };
+
+        assert_eq!(options.unsupported(support), ["seed", "response_format"]);
+        assert!(options.unsupported(OptionSupport::ALL).is_empty());
+        assert!(
+            ChatOptions::new(0.2)
+                .unsupported(OptionSupport::default())
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn json_schema_maps_to_openai_response_format() {
+        let format = ResponseFormat::json_schema("plan", serde_json::json!({"type": "object"}));
+        let value = format.to_openai();
+        assert_eq!(value["type"], "json_schema");
+        assert_eq!(value["json_schema"]["name"], "plan");
+        assert_eq!(value["json_schema"]["schema"]["type"], "object");
+    }
+}
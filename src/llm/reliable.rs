@@ -1,3 +1,4 @@
+use super::options::ChatOptions;
 use super::streaming::ProviderStream;
 use super::traits::{Provider, ProviderCapabilities};
 use super::types::{ProviderMessage, ProviderResponse};
@@ -75,6 +76,26 @@ impl Provider for ReliableProvider {
             .map_or_else(ProviderCapabilities::default, |(_, p)| p.capabilities())
     }
 
+    /// Models from every provider in the chain that can list them.
+    fn list_models(
+        &self,
+    ) -> Pin<Box<dyn Future<Output = anyhow::Result<Vec<String>>> + Send + '_>> {
+        Box::pin(async move {
+            let mut models = Vec::new();
+            for (name, provider) in &self.providers {
+                match provider.list_models().await {
+                    Ok(listed) => models.extend(listed),
+                    Err(e) => {
+                        tracing::debug!(provider = name.as_str(), "Listing models failed: {e}");
+                    }
+                }
+            }
+            models.sort();
+            models.dedup();
+            Ok(models)
+        })
+    }
+
     fn warmup(&self) -> Pin<Box<dyn Future<Output = anyhow::Result<()>> + Send + '_>> {
         Box::pin(async move {
             for (name, provider) in &self.providers {
@@ -232,7 +253,7 @@ impl Provider for ReliableProvider {
         messages: &'a [ProviderMessage],
         tools: &'a [ToolSpec],
         model: &'a str,
-        temperature: f64,
+        options: &'a ChatOptions,
     ) -> Pin<Box<dyn Future<Output = anyhow::Result<ProviderResponse>> + Send + 'a>> {
         Box::pin(async move {
             let mut failures = Vec::new();
@@ -242,7 +263,7 @@ impl Provider for ReliableProvider {
 
                 for attempt in 0..=self.max_retries {
                     match provider
-                        .chat_with_tools(system_prompt, messages, tools, model, temperature)
+                        .chat_with_tools(system_prompt, messages, tools, model, options)
                         .await
                     {
                         Ok(resp) => {
@@ -301,7 +322,7 @@ impl Provider for ReliableProvider {
         messages: &'a [ProviderMessage],
         tools: &'a [ToolSpec],
         model: &'a str,
-        temperature: f64,
+        options: &'a ChatOptions,
     ) -> Pin<Box<dyn Future<Output = anyhow::Result<ProviderStream>> + Send + 'a>> {
         Box::pin(async move {
             let mut failures = Vec::new();
@@ -311,7 +332,7 @@ impl Provider for ReliableProvider {
 
                 for attempt in 0..=self.max_retries {
                     match provider
-                        .chat_with_tools_stream(system_prompt, messages, tools, model, temperature)
+                        .chat_with_tools_stream(system_prompt, messages, tools, model, options)
                         .await
                     {
                         Ok(resp) => {
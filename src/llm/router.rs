@@ -0,0 +1,393 @@
+//! Cost-aware model routing: picks the provider and model for each turn
+//! from `[routing]` rules, and moves turns to the cheapest priced model
+//! once the daily cost budget is nearly spent.
+
+use super::factory;
+use super::traits::Provider;
+use crate::config::{Config, RouteRule, RoutingConfig};
+use crate::runtime::usage::{ModelPricing, lookup_pricing, pricing_table};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, PoisonError};
+
+/// What the router knows about a turn before it runs.
+#[derive(Debug, Clone, Copy)]
+pub struct TurnFeatures<'a> {
+    /// Length of the user message in characters.
+    pub chars: usize,
+    pub has_images: bool,
+    pub needs_tools: bool,
+    pub channel: Option<&'a str>,
+    /// Share of the daily cost budget still unspent, 0–100.
+    pub budget_remaining_percent: u8,
+}
+
+/// Where a turn should go.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Route {
+    pub provider: String,
+    pub model: String,
+    /// The rule that picked the model; `None` for the default.
+    pub rule: Option<String>,
+    /// Whether the low-budget fallback overrode the normal choice.
+    pub low_budget: bool,
+}
+
+/// Share of `max_cents` not yet spent, rounded down. A zero budget counts
+/// as exhausted.
+pub fn budget_remaining_percent(spent_cents: u32, max_cents: u32) -> u8 {
+    if max_cents == 0 {
+        return 0;
+    }
+    let remaining = u64::from(max_cents.saturating_sub(spent_cents)) * 100 / u64::from(max_cents);
+    u8::try_from(remaining).unwrap_or(100)
+}
+
+pub struct ModelRouter {
+    rules: Vec<RouteRule>,
+    low_budget_percent: u8,
+    pricing: Vec<ModelPricing>,
+    default_provider: String,
+    default_model: String,
+}
+
+impl ModelRouter {
+    /// `custom_pricing` comes from `[usage] pricing` and extends the
+    /// built-in price table.
+    pub fn new(
+        config: &RoutingConfig,
+        custom_pricing: &[ModelPricing],
+        default_provider: &str,
+        default_model: &str,
+    ) -> Self {
+        Self {
+            rules: config.rules.clone(),
+            low_budget_percent: config.low_budget_percent,
+            pricing: pricing_table(custom_pricing),
+            default_provider: default_provider.to_string(),
+            default_model: default_model.to_string(),
+        }
+    }
+
+    pub fn route(&self, features: &TurnFeatures<'_>) -> Route {
+        let chosen_rule = self.rules.iter().enumerate().find(|(_, rule)| {
+            rule_matches(rule, features) && budget_allows(rule, features.budget_remaining_percent)
+        });
+        let replace_unpriced = chosen_rule.is_some_and(|(_, rule)| rule.replace_when_unpriced);
+        let chosen = chosen_rule.map_or_else(
+            || self.default_route(),
+            |(index, rule)| self.rule_route(index, rule),
+        );
+
+        if features.budget_remaining_percent >= self.low_budget_percent {
+            return chosen;
+        }
+        let cheapest = self
+            .rules
+            .iter()
+            .enumerate()
+            .filter(|(_, rule)| rule_matches(rule, features))
+            .map(|(index, rule)| self.rule_route(index, rule))
+            .chain(std::iter::once(self.default_route()))
+            .filter_map(|route| self.blended_price(&route.model).map(|price| (price, route)))
+            .min_by(|(a, _), (b, _)| a.total_cmp(b));
+        match (cheapest, self.blended_price(&chosen.model)) {
+            (Some((price, route)), Some(chosen_price)) if price < chosen_price => Route {
+                low_budget: true,
+                ..route
+            },
+            (Some((_, route)), None) if replace_unpriced => Route {
+                low_budget: true,
+                ..route
+            },
+            _ => chosen,
+        }
+    }
+
+    fn default_route(&self) -> Route {
+        Route {
+            provider: self.default_provider.clone(),
+            model: self.default_model.clone(),
+            rule: None,
+            low_budget: false,
+        }
+    }
+
+    fn rule_route(&self, index: usize, rule: &RouteRule) -> Route {
+        Route {
+            provider: rule
+                .provider
+                .clone()
+                .unwrap_or_else(|| self.default_provider.clone()),
+            model: rule.model.clone(),
+            rule: Some(
+                rule.name
+                    .clone()
+                    .unwrap_or_else(|| format!("rule {}", index + 1)),
+            ),
+            low_budget: false,
+        }
+    }
+
+    /// Dollars per million tokens, assuming a turn reads about three times
+    /// as many tokens as it writes.
+    fn blended_price(&self, model: &str) -> Option<f64> {
+        lookup_pricing(model, &self.pricing).map(|pricing| {
+            (pricing.input_cost_per_million * 3.0 + pricing.output_cost_per_million) / 4.0
+        })
+    }
+}
+
+fn rule_matches(rule: &RouteRule, features: &TurnFeatures<'_>) -> bool {
+    rule.min_chars.is_none_or(|min| features.chars >= min)
+        && rule.max_chars.is_none_or(|max| features.chars <= max)
+        && rule
+            .has_images
+            .is_none_or(|want| want == features.has_images)
+        && rule
+            .needs_tools
+            .is_none_or(|want| want == features.needs_tools)
+        && (rule.channels.is_empty()
+            || features
+                .channel
+                .is_some_and(|channel| rule.channels.iter().any(|name| name == channel)))
+}
+
+fn budget_allows(rule: &RouteRule, remaining_percent: u8) -> bool {
+    rule.min_budget_remaining_percent
+        .is_none_or(|min| remaining_percent >= min)
+}
+
+/// Providers named by routing rules, created on first use and reused for
+/// later turns. The default provider is passed in already built.
+pub struct RoutedProviders {
+    config: Arc<Config>,
+    default_name: String,
+    default: Arc<dyn Provider>,
+    created: Mutex<HashMap<String, Arc<dyn Provider>>>,
+}
+
+impl RoutedProviders {
+    pub fn new(config: Arc<Config>, default_name: &str, default: Arc<dyn Provider>) -> Self {
+        Self {
+            config,
+            default_name: default_name.to_string(),
+            default,
+            created: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// The provider called `name`, with the same resilient and OAuth
+    /// recovery wrapping as the default provider.
+    pub fn get(&self, name: &str) -> anyhow::Result<Arc<dyn Provider>> {
+        if name == self.default_name {
+            return Ok(Arc::clone(&self.default));
+        }
+        let mut created = self.created.lock().unwrap_or_else(PoisonError::into_inner);
+        if let Some(provider) = created.get(name) {
+            return Ok(Arc::clone(provider));
+        }
+        let api_key = self.config.api_key.as_deref();
+        let provider: Arc<dyn Provider> =
+            Arc::from(factory::create_resilient_provider_with_oauth_recovery(
+                &self.config,
+                name,
+                &self.config.reliability,
+                |provider| factory::resolve_api_key(provider, api_key),
+            )?);
+        created.insert(name.to_string(), Arc::clone(&provider));
+        Ok(provider)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn features(chars: usize, budget_remaining_percent: u8) -> TurnFeatures<'static> {
+        TurnFeatures {
+            chars,
+            has_images: false,
+            needs_tools: true,
+            channel: Some("telegram"),
+            budget_remaining_percent,
+        }
+    }
+
+    fn router(rules: Vec<RouteRule>) -> ModelRouter {
+        let config = RoutingConfig {
+            enabled: true,
+            rules,
+            ..RoutingConfig::default()
+        };
+        ModelRouter::new(&config, &[], "anthropic", "claude-sonnet-4-20250514")
+    }
+
+    fn rule(model: &str) -> RouteRule {
+        RouteRule {
+            model: model.to_string(),
+            ..RouteRule::default()
+        }
+    }
+
+    #[test]
+    fn first_matching_rule_wins_and_unmatched_turns_use_the_default() {
+        let router = router(vec![
+            RouteRule {
+                name: Some("short".into()),
+                max_chars: Some(20),
+                ..rule("claude-3-5-haiku-20241022")
+            },
+            RouteRule {
+                provider: Some("openai".into()),
+                has_images: Some(true),
+                ..rule("gpt-4o")
+            },
+        ]);
+
+        let short = router.route(&features(7, 100));
+        assert_eq!(short.model, "claude-3-5-haiku-20241022");
+        assert_eq!(short.provider, "anthropic");
+        assert_eq!(short.rule.as_deref(), Some("short"));
+
+        let image = router.route(&TurnFeatures {
+            has_images: true,
+            ..features(500, 100)
+        });
+        assert_eq!(image.provider, "openai");
+        assert_eq!(image.rule.as_deref(), Some("rule 2"));
+
+        let long = router.route(&features(500, 100));
+        assert_eq!(long.model, "claude-sonnet-4-20250514");
+        assert!(long.rule.is_none());
+    }
+
+    #[test]
+    fn channel_and_tool_conditions_must_hold() {
+        let router = router(vec![RouteRule {
+            channels: vec!["slack".into()],
+            needs_tools: Some(false),
+            ..rule("gpt-4o-mini")
+        }]);
+
+        assert!(router.route(&features(10, 100)).rule.is_none());
+        let slack_chat = router.route(&TurnFeatures {
+            channel: Some("slack"),
+            needs_tools: false,
+            ..features(10, 100)
+        });
+        assert_eq!(slack_chat.model, "gpt-4o-mini");
+    }
+
+    #[test]
+    fn budget_gated_rules_fall_through_as_the_budget_drains() {
+        let router = router(vec![
+            RouteRule {
+                min_budget_remaining_percent: Some(50),
+                ..rule("claude-3-opus-20240229")
+            },
+            rule("claude-3-5-haiku-20241022"),
+        ]);
+
+        assert_eq!(
+            router.route(&features(100, 80)).model,
+            "claude-3-opus-20240229"
+        );
+        assert_eq!(
+            router.route(&features(100, 30)).model,
+            "claude-3-5-haiku-20241022"
+        );
+    }
+
+    #[test]
+    fn low_budget_switches_to_the_cheapest_matching_model() {
+        let router = router(vec![
+            RouteRule {
+                has_images: Some(true),
+                ..rule("gemini-2.0-flash")
+            },
+            RouteRule {
+                max_chars: Some(1_000),
+                ..rule("claude-3-opus-20240229")
+            },
+            RouteRule {
+                max_chars: Some(1_000),
+                ..rule("gpt-4o-mini")
+            },
+        ]);
+
+        let normal = router.route(&features(500, 50));
+        assert_eq!(normal.model, "claude-3-opus-20240229");
+        assert!(!normal.low_budget);
+
+        let drained = router.route(&features(500, 5));
+        assert_eq!(drained.model, "gpt-4o-mini");
+        assert!(drained.low_budget);
+
+        // Only the default is eligible for a long text turn.
+        let long = router.route(&features(5_000, 5));
+        assert_eq!(long.model, "claude-sonnet-4-20250514");
+        assert!(!long.low_budget);
+    }
+
+    #[test]
+    fn low_budget_keeps_unpriced_models_unless_the_rule_allows_replacing_them() {
+        let local = RouteRule {
+            max_chars: Some(1_000),
+            ..rule("my-local-model")
+        };
+        let cheap = RouteRule {
+            max_chars: Some(1_000),
+            ..rule("gpt-4o-mini")
+        };
+
+        let kept = router(vec![local.clone(), cheap.clone()]).route(&features(500, 5));
+        assert_eq!(kept.model, "my-local-model");
+        assert!(!kept.low_budget);
+
+        let replaced = router(vec![
+            RouteRule {
+                replace_when_unpriced: true,
+                ..local
+            },
+            cheap,
+        ])
+        .route(&features(500, 5));
+        assert_eq!(replaced.model, "gpt-4o-mini");
+        assert!(replaced.low_budget);
+    }
+
+    #[test]
+    fn configured_pricing_overrides_the_builtin_table() {
+        let haiku = RouteRule {
+            min_budget_remaining_percent: Some(50),
+            ..rule("claude-3-5-haiku-20241022")
+        };
+        assert_eq!(
+            router(vec![haiku.clone()]).route(&features(100, 0)).model,
+            "claude-3-5-haiku-20241022"
+        );
+
+        let config = RoutingConfig {
+            rules: vec![haiku],
+            ..RoutingConfig::default()
+        };
+        let pricing = [ModelPricing {
+            model_pattern: "claude-3-5-haiku".into(),
+            input_cost_per_million: 100.0,
+            output_cost_per_million: 100.0,
+        }];
+        let router = ModelRouter::new(&config, &pricing, "anthropic", "claude-sonnet-4-20250514");
+        assert_eq!(
+            router.route(&features(100, 0)).model,
+            "claude-sonnet-4-20250514"
+        );
+    }
+
+    #[test]
+    fn remaining_percent_handles_overspend_and_zero_budget() {
+        assert_eq!(budget_remaining_percent(0, 500), 100);
+        assert_eq!(budget_remaining_percent(450, 500), 10);
+        assert_eq!(budget_remaining_percent(900, 500), 0);
+        assert_eq!(budget_remaining_percent(0, 0), 0);
+    }
+}
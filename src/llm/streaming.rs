@@ -17,6 +17,14 @@ pub enum StreamEvent {
     TextDelta {
         text: String,
     },
+    /// Extended-thinking text, shown apart from the answer.
+    ThinkingDelta {
+        thinking: String,
+    },
+    /// Signature closing the current thinking block.
+    ThinkingSignature {
+        signature: String,
+    },
     ToolCallDelta {
         index: u32,
         id: Option<String>,
@@ -110,6 +118,8 @@ impl StreamSink for ChannelStreamSink {
                     self.flush_buffer().await;
                 }
                 StreamEvent::ResponseStart { .. }
+                | StreamEvent::ThinkingDelta { .. }
+                | StreamEvent::ThinkingSignature { .. }
                 | StreamEvent::ToolCallDelta { .. }
                 | StreamEvent::ToolCallComplete { .. } => {}
             }
@@ -157,6 +167,8 @@ impl StreamSink for CliStreamSink {
 
 pub struct StreamCollector {
     text: String,
+    thinking: String,
+    thinking_signature: String,
     content_blocks: Vec<ContentBlock>,
     tool_call_builders: Vec<ToolCallBuilder>,
     stop_reason: Option<StopReason>,
@@ -175,6 +187,8 @@ impl StreamCollector {
     pub fn new() -> Self {
         Self {
             text: String::new(),
+            thinking: String::new(),
+            thinking_signature: String::new(),
             content_blocks: Vec::new(),
             tool_call_builders: Vec::new(),
             stop_reason: None,
@@ -192,6 +206,12 @@ impl StreamCollector {
             StreamEvent::TextDelta { text } => {
                 self.text.push_str(text);
             }
+            StreamEvent::ThinkingDelta { thinking } => {
+                self.thinking.push_str(thinking);
+            }
+            StreamEvent::ThinkingSignature { signature } => {
+                self.thinking_signature.push_str(signature);
+            }
             StreamEvent::ToolCallDelta {
                 index,
                 id,
@@ -274,6 +294,16 @@ impl StreamCollector {
                 },
             );
         }
+        // Thinking precedes everything else in the assistant turn.
+        if !self.thinking.is_empty() {
+            self.content_blocks.insert(
+                0,
+                ContentBlock::Thinking {
+                    thinking: self.thinking,
+                    signature: self.thinking_signature,
+                },
+            );
+        }
 
         ProviderResponse {
             text: self.text,
@@ -297,6 +327,22 @@ pub fn resp_to_events(resp: ProviderResponse) -> Vec<Result<StreamEvent>> {
     } = resp;
 
     let mut events = vec![Ok(StreamEvent::ResponseStart { model })];
+    for block in &content_blocks {
+        if let ContentBlock::Thinking {
+            thinking,
+            signature,
+        } = block
+        {
+            events.push(Ok(StreamEvent::ThinkingDelta {
+                thinking: thinking.clone(),
+            }));
+            if !signature.is_empty() {
+                events.push(Ok(StreamEvent::ThinkingSignature {
+                    signature: signature.clone(),
+                }));
+            }
+        }
+    }
     if !text.is_empty() {
         events.push(Ok(StreamEvent::TextDelta { text }));
     }
@@ -307,7 +353,8 @@ pub fn resp_to_events(resp: ProviderResponse) -> Vec<Result<StreamEvent>> {
             }
             ContentBlock::Text { .. }
             | ContentBlock::ToolResult { .. }
-            | ContentBlock::Image { .. } => {}
+            | ContentBlock::Image { .. }
+            | ContentBlock::Thinking { .. } => {}
         }
     }
     events.push(Ok(StreamEvent::Done {
@@ -368,6 +415,31 @@ mod tests {
         }
     }
 
+    #[test]
+    fn collector_puts_thinking_first() {
+        let mut collector = StreamCollector::new();
+        collector.feed(&StreamEvent::ThinkingDelta {
+            thinking: "weighing ".into(),
+        });
+        collector.feed(&StreamEvent::ThinkingDelta {
+            thinking: "options".into(),
+        });
+        collector.feed(&StreamEvent::ThinkingSignature {
+            signature: "sig".into(),
+        });
+        collector.feed(&StreamEvent::TextDelta {
+            text: "answer".into(),
+        });
+        let response = collector.finish();
+
+        assert_eq!(response.text, "answer");
+        assert!(matches!(
+            response.content_blocks.as_slice(),
+            [ContentBlock::Thinking { thinking, signature }, ContentBlock::Text { .. }]
+            if thinking == "weighing options" && signature == "sig"
+        ));
+    }
+
     #[tokio::test]
     async fn null_stream_sink_is_noop() {
         let sink = NullStreamSink;
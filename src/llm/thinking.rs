@@ -0,0 +1,59 @@
+//! Extended-thinking level chosen with `/think`. Each conversation keeps its
+//! own level and passes it to the provider in
+//! [`ChatOptions::thinking`](crate::llm::ChatOptions::thinking).
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ThinkingLevel {
+    #[default]
+    Off,
+    Low,
+    Medium,
+    High,
+}
+
+impl ThinkingLevel {
+    /// Accepts `off`/`low`/`medium`/`high` plus a few common aliases.
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "off" | "none" | "0" => Some(Self::Off),
+            "low" | "minimal" | "1" => Some(Self::Low),
+            "medium" | "med" | "on" | "2" => Some(Self::Medium),
+            "high" | "max" | "3" => Some(Self::High),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Off => "off",
+            Self::Low => "low",
+            Self::Medium => "medium",
+            Self::High => "high",
+        }
+    }
+
+    /// Tokens the model may spend thinking before it answers.
+    pub fn budget_tokens(self) -> Option<u32> {
+        match self {
+            Self::Off => None,
+            Self::Low => Some(4_096),
+            Self::Medium => Some(16_384),
+            Self::High => Some(32_768),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_accepts_levels_and_aliases() {
+        assert_eq!(ThinkingLevel::parse("HIGH"), Some(ThinkingLevel::High));
+        assert_eq!(ThinkingLevel::parse(" med "), Some(ThinkingLevel::Medium));
+        assert_eq!(ThinkingLevel::parse("none"), Some(ThinkingLevel::Off));
+        assert_eq!(ThinkingLevel::parse("extreme"), None);
+        assert_eq!(ThinkingLevel::Off.budget_tokens(), None);
+        assert!(ThinkingLevel::High.budget_tokens() > ThinkingLevel::Low.budget_tokens());
+    }
+}
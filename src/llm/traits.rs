@@ -1,3 +1,4 @@
+use super::options::{ChatOptions, OptionSupport};
 use super::types::{ContentBlock, MessageRole, ProviderMessage, ProviderResponse};
 use crate::tools::ToolSpec;
 use futures_util::stream;
@@ -20,7 +21,8 @@ pub fn messages_to_text(messages: &[ProviderMessage]) -> String {
                     ContentBlock::Text { text } => Some(text.clone()),
                     ContentBlock::ToolUse { .. }
                     | ContentBlock::ToolResult { .. }
-                    | ContentBlock::Image { .. } => None,
+                    | ContentBlock::Image { .. }
+                    | ContentBlock::Thinking { .. } => None,
                 })
                 .collect();
             if text_parts.is_empty() {
@@ -41,6 +43,8 @@ pub struct ProviderCapabilities {
     pub tool_calling: bool,
     pub streaming: bool,
     pub vision: bool,
+    /// Which [`ChatOptions`] beyond temperature the provider applies.
+    pub options: OptionSupport,
 }
 
 pub trait Provider: Send + Sync {
@@ -72,6 +76,14 @@ pub trait Provider: Send + Sync {
         temperature: f64,
     ) -> Pin<Box<dyn Future<Output = anyhow::Result<String>> + Send + 'a>>;
 
+    /// Models the provider can serve, for providers that can enumerate them;
+    /// empty otherwise.
+    fn list_models(
+        &self,
+    ) -> Pin<Box<dyn Future<Output = anyhow::Result<Vec<String>>> + Send + '_>> {
+        Box::pin(async move { Ok(Vec::new()) })
+    }
+
     /// Warm up the HTTP connection pool.
     fn warmup(&self) -> Pin<Box<dyn Future<Output = anyhow::Result<()>> + Send + '_>> {
         Box::pin(async move { Ok(()) })
@@ -92,17 +104,20 @@ pub trait Provider: Send + Sync {
         })
     }
 
+    /// Chat with tool definitions and full message history. Options the
+    /// provider does not list in [`ProviderCapabilities::options`] are
+    /// ignored.
     fn chat_with_tools<'a>(
         &'a self,
         system_prompt: Option<&'a str>,
         messages: &'a [ProviderMessage],
         _tools: &'a [ToolSpec],
         model: &'a str,
-        temperature: f64,
+        options: &'a ChatOptions,
     ) -> Pin<Box<dyn Future<Output = anyhow::Result<ProviderResponse>> + Send + 'a>> {
         Box::pin(async move {
             let text = messages_to_text(messages);
-            self.chat_with_system_full(system_prompt, &text, model, temperature)
+            self.chat_with_system_full(system_prompt, &text, model, options.temperature)
                 .await
         })
     }
@@ -125,11 +140,11 @@ pub trait Provider: Send + Sync {
         messages: &'a [ProviderMessage],
         tools: &'a [ToolSpec],
         model: &'a str,
-        temperature: f64,
+        options: &'a ChatOptions,
     ) -> Pin<Box<dyn Future<Output = anyhow::Result<ProviderStream>> + Send + 'a>> {
         Box::pin(async move {
             let resp = self
-                .chat_with_tools(system_prompt, messages, tools, model, temperature)
+                .chat_with_tools(system_prompt, messages, tools, model, options)
                 .await?;
             Ok(Box::pin(stream::iter(resp_to_events(resp))) as ProviderStream)
         })
@@ -191,5 +206,6 @@ mod tests {
         assert!(!caps.tool_calling);
         assert!(!caps.streaming);
         assert!(!caps.vision);
+        assert_eq!(caps.options, OptionSupport::default());
     }
 }
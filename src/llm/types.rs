@@ -26,6 +26,13 @@ pub enum ContentBlock {
     Image {
         source: ImageSource,
     },
+    /// Extended-thinking output. The signature lets the provider verify the
+    /// block when it is sent back alongside tool results.
+    Thinking {
+        thinking: String,
+        #[serde(default, skip_serializing_if = "String::is_empty")]
+        signature: String,
+    },
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -15,7 +15,7 @@ use tracing::Level;
 use tracing_subscriber::FmtSubscriber;
 
 use asteroniris::Config;
-use asteroniris::cli::commands::Cli;
+use asteroniris::cli::commands::{Cli, Commands};
 
 #[tokio::main]
 async fn main() -> Result<()> {
@@ -24,14 +24,18 @@ async fn main() -> Result<()> {
         eprintln!("Warning: Failed to install default crypto provider: {e:?}");
     }
 
-    // Initialize logging
-    let subscriber = FmtSubscriber::builder()
-        .with_max_level(Level::INFO)
-        .finish();
-    tracing::subscriber::set_global_default(subscriber)
-        .context("setting default subscriber failed")?;
-
     let cli = Cli::parse();
+
+    // Initialize logging. `mcp serve` speaks the protocol on stdout, so its
+    // logs must go to stderr.
+    let builder = FmtSubscriber::builder().with_max_level(Level::INFO);
+    if matches!(cli.command, Commands::Mcp { .. }) {
+        tracing::subscriber::set_global_default(builder.with_writer(std::io::stderr).finish())
+    } else {
+        tracing::subscriber::set_global_default(builder.finish())
+    }
+    .context("setting default subscriber failed")?;
+
     let config = Arc::new(Config::load_or_init()?);
     asteroniris::app::dispatch::dispatch(cli, config).await
 }
@@ -26,6 +26,8 @@ pub fn detect_mime_from_extension(filename: &str) -> Option<String> {
         "mp4" => Some("video/mp4".into()),
         "webm" => Some("video/webm".into()),
         "pdf" => Some("application/pdf".into()),
+        "txt" => Some("text/plain".into()),
+        "md" => Some("text/markdown".into()),
         _ => None,
     }
 }
@@ -80,6 +82,10 @@ mod tests {
             detect_mime_from_extension("report.pdf").as_deref(),
             Some("application/pdf")
         );
+        assert_eq!(
+            detect_mime_from_extension("notes.txt").as_deref(),
+            Some("text/plain")
+        );
     }
 
     #[test]
@@ -1,5 +1,9 @@
+pub mod detection;
+pub mod processing;
+pub mod storage;
 pub mod types;
 
+pub use detection::detect_media_type;
+pub use processing::MediaProcessor;
+pub use storage::MediaStore;
 pub use types::{MediaConfig, MediaFile, MediaType, StoredMedia};
-
-// Remaining media modules (detection, processing, storage) will be ported in Phase 6.
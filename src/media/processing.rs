@@ -2,8 +2,9 @@ use super::types::{MediaFile, MediaType};
 use anyhow::Result;
 use std::sync::Arc;
 
-use crate::core::providers::response::{ContentBlock, ImageSource, ProviderMessage};
-use crate::core::providers::traits::Provider;
+use crate::llm::options::ChatOptions;
+use crate::llm::traits::Provider;
+use crate::llm::types::{ContentBlock, ImageSource, ProviderMessage, ProviderResponse};
 
 const IMAGE_DESCRIPTION_PROMPT: &str = "Describe this image concisely in 1-2 sentences.";
 
@@ -52,7 +53,13 @@ impl MediaProcessor {
             )];
 
             match provider
-                .chat_with_tools(Some(IMAGE_DESCRIPTION_PROMPT), &messages, &[], model, 0.2)
+                .chat_with_tools(
+                    Some(IMAGE_DESCRIPTION_PROMPT),
+                    &messages,
+                    &[],
+                    model,
+                    &ChatOptions::new(0.2),
+                )
                 .await
             {
                 Ok(response) => {
@@ -131,9 +138,7 @@ impl MediaProcessor {
     }
 }
 
-fn extract_response_text(
-    response: &crate::core::providers::response::ProviderResponse,
-) -> Option<String> {
+fn extract_response_text(response: &ProviderResponse) -> Option<String> {
     let text = response.text.trim();
     if !text.is_empty() {
         return Some(text.to_string());
@@ -200,10 +205,12 @@ fn encode_base64(bytes: &[u8]) -> String {
 mod tests {
     use super::*;
     use anyhow::anyhow;
-    use async_trait::async_trait;
+    use std::future::Future;
+    use std::pin::Pin;
 
-    use crate::core::providers::response::{ImageSource, MessageRole, ProviderResponse};
-    use crate::core::tools::traits::ToolSpec;
+    use crate::llm::traits::ProviderCapabilities;
+    use crate::llm::types::MessageRole;
+    use crate::tools::ToolSpec;
 
     #[derive(Debug, Clone, Copy)]
     enum VisionMode {
@@ -212,10 +219,12 @@ mod tests {
         Error,
     }
 
+    type VisionCall = (Option<String>, String, f64, usize);
+
     struct MockVisionProvider {
         supports_vision: bool,
         mode: VisionMode,
-        calls: std::sync::Mutex<Vec<(Option<String>, String, f64, usize)>>,
+        calls: std::sync::Mutex<Vec<VisionCall>>,
     }
 
     impl MockVisionProvider {
@@ -231,74 +240,82 @@ mod tests {
             self.calls.lock().unwrap().len()
         }
 
-        fn first_call(&self) -> (Option<String>, String, f64, usize) {
+        fn first_call(&self) -> VisionCall {
             self.calls.lock().unwrap()[0].clone()
         }
     }
 
-    #[async_trait]
     impl Provider for MockVisionProvider {
-        async fn chat_with_system(
-            &self,
-            _system_prompt: Option<&str>,
-            _message: &str,
-            _model: &str,
-            _temperature: f64,
-        ) -> anyhow::Result<String> {
-            Ok("unused".to_string())
+        fn name(&self) -> &str {
+            "mock-vision"
         }
 
-        async fn chat_with_tools(
-            &self,
-            system_prompt: Option<&str>,
-            messages: &[ProviderMessage],
-            tools: &[ToolSpec],
-            model: &str,
-            temperature: f64,
-        ) -> anyhow::Result<ProviderResponse> {
-            self.calls.lock().unwrap().push((
-                system_prompt.map(ToString::to_string),
-                model.to_string(),
-                temperature,
-                tools.len(),
-            ));
-
-            assert_eq!(messages.len(), 1);
-            assert!(matches!(messages[0].role, MessageRole::User));
-            assert!(matches!(&messages[0].content[0], ContentBlock::Text { .. }));
-            assert!(matches!(
-                &messages[0].content[1],
-                ContentBlock::Image { .. }
-            ));
-
-            if let ContentBlock::Image { source } = &messages[0].content[1] {
-                match source {
-                    ImageSource::Base64 { media_type, data } => {
-                        assert_eq!(media_type, "image/png");
-                        assert_eq!(data, "AQID");
-                    }
-                    ImageSource::Url { .. } => panic!("expected base64 image source"),
-                }
+        fn capabilities(&self) -> ProviderCapabilities {
+            ProviderCapabilities {
+                vision: self.supports_vision,
+                ..ProviderCapabilities::default()
             }
+        }
 
-            match self.mode {
-                VisionMode::Success => Ok(ProviderResponse::text_only(
-                    "A small test image with three bytes.".to_string(),
-                )),
-                VisionMode::EmptyText => Ok(ProviderResponse {
-                    text: "   ".to_string(),
-                    input_tokens: None,
-                    output_tokens: None,
-                    model: None,
-                    content_blocks: vec![],
-                    stop_reason: None,
-                }),
-                VisionMode::Error => Err(anyhow!("vision provider failed")),
-            }
+        fn chat_with_system<'a>(
+            &'a self,
+            _system_prompt: Option<&'a str>,
+            _message: &'a str,
+            _model: &'a str,
+            _temperature: f64,
+        ) -> Pin<Box<dyn Future<Output = anyhow::Result<String>> + Send + 'a>> {
+            Box::pin(async move { Ok("unused".to_string()) })
         }
 
-        fn supports_vision(&self) -> bool {
-            self.supports_vision
+        fn chat_with_tools<'a>(
+            &'a self,
+            system_prompt: Option<&'a str>,
+            messages: &'a [ProviderMessage],
+            tools: &'a [ToolSpec],
+            model: &'a str,
+            options: &'a ChatOptions,
+        ) -> Pin<Box<dyn Future<Output = anyhow::Result<ProviderResponse>> + Send + 'a>> {
+            Box::pin(async move {
+                self.calls.lock().unwrap().push((
+                    system_prompt.map(ToString::to_string),
+                    model.to_string(),
+                    options.temperature,
+                    tools.len(),
+                ));
+
+                assert_eq!(messages.len(), 1);
+                assert!(matches!(messages[0].role, MessageRole::User));
+                assert!(matches!(&messages[0].content[0], ContentBlock::Text { .. }));
+                assert!(matches!(
+                    &messages[0].content[1],
+                    ContentBlock::Image { .. }
+                ));
+
+                if let ContentBlock::Image { source } = &messages[0].content[1] {
+                    match source {
+                        ImageSource::Base64 { media_type, data } => {
+                            assert_eq!(media_type, "image/png");
+                            assert_eq!(data, "AQID");
+                        }
+                        ImageSource::Url { .. } => panic!("expected base64 image source"),
+                    }
+                }
+
+                match self.mode {
+                    VisionMode::Success => Ok(ProviderResponse::text_only(
+                        "A small test image with three bytes.".to_string(),
+                    )),
+                    VisionMode::EmptyText => Ok(ProviderResponse {
+                        text: "   ".to_string(),
+                        input_tokens: None,
+                        output_tokens: None,
+                        model: None,
+                        content_blocks: vec![],
+                        stop_reason: None,
+                    }),
+                    VisionMode::Error => Err(anyhow!("vision provider failed")),
+                }
+            })
         }
     }
 
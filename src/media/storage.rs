@@ -1,8 +1,11 @@
 use super::types::{MediaConfig, MediaFile, MediaType};
 use anyhow::{Context, Result};
+use sha2::{Digest, Sha256};
 use sqlx::SqlitePool;
 use std::path::{Path, PathBuf};
 
+type MediaRow = (String, String, String, Option<String>, i64, String, String);
+
 pub struct MediaStore {
     storage_dir: PathBuf,
     pool: SqlitePool,
@@ -11,10 +14,10 @@ pub struct MediaStore {
 
 impl MediaStore {
     pub async fn new(config: &MediaConfig, workspace_dir: &Path) -> Result<Self> {
-        let storage_dir = config
-            .storage_dir
-            .as_deref()
-            .map_or_else(|| workspace_dir.join("media"), PathBuf::from);
+        let storage_dir = config.storage_dir.as_deref().map_or_else(
+            || workspace_dir.join("media"),
+            |dir| workspace_dir.join(dir),
+        );
 
         tokio::fs::create_dir_all(&storage_dir).await?;
 
@@ -46,6 +49,13 @@ impl MediaStore {
         })
     }
 
+    /// Largest file [`MediaStore::store`] accepts, in bytes.
+    pub fn max_file_size(&self) -> u64 {
+        self.max_file_size
+    }
+
+    /// Store `data` under its SHA-256 digest. Storing identical bytes again
+    /// returns the existing record instead of writing a second copy.
     pub async fn store(&self, data: &[u8], filename: Option<&str>) -> Result<MediaFile> {
         let size = data.len() as u64;
         if size > self.max_file_size {
@@ -56,7 +66,14 @@ impl MediaStore {
             );
         }
 
-        let id = uuid::Uuid::new_v4().to_string();
+        let id = content_id(data);
+        if let Some(existing) = self.find(&id).await? {
+            if !Path::new(&existing.storage_path).exists() {
+                tokio::fs::write(&existing.storage_path, data).await?;
+            }
+            return Ok(existing);
+        }
+
         let (mime_type, media_type) = super::detection::detect_media_type(data, filename);
 
         let ext = extension_from_mime(&mime_type);
@@ -98,19 +115,32 @@ impl MediaStore {
     }
 
     pub async fn retrieve(&self, id: &str) -> Result<(MediaFile, Vec<u8>)> {
-        let row: (String, String, String, Option<String>, i64, String, String) = sqlx::query_as(
+        let media_file = self
+            .find(id)
+            .await?
+            .with_context(|| format!("media file {id} not found"))?;
+
+        let data = tokio::fs::read(&media_file.storage_path).await?;
+        Ok((media_file, data))
+    }
+
+    async fn find(&self, id: &str) -> Result<Option<MediaFile>> {
+        let row: Option<MediaRow> = sqlx::query_as(
             "SELECT id, mime_type, media_type, filename, size_bytes, storage_path, created_at
              FROM media_files
              WHERE id = ?1",
         )
         .bind(id)
-        .fetch_one(&self.pool)
+        .fetch_optional(&self.pool)
         .await
         .context("retrieve media file")?;
 
+        let Some(row) = row else {
+            return Ok(None);
+        };
         let size_bytes = u64::try_from(row.4).context("stored size_bytes is negative")?;
 
-        let media_file = MediaFile {
+        Ok(Some(MediaFile {
             id: row.0,
             mime_type: row.1,
             media_type: MediaType::from_kind(&row.2),
@@ -118,10 +148,7 @@ impl MediaStore {
             size_bytes,
             storage_path: row.5,
             created_at: row.6,
-        };
-
-        let data = tokio::fs::read(&media_file.storage_path).await?;
-        Ok((media_file, data))
+        }))
     }
 
     pub async fn delete(&self, id: &str) -> Result<()> {
@@ -152,6 +179,10 @@ impl MediaStore {
     }
 }
 
+fn content_id(data: &[u8]) -> String {
+    hex::encode(Sha256::digest(data))
+}
+
 fn extension_from_mime(mime_type: &str) -> &'static str {
     match mime_type {
         "image/jpeg" => "jpg",
@@ -218,6 +249,29 @@ mod tests {
         assert!(Path::new(&stored.storage_path).exists());
     }
 
+    #[tokio::test]
+    async fn store_is_content_addressed() {
+        let temp_dir = TempDir::new().unwrap();
+        let store = MediaStore::new(&MediaConfig::default(), temp_dir.path())
+            .await
+            .unwrap();
+
+        let first = store.store(b"same bytes", Some("a.txt")).await.unwrap();
+        let second = store.store(b"same bytes", Some("b.txt")).await.unwrap();
+        let other = store.store(b"other bytes", Some("a.txt")).await.unwrap();
+
+        assert_eq!(first.id.len(), 64);
+        assert_eq!(first.id, second.id);
+        assert_eq!(first.storage_path, second.storage_path);
+        assert_eq!(second.filename.as_deref(), Some("a.txt"));
+        assert_ne!(first.id, other.id);
+        assert!(
+            first
+                .storage_path
+                .starts_with(&*temp_dir.path().to_string_lossy())
+        );
+    }
+
     #[tokio::test]
     async fn retrieve_errors_for_nonexistent_id() {
         let temp_dir = TempDir::new().unwrap();
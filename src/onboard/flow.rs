@@ -87,7 +87,14 @@ async fn run_wizard_cli(install_daemon_flag: bool) -> Result<(Config, bool)> {
         identity: crate::config::IdentityConfig::default(),
         tools: crate::config::ToolsConfig::default(),
         mcp: crate::config::McpConfig::default(),
+        skills: crate::config::SkillsConfig::default(),
+        planner: crate::config::PlannerConfig::default(),
         taste: crate::config::TasteConfig::default(),
+        session: crate::session::SessionConfig::default(),
+        process: crate::config::ProcessConfig::default(),
+        context: crate::config::ContextConfig::default(),
+        routing: crate::config::RoutingConfig::default(),
+        usage: crate::config::UsageConfig::default(),
         locale: String::from("en"),
     };
 
@@ -161,6 +168,7 @@ fn offer_launch_channels(config: &Config) -> Result<bool> {
         || config.channels_config.slack.is_some()
         || config.channels_config.imessage.is_some()
         || config.channels_config.matrix.is_some()
+        || config.channels_config.mattermost.is_some()
         || config.channels_config.email.is_some();
 
     if has_channels && config.api_key.is_some() {
@@ -289,7 +297,14 @@ pub fn run_quick_setup(
         identity: crate::config::IdentityConfig::default(),
         tools: crate::config::ToolsConfig::default(),
         mcp: crate::config::McpConfig::default(),
+        skills: crate::config::SkillsConfig::default(),
+        planner: crate::config::PlannerConfig::default(),
         taste: crate::config::TasteConfig::default(),
+        session: crate::session::SessionConfig::default(),
+        process: crate::config::ProcessConfig::default(),
+        context: crate::config::ContextConfig::default(),
+        routing: crate::config::RoutingConfig::default(),
+        usage: crate::config::UsageConfig::default(),
         locale: String::from("en"),
     };
 
@@ -1,7 +1,7 @@
 use crate::config::schema::{IrcConfig, WhatsAppConfig};
 use crate::config::{
-    ChannelsConfig, DiscordConfig, IMessageConfig, MatrixConfig, SlackConfig, TelegramConfig,
-    WebhookConfig,
+    ChannelsConfig, DiscordConfig, IMessageConfig, MatrixAutoJoin, MatrixConfig, MattermostConfig,
+    SlackConfig, TelegramConfig, WebhookConfig,
 };
 use anyhow::Result;
 use dialoguer::{Confirm, Input, Select};
@@ -27,6 +27,7 @@ pub async fn setup_channels() -> Result<ChannelsConfig> {
         whatsapp: None,
         email: None,
         irc: None,
+        mattermost: None,
     };
 
     loop {
@@ -73,6 +74,14 @@ pub async fn setup_channels() -> Result<ChannelsConfig> {
                     "— self-hosted chat".into()
                 }
             ),
+            format!(
+                "Mattermost {}",
+                if config.mattermost.is_some() {
+                    format!("✓ {connected}")
+                } else {
+                    "— self-hosted team chat".into()
+                }
+            ),
             format!(
                 "WhatsApp   {}",
                 if config.whatsapp.is_some() {
@@ -103,7 +112,7 @@ pub async fn setup_channels() -> Result<ChannelsConfig> {
         let choice = Select::new()
             .with_prompt(format!("  {}", t!("onboard.channels.select_prompt")))
             .items(&options)
-            .default(8)
+            .default(9)
             .interact()?;
 
         match choice {
@@ -112,9 +121,10 @@ pub async fn setup_channels() -> Result<ChannelsConfig> {
             2 => setup_slack(&mut config).await?,
             3 => setup_imessage(&mut config)?,
             4 => setup_matrix(&mut config).await?,
-            5 => setup_whatsapp(&mut config).await?,
-            6 => setup_irc(&mut config)?,
-            7 => setup_webhook(&mut config)?,
+            5 => setup_mattermost(&mut config).await?,
+            6 => setup_whatsapp(&mut config).await?,
+            7 => setup_irc(&mut config)?,
+            8 => setup_webhook(&mut config)?,
             _ => break,
         }
         println!();
@@ -203,6 +213,8 @@ async fn setup_telegram(config: &mut ChannelsConfig) -> Result<()> {
     config.telegram = Some(TelegramConfig {
         bot_token: token,
         allowed_users,
+        webhook_url: None,
+        webhook_secret: None,
         autonomy_level: None,
         tool_allowlist: None,
     });
@@ -387,6 +399,7 @@ async fn setup_slack(config: &mut ChannelsConfig) -> Result<()> {
         } else {
             Some(app_token)
         },
+        signing_secret: None,
         channel_id: if channel.is_empty() {
             None
         } else {
@@ -529,6 +542,107 @@ async fn setup_matrix(config: &mut ChannelsConfig) -> Result<()> {
         } else {
             allowed_users
         },
+        auto_join: MatrixAutoJoin::default(),
+        e2e: true,
+        store_passphrase: None,
+        autonomy_level: None,
+        tool_allowlist: None,
+    });
+
+    Ok(())
+}
+
+async fn setup_mattermost(config: &mut ChannelsConfig) -> Result<()> {
+    println!();
+    println!(
+        "  {} {}",
+        ui::header(t!("onboard.channels.mattermost_setup")),
+        ui::dim(format!("— {}", t!("onboard.channels.mattermost_subtitle")))
+    );
+    print_bullet(&t!("onboard.channels.mattermost_desc"));
+    print_bullet(&t!("onboard.channels.mattermost_token_hint"));
+    println!();
+
+    let url: String = Input::new()
+        .with_prompt(format!(
+            "  {}",
+            t!("onboard.channels.mattermost_url_prompt")
+        ))
+        .interact_text()?;
+
+    if url.trim().is_empty() {
+        println!("  {} {}", ui::dim("→"), t!("onboard.channels.skipped"));
+        return Ok(());
+    }
+
+    let bot_token: String = Input::new()
+        .with_prompt(format!(
+            "  {}",
+            t!("onboard.channels.mattermost_token_prompt")
+        ))
+        .interact_text()?;
+
+    if bot_token.trim().is_empty() {
+        println!(
+            "  {} {}",
+            ui::dim("→"),
+            t!("onboard.channels.mattermost_token_required")
+        );
+        return Ok(());
+    }
+
+    let url = url.trim().trim_end_matches('/').to_string();
+    print!("  › {}... ", t!("onboard.channels.testing"));
+    let client = reqwest::Client::new();
+    match client
+        .get(format!("{url}/api/v4/users/me"))
+        .bearer_auth(&bot_token)
+        .send()
+        .await
+    {
+        Ok(resp) if resp.status().is_success() => {
+            let data: serde_json::Value = resp.json().await.unwrap_or_default();
+            let username = data
+                .get("username")
+                .and_then(serde_json::Value::as_str)
+                .unwrap_or("unknown");
+            println!(
+                "\r  ✓ {}        ",
+                t!("onboard.channels.test_success", name = username)
+            );
+        }
+        _ => {
+            println!("\r  ✗ {}", t!("onboard.channels.mattermost_test_fail"));
+            return Ok(());
+        }
+    }
+
+    let channel_id: String = Input::new()
+        .with_prompt(format!(
+            "  {}",
+            t!("onboard.channels.mattermost_channel_prompt")
+        ))
+        .allow_empty(true)
+        .interact_text()?;
+
+    let users_str: String = Input::new()
+        .with_prompt(format!(
+            "  {}",
+            t!("onboard.channels.mattermost_users_prompt")
+        ))
+        .allow_empty(true)
+        .interact_text()?;
+
+    let allowed_users = parse_allowlist(&users_str);
+    if allowed_users.is_empty() {
+        println!("  ! {}", t!("onboard.channels.mattermost_no_users"));
+    }
+
+    config.mattermost = Some(MattermostConfig {
+        url,
+        bot_token,
+        channel_id: Some(channel_id.trim().to_string()).filter(|id| !id.is_empty()),
+        allowed_users,
         autonomy_level: None,
         tool_allowlist: None,
     });
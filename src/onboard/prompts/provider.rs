@@ -1,11 +1,38 @@
 use anyhow::Result;
 use dialoguer::{Input, Select};
+use std::time::Duration;
 
+use crate::llm::Provider;
 use crate::ui::style as ui;
 
 use super::super::domain::{provider_env_var, validate_base_url};
 use super::super::view::print_bullet;
 
+/// Models installed in the local Ollama server, or empty when it is not
+/// reachable. Runs on its own thread so it works inside or outside a runtime.
+fn installed_ollama_models() -> Vec<String> {
+    let lookup = || {
+        let Ok(runtime) = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+        else {
+            return Vec::new();
+        };
+        runtime.block_on(async {
+            let provider = crate::llm::OllamaProvider::new(None);
+            match tokio::time::timeout(Duration::from_secs(3), provider.list_models()).await {
+                Ok(Ok(models)) => models,
+                Ok(Err(error)) => {
+                    tracing::debug!(%error, "could not list Ollama models");
+                    Vec::new()
+                }
+                Err(_) => Vec::new(),
+            }
+        })
+    };
+    std::thread::spawn(lookup).join().unwrap_or_default()
+}
+
 fn prompt_api_key_for_provider(provider_name: &str) -> Result<String> {
     let key_url = match provider_name {
         "openrouter" => "https://openrouter.ai/keys",
@@ -408,6 +435,25 @@ pub fn setup_provider() -> Result<(String, String, String, Option<String>)> {
         _ => vec![("default", "Default model")],
     };
 
+    // Offer what is already pulled into a local Ollama, when it answers.
+    let installed = if provider_name == "ollama" {
+        installed_ollama_models()
+    } else {
+        Vec::new()
+    };
+    let models: Vec<(&str, &str)> = if installed.is_empty() {
+        models
+    } else {
+        print_bullet(&t!(
+            "onboard.provider.ollama_models_found",
+            count = installed.len()
+        ));
+        installed
+            .iter()
+            .map(|name| (name.as_str(), name.as_str()))
+            .collect()
+    };
+
     let model_labels: Vec<&str> = models.iter().map(|(_, label)| *label).collect();
 
     let model_idx = Select::new()
@@ -31,6 +31,7 @@ pub fn print_summary(config: &Config) {
         || config.channels_config.slack.is_some()
         || config.channels_config.imessage.is_some()
         || config.channels_config.matrix.is_some()
+        || config.channels_config.mattermost.is_some()
         || config.channels_config.email.is_some();
 
     println!();
@@ -89,6 +90,9 @@ pub fn print_summary(config: &Config) {
     if config.channels_config.matrix.is_some() {
         channels.push("Matrix");
     }
+    if config.channels_config.mattermost.is_some() {
+        channels.push("Mattermost");
+    }
     if config.channels_config.email.is_some() {
         channels.push("Email");
     }
@@ -0,0 +1,105 @@
+use crate::config::Config;
+use crate::planner::store::{PlanStore, StoredPlan};
+use crate::planner::{StepAction, resume_plan};
+use anyhow::{Result, bail};
+
+/// Plan management commands.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PlanCommand {
+    List,
+    Show { id: String },
+    Resume { id: String },
+    Cancel { id: String },
+}
+
+pub async fn handle_command(command: PlanCommand, config: &Config) -> Result<()> {
+    let store = PlanStore::open(&config.workspace_dir).await?;
+    match command {
+        PlanCommand::List => {
+            let plans = store.list(None).await?;
+            if plans.is_empty() {
+                println!("No plans recorded yet.");
+                return Ok(());
+            }
+
+            println!("Plans ({}):", plans.len());
+            for stored in plans {
+                println!(
+                    "- {} | {} | {}/{} steps | updated={}\n    {}",
+                    stored.plan.id,
+                    stored.status.as_str(),
+                    stored.completed_step_count(),
+                    stored.plan.steps.len(),
+                    stored.updated_at,
+                    stored.plan.description
+                );
+            }
+            Ok(())
+        }
+        PlanCommand::Show { id } => {
+            let Some(stored) = store.get(&id).await? else {
+                bail!("Plan '{id}' not found");
+            };
+            print_plan(&stored);
+            Ok(())
+        }
+        PlanCommand::Resume { id } => {
+            let report = resume_plan(config, &store, &id).await?;
+            println!(
+                "Plan {} {}: completed={} failed={} skipped={}",
+                report.plan_id,
+                if report.cancelled {
+                    "cancelled"
+                } else if report.success {
+                    "completed"
+                } else {
+                    "failed"
+                },
+                report.completed_steps.len(),
+                report.failed_steps.len(),
+                report.skipped_steps.len()
+            );
+            Ok(())
+        }
+        PlanCommand::Cancel { id } => {
+            if store.cancel(&id).await? {
+                println!("Cancelled plan {id}");
+                Ok(())
+            } else {
+                bail!("Plan '{id}' is not running")
+            }
+        }
+    }
+}
+
+fn print_plan(stored: &StoredPlan) {
+    println!("Plan {}", stored.plan.id);
+    println!("  Status : {}", stored.status.as_str());
+    println!("  Entity : {}", stored.entity_id);
+    println!("  Created: {}", stored.created_at);
+    println!("  Updated: {}", stored.updated_at);
+    println!("  {}", stored.plan.description);
+    println!("\nSteps:");
+    for step in &stored.plan.steps {
+        let action = match &step.action {
+            StepAction::ToolCall { tool_name, .. } => format!("tool {tool_name}"),
+            StepAction::Prompt { .. } => "prompt".to_string(),
+            StepAction::Checkpoint { label } => format!("checkpoint {label}"),
+        };
+        println!(
+            "- {} [{}] {} ({action})",
+            step.id,
+            step.status.as_str(),
+            step.description
+        );
+        if !step.depends_on.is_empty() {
+            println!("    after: {}", step.depends_on.join(", "));
+        }
+        if let Some(output) = step.output.as_deref().filter(|output| !output.is_empty()) {
+            println!("    output: {output}");
+        }
+        if let Some(error) = step.error.as_deref() {
+            println!("    error: {error}");
+        }
+    }
+}
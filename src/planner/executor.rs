@@ -1,16 +1,71 @@
+use crate::config::PlannerConfig;
+use crate::llm::Provider;
+use crate::planner::store::{PlanRunStatus, PlanStore};
 use crate::planner::{Plan, PlanStep, StepAction, StepStatus};
+use crate::security::AutonomyLevel;
+use crate::security::approval::ApprovalDenied;
 use crate::tools::ExecutionContext;
 use crate::tools::ToolRegistry;
+use crate::utils::text::truncate_with_ellipsis;
 use anyhow::Result;
+use futures_util::StreamExt;
+use futures_util::stream::FuturesUnordered;
 use std::collections::{BTreeMap, BTreeSet, VecDeque};
 use std::future::Future;
 use std::pin::Pin;
 use std::sync::Arc;
+use std::time::Duration;
+
+const PROMPT_STEP_SYSTEM_PROMPT: &str = "You are executing one step of a multi-step plan. \
+Complete the instruction using the outputs of earlier steps where relevant and reply with the result only.";
+const MAX_UPSTREAM_OUTPUT_CHARS: usize = 4_000;
 
 pub struct PlanExecutor;
 
 pub struct AgentLoopPlanInterface;
 
+/// Scheduling limits applied by [`PlanExecutor::execute_with_policy`].
+#[derive(Debug, Clone)]
+pub struct ExecutionPolicy {
+    pub max_parallelism: usize,
+    pub step_timeout: Option<Duration>,
+    pub max_retries: u32,
+    pub retry_backoff: Duration,
+    /// Cap on per-step timeout overrides from the plan.
+    pub max_step_timeout: Duration,
+    /// Cap on per-step retry overrides from the plan.
+    pub max_step_retries: u32,
+}
+
+impl Default for ExecutionPolicy {
+    fn default() -> Self {
+        Self::from_config(&PlannerConfig::default())
+    }
+}
+
+impl ExecutionPolicy {
+    #[must_use]
+    pub fn from_config(config: &PlannerConfig) -> Self {
+        let max_step_timeout = Duration::from_secs(config.max_step_timeout_secs);
+        Self {
+            max_parallelism: config.max_parallelism.max(1),
+            step_timeout: (config.step_timeout_secs > 0)
+                .then(|| Duration::from_secs(config.step_timeout_secs).min(max_step_timeout)),
+            max_retries: config.max_retries.min(config.max_step_retries),
+            retry_backoff: Duration::from_millis(config.retry_backoff_ms),
+            max_step_timeout,
+            max_step_retries: config.max_step_retries,
+        }
+    }
+}
+
+/// Output of a completed dependency, handed to the steps that depend on it.
+#[derive(Debug, Clone)]
+pub struct UpstreamOutput {
+    pub step_id: String,
+    pub output: String,
+}
+
 pub struct ToolStepRunner {
     registry: Arc<ToolRegistry>,
     ctx: ExecutionContext,
@@ -30,10 +85,21 @@ impl StepRunner for ToolStepRunner {
         Box::pin(async move {
             match &step.action {
                 StepAction::ToolCall { tool_name, args } => {
-                    let result = self
+                    let result = match self
                         .registry
                         .execute(tool_name, args.clone(), &self.ctx)
-                        .await?;
+                        .await
+                    {
+                        Ok(result) => result,
+                        Err(error) if error.downcast_ref::<ApprovalDenied>().is_some() => {
+                            return Ok(StepOutput {
+                                success: false,
+                                output: String::new(),
+                                error: Some(error.to_string()),
+                            });
+                        }
+                        Err(error) => return Err(error),
+                    };
                     Ok(StepOutput {
                         success: result.success,
                         output: result.output,
@@ -45,22 +111,128 @@ impl StepRunner for ToolStepRunner {
                     output: format!("[prompt] {text}"),
                     error: None,
                 }),
-                StepAction::Checkpoint { label } => Ok(StepOutput {
+                StepAction::Checkpoint { label } => {
+                    if self.ctx.autonomy_level == AutonomyLevel::Supervised {
+                        // Without a gate nobody can approve, so the plan stops here.
+                        let error = match &self.ctx.approval {
+                            Some(gate) => {
+                                (!gate.confirm_checkpoint(label, &self.ctx.entity_id).await)
+                                    .then(|| format!("checkpoint '{label}' was not approved"))
+                            }
+                            None => Some(format!(
+                                "checkpoint '{label}' needs approval but no approver is available"
+                            )),
+                        };
+                        if error.is_some() {
+                            return Ok(StepOutput {
+                                success: false,
+                                output: String::new(),
+                                error,
+                            });
+                        }
+                    }
+                    Ok(StepOutput {
+                        success: true,
+                        output: format!("[checkpoint] {label}"),
+                        error: None,
+                    })
+                }
+            }
+        })
+    }
+}
+
+/// Runs `Prompt` steps through a provider and delegates every other step to
+/// the wrapped runner.
+pub struct PromptStepRunner<'p, R> {
+    inner: R,
+    provider: &'p dyn Provider,
+    model: &'p str,
+    temperature: f64,
+}
+
+impl<'p, R: StepRunner> PromptStepRunner<'p, R> {
+    pub fn new(inner: R, provider: &'p dyn Provider, model: &'p str, temperature: f64) -> Self {
+        Self {
+            inner,
+            provider,
+            model,
+            temperature,
+        }
+    }
+}
+
+impl<R: StepRunner> StepRunner for PromptStepRunner<'_, R> {
+    fn run_step<'a>(
+        &'a self,
+        step: &'a PlanStep,
+    ) -> Pin<Box<dyn Future<Output = Result<StepOutput>> + Send + 'a>> {
+        self.run_step_with_inputs(step, &[])
+    }
+
+    fn run_step_with_inputs<'a>(
+        &'a self,
+        step: &'a PlanStep,
+        upstream: &'a [UpstreamOutput],
+    ) -> Pin<Box<dyn Future<Output = Result<StepOutput>> + Send + 'a>> {
+        let StepAction::Prompt { text } = &step.action else {
+            return self.inner.run_step_with_inputs(step, upstream);
+        };
+
+        Box::pin(async move {
+            let message = build_prompt_step_message(text, upstream);
+            match self
+                .provider
+                .chat_with_system(
+                    Some(PROMPT_STEP_SYSTEM_PROMPT),
+                    &message,
+                    self.model,
+                    self.temperature,
+                )
+                .await
+            {
+                Ok(output) => Ok(StepOutput {
                     success: true,
-                    output: format!("[checkpoint] {label}"),
+                    output,
                     error: None,
                 }),
+                Err(error) => Ok(StepOutput {
+                    success: false,
+                    output: String::new(),
+                    error: Some(format!("prompt step failed: {error}")),
+                }),
             }
         })
     }
 }
 
+fn build_prompt_step_message(text: &str, upstream: &[UpstreamOutput]) -> String {
+    if upstream.is_empty() {
+        return text.to_string();
+    }
+
+    let mut message = String::from(text);
+    message.push_str("\n\nOutputs from earlier steps:");
+    for input in upstream {
+        message.push_str("\n\n[");
+        message.push_str(&input.step_id);
+        message.push_str("]\n");
+        message.push_str(&truncate_with_ellipsis(
+            &input.output,
+            MAX_UPSTREAM_OUTPUT_CHARS,
+        ));
+    }
+    message
+}
+
 #[derive(Debug, Clone)]
 pub struct ExecutionReport {
     pub plan_id: String,
     pub completed_steps: Vec<String>,
     pub failed_steps: Vec<String>,
     pub skipped_steps: Vec<String>,
+    /// The plan was cancelled before every step could run.
+    pub cancelled: bool,
     pub success: bool,
 }
 
@@ -76,49 +248,144 @@ pub trait StepRunner: Send + Sync {
         &'a self,
         step: &'a PlanStep,
     ) -> Pin<Box<dyn Future<Output = Result<StepOutput>> + Send + 'a>>;
+
+    /// Run a step with the outputs of its completed dependencies.
+    fn run_step_with_inputs<'a>(
+        &'a self,
+        step: &'a PlanStep,
+        _upstream: &'a [UpstreamOutput],
+    ) -> Pin<Box<dyn Future<Output = Result<StepOutput>> + Send + 'a>> {
+        self.run_step(step)
+    }
 }
 
 impl PlanExecutor {
     pub async fn execute(plan: &mut Plan, runner: &dyn StepRunner) -> Result<ExecutionReport> {
-        let execution_order = plan.execution_order()?;
-        let step_index = plan.step_index();
+        Self::execute_with_policy(plan, runner, &ExecutionPolicy::default()).await
+    }
 
-        let mut downstream = BTreeMap::new();
-        for node in &plan.dag.nodes {
-            downstream.insert(node.id.clone(), BTreeSet::new());
-        }
-        for edge in &plan.dag.edges {
-            if let Some(children) = downstream.get_mut(&edge.from) {
-                children.insert(edge.to.clone());
+    /// Execute the plan, running up to `policy.max_parallelism` steps whose
+    /// dependencies have completed at the same time.
+    pub async fn execute_with_policy(
+        plan: &mut Plan,
+        runner: &dyn StepRunner,
+        policy: &ExecutionPolicy,
+    ) -> Result<ExecutionReport> {
+        Self::run(plan, runner, policy, None).await
+    }
+
+    /// Execute the plan while recording every step transition in `store`.
+    ///
+    /// Steps already marked completed are not run again, so a plan loaded
+    /// from the store resumes after its last completed step. Cancelling the
+    /// plan in the store stops new steps from being dispatched.
+    pub async fn execute_persisted(
+        plan: &mut Plan,
+        runner: &dyn StepRunner,
+        policy: &ExecutionPolicy,
+        store: &PlanStore,
+    ) -> Result<ExecutionReport> {
+        let report = match Self::run(plan, runner, policy, Some(store)).await {
+            Ok(report) => report,
+            Err(error) => {
+                if let Err(status_error) = store.set_status(&plan.id, PlanRunStatus::Failed).await {
+                    tracing::warn!(plan_id = %plan.id, "failed to persist plan status: {status_error}");
+                }
+                return Err(error);
+            }
+        };
+        if !report.cancelled {
+            let status = if report.success {
+                PlanRunStatus::Completed
+            } else {
+                PlanRunStatus::Failed
+            };
+            if let Err(error) = store.set_status(&plan.id, status).await {
+                tracing::warn!(plan_id = %plan.id, "failed to persist plan status: {error}");
             }
         }
+        Ok(report)
+    }
+
+    async fn run(
+        plan: &mut Plan,
+        runner: &dyn StepRunner,
+        policy: &ExecutionPolicy,
+        store: Option<&PlanStore>,
+    ) -> Result<ExecutionReport> {
+        let execution_order = plan.execution_order()?;
+        let step_index = plan.step_index();
+        let position = execution_order
+            .iter()
+            .enumerate()
+            .map(|(position, id)| (id.clone(), position))
+            .collect::<BTreeMap<_, _>>();
+
+        let (downstream, upstream) = dependency_maps(plan);
+        let mut completed_steps = reset_unfinished_steps(plan);
+
+        let (mut pending_deps, mut ready) =
+            initial_schedule(&execution_order, &upstream, &completed_steps);
 
-        let mut completed_steps = Vec::new();
         let mut failed_steps = Vec::new();
         let mut skipped_steps = Vec::new();
         let mut skipped_ids = BTreeSet::new();
+        let mut in_flight = FuturesUnordered::new();
+        let mut cancelled = false;
+        let max_parallelism = policy.max_parallelism.max(1);
+
+        loop {
+            if !ready.is_empty() && is_cancelled(store, &plan.id).await {
+                cancelled = true;
+                ready.clear();
+            }
 
-        for step_id in execution_order {
-            if skipped_ids.contains(&step_id) {
-                if let Some(index) = step_index.get(&step_id) {
-                    plan.steps[*index].status = StepStatus::Skipped;
-                    skipped_steps.push(step_id);
-                }
-                continue;
+            while in_flight.len() < max_parallelism && !ready.is_empty() {
+                let step_id = ready.remove(0);
+                let Some(index) = step_index.get(&step_id).copied() else {
+                    continue;
+                };
+
+                plan.steps[index].status = StepStatus::Running;
+                persist_step(store, &plan.id, &plan.steps[index]).await;
+                let step_snapshot = plan.steps[index].clone();
+                let inputs = upstream_outputs(plan, &step_index, upstream.get(&step_id));
+                in_flight.push(async move {
+                    let result =
+                        run_step_with_policy(runner, &step_snapshot, &inputs, policy).await;
+                    (index, step_snapshot.id, result)
+                });
             }
 
-            let Some(index) = step_index.get(&step_id).copied() else {
-                continue;
+            let Some((index, step_id, result)) = in_flight.next().await else {
+                break;
+            };
+            let result = match result {
+                Ok(result) => result,
+                Err(error) => {
+                    // Leave no step behind as running for a resume to trip on.
+                    plan.steps[index].status = StepStatus::Failed;
+                    plan.steps[index].error = Some(format!("{error:#}"));
+                    persist_step(store, &plan.id, &plan.steps[index]).await;
+                    return Err(error);
+                }
             };
-
-            plan.steps[index].status = StepStatus::Running;
-            let step_snapshot = plan.steps[index].clone();
-            let result = runner.run_step(&step_snapshot).await?;
 
             if result.success {
                 plan.steps[index].status = StepStatus::Completed;
                 plan.steps[index].output = Some(result.output);
                 plan.steps[index].error = None;
+                persist_step(store, &plan.id, &plan.steps[index]).await;
+                for child in downstream.get(&step_id).into_iter().flatten() {
+                    let Some(remaining) = pending_deps.get_mut(child) else {
+                        continue;
+                    };
+                    *remaining = remaining.saturating_sub(1);
+                    if *remaining == 0 && !skipped_ids.contains(child) {
+                        ready.push(child.clone());
+                    }
+                }
+                ready.sort_by_key(|id| position.get(id).copied());
                 completed_steps.push(step_id);
                 continue;
             }
@@ -126,22 +393,186 @@ impl PlanExecutor {
             plan.steps[index].status = StepStatus::Failed;
             plan.steps[index].output = Some(result.output);
             plan.steps[index].error = result.error;
-            mark_downstream_skipped(&step_id, &downstream, &mut skipped_ids);
+            persist_step(store, &plan.id, &plan.steps[index]).await;
+            let mut newly_skipped = BTreeSet::new();
+            mark_downstream_skipped(&step_id, &downstream, &mut newly_skipped);
+            for skipped_id in newly_skipped {
+                if !skipped_ids.insert(skipped_id.clone()) {
+                    continue;
+                }
+                if let Some(skipped_index) = step_index.get(&skipped_id) {
+                    plan.steps[*skipped_index].status = StepStatus::Skipped;
+                    persist_step(store, &plan.id, &plan.steps[*skipped_index]).await;
+                    skipped_steps.push(skipped_id);
+                }
+            }
             failed_steps.push(step_id);
         }
 
-        let success = failed_steps.is_empty();
+        let by_position = |id: &String| position.get(id).copied();
+        completed_steps.sort_by_key(by_position);
+        failed_steps.sort_by_key(by_position);
+        skipped_steps.sort_by_key(by_position);
+        let success = failed_steps.is_empty() && !cancelled;
 
         Ok(ExecutionReport {
             plan_id: plan.id.clone(),
             completed_steps,
             failed_steps,
             skipped_steps,
+            cancelled,
             success,
         })
     }
 }
 
+/// Keep completed steps from an earlier run and reset everything else to
+/// pending. Returns the ids of the completed steps.
+fn reset_unfinished_steps(plan: &mut Plan) -> Vec<String> {
+    let mut completed = Vec::new();
+    for step in &mut plan.steps {
+        if step.status == StepStatus::Completed {
+            completed.push(step.id.clone());
+        } else if step.status != StepStatus::Pending {
+            step.status = StepStatus::Pending;
+            step.output = None;
+            step.error = None;
+        }
+    }
+    completed
+}
+
+/// Remaining dependency counts and the steps that can start immediately,
+/// treating `completed` steps as already done.
+fn initial_schedule(
+    execution_order: &[String],
+    upstream: &DependencyMap,
+    completed: &[String],
+) -> (BTreeMap<String, usize>, Vec<String>) {
+    let pending_deps = upstream
+        .iter()
+        .map(|(id, parents)| {
+            let remaining = parents
+                .iter()
+                .filter(|parent| !completed.contains(*parent))
+                .count();
+            (id.clone(), remaining)
+        })
+        .collect::<BTreeMap<_, _>>();
+    let ready = execution_order
+        .iter()
+        .filter(|id| pending_deps.get(*id).copied() == Some(0) && !completed.contains(*id))
+        .cloned()
+        .collect::<Vec<_>>();
+    (pending_deps, ready)
+}
+
+async fn is_cancelled(store: Option<&PlanStore>, plan_id: &str) -> bool {
+    let Some(store) = store else {
+        return false;
+    };
+    match store.status(plan_id).await {
+        Ok(status) => status == Some(PlanRunStatus::Cancelled),
+        Err(error) => {
+            tracing::warn!(plan_id, "failed to read plan status: {error}");
+            false
+        }
+    }
+}
+
+async fn persist_step(store: Option<&PlanStore>, plan_id: &str, step: &PlanStep) {
+    let Some(store) = store else {
+        return;
+    };
+    if let Err(error) = store.update_step(plan_id, step).await {
+        tracing::warn!(plan_id, step_id = %step.id, "failed to persist plan step: {error}");
+    }
+}
+
+type DependencyMap = BTreeMap<String, BTreeSet<String>>;
+
+/// Children and parents of every DAG node.
+fn dependency_maps(plan: &Plan) -> (DependencyMap, DependencyMap) {
+    let mut downstream = BTreeMap::new();
+    let mut upstream = BTreeMap::new();
+    for node in &plan.dag.nodes {
+        downstream.insert(node.id.clone(), BTreeSet::new());
+        upstream.insert(node.id.clone(), BTreeSet::new());
+    }
+    for edge in &plan.dag.edges {
+        if let Some(children) = downstream.get_mut(&edge.from) {
+            children.insert(edge.to.clone());
+        }
+        if let Some(parents) = upstream.get_mut(&edge.to) {
+            parents.insert(edge.from.clone());
+        }
+    }
+    (downstream, upstream)
+}
+
+fn upstream_outputs(
+    plan: &Plan,
+    step_index: &BTreeMap<String, usize>,
+    parents: Option<&BTreeSet<String>>,
+) -> Vec<UpstreamOutput> {
+    parents
+        .into_iter()
+        .flatten()
+        .filter_map(|parent| {
+            let index = step_index.get(parent)?;
+            Some(UpstreamOutput {
+                step_id: parent.clone(),
+                output: plan.steps[*index].output.clone()?,
+            })
+        })
+        .collect()
+}
+
+async fn run_step_with_policy(
+    runner: &dyn StepRunner,
+    step: &PlanStep,
+    upstream: &[UpstreamOutput],
+    policy: &ExecutionPolicy,
+) -> Result<StepOutput> {
+    let timeout = step
+        .timeout_secs
+        .map(|secs| Duration::from_secs(secs).min(policy.max_step_timeout))
+        .or(policy.step_timeout);
+    let max_retries = step.max_retries.map_or(policy.max_retries, |retries| {
+        retries.min(policy.max_step_retries)
+    });
+    let mut attempt = 0_u32;
+
+    loop {
+        let result = match timeout {
+            Some(limit) => tokio::time::timeout(limit, runner.run_step_with_inputs(step, upstream))
+                .await
+                .unwrap_or_else(|_| {
+                    Ok(StepOutput {
+                        success: false,
+                        output: String::new(),
+                        error: Some(format!("step timed out after {}s", limit.as_secs())),
+                    })
+                }),
+            None => runner.run_step_with_inputs(step, upstream).await,
+        };
+
+        let failed = result.as_ref().map_or(true, |output| !output.success);
+        if !failed || attempt >= max_retries {
+            return result;
+        }
+
+        attempt = attempt.saturating_add(1);
+        tracing::debug!(
+            step_id = %step.id,
+            attempt,
+            max_retries,
+            "retrying failed plan step"
+        );
+        tokio::time::sleep(policy.retry_backoff.saturating_mul(attempt)).await;
+    }
+}
+
 impl AgentLoopPlanInterface {
     pub async fn execute_plan(
         &self,
@@ -243,6 +674,8 @@ mod tests {
             depends_on: Vec::new(),
             output: None,
             error: None,
+            timeout_secs: None,
+            max_retries: None,
         }
     }
 
@@ -429,10 +862,35 @@ mod tests {
             .to_string();
 
         assert_eq!(error, "runner transport error");
-        assert_eq!(plan.steps[0].status, StepStatus::Running);
+        assert_eq!(plan.steps[0].status, StepStatus::Failed);
+        assert_eq!(
+            plan.steps[0].error.as_deref(),
+            Some("runner transport error")
+        );
         assert_eq!(plan.steps[1].status, StepStatus::Pending);
     }
 
+    #[tokio::test]
+    async fn executor_persists_the_failed_step_on_runner_errors() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let store = PlanStore::open(tmp.path()).await.unwrap();
+        let mut plan = make_plan(vec!["A", "B"], vec![("A", "B")]);
+        store
+            .create(&plan, "default", &stored_policy())
+            .await
+            .unwrap();
+        let runner = MockRunner::new(BTreeMap::new()).with_runner_error();
+
+        PlanExecutor::execute_persisted(&mut plan, &runner, &policy(1), &store)
+            .await
+            .unwrap_err();
+
+        let stored = store.get(&plan.id).await.unwrap().unwrap();
+        assert_eq!(stored.status, PlanRunStatus::Failed);
+        assert_eq!(stored.plan.steps[0].status, StepStatus::Failed);
+        assert_eq!(stored.plan.steps[1].status, StepStatus::Pending);
+    }
+
     use crate::security::SecurityPolicy;
     use crate::tools::ExecutionContext;
     use crate::tools::ToolRegistry;
@@ -498,6 +956,8 @@ mod tests {
             depends_on: Vec::new(),
             output: None,
             error: None,
+            timeout_secs: None,
+            max_retries: None,
         };
 
         let out = runner.run_step(&step).await.unwrap();
@@ -521,6 +981,8 @@ mod tests {
             depends_on: Vec::new(),
             output: None,
             error: None,
+            timeout_secs: None,
+            max_retries: None,
         };
 
         let out = runner.run_step(&step).await.unwrap();
@@ -530,8 +992,9 @@ mod tests {
 
     #[tokio::test]
     async fn tool_step_runner_handles_checkpoint_action() {
-        let registry = ToolRegistry::new(vec![]);
-        let runner = ToolStepRunner::new(Arc::new(registry), test_ctx());
+        let mut ctx = test_ctx();
+        ctx.autonomy_level = AutonomyLevel::Full;
+        let runner = ToolStepRunner::new(Arc::new(ToolRegistry::new(vec![])), ctx);
 
         let step = PlanStep {
             id: "c1".to_string(),
@@ -543,11 +1006,63 @@ mod tests {
             depends_on: Vec::new(),
             output: None,
             error: None,
+            timeout_secs: None,
+            max_retries: None,
         };
 
         let out = runner.run_step(&step).await.unwrap();
         assert!(out.success);
         assert_eq!(out.output, "[checkpoint] pre-deploy");
+
+        let mut ctx = test_ctx();
+        ctx.autonomy_level = AutonomyLevel::Supervised;
+        let runner = ToolStepRunner::new(Arc::new(ToolRegistry::new(vec![])), ctx);
+        let out = runner.run_step(&step).await.unwrap();
+        assert!(!out.success);
+        assert!(out.error.unwrap().contains("no approver"));
+    }
+
+    struct DenyBroker;
+
+    impl crate::security::ApprovalBroker for DenyBroker {
+        fn request_approval<'a>(
+            &'a self,
+            _request: &'a crate::security::ApprovalRequest,
+        ) -> Pin<Box<dyn Future<Output = Result<crate::security::ApprovalDecision>> + Send + 'a>>
+        {
+            Box::pin(async { Ok(crate::security::ApprovalDecision::Deny) })
+        }
+    }
+
+    #[tokio::test]
+    async fn tool_step_runner_fails_checkpoint_when_denied() {
+        let mut ctx = test_ctx();
+        ctx.autonomy_level = AutonomyLevel::Supervised;
+        ctx.approval = Some(Arc::new(crate::security::ApprovalGate::new(Arc::new(
+            DenyBroker,
+        ))));
+        let runner = ToolStepRunner::new(Arc::new(ToolRegistry::new(vec![])), ctx);
+
+        let step = PlanStep {
+            id: "c1".to_string(),
+            description: "pre-deploy gate".to_string(),
+            action: StepAction::Checkpoint {
+                label: "pre-deploy".to_string(),
+            },
+            status: StepStatus::Pending,
+            depends_on: Vec::new(),
+            output: None,
+            error: None,
+            timeout_secs: None,
+            max_retries: None,
+        };
+
+        let out = runner.run_step(&step).await.unwrap();
+        assert!(!out.success);
+        assert_eq!(
+            out.error.as_deref(),
+            Some("checkpoint 'pre-deploy' was not approved")
+        );
     }
 
     #[tokio::test]
@@ -566,6 +1081,8 @@ mod tests {
             depends_on: Vec::new(),
             output: None,
             error: None,
+            timeout_secs: None,
+            max_retries: None,
         };
 
         let out = runner.run_step(&step).await.unwrap();
@@ -577,7 +1094,9 @@ mod tests {
     async fn tool_step_runner_integrates_with_plan_executor() {
         let mut registry = ToolRegistry::new(vec![]);
         registry.register(Box::new(EchoTool));
-        let runner = ToolStepRunner::new(Arc::new(registry), test_ctx());
+        let mut ctx = test_ctx();
+        ctx.autonomy_level = AutonomyLevel::Full;
+        let runner = ToolStepRunner::new(Arc::new(registry), ctx);
 
         let dag = DagContract::new(
             vec![DagNode::new("A"), DagNode::new("B"), DagNode::new("C")],
@@ -596,6 +1115,8 @@ mod tests {
                 depends_on: Vec::new(),
                 output: None,
                 error: None,
+                timeout_secs: None,
+                max_retries: None,
             },
             PlanStep {
                 id: "B".to_string(),
@@ -607,6 +1128,8 @@ mod tests {
                 depends_on: vec!["A".to_string()],
                 output: None,
                 error: None,
+                timeout_secs: None,
+                max_retries: None,
             },
             PlanStep {
                 id: "C".to_string(),
@@ -618,6 +1141,8 @@ mod tests {
                 depends_on: vec!["B".to_string()],
                 output: None,
                 error: None,
+                timeout_secs: None,
+                max_retries: None,
             },
         ];
 
@@ -630,4 +1155,344 @@ mod tests {
         assert_eq!(plan.steps[1].output.as_deref(), Some("[checkpoint] mid"));
         assert_eq!(plan.steps[2].output.as_deref(), Some("[prompt] done?"));
     }
+
+    struct ConcurrencyProbe {
+        active: Mutex<usize>,
+        peak: Mutex<usize>,
+        failures_left: Mutex<u32>,
+        delay: Duration,
+    }
+
+    impl ConcurrencyProbe {
+        fn new(delay: Duration, failures: u32) -> Self {
+            Self {
+                active: Mutex::new(0),
+                peak: Mutex::new(0),
+                failures_left: Mutex::new(failures),
+                delay,
+            }
+        }
+    }
+
+    impl StepRunner for ConcurrencyProbe {
+        fn run_step<'a>(
+            &'a self,
+            step: &'a PlanStep,
+        ) -> Pin<Box<dyn Future<Output = Result<StepOutput>> + Send + 'a>> {
+            Box::pin(async move {
+                {
+                    let mut active = self.active.lock().unwrap();
+                    *active += 1;
+                    let mut peak = self.peak.lock().unwrap();
+                    *peak = (*peak).max(*active);
+                }
+                tokio::time::sleep(self.delay).await;
+                *self.active.lock().unwrap() -= 1;
+
+                let mut failures_left = self.failures_left.lock().unwrap();
+                if *failures_left > 0 {
+                    *failures_left -= 1;
+                    return Ok(StepOutput {
+                        success: false,
+                        output: String::new(),
+                        error: Some("flaky".to_string()),
+                    });
+                }
+                Ok(StepOutput {
+                    success: true,
+                    output: format!("ok:{}", step.id),
+                    error: None,
+                })
+            })
+        }
+    }
+
+    fn policy(max_parallelism: usize) -> ExecutionPolicy {
+        ExecutionPolicy {
+            max_parallelism,
+            step_timeout: None,
+            max_retries: 0,
+            retry_backoff: Duration::ZERO,
+            max_step_timeout: Duration::from_hours(1),
+            max_step_retries: 5,
+        }
+    }
+
+    fn stored_policy() -> crate::planner::PlanPolicyContext {
+        crate::planner::PlanPolicyContext::from_execution_context(&ExecutionContext::test_default(
+            Arc::new(SecurityPolicy::default()),
+        ))
+    }
+
+    #[tokio::test]
+    async fn executor_runs_independent_steps_concurrently_up_to_cap() {
+        let mut plan = make_plan(
+            vec!["A", "B", "C", "D"],
+            vec![("A", "D"), ("B", "D"), ("C", "D")],
+        );
+        let runner = ConcurrencyProbe::new(Duration::from_millis(30), 0);
+
+        let report = PlanExecutor::execute_with_policy(&mut plan, &runner, &policy(2))
+            .await
+            .unwrap();
+
+        assert!(report.success);
+        assert_eq!(*runner.peak.lock().unwrap(), 2);
+        assert_eq!(report.completed_steps, vec!["A", "B", "C", "D"]);
+    }
+
+    #[tokio::test]
+    async fn executor_with_parallelism_one_runs_sequentially() {
+        let mut plan = make_plan(vec!["A", "B", "C"], Vec::new());
+        let runner = ConcurrencyProbe::new(Duration::from_millis(5), 0);
+
+        let report = PlanExecutor::execute_with_policy(&mut plan, &runner, &policy(1))
+            .await
+            .unwrap();
+
+        assert!(report.success);
+        assert_eq!(*runner.peak.lock().unwrap(), 1);
+    }
+
+    #[tokio::test]
+    async fn executor_retries_failed_step_before_skipping_dependents() {
+        let mut plan = make_plan(vec!["A", "B"], vec![("A", "B")]);
+        let runner = ConcurrencyProbe::new(Duration::ZERO, 2);
+        let mut retrying = policy(1);
+        retrying.max_retries = 2;
+
+        let report = PlanExecutor::execute_with_policy(&mut plan, &runner, &retrying)
+            .await
+            .unwrap();
+
+        assert!(report.success);
+        assert_eq!(plan.steps[0].output.as_deref(), Some("ok:A"));
+    }
+
+    #[tokio::test]
+    async fn executor_step_retry_override_takes_precedence() {
+        let mut plan = make_plan(vec!["A", "B"], vec![("A", "B")]);
+        plan.steps[0].max_retries = Some(0);
+        let runner = ConcurrencyProbe::new(Duration::ZERO, 1);
+        let mut retrying = policy(1);
+        retrying.max_retries = 3;
+
+        let report = PlanExecutor::execute_with_policy(&mut plan, &runner, &retrying)
+            .await
+            .unwrap();
+
+        assert_eq!(report.failed_steps, vec!["A"]);
+        assert_eq!(report.skipped_steps, vec!["B"]);
+    }
+
+    #[tokio::test]
+    async fn executor_clamps_step_retry_override_to_the_configured_limit() {
+        let mut plan = make_plan(vec!["A"], Vec::new());
+        plan.steps[0].max_retries = Some(u32::MAX);
+        let runner = ConcurrencyProbe::new(Duration::ZERO, 3);
+        let mut capped = policy(1);
+        capped.max_step_retries = 2;
+
+        let report = PlanExecutor::execute_with_policy(&mut plan, &runner, &capped)
+            .await
+            .unwrap();
+
+        assert_eq!(report.failed_steps, vec!["A"]);
+    }
+
+    #[tokio::test]
+    async fn executor_clamps_step_timeout_override_to_the_configured_limit() {
+        let mut plan = make_plan(vec!["A"], Vec::new());
+        plan.steps[0].timeout_secs = Some(u64::MAX);
+        let runner = ConcurrencyProbe::new(Duration::from_secs(5), 0);
+        let mut capped = policy(1);
+        capped.max_step_timeout = Duration::from_millis(20);
+
+        let report = PlanExecutor::execute_with_policy(&mut plan, &runner, &capped)
+            .await
+            .unwrap();
+
+        assert_eq!(report.failed_steps, vec!["A"]);
+        assert!(
+            plan.steps[0]
+                .error
+                .as_deref()
+                .unwrap()
+                .contains("timed out")
+        );
+    }
+
+    #[test]
+    fn execution_policy_clamps_configured_defaults_to_the_limits() {
+        let config = PlannerConfig {
+            step_timeout_secs: 7_200,
+            max_retries: 9,
+            max_step_timeout_secs: 600,
+            max_step_retries: 3,
+            ..PlannerConfig::default()
+        };
+
+        let policy = ExecutionPolicy::from_config(&config);
+
+        assert_eq!(policy.step_timeout, Some(Duration::from_mins(10)));
+        assert_eq!(policy.max_retries, 3);
+    }
+
+    #[tokio::test]
+    async fn executor_fails_step_that_exceeds_timeout() {
+        let mut plan = make_plan(vec!["A", "B"], vec![("A", "B")]);
+        let runner = ConcurrencyProbe::new(Duration::from_secs(5), 0);
+        let mut timed = policy(1);
+        timed.step_timeout = Some(Duration::from_millis(20));
+
+        let report = PlanExecutor::execute_with_policy(&mut plan, &runner, &timed)
+            .await
+            .unwrap();
+
+        assert_eq!(report.failed_steps, vec!["A"]);
+        assert_eq!(plan.steps[0].status, StepStatus::Failed);
+        assert!(
+            plan.steps[0]
+                .error
+                .as_deref()
+                .unwrap()
+                .contains("timed out")
+        );
+        assert_eq!(plan.steps[1].status, StepStatus::Skipped);
+    }
+
+    struct RecordingProvider {
+        messages: Mutex<Vec<String>>,
+    }
+
+    impl Provider for RecordingProvider {
+        fn name(&self) -> &str {
+            "recording"
+        }
+
+        fn chat_with_system<'a>(
+            &'a self,
+            _system_prompt: Option<&'a str>,
+            message: &'a str,
+            _model: &'a str,
+            _temperature: f64,
+        ) -> Pin<Box<dyn Future<Output = anyhow::Result<String>> + Send + 'a>> {
+            Box::pin(async move {
+                self.messages.lock().unwrap().push(message.to_string());
+                Ok("summary".to_string())
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn prompt_step_runner_calls_provider_with_upstream_outputs() {
+        let mut registry = ToolRegistry::new(vec![]);
+        registry.register(Box::new(EchoTool));
+        let provider = RecordingProvider {
+            messages: Mutex::new(Vec::new()),
+        };
+        let runner = PromptStepRunner::new(
+            ToolStepRunner::new(Arc::new(registry), test_ctx()),
+            &provider,
+            "test-model",
+            0.2,
+        );
+
+        let dag = DagContract::new(
+            vec![DagNode::new("fetch"), DagNode::new("summarize")],
+            vec![DagEdge::new("fetch", "summarize")],
+        );
+        let mut steps = vec![step("fetch"), step("summarize")];
+        steps[0].action = StepAction::ToolCall {
+            tool_name: "echo".to_string(),
+            args: json!({"msg": "raw data"}),
+        };
+        steps[1].action = StepAction::Prompt {
+            text: "Summarize the data".to_string(),
+        };
+        let mut plan = Plan::new("prompt-plan", "prompt", steps, dag).unwrap();
+
+        let report = PlanExecutor::execute(&mut plan, &runner).await.unwrap();
+
+        assert!(report.success);
+        assert_eq!(plan.steps[1].output.as_deref(), Some("summary"));
+        let messages = provider.messages.lock().unwrap();
+        assert_eq!(messages.len(), 1);
+        assert!(messages[0].starts_with("Summarize the data"));
+        assert!(messages[0].contains("[fetch]\nraw data"));
+    }
+
+    #[tokio::test]
+    async fn executor_persisted_resumes_after_last_completed_step() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let store = PlanStore::open(tmp.path()).await.unwrap();
+        let mut plan = make_plan(vec!["A", "B", "C"], vec![("A", "B"), ("B", "C")]);
+        store
+            .create(&plan, "default", &stored_policy())
+            .await
+            .unwrap();
+
+        plan.steps[0].status = StepStatus::Completed;
+        plan.steps[0].output = Some("ok:A".to_string());
+        store.update_step(&plan.id, &plan.steps[0]).await.unwrap();
+        plan.steps[1].status = StepStatus::Running;
+        store.update_step(&plan.id, &plan.steps[1]).await.unwrap();
+
+        let mut resumed = store.get(&plan.id).await.unwrap().unwrap().plan;
+        let runner = MockRunner::new(BTreeMap::new());
+        let report = PlanExecutor::execute_persisted(&mut resumed, &runner, &policy(1), &store)
+            .await
+            .unwrap();
+
+        assert!(report.success);
+        assert_eq!(runner.called_ids(), vec!["B", "C"]);
+        assert_eq!(report.completed_steps, vec!["A", "B", "C"]);
+        let stored = store.get(&plan.id).await.unwrap().unwrap();
+        assert_eq!(stored.status, PlanRunStatus::Completed);
+        assert_eq!(stored.completed_step_count(), 3);
+        assert_eq!(stored.plan.steps[2].output.as_deref(), Some("ok:C"));
+    }
+
+    struct CancellingRunner<'s> {
+        store: &'s PlanStore,
+    }
+
+    impl StepRunner for CancellingRunner<'_> {
+        fn run_step<'a>(
+            &'a self,
+            step: &'a PlanStep,
+        ) -> Pin<Box<dyn Future<Output = Result<StepOutput>> + Send + 'a>> {
+            Box::pin(async move {
+                self.store.cancel("plan-1").await?;
+                Ok(StepOutput {
+                    success: true,
+                    output: format!("ok:{}", step.id),
+                    error: None,
+                })
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn executor_persisted_stops_dispatching_after_cancel() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let store = PlanStore::open(tmp.path()).await.unwrap();
+        let mut plan = make_plan(vec!["A", "B"], vec![("A", "B")]);
+        store
+            .create(&plan, "default", &stored_policy())
+            .await
+            .unwrap();
+
+        let runner = CancellingRunner { store: &store };
+        let report = PlanExecutor::execute_persisted(&mut plan, &runner, &policy(1), &store)
+            .await
+            .unwrap();
+
+        assert!(report.cancelled);
+        assert!(!report.success);
+        assert_eq!(report.completed_steps, vec!["A"]);
+        let stored = store.get("plan-1").await.unwrap().unwrap();
+        assert_eq!(stored.status, PlanRunStatus::Cancelled);
+        assert_eq!(stored.plan.steps[1].status, StepStatus::Pending);
+    }
 }
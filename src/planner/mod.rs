@@ -1,11 +1,19 @@
+mod commands;
 mod dag_contract;
 mod executor;
 mod parser;
+mod resume;
+mod store;
 mod types;
 
+pub use commands::{PlanCommand, handle_command};
 pub use dag_contract::{DagContract, DagEdge, DagNode};
 pub use executor::{
-    AgentLoopPlanInterface, ExecutionReport, PlanExecutor, StepOutput, StepRunner, ToolStepRunner,
+    AgentLoopPlanInterface, ExecutionPolicy, ExecutionReport, PlanExecutor, PromptStepRunner,
+    StepOutput, StepRunner, ToolStepRunner, UpstreamOutput,
 };
 pub use parser::PlanParser;
+pub(crate) use resume::plan_prompt_provider;
+pub use resume::{resume_interrupted_plans, resume_plan, run_claimed_plan};
+pub use store::{PlanPolicyContext, PlanRunStatus, PlanStore, StoredPlan, set_process_owner};
 pub use types::{Plan, PlanStep, StepAction, StepStatus};
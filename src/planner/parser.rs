@@ -18,6 +18,10 @@ struct RawStep {
     action: StepAction,
     #[serde(default)]
     depends_on: Vec<String>,
+    #[serde(default)]
+    timeout_secs: Option<u64>,
+    #[serde(default)]
+    max_retries: Option<u32>,
 }
 
 impl PlanParser {
@@ -41,6 +45,8 @@ impl PlanParser {
             "- Prompt: { \"kind\": \"prompt\", \"text\": \"<instruction>\" }\n",
             "- Checkpoint: { \"kind\": \"checkpoint\", \"label\": \"<label>\" }\n\n",
             "Steps with no dependencies use \"depends_on\": [].\n",
+            "Independent steps run in parallel; a prompt step receives the outputs of the steps it depends on.\n",
+            "Optional per-step fields: \"timeout_secs\" and \"max_retries\".\n",
             "Wrap the JSON in a ```json code fence.",
         )
     }
@@ -63,6 +69,8 @@ impl PlanParser {
                 depends_on: rs.depends_on.clone(),
                 output: None,
                 error: None,
+                timeout_secs: rs.timeout_secs,
+                max_retries: rs.max_retries,
             })
             .collect();
 
@@ -334,6 +342,26 @@ mod tests {
         assert!(plan.steps[0].depends_on.is_empty());
     }
 
+    #[test]
+    fn parse_reads_optional_step_policy_fields() {
+        let input = json!({
+            "id": "policy",
+            "description": "per-step policy",
+            "steps": [{
+                "id": "slow",
+                "description": "slow step",
+                "action": { "kind": "prompt", "text": "summarize" },
+                "timeout_secs": 30,
+                "max_retries": 2
+            }]
+        })
+        .to_string();
+
+        let plan = PlanParser::parse(&input).unwrap();
+        assert_eq!(plan.steps[0].timeout_secs, Some(30));
+        assert_eq!(plan.steps[0].max_retries, Some(2));
+    }
+
     #[test]
     fn parse_and_extract_roundtrip() {
         let raw_json = valid_plan_json();
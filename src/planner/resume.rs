@@ -0,0 +1,247 @@
+use crate::config::{Config, DEFAULT_MODEL};
+use crate::llm::Provider;
+use crate::memory::factory::create_memory;
+use crate::planner::store::{PlanRunStatus, PlanStore};
+use crate::planner::{
+    ExecutionPolicy, ExecutionReport, PlanExecutor, PromptStepRunner, StepRunner, ToolStepRunner,
+};
+use crate::plugins::skills::load_skill_tools;
+use crate::security::SecurityPolicy;
+use crate::tools::middleware::default_middleware_chain;
+use crate::tools::{self, ExecutionContext, ToolRegistry};
+use anyhow::{Result, bail};
+use std::sync::Arc;
+
+/// Continue a failed or interrupted plan from its last completed step.
+///
+/// The plan is claimed atomically first, so two resumes of the same plan
+/// cannot run it concurrently.
+pub async fn resume_plan(
+    config: &Config,
+    store: &PlanStore,
+    plan_id: &str,
+) -> Result<ExecutionReport> {
+    if !store.claim(plan_id).await? {
+        match store.status(plan_id).await? {
+            Some(status) => bail!(
+                "plan {plan_id} is {}; only failed or interrupted plans can be resumed",
+                status.as_str()
+            ),
+            None => bail!("plan not found: {plan_id}"),
+        }
+    }
+    run_claimed_plan(config, store, plan_id).await
+}
+
+/// Run a plan already claimed with [`PlanStore::claim`]. A run that errors
+/// out is marked failed so it can be claimed again.
+pub async fn run_claimed_plan(
+    config: &Config,
+    store: &PlanStore,
+    plan_id: &str,
+) -> Result<ExecutionReport> {
+    let result = execute_claimed(config, store, plan_id).await;
+    if result.is_err()
+        && let Err(error) = store.set_status(plan_id, PlanRunStatus::Failed).await
+    {
+        tracing::warn!(plan_id, "failed to persist plan status: {error}");
+    }
+    result
+}
+
+async fn execute_claimed(
+    config: &Config,
+    store: &PlanStore,
+    plan_id: &str,
+) -> Result<ExecutionReport> {
+    let Some(stored) = store.get(plan_id).await? else {
+        bail!("plan not found: {plan_id}");
+    };
+
+    let memory = create_memory(&config.memory, &config.workspace_dir, None).await?;
+    let mut registry = ToolRegistry::new(default_middleware_chain());
+    for tool in tools::all_tools(Arc::from(memory)) {
+        registry.register(tool);
+    }
+    for tool in load_skill_tools(&config.workspace_dir, &config.skills) {
+        registry.register(tool);
+    }
+
+    let security = Arc::new(SecurityPolicy::from_config(
+        &config.autonomy,
+        &config.workspace_dir,
+    ));
+    let mut ctx = ExecutionContext::from_security(security);
+    ctx.entity_id = stored.entity_id.clone();
+    // Older plans have no recorded policy and run under the configured one.
+    if let Some(policy) = &stored.policy {
+        policy.apply_to(&mut ctx);
+    }
+
+    let tool_runner = ToolStepRunner::new(Arc::new(registry), ctx);
+    let provider = plan_prompt_provider(config);
+    let model = config
+        .default_model
+        .clone()
+        .unwrap_or_else(|| DEFAULT_MODEL.into());
+    let runner: Box<dyn StepRunner + '_> = match provider.as_deref() {
+        Some(provider) => Box::new(PromptStepRunner::new(
+            tool_runner,
+            provider,
+            &model,
+            config.default_temperature,
+        )),
+        None => Box::new(tool_runner),
+    };
+
+    let mut plan = stored.plan;
+    let policy = ExecutionPolicy::from_config(&config.planner);
+    PlanExecutor::execute_persisted(&mut plan, runner.as_ref(), &policy, store).await
+}
+
+/// Resume every plan this process's owner was still running when it last
+/// stopped. Returns the number of plans that were resumed.
+pub async fn resume_interrupted_plans(config: &Config) -> Result<usize> {
+    let store = PlanStore::open(&config.workspace_dir).await?;
+    store.mark_interrupted().await?;
+    let interrupted = store.list(Some(PlanRunStatus::Interrupted)).await?;
+
+    let mut resumed = 0;
+    for stored in interrupted {
+        let plan_id = stored.plan.id;
+        match resume_plan(config, &store, &plan_id).await {
+            Ok(report) => {
+                resumed += 1;
+                tracing::info!(
+                    plan_id = %plan_id,
+                    success = report.success,
+                    completed = report.completed_steps.len(),
+                    failed = report.failed_steps.len(),
+                    "resumed interrupted plan"
+                );
+            }
+            Err(error) => {
+                tracing::warn!(plan_id = %plan_id, "failed to resume plan: {error}");
+            }
+        }
+    }
+    Ok(resumed)
+}
+
+/// Provider used for prompt steps. Plans still run without one, but prompt
+/// steps then only echo their instruction.
+pub(crate) fn plan_prompt_provider(config: &Config) -> Option<Box<dyn Provider>> {
+    let provider_name = config.default_provider.as_deref().unwrap_or("openrouter");
+    let api_key = config.api_key.clone();
+    if provider_name != "ollama"
+        && crate::llm::factory::resolve_api_key(provider_name, api_key.as_deref()).is_none()
+    {
+        tracing::debug!(
+            provider = provider_name,
+            "no API key for plan prompt steps; prompts will not call a model"
+        );
+        return None;
+    }
+    match crate::llm::factory::create_resilient_provider_with_oauth_recovery(
+        config,
+        provider_name,
+        &config.reliability,
+        move |name| crate::llm::factory::resolve_api_key(name, api_key.as_deref()),
+    ) {
+        Ok(provider) => Some(provider),
+        Err(error) => {
+            tracing::warn!(%error, "no provider for plan prompt steps; prompts will not call a model");
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::planner::{PlanParser, PlanPolicyContext};
+    use tempfile::TempDir;
+
+    fn default_policy() -> PlanPolicyContext {
+        PlanPolicyContext::from_execution_context(&ExecutionContext::from_security(Arc::new(
+            SecurityPolicy::default(),
+        )))
+    }
+
+    #[tokio::test]
+    async fn a_plan_is_resumed_only_once() {
+        let tmp = TempDir::new().unwrap();
+        let config = Config {
+            workspace_dir: tmp.path().to_path_buf(),
+            ..Config::default()
+        };
+        let store = PlanStore::open(tmp.path()).await.unwrap();
+        let plan = PlanParser::parse(
+            r#"{"id":"p1","description":"twice","steps":[
+                {"id":"a","description":"a","action":{"kind":"prompt","text":"a"}}]}"#,
+        )
+        .unwrap();
+        store
+            .create(&plan, "default", &default_policy())
+            .await
+            .unwrap();
+
+        let running = resume_plan(&config, &store, "p1").await.unwrap_err();
+        assert!(running.to_string().contains("is running"), "{running}");
+
+        store.set_status("p1", PlanRunStatus::Failed).await.unwrap();
+        let (first, second) = tokio::join!(
+            resume_plan(&config, &store, "p1"),
+            resume_plan(&config, &store, "p1")
+        );
+        assert_eq!(usize::from(first.is_ok()) + usize::from(second.is_ok()), 1);
+        assert_eq!(
+            store.status("p1").await.unwrap(),
+            Some(PlanRunStatus::Completed)
+        );
+        assert!(resume_plan(&config, &store, "p1").await.is_err());
+        assert!(
+            resume_plan(&config, &store, "missing")
+                .await
+                .unwrap_err()
+                .to_string()
+                .contains("not found")
+        );
+    }
+
+    #[tokio::test]
+    async fn resume_restores_the_policy_the_plan_was_started_under() {
+        let tmp = TempDir::new().unwrap();
+        let config = Config {
+            workspace_dir: tmp.path().to_path_buf(),
+            ..Config::default()
+        };
+        let store = PlanStore::open(tmp.path()).await.unwrap();
+        let plan = PlanParser::parse(
+            r#"{"id":"p1","description":"read","steps":[{"id":"a","description":"read notes",
+                "action":{"kind":"tool_call","tool_name":"file_read","args":{"path":"notes.txt"}}}]}"#,
+        )
+        .unwrap();
+        std::fs::write(tmp.path().join("notes.txt"), "hello").unwrap();
+        let mut ctx = ExecutionContext::from_security(Arc::new(SecurityPolicy::from_config(
+            &config.autonomy,
+            &config.workspace_dir,
+        )));
+        ctx.allowed_tools = Some(["memory_recall".to_string()].into());
+        store
+            .create(
+                &plan,
+                "person:discord.alice",
+                &PlanPolicyContext::from_execution_context(&ctx),
+            )
+            .await
+            .unwrap();
+        store.mark_interrupted().await.unwrap();
+
+        let report = resume_plan(&config, &store, "p1").await.unwrap();
+        assert_eq!(report.failed_steps, vec!["a"]);
+        let stored = store.get("p1").await.unwrap().unwrap();
+        let error = stored.plan.steps[0].error.as_deref().unwrap();
+        assert!(error.contains("not allowed"), "{error}");
+    }
+}
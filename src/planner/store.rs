@@ -0,0 +1,704 @@
+use crate::planner::{DagContract, Plan, PlanStep, StepAction, StepStatus};
+use crate::security::AutonomyLevel;
+use crate::security::policy::TenantPolicyContext;
+use crate::tools::ExecutionContext;
+use anyhow::{Context, Result};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use sqlx::Row;
+use sqlx::sqlite::{SqlitePool, SqlitePoolOptions, SqliteRow};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+static PROCESS_OWNER: OnceLock<String> = OnceLock::new();
+
+/// Name this process as the owner of the plans it runs. Stores opened
+/// afterwards record the name, and [`PlanStore::mark_interrupted`] only
+/// touches plans with the same owner. Only the first call takes effect.
+pub fn set_process_owner(owner: &str) {
+    let _ = PROCESS_OWNER.set(owner.to_string());
+}
+
+/// Owner recorded by stores in this process: the name given to
+/// [`set_process_owner`], or the process id when none was set.
+fn process_owner() -> String {
+    PROCESS_OWNER
+        .get()
+        .cloned()
+        .unwrap_or_else(|| format!("pid:{}", std::process::id()))
+}
+
+/// Lifecycle of a persisted plan run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PlanRunStatus {
+    Running,
+    Completed,
+    Failed,
+    Cancelled,
+    /// Was running when the process stopped.
+    Interrupted,
+}
+
+impl PlanRunStatus {
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Running => "running",
+            Self::Completed => "completed",
+            Self::Failed => "failed",
+            Self::Cancelled => "cancelled",
+            Self::Interrupted => "interrupted",
+        }
+    }
+
+    pub fn parse(value: &str) -> Result<Self> {
+        match value {
+            "running" => Ok(Self::Running),
+            "completed" => Ok(Self::Completed),
+            "failed" => Ok(Self::Failed),
+            "cancelled" => Ok(Self::Cancelled),
+            "interrupted" => Ok(Self::Interrupted),
+            _ => anyhow::bail!("unknown plan status: {value}"),
+        }
+    }
+}
+
+/// The policy a plan was started under, so a resumed run gets the same
+/// autonomy, tool allowlist and tenant scope as the turn that created it
+/// rather than the global defaults.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PlanPolicyContext {
+    pub autonomy_level: AutonomyLevel,
+    pub allowed_tools: Option<Vec<String>>,
+    pub tenant_context: TenantPolicyContext,
+    pub workspace_dir: PathBuf,
+}
+
+impl PlanPolicyContext {
+    #[must_use]
+    pub fn from_execution_context(ctx: &ExecutionContext) -> Self {
+        Self {
+            autonomy_level: ctx.autonomy_level,
+            allowed_tools: ctx.allowed_tools.as_ref().map(|tools| {
+                let mut tools = tools.iter().cloned().collect::<Vec<_>>();
+                tools.sort();
+                tools
+            }),
+            tenant_context: ctx.tenant_context.clone(),
+            workspace_dir: ctx.workspace_dir.clone(),
+        }
+    }
+
+    /// Restore the stored policy onto a freshly built context.
+    pub fn apply_to(&self, ctx: &mut ExecutionContext) {
+        ctx.autonomy_level = self.autonomy_level;
+        ctx.allowed_tools = self
+            .allowed_tools
+            .as_ref()
+            .map(|tools| tools.iter().cloned().collect());
+        ctx.tenant_context.clone_from(&self.tenant_context);
+        ctx.workspace_dir.clone_from(&self.workspace_dir);
+    }
+}
+
+/// A plan together with its persisted run state.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StoredPlan {
+    pub plan: Plan,
+    pub status: PlanRunStatus,
+    pub entity_id: String,
+    /// `None` for plans persisted before policies were recorded.
+    pub policy: Option<PlanPolicyContext>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+impl StoredPlan {
+    #[must_use]
+    pub fn completed_step_count(&self) -> usize {
+        self.plan
+            .steps
+            .iter()
+            .filter(|step| step.status == StepStatus::Completed)
+            .count()
+    }
+}
+
+/// SQLite-backed plan persistence with one row per step.
+pub struct PlanStore {
+    pool: SqlitePool,
+    owner: String,
+}
+
+impl PlanStore {
+    /// Open (or create) the plan database under `<workspace>/plans/plans.db`.
+    pub async fn open(workspace_dir: &Path) -> Result<Self> {
+        let dir = workspace_dir.join("plans");
+        tokio::fs::create_dir_all(&dir)
+            .await
+            .context("create plans directory")?;
+        let url = format!("sqlite://{}?mode=rwc", dir.join("plans.db").display());
+        let pool = SqlitePoolOptions::new()
+            .max_connections(2)
+            .connect(&url)
+            .await
+            .context("open plan database")?;
+        Self::new(pool).await
+    }
+
+    /// Create a store with an existing pool and run migrations.
+    pub async fn new(pool: SqlitePool) -> Result<Self> {
+        sqlx::query("PRAGMA foreign_keys = ON;")
+            .execute(&pool)
+            .await?;
+
+        sqlx::query(
+            "CREATE TABLE IF NOT EXISTS plans (
+                 id TEXT PRIMARY KEY,
+                 description TEXT NOT NULL,
+                 status TEXT NOT NULL,
+                 entity_id TEXT NOT NULL,
+                 dag_json TEXT NOT NULL,
+                 created_at TEXT NOT NULL,
+                 updated_at TEXT NOT NULL,
+                 policy_json TEXT
+             )",
+        )
+        .execute(&pool)
+        .await
+        .context("create plans table")?;
+
+        // Plan databases created before policies and owners were recorded.
+        for column in ["policy_json", "owner"] {
+            let exists = sqlx::query("SELECT 1 FROM pragma_table_info('plans') WHERE name = ?1")
+                .bind(column)
+                .fetch_optional(&pool)
+                .await?
+                .is_some();
+            if !exists {
+                sqlx::query(&format!("ALTER TABLE plans ADD COLUMN {column} TEXT"))
+                    .execute(&pool)
+                    .await
+                    .with_context(|| format!("add plans.{column}"))?;
+            }
+        }
+
+        sqlx::query(
+            "CREATE TABLE IF NOT EXISTS plan_steps (
+                 plan_id TEXT NOT NULL REFERENCES plans(id) ON DELETE CASCADE,
+                 step_id TEXT NOT NULL,
+                 position INTEGER NOT NULL,
+                 description TEXT NOT NULL,
+                 action_json TEXT NOT NULL,
+                 depends_on_json TEXT NOT NULL,
+                 status TEXT NOT NULL,
+                 output TEXT,
+                 error TEXT,
+                 timeout_secs INTEGER,
+                 max_retries INTEGER,
+                 PRIMARY KEY (plan_id, step_id)
+             )",
+        )
+        .execute(&pool)
+        .await
+        .context("create plan_steps table")?;
+
+        sqlx::query("CREATE INDEX IF NOT EXISTS idx_plans_status ON plans(status, updated_at)")
+            .execute(&pool)
+            .await?;
+
+        Ok(Self {
+            pool,
+            owner: process_owner(),
+        })
+    }
+
+    /// Record `owner` instead of the process owner on plans this store
+    /// creates or claims.
+    #[must_use]
+    pub fn with_owner(mut self, owner: &str) -> Self {
+        self.owner = owner.to_string();
+        self
+    }
+
+    /// Persist a new plan run started under `policy`. Existing rows for the
+    /// same id are replaced.
+    pub async fn create(
+        &self,
+        plan: &Plan,
+        entity_id: &str,
+        policy: &PlanPolicyContext,
+    ) -> Result<()> {
+        let now = Utc::now().to_rfc3339();
+        let dag_json = serde_json::to_string(&plan.dag).context("serialize plan DAG")?;
+        let policy_json = serde_json::to_string(policy).context("serialize plan policy")?;
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query("DELETE FROM plans WHERE id = ?1")
+            .bind(&plan.id)
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query(
+            "INSERT INTO plans (
+                 id, description, status, entity_id, dag_json, created_at, updated_at, policy_json,
+                 owner
+             ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?6, ?7, ?8)",
+        )
+        .bind(&plan.id)
+        .bind(&plan.description)
+        .bind(PlanRunStatus::Running.as_str())
+        .bind(entity_id)
+        .bind(&dag_json)
+        .bind(&now)
+        .bind(&policy_json)
+        .bind(&self.owner)
+        .execute(&mut *tx)
+        .await
+        .context("insert plan")?;
+
+        for (position, step) in plan.steps.iter().enumerate() {
+            sqlx::query(
+                "INSERT INTO plan_steps (
+                     plan_id, step_id, position, description, action_json, depends_on_json,
+                     status, output, error, timeout_secs, max_retries
+                 ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+            )
+            .bind(&plan.id)
+            .bind(&step.id)
+            .bind(i64::try_from(position).unwrap_or(i64::MAX))
+            .bind(&step.description)
+            .bind(serde_json::to_string(&step.action).context("serialize step action")?)
+            .bind(serde_json::to_string(&step.depends_on).context("serialize step deps")?)
+            .bind(step.status.as_str())
+            .bind(&step.output)
+            .bind(&step.error)
+            .bind(step.timeout_secs.and_then(|secs| i64::try_from(secs).ok()))
+            .bind(step.max_retries.map(i64::from))
+            .execute(&mut *tx)
+            .await
+            .context("insert plan step")?;
+        }
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Record the status, output and error of a single step.
+    pub async fn update_step(&self, plan_id: &str, step: &PlanStep) -> Result<()> {
+        sqlx::query(
+            "UPDATE plan_steps SET status = ?1, output = ?2, error = ?3
+             WHERE plan_id = ?4 AND step_id = ?5",
+        )
+        .bind(step.status.as_str())
+        .bind(&step.output)
+        .bind(&step.error)
+        .bind(plan_id)
+        .bind(&step.id)
+        .execute(&self.pool)
+        .await
+        .context("update plan step")?;
+        self.touch(plan_id).await
+    }
+
+    pub async fn set_status(&self, plan_id: &str, status: PlanRunStatus) -> Result<bool> {
+        let result = sqlx::query("UPDATE plans SET status = ?1, updated_at = ?2 WHERE id = ?3")
+            .bind(status.as_str())
+            .bind(Utc::now().to_rfc3339())
+            .bind(plan_id)
+            .execute(&self.pool)
+            .await
+            .context("update plan status")?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Take a failed or interrupted plan for resuming by switching it to
+    /// running in a single statement. Returns `false` if the plan is missing
+    /// or in any other state, including already claimed by another resume.
+    pub async fn claim(&self, plan_id: &str) -> Result<bool> {
+        let result = sqlx::query(
+            "UPDATE plans SET status = ?1, updated_at = ?2, owner = ?6
+             WHERE id = ?3 AND status IN (?4, ?5)",
+        )
+        .bind(PlanRunStatus::Running.as_str())
+        .bind(Utc::now().to_rfc3339())
+        .bind(plan_id)
+        .bind(PlanRunStatus::Failed.as_str())
+        .bind(PlanRunStatus::Interrupted.as_str())
+        .bind(&self.owner)
+        .execute(&self.pool)
+        .await
+        .context("claim plan")?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Mark the running plans owned by this store's owner as interrupted.
+    /// Called at startup, when no plan from an earlier run of the same owner
+    /// can still be running. Plans other processes are running are left
+    /// alone; plans persisted before owners were recorded count as ours.
+    pub async fn mark_interrupted(&self) -> Result<u64> {
+        let result = sqlx::query(
+            "UPDATE plans SET status = ?1, updated_at = ?2
+             WHERE status = ?3 AND (owner IS NULL OR owner = ?4)",
+        )
+        .bind(PlanRunStatus::Interrupted.as_str())
+        .bind(Utc::now().to_rfc3339())
+        .bind(PlanRunStatus::Running.as_str())
+        .bind(&self.owner)
+        .execute(&self.pool)
+        .await
+        .context("mark interrupted plans")?;
+        Ok(result.rows_affected())
+    }
+
+    pub async fn status(&self, plan_id: &str) -> Result<Option<PlanRunStatus>> {
+        let row: Option<(String,)> = sqlx::query_as("SELECT status FROM plans WHERE id = ?1")
+            .bind(plan_id)
+            .fetch_optional(&self.pool)
+            .await
+            .context("load plan status")?;
+        row.map(|(status,)| PlanRunStatus::parse(&status))
+            .transpose()
+    }
+
+    /// Mark a running plan as cancelled. Returns `false` if the plan is
+    /// missing or already finished.
+    pub async fn cancel(&self, plan_id: &str) -> Result<bool> {
+        let result = sqlx::query(
+            "UPDATE plans SET status = ?1, updated_at = ?2 WHERE id = ?3 AND status = ?4",
+        )
+        .bind(PlanRunStatus::Cancelled.as_str())
+        .bind(Utc::now().to_rfc3339())
+        .bind(plan_id)
+        .bind(PlanRunStatus::Running.as_str())
+        .execute(&self.pool)
+        .await
+        .context("cancel plan")?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    pub async fn get(&self, plan_id: &str) -> Result<Option<StoredPlan>> {
+        let row = sqlx::query(
+            "SELECT id, description, status, entity_id, dag_json, created_at, updated_at,
+                    policy_json
+             FROM plans WHERE id = ?1",
+        )
+        .bind(plan_id)
+        .fetch_optional(&self.pool)
+        .await
+        .context("load plan")?;
+
+        match row {
+            Some(row) => Ok(Some(self.map_plan_row(&row).await?)),
+            None => Ok(None),
+        }
+    }
+
+    /// List plans, most recently updated first.
+    pub async fn list(&self, status: Option<PlanRunStatus>) -> Result<Vec<StoredPlan>> {
+        let rows = sqlx::query(
+            "SELECT id, description, status, entity_id, dag_json, created_at, updated_at,
+                    policy_json
+             FROM plans
+             WHERE ?1 IS NULL OR status = ?1
+             ORDER BY updated_at DESC",
+        )
+        .bind(status.map(PlanRunStatus::as_str))
+        .fetch_all(&self.pool)
+        .await
+        .context("list plans")?;
+
+        let mut plans = Vec::with_capacity(rows.len());
+        for row in &rows {
+            plans.push(self.map_plan_row(row).await?);
+        }
+        Ok(plans)
+    }
+
+    async fn touch(&self, plan_id: &str) -> Result<()> {
+        sqlx::query("UPDATE plans SET updated_at = ?1 WHERE id = ?2")
+            .bind(Utc::now().to_rfc3339())
+            .bind(plan_id)
+            .execute(&self.pool)
+            .await
+            .context("touch plan")?;
+        Ok(())
+    }
+
+    async fn map_plan_row(&self, row: &SqliteRow) -> Result<StoredPlan> {
+        let id: String = row.try_get("id")?;
+        let status: String = row.try_get("status")?;
+        let dag_json: String = row.try_get("dag_json")?;
+        let dag: DagContract = serde_json::from_str(&dag_json).context("deserialize plan DAG")?;
+        let policy_json: Option<String> = row.try_get("policy_json")?;
+        let policy = policy_json
+            .map(|json| serde_json::from_str(&json).context("deserialize plan policy"))
+            .transpose()?;
+
+        let step_rows = sqlx::query(
+            "SELECT step_id, description, action_json, depends_on_json, status, output, error,
+                    timeout_secs, max_retries
+             FROM plan_steps WHERE plan_id = ?1 ORDER BY position ASC",
+        )
+        .bind(&id)
+        .fetch_all(&self.pool)
+        .await
+        .context("load plan steps")?;
+        let steps = step_rows
+            .iter()
+            .map(map_step_row)
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(StoredPlan {
+            plan: Plan {
+                id,
+                description: row.try_get("description")?,
+                steps,
+                dag,
+            },
+            status: PlanRunStatus::parse(&status)?,
+            entity_id: row.try_get("entity_id")?,
+            policy,
+            created_at: row.try_get("created_at")?,
+            updated_at: row.try_get("updated_at")?,
+        })
+    }
+}
+
+fn map_step_row(row: &SqliteRow) -> Result<PlanStep> {
+    let action_json: String = row.try_get("action_json")?;
+    let depends_on_json: String = row.try_get("depends_on_json")?;
+    let status: String = row.try_get("status")?;
+    let timeout_secs: Option<i64> = row.try_get("timeout_secs")?;
+    let max_retries: Option<i64> = row.try_get("max_retries")?;
+
+    Ok(PlanStep {
+        id: row.try_get("step_id")?,
+        description: row.try_get("description")?,
+        action: serde_json::from_str::<StepAction>(&action_json)
+            .context("deserialize step action")?,
+        status: parse_step_status(&status)?,
+        depends_on: serde_json::from_str(&depends_on_json).context("deserialize step deps")?,
+        output: row.try_get("output")?,
+        error: row.try_get("error")?,
+        timeout_secs: timeout_secs.and_then(|secs| u64::try_from(secs).ok()),
+        max_retries: max_retries.and_then(|retries| u32::try_from(retries).ok()),
+    })
+}
+
+fn parse_step_status(value: &str) -> Result<StepStatus> {
+    match value {
+        "pending" => Ok(StepStatus::Pending),
+        "running" => Ok(StepStatus::Running),
+        "completed" => Ok(StepStatus::Completed),
+        "failed" => Ok(StepStatus::Failed),
+        "skipped" => Ok(StepStatus::Skipped),
+        _ => anyhow::bail!("unknown step status: {value}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::planner::PlanParser;
+    use crate::security::SecurityPolicy;
+    use serde_json::json;
+    use std::sync::Arc;
+    use tempfile::TempDir;
+
+    fn policy() -> PlanPolicyContext {
+        PlanPolicyContext::from_execution_context(&ExecutionContext::test_default(Arc::new(
+            SecurityPolicy::default(),
+        )))
+    }
+
+    fn sample_plan() -> Plan {
+        PlanParser::parse(
+            &json!({
+                "id": "plan-store",
+                "description": "store test",
+                "steps": [
+                    {"id": "a", "description": "first", "action": {"kind": "checkpoint", "label": "a"}},
+                    {"id": "b", "description": "second", "action": {"kind": "prompt", "text": "b"},
+                     "depends_on": ["a"], "timeout_secs": 5}
+                ]
+            })
+            .to_string(),
+        )
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn create_and_get_roundtrip() {
+        let tmp = TempDir::new().unwrap();
+        let store = PlanStore::open(tmp.path()).await.unwrap();
+        let plan = sample_plan();
+
+        store.create(&plan, "person:test", &policy()).await.unwrap();
+        let stored = store.get("plan-store").await.unwrap().unwrap();
+
+        assert_eq!(stored.status, PlanRunStatus::Running);
+        assert_eq!(stored.entity_id, "person:test");
+        assert_eq!(stored.plan.steps.len(), 2);
+        assert_eq!(stored.plan.steps[1].depends_on, vec!["a".to_string()]);
+        assert_eq!(stored.plan.steps[1].timeout_secs, Some(5));
+        assert_eq!(stored.plan.execution_order().unwrap(), vec!["a", "b"]);
+    }
+
+    #[tokio::test]
+    async fn policy_roundtrips_and_legacy_rows_have_none() {
+        let tmp = TempDir::new().unwrap();
+        let pool = SqlitePoolOptions::new()
+            .connect(&format!(
+                "sqlite://{}?mode=rwc",
+                tmp.path().join("plans.db").display()
+            ))
+            .await
+            .unwrap();
+        sqlx::query(
+            "CREATE TABLE plans (
+                 id TEXT PRIMARY KEY, description TEXT NOT NULL, status TEXT NOT NULL,
+                 entity_id TEXT NOT NULL, dag_json TEXT NOT NULL,
+                 created_at TEXT NOT NULL, updated_at TEXT NOT NULL
+             )",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        sqlx::query(
+            "INSERT INTO plans VALUES ('legacy', 'old', 'failed', 'default', ?1, 'now', 'now')",
+        )
+        .bind(serde_json::to_string(&sample_plan().dag).unwrap())
+        .execute(&pool)
+        .await
+        .unwrap();
+        let store = PlanStore::new(pool).await.unwrap();
+        assert!(store.get("legacy").await.unwrap().unwrap().policy.is_none());
+
+        let mut ctx = ExecutionContext::test_default(Arc::new(SecurityPolicy::default()));
+        ctx.autonomy_level = AutonomyLevel::ReadOnly;
+        ctx.allowed_tools = Some(["memory_recall".to_string()].into());
+        ctx.tenant_context = TenantPolicyContext::enabled("acme");
+        let policy = PlanPolicyContext::from_execution_context(&ctx);
+        store
+            .create(&sample_plan(), "person:test", &policy)
+            .await
+            .unwrap();
+        let stored = store.get("plan-store").await.unwrap().unwrap();
+        assert_eq!(stored.policy.as_ref(), Some(&policy));
+
+        let mut restored = ExecutionContext::test_default(Arc::new(SecurityPolicy::default()));
+        policy.apply_to(&mut restored);
+        assert_eq!(restored.autonomy_level, AutonomyLevel::ReadOnly);
+        assert_eq!(restored.allowed_tools, ctx.allowed_tools);
+        assert_eq!(restored.tenant_context, ctx.tenant_context);
+    }
+
+    #[tokio::test]
+    async fn update_step_persists_status_output_and_error() {
+        let tmp = TempDir::new().unwrap();
+        let store = PlanStore::open(tmp.path()).await.unwrap();
+        let mut plan = sample_plan();
+        store.create(&plan, "default", &policy()).await.unwrap();
+
+        plan.steps[0].status = StepStatus::Completed;
+        plan.steps[0].output = Some("done".to_string());
+        plan.steps[1].status = StepStatus::Failed;
+        plan.steps[1].error = Some("boom".to_string());
+        store.update_step(&plan.id, &plan.steps[0]).await.unwrap();
+        store.update_step(&plan.id, &plan.steps[1]).await.unwrap();
+
+        let stored = store.get(&plan.id).await.unwrap().unwrap();
+        assert_eq!(stored.completed_step_count(), 1);
+        assert_eq!(stored.plan.steps[0].output.as_deref(), Some("done"));
+        assert_eq!(stored.plan.steps[1].status, StepStatus::Failed);
+        assert_eq!(stored.plan.steps[1].error.as_deref(), Some("boom"));
+    }
+
+    #[tokio::test]
+    async fn cancel_only_affects_running_plans() {
+        let tmp = TempDir::new().unwrap();
+        let store = PlanStore::open(tmp.path()).await.unwrap();
+        let plan = sample_plan();
+        store.create(&plan, "default", &policy()).await.unwrap();
+
+        assert!(store.cancel(&plan.id).await.unwrap());
+        assert!(!store.cancel(&plan.id).await.unwrap());
+        assert!(!store.cancel("missing").await.unwrap());
+        assert_eq!(
+            store.status(&plan.id).await.unwrap(),
+            Some(PlanRunStatus::Cancelled)
+        );
+    }
+
+    #[tokio::test]
+    async fn claim_takes_failed_or_interrupted_plans_once() {
+        let tmp = TempDir::new().unwrap();
+        let store = PlanStore::open(tmp.path()).await.unwrap();
+        let plan = sample_plan();
+        store.create(&plan, "default", &policy()).await.unwrap();
+
+        assert!(!store.claim(&plan.id).await.unwrap());
+        assert_eq!(store.mark_interrupted().await.unwrap(), 1);
+        assert!(store.claim(&plan.id).await.unwrap());
+        assert!(!store.claim(&plan.id).await.unwrap());
+
+        store
+            .set_status(&plan.id, PlanRunStatus::Failed)
+            .await
+            .unwrap();
+        assert!(store.claim(&plan.id).await.unwrap());
+        store
+            .set_status(&plan.id, PlanRunStatus::Completed)
+            .await
+            .unwrap();
+        assert!(!store.claim(&plan.id).await.unwrap());
+        assert!(!store.claim("missing").await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn mark_interrupted_skips_plans_of_other_owners() {
+        let tmp = TempDir::new().unwrap();
+        let daemon = PlanStore::open(tmp.path())
+            .await
+            .unwrap()
+            .with_owner("daemon");
+        let other = PlanStore::open(tmp.path())
+            .await
+            .unwrap()
+            .with_owner("pid:1");
+        let mut plan = sample_plan();
+        daemon.create(&plan, "default", &policy()).await.unwrap();
+        plan.id = "plan-other".to_string();
+        other.create(&plan, "default", &policy()).await.unwrap();
+
+        assert_eq!(daemon.mark_interrupted().await.unwrap(), 1);
+        assert_eq!(
+            daemon.status("plan-store").await.unwrap(),
+            Some(PlanRunStatus::Interrupted)
+        );
+        assert_eq!(
+            daemon.status("plan-other").await.unwrap(),
+            Some(PlanRunStatus::Running)
+        );
+    }
+
+    #[tokio::test]
+    async fn list_filters_by_status() {
+        let tmp = TempDir::new().unwrap();
+        let store = PlanStore::open(tmp.path()).await.unwrap();
+        let mut plan = sample_plan();
+        store.create(&plan, "default", &policy()).await.unwrap();
+        plan.id = "plan-other".to_string();
+        store.create(&plan, "default", &policy()).await.unwrap();
+        store
+            .set_status("plan-other", PlanRunStatus::Completed)
+            .await
+            .unwrap();
+
+        assert_eq!(store.list(None).await.unwrap().len(), 2);
+        let running = store.list(Some(PlanRunStatus::Running)).await.unwrap();
+        assert_eq!(running.len(), 1);
+        assert_eq!(running[0].plan.id, "plan-store");
+    }
+}
@@ -13,6 +13,19 @@ pub enum StepStatus {
     Skipped,
 }
 
+impl StepStatus {
+    #[must_use]
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Pending => "pending",
+            Self::Running => "running",
+            Self::Completed => "completed",
+            Self::Failed => "failed",
+            Self::Skipped => "skipped",
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PlanStep {
     pub id: String,
@@ -22,6 +35,12 @@ pub struct PlanStep {
     pub depends_on: Vec<String>,
     pub output: Option<String>,
     pub error: Option<String>,
+    /// Overrides the executor timeout for this step.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout_secs: Option<u64>,
+    /// Overrides the executor retry count for this step.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_retries: Option<u32>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -127,6 +146,8 @@ mod tests {
             depends_on: Vec::new(),
             output: None,
             error: None,
+            timeout_secs: None,
+            max_retries: None,
         }
     }
 
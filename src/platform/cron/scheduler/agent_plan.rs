@@ -1,7 +1,11 @@
 use super::{ROUTE_MARKER_AGENT_BLOCKED, ROUTE_MARKER_AGENT_PLANNER};
-use crate::config::Config;
-use crate::planner::{PlanExecutor, PlanParser, ToolStepRunner};
+use crate::config::{Config, DEFAULT_MODEL};
+use crate::planner::{
+    ExecutionPolicy, ExecutionReport, Plan, PlanExecutor, PlanParser, PlanPolicyContext, PlanStore,
+    PromptStepRunner, StepRunner, ToolStepRunner, plan_prompt_provider,
+};
 use crate::platform::cron::CronJob;
+use crate::plugins::skills::load_skill_tools;
 use crate::security::SecurityPolicy;
 use crate::tools::middleware::default_middleware_chain;
 use crate::tools::{ExecutionContext, ToolRegistry, default_tools};
@@ -11,6 +15,10 @@ use sqlx::{Row, SqlitePool};
 use std::sync::Arc;
 use uuid::Uuid;
 
+/// Owner recorded on plans run by cron jobs. Cron requeues its own
+/// interrupted runs, so the daemon's plan resume leaves these alone.
+const CRON_PLAN_OWNER: &str = "cron";
+
 #[allow(clippy::too_many_lines)]
 pub(super) async fn run_agent_job_command(
     config: &Config,
@@ -42,17 +50,46 @@ pub(super) async fn run_agent_job_command(
         for tool in default_tools() {
             registry.register(tool);
         }
+        for tool in load_skill_tools(&config.workspace_dir, &config.skills) {
+            registry.register(tool);
+        }
 
-        let runner = ToolStepRunner::new(
-            Arc::new(registry),
-            ExecutionContext::from_security(security_arc),
-        );
+        let ctx = ExecutionContext::from_security(security_arc);
+        let plan_policy = PlanPolicyContext::from_execution_context(&ctx);
+        let entity_id = ctx.entity_id.clone();
+        let tool_runner = ToolStepRunner::new(Arc::new(registry), ctx);
+        let provider = plan_prompt_provider(config);
+        let model = config
+            .default_model
+            .clone()
+            .unwrap_or_else(|| DEFAULT_MODEL.into());
+        let runner: Box<dyn StepRunner + '_> = match provider.as_deref() {
+            Some(provider) => Box::new(PromptStepRunner::new(
+                tool_runner,
+                provider,
+                &model,
+                config.default_temperature,
+            )),
+            None => Box::new(tool_runner),
+        };
+        let runner = runner.as_ref();
+        let policy = ExecutionPolicy::from_config(&config.planner);
+        let store = match PlanStore::open(&config.workspace_dir).await {
+            Ok(store) => Some(store.with_owner(CRON_PLAN_OWNER)),
+            Err(error) => {
+                tracing::warn!(error = %error, "failed to open plan store; plan will not be persisted");
+                None
+            }
+        };
+        let store = store.as_ref();
         let execution_id = begin_plan_execution(config, job, &plan.id, raw_plan)
             .await
             .ok();
         let max_attempts = job.max_attempts.max(1);
         let mut attempts = 1_u32;
-        let mut final_report = match PlanExecutor::execute(&mut plan, &runner).await {
+        let first_run =
+            execute_plan(&mut plan, runner, &policy, store, &entity_id, &plan_policy).await;
+        let mut final_report = match first_run {
             Ok(report) => report,
             Err(error) => {
                 if let Some(execution_id) = execution_id.as_deref() {
@@ -86,12 +123,22 @@ pub(super) async fn run_agent_job_command(
             }
         };
 
-        while !final_report.success && attempts < max_attempts {
+        // A cancelled plan is not retried; a retry would recreate it.
+        while !final_report.success && !final_report.cancelled && attempts < max_attempts {
             attempts = attempts.saturating_add(1);
             let Ok(mut retry_plan) = PlanParser::parse(raw_plan.trim()) else {
                 break;
             };
-            let Ok(retry_report) = PlanExecutor::execute(&mut retry_plan, &runner).await else {
+            let Ok(retry_report) = execute_plan(
+                &mut retry_plan,
+                runner,
+                &policy,
+                store,
+                &entity_id,
+                &plan_policy,
+            )
+            .await
+            else {
                 break;
             };
             final_report = retry_report;
@@ -152,6 +199,29 @@ pub(super) async fn run_agent_job_command(
     )
 }
 
+/// Run one attempt of a cron plan. The attempt is recorded in the plan
+/// store, replacing any earlier attempt of the same plan, so it can be
+/// inspected and cancelled like other plans. Without a store the plan runs
+/// unpersisted.
+async fn execute_plan(
+    plan: &mut Plan,
+    runner: &dyn StepRunner,
+    policy: &ExecutionPolicy,
+    store: Option<&PlanStore>,
+    entity_id: &str,
+    plan_policy: &PlanPolicyContext,
+) -> anyhow::Result<ExecutionReport> {
+    if let Some(store) = store {
+        match store.create(plan, entity_id, plan_policy).await {
+            Ok(()) => return PlanExecutor::execute_persisted(plan, runner, policy, store).await,
+            Err(error) => {
+                tracing::warn!(error = %error, "failed to persist plan; plan will not be persisted");
+            }
+        }
+    }
+    PlanExecutor::execute_with_policy(plan, runner, policy).await
+}
+
 // ── Pool helpers ────────────────────────────────────────────────────────────
 
 async fn open_plan_pool(config: &Config) -> anyhow::Result<SqlitePool> {
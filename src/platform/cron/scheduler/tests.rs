@@ -588,7 +588,7 @@ async fn scheduler_agent_plan_route_executes_via_planner() {
     let config = test_config(&tmp);
     let security = SecurityPolicy::from_config(&config.autonomy, &config.workspace_dir);
 
-    let plan_json = r#"{"id":"agent-plan-1","description":"agent plan","steps":[{"id":"s1","description":"checkpoint","action":{"kind":"checkpoint","label":"gate"},"depends_on":[]},{"id":"s2","description":"prompt","action":{"kind":"prompt","text":"done"},"depends_on":["s1"]}]}"#;
+    let plan_json = r#"{"id":"agent-plan-1","description":"agent plan","steps":[{"id":"s1","description":"prompt","action":{"kind":"prompt","text":"start"},"depends_on":[]},{"id":"s2","description":"prompt","action":{"kind":"prompt","text":"done"},"depends_on":["s1"]}]}"#;
     let mut job = test_job(&format!("plan:{plan_json}"));
     job.job_kind = CronJobKind::Agent;
     job.origin = CronJobOrigin::Agent;
@@ -601,6 +601,22 @@ async fn scheduler_agent_plan_route_executes_via_planner() {
     assert!(output.contains("retry_limit_reached=false"));
 }
 
+#[tokio::test]
+async fn scheduler_agent_plan_checkpoints_fail_without_an_approver() {
+    let tmp = TempDir::new().unwrap();
+    let config = test_config(&tmp);
+    let security = SecurityPolicy::from_config(&config.autonomy, &config.workspace_dir);
+
+    let plan_json = r#"{"id":"agent-plan-gate","description":"gate","steps":[{"id":"s1","description":"checkpoint","action":{"kind":"checkpoint","label":"gate"},"depends_on":[]}]}"#;
+    let mut job = test_job(&format!("plan:{plan_json}"));
+    job.job_kind = CronJobKind::Agent;
+    job.origin = CronJobOrigin::Agent;
+
+    let (success, output) = run_job_command(&config, &security, &job).await;
+    assert!(!success, "{output}");
+    assert!(output.contains("success=false"), "{output}");
+}
+
 #[tokio::test]
 async fn scheduler_agent_plan_route_rejects_invalid_plan() {
     let tmp = TempDir::new().unwrap();
@@ -623,7 +639,7 @@ async fn scheduler_agent_plan_route_persists_execution_row() {
     let config = test_config(&tmp);
     let security = SecurityPolicy::from_config(&config.autonomy, &config.workspace_dir);
 
-    let plan_json = r#"{"id":"agent-plan-persist","description":"persist","steps":[{"id":"s1","description":"checkpoint","action":{"kind":"checkpoint","label":"gate"},"depends_on":[]}]}"#;
+    let plan_json = r#"{"id":"agent-plan-persist","description":"persist","steps":[{"id":"s1","description":"prompt","action":{"kind":"prompt","text":"done"},"depends_on":[]}]}"#;
     let mut job = test_job(&format!("plan:{plan_json}"));
     job.job_kind = CronJobKind::Agent;
     job.origin = CronJobOrigin::Agent;
@@ -2,6 +2,7 @@ use crate::agent::tool_loop::{ToolLoop, ToolLoopRunParams};
 use crate::config::Config;
 use crate::llm::manager::LlmManager;
 use crate::memory::factory::create_memory;
+use crate::plugins::skills::load_skill_tools;
 use crate::security::SecurityPolicy;
 use crate::tools::middleware::default_middleware_chain;
 use crate::tools::{ExecutionContext, ToolRegistry, all_tools};
@@ -41,6 +42,9 @@ pub(super) async fn run_heartbeat_worker(config: Arc<Config>) -> Result<()> {
     for tool in all_tools(Arc::clone(&memory)) {
         registry.register(tool);
     }
+    for tool in load_skill_tools(&config.workspace_dir, &config.skills) {
+        registry.register(tool);
+    }
     let registry = Arc::new(registry);
     let llm_config = Arc::new(ArcSwap::new(Arc::clone(&config)));
     let llm = Arc::new(LlmManager::new(llm_config));
@@ -61,7 +65,8 @@ pub(super) async fn run_heartbeat_worker(config: Arc<Config>) -> Result<()> {
             let temp = heartbeat_temperature(&config);
 
             let ctx = ExecutionContext::from_security(Arc::clone(&security));
-            let tool_loop = ToolLoop::new(Arc::clone(&registry), 10);
+            let tool_loop = ToolLoop::new(Arc::clone(&registry), 10)
+                .with_context_config(config.context.clone());
             let provider = match llm.get_provider() {
                 Ok(p) => p,
                 Err(e) => {
@@ -81,6 +86,8 @@ pub(super) async fn run_heartbeat_worker(config: Arc<Config>) -> Result<()> {
                 image_content: &[],
                 model,
                 temperature: temp,
+                thinking: crate::llm::ThinkingLevel::Off,
+                request_options: None,
                 ctx: &ctx,
                 stream_sink: None,
                 conversation_history: &[],
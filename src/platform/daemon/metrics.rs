@@ -0,0 +1,107 @@
+//! Standalone Prometheus listener for daemons that do not expose the gateway.
+//!
+//! The listener is unauthenticated, so it binds loopback addresses only
+//! unless `observability.metrics_allow_public` is set.
+
+use crate::config::Config;
+use crate::runtime::observability::{METRICS_CONTENT_TYPE, render_metrics};
+use crate::runtime::usage::{SqliteUsageTracker, usage_db_path};
+use anyhow::{Context, Result};
+use axum::Router;
+use axum::http::header;
+use axum::response::IntoResponse;
+use axum::routing::get;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use tokio::sync::OnceCell;
+
+/// Serve `GET /metrics` on `addr` until the listener fails.
+pub(super) async fn serve_metrics(addr: &str, config: Arc<Config>) -> Result<()> {
+    let addrs: Vec<SocketAddr> = tokio::net::lookup_host(addr)
+        .await
+        .with_context(|| format!("resolve metrics listener address {addr}"))?
+        .collect();
+    if !config.observability.metrics_allow_public && !all_loopback(&addrs) {
+        anyhow::bail!(
+            "Refusing to serve unauthenticated metrics on {addr}.\n\
+             Fix: use a loopback address such as 127.0.0.1:9464, scrape the gateway's\n\
+             authenticated /metrics, or set [observability] metrics_allow_public = true."
+        );
+    }
+    let listener = tokio::net::TcpListener::bind(addrs.as_slice())
+        .await
+        .with_context(|| format!("bind metrics listener on {addr}"))?;
+
+    // One usage database handle for every scrape, opened once it exists.
+    let tracker: Arc<OnceCell<SqliteUsageTracker>> = Arc::new(OnceCell::new());
+    let app = Router::new().route(
+        "/metrics",
+        get(move || {
+            let config = Arc::clone(&config);
+            let tracker = Arc::clone(&tracker);
+            async move {
+                let usage = usage_tracker(&config, &tracker).await;
+                let body = render_metrics(usage).await;
+                ([(header::CONTENT_TYPE, METRICS_CONTENT_TYPE)], body).into_response()
+            }
+        }),
+    );
+    axum::serve(listener, app)
+        .await
+        .context("serve metrics listener")
+}
+
+fn all_loopback(addrs: &[SocketAddr]) -> bool {
+    !addrs.is_empty() && addrs.iter().all(|addr| addr.ip().is_loopback())
+}
+
+async fn usage_tracker<'a>(
+    config: &Config,
+    tracker: &'a OnceCell<SqliteUsageTracker>,
+) -> Option<&'a SqliteUsageTracker> {
+    if let Some(tracker) = tracker.get() {
+        return Some(tracker);
+    }
+    let db_path = usage_db_path(&config.workspace_dir);
+    if !db_path.exists() {
+        return None;
+    }
+    match tracker
+        .get_or_try_init(|| SqliteUsageTracker::new(&db_path))
+        .await
+    {
+        Ok(tracker) => Some(tracker),
+        Err(error) => {
+            tracing::warn!("failed to open usage database for metrics: {error}");
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_loopback_addresses_count_as_loopback() {
+        let addr = |s: &str| s.parse::<SocketAddr>().unwrap();
+        assert!(all_loopback(&[addr("127.0.0.1:9464"), addr("[::1]:9464")]));
+        assert!(!all_loopback(&[addr("0.0.0.0:9464")]));
+        assert!(!all_loopback(&[
+            addr("127.0.0.1:9464"),
+            addr("10.0.0.5:9464")
+        ]));
+        assert!(!all_loopback(&[]));
+    }
+
+    #[tokio::test]
+    async fn public_metrics_address_is_refused_without_opt_in() {
+        let config = Arc::new(Config::default());
+        let error = serve_metrics("0.0.0.0:0", config).await.unwrap_err();
+        assert!(
+            error
+                .to_string()
+                .contains("Refusing to serve unauthenticated metrics")
+        );
+    }
+}
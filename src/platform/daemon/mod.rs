@@ -7,6 +7,7 @@ use std::sync::Arc;
 use tokio::task::JoinHandle;
 
 mod heartbeat_worker;
+mod metrics;
 mod state;
 mod supervisor;
 
@@ -22,11 +23,18 @@ pub async fn run(config: Arc<Config>, host: String, port: u16) -> Result<()> {
         .channel_max_backoff_secs
         .max(initial_backoff);
 
+    // Plans this daemon runs are resumed by the next daemon start, never by
+    // other processes sharing the workspace.
+    crate::planner::set_process_owner("daemon");
+
     if let Err(error) = initialize_persona_startup_state(&config).await {
         tracing::warn!(%error, "failed to initialize persona startup state");
     }
 
-    let mut handles: Vec<JoinHandle<()>> = vec![spawn_state_writer(Arc::clone(&config))];
+    let mut handles: Vec<JoinHandle<()>> = vec![
+        spawn_state_writer(Arc::clone(&config)),
+        spawn_plan_resume(Arc::clone(&config)),
+    ];
     handles.extend(spawn_supervised_components(
         Arc::clone(&config),
         host.clone(),
@@ -38,6 +46,9 @@ pub async fn run(config: Arc<Config>, host: String, port: u16) -> Result<()> {
 
     println!("Daemon started");
     println!("   Gateway: {host}:{port}");
+    if let Some(addr) = &config.observability.metrics_listen {
+        println!("   Metrics: {addr}/metrics");
+    }
     println!("   Press Ctrl+C to stop");
 
     tokio::signal::ctrl_c().await?;
@@ -54,6 +65,17 @@ pub async fn run(config: Arc<Config>, host: String, port: u16) -> Result<()> {
     Ok(())
 }
 
+/// Resume plans that were still running when the daemon last stopped.
+fn spawn_plan_resume(config: Arc<Config>) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        match crate::planner::resume_interrupted_plans(&config).await {
+            Ok(0) => {}
+            Ok(count) => tracing::info!(count, "resumed interrupted plans"),
+            Err(error) => tracing::warn!(%error, "failed to resume interrupted plans"),
+        }
+    })
+}
+
 async fn initialize_persona_startup_state(config: &Config) -> Result<()> {
     if !config.persona.enabled_main_session {
         return Ok(());
@@ -87,6 +109,7 @@ fn has_supervised_channels(config: &Config) -> bool {
         || config.channels_config.slack.is_some()
         || config.channels_config.imessage.is_some()
         || config.channels_config.matrix.is_some()
+        || config.channels_config.mattermost.is_some()
         || config.channels_config.whatsapp.is_some()
         || config.channels_config.email.is_some()
 }
@@ -66,7 +66,9 @@ pub(super) fn spawn_supervised_components(
         move || {
             let cfg = Arc::clone(&gateway_cfg);
             let host = host.clone();
-            async move { crate::transport::gateway::run_gateway(&host, port, cfg).await }
+            async move {
+                crate::transport::gateway::run_gateway(&host, port, cfg, supervise_channels).await
+            }
         },
     ));
 
@@ -100,6 +102,21 @@ pub(super) fn spawn_supervised_components(
         ));
     }
 
+    if let Some(addr) = config.observability.metrics_listen.clone() {
+        let metrics_cfg = Arc::clone(&config);
+        handles.push(spawn_component_supervisor(
+            "metrics",
+            initial_backoff,
+            max_backoff,
+            10,
+            move || {
+                let cfg = Arc::clone(&metrics_cfg);
+                let addr = addr.clone();
+                async move { super::metrics::serve_metrics(&addr, cfg).await }
+            },
+        ));
+    }
+
     let scheduler_cfg = config;
     handles.push(spawn_component_supervisor(
         "scheduler",
@@ -8,7 +8,7 @@ pub(super) fn all_integrations() -> Vec<IntegrationEntry> {
         // -- Chat Providers --
         IntegrationEntry {
             name: "Telegram",
-            description: "Bot API — long-polling",
+            description: "Bot API — long-polling or webhook",
             category: IntegrationCategory::Chat,
             status_fn: status::telegram,
         },
@@ -60,6 +60,12 @@ pub(super) fn all_integrations() -> Vec<IntegrationEntry> {
             category: IntegrationCategory::Chat,
             status_fn: status::matrix,
         },
+        IntegrationEntry {
+            name: "Mattermost",
+            description: "Self-hosted team chat",
+            category: IntegrationCategory::Chat,
+            status_fn: status::mattermost,
+        },
         IntegrationEntry {
             name: "Nostr",
             description: "Decentralized DMs (NIP-04)",
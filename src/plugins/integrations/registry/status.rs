@@ -52,6 +52,7 @@ channel_status!(slack, slack);
 channel_status!(webhooks, webhook);
 channel_status!(imessage, imessage);
 channel_status!(matrix, matrix);
+channel_status!(mattermost, mattermost);
 
 pub(super) fn openrouter(config: &Config) -> IntegrationStatus {
     active_when(
@@ -1,6 +1,6 @@
 use super::*;
 use crate::config::Config;
-use crate::config::{IMessageConfig, MatrixConfig, TelegramConfig};
+use crate::config::{IMessageConfig, MatrixAutoJoin, MatrixConfig, TelegramConfig};
 use crate::plugins::integrations::{IntegrationCategory, IntegrationStatus};
 
 #[test]
@@ -63,6 +63,8 @@ fn telegram_active_when_configured() {
     config.channels_config.telegram = Some(TelegramConfig {
         bot_token: "123:ABC".into(),
         allowed_users: vec!["user".into()],
+        webhook_url: None,
+        webhook_secret: None,
         autonomy_level: None,
         tool_allowlist: None,
     });
@@ -114,6 +116,9 @@ fn matrix_active_when_configured() {
         access_token: "tok".into(),
         room_id: "!r:m".into(),
         allowed_users: vec![],
+        auto_join: MatrixAutoJoin::default(),
+        e2e: true,
+        store_passphrase: None,
         autonomy_level: None,
         tool_allowlist: None,
     });
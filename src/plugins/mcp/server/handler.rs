@@ -0,0 +1,345 @@
+use crate::plugins::mcp::bridge::to_rmcp_contents;
+use crate::plugins::mcp::content::ToolContent;
+use crate::security::policy::AutonomyLevel;
+use crate::security::{ApprovalGate, GrantsOnlyBroker, PermissionStore, grant_subject};
+use crate::tools::middleware::is_read_only_tool;
+use crate::tools::{ExecutionContext, ToolRegistry, ToolResult};
+use rmcp::ErrorData as McpError;
+use rmcp::handler::server::ServerHandler;
+use rmcp::model::{
+    CallToolRequestParams, CallToolResult, Implementation, ListToolsResult, PaginatedRequestParams,
+    ServerCapabilities, ServerInfo,
+};
+use rmcp::service::{RequestContext, RoleServer};
+use std::sync::Arc;
+
+const SERVER_INSTRUCTIONS: &str = "AsteronIris tools (shell, files, memory) executed under the \
+host's security policy. Calls that need approval under supervised autonomy must be pre-granted \
+in the workspace permissions.toml.";
+
+/// MCP server handler backed by a [`ToolRegistry`].
+///
+/// Tool calls go through `ToolRegistry::execute`, so the same middleware
+/// chain (security policy, rate limits, audit, output limits, sanitization)
+/// applies as in the agent tool loop. MCP has no interactive approval
+/// channel, so under supervised autonomy any mutating tool call must match
+/// an existing [`PermissionStore`] grant.
+#[derive(Clone)]
+pub struct McpToolServer {
+    registry: Arc<ToolRegistry>,
+    ctx: ExecutionContext,
+    permissions: Arc<PermissionStore>,
+}
+
+impl McpToolServer {
+    pub fn new(
+        registry: Arc<ToolRegistry>,
+        mut ctx: ExecutionContext,
+        permissions: Arc<PermissionStore>,
+    ) -> Self {
+        // Granted calls pass the approval middleware through the same store.
+        ctx.approval.get_or_insert_with(|| {
+            Arc::new(
+                ApprovalGate::new(Arc::new(GrantsOnlyBroker))
+                    .with_permissions(Arc::clone(&permissions)),
+            )
+        });
+        Self {
+            registry,
+            ctx,
+            permissions,
+        }
+    }
+
+    /// Tools visible to MCP clients, honouring the context's allowlist.
+    pub fn tools(&self) -> Vec<rmcp::model::Tool> {
+        let mut specs = self.registry.specs_for_context(&self.ctx);
+        specs.sort_by(|a, b| a.name.cmp(&b.name));
+        specs
+            .into_iter()
+            .map(|spec| {
+                let schema = match spec.parameters {
+                    serde_json::Value::Object(object) => object,
+                    _ => serde_json::Map::new(),
+                };
+                rmcp::model::Tool::new(spec.name, spec.description, schema)
+            })
+            .collect()
+    }
+
+    /// Execute one MCP tool call through the registry pipeline.
+    pub async fn call(
+        &self,
+        tool_name: &str,
+        args: serde_json::Value,
+    ) -> Result<CallToolResult, McpError> {
+        let exposed = self.registry.get(tool_name).is_some()
+            && self
+                .ctx
+                .allowed_tools
+                .as_ref()
+                .is_none_or(|allowed| allowed.contains(tool_name));
+        if !exposed {
+            return Err(McpError::invalid_params(
+                format!("unknown tool: {tool_name}"),
+                None,
+            ));
+        }
+
+        if let Some(reason) = self.approval_block_reason(tool_name, &args) {
+            return Ok(CallToolResult::error(to_rmcp_contents(&[
+                ToolContent::Text { text: reason },
+            ])));
+        }
+
+        let result = match self.registry.execute(tool_name, args, &self.ctx).await {
+            Ok(result) => result,
+            Err(error) => ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some(error.to_string()),
+                attachments: Vec::new(),
+            },
+        };
+        Ok(call_result_from_tool_result(&result))
+    }
+
+    fn approval_block_reason(&self, tool_name: &str, args: &serde_json::Value) -> Option<String> {
+        if self.ctx.autonomy_level != AutonomyLevel::Supervised || is_read_only_tool(tool_name) {
+            return None;
+        }
+
+        let subject = grant_subject(tool_name, args);
+        if self.permissions.is_granted(tool_name, &subject) {
+            return None;
+        }
+
+        Some(format!(
+            "blocked by security policy: tool '{tool_name}' requires approval under supervised autonomy and no matching grant exists for '{subject}'"
+        ))
+    }
+}
+
+fn call_result_from_tool_result(result: &ToolResult) -> CallToolResult {
+    let mut content = Vec::new();
+    if !result.output.is_empty() {
+        content.push(ToolContent::Text {
+            text: result.output.clone(),
+        });
+    }
+    if let Some(error) = &result.error {
+        content.push(ToolContent::Text {
+            text: format!("Error: {error}"),
+        });
+    }
+    content.extend(result.attachments.iter().filter_map(|attachment| {
+        attachment
+            .path
+            .as_ref()
+            .or(attachment.url.as_ref())
+            .map(|uri| ToolContent::Resource {
+                uri: uri.clone(),
+                mime_type: Some(attachment.mime_type.clone()),
+                name: attachment.filename.clone(),
+            })
+    }));
+
+    let content = to_rmcp_contents(&content);
+    if result.success {
+        CallToolResult::success(content)
+    } else {
+        CallToolResult::error(content)
+    }
+}
+
+impl ServerHandler for McpToolServer {
+    fn get_info(&self) -> ServerInfo {
+        ServerInfo {
+            capabilities: ServerCapabilities::builder().enable_tools().build(),
+            server_info: Implementation {
+                name: "asteroniris".to_string(),
+                title: Some("AsteronIris".to_string()),
+                version: env!("CARGO_PKG_VERSION").to_string(),
+                description: None,
+                icons: None,
+                website_url: None,
+            },
+            instructions: Some(SERVER_INSTRUCTIONS.to_string()),
+            ..ServerInfo::default()
+        }
+    }
+
+    async fn list_tools(
+        &self,
+        _request: Option<PaginatedRequestParams>,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ListToolsResult, McpError> {
+        Ok(ListToolsResult::with_all_items(self.tools()))
+    }
+
+    async fn call_tool(
+        &self,
+        request: CallToolRequestParams,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<CallToolResult, McpError> {
+        let args = request.arguments.map_or(
+            serde_json::Value::Object(serde_json::Map::new()),
+            serde_json::Value::Object,
+        );
+        self.call(&request.name, args).await
+    }
+
+    fn get_tool(&self, name: &str) -> Option<rmcp::model::Tool> {
+        self.tools().into_iter().find(|tool| tool.name == name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::security::{GrantScope, PermissionGrant, SecurityPolicy};
+    use crate::tools::middleware::default_middleware_chain;
+    use crate::tools::{Tool, ToolResult};
+    use serde_json::{Value, json};
+    use std::collections::HashSet;
+    use std::future::Future;
+    use std::pin::Pin;
+    use tempfile::TempDir;
+
+    struct EchoTool(&'static str);
+
+    impl Tool for EchoTool {
+        fn name(&self) -> &str {
+            self.0
+        }
+
+        fn description(&self) -> &str {
+            "Echo arguments"
+        }
+
+        fn parameters_schema(&self) -> Value {
+            json!({"type": "object", "properties": {"text": {"type": "string"}}})
+        }
+
+        fn execute<'a>(
+            &'a self,
+            args: Value,
+            _ctx: &'a ExecutionContext,
+        ) -> Pin<Box<dyn Future<Output = anyhow::Result<ToolResult>> + Send + 'a>> {
+            Box::pin(async move {
+                Ok(ToolResult {
+                    success: true,
+                    output: args["text"].as_str().unwrap_or_default().to_string(),
+                    error: None,
+                    attachments: Vec::new(),
+                })
+            })
+        }
+    }
+
+    fn server(tmp: &TempDir, autonomy: AutonomyLevel) -> McpToolServer {
+        let mut registry = ToolRegistry::new(default_middleware_chain());
+        registry.register(Box::new(EchoTool("file_read")));
+        registry.register(Box::new(EchoTool("echo")));
+        let security = Arc::new(SecurityPolicy {
+            autonomy,
+            workspace_dir: tmp.path().to_path_buf(),
+            ..SecurityPolicy::default()
+        });
+        let mut ctx = ExecutionContext::test_default(security);
+        ctx.autonomy_level = autonomy;
+        McpToolServer::new(
+            Arc::new(registry),
+            ctx,
+            Arc::new(PermissionStore::load(tmp.path())),
+        )
+    }
+
+    fn text_of(result: &CallToolResult) -> String {
+        result
+            .content
+            .iter()
+            .filter_map(|content| content.raw.as_text().map(|text| text.text.clone()))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    #[test]
+    fn tools_are_listed_sorted_with_schema() {
+        let tmp = TempDir::new().unwrap();
+        let server = server(&tmp, AutonomyLevel::Full);
+        let tools = server.tools();
+        let names: Vec<&str> = tools.iter().map(|tool| tool.name.as_ref()).collect();
+        assert_eq!(names, vec!["echo", "file_read"]);
+        assert_eq!(tools[0].input_schema["type"], "object");
+    }
+
+    #[test]
+    fn allowlist_hides_tools() {
+        let tmp = TempDir::new().unwrap();
+        let mut server = server(&tmp, AutonomyLevel::Full);
+        server.ctx.allowed_tools = Some(HashSet::from(["file_read".to_string()]));
+        assert_eq!(server.tools().len(), 1);
+        assert!(server.get_tool("echo").is_none());
+    }
+
+    #[tokio::test]
+    async fn call_runs_tool_through_registry() {
+        let tmp = TempDir::new().unwrap();
+        let server = server(&tmp, AutonomyLevel::Full);
+        let result = server.call("echo", json!({"text": "hi"})).await.unwrap();
+        assert_eq!(result.is_error, Some(false));
+        assert!(text_of(&result).contains("hi"));
+    }
+
+    #[tokio::test]
+    async fn call_rejects_unknown_tool() {
+        let tmp = TempDir::new().unwrap();
+        let server = server(&tmp, AutonomyLevel::Full);
+        assert!(server.call("missing", json!({})).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn read_only_autonomy_is_enforced_by_middleware() {
+        let tmp = TempDir::new().unwrap();
+        let server = server(&tmp, AutonomyLevel::ReadOnly);
+        let result = server.call("echo", json!({"text": "hi"})).await.unwrap();
+        assert_eq!(result.is_error, Some(true));
+        assert!(text_of(&result).contains("read-only"));
+    }
+
+    #[tokio::test]
+    async fn supervised_mutating_call_requires_grant() {
+        let tmp = TempDir::new().unwrap();
+        let server = server(&tmp, AutonomyLevel::Supervised);
+
+        let blocked = server.call("echo", json!({"text": "hi"})).await.unwrap();
+        assert_eq!(blocked.is_error, Some(true));
+        assert!(text_of(&blocked).contains("requires approval"));
+
+        server
+            .permissions
+            .add_grant(
+                PermissionGrant {
+                    tool: "echo".to_string(),
+                    pattern: "*".to_string(),
+                    scope: GrantScope::Session,
+                },
+                "test:default",
+            )
+            .unwrap();
+        let allowed = server.call("echo", json!({"text": "hi"})).await.unwrap();
+        assert_eq!(allowed.is_error, Some(false));
+    }
+
+    #[tokio::test]
+    async fn supervised_read_only_call_needs_no_grant() {
+        let tmp = TempDir::new().unwrap();
+        let server = server(&tmp, AutonomyLevel::Supervised);
+        let result = server
+            .call("file_read", json!({"path": "notes.md", "text": "ok"}))
+            .await
+            .unwrap();
+        assert_eq!(result.is_error, Some(false));
+    }
+}
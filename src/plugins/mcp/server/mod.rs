@@ -1,3 +1,13 @@
 //! MCP server — exposes `AsteronIris` tools via MCP protocol.
 //!
-//! Implemented in Wave 4.
+//! Serves every tool in the [`ToolRegistry`](crate::tools::ToolRegistry)
+//! over stdio (`asteroniris mcp serve`) and over streamable HTTP/SSE on the
+//! gateway (`/mcp`, enabled with `[mcp.serve] gateway = true`).
+
+mod handler;
+mod transport;
+
+pub use handler::McpToolServer;
+pub use transport::{
+    McpHttpService, build_mcp_tool_server, http_service, mcp_tool_server, serve_stdio,
+};
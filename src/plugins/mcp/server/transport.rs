@@ -0,0 +1,111 @@
+use super::handler::McpToolServer;
+use crate::config::Config;
+use crate::memory::{self, Memory};
+use crate::plugins::skills::load_skill_tools;
+use crate::security::PermissionStore;
+use crate::security::policy::{EntityRateLimiter, SecurityPolicy, TenantPolicyContext};
+use crate::tools::middleware::default_middleware_chain;
+use crate::tools::{self, ExecutionContext, ToolRegistry};
+use anyhow::{Context, Result};
+use rmcp::ServiceExt;
+use rmcp::transport::streamable_http_server::session::local::LocalSessionManager;
+use rmcp::transport::{StreamableHttpServerConfig, StreamableHttpService};
+use std::sync::Arc;
+
+/// Streamable HTTP (POST + SSE) service type mounted by the gateway.
+pub type McpHttpService = StreamableHttpService<McpToolServer, LocalSessionManager>;
+
+/// Build an [`McpToolServer`] over shared runtime resources.
+///
+/// `entity_id` identifies the MCP client for rate limiting and audit logs.
+pub fn mcp_tool_server(
+    config: &Config,
+    registry: Arc<ToolRegistry>,
+    security: Arc<SecurityPolicy>,
+    rate_limiter: Arc<EntityRateLimiter>,
+    entity_id: &str,
+) -> McpToolServer {
+    let ctx = ExecutionContext {
+        autonomy_level: security.autonomy,
+        workspace_dir: security.workspace_dir.clone(),
+        security,
+        entity_id: entity_id.to_string(),
+        turn_number: 0,
+        allowed_tools: config.mcp.serve.allowed_tool_set(),
+        rate_limiter,
+        tenant_context: TenantPolicyContext::disabled(),
+        approval: None,
+    };
+    let permissions = Arc::new(PermissionStore::load(&config.workspace_dir));
+    permissions.set_entity_allowlist(entity_id, ctx.allowed_tools.clone());
+    McpToolServer::new(registry, ctx, permissions)
+}
+
+/// Build an [`McpToolServer`] with its own memory backend and tool registry.
+pub async fn build_mcp_tool_server(config: &Config, entity_id: &str) -> Result<McpToolServer> {
+    let mem: Arc<dyn Memory> = Arc::from(
+        memory::factory::create_memory(
+            &config.memory,
+            &config.workspace_dir,
+            config.api_key.as_deref(),
+        )
+        .await
+        .context("create memory backend for MCP server")?,
+    );
+
+    let mut registry = ToolRegistry::new(default_middleware_chain());
+    for tool in tools::all_tools(mem) {
+        registry.register(tool);
+    }
+    for tool in load_skill_tools(&config.workspace_dir, &config.skills) {
+        registry.register(tool);
+    }
+
+    let security = Arc::new(SecurityPolicy::from_config(
+        &config.autonomy,
+        &config.workspace_dir,
+    ));
+    let rate_limiter = Arc::new(EntityRateLimiter::new(
+        config.autonomy.max_actions_per_hour,
+        config.autonomy.max_actions_per_entity_per_hour,
+    ));
+
+    Ok(mcp_tool_server(
+        config,
+        Arc::new(registry),
+        security,
+        rate_limiter,
+        entity_id,
+    ))
+}
+
+/// Serve the tool registry over stdio until the client disconnects.
+///
+/// Stdout carries the MCP protocol, so logging must go to stderr.
+pub async fn serve_stdio(config: &Config) -> Result<()> {
+    let server = build_mcp_tool_server(config, "mcp:stdio").await?;
+    tracing::info!(
+        tools = server.tools().len(),
+        "MCP server listening on stdio"
+    );
+
+    let running = server
+        .serve(rmcp::transport::stdio())
+        .await
+        .context("start MCP stdio server")?;
+    running
+        .waiting()
+        .await
+        .context("MCP stdio server task failed")?;
+    Ok(())
+}
+
+/// Wrap a tool server in the streamable HTTP transport (JSON-RPC over POST,
+/// server events over SSE) with per-client sessions.
+pub fn http_service(server: McpToolServer) -> McpHttpService {
+    StreamableHttpService::new(
+        move || Ok(server.clone()),
+        Arc::new(LocalSessionManager::default()),
+        StreamableHttpServerConfig::default(),
+    )
+}
@@ -342,7 +342,9 @@ pub fn skills_to_prompt(skills: &[Skill]) -> String {
                 let _ = writeln!(
                     prompt,
                     "- **{}**: {} ({})",
-                    tool.name, tool.description, tool.kind
+                    super::skill_tool_name(&skill.name, &tool.name),
+                    tool.description,
+                    tool.kind
                 );
             }
         }
@@ -385,9 +387,14 @@ pub fn init_skills_dir(workspace_dir: &Path) -> Result<()> {
              [[tools]]\n\
              name = \"my_tool\"\n\
              description = \"What this tool does\"\n\
-             kind = \"shell\"\n\
-             command = \"echo hello\"\n\
+             kind = \"shell\"  # shell | http | script\n\
+             command = \"echo hello {{name}}\"\n\
+             args = { name = \"Who to greet\" }\n\
              ```\n\n\
+             Each tool is registered as `skill_<skill>_<tool>`. `{{arg}}` placeholders are\n\
+             filled from the call arguments (shell-quoted for shell/script, URL-encoded for\n\
+             http). Shell and script tools obey `autonomy.allowed_commands`; disable a skill\n\
+             with `asteroniris skills disable <name>`.\n\n\
              ## SKILL.md format (simpler)\n\n\
              Just write a markdown file with instructions for the agent.\n\
              The agent will read it and follow the instructions.\n\n\
@@ -1,8 +1,12 @@
 pub mod loader;
+pub mod tool;
 pub mod types;
 
 #[allow(unused_imports)]
 pub use loader::{init_skills_dir, load_skills, skills_dir, skills_to_prompt};
+pub use tool::{
+    SkillToolAdapter, SkillToolKind, enabled_skills, load_skill_tools, skill_tool_name, skill_tools,
+};
 pub use types::{Skill, SkillTool};
 
 #[cfg(test)]
@@ -0,0 +1,579 @@
+use super::{Skill, SkillTool};
+use crate::config::SkillsConfig;
+use crate::security::url_validation::resolve_public_addrs;
+use crate::tools::{ExecutionContext, ShellTool, Tool, ToolResult};
+use anyhow::{Result, bail};
+use serde_json::{Value, json};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write as _;
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::time::Duration;
+
+/// Maximum HTTP skill request time.
+const HTTP_TIMEOUT_SECS: u64 = 30;
+/// Maximum HTTP response body returned to the model (1 MB).
+const MAX_HTTP_BODY_BYTES: usize = 1_048_576;
+
+/// Execution backend declared by a skill tool's `kind`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SkillToolKind {
+    /// `command` is a shell command line run in the workspace.
+    Shell,
+    /// `command` is a URL fetched with GET.
+    Http,
+    /// `command` starts with a script path relative to the skill directory.
+    Script,
+}
+
+impl SkillToolKind {
+    pub fn parse(kind: &str) -> Option<Self> {
+        match kind.trim().to_ascii_lowercase().as_str() {
+            "shell" => Some(Self::Shell),
+            "http" => Some(Self::Http),
+            "script" => Some(Self::Script),
+            _ => None,
+        }
+    }
+}
+
+/// Registry name for a skill tool: `skill_<skill>_<tool>`.
+///
+/// Namespacing keeps skills from shadowing built-in tools such as `shell`.
+pub fn skill_tool_name(skill_name: &str, tool_name: &str) -> String {
+    format!(
+        "skill_{}_{}",
+        sanitize_name_part(skill_name),
+        sanitize_name_part(tool_name)
+    )
+}
+
+fn sanitize_name_part(part: &str) -> String {
+    part.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+/// A `[[tools]]` entry from a SKILL.toml manifest exposed as a registry tool.
+///
+/// Arguments are substituted into `{{name}}` placeholders of the command or
+/// URL. Shell and script tools run through [`ShellTool`], so they are subject
+/// to `SecurityPolicy::is_command_allowed` exactly like the built-in shell
+/// tool; a script's file name must therefore be in `allowed_commands`. HTTP
+/// tools are refused for private/internal hosts.
+pub struct SkillToolAdapter {
+    name: String,
+    description: String,
+    kind: SkillToolKind,
+    command: String,
+    args: BTreeMap<String, String>,
+    skill_dir: Option<PathBuf>,
+}
+
+impl SkillToolAdapter {
+    pub fn new(skill: &Skill, tool: &SkillTool) -> Result<Self> {
+        let Some(kind) = SkillToolKind::parse(&tool.kind) else {
+            bail!("unsupported skill tool kind '{}'", tool.kind);
+        };
+        if tool.command.trim().is_empty() {
+            bail!("skill tool '{}' has an empty command", tool.name);
+        }
+
+        let skill_dir = skill
+            .location
+            .as_deref()
+            .and_then(Path::parent)
+            .map(Path::to_path_buf);
+        if kind == SkillToolKind::Script && skill_dir.is_none() {
+            bail!("script tool '{}' has no skill directory", tool.name);
+        }
+
+        Ok(Self {
+            name: skill_tool_name(&skill.name, &tool.name),
+            description: format!("{} (skill: {})", tool.description, skill.name),
+            kind,
+            command: tool.command.clone(),
+            args: tool
+                .args
+                .iter()
+                .map(|(name, description)| (name.clone(), description.clone()))
+                .collect(),
+            skill_dir,
+        })
+    }
+
+    pub fn kind(&self) -> SkillToolKind {
+        self.kind
+    }
+
+    fn render(&self, template: &str, args: &Value, encode: fn(&str) -> String) -> Result<String> {
+        let values = self.argument_values(args)?;
+        render_template(template, &values, encode)
+    }
+
+    fn argument_values(&self, args: &Value) -> Result<HashMap<String, String>> {
+        let mut values = HashMap::new();
+        for name in self.args.keys() {
+            let value = match args.get(name) {
+                Some(Value::String(s)) => s.clone(),
+                Some(Value::Number(n)) => n.to_string(),
+                Some(Value::Bool(b)) => b.to_string(),
+                Some(Value::Null) | None => bail!("Missing '{name}' parameter"),
+                Some(_) => bail!("Parameter '{name}' must be a string, number, or boolean"),
+            };
+            values.insert(name.clone(), value);
+        }
+        Ok(values)
+    }
+
+    fn script_command(&self, args: &Value) -> Result<String> {
+        let template = self.command.trim();
+        let (script, rest) = template
+            .split_once(char::is_whitespace)
+            .unwrap_or((template, ""));
+        if script.contains("{{") {
+            bail!("script path must not contain placeholders");
+        }
+
+        let Some(skill_dir) = &self.skill_dir else {
+            bail!("script tool has no skill directory");
+        };
+        let skill_dir = skill_dir.canonicalize()?;
+        let script_path = skill_dir.join(script).canonicalize()?;
+        if !script_path.starts_with(&skill_dir) || !script_path.is_file() {
+            bail!("script must be a file inside the skill directory: {script}");
+        }
+        let script_path = script_path.to_string_lossy().into_owned();
+        if script_path != shell_quote(&script_path) {
+            bail!("script path contains characters that require quoting: {script_path}");
+        }
+
+        let rendered = self.render(rest, args, shell_quote)?;
+        Ok(format!("{script_path} {rendered}").trim_end().to_string())
+    }
+
+    async fn fetch(&self, args: &Value) -> Result<ToolResult> {
+        let url = self.render(&self.command, args, url_encode)?;
+        let parsed = url::Url::parse(&url)?;
+        if !matches!(parsed.scheme(), "http" | "https") {
+            bail!("only http and https URLs are supported");
+        }
+        // Connect only to the addresses that passed the check, so the name
+        // cannot be re-resolved to an internal address in between.
+        let (host, addrs) = resolve_public_addrs(&parsed).await?;
+
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(HTTP_TIMEOUT_SECS))
+            .redirect(reqwest::redirect::Policy::none())
+            .no_proxy()
+            .resolve_to_addrs(&host, &addrs)
+            .build()?;
+        let mut response = client.get(parsed).send().await?;
+        let status = response.status();
+        let body = read_capped_body(&mut response, MAX_HTTP_BODY_BYTES).await?;
+
+        Ok(ToolResult {
+            success: status.is_success(),
+            output: body,
+            error: (!status.is_success()).then(|| format!("HTTP {status}")),
+            attachments: Vec::new(),
+        })
+    }
+}
+
+impl Tool for SkillToolAdapter {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn description(&self) -> &str {
+        &self.description
+    }
+
+    fn parameters_schema(&self) -> Value {
+        let properties: serde_json::Map<String, Value> = self
+            .args
+            .iter()
+            .map(|(name, description)| {
+                (
+                    name.clone(),
+                    json!({"type": "string", "description": description}),
+                )
+            })
+            .collect();
+        json!({
+            "type": "object",
+            "properties": properties,
+            "required": self.args.keys().collect::<Vec<_>>(),
+        })
+    }
+
+    fn execute<'a>(
+        &'a self,
+        args: Value,
+        ctx: &'a ExecutionContext,
+    ) -> Pin<Box<dyn Future<Output = anyhow::Result<ToolResult>> + Send + 'a>> {
+        Box::pin(async move {
+            let command = match self.kind {
+                SkillToolKind::Http => {
+                    return Ok(self
+                        .fetch(&args)
+                        .await
+                        .unwrap_or_else(|error| failed_result(&error)));
+                }
+                SkillToolKind::Shell => self.render(&self.command, &args, shell_quote),
+                SkillToolKind::Script => self.script_command(&args),
+            };
+            match command {
+                Ok(command) => {
+                    ShellTool::new()
+                        .execute(json!({ "command": command }), ctx)
+                        .await
+                }
+                Err(error) => Ok(failed_result(&error)),
+            }
+        })
+    }
+}
+
+/// Read at most `limit` bytes of the body, then stop reading, so a huge or
+/// endless response is never buffered whole.
+async fn read_capped_body(response: &mut reqwest::Response, limit: usize) -> Result<String> {
+    let mut bytes = Vec::new();
+    let mut truncated = false;
+    while let Some(chunk) = response.chunk().await? {
+        let room = limit - bytes.len();
+        if chunk.len() > room {
+            bytes.extend_from_slice(&chunk[..room]);
+            truncated = true;
+            break;
+        }
+        bytes.extend_from_slice(&chunk);
+    }
+    // Drop a multi-byte character cut in half at the limit.
+    if truncated
+        && let Err(error) = std::str::from_utf8(&bytes)
+        && error.error_len().is_none()
+    {
+        bytes.truncate(error.valid_up_to());
+    }
+    let mut body = String::from_utf8_lossy(&bytes).into_owned();
+    if truncated {
+        let _ = write!(body, "\n... [response truncated at {limit} bytes]");
+    }
+    Ok(body)
+}
+
+fn failed_result(error: &anyhow::Error) -> ToolResult {
+    ToolResult {
+        success: false,
+        output: String::new(),
+        error: Some(error.to_string()),
+        attachments: Vec::new(),
+    }
+}
+
+/// Replace `{{name}}` placeholders with encoded argument values.
+fn render_template(
+    template: &str,
+    values: &HashMap<String, String>,
+    encode: fn(&str) -> String,
+) -> Result<String> {
+    let mut rendered = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        rendered.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        let Some(end) = after.find("}}") else {
+            bail!("unterminated placeholder in skill template");
+        };
+        let key = after[..end].trim();
+        let Some(value) = values.get(key) else {
+            bail!("placeholder '{key}' is not a declared argument");
+        };
+        rendered.push_str(&encode(value));
+        rest = &after[end + 2..];
+    }
+    rendered.push_str(rest);
+    Ok(rendered)
+}
+
+/// Quote a value as a single shell word. Plain words are left unquoted so
+/// the command policy sees them exactly as the user would type them.
+fn shell_quote(value: &str) -> String {
+    let is_plain = !value.is_empty()
+        && value
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "-_.,:/=@+%".contains(c));
+    if is_plain {
+        value.to_string()
+    } else {
+        format!("'{}'", value.replace('\'', r"'\''"))
+    }
+}
+
+fn url_encode(value: &str) -> String {
+    url::form_urlencoded::byte_serialize(value.as_bytes()).collect()
+}
+
+/// Skills that are not disabled in `[skills]`.
+pub fn enabled_skills(skills: Vec<Skill>, config: &SkillsConfig) -> Vec<Skill> {
+    skills
+        .into_iter()
+        .filter(|skill| config.is_skill_enabled(&skill.name))
+        .collect()
+}
+
+/// Build registry tools for the `[[tools]]` entries of every enabled skill.
+///
+/// Entries with an unknown kind or an empty command are skipped with a warning.
+pub fn skill_tools(skills: &[Skill], config: &SkillsConfig) -> Vec<Box<dyn Tool>> {
+    if !config.tools_enabled {
+        return Vec::new();
+    }
+
+    let mut tools: Vec<Box<dyn Tool>> = Vec::new();
+    for skill in skills
+        .iter()
+        .filter(|skill| config.is_skill_enabled(&skill.name))
+    {
+        for tool in &skill.tools {
+            match SkillToolAdapter::new(skill, tool) {
+                Ok(adapter) => tools.push(Box::new(adapter)),
+                Err(error) => tracing::warn!(
+                    skill = %skill.name,
+                    tool = %tool.name,
+                    "skipping skill tool: {error}"
+                ),
+            }
+        }
+    }
+    tools
+}
+
+/// Load skills from the workspace and build tools for the enabled ones.
+pub fn load_skill_tools(workspace_dir: &Path, config: &SkillsConfig) -> Vec<Box<dyn Tool>> {
+    if !config.tools_enabled {
+        return Vec::new();
+    }
+    skill_tools(&super::load_skills(workspace_dir), config)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::security::{AutonomyLevel, SecurityPolicy};
+    use std::sync::Arc;
+    use tempfile::TempDir;
+
+    fn skill_with_tool(location: Option<PathBuf>, tool: SkillTool) -> Skill {
+        Skill {
+            name: "weather".to_string(),
+            description: "Weather lookups".to_string(),
+            version: "1.0.0".to_string(),
+            author: None,
+            tags: vec![],
+            tools: vec![tool],
+            prompts: vec![],
+            location,
+        }
+    }
+
+    fn tool(kind: &str, command: &str, args: &[(&str, &str)]) -> SkillTool {
+        SkillTool {
+            name: "forecast".to_string(),
+            description: "Get the forecast".to_string(),
+            kind: kind.to_string(),
+            command: command.to_string(),
+            args: args
+                .iter()
+                .map(|(k, v)| ((*k).to_string(), (*v).to_string()))
+                .collect(),
+        }
+    }
+
+    fn ctx(workspace: &Path) -> ExecutionContext {
+        ExecutionContext::test_default(Arc::new(SecurityPolicy {
+            autonomy: AutonomyLevel::Supervised,
+            workspace_dir: workspace.to_path_buf(),
+            ..SecurityPolicy::default()
+        }))
+    }
+
+    #[test]
+    fn skill_tool_name_is_namespaced_and_sanitized() {
+        assert_eq!(
+            skill_tool_name("my-skill", "get.weather"),
+            "skill_my_skill_get_weather"
+        );
+    }
+
+    #[test]
+    fn schema_is_generated_from_args() {
+        let skill = skill_with_tool(
+            None,
+            tool("shell", "echo {{city}}", &[("city", "City name")]),
+        );
+        let adapter = SkillToolAdapter::new(&skill, &skill.tools[0]).unwrap();
+        let schema = adapter.parameters_schema();
+        assert_eq!(schema["properties"]["city"]["type"], "string");
+        assert_eq!(schema["properties"]["city"]["description"], "City name");
+        assert_eq!(schema["required"], json!(["city"]));
+        assert_eq!(adapter.name(), "skill_weather_forecast");
+    }
+
+    #[test]
+    fn unknown_kind_is_rejected() {
+        let skill = skill_with_tool(None, tool("ftp", "get", &[]));
+        assert!(SkillToolAdapter::new(&skill, &skill.tools[0]).is_err());
+        assert!(skill_tools(&[skill], &SkillsConfig::default()).is_empty());
+    }
+
+    #[test]
+    fn render_template_quotes_shell_values() {
+        let values = HashMap::from([("q".to_string(), "it's here".to_string())]);
+        let rendered = render_template("echo {{ q }}", &values, shell_quote).unwrap();
+        assert_eq!(rendered, r"echo 'it'\''s here'");
+    }
+
+    #[test]
+    fn render_template_encodes_url_values() {
+        let values = HashMap::from([("city".to_string(), "New York".to_string())]);
+        let rendered =
+            render_template("https://wttr.in/{{city}}?format=3", &values, url_encode).unwrap();
+        assert_eq!(rendered, "https://wttr.in/New+York?format=3");
+    }
+
+    #[test]
+    fn render_template_rejects_undeclared_placeholder() {
+        assert!(render_template("echo {{other}}", &HashMap::new(), shell_quote).is_err());
+    }
+
+    #[tokio::test]
+    async fn shell_tool_runs_with_templated_args() {
+        let tmp = TempDir::new().unwrap();
+        let skill = skill_with_tool(
+            None,
+            tool("shell", "echo forecast {{city}}", &[("city", "City name")]),
+        );
+        let adapter = SkillToolAdapter::new(&skill, &skill.tools[0]).unwrap();
+        let result = adapter
+            .execute(json!({"city": "Tokyo"}), &ctx(tmp.path()))
+            .await
+            .unwrap();
+        assert!(result.success);
+        assert!(result.output.contains("forecast Tokyo"));
+    }
+
+    #[tokio::test]
+    async fn shell_tool_enforces_command_policy() {
+        let tmp = TempDir::new().unwrap();
+        let skill = skill_with_tool(None, tool("shell", "curl {{url}}", &[("url", "URL")]));
+        let adapter = SkillToolAdapter::new(&skill, &skill.tools[0]).unwrap();
+        let result = adapter
+            .execute(json!({"url": "example.com"}), &ctx(tmp.path()))
+            .await
+            .unwrap();
+        assert!(!result.success);
+        assert!(result.error.unwrap().contains("not allowed"));
+    }
+
+    #[tokio::test]
+    async fn shell_tool_reports_missing_argument() {
+        let tmp = TempDir::new().unwrap();
+        let skill = skill_with_tool(None, tool("shell", "echo {{city}}", &[("city", "City")]));
+        let adapter = SkillToolAdapter::new(&skill, &skill.tools[0]).unwrap();
+        let result = adapter.execute(json!({}), &ctx(tmp.path())).await.unwrap();
+        assert!(!result.success);
+        assert!(result.error.unwrap().contains("Missing 'city'"));
+    }
+
+    #[tokio::test]
+    async fn http_tool_blocks_private_hosts() {
+        let tmp = TempDir::new().unwrap();
+        let skill = skill_with_tool(
+            None,
+            tool("http", "http://127.0.0.1/{{path}}", &[("path", "Path")]),
+        );
+        let adapter = SkillToolAdapter::new(&skill, &skill.tools[0]).unwrap();
+        let result = adapter
+            .execute(json!({"path": "admin"}), &ctx(tmp.path()))
+            .await
+            .unwrap();
+        assert!(!result.success);
+        assert!(result.error.unwrap().contains("private/internal"));
+    }
+
+    #[tokio::test]
+    async fn capped_body_stops_at_the_limit_on_a_char_boundary() {
+        use wiremock::matchers::method;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("123456789\u{e9}-and more"))
+            .mount(&server)
+            .await;
+
+        let mut response = reqwest::get(server.uri()).await.unwrap();
+        let body = read_capped_body(&mut response, 10).await.unwrap();
+        assert_eq!(body, "123456789\n... [response truncated at 10 bytes]");
+
+        let mut response = reqwest::get(server.uri()).await.unwrap();
+        let body = read_capped_body(&mut response, 1_000).await.unwrap();
+        assert_eq!(body, "123456789\u{e9}-and more");
+    }
+
+    #[test]
+    fn script_path_must_stay_inside_skill_dir() {
+        let tmp = TempDir::new().unwrap();
+        let skill_dir = tmp.path().join("skills").join("weather");
+        std::fs::create_dir_all(&skill_dir).unwrap();
+        std::fs::write(tmp.path().join("outside.sh"), "echo hi").unwrap();
+        let skill = skill_with_tool(
+            Some(skill_dir.join("SKILL.toml")),
+            tool("script", "../../outside.sh", &[]),
+        );
+        let adapter = SkillToolAdapter::new(&skill, &skill.tools[0]).unwrap();
+        assert!(adapter.script_command(&json!({})).is_err());
+    }
+
+    #[test]
+    fn script_command_resolves_relative_to_skill_dir() {
+        let tmp = TempDir::new().unwrap();
+        let skill_dir = tmp.path().join("skills").join("weather");
+        std::fs::create_dir_all(&skill_dir).unwrap();
+        std::fs::write(skill_dir.join("run.sh"), "echo hi").unwrap();
+        let skill = skill_with_tool(
+            Some(skill_dir.join("SKILL.toml")),
+            tool("script", "run.sh {{city}}", &[("city", "City")]),
+        );
+        let adapter = SkillToolAdapter::new(&skill, &skill.tools[0]).unwrap();
+        let command = adapter.script_command(&json!({"city": "Oslo"})).unwrap();
+        assert!(command.ends_with("run.sh Oslo"));
+        assert!(command.starts_with(&skill_dir.canonicalize().unwrap().display().to_string()));
+    }
+
+    #[test]
+    fn disabled_skills_register_no_tools() {
+        let skill = skill_with_tool(None, tool("shell", "echo hi", &[]));
+        let mut config = SkillsConfig::default();
+        assert_eq!(skill_tools(std::slice::from_ref(&skill), &config).len(), 1);
+
+        config.set_skill_enabled("weather", false);
+        assert!(skill_tools(std::slice::from_ref(&skill), &config).is_empty());
+
+        let config = SkillsConfig {
+            tools_enabled: false,
+            disabled: vec![],
+        };
+        assert!(skill_tools(&[skill], &config).is_empty());
+    }
+}
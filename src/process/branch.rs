@@ -1,7 +1,12 @@
+use super::compactor::{
+    CompactionLevel, CompactionThresholds, assess_compaction, compact_messages, compaction_split,
+};
 use super::deps::AgentDeps;
-use super::events::EventSender;
+use super::events::{EventSender, ProcessEvent};
 use super::worker::{WorkerParams, WorkerResult, run_worker};
+use crate::llm::ThinkingLevel;
 use crate::llm::types::{ContentBlock, MessageRole, ProviderMessage};
+use std::time::{Duration, Instant};
 
 /// Per-entity conversation state.
 ///
@@ -11,6 +16,9 @@ pub struct Branch {
     entity_id: String,
     conversation_history: Vec<ProviderMessage>,
     turn_count: u32,
+    last_active: Instant,
+    /// Extended-thinking level set with `/think` for this conversation.
+    thinking: ThinkingLevel,
     deps: AgentDeps,
     events: EventSender,
 }
@@ -21,6 +29,8 @@ impl Branch {
             entity_id,
             conversation_history: Vec::new(),
             turn_count: 0,
+            last_active: Instant::now(),
+            thinking: ThinkingLevel::Off,
             deps,
             events,
         }
@@ -34,8 +44,6 @@ impl Branch {
         model: &str,
         temperature: f64,
     ) -> anyhow::Result<WorkerResult> {
-        self.turn_count += 1;
-
         let params = WorkerParams {
             entity_id: self.entity_id.clone(),
             system_prompt: system_prompt.to_string(),
@@ -43,38 +51,75 @@ impl Branch {
             image_content: Vec::new(),
             model: model.to_string(),
             temperature,
+            thinking: self.thinking,
             max_tool_iterations: 25,
             conversation_history: self.conversation_history.clone(),
             stream_sink: None,
         };
 
         let result = run_worker(&self.deps, params, &self.events).await?;
+        self.record_turn(message, &result.tool_loop_result.transcript);
+        Ok(result)
+    }
 
-        // Append user message to history.
+    /// Append a finished turn: `user_message` as the user sent it, followed
+    /// by every reply in `transcript` after its leading user message.
+    pub fn record_turn(&mut self, user_message: &str, transcript: &[ProviderMessage]) {
+        self.turn_count += 1;
+        self.last_active = Instant::now();
         self.conversation_history.push(ProviderMessage {
             role: MessageRole::User,
             content: vec![ContentBlock::Text {
-                text: message.to_string(),
+                text: user_message.to_string(),
             }],
         });
+        self.conversation_history
+            .extend(transcript.iter().skip(1).cloned());
+    }
 
-        // Append assistant response to history.
-        if !result.tool_loop_result.final_text.is_empty() {
-            self.conversation_history.push(ProviderMessage {
-                role: MessageRole::Assistant,
-                content: vec![ContentBlock::Text {
-                    text: result.tool_loop_result.final_text.clone(),
-                }],
-            });
+    /// Compact the history when it crosses one of `thresholds`.
+    pub fn compact_if_needed(&mut self, thresholds: &CompactionThresholds) -> CompactionLevel {
+        let level = assess_compaction(&self.conversation_history, thresholds);
+        self.compact(level);
+        level
+    }
+
+    /// Summarize older history at `level`; returns how many messages were
+    /// folded into the summary.
+    pub fn compact(&mut self, level: CompactionLevel) -> usize {
+        let removed = compaction_split(&self.conversation_history, level);
+        if removed == 0 {
+            return 0;
         }
+        self.conversation_history = compact_messages(&self.conversation_history, level);
+        let _ = self.events.send(ProcessEvent::ContextCompacted {
+            entity_id: self.entity_id.clone(),
+            level: format!("{level:?}"),
+        });
+        removed
+    }
 
-        Ok(result)
+    pub fn thinking(&self) -> ThinkingLevel {
+        self.thinking
+    }
+
+    pub fn set_thinking(&mut self, level: ThinkingLevel) {
+        self.thinking = level;
+    }
+
+    pub fn history(&self) -> &[ProviderMessage] {
+        &self.conversation_history
     }
 
     pub fn history_len(&self) -> usize {
         self.conversation_history.len()
     }
 
+    /// Time since the branch was created or last recorded a turn.
+    pub fn idle_for(&self) -> Duration {
+        self.last_active.elapsed()
+    }
+
     pub fn entity_id(&self) -> &str {
         &self.entity_id
     }
@@ -219,4 +264,62 @@ mod tests {
         branch.set_history(history);
         assert_eq!(branch.history_len(), 2);
     }
+
+    #[test]
+    fn record_turn_keeps_raw_user_message_and_replies() {
+        let deps = make_deps();
+        let (tx, _rx) = event_bus(8);
+        let mut branch = Branch::new("user:1".to_string(), deps, tx);
+        let text = |role, text: &str| ProviderMessage {
+            role,
+            content: vec![ContentBlock::Text {
+                text: text.to_string(),
+            }],
+        };
+
+        branch.record_turn(
+            "hello",
+            &[
+                text(MessageRole::User, "[memory context]\nhello"),
+                text(MessageRole::Assistant, "hi"),
+            ],
+        );
+
+        assert_eq!(branch.turn_count(), 1);
+        assert_eq!(branch.history_len(), 2);
+        assert!(matches!(
+            &branch.history()[0].content[0],
+            ContentBlock::Text { text } if text == "hello"
+        ));
+    }
+
+    #[tokio::test]
+    async fn compact_if_needed_summarizes_and_emits_event() {
+        let deps = make_deps();
+        let (tx, mut rx) = event_bus(8);
+        let mut branch = Branch::new("user:1".to_string(), deps, tx);
+        let history = (0..8)
+            .map(|_| ProviderMessage {
+                role: MessageRole::User,
+                content: vec![ContentBlock::Text {
+                    text: "x".repeat(100),
+                }],
+            })
+            .collect();
+        branch.set_history(history);
+
+        let thresholds = CompactionThresholds {
+            max_context_tokens: 240,
+            ..CompactionThresholds::default()
+        };
+        let level = branch.compact_if_needed(&thresholds);
+
+        assert_eq!(level, CompactionLevel::Aggressive);
+        assert_eq!(branch.history_len(), 3);
+        assert_eq!(branch.history()[0].role, MessageRole::System);
+        assert!(matches!(
+            rx.recv().await.unwrap(),
+            ProcessEvent::ContextCompacted { .. }
+        ));
+    }
 }
@@ -1,20 +1,29 @@
 use super::branch::Branch;
+use super::compactor::CompactionThresholds;
 use super::deps::AgentDeps;
 use super::events::{EventSender, ProcessEvent};
 use super::worker::WorkerResult;
 use std::collections::HashMap;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::Duration;
+
+/// Shared handle to one entity's branch; holding the lock serializes that
+/// entity's turns while other entities proceed concurrently.
+pub type BranchHandle = Arc<tokio::sync::Mutex<Branch>>;
 
 /// Channel-level process that routes incoming messages to per-entity [`Branch`]es.
 ///
 /// Each entity gets its own conversation branch. Branches are created on demand
-/// when the first message arrives for a given `entity_id`.
+/// when the first message arrives for a given `entity_id`, and closed by
+/// [`ChannelProcess::evict_idle`] once they have been quiet long enough.
 pub struct ChannelProcess {
-    branches: HashMap<String, Branch>,
+    branches: Mutex<HashMap<String, BranchHandle>>,
     deps: AgentDeps,
     events: EventSender,
     default_model: String,
     default_temperature: f64,
     system_prompt: String,
+    thresholds: CompactionThresholds,
 }
 
 impl ChannelProcess {
@@ -26,71 +35,137 @@ impl ChannelProcess {
         default_temperature: f64,
     ) -> Self {
         Self {
-            branches: HashMap::new(),
+            branches: Mutex::new(HashMap::new()),
             deps,
             events,
             default_model: default_model.into(),
             default_temperature,
             system_prompt: system_prompt.into(),
+            thresholds: CompactionThresholds::default(),
+        }
+    }
+
+    pub fn with_compaction_thresholds(mut self, thresholds: CompactionThresholds) -> Self {
+        self.thresholds = thresholds;
+        self
+    }
+
+    pub fn deps(&self) -> &AgentDeps {
+        &self.deps
+    }
+
+    pub fn events(&self) -> &EventSender {
+        &self.events
+    }
+
+    pub fn compaction_thresholds(&self) -> &CompactionThresholds {
+        &self.thresholds
+    }
+
+    /// The base system prompt followed by the latest cortex bulletin, if any.
+    pub fn system_prompt(&self) -> String {
+        match self.deps.bulletin_cache.load().as_ref() {
+            Some(bulletin) => format!("{}\n\n{bulletin}", self.system_prompt),
+            None => self.system_prompt.clone(),
         }
     }
 
+    /// The branch for `entity_id`, created if this is its first message.
+    pub fn branch(&self, entity_id: &str) -> BranchHandle {
+        let mut branches = self.branches.lock().unwrap_or_else(PoisonError::into_inner);
+        if let Some(branch) = branches.get(entity_id) {
+            return Arc::clone(branch);
+        }
+        let branch = Arc::new(tokio::sync::Mutex::new(Branch::new(
+            entity_id.to_string(),
+            self.deps.clone(),
+            self.events.clone(),
+        )));
+        let _ = self.events.send(ProcessEvent::BranchCreated {
+            entity_id: entity_id.to_string(),
+        });
+        branches.insert(entity_id.to_string(), Arc::clone(&branch));
+        branch
+    }
+
     /// Handle an incoming message from the given entity.
     ///
     /// Creates a new branch if this is the first message from this entity.
     pub async fn handle_message(
-        &mut self,
+        &self,
         entity_id: &str,
         message: &str,
     ) -> anyhow::Result<WorkerResult> {
-        if !self.branches.contains_key(entity_id) {
-            let branch = Branch::new(
-                entity_id.to_string(),
-                self.deps.clone(),
-                self.events.clone(),
-            );
-            let _ = self.events.send(ProcessEvent::BranchCreated {
-                entity_id: entity_id.to_string(),
-            });
-            self.branches.insert(entity_id.to_string(), branch);
-        }
-
-        let branch = self
-            .branches
-            .get_mut(entity_id)
-            .expect("branch was just inserted");
-        branch
+        let branch = self.branch(entity_id);
+        let mut branch = branch.lock().await;
+        let result = branch
             .process_message(
                 message,
-                &self.system_prompt,
+                &self.system_prompt(),
                 &self.default_model,
                 self.default_temperature,
             )
-            .await
+            .await?;
+        branch.compact_if_needed(&self.thresholds);
+        Ok(result)
     }
 
     /// Close and remove the branch for the given entity.
     ///
     /// Returns `true` if the branch existed and was removed.
-    pub fn close_branch(&mut self, entity_id: &str) -> bool {
-        if self.branches.remove(entity_id).is_some() {
+    pub fn close_branch(&self, entity_id: &str) -> bool {
+        let removed = self
+            .branches
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .remove(entity_id)
+            .is_some();
+        if removed {
             let _ = self.events.send(ProcessEvent::BranchClosed {
                 entity_id: entity_id.to_string(),
             });
-            true
-        } else {
-            false
         }
+        removed
+    }
+
+    /// Close branches that have been idle for at least `max_idle` and are not
+    /// mid-turn. Returns the evicted entity IDs.
+    pub fn evict_idle(&self, max_idle: Duration) -> Vec<String> {
+        let mut branches = self.branches.lock().unwrap_or_else(PoisonError::into_inner);
+        let idle: Vec<String> = branches
+            .iter()
+            .filter(|(_, branch)| {
+                branch
+                    .try_lock()
+                    .is_ok_and(|branch| branch.idle_for() >= max_idle)
+            })
+            .map(|(entity_id, _)| entity_id.clone())
+            .collect();
+        for entity_id in &idle {
+            branches.remove(entity_id);
+            let _ = self.events.send(ProcessEvent::BranchClosed {
+                entity_id: entity_id.clone(),
+            });
+        }
+        idle
     }
 
     /// Return the entity IDs of all active branches.
-    pub fn active_entities(&self) -> Vec<&str> {
-        self.branches.keys().map(String::as_str).collect()
+    pub fn active_entities(&self) -> Vec<String> {
+        self.branches
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .keys()
+            .cloned()
+            .collect()
     }
 
     /// Return the number of active branches.
     pub fn branch_count(&self) -> usize {
-        self.branches.len()
+        self.branches
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .len()
     }
 }
 
@@ -197,7 +272,7 @@ mod tests {
     fn close_branch_returns_false_for_nonexistent() {
         let deps = make_deps();
         let (tx, _rx) = event_bus(8);
-        let mut proc = ChannelProcess::new(deps, tx, "prompt", "model", 0.7);
+        let proc = ChannelProcess::new(deps, tx, "prompt", "model", 0.7);
 
         assert!(!proc.close_branch("nobody"));
     }
@@ -206,20 +281,17 @@ mod tests {
     async fn close_branch_emits_event() {
         let deps = make_deps();
         let (tx, mut rx) = event_bus(16);
-        let mut proc = ChannelProcess::new(deps, tx, "prompt", "model", 0.7);
-
-        // Manually insert a branch so we don't need a working provider.
-        let branch_deps = proc.deps.clone();
-        let branch_events = proc.events.clone();
-        proc.branches.insert(
-            "user:1".to_string(),
-            Branch::new("user:1".to_string(), branch_deps, branch_events),
-        );
+        let proc = ChannelProcess::new(deps, tx, "prompt", "model", 0.7);
+
+        // Creating a branch does not need a working provider.
+        proc.branch("user:1");
 
         assert_eq!(proc.branch_count(), 1);
         assert!(proc.close_branch("user:1"));
         assert_eq!(proc.branch_count(), 0);
 
+        let event = rx.recv().await.unwrap();
+        assert!(matches!(event, ProcessEvent::BranchCreated { .. }));
         let event = rx.recv().await.unwrap();
         assert!(matches!(event, ProcessEvent::BranchClosed { .. }));
     }
@@ -228,25 +300,45 @@ mod tests {
     fn active_entities_returns_keys() {
         let deps = make_deps();
         let (tx, _rx) = event_bus(8);
-        let mut proc = ChannelProcess::new(deps, tx, "prompt", "model", 0.7);
-
-        let branch_deps = proc.deps.clone();
-        let branch_events = proc.events.clone();
-        proc.branches.insert(
-            "user:a".to_string(),
-            Branch::new(
-                "user:a".to_string(),
-                branch_deps.clone(),
-                branch_events.clone(),
-            ),
-        );
-        proc.branches.insert(
-            "user:b".to_string(),
-            Branch::new("user:b".to_string(), branch_deps, branch_events),
-        );
+        let proc = ChannelProcess::new(deps, tx, "prompt", "model", 0.7);
+
+        proc.branch("user:a");
+        proc.branch("user:b");
+        proc.branch("user:a");
 
         let mut entities = proc.active_entities();
         entities.sort_unstable();
         assert_eq!(entities, vec!["user:a", "user:b"]);
     }
+
+    #[tokio::test]
+    async fn evict_idle_skips_busy_and_recent_branches() {
+        let deps = make_deps();
+        let (tx, _rx) = event_bus(16);
+        let proc = ChannelProcess::new(deps, tx, "prompt", "model", 0.7);
+
+        let busy = proc.branch("user:busy");
+        let _guard = busy.lock().await;
+        proc.branch("user:idle");
+
+        assert!(proc.evict_idle(Duration::from_hours(1)).is_empty());
+        assert_eq!(proc.evict_idle(Duration::ZERO), vec!["user:idle"]);
+        assert_eq!(proc.active_entities(), vec!["user:busy"]);
+    }
+
+    #[test]
+    fn system_prompt_appends_cortex_bulletin() {
+        let deps = make_deps();
+        let (tx, _rx) = event_bus(8);
+        let proc = ChannelProcess::new(deps, tx, "You are helpful.", "model", 0.7);
+        assert_eq!(proc.system_prompt(), "You are helpful.");
+
+        proc.deps()
+            .bulletin_cache
+            .store(Arc::new(Some("## Recent Context\n- likes tea".to_string())));
+        assert_eq!(
+            proc.system_prompt(),
+            "You are helpful.\n\n## Recent Context\n- likes tea"
+        );
+    }
 }
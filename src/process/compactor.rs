@@ -1,5 +1,6 @@
 use crate::agent::token_estimate;
 use crate::llm::types::{ContentBlock, MessageRole, ProviderMessage};
+use crate::utils::text::truncate_with_ellipsis;
 
 /// Thresholds that control when and how aggressively context is compacted.
 #[derive(Debug, Clone)]
@@ -54,6 +55,34 @@ pub fn assess_compaction(
     }
 }
 
+/// Number of leading messages [`compact_messages`] folds into its summary
+/// at `level`; `0` when nothing would be compacted.
+///
+/// The split never lands between a tool call and its result, so the kept
+/// history always starts with a complete exchange.
+pub fn compaction_split(messages: &[ProviderMessage], level: CompactionLevel) -> usize {
+    let keep_fraction = match level {
+        CompactionLevel::None => return 0,
+        CompactionLevel::Light => 2,      // keep 1/2
+        CompactionLevel::Moderate => 3,   // keep 1/3
+        CompactionLevel::Aggressive => 4, // keep 1/4
+    };
+
+    let keep_count = messages.len() / keep_fraction;
+    let mut split_at = messages.len().saturating_sub(keep_count.max(2));
+    while split_at > 0
+        && messages.get(split_at).is_some_and(|message| {
+            message
+                .content
+                .iter()
+                .any(|block| matches!(block, ContentBlock::ToolResult { .. }))
+        })
+    {
+        split_at -= 1;
+    }
+    split_at
+}
+
 /// Compact messages by summarizing older ones textually.
 ///
 /// Produces a simple text summary of the oldest messages and keeps the most
@@ -62,26 +91,13 @@ pub fn compact_messages(
     messages: &[ProviderMessage],
     level: CompactionLevel,
 ) -> Vec<ProviderMessage> {
-    if level == CompactionLevel::None || messages.is_empty() {
+    let split_at = compaction_split(messages, level);
+    if split_at == 0 {
         return messages.to_vec();
     }
-
-    let keep_fraction = match level {
-        CompactionLevel::Light => 2,      // keep 1/2
-        CompactionLevel::Moderate => 3,   // keep 1/3
-        CompactionLevel::Aggressive => 4, // keep 1/4
-        CompactionLevel::None => unreachable!(),
-    };
-
-    let keep_count = messages.len() / keep_fraction;
-    let split_at = messages.len().saturating_sub(keep_count.max(2));
     let to_summarize = &messages[..split_at];
     let to_keep = &messages[split_at..];
 
-    if to_summarize.is_empty() {
-        return messages.to_vec();
-    }
-
     // Build simple text summary of compacted messages.
     let summary_parts: Vec<String> = to_summarize
         .iter()
@@ -100,12 +116,7 @@ pub fn compact_messages(
                 })
                 .collect::<Vec<_>>()
                 .join(" ");
-            let truncated = if text.len() > 200 {
-                format!("{}...", &text[..200])
-            } else {
-                text
-            };
-            format!("{role_label}: {truncated}")
+            format!("{role_label}: {}", truncate_with_ellipsis(&text, 200))
         })
         .collect();
 
@@ -343,4 +354,30 @@ mod tests {
         assert!((t.aggressive_ratio - 0.95).abs() < f64::EPSILON);
         assert_eq!(t.max_context_tokens, 128_000);
     }
+
+    #[test]
+    fn compaction_split_keeps_tool_results_with_their_calls() {
+        let mut messages = make_messages(6, 20);
+        messages[3] = ProviderMessage {
+            role: MessageRole::Assistant,
+            content: vec![ContentBlock::ToolUse {
+                id: "t1".to_string(),
+                name: "shell".to_string(),
+                input: serde_json::json!({}),
+            }],
+        };
+        messages[4] = ProviderMessage {
+            role: MessageRole::User,
+            content: vec![ContentBlock::ToolResult {
+                tool_use_id: "t1".to_string(),
+                content: "ok".to_string(),
+                is_error: false,
+            }],
+        };
+
+        // Aggressive keeps max(6/4, 2) = 2 messages, which would start at
+        // the tool result; the split moves back to include its call.
+        assert_eq!(compaction_split(&messages, CompactionLevel::Aggressive), 3);
+        assert_eq!(compaction_split(&messages, CompactionLevel::None), 0);
+    }
 }
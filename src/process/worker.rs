@@ -1,6 +1,7 @@
 use super::deps::AgentDeps;
 use super::events::{EventSender, ProcessEvent};
 use crate::agent::tool_loop::{ToolLoop, ToolLoopResult, ToolLoopRunParams};
+use crate::llm::ThinkingLevel;
 use crate::llm::streaming::StreamSink;
 use crate::llm::types::{ContentBlock, ProviderMessage};
 use crate::security::policy::{AutonomyLevel, EntityRateLimiter, TenantPolicyContext};
@@ -15,6 +16,7 @@ pub struct WorkerParams {
     pub image_content: Vec<ContentBlock>,
     pub model: String,
     pub temperature: f64,
+    pub thinking: ThinkingLevel,
     pub max_tool_iterations: u32,
     pub conversation_history: Vec<ProviderMessage>,
     pub stream_sink: Option<Arc<dyn StreamSink>>,
@@ -51,9 +53,11 @@ pub async fn run_worker(
         allowed_tools: None,
         rate_limiter: Arc::new(EntityRateLimiter::new(100, 20)),
         tenant_context: TenantPolicyContext::disabled(),
+        approval: None,
     };
 
-    let tool_loop = ToolLoop::new(Arc::clone(&deps.tool_registry), params.max_tool_iterations);
+    let tool_loop = ToolLoop::new(Arc::clone(&deps.tool_registry), params.max_tool_iterations)
+        .with_context_config(deps.config.context.clone());
 
     let run_params = ToolLoopRunParams {
         provider: provider.as_ref(),
@@ -62,6 +66,8 @@ pub async fn run_worker(
         image_content: &params.image_content,
         model: &params.model,
         temperature: params.temperature,
+        thinking: params.thinking,
+        request_options: None,
         ctx: &ctx,
         stream_sink: params.stream_sink,
         conversation_history: &params.conversation_history,
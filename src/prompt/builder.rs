@@ -16,6 +16,8 @@ You are {{ persona_name }}, {{ persona_description }}.
 
 const COMPACTION_TEMPLATE: &str = "\
 Summarize the following conversation, preserving key facts, decisions, and context.
+Keep the outcome of every tool call (what was run and what it returned or changed), \
+commitments made to the user, and questions still open. Drop greetings and repetition.
 Target compression ratio: {{ target_ratio }}%.
 Conversation:
 {{ messages_text }}";
@@ -0,0 +1,161 @@
+//! Prometheus text exposition: the process-wide [`PrometheusObserver`] plus
+//! provider usage read from the usage database.
+
+use super::global::prometheus_observer;
+use crate::runtime::usage::{ModelUsageSummary, SqliteUsageTracker};
+use std::fmt::{Display, Write};
+
+/// `Content-Type` for the text exposition format.
+pub const METRICS_CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+/// Render every metric for a `/metrics` scrape, with provider usage read
+/// through `usage` when given. Callers keep one tracker for all scrapes.
+pub async fn render_metrics(usage: Option<&SqliteUsageTracker>) -> String {
+    let mut out = MetricsWriter::default();
+    prometheus_observer().render(&mut out);
+
+    if let Some(tracker) = usage {
+        match tracker.summarize_by_model().await {
+            Ok(usage) => render_usage(&mut out, &usage),
+            Err(error) => tracing::warn!("failed to summarize usage for metrics: {error}"),
+        }
+    }
+
+    out.finish()
+}
+
+fn render_usage(out: &mut MetricsWriter, usage: &[ModelUsageSummary]) {
+    out.family(
+        "asteroniris_provider_requests_total",
+        "counter",
+        "Provider calls recorded in the usage database.",
+    );
+    for entry in usage {
+        out.sample(
+            "asteroniris_provider_requests_total",
+            &[("provider", &entry.provider), ("model", &entry.model)],
+            entry.summary.record_count,
+        );
+    }
+    out.family(
+        "asteroniris_provider_tokens_total",
+        "counter",
+        "Provider tokens recorded in the usage database.",
+    );
+    for entry in usage {
+        let labels = [
+            ("provider", entry.provider.as_str()),
+            ("model", &entry.model),
+        ];
+        out.sample(
+            "asteroniris_provider_tokens_total",
+            &[labels[0], labels[1], ("direction", "input")],
+            entry.summary.total_input_tokens,
+        );
+        out.sample(
+            "asteroniris_provider_tokens_total",
+            &[labels[0], labels[1], ("direction", "output")],
+            entry.summary.total_output_tokens,
+        );
+    }
+    out.family(
+        "asteroniris_provider_cost_usd_total",
+        "counter",
+        "Estimated provider cost in US dollars.",
+    );
+    for entry in usage {
+        #[allow(clippy::cast_precision_loss)]
+        let dollars = entry.summary.total_estimated_cost_micros as f64 / 1_000_000.0;
+        out.sample(
+            "asteroniris_provider_cost_usd_total",
+            &[("provider", &entry.provider), ("model", &entry.model)],
+            dollars,
+        );
+    }
+}
+
+/// Accumulates metric families in the text exposition format.
+#[derive(Default)]
+pub struct MetricsWriter {
+    out: String,
+}
+
+impl MetricsWriter {
+    /// Start a metric family with its `# HELP` and `# TYPE` lines.
+    pub fn family(&mut self, name: &str, kind: &str, help: &str) {
+        let _ = writeln!(self.out, "# HELP {name} {help}");
+        let _ = writeln!(self.out, "# TYPE {name} {kind}");
+    }
+
+    pub fn sample(&mut self, name: &str, labels: &[(&str, &str)], value: impl Display) {
+        self.out.push_str(name);
+        if !labels.is_empty() {
+            self.out.push('{');
+            for (index, (key, label_value)) in labels.iter().enumerate() {
+                if index > 0 {
+                    self.out.push(',');
+                }
+                let _ = write!(self.out, "{key}=\"{}\"", escape_label(label_value));
+            }
+            self.out.push('}');
+        }
+        let _ = writeln!(self.out, " {value}");
+    }
+
+    pub fn finish(self) -> String {
+        self.out
+    }
+}
+
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runtime::usage::UsageSummary;
+
+    #[test]
+    fn writer_formats_labels_and_escapes_values() {
+        let mut out = MetricsWriter::default();
+        out.family("demo_total", "counter", "Demo counter.");
+        out.sample("demo_total", &[], 3);
+        out.sample("demo_total", &[("tool", "sh\"ell"), ("outcome", "ok")], 1);
+
+        assert_eq!(
+            out.finish(),
+            "# HELP demo_total Demo counter.\n# TYPE demo_total counter\ndemo_total 3\n\
+             demo_total{tool=\"sh\\\"ell\",outcome=\"ok\"} 1\n"
+        );
+    }
+
+    #[test]
+    fn usage_renders_tokens_and_cost_per_model() {
+        let mut out = MetricsWriter::default();
+        render_usage(
+            &mut out,
+            &[ModelUsageSummary {
+                provider: "anthropic".into(),
+                model: "claude-sonnet-4".into(),
+                summary: UsageSummary {
+                    total_input_tokens: 120,
+                    total_output_tokens: 80,
+                    total_estimated_cost_micros: 1_500_000,
+                    record_count: 2,
+                },
+            }],
+        );
+        let text = out.finish();
+
+        assert!(text.contains(
+            "asteroniris_provider_tokens_total{provider=\"anthropic\",model=\"claude-sonnet-4\",direction=\"input\"} 120"
+        ));
+        assert!(text.contains(
+            "asteroniris_provider_cost_usd_total{provider=\"anthropic\",model=\"claude-sonnet-4\"} 1.5"
+        ));
+    }
+}
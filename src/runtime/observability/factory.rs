@@ -7,7 +7,7 @@ pub fn create_observer(config: &ObservabilityConfig) -> Box<dyn Observer> {
     match config.backend.as_str() {
         "log" => Box::new(LogObserver::new()),
         "prometheus" => Box::new(PrometheusObserver::new()),
-        "otel" => Box::new(OtelObserver::new()),
+        "otel" => Box::new(OtelObserver::from_config(config)),
         "none" | "noop" => Box::new(NoopObserver),
         _ => {
             tracing::warn!(
@@ -32,6 +32,7 @@ mod tests {
     fn factory_none_returns_noop() {
         let cfg = ObservabilityConfig {
             backend: "none".into(),
+            ..ObservabilityConfig::default()
         };
         assert_eq!(create_observer(&cfg).name(), "noop");
     }
@@ -40,6 +41,7 @@ mod tests {
     fn factory_noop_returns_noop() {
         let cfg = ObservabilityConfig {
             backend: "noop".into(),
+            ..ObservabilityConfig::default()
         };
         assert_eq!(create_observer(&cfg).name(), "noop");
     }
@@ -48,6 +50,7 @@ mod tests {
     fn factory_log_returns_log() {
         let cfg = ObservabilityConfig {
             backend: "log".into(),
+            ..ObservabilityConfig::default()
         };
         assert_eq!(create_observer(&cfg).name(), "log");
     }
@@ -56,6 +59,7 @@ mod tests {
     fn factory_prometheus_returns_prometheus() {
         let cfg = ObservabilityConfig {
             backend: "prometheus".into(),
+            ..ObservabilityConfig::default()
         };
         assert_eq!(create_observer(&cfg).name(), "prometheus");
     }
@@ -64,6 +68,7 @@ mod tests {
     fn factory_otel_returns_otel() {
         let cfg = ObservabilityConfig {
             backend: "otel".into(),
+            ..ObservabilityConfig::default()
         };
         assert_eq!(create_observer(&cfg).name(), "otel");
     }
@@ -72,6 +77,7 @@ mod tests {
     fn factory_expanded_backends_smoke_paths() {
         let prometheus = create_observer(&ObservabilityConfig {
             backend: "prometheus".into(),
+            ..ObservabilityConfig::default()
         });
         prometheus.record_event(&ObserverEvent::HeartbeatTick);
         prometheus.record_metric(&ObserverMetric::QueueDepth(1));
@@ -79,10 +85,12 @@ mod tests {
 
         let otel = create_observer(&ObservabilityConfig {
             backend: "otel".into(),
+            ..ObservabilityConfig::default()
         });
         otel.record_event(&ObserverEvent::AgentEnd {
             duration: Duration::from_secs(1),
             tokens_used: Some(123),
+            success: true,
         });
         otel.record_metric(&ObserverMetric::TokensUsed(123));
         otel.flush();
@@ -92,6 +100,7 @@ mod tests {
     fn factory_unknown_falls_back_to_noop() {
         let cfg = ObservabilityConfig {
             backend: "xyzzy_garbage_123".into(),
+            ..ObservabilityConfig::default()
         };
         assert_eq!(create_observer(&cfg).name(), "noop");
     }
@@ -100,6 +109,7 @@ mod tests {
     fn factory_empty_string_falls_back_to_noop() {
         let cfg = ObservabilityConfig {
             backend: String::new(),
+            ..ObservabilityConfig::default()
         };
         assert_eq!(create_observer(&cfg).name(), "noop");
     }
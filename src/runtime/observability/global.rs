@@ -0,0 +1,42 @@
+//! Process-wide observer shared by the agent, tools, gateway and daemon.
+//!
+//! A [`PrometheusObserver`] always receives events so `/metrics` can be
+//! served whichever backend is configured.
+
+use super::factory::create_observer;
+use super::multi::MultiObserver;
+use super::prometheus::PrometheusObserver;
+use super::traits::Observer;
+use crate::config::ObservabilityConfig;
+use std::sync::{Arc, OnceLock};
+
+static PROMETHEUS: OnceLock<Arc<PrometheusObserver>> = OnceLock::new();
+static GLOBAL: OnceLock<Arc<dyn Observer>> = OnceLock::new();
+
+/// The observer backing `/metrics`.
+pub fn prometheus_observer() -> Arc<PrometheusObserver> {
+    Arc::clone(PROMETHEUS.get_or_init(|| Arc::new(PrometheusObserver::new())))
+}
+
+/// Install the configured backend alongside the Prometheus observer. Only the
+/// first call takes effect.
+pub fn init_global_observer(config: &ObservabilityConfig) {
+    GLOBAL.get_or_init(|| build_observer(config));
+}
+
+/// The process-wide observer; Prometheus-only until [`init_global_observer`]
+/// runs.
+pub fn global_observer() -> Arc<dyn Observer> {
+    Arc::clone(GLOBAL.get_or_init(|| prometheus_observer()))
+}
+
+fn build_observer(config: &ObservabilityConfig) -> Arc<dyn Observer> {
+    let prometheus = prometheus_observer();
+    match config.backend.as_str() {
+        "prometheus" | "none" | "noop" => prometheus,
+        _ => Arc::new(MultiObserver::new(vec![
+            create_observer(config),
+            Box::new(prometheus),
+        ])),
+    }
+}
@@ -15,15 +15,20 @@ impl LogObserver {
 impl Observer for LogObserver {
     fn record_event(&self, event: &ObserverEvent) {
         match event {
-            ObserverEvent::AgentStart { provider, model } => {
-                info!(provider = %provider, model = %model, "agent.start");
+            ObserverEvent::AgentStart {
+                provider,
+                model,
+                entity_id,
+            } => {
+                info!(provider = %provider, model = %model, entity_id = %entity_id, "agent.start");
             }
             ObserverEvent::AgentEnd {
                 duration,
                 tokens_used,
+                success,
             } => {
                 let ms = u64::try_from(duration.as_millis()).unwrap_or(u64::MAX);
-                info!(duration_ms = ms, tokens = ?tokens_used, "agent.end");
+                info!(duration_ms = ms, tokens = ?tokens_used, success = success, "agent.end");
             }
             ObserverEvent::ToolCall {
                 tool,
@@ -33,6 +38,25 @@ impl Observer for LogObserver {
                 let ms = u64::try_from(duration.as_millis()).unwrap_or(u64::MAX);
                 info!(tool = %tool, duration_ms = ms, success = success, "tool.call");
             }
+            ObserverEvent::ProviderRequest {
+                provider,
+                model,
+                duration,
+                success,
+                ..
+            } => {
+                let ms = u64::try_from(duration.as_millis()).unwrap_or(u64::MAX);
+                info!(provider = %provider, model = %model, duration_ms = ms, success = success, "provider.request");
+            }
+            ObserverEvent::MemoryRecall {
+                entity_id,
+                results,
+                duration,
+                success,
+            } => {
+                let ms = u64::try_from(duration.as_millis()).unwrap_or(u64::MAX);
+                info!(entity_id = %entity_id, results = results, duration_ms = ms, success = success, "memory.recall");
+            }
             ObserverEvent::ChannelMessage { channel, direction } => {
                 info!(channel = %channel, direction = %direction, "channel.message");
             }
@@ -142,14 +166,17 @@ mod tests {
         obs.record_event(&ObserverEvent::AgentStart {
             provider: "openrouter".into(),
             model: "claude-sonnet".into(),
+            entity_id: "default".into(),
         });
         obs.record_event(&ObserverEvent::AgentEnd {
             duration: Duration::from_millis(500),
             tokens_used: Some(100),
+            success: true,
         });
         obs.record_event(&ObserverEvent::AgentEnd {
             duration: Duration::ZERO,
             tokens_used: None,
+            success: false,
         });
         obs.record_event(&ObserverEvent::ToolCall {
             tool: "shell".into(),
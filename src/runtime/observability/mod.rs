@@ -1,15 +1,21 @@
+pub mod exposition;
 mod factory;
+mod global;
 pub mod log;
 pub mod multi;
 pub mod noop;
 pub mod otel;
+mod otlp;
 pub mod prometheus;
+pub mod trace;
 pub mod traits;
 
 pub use self::log::LogObserver;
 pub use self::otel::OtelObserver;
 pub use self::prometheus::PrometheusObserver;
+pub use exposition::{METRICS_CONTENT_TYPE, render_metrics};
 pub use factory::create_observer;
+pub use global::{global_observer, init_global_observer, prometheus_observer};
 pub use multi::MultiObserver;
 pub use noop::NoopObserver;
-pub use traits::{Observer, ObserverEvent};
+pub use traits::{Observer, ObserverEvent, ObserverMetric};
@@ -33,14 +33,17 @@ mod tests {
         obs.record_event(&ObserverEvent::AgentStart {
             provider: "test".into(),
             model: "test".into(),
+            entity_id: "default".into(),
         });
         obs.record_event(&ObserverEvent::AgentEnd {
             duration: Duration::from_millis(100),
             tokens_used: Some(42),
+            success: true,
         });
         obs.record_event(&ObserverEvent::AgentEnd {
             duration: Duration::ZERO,
             tokens_used: None,
+            success: false,
         });
         obs.record_event(&ObserverEvent::ToolCall {
             tool: "shell".into(),
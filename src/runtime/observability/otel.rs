@@ -1,30 +1,68 @@
+//! OpenTelemetry backend: agent turns, tool calls, provider requests, memory
+//! recalls and channel messages become OTLP spans, linked through the
+//! task-local [`super::trace`] context, and metrics are exported as
+//! cumulative sums and gauges over OTLP/HTTP.
+
+use super::otlp::{
+    AttrValue, Attributes, MetricSet, OtlpExporter, SPAN_KIND_CLIENT, SPAN_KIND_INTERNAL,
+    SpanRecord, encode_metrics, encode_traces,
+};
+use super::trace::{SpanContext, current_span};
 use super::traits::{
     AutonomyLifecycleSignal, MemoryLifecycleSignal, Observer, ObserverEvent, ObserverMetric,
 };
+use crate::config::ObservabilityConfig;
+use std::collections::HashMap;
+use std::sync::Mutex;
 use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, SystemTime};
+
+/// Spans buffered before an export is forced even mid-turn.
+const MAX_BATCH: usize = 256;
 
 pub struct OtelObserver {
     event_count: AtomicU64,
     metric_count: AtomicU64,
+    started: SystemTime,
+    exporter: Option<OtlpExporter>,
+    state: Mutex<OtelState>,
+}
+
+#[derive(Default)]
+struct OtelState {
+    open_turns: HashMap<[u8; 8], OpenTurn>,
+    spans: Vec<SpanRecord>,
+    metrics: MetricSet,
+}
+
+struct OpenTurn {
+    started: SystemTime,
+    attributes: Attributes,
 }
 
 impl OtelObserver {
+    /// An observer that records spans and metrics without exporting them.
     #[must_use]
     pub fn new() -> Self {
+        Self::with_exporter(None)
+    }
+
+    /// Export to the collector configured in `observability.otlp_endpoint`.
+    #[must_use]
+    pub fn from_config(config: &ObservabilityConfig) -> Self {
+        Self::with_exporter(Some(OtlpExporter::new(
+            &config.otlp_endpoint,
+            &config.otlp_service_name,
+        )))
+    }
+
+    fn with_exporter(exporter: Option<OtlpExporter>) -> Self {
         Self {
             event_count: AtomicU64::new(0),
             metric_count: AtomicU64::new(0),
-        }
-    }
-
-    fn event_kind(event: &ObserverEvent) -> &'static str {
-        match event {
-            ObserverEvent::AgentStart { .. } => "agent_start",
-            ObserverEvent::AgentEnd { .. } => "agent_end",
-            ObserverEvent::ToolCall { .. } => "tool_call",
-            ObserverEvent::ChannelMessage { .. } => "channel_message",
-            ObserverEvent::HeartbeatTick => "heartbeat_tick",
-            ObserverEvent::Error { .. } => "error",
+            started: SystemTime::now(),
+            exporter,
+            state: Mutex::new(OtelState::default()),
         }
     }
 
@@ -47,6 +85,251 @@ impl OtelObserver {
         }
     }
 
+    /// Record a span that has just finished, nested under the current span.
+    fn finished_span(
+        &self,
+        name: &'static str,
+        kind: u8,
+        duration: Duration,
+        attributes: Attributes,
+        error: bool,
+    ) {
+        let end = SystemTime::now();
+        self.push_span(SpanRecord {
+            context: SpanContext::child_of_current(),
+            name,
+            kind,
+            start: end.checked_sub(duration).unwrap_or(end),
+            end,
+            attributes,
+            error,
+        });
+    }
+
+    fn open_turn(&self, attributes: Attributes) {
+        let Some(span) = current_span() else {
+            return;
+        };
+        if let Ok(mut state) = self.state.lock() {
+            state.open_turns.insert(
+                span.span_id,
+                OpenTurn {
+                    started: SystemTime::now(),
+                    attributes,
+                },
+            );
+        }
+    }
+
+    fn close_turn(&self, duration: Duration, tokens_used: Option<u64>, success: bool) {
+        let Some(span) = current_span() else {
+            return;
+        };
+        let end = SystemTime::now();
+        let turn = self
+            .state
+            .lock()
+            .ok()
+            .and_then(|mut state| state.open_turns.remove(&span.span_id));
+        let (start, mut attributes) = match turn {
+            Some(turn) => (turn.started, turn.attributes),
+            None => (end.checked_sub(duration).unwrap_or(end), Vec::new()),
+        };
+        if let Some(tokens) = tokens_used {
+            attributes.push(("tokens.total", tokens.into()));
+        }
+        self.push_span(SpanRecord {
+            context: span,
+            name: "agent.turn",
+            kind: SPAN_KIND_INTERNAL,
+            start,
+            end,
+            attributes,
+            error: !success,
+        });
+        // A finished top-level turn is the natural point to ship its trace.
+        if span.parent_span_id.is_none() {
+            self.export();
+        }
+    }
+
+    fn push_span(&self, span: SpanRecord) {
+        let full = match self.state.lock() {
+            Ok(mut state) => {
+                state.spans.push(span);
+                state.spans.len() >= MAX_BATCH
+            }
+            Err(_) => false,
+        };
+        if full {
+            self.export();
+        }
+    }
+
+    fn add_sum(&self, name: &str, labels: Vec<(&'static str, String)>, by: u64) {
+        if let Ok(mut state) = self.state.lock() {
+            state.metrics.add(format!("asteroniris.{name}"), labels, by);
+        }
+    }
+
+    fn set_gauge(&self, name: &str, labels: Vec<(&'static str, String)>, value: u64) {
+        if let Ok(mut state) = self.state.lock() {
+            state
+                .metrics
+                .set(format!("asteroniris.{name}"), labels, value);
+        }
+    }
+
+    /// Hand buffered spans and a metrics snapshot to the exporter. Without
+    /// an exporter the buffer is simply cleared.
+    fn export(&self) {
+        let Ok(mut state) = self.state.lock() else {
+            return;
+        };
+        let spans = std::mem::take(&mut state.spans);
+        let Some(exporter) = &self.exporter else {
+            return;
+        };
+        let service_name = exporter.service_name();
+        let traces = (!spans.is_empty()).then(|| encode_traces(service_name, &spans));
+        let metrics = encode_metrics(
+            service_name,
+            &state.metrics,
+            self.started,
+            SystemTime::now(),
+        );
+        drop(state);
+        exporter.send(traces, metrics);
+    }
+
+    fn record_span_event(&self, event: &ObserverEvent) {
+        match event {
+            ObserverEvent::AgentStart {
+                provider,
+                model,
+                entity_id,
+            } => {
+                self.add_sum("agent.turns", Vec::new(), 1);
+                self.open_turn(vec![
+                    ("provider", provider.into()),
+                    ("model", model.into()),
+                    ("entity_id", entity_id.into()),
+                ]);
+            }
+            ObserverEvent::AgentEnd {
+                duration,
+                tokens_used,
+                success,
+            } => self.close_turn(*duration, *tokens_used, *success),
+            ObserverEvent::ToolCall {
+                tool,
+                duration,
+                success,
+            } => {
+                self.add_sum(
+                    "tool.calls",
+                    vec![("tool", tool.clone()), ("success", success.to_string())],
+                    1,
+                );
+                let attributes = vec![("tool.name", tool.into()), ("success", (*success).into())];
+                self.finished_span(
+                    "tool.call",
+                    SPAN_KIND_INTERNAL,
+                    *duration,
+                    attributes,
+                    !success,
+                );
+            }
+            ObserverEvent::ProviderRequest {
+                provider,
+                model,
+                duration,
+                success,
+                input_tokens,
+                output_tokens,
+            } => {
+                let labels = vec![("provider", provider.clone()), ("model", model.clone())];
+                self.add_sum("provider.requests", labels, 1);
+                let mut attributes: Attributes =
+                    vec![("provider", provider.into()), ("model", model.into())];
+                attributes.extend(input_tokens.map(|t| ("tokens.input", AttrValue::Int(t))));
+                attributes.extend(output_tokens.map(|t| ("tokens.output", AttrValue::Int(t))));
+                self.finished_span(
+                    "provider.request",
+                    SPAN_KIND_CLIENT,
+                    *duration,
+                    attributes,
+                    !success,
+                );
+            }
+            _ => self.record_other_event(event),
+        }
+    }
+
+    fn record_other_event(&self, event: &ObserverEvent) {
+        match event {
+            ObserverEvent::MemoryRecall {
+                entity_id,
+                results,
+                duration,
+                success,
+            } => {
+                self.add_sum("memory.recalls", Vec::new(), 1);
+                let results = u64::try_from(*results).unwrap_or(u64::MAX);
+                let attributes = vec![("entity_id", entity_id.into()), ("results", results.into())];
+                self.finished_span(
+                    "memory.recall",
+                    SPAN_KIND_INTERNAL,
+                    *duration,
+                    attributes,
+                    !success,
+                );
+            }
+            ObserverEvent::ChannelMessage { channel, direction } => {
+                self.add_sum(
+                    "channel.messages",
+                    vec![
+                        ("channel", channel.clone()),
+                        ("direction", direction.clone()),
+                    ],
+                    1,
+                );
+                let attributes = vec![("channel", channel.into()), ("direction", direction.into())];
+                self.finished_span(
+                    "channel.message",
+                    SPAN_KIND_INTERNAL,
+                    Duration::ZERO,
+                    attributes,
+                    false,
+                );
+            }
+            ObserverEvent::Error { component, message } => {
+                self.add_sum("errors", vec![("component", component.clone())], 1);
+                let attributes = vec![("component", component.into()), ("message", message.into())];
+                self.finished_span(
+                    "error",
+                    SPAN_KIND_INTERNAL,
+                    Duration::ZERO,
+                    attributes,
+                    true,
+                );
+            }
+            ObserverEvent::HeartbeatTick => self.add_sum("heartbeat.ticks", Vec::new(), 1),
+            ObserverEvent::AgentStart { .. }
+            | ObserverEvent::AgentEnd { .. }
+            | ObserverEvent::ToolCall { .. }
+            | ObserverEvent::ProviderRequest { .. } => {}
+        }
+    }
+
+    #[cfg(test)]
+    fn buffered_spans(&self) -> Vec<SpanRecord> {
+        self.state
+            .lock()
+            .map(|s| s.spans.clone())
+            .unwrap_or_default()
+    }
+
     #[cfg(test)]
     fn snapshot_counts(&self) -> (u64, u64) {
         (
@@ -87,20 +370,48 @@ fn memory_signal_name(signal: MemoryLifecycleSignal) -> &'static str {
 impl Observer for OtelObserver {
     fn record_event(&self, event: &ObserverEvent) {
         self.event_count.fetch_add(1, Ordering::Relaxed);
-        tracing::debug!(event = Self::event_kind(event), "observer.otel.event");
+        self.record_span_event(event);
     }
 
     fn record_metric(&self, metric: &ObserverMetric) {
         self.metric_count.fetch_add(1, Ordering::Relaxed);
-        tracing::debug!(metric = Self::metric_kind(metric), "observer.otel.metric");
+        match metric {
+            ObserverMetric::RequestLatency(latency) => {
+                let ms = u64::try_from(latency.as_millis()).unwrap_or(u64::MAX);
+                self.add_sum("request.duration_ms", Vec::new(), ms);
+                self.add_sum("requests", Vec::new(), 1);
+            }
+            ObserverMetric::TokensUsed(tokens) => self.add_sum("tokens.used", Vec::new(), *tokens),
+            ObserverMetric::ActiveSessions(count) => {
+                self.set_gauge("sessions.active", Vec::new(), *count);
+            }
+            ObserverMetric::QueueDepth(depth) => self.set_gauge("queue.depth", Vec::new(), *depth),
+            ObserverMetric::SignalIngestTotal { source_kind }
+            | ObserverMetric::SignalDedupDropTotal { source_kind } => {
+                let labels = vec![("source_kind", source_kind.clone())];
+                self.add_sum(Self::metric_kind(metric), labels, 1);
+            }
+            ObserverMetric::BeliefPromotionTotal { count }
+            | ObserverMetric::ContradictionMarkTotal { count }
+            | ObserverMetric::StaleTrendPurgeTotal { count } => {
+                self.set_gauge(Self::metric_kind(metric), Vec::new(), *count);
+            }
+            ObserverMetric::SignalTierSnapshot { tier, count } => {
+                self.set_gauge("signal_tier", vec![("tier", tier.clone())], *count);
+            }
+            ObserverMetric::PromotionStatusSnapshot { status, count } => {
+                self.set_gauge("promotion_status", vec![("status", status.clone())], *count);
+            }
+            ObserverMetric::MemorySloViolation
+            | ObserverMetric::AutonomyLifecycle(_)
+            | ObserverMetric::MemoryLifecycle(_) => {
+                self.add_sum(Self::metric_kind(metric), Vec::new(), 1);
+            }
+        }
     }
 
     fn flush(&self) {
-        tracing::debug!(
-            events_total = self.event_count.load(Ordering::Relaxed),
-            metrics_total = self.metric_count.load(Ordering::Relaxed),
-            "observer.otel.flush"
-        );
+        self.export();
     }
 
     fn name(&self) -> &str {
@@ -125,6 +436,7 @@ mod tests {
         obs.record_event(&ObserverEvent::AgentStart {
             provider: "openrouter".into(),
             model: "gpt-5".into(),
+            entity_id: "default".into(),
         });
         obs.record_event(&ObserverEvent::HeartbeatTick);
         obs.record_metric(&ObserverMetric::RequestLatency(Duration::from_millis(5)));
@@ -151,4 +463,134 @@ mod tests {
 
         assert_eq!(obs.snapshot_counts(), (2, 10));
     }
+
+    #[tokio::test]
+    async fn otel_observer_nests_spans_under_the_turn() {
+        let obs = OtelObserver::new();
+        let outer = SpanContext::root();
+        let turn = outer.child();
+
+        super::super::trace::in_span(turn, async {
+            obs.record_event(&ObserverEvent::AgentStart {
+                provider: "anthropic".into(),
+                model: "claude".into(),
+                entity_id: "user-1".into(),
+            });
+            obs.record_event(&ObserverEvent::ProviderRequest {
+                provider: "anthropic".into(),
+                model: "claude".into(),
+                duration: Duration::from_millis(40),
+                success: true,
+                input_tokens: Some(12),
+                output_tokens: Some(3),
+            });
+            obs.record_event(&ObserverEvent::ToolCall {
+                tool: "shell".into(),
+                duration: Duration::from_millis(5),
+                success: false,
+            });
+            obs.record_event(&ObserverEvent::AgentEnd {
+                duration: Duration::from_millis(50),
+                tokens_used: Some(15),
+                success: true,
+            });
+        })
+        .await;
+
+        let spans = obs.buffered_spans();
+        let names: Vec<_> = spans.iter().map(|span| span.name).collect();
+        assert_eq!(names, ["provider.request", "tool.call", "agent.turn"]);
+        for span in &spans {
+            assert_eq!(span.context.trace_id, outer.trace_id);
+        }
+        assert_eq!(spans[0].context.parent_span_id, Some(turn.span_id));
+        assert!(spans[1].error);
+        assert_eq!(spans[2].context, turn);
+        assert!(!spans[2].error);
+        assert!(
+            spans[2]
+                .attributes
+                .contains(&("entity_id", AttrValue::from("user-1")))
+        );
+        assert!(
+            spans[2]
+                .attributes
+                .contains(&("tokens.total", AttrValue::Int(15)))
+        );
+
+        let encoded = encode_traces("asteroniris", &spans);
+        let first = &encoded["resourceSpans"][0]["scopeSpans"][0]["spans"][0];
+        assert_eq!(first["parentSpanId"], hex::encode(turn.span_id));
+        assert_eq!(first["kind"], SPAN_KIND_CLIENT);
+    }
+
+    #[tokio::test]
+    async fn cancelled_turn_is_closed_as_an_error_by_its_guard() {
+        use super::super::trace::{TurnGuard, in_span, in_turn_span};
+        use std::sync::Arc;
+
+        let obs = Arc::new(OtelObserver::new());
+        // Nested, so closing the turn does not export (and clear) the buffer.
+        let turn = SpanContext::root().child();
+        let observer: Arc<dyn Observer> = obs.clone();
+        let (started_tx, started_rx) = tokio::sync::oneshot::channel();
+        let task = tokio::spawn(in_turn_span(turn, async move {
+            observer.record_event(&ObserverEvent::ChannelMessage {
+                channel: "telegram".into(),
+                direction: "inbound".into(),
+            });
+            in_span(SpanContext::for_turn(), async {
+                observer.record_event(&ObserverEvent::AgentStart {
+                    provider: "anthropic".into(),
+                    model: "claude".into(),
+                    entity_id: "user-1".into(),
+                });
+                let _turn = TurnGuard::start(Arc::clone(&observer));
+                started_tx.send(()).unwrap();
+                std::future::pending::<()>().await;
+            })
+            .await;
+        }));
+        started_rx.await.unwrap();
+        task.abort();
+        assert!(task.await.unwrap_err().is_cancelled());
+
+        assert!(obs.state.lock().unwrap().open_turns.is_empty());
+        let spans = obs.buffered_spans();
+        let names: Vec<_> = spans.iter().map(|span| span.name).collect();
+        assert_eq!(names, ["channel.message", "agent.turn"]);
+        assert_eq!(spans[0].context.parent_span_id, Some(turn.span_id));
+        assert_eq!(spans[1].context, turn);
+        assert!(spans[1].error);
+    }
+
+    #[test]
+    fn otel_metrics_encode_as_cumulative_sums_and_gauges() {
+        let obs = OtelObserver::new();
+        obs.record_metric(&ObserverMetric::TokensUsed(10));
+        obs.record_metric(&ObserverMetric::TokensUsed(5));
+        obs.record_metric(&ObserverMetric::QueueDepth(3));
+
+        let state = obs.state.lock().unwrap();
+        let encoded = encode_metrics(
+            "asteroniris",
+            &state.metrics,
+            obs.started,
+            SystemTime::now(),
+        );
+        let metrics = encoded["resourceMetrics"][0]["scopeMetrics"][0]["metrics"]
+            .as_array()
+            .unwrap();
+        let tokens = metrics
+            .iter()
+            .find(|m| m["name"] == "asteroniris.tokens.used")
+            .unwrap();
+        assert_eq!(tokens["sum"]["dataPoints"][0]["asInt"], "15");
+        assert_eq!(tokens["sum"]["isMonotonic"], true);
+        let depth = metrics
+            .iter()
+            .find(|m| m["name"] == "asteroniris.queue.depth")
+            .unwrap();
+        assert_eq!(depth["gauge"]["dataPoints"][0]["asInt"], "3");
+    }
 }
@@ -0,0 +1,268 @@
+//! OTLP/HTTP JSON encoding and export for [`super::OtelObserver`].
+
+use super::trace::SpanContext;
+use serde_json::{Value, json};
+use std::collections::BTreeMap;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+const SCOPE_NAME: &str = "asteroniris";
+
+/// Span kinds from the OTLP protocol.
+pub(super) const SPAN_KIND_INTERNAL: u8 = 1;
+pub(super) const SPAN_KIND_CLIENT: u8 = 3;
+
+const STATUS_CODE_ERROR: u8 = 2;
+const TEMPORALITY_CUMULATIVE: u8 = 2;
+
+#[derive(Debug, Clone, PartialEq)]
+pub(super) enum AttrValue {
+    Str(String),
+    Int(u64),
+    Bool(bool),
+}
+
+impl From<&str> for AttrValue {
+    fn from(value: &str) -> Self {
+        Self::Str(value.to_string())
+    }
+}
+
+impl From<&String> for AttrValue {
+    fn from(value: &String) -> Self {
+        Self::Str(value.clone())
+    }
+}
+
+impl From<u64> for AttrValue {
+    fn from(value: u64) -> Self {
+        Self::Int(value)
+    }
+}
+
+impl From<bool> for AttrValue {
+    fn from(value: bool) -> Self {
+        Self::Bool(value)
+    }
+}
+
+pub(super) type Attributes = Vec<(&'static str, AttrValue)>;
+
+/// A finished span waiting to be exported.
+#[derive(Debug, Clone)]
+pub(super) struct SpanRecord {
+    pub context: SpanContext,
+    pub name: &'static str,
+    pub kind: u8,
+    pub start: SystemTime,
+    pub end: SystemTime,
+    pub attributes: Attributes,
+    pub error: bool,
+}
+
+/// Metric series identity: name plus string labels.
+pub(super) type MetricKey = (String, Vec<(&'static str, String)>);
+
+/// Cumulative sums and last-value gauges accumulated since start-up.
+#[derive(Debug, Default)]
+pub(super) struct MetricSet {
+    pub sums: BTreeMap<MetricKey, u64>,
+    pub gauges: BTreeMap<MetricKey, u64>,
+}
+
+impl MetricSet {
+    pub fn add(&mut self, name: impl Into<String>, labels: Vec<(&'static str, String)>, by: u64) {
+        let entry = self.sums.entry((name.into(), labels)).or_insert(0);
+        *entry = entry.saturating_add(by);
+    }
+
+    pub fn set(
+        &mut self,
+        name: impl Into<String>,
+        labels: Vec<(&'static str, String)>,
+        value: u64,
+    ) {
+        self.gauges.insert((name.into(), labels), value);
+    }
+}
+
+/// Posts encoded batches to an OTLP/HTTP collector.
+pub(super) struct OtlpExporter {
+    client: reqwest::Client,
+    traces_url: String,
+    metrics_url: String,
+    service_name: String,
+}
+
+impl OtlpExporter {
+    pub fn new(endpoint: &str, service_name: &str) -> Self {
+        let base = endpoint.trim_end_matches('/');
+        let client = reqwest::Client::builder()
+            .connect_timeout(Duration::from_secs(3))
+            .timeout(Duration::from_secs(10))
+            .build()
+            .unwrap_or_else(|_| reqwest::Client::new());
+
+        Self {
+            client,
+            traces_url: format!("{base}/v1/traces"),
+            metrics_url: format!("{base}/v1/metrics"),
+            service_name: service_name.to_string(),
+        }
+    }
+
+    pub fn service_name(&self) -> &str {
+        &self.service_name
+    }
+
+    /// Send in the background; batches are dropped when no runtime is
+    /// available (e.g. during shutdown).
+    pub fn send(&self, traces: Option<Value>, metrics: Value) {
+        let Ok(handle) = tokio::runtime::Handle::try_current() else {
+            tracing::debug!("observer.otel: no async runtime, dropping OTLP batch");
+            return;
+        };
+        let client = self.client.clone();
+        let traces_url = self.traces_url.clone();
+        let metrics_url = self.metrics_url.clone();
+        handle.spawn(async move {
+            if let Some(traces) = traces {
+                post(&client, &traces_url, &traces).await;
+            }
+            post(&client, &metrics_url, &metrics).await;
+        });
+    }
+}
+
+async fn post(client: &reqwest::Client, url: &str, body: &Value) {
+    match client.post(url).json(body).send().await {
+        Ok(response) if !response.status().is_success() => {
+            tracing::warn!(url, status = %response.status(), "OTLP export rejected");
+        }
+        Ok(_) => {}
+        Err(error) => tracing::warn!(url, %error, "OTLP export failed"),
+    }
+}
+
+pub(super) fn encode_traces(service_name: &str, spans: &[SpanRecord]) -> Value {
+    let spans: Vec<Value> = spans.iter().map(encode_span).collect();
+    json!({
+        "resourceSpans": [{
+            "resource": resource(service_name),
+            "scopeSpans": [{ "scope": { "name": SCOPE_NAME }, "spans": spans }],
+        }],
+    })
+}
+
+fn encode_span(span: &SpanRecord) -> Value {
+    let mut value = json!({
+        "traceId": hex::encode(span.context.trace_id),
+        "spanId": hex::encode(span.context.span_id),
+        "name": span.name,
+        "kind": span.kind,
+        "startTimeUnixNano": unix_nanos(span.start),
+        "endTimeUnixNano": unix_nanos(span.end),
+        "attributes": encode_attributes(&span.attributes),
+    });
+    if let Some(parent) = span.context.parent_span_id {
+        value["parentSpanId"] = Value::String(hex::encode(parent));
+    }
+    if span.error {
+        value["status"] = json!({ "code": STATUS_CODE_ERROR });
+    }
+    value
+}
+
+pub(super) fn encode_metrics(
+    service_name: &str,
+    metrics: &MetricSet,
+    started: SystemTime,
+    now: SystemTime,
+) -> Value {
+    let start = unix_nanos(started);
+    let time = unix_nanos(now);
+    let mut encoded = Vec::new();
+
+    for (name, series) in group_by_name(&metrics.sums) {
+        let points = data_points(&series, Some(&start), &time);
+        encoded.push(json!({
+            "name": name,
+            "sum": {
+                "dataPoints": points,
+                "aggregationTemporality": TEMPORALITY_CUMULATIVE,
+                "isMonotonic": true,
+            },
+        }));
+    }
+    for (name, series) in group_by_name(&metrics.gauges) {
+        let points = data_points(&series, None, &time);
+        encoded.push(json!({ "name": name, "gauge": { "dataPoints": points } }));
+    }
+
+    json!({
+        "resourceMetrics": [{
+            "resource": resource(service_name),
+            "scopeMetrics": [{ "scope": { "name": SCOPE_NAME }, "metrics": encoded }],
+        }],
+    })
+}
+
+type Series<'a> = Vec<(&'a [(&'static str, String)], u64)>;
+
+fn group_by_name(values: &BTreeMap<MetricKey, u64>) -> BTreeMap<&str, Series<'_>> {
+    let mut grouped: BTreeMap<&str, Series<'_>> = BTreeMap::new();
+    for ((name, labels), value) in values {
+        grouped
+            .entry(name.as_str())
+            .or_default()
+            .push((labels.as_slice(), *value));
+    }
+    grouped
+}
+
+fn data_points(series: &Series<'_>, start: Option<&str>, time: &str) -> Vec<Value> {
+    series
+        .iter()
+        .map(|(labels, value)| {
+            let attributes: Vec<Value> = labels
+                .iter()
+                .map(|(key, value)| attribute(key, &AttrValue::Str(value.clone())))
+                .collect();
+            let mut point = json!({
+                "attributes": attributes,
+                "timeUnixNano": time,
+                "asInt": value.to_string(),
+            });
+            if let Some(start) = start {
+                point["startTimeUnixNano"] = Value::String(start.to_string());
+            }
+            point
+        })
+        .collect()
+}
+
+fn resource(service_name: &str) -> Value {
+    json!({ "attributes": [attribute("service.name", &AttrValue::from(service_name))] })
+}
+
+fn encode_attributes(attributes: &Attributes) -> Vec<Value> {
+    attributes
+        .iter()
+        .map(|(key, value)| attribute(key, value))
+        .collect()
+}
+
+fn attribute(key: &str, value: &AttrValue) -> Value {
+    let value = match value {
+        AttrValue::Str(s) => json!({ "stringValue": s }),
+        AttrValue::Int(i) => json!({ "intValue": i.to_string() }),
+        AttrValue::Bool(b) => json!({ "boolValue": b }),
+    };
+    json!({ "key": key, "value": value })
+}
+
+fn unix_nanos(time: SystemTime) -> String {
+    time.duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos()
+        .to_string()
+}
@@ -1,9 +1,63 @@
+use super::exposition::MetricsWriter;
 use super::traits::{
     AutonomyLifecycleSignal, MemoryLifecycleSignal, Observer, ObserverEvent, ObserverMetric,
 };
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::sync::Mutex;
 use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+/// Upper bounds (seconds) shared by the latency histograms.
+const LATENCY_BUCKETS: [f64; 12] = [
+    0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0,
+];
+
+/// Cumulative histogram with fixed [`LATENCY_BUCKETS`].
+struct LatencyHistogram {
+    buckets: [AtomicU64; LATENCY_BUCKETS.len()],
+    count: AtomicU64,
+    sum_micros: AtomicU64,
+}
+
+impl LatencyHistogram {
+    fn new() -> Self {
+        Self {
+            buckets: std::array::from_fn(|_| AtomicU64::new(0)),
+            count: AtomicU64::new(0),
+            sum_micros: AtomicU64::new(0),
+        }
+    }
+
+    fn observe(&self, duration: Duration) {
+        let seconds = duration.as_secs_f64();
+        for (bound, bucket) in LATENCY_BUCKETS.iter().zip(&self.buckets) {
+            if seconds <= *bound {
+                bucket.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+        self.count.fetch_add(1, Ordering::Relaxed);
+        let micros = u64::try_from(duration.as_micros()).unwrap_or(u64::MAX);
+        self.sum_micros.fetch_add(micros, Ordering::Relaxed);
+    }
+
+    fn render(&self, out: &mut MetricsWriter, name: &str, help: &str) {
+        out.family(name, "histogram", help);
+        let bucket_name = format!("{name}_bucket");
+        for (bound, bucket) in LATENCY_BUCKETS.iter().zip(&self.buckets) {
+            out.sample(
+                &bucket_name,
+                &[("le", &bound.to_string())],
+                bucket.load(Ordering::Relaxed),
+            );
+        }
+        let count = self.count.load(Ordering::Relaxed);
+        out.sample(&bucket_name, &[("le", "+Inf")], count);
+        #[allow(clippy::cast_precision_loss)]
+        let sum = self.sum_micros.load(Ordering::Relaxed) as f64 / 1_000_000.0;
+        out.sample(&format!("{name}_sum"), &[], sum);
+        out.sample(&format!("{name}_count"), &[], count);
+    }
+}
 
 pub struct PrometheusObserver {
     event_count: AtomicU64,
@@ -37,6 +91,13 @@ pub struct PrometheusObserver {
     stale_trend_purge_total_snapshot: AtomicU64,
     signal_tier_snapshot: Mutex<HashMap<String, u64>>,
     promotion_status_snapshot: Mutex<HashMap<String, u64>>,
+    request_latency: LatencyHistogram,
+    agent_turn_duration: LatencyHistogram,
+    tokens_used_total: AtomicU64,
+    active_sessions: AtomicU64,
+    queue_depth: AtomicU64,
+    tool_calls: Mutex<BTreeMap<(String, &'static str), u64>>,
+    channel_messages: Mutex<BTreeMap<(String, String), u64>>,
 }
 
 #[cfg(test)]
@@ -116,6 +177,13 @@ impl PrometheusObserver {
             stale_trend_purge_total_snapshot: AtomicU64::new(0),
             signal_tier_snapshot: Mutex::new(HashMap::new()),
             promotion_status_snapshot: Mutex::new(HashMap::new()),
+            request_latency: LatencyHistogram::new(),
+            agent_turn_duration: LatencyHistogram::new(),
+            tokens_used_total: AtomicU64::new(0),
+            active_sessions: AtomicU64::new(0),
+            queue_depth: AtomicU64::new(0),
+            tool_calls: Mutex::new(BTreeMap::new()),
+            channel_messages: Mutex::new(BTreeMap::new()),
         }
     }
 
@@ -245,6 +313,219 @@ impl PrometheusObserver {
         }
     }
 
+    /// Write every counter, gauge and histogram in text exposition format.
+    pub fn render(&self, out: &mut MetricsWriter) {
+        self.render_totals(out);
+        self.render_lifecycle(out);
+        self.render_signals(out);
+        self.render_activity(out);
+    }
+
+    fn render_totals(&self, out: &mut MetricsWriter) {
+        for (name, help, value) in [
+            (
+                "asteroniris_observer_events_total",
+                "Observer events recorded.",
+                &self.event_count,
+            ),
+            (
+                "asteroniris_observer_metrics_total",
+                "Observer metrics recorded.",
+                &self.metric_count,
+            ),
+            (
+                "asteroniris_errors_total",
+                "Error events recorded.",
+                &self.error_count,
+            ),
+            (
+                "asteroniris_tokens_used_total",
+                "LLM tokens used by agent turns.",
+                &self.tokens_used_total,
+            ),
+            (
+                "asteroniris_memory_slo_violations_total",
+                "Memory SLO violations.",
+                &self.memory_slo_violation_count,
+            ),
+        ] {
+            out.family(name, "counter", help);
+            out.sample(name, &[], value.load(Ordering::Relaxed));
+        }
+    }
+
+    fn render_lifecycle(&self, out: &mut MetricsWriter) {
+        out.family(
+            "asteroniris_autonomy_lifecycle_total",
+            "counter",
+            "Autonomy lifecycle signals by kind.",
+        );
+        for (signal, value) in [
+            ("ingested", &self.autonomy_ingested_count),
+            ("deduplicated", &self.autonomy_deduplicated_count),
+            ("promoted", &self.autonomy_promoted_count),
+            ("contradiction_detected", &self.autonomy_contradiction_count),
+            ("mode_transition", &self.autonomy_mode_transition_count),
+            ("intent_created", &self.autonomy_intent_created_count),
+            (
+                "intent_policy_allowed",
+                &self.autonomy_intent_policy_allowed_count,
+            ),
+            (
+                "intent_policy_denied",
+                &self.autonomy_intent_policy_denied_count,
+            ),
+            ("intent_dispatched", &self.autonomy_intent_dispatched_count),
+            (
+                "intent_execution_blocked",
+                &self.autonomy_intent_execution_blocked_count,
+            ),
+        ] {
+            out.sample(
+                "asteroniris_autonomy_lifecycle_total",
+                &[("signal", signal)],
+                value.load(Ordering::Relaxed),
+            );
+        }
+
+        out.family(
+            "asteroniris_memory_lifecycle_total",
+            "counter",
+            "Memory lifecycle signals by kind.",
+        );
+        for (signal, value) in [
+            (
+                "consolidation_started",
+                &self.memory_consolidation_started_count,
+            ),
+            (
+                "consolidation_completed",
+                &self.memory_consolidation_completed_count,
+            ),
+            ("conflict_detected", &self.memory_conflict_detected_count),
+            ("conflict_resolved", &self.memory_conflict_resolved_count),
+            ("revocation_applied", &self.memory_revocation_applied_count),
+            ("governance_inspect", &self.memory_governance_inspect_count),
+            ("governance_export", &self.memory_governance_export_count),
+            ("governance_delete", &self.memory_governance_delete_count),
+        ] {
+            out.sample(
+                "asteroniris_memory_lifecycle_total",
+                &[("signal", signal)],
+                value.load(Ordering::Relaxed),
+            );
+        }
+    }
+
+    fn render_signals(&self, out: &mut MetricsWriter) {
+        render_labelled(
+            out,
+            "asteroniris_signal_ingest_total",
+            "counter",
+            "Signals ingested by source kind.",
+            "source",
+            &self.signal_ingest_by_source,
+        );
+        render_labelled(
+            out,
+            "asteroniris_signal_dedup_drop_total",
+            "counter",
+            "Signals dropped as duplicates by source kind.",
+            "source",
+            &self.signal_dedup_drop_by_source,
+        );
+        render_labelled(
+            out,
+            "asteroniris_signal_tier",
+            "gauge",
+            "Signals per tier at the last snapshot.",
+            "tier",
+            &self.signal_tier_snapshot,
+        );
+        render_labelled(
+            out,
+            "asteroniris_promotion_status",
+            "gauge",
+            "Beliefs per promotion status at the last snapshot.",
+            "status",
+            &self.promotion_status_snapshot,
+        );
+
+        for (name, help, value) in [
+            (
+                "asteroniris_belief_promotions",
+                "Belief promotions at the last snapshot.",
+                &self.belief_promotion_total_snapshot,
+            ),
+            (
+                "asteroniris_contradiction_marks",
+                "Contradiction marks at the last snapshot.",
+                &self.contradiction_mark_total_snapshot,
+            ),
+            (
+                "asteroniris_stale_trend_purges",
+                "Stale trend purges at the last snapshot.",
+                &self.stale_trend_purge_total_snapshot,
+            ),
+            (
+                "asteroniris_active_sessions",
+                "Active sessions.",
+                &self.active_sessions,
+            ),
+            (
+                "asteroniris_queue_depth",
+                "Pending work items.",
+                &self.queue_depth,
+            ),
+        ] {
+            out.family(name, "gauge", help);
+            out.sample(name, &[], value.load(Ordering::Relaxed));
+        }
+    }
+
+    fn render_activity(&self, out: &mut MetricsWriter) {
+        out.family(
+            "asteroniris_tool_calls_total",
+            "counter",
+            "Tool calls by tool and outcome.",
+        );
+        if let Ok(guard) = self.tool_calls.lock() {
+            for ((tool, outcome), count) in guard.iter() {
+                out.sample(
+                    "asteroniris_tool_calls_total",
+                    &[("tool", tool), ("outcome", outcome)],
+                    count,
+                );
+            }
+        }
+
+        out.family(
+            "asteroniris_channel_messages_total",
+            "counter",
+            "Channel messages by channel and direction.",
+        );
+        if let Ok(guard) = self.channel_messages.lock() {
+            for ((channel, direction), count) in guard.iter() {
+                out.sample(
+                    "asteroniris_channel_messages_total",
+                    &[("channel", channel), ("direction", direction)],
+                    count,
+                );
+            }
+        }
+
+        self.request_latency.render(
+            out,
+            "asteroniris_request_latency_seconds",
+            "Gateway request latency.",
+        );
+        self.agent_turn_duration.render(
+            out,
+            "asteroniris_agent_turn_duration_seconds",
+            "Agent turn duration.",
+        );
+    }
+
     #[cfg(test)]
     fn snapshot_counts(&self) -> (u64, u64, u64) {
         (
@@ -339,11 +620,53 @@ impl PrometheusObserver {
     }
 }
 
+fn render_labelled(
+    out: &mut MetricsWriter,
+    name: &str,
+    kind: &str,
+    help: &str,
+    label: &str,
+    values: &Mutex<HashMap<String, u64>>,
+) {
+    out.family(name, kind, help);
+    let Ok(guard) = values.lock() else {
+        return;
+    };
+    let sorted = guard.iter().collect::<BTreeMap<_, _>>();
+    for (value, count) in sorted {
+        out.sample(name, &[(label, value)], count);
+    }
+}
+
 impl Observer for PrometheusObserver {
     fn record_event(&self, event: &ObserverEvent) {
         self.event_count.fetch_add(1, Ordering::Relaxed);
-        if matches!(event, ObserverEvent::Error { .. }) {
-            self.error_count.fetch_add(1, Ordering::Relaxed);
+        match event {
+            ObserverEvent::Error { .. } => {
+                self.error_count.fetch_add(1, Ordering::Relaxed);
+            }
+            ObserverEvent::ToolCall { tool, success, .. } => {
+                let outcome = if *success { "success" } else { "failure" };
+                if let Ok(mut guard) = self.tool_calls.lock() {
+                    let entry = guard.entry((tool.clone(), outcome)).or_insert(0);
+                    *entry = entry.saturating_add(1);
+                }
+            }
+            ObserverEvent::ChannelMessage { channel, direction } => {
+                if let Ok(mut guard) = self.channel_messages.lock() {
+                    let entry = guard
+                        .entry((channel.clone(), direction.clone()))
+                        .or_insert(0);
+                    *entry = entry.saturating_add(1);
+                }
+            }
+            ObserverEvent::AgentEnd { duration, .. } => {
+                self.agent_turn_duration.observe(*duration);
+            }
+            ObserverEvent::AgentStart { .. }
+            | ObserverEvent::ProviderRequest { .. }
+            | ObserverEvent::MemoryRecall { .. }
+            | ObserverEvent::HeartbeatTick => {}
         }
     }
 
@@ -377,10 +700,16 @@ impl Observer for PrometheusObserver {
             }
             ObserverMetric::AutonomyLifecycle(signal) => self.record_autonomy_signal(*signal),
             ObserverMetric::MemoryLifecycle(signal) => self.record_memory_signal(*signal),
-            ObserverMetric::RequestLatency(_)
-            | ObserverMetric::TokensUsed(_)
-            | ObserverMetric::ActiveSessions(_)
-            | ObserverMetric::QueueDepth(_) => {}
+            ObserverMetric::RequestLatency(duration) => self.request_latency.observe(*duration),
+            ObserverMetric::TokensUsed(tokens) => {
+                self.tokens_used_total.fetch_add(*tokens, Ordering::Relaxed);
+            }
+            ObserverMetric::ActiveSessions(count) => {
+                self.active_sessions.store(*count, Ordering::Relaxed);
+            }
+            ObserverMetric::QueueDepth(depth) => {
+                self.queue_depth.store(*depth, Ordering::Relaxed);
+            }
         }
     }
 
@@ -490,4 +819,41 @@ mod tests {
         assert_eq!(signal.tier_snapshot.get("raw"), Some(&5));
         assert_eq!(signal.promotion_status_snapshot.get("candidate"), Some(&4));
     }
+
+    #[test]
+    fn prometheus_render_includes_tool_calls_and_histograms() {
+        let obs = PrometheusObserver::new();
+        obs.record_event(&ObserverEvent::ToolCall {
+            tool: "shell".into(),
+            duration: Duration::from_millis(5),
+            success: true,
+        });
+        obs.record_event(&ObserverEvent::ToolCall {
+            tool: "shell".into(),
+            duration: Duration::from_millis(5),
+            success: false,
+        });
+        obs.record_metric(&ObserverMetric::RequestLatency(Duration::from_millis(30)));
+        obs.record_metric(&ObserverMetric::TokensUsed(42));
+        obs.record_metric(&ObserverMetric::AutonomyLifecycle(
+            AutonomyLifecycleSignal::Promoted,
+        ));
+
+        let mut out = MetricsWriter::default();
+        obs.render(&mut out);
+        let text = out.finish();
+
+        assert!(
+            text.contains("asteroniris_tool_calls_total{tool=\"shell\",outcome=\"success\"} 1")
+        );
+        assert!(
+            text.contains("asteroniris_tool_calls_total{tool=\"shell\",outcome=\"failure\"} 1")
+        );
+        assert!(text.contains("asteroniris_tokens_used_total 42"));
+        assert!(text.contains("asteroniris_autonomy_lifecycle_total{signal=\"promoted\"} 1"));
+        assert!(text.contains("asteroniris_request_latency_seconds_bucket{le=\"0.025\"} 0"));
+        assert!(text.contains("asteroniris_request_latency_seconds_bucket{le=\"0.05\"} 1"));
+        assert!(text.contains("asteroniris_request_latency_seconds_count 1"));
+        assert!(text.contains("# TYPE asteroniris_agent_turn_duration_seconds histogram"));
+    }
 }
@@ -0,0 +1,166 @@
+//! Task-local trace context so events recorded deep inside an agent turn
+//! (tool calls, provider requests, memory recalls) can be attached to the
+//! turn's span without threading ids through every call.
+
+use super::traits::{Observer, ObserverEvent};
+use std::cell::Cell;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Instant;
+
+tokio::task_local! {
+    static CURRENT_SPAN: SpanContext;
+    /// Span set aside by [`in_turn_span`] for the next agent turn.
+    static RESERVED_TURN: Cell<Option<SpanContext>>;
+}
+
+/// Identifies one span within a trace.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SpanContext {
+    pub trace_id: [u8; 16],
+    pub span_id: [u8; 8],
+    pub parent_span_id: Option<[u8; 8]>,
+}
+
+impl SpanContext {
+    /// Start a new trace.
+    pub fn root() -> Self {
+        Self {
+            trace_id: rand::random(),
+            span_id: rand::random(),
+            parent_span_id: None,
+        }
+    }
+
+    /// A span nested under this one in the same trace.
+    #[must_use]
+    pub fn child(&self) -> Self {
+        Self {
+            trace_id: self.trace_id,
+            span_id: rand::random(),
+            parent_span_id: Some(self.span_id),
+        }
+    }
+
+    /// A child of the current span, or a new trace when none is active.
+    pub fn child_of_current() -> Self {
+        current_span().map_or_else(Self::root, |span| span.child())
+    }
+
+    /// The span for an agent turn: the one reserved by an enclosing
+    /// [`in_turn_span`] for the first turn started in it, otherwise a child
+    /// of the current span.
+    pub fn for_turn() -> Self {
+        RESERVED_TURN
+            .try_with(Cell::take)
+            .ok()
+            .flatten()
+            .unwrap_or_else(Self::child_of_current)
+    }
+}
+
+/// The span of the enclosing [`in_span`] scope, if any.
+pub fn current_span() -> Option<SpanContext> {
+    CURRENT_SPAN.try_with(|span| *span).ok()
+}
+
+/// Run `fut` with `span` as the current span.
+pub async fn in_span<F: Future>(span: SpanContext, fut: F) -> F::Output {
+    CURRENT_SPAN.scope(span, fut).await
+}
+
+/// Like [`in_span`], but the first agent turn started inside `fut` takes
+/// `span` as its own, so events recorded around the turn (the channel
+/// messages that start and answer it) nest under the turn span.
+pub async fn in_turn_span<F: Future>(span: SpanContext, fut: F) -> F::Output {
+    RESERVED_TURN
+        .scope(Cell::new(Some(span)), CURRENT_SPAN.scope(span, fut))
+        .await
+}
+
+/// Records [`ObserverEvent::AgentEnd`] for a turn, as a failure if the turn
+/// is dropped before [`TurnGuard::finish`], e.g. when its task is cancelled.
+/// Observers holding per-turn state (such as an open trace span) rely on seeing the
+/// end of every turn they saw start.
+///
+/// Create it inside the turn's [`in_span`] scope: tokio drops a cancelled
+/// future with its task-locals still set, so the end is recorded against
+/// the right span either way.
+pub struct TurnGuard {
+    observer: Arc<dyn Observer>,
+    started: Instant,
+    finished: bool,
+}
+
+impl TurnGuard {
+    pub fn start(observer: Arc<dyn Observer>) -> Self {
+        Self {
+            observer,
+            started: Instant::now(),
+            finished: false,
+        }
+    }
+
+    pub fn finish(mut self, tokens_used: Option<u64>, success: bool) {
+        self.finished = true;
+        self.record(tokens_used, success);
+    }
+
+    fn record(&self, tokens_used: Option<u64>, success: bool) {
+        self.observer.record_event(&ObserverEvent::AgentEnd {
+            duration: self.started.elapsed(),
+            tokens_used,
+            success,
+        });
+    }
+}
+
+impl Drop for TurnGuard {
+    fn drop(&mut self) {
+        if !self.finished {
+            self.record(None, false);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn nested_scopes_share_trace_and_link_parents() {
+        assert!(current_span().is_none());
+
+        let root = SpanContext::child_of_current();
+        assert!(root.parent_span_id.is_none());
+
+        in_span(root, async {
+            let child = SpanContext::child_of_current();
+            assert_eq!(child.trace_id, root.trace_id);
+            assert_eq!(child.parent_span_id, Some(root.span_id));
+            assert_eq!(current_span(), Some(root));
+        })
+        .await;
+
+        assert!(current_span().is_none());
+    }
+
+    #[tokio::test]
+    async fn a_reserved_span_goes_to_the_first_turn_only() {
+        let reserved = SpanContext::root();
+        in_turn_span(reserved, async {
+            let turn = SpanContext::for_turn();
+            assert_eq!(turn, reserved);
+            in_span(turn, async {
+                let nested = SpanContext::for_turn();
+                assert_eq!(nested.parent_span_id, Some(reserved.span_id));
+            })
+            .await;
+        })
+        .await;
+
+        let unreserved = SpanContext::for_turn();
+        assert!(unreserved.parent_span_id.is_none());
+        assert_ne!(unreserved, reserved);
+    }
+}
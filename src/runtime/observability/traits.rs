@@ -1,3 +1,4 @@
+use std::sync::Arc;
 use std::time::Duration;
 
 /// Events the observer can record
@@ -6,16 +7,33 @@ pub enum ObserverEvent {
     AgentStart {
         provider: String,
         model: String,
+        entity_id: String,
     },
     AgentEnd {
         duration: Duration,
         tokens_used: Option<u64>,
+        /// `false` when the turn failed or was cancelled.
+        success: bool,
     },
     ToolCall {
         tool: String,
         duration: Duration,
         success: bool,
     },
+    ProviderRequest {
+        provider: String,
+        model: String,
+        duration: Duration,
+        success: bool,
+        input_tokens: Option<u64>,
+        output_tokens: Option<u64>,
+    },
+    MemoryRecall {
+        entity_id: String,
+        results: usize,
+        duration: Duration,
+        success: bool,
+    },
     ChannelMessage {
         channel: String,
         direction: String,
@@ -94,3 +112,21 @@ pub trait Observer: Send + Sync {
     /// Human-readable name of this observer
     fn name(&self) -> &str;
 }
+
+impl<T: Observer + ?Sized> Observer for Arc<T> {
+    fn record_event(&self, event: &ObserverEvent) {
+        (**self).record_event(event);
+    }
+
+    fn record_metric(&self, metric: &ObserverMetric) {
+        (**self).record_metric(metric);
+    }
+
+    fn flush(&self) {
+        (**self).flush();
+    }
+
+    fn name(&self) -> &str {
+        (**self).name()
+    }
+}
@@ -0,0 +1,69 @@
+use super::report::{ReportFormat, breakdown_csv, build_report, records_csv, render_table};
+use super::tracker::{SqliteUsageTracker, usage_db_path};
+use super::types::UsageGroup;
+use crate::config::Config;
+use anyhow::{Result, bail};
+
+/// Usage reporting commands.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UsageCommand {
+    /// Totals broken down by one dimension.
+    Summary {
+        by: String,
+        since: Option<String>,
+        until: Option<String>,
+        format: String,
+    },
+    /// Every recorded turn, for spreadsheets and billing.
+    Export {
+        since: Option<String>,
+        until: Option<String>,
+        format: String,
+    },
+}
+
+pub async fn handle_command(command: UsageCommand, config: &Config) -> Result<()> {
+    let db_path = usage_db_path(&config.workspace_dir);
+    if !db_path.exists() {
+        println!("No usage recorded yet.");
+        return Ok(());
+    }
+    let tracker = SqliteUsageTracker::new(&db_path).await?;
+    match command {
+        UsageCommand::Summary {
+            by,
+            since,
+            until,
+            format,
+        } => {
+            let Some(group) = UsageGroup::parse(&by) else {
+                bail!("Unknown breakdown '{by}'; use entity, channel, session, model or day");
+            };
+            let format = parse_format(&format)?;
+            let report =
+                build_report(&tracker, config, group, since.as_deref(), until.as_deref()).await?;
+            match format {
+                ReportFormat::Table => println!("{}", render_table(&report)),
+                ReportFormat::Csv => print!("{}", breakdown_csv(&report)),
+                ReportFormat::Json => println!("{}", serde_json::to_string_pretty(&report)?),
+            }
+        }
+        UsageCommand::Export {
+            since,
+            until,
+            format,
+        } => {
+            let records = tracker.records(since.as_deref(), until.as_deref()).await?;
+            match parse_format(&format)? {
+                ReportFormat::Json => println!("{}", serde_json::to_string_pretty(&records)?),
+                ReportFormat::Csv | ReportFormat::Table => print!("{}", records_csv(&records)),
+            }
+        }
+    }
+    Ok(())
+}
+
+fn parse_format(value: &str) -> Result<ReportFormat> {
+    ReportFormat::parse(value)
+        .ok_or_else(|| anyhow::anyhow!("Unknown format '{value}'; use table, csv or json"))
+}
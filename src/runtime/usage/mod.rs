@@ -1,5 +1,13 @@
+mod commands;
+pub mod recorder;
+pub mod report;
 pub mod tracker;
 pub mod types;
 
-pub use tracker::{SqliteUsageTracker, UsageTracker};
-pub use types::{ModelPricing, UsageRecord, UsageSummary, default_pricing, lookup_pricing};
+pub use commands::{UsageCommand, handle_command};
+pub use recorder::{TurnUsage, UsageRecorder};
+pub use tracker::{SqliteUsageTracker, UsageTracker, usage_db_path};
+pub use types::{
+    ModelPricing, ModelUsageSummary, UsageBreakdown, UsageGroup, UsageRecord, UsageSummary,
+    default_pricing, lookup_pricing, pricing_table,
+};
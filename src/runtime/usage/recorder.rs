@@ -0,0 +1,249 @@
+//! Records each agent turn's provider usage and keeps a daily spend counter
+//! for budget alerts and cost-aware routing. With `[usage] enforce_budget`
+//! the spend also feeds the security policy's cost tracker, so
+//! `max_cost_per_day_cents` refuses further actions.
+
+use super::tracker::{SqliteUsageTracker, UsageTracker, usage_db_path};
+use super::types::{ModelPricing, UsageRecord, lookup_pricing, pricing_table};
+use crate::config::{Config, UsageConfig};
+use crate::security::policy::{CostTracker, SecurityPolicy};
+use anyhow::Result;
+use chrono::{SecondsFormat, Utc};
+use std::path::Path;
+use std::sync::{Arc, Mutex, PoisonError};
+
+const MICROS_PER_CENT: i64 = 10_000;
+
+/// One finished turn, as seen by the runtime that ran it.
+#[derive(Debug, Clone, Copy)]
+pub struct TurnUsage<'a> {
+    pub entity_id: &'a str,
+    pub channel: &'a str,
+    pub session_id: Option<&'a str>,
+    pub provider: &'a str,
+    pub model: &'a str,
+    pub input_tokens: Option<u64>,
+    pub output_tokens: Option<u64>,
+}
+
+pub struct UsageRecorder {
+    tracker: SqliteUsageTracker,
+    pricing: Vec<ModelPricing>,
+    security: Arc<SecurityPolicy>,
+    enforce_budget: bool,
+    /// Today's estimated spend, whether or not it is enforced.
+    spend: CostTracker,
+    alert_percents: Vec<u8>,
+    state: Mutex<BudgetState>,
+}
+
+#[derive(Default)]
+struct BudgetState {
+    /// Spend below one cent not yet added to the daily counters.
+    pending_micros: i64,
+    /// Day (`YYYY-MM-DD`) and highest alert percentage already logged.
+    alerted: Option<(String, u8)>,
+}
+
+impl UsageRecorder {
+    /// The workspace usage recorder, or `None` when `[usage]` is disabled
+    /// or the database cannot be opened.
+    pub async fn open_if_enabled(
+        config: &Config,
+        security: Arc<SecurityPolicy>,
+    ) -> Option<Arc<Self>> {
+        if !config.usage.enabled {
+            return None;
+        }
+        match Self::open(&config.workspace_dir, &config.usage, security).await {
+            Ok(recorder) => Some(Arc::new(recorder)),
+            Err(error) => {
+                tracing::warn!(%error, "usage database unavailable; usage will not be recorded");
+                None
+            }
+        }
+    }
+
+    /// Open the usage database and count today's recorded spend against
+    /// the daily budget.
+    pub async fn open(
+        workspace_dir: &Path,
+        config: &UsageConfig,
+        security: Arc<SecurityPolicy>,
+    ) -> Result<Self> {
+        let db_path = usage_db_path(workspace_dir);
+        if let Some(parent) = db_path.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        let tracker = SqliteUsageTracker::new(&db_path).await?;
+        let today = tracker.summarize(Some(&today())).await?;
+        let recorder = Self {
+            tracker,
+            pricing: pricing_table(&config.pricing),
+            security,
+            enforce_budget: config.enforce_budget,
+            spend: CostTracker::new(),
+            alert_percents: config.alert_percents.clone(),
+            state: Mutex::new(BudgetState::default()),
+        };
+        recorder.add_spend(today.total_estimated_cost_micros);
+        Ok(recorder)
+    }
+
+    pub fn tracker(&self) -> &SqliteUsageTracker {
+        &self.tracker
+    }
+
+    /// Today's estimated spend in cents, for routing and alerts.
+    pub fn spent_today_cents(&self) -> u32 {
+        self.spend.spent_today()
+    }
+
+    /// Store the turn and add its estimated cost to today's spend. Failures
+    /// are logged; accounting never fails a turn.
+    pub async fn record_turn(&self, usage: TurnUsage<'_>) {
+        let cost = lookup_pricing(usage.model, &self.pricing).map(|pricing| {
+            pricing.estimate_cost_micros(
+                usage.input_tokens.unwrap_or(0),
+                usage.output_tokens.unwrap_or(0),
+            )
+        });
+        let record = UsageRecord {
+            id: uuid::Uuid::new_v4().to_string(),
+            session_id: usage.session_id.map(ToString::to_string),
+            entity_id: Some(usage.entity_id.to_string()),
+            channel: Some(usage.channel.to_string()),
+            provider: usage.provider.to_string(),
+            model: usage.model.to_string(),
+            input_tokens: usage.input_tokens,
+            output_tokens: usage.output_tokens,
+            estimated_cost_micros: cost,
+            created_at: Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true),
+        };
+        if let Err(error) = self.tracker.record(&record).await {
+            tracing::warn!(%error, "failed to record turn usage");
+            return;
+        }
+        if let Some(cost) = cost {
+            self.add_spend(cost);
+        }
+    }
+
+    fn add_spend(&self, micros: i64) {
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        state.pending_micros += micros.max(0);
+        let cents = u32::try_from(state.pending_micros / MICROS_PER_CENT).unwrap_or(u32::MAX);
+        state.pending_micros %= MICROS_PER_CENT;
+        self.spend.add(cents);
+        if self.enforce_budget {
+            self.security.cost_tracker.add(cents);
+        }
+        self.check_alerts(&mut state);
+    }
+
+    fn check_alerts(&self, state: &mut BudgetState) {
+        let max = self.security.max_cost_per_day_cents;
+        if max == 0 {
+            return;
+        }
+        let spent = self.spend.spent_today();
+        let percent = u64::from(spent) * 100 / u64::from(max);
+        let Some(crossed) = self
+            .alert_percents
+            .iter()
+            .copied()
+            .filter(|threshold| u64::from(*threshold) <= percent)
+            .max()
+        else {
+            return;
+        };
+
+        let today = today();
+        let already = match &state.alerted {
+            Some((day, alerted)) if *day == today => *alerted >= crossed,
+            _ => false,
+        };
+        if !already {
+            tracing::warn!(
+                spent_cents = spent,
+                max_cost_per_day_cents = max,
+                threshold_percent = crossed,
+                "daily cost budget alert: {percent}% of today's budget spent"
+            );
+            state.alerted = Some((today, crossed));
+        }
+    }
+}
+
+/// Start of the current UTC day, comparable with record timestamps.
+pub fn today() -> String {
+    Utc::now().format("%Y-%m-%d").to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runtime::usage::UsageGroup;
+
+    fn turn(model: &str, input: u64, output: u64) -> TurnUsage<'_> {
+        TurnUsage {
+            entity_id: "person:telegram.1",
+            channel: "telegram",
+            session_id: Some("s-1"),
+            provider: "openai",
+            model,
+            input_tokens: Some(input),
+            output_tokens: Some(output),
+        }
+    }
+
+    #[tokio::test]
+    async fn recorded_turns_are_priced_and_counted_without_enforcement() {
+        let dir = tempfile::tempdir().unwrap();
+        let security = Arc::new(SecurityPolicy::default());
+        let recorder =
+            UsageRecorder::open(dir.path(), &UsageConfig::default(), Arc::clone(&security))
+                .await
+                .unwrap();
+
+        // gpt-4o: $2.50 in + $10 out per million tokens.
+        recorder
+            .record_turn(turn("gpt-4o", 1_000_000, 100_000))
+            .await;
+        recorder.record_turn(turn("local-model", 500, 500)).await;
+
+        assert_eq!(recorder.spent_today_cents(), 350);
+        // Spend is only reported unless enforcement is turned on.
+        assert_eq!(security.cost_tracker.spent_today(), 0);
+        let by_entity = recorder
+            .tracker()
+            .summarize_by(UsageGroup::Entity, None, None)
+            .await
+            .unwrap();
+        assert_eq!(by_entity[0].key, "person:telegram.1");
+        assert_eq!(by_entity[0].summary.record_count, 2);
+        assert_eq!(by_entity[0].summary.total_estimated_cost_micros, 3_500_000);
+    }
+
+    #[tokio::test]
+    async fn reopening_restores_todays_spend() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = UsageConfig::default();
+        let first = UsageRecorder::open(dir.path(), &config, Arc::new(SecurityPolicy::default()))
+            .await
+            .unwrap();
+        first.record_turn(turn("gpt-4o-mini", 1_000_000, 0)).await;
+        first.record_turn(turn("gpt-4o-mini", 1_000_000, 0)).await;
+
+        let security = Arc::new(SecurityPolicy::default());
+        let enforced = UsageConfig {
+            enforce_budget: true,
+            ..config
+        };
+        let second = UsageRecorder::open(dir.path(), &enforced, Arc::clone(&security))
+            .await
+            .unwrap();
+        assert_eq!(second.spent_today_cents(), 30);
+        assert_eq!(security.cost_tracker.spent_today(), 30);
+    }
+}
@@ -0,0 +1,283 @@
+//! Usage reports shared by the `usage` CLI command, the gateway's
+//! `/api/usage` endpoints and the `/usage` chat command.
+
+use super::recorder::today;
+use super::tracker::{SqliteUsageTracker, UsageTracker};
+use super::types::{UsageBreakdown, UsageGroup, UsageRecord, UsageSummary};
+use crate::config::Config;
+use anyhow::Result;
+use serde::Serialize;
+use std::fmt::Write as _;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReportFormat {
+    Table,
+    Csv,
+    Json,
+}
+
+impl ReportFormat {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "table" | "text" => Some(Self::Table),
+            "csv" => Some(Self::Csv),
+            "json" => Some(Self::Json),
+            _ => None,
+        }
+    }
+}
+
+/// Totals for one breakdown over `[since, until)`, plus today's spend
+/// against the daily budget.
+#[derive(Debug, Clone, Serialize)]
+pub struct UsageReport {
+    pub group: &'static str,
+    pub since: Option<String>,
+    pub until: Option<String>,
+    pub total: UsageSummary,
+    pub breakdown: Vec<UsageBreakdown>,
+    pub budget: BudgetStatus,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BudgetStatus {
+    pub spent_today_micros: i64,
+    pub max_cost_per_day_cents: u32,
+    pub percent_used: u64,
+    /// Whether today's spend has reached the lowest `[usage] alert_percents`
+    /// threshold.
+    pub alert: bool,
+}
+
+pub async fn build_report(
+    tracker: &SqliteUsageTracker,
+    config: &Config,
+    group: UsageGroup,
+    since: Option<&str>,
+    until: Option<&str>,
+) -> Result<UsageReport> {
+    let breakdown = tracker.summarize_by(group, since, until).await?;
+    let total = breakdown
+        .iter()
+        .fold(UsageSummary::default(), |mut total, entry| {
+            total.total_input_tokens += entry.summary.total_input_tokens;
+            total.total_output_tokens += entry.summary.total_output_tokens;
+            total.total_estimated_cost_micros += entry.summary.total_estimated_cost_micros;
+            total.record_count += entry.summary.record_count;
+            total
+        });
+
+    let spent_today_micros = tracker
+        .summarize(Some(&today()))
+        .await?
+        .total_estimated_cost_micros;
+    let max_cost_per_day_cents = config.autonomy.max_cost_per_day_cents;
+    let percent_used = if max_cost_per_day_cents == 0 {
+        0
+    } else {
+        u64::try_from(spent_today_micros).unwrap_or(0) / (u64::from(max_cost_per_day_cents) * 100)
+    };
+    let alert = config
+        .usage
+        .alert_percents
+        .iter()
+        .min()
+        .is_some_and(|threshold| percent_used >= u64::from(*threshold));
+
+    Ok(UsageReport {
+        group: group.as_str(),
+        since: since.map(ToString::to_string),
+        until: until.map(ToString::to_string),
+        total,
+        breakdown,
+        budget: BudgetStatus {
+            spent_today_micros,
+            max_cost_per_day_cents,
+            percent_used,
+            alert,
+        },
+    })
+}
+
+/// Dollars with four decimals, e.g. `$0.0123`.
+pub fn format_usd(micros: i64) -> String {
+    #[allow(clippy::cast_precision_loss)]
+    let dollars = micros as f64 / 1_000_000.0;
+    format!("${dollars:.4}")
+}
+
+pub fn render_table(report: &UsageReport) -> String {
+    let mut out = String::new();
+    let range = match (&report.since, &report.until) {
+        (None, None) => "all time".to_string(),
+        (since, until) => format!(
+            "{} .. {}",
+            since.as_deref().unwrap_or("start"),
+            until.as_deref().unwrap_or("now")
+        ),
+    };
+    let _ = writeln!(out, "Usage by {} ({range})", report.group);
+    let width = report
+        .breakdown
+        .iter()
+        .map(|entry| entry.key.chars().count())
+        .chain(std::iter::once("TOTAL".len()))
+        .max()
+        .unwrap_or(5);
+    let _ = writeln!(
+        out,
+        "{:<width$}  {:>6}  {:>12}  {:>12}  {:>12}",
+        report.group, "turns", "input", "output", "cost"
+    );
+    let rows = report
+        .breakdown
+        .iter()
+        .map(|entry| (entry.key.as_str(), &entry.summary))
+        .chain(std::iter::once(("TOTAL", &report.total)));
+    for (key, summary) in rows {
+        let _ = writeln!(
+            out,
+            "{key:<width$}  {:>6}  {:>12}  {:>12}  {:>12}",
+            summary.record_count,
+            summary.total_input_tokens,
+            summary.total_output_tokens,
+            format_usd(summary.total_estimated_cost_micros)
+        );
+    }
+    let budget = &report.budget;
+    let _ = write!(
+        out,
+        "Today: {} of {} daily budget ({}%)",
+        format_usd(budget.spent_today_micros),
+        format_usd(i64::from(budget.max_cost_per_day_cents) * 10_000),
+        budget.percent_used
+    );
+    if budget.alert {
+        out.push_str(" — budget alert");
+    }
+    out
+}
+
+pub fn breakdown_csv(report: &UsageReport) -> String {
+    let mut out = format!(
+        "{},turns,input_tokens,output_tokens,cost_usd\n",
+        report.group
+    );
+    for entry in &report.breakdown {
+        let summary = &entry.summary;
+        let _ = writeln!(
+            out,
+            "{},{},{},{},{}",
+            csv_field(&entry.key),
+            summary.record_count,
+            summary.total_input_tokens,
+            summary.total_output_tokens,
+            usd_plain(summary.total_estimated_cost_micros)
+        );
+    }
+    out
+}
+
+pub fn records_csv(records: &[UsageRecord]) -> String {
+    let mut out = String::from(
+        "created_at,entity_id,channel,session_id,provider,model,input_tokens,output_tokens,cost_usd\n",
+    );
+    for record in records {
+        let _ = writeln!(
+            out,
+            "{},{},{},{},{},{},{},{},{}",
+            csv_field(&record.created_at),
+            csv_field(record.entity_id.as_deref().unwrap_or_default()),
+            csv_field(record.channel.as_deref().unwrap_or_default()),
+            csv_field(record.session_id.as_deref().unwrap_or_default()),
+            csv_field(&record.provider),
+            csv_field(&record.model),
+            record
+                .input_tokens
+                .map(|t| t.to_string())
+                .unwrap_or_default(),
+            record
+                .output_tokens
+                .map(|t| t.to_string())
+                .unwrap_or_default(),
+            record
+                .estimated_cost_micros
+                .map(usd_plain)
+                .unwrap_or_default(),
+        );
+    }
+    out
+}
+
+fn usd_plain(micros: i64) -> String {
+    format_usd(micros).trim_start_matches('$').to_string()
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn report() -> UsageReport {
+        let summary = |cost| UsageSummary {
+            total_input_tokens: 10,
+            total_output_tokens: 5,
+            total_estimated_cost_micros: cost,
+            record_count: 1,
+        };
+        UsageReport {
+            group: "entity",
+            since: Some("2026-09-01".into()),
+            until: None,
+            total: summary(1_500_000),
+            breakdown: vec![
+                UsageBreakdown {
+                    key: "person:slack,1".into(),
+                    summary: summary(1_000_000),
+                },
+                UsageBreakdown {
+                    key: "person:telegram.2".into(),
+                    summary: summary(500_000),
+                },
+            ],
+            budget: BudgetStatus {
+                spent_today_micros: 4_000_000,
+                max_cost_per_day_cents: 500,
+                percent_used: 80,
+                alert: true,
+            },
+        }
+    }
+
+    #[test]
+    fn csv_quotes_fields_with_separators() {
+        let csv = breakdown_csv(&report());
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines[0], "entity,turns,input_tokens,output_tokens,cost_usd");
+        assert_eq!(lines[1], "\"person:slack,1\",1,10,5,1.0000");
+        assert_eq!(lines[2], "person:telegram.2,1,10,5,0.5000");
+    }
+
+    #[test]
+    fn table_lists_rows_total_and_budget() {
+        let table = render_table(&report());
+        assert!(table.starts_with("Usage by entity (2026-09-01 .. now)"));
+        assert!(table.contains("TOTAL"));
+        assert!(table.contains("$1.5000"));
+        assert!(table.ends_with("Today: $4.0000 of $5.0000 daily budget (80%) — budget alert"));
+    }
+
+    #[test]
+    fn report_format_parses_aliases() {
+        assert_eq!(ReportFormat::parse("CSV"), Some(ReportFormat::Csv));
+        assert_eq!(ReportFormat::parse("text"), Some(ReportFormat::Table));
+        assert_eq!(ReportFormat::parse("xml"), None);
+    }
+}
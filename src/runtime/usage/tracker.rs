@@ -1,8 +1,13 @@
-use super::types::{UsageRecord, UsageSummary};
+use super::types::{ModelUsageSummary, UsageBreakdown, UsageGroup, UsageRecord, UsageSummary};
 use anyhow::Result;
-use sqlx::sqlite::SqlitePoolOptions;
+use sqlx::sqlite::{SqlitePoolOptions, SqliteRow};
 use sqlx::{Row, SqlitePool};
-use std::path::Path;
+use std::path::{Path, PathBuf};
+
+/// Location of the usage database inside a workspace.
+pub fn usage_db_path(workspace_dir: &Path) -> PathBuf {
+    workspace_dir.join("usage").join("usage.db")
+}
 
 /// Async usage tracking trait.
 pub trait UsageTracker: Send + Sync {
@@ -44,9 +49,115 @@ impl SqliteUsageTracker {
         )
         .execute(&pool)
         .await?;
+        add_missing_column(&pool, "entity_id").await?;
+        add_missing_column(&pool, "channel").await?;
 
         Ok(Self { pool })
     }
+
+    /// Totals per value of `group` for records created in
+    /// `[since, until)`, most expensive first. Bounds are RFC 3339
+    /// timestamps or dates and compare as text.
+    pub async fn summarize_by(
+        &self,
+        group: UsageGroup,
+        since: Option<&str>,
+        until: Option<&str>,
+    ) -> Result<Vec<UsageBreakdown>> {
+        let key = match group {
+            UsageGroup::Entity => "entity_id",
+            UsageGroup::Channel => "channel",
+            UsageGroup::Session => "session_id",
+            UsageGroup::Model => "provider || '/' || model",
+            UsageGroup::Day => "substr(created_at, 1, 10)",
+        };
+        let rows = sqlx::query(&format!(
+            "SELECT
+                COALESCE({key}, '(none)') as k,
+                COALESCE(SUM(input_tokens), 0) as ti,
+                COALESCE(SUM(output_tokens), 0) as to_,
+                COALESCE(SUM(estimated_cost_micros), 0) as tc,
+                COUNT(*) as rc
+             FROM usage_records
+             WHERE (?1 IS NULL OR created_at >= ?1) AND (?2 IS NULL OR created_at < ?2)
+             GROUP BY k
+             ORDER BY tc DESC, k"
+        ))
+        .bind(since)
+        .bind(until)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .iter()
+            .map(|row| UsageBreakdown {
+                key: row.get("k"),
+                summary: summary_from_row(row),
+            })
+            .collect())
+    }
+
+    /// Raw records created in `[since, until)`, oldest first, for export.
+    pub async fn records(
+        &self,
+        since: Option<&str>,
+        until: Option<&str>,
+    ) -> Result<Vec<UsageRecord>> {
+        let rows = sqlx::query(
+            "SELECT id, session_id, entity_id, channel, provider, model,
+                    input_tokens, output_tokens, estimated_cost_micros, created_at
+             FROM usage_records
+             WHERE (?1 IS NULL OR created_at >= ?1) AND (?2 IS NULL OR created_at < ?2)
+             ORDER BY created_at, id",
+        )
+        .bind(since)
+        .bind(until)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .iter()
+            .map(|row| UsageRecord {
+                id: row.get("id"),
+                session_id: row.get("session_id"),
+                entity_id: row.get("entity_id"),
+                channel: row.get("channel"),
+                provider: row.get("provider"),
+                model: row.get("model"),
+                input_tokens: row.get::<Option<i64>, _>("input_tokens").map(i64_to_u64),
+                output_tokens: row.get::<Option<i64>, _>("output_tokens").map(i64_to_u64),
+                estimated_cost_micros: row.get("estimated_cost_micros"),
+                created_at: row.get("created_at"),
+            })
+            .collect())
+    }
+
+    /// Totals per provider/model pair, most expensive first.
+    pub async fn summarize_by_model(&self) -> Result<Vec<ModelUsageSummary>> {
+        let rows = sqlx::query(
+            "SELECT
+                provider,
+                model,
+                COALESCE(SUM(input_tokens), 0) as ti,
+                COALESCE(SUM(output_tokens), 0) as to_,
+                COALESCE(SUM(estimated_cost_micros), 0) as tc,
+                COUNT(*) as rc
+             FROM usage_records
+             GROUP BY provider, model
+             ORDER BY tc DESC, provider, model",
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .iter()
+            .map(|row| ModelUsageSummary {
+                provider: row.get("provider"),
+                model: row.get("model"),
+                summary: summary_from_row(row),
+            })
+            .collect())
+    }
 }
 
 impl UsageTracker for SqliteUsageTracker {
@@ -56,6 +167,8 @@ impl UsageTracker for SqliteUsageTracker {
     ) -> std::pin::Pin<Box<dyn std::future::Future<Output = Result<()>> + Send + '_>> {
         let id = record.id.clone();
         let session_id = record.session_id.clone();
+        let entity_id = record.entity_id.clone();
+        let channel = record.channel.clone();
         let provider = record.provider.clone();
         let model = record.model.clone();
         let input_tokens = record.input_tokens.map(u64::cast_signed);
@@ -65,10 +178,12 @@ impl UsageTracker for SqliteUsageTracker {
 
         Box::pin(async move {
             sqlx::query(
-                "INSERT INTO usage_records (id, session_id, provider, model, input_tokens, output_tokens, estimated_cost_micros, created_at) VALUES (?, ?, ?, ?, ?, ?, ?, ?)"
+                "INSERT INTO usage_records (id, session_id, entity_id, channel, provider, model, input_tokens, output_tokens, estimated_cost_micros, created_at) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)"
             )
             .bind(id)
             .bind(session_id)
+            .bind(entity_id)
+            .bind(channel)
             .bind(provider)
             .bind(model)
             .bind(input_tokens)
@@ -115,16 +230,37 @@ impl UsageTracker for SqliteUsageTracker {
                 .await?
             };
 
-            Ok(UsageSummary {
-                total_input_tokens: i64_to_u64(row.get::<i64, _>("ti")),
-                total_output_tokens: i64_to_u64(row.get::<i64, _>("to_")),
-                total_estimated_cost_micros: row.get::<i64, _>("tc"),
-                record_count: i64_to_u64(row.get::<i64, _>("rc")),
-            })
+            Ok(summary_from_row(&row))
         })
     }
 }
 
+/// Databases created before per-entity accounting lack some columns.
+async fn add_missing_column(pool: &SqlitePool, column: &str) -> Result<()> {
+    let exists = sqlx::query("SELECT 1 FROM pragma_table_info('usage_records') WHERE name = ?")
+        .bind(column)
+        .fetch_optional(pool)
+        .await?
+        .is_some();
+    if !exists {
+        sqlx::query(&format!(
+            "ALTER TABLE usage_records ADD COLUMN {column} TEXT"
+        ))
+        .execute(pool)
+        .await?;
+    }
+    Ok(())
+}
+
+fn summary_from_row(row: &SqliteRow) -> UsageSummary {
+    UsageSummary {
+        total_input_tokens: i64_to_u64(row.get::<i64, _>("ti")),
+        total_output_tokens: i64_to_u64(row.get::<i64, _>("to_")),
+        total_estimated_cost_micros: row.get::<i64, _>("tc"),
+        record_count: i64_to_u64(row.get::<i64, _>("rc")),
+    }
+}
+
 fn i64_to_u64(value: i64) -> u64 {
     u64::try_from(value).unwrap_or(0)
 }
@@ -132,7 +268,7 @@ fn i64_to_u64(value: i64) -> u64 {
 #[cfg(test)]
 mod tests {
     use super::{SqliteUsageTracker, UsageTracker};
-    use crate::runtime::usage::types::{ModelPricing, UsageRecord};
+    use crate::runtime::usage::types::{ModelPricing, UsageGroup, UsageRecord};
     use tempfile::NamedTempFile;
 
     fn sample_record(
@@ -145,6 +281,8 @@ mod tests {
         UsageRecord {
             id: id.to_string(),
             session_id: Some("session-1".to_string()),
+            entity_id: Some("person:telegram.1".to_string()),
+            channel: Some("telegram".to_string()),
             provider: "openrouter".to_string(),
             model: "anthropic/claude-sonnet-4-20250514".to_string(),
             input_tokens: Some(input),
@@ -226,6 +364,105 @@ mod tests {
         assert_eq!(summary.record_count, 1);
     }
 
+    #[tokio::test]
+    async fn summarize_by_model_groups_provider_and_model() {
+        let file = NamedTempFile::new().unwrap();
+        let tracker = SqliteUsageTracker::new(file.path()).await.unwrap();
+
+        tracker
+            .record(&sample_record("id-1", "2026-02-20T10:00:00Z", 100, 50, 900))
+            .await
+            .unwrap();
+        tracker
+            .record(&sample_record("id-2", "2026-02-20T11:00:00Z", 20, 10, 100))
+            .await
+            .unwrap();
+        let mut other = sample_record("id-3", "2026-02-20T12:00:00Z", 5, 5, 50);
+        other.provider = "ollama".to_string();
+        other.model = "llama3".to_string();
+        tracker.record(&other).await.unwrap();
+
+        let by_model = tracker.summarize_by_model().await.unwrap();
+        assert_eq!(by_model.len(), 2);
+        assert_eq!(by_model[0].provider, "openrouter");
+        assert_eq!(by_model[0].summary.total_input_tokens, 120);
+        assert_eq!(by_model[0].summary.record_count, 2);
+        assert_eq!(by_model[1].model, "llama3");
+        assert_eq!(by_model[1].summary.total_estimated_cost_micros, 50);
+    }
+
+    #[tokio::test]
+    async fn summarize_by_groups_within_range_and_records_export() {
+        let file = NamedTempFile::new().unwrap();
+        let tracker = SqliteUsageTracker::new(file.path()).await.unwrap();
+
+        tracker
+            .record(&sample_record("id-1", "2026-02-19T10:00:00Z", 100, 50, 900))
+            .await
+            .unwrap();
+        tracker
+            .record(&sample_record("id-2", "2026-02-20T10:00:00Z", 20, 10, 100))
+            .await
+            .unwrap();
+        let mut other = sample_record("id-3", "2026-02-20T12:00:00Z", 5, 5, 300);
+        other.entity_id = None;
+        other.channel = Some("slack".to_string());
+        tracker.record(&other).await.unwrap();
+
+        let by_entity = tracker
+            .summarize_by(UsageGroup::Entity, None, None)
+            .await
+            .unwrap();
+        assert_eq!(by_entity.len(), 2);
+        assert_eq!(by_entity[0].key, "person:telegram.1");
+        assert_eq!(by_entity[0].summary.total_estimated_cost_micros, 1_000);
+        assert_eq!(by_entity[1].key, "(none)");
+
+        let by_day = tracker
+            .summarize_by(UsageGroup::Day, Some("2026-02-20"), None)
+            .await
+            .unwrap();
+        assert_eq!(by_day.len(), 1);
+        assert_eq!(by_day[0].key, "2026-02-20");
+        assert_eq!(by_day[0].summary.record_count, 2);
+
+        let by_channel = tracker
+            .summarize_by(UsageGroup::Channel, None, Some("2026-02-20"))
+            .await
+            .unwrap();
+        assert_eq!(by_channel.len(), 1);
+        assert_eq!(by_channel[0].key, "telegram");
+
+        let records = tracker.records(Some("2026-02-20"), None).await.unwrap();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[1].channel.as_deref(), Some("slack"));
+        assert_eq!(records[1].input_tokens, Some(5));
+    }
+
+    #[tokio::test]
+    async fn opening_an_older_database_adds_the_new_columns() {
+        let file = NamedTempFile::new().unwrap();
+        let url = format!("sqlite://{}?mode=rwc", file.path().display());
+        let pool = sqlx::SqlitePool::connect(&url).await.unwrap();
+        sqlx::query(
+            "CREATE TABLE usage_records (id TEXT PRIMARY KEY, session_id TEXT, provider TEXT NOT NULL,
+             model TEXT NOT NULL, input_tokens INTEGER, output_tokens INTEGER,
+             estimated_cost_micros INTEGER, created_at TEXT NOT NULL)",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        pool.close().await;
+
+        let tracker = SqliteUsageTracker::new(file.path()).await.unwrap();
+        tracker
+            .record(&sample_record("id-1", "2026-02-20T10:00:00Z", 1, 1, 1))
+            .await
+            .unwrap();
+        let records = tracker.records(None, None).await.unwrap();
+        assert_eq!(records[0].entity_id.as_deref(), Some("person:telegram.1"));
+    }
+
     #[tokio::test]
     async fn multiple_records_aggregate_correctly() {
         let file = NamedTempFile::new().unwrap();
@@ -4,6 +4,12 @@ use serde::{Deserialize, Serialize};
 pub struct UsageRecord {
     pub id: String,
     pub session_id: Option<String>,
+    /// Who the turn was for, e.g. `person:telegram.12345`.
+    #[serde(default)]
+    pub entity_id: Option<String>,
+    /// Channel or gateway surface the turn came from.
+    #[serde(default)]
+    pub channel: Option<String>,
     pub provider: String,
     pub model: String,
     pub input_tokens: Option<u64>,
@@ -20,6 +26,60 @@ pub struct UsageSummary {
     pub record_count: u64,
 }
 
+/// Usage totals for one provider/model pair.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelUsageSummary {
+    pub provider: String,
+    pub model: String,
+    pub summary: UsageSummary,
+}
+
+/// Dimension usage totals are broken down by.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UsageGroup {
+    Entity,
+    Channel,
+    Session,
+    Model,
+    /// UTC calendar day (`YYYY-MM-DD`).
+    Day,
+}
+
+impl UsageGroup {
+    pub const ALL: [Self; 5] = [
+        Self::Entity,
+        Self::Channel,
+        Self::Session,
+        Self::Model,
+        Self::Day,
+    ];
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|group| group.as_str().eq_ignore_ascii_case(value.trim()))
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Entity => "entity",
+            Self::Channel => "channel",
+            Self::Session => "session",
+            Self::Model => "model",
+            Self::Day => "day",
+        }
+    }
+}
+
+/// Usage totals for one value of a [`UsageGroup`]; `key` is `(none)` for
+/// records that carry no value for the dimension.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UsageBreakdown {
+    pub key: String,
+    #[serde(flatten)]
+    pub summary: UsageSummary,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ModelPricing {
     pub model_pattern: String,
@@ -95,6 +155,21 @@ pub fn default_pricing() -> Vec<ModelPricing> {
     ]
 }
 
+/// The built-in table with `custom` entries added; a custom entry replaces
+/// the built-in one with the same pattern.
+#[must_use]
+pub fn pricing_table(custom: &[ModelPricing]) -> Vec<ModelPricing> {
+    let mut table = custom.to_vec();
+    table.extend(default_pricing().into_iter().filter(|builtin| {
+        !custom
+            .iter()
+            .any(|entry| entry.model_pattern == builtin.model_pattern)
+    }));
+    table
+}
+
+/// The pricing whose pattern is the longest substring of `model`, so
+/// `gpt-4o-mini` is not priced as `gpt-4o`.
 #[must_use]
 pub fn lookup_pricing<'a>(
     model: &str,
@@ -102,12 +177,13 @@ pub fn lookup_pricing<'a>(
 ) -> Option<&'a ModelPricing> {
     pricing_table
         .iter()
-        .find(|pricing| model.contains(&pricing.model_pattern))
+        .filter(|pricing| model.contains(&pricing.model_pattern))
+        .max_by_key(|pricing| pricing.model_pattern.len())
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{ModelPricing, default_pricing, lookup_pricing};
+    use super::{ModelPricing, UsageGroup, default_pricing, lookup_pricing, pricing_table};
 
     #[test]
     fn default_pricing_returns_non_empty_list() {
@@ -122,6 +198,13 @@ mod tests {
         assert!(found.is_some());
     }
 
+    #[test]
+    fn lookup_pricing_prefers_the_most_specific_pattern() {
+        let pricing = default_pricing();
+        let found = lookup_pricing("openai/gpt-4o-mini", &pricing).unwrap();
+        assert_eq!(found.model_pattern, "gpt-4o-mini");
+    }
+
     #[test]
     fn lookup_pricing_returns_none_for_unknown_model() {
         let pricing = default_pricing();
@@ -129,6 +212,27 @@ mod tests {
         assert!(found.is_none());
     }
 
+    #[test]
+    fn pricing_table_replaces_builtin_patterns() {
+        let table = pricing_table(&[ModelPricing {
+            model_pattern: "gpt-4o".into(),
+            input_cost_per_million: 1.0,
+            output_cost_per_million: 1.0,
+        }]);
+        assert_eq!(table.len(), default_pricing().len());
+        let found = lookup_pricing("gpt-4o-2024-08-06", &table).unwrap();
+        assert!((found.input_cost_per_million - 1.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn usage_group_round_trips_names() {
+        for group in UsageGroup::ALL {
+            assert_eq!(UsageGroup::parse(group.as_str()), Some(group));
+        }
+        assert_eq!(UsageGroup::parse(" Model "), Some(UsageGroup::Model));
+        assert_eq!(UsageGroup::parse("week"), None);
+    }
+
     #[test]
     fn estimate_cost_micros_calculates_correctly() {
         let pricing = ModelPricing {
@@ -0,0 +1,514 @@
+//! Human-in-the-loop approval for supervised tool calls and plan checkpoints.
+//!
+//! An [`ApprovalGate`] asks an [`ApprovalBroker`] (CLI prompt, chat channel,
+//! gateway WebSocket) whether a pending action may proceed. Approvals can be
+//! remembered as permanent [`PermissionGrant`]s so the same call is not asked
+//! about again.
+
+use crate::security::grants::{GrantScope, PermissionGrant, exact_pattern, grant_subject};
+use crate::security::permissions::PermissionStore;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::oneshot;
+use uuid::Uuid;
+
+/// How long a run waits for a reply before treating the request as denied.
+pub const DEFAULT_APPROVAL_TIMEOUT: Duration = Duration::from_mins(5);
+
+/// What a human is asked to approve.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ApprovalSubject {
+    ToolCall { tool: String, pattern: String },
+    Checkpoint { label: String },
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ApprovalRequest {
+    pub id: String,
+    pub entity_id: String,
+    #[serde(flatten)]
+    pub subject: ApprovalSubject,
+}
+
+impl ApprovalRequest {
+    #[must_use]
+    pub fn new(entity_id: &str, subject: ApprovalSubject) -> Self {
+        let mut id = Uuid::new_v4().simple().to_string();
+        id.truncate(8);
+        Self {
+            id,
+            entity_id: entity_id.to_string(),
+            subject,
+        }
+    }
+
+    /// One-line description of the pending action.
+    #[must_use]
+    pub fn summary(&self) -> String {
+        match &self.subject {
+            ApprovalSubject::ToolCall { tool, pattern } if pattern.is_empty() => {
+                format!("Run tool `{tool}`")
+            }
+            ApprovalSubject::ToolCall { tool, pattern } => {
+                format!("Run tool `{tool}`: {pattern}")
+            }
+            ApprovalSubject::Checkpoint { label } => format!("Continue past checkpoint `{label}`"),
+        }
+    }
+
+    /// Whether approving "always" makes sense for this request.
+    #[must_use]
+    pub fn can_remember(&self) -> bool {
+        matches!(self.subject, ApprovalSubject::ToolCall { .. })
+    }
+
+    /// Plain-text prompt for channels without interactive controls.
+    #[must_use]
+    pub fn prompt_text(&self) -> String {
+        let id = &self.id;
+        let options = if self.can_remember() {
+            format!("`approve {id}`, `always {id}` or `deny {id}`")
+        } else {
+            format!("`approve {id}` or `deny {id}`")
+        };
+        format!("Approval needed: {}\nReply {options}.", self.summary())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ApprovalDecision {
+    Approve,
+    /// Approve and persist a permanent grant for the same call.
+    #[serde(alias = "always")]
+    ApproveAlways,
+    Deny,
+}
+
+impl ApprovalDecision {
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Approve => "approve",
+            Self::ApproveAlways => "always",
+            Self::Deny => "deny",
+        }
+    }
+
+    /// Parse a reply keyword such as `approve`, `always` or `deny`.
+    #[must_use]
+    pub fn parse(word: &str) -> Option<Self> {
+        match word.trim().to_ascii_lowercase().as_str() {
+            "approve" | "approved" | "yes" | "y" | "ok" => Some(Self::Approve),
+            "always" | "approve_always" => Some(Self::ApproveAlways),
+            "deny" | "denied" | "reject" | "no" | "n" => Some(Self::Deny),
+            _ => None,
+        }
+    }
+}
+
+/// Error returned when a human denies (or never answers) an approval request.
+///
+/// The message contains "approval denied" so the tool loop stops with
+/// `LoopStopReason::ApprovalDenied`.
+#[derive(Debug, Clone)]
+pub struct ApprovalDenied {
+    pub summary: String,
+}
+
+impl std::fmt::Display for ApprovalDenied {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "approval denied: {}", self.summary)
+    }
+}
+
+impl std::error::Error for ApprovalDenied {}
+
+/// Delivers approval requests to a human and waits for the decision.
+pub trait ApprovalBroker: Send + Sync {
+    fn request_approval<'a>(
+        &'a self,
+        request: &'a ApprovalRequest,
+    ) -> Pin<Box<dyn Future<Output = Result<ApprovalDecision>> + Send + 'a>>;
+}
+
+/// Checks grants, asks the broker, and records "always" decisions.
+pub struct ApprovalGate {
+    broker: Arc<dyn ApprovalBroker>,
+    permissions: Option<Arc<PermissionStore>>,
+    timeout: Duration,
+}
+
+impl ApprovalGate {
+    pub fn new(broker: Arc<dyn ApprovalBroker>) -> Self {
+        Self {
+            broker,
+            permissions: None,
+            timeout: DEFAULT_APPROVAL_TIMEOUT,
+        }
+    }
+
+    /// Consult and update `permissions` for tool call approvals.
+    #[must_use]
+    pub fn with_permissions(mut self, permissions: Arc<PermissionStore>) -> Self {
+        self.permissions = Some(permissions);
+        self
+    }
+
+    #[must_use]
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Allow a tool call, asking for approval unless a grant already covers
+    /// it. Returns [`ApprovalDenied`] when the call must not run.
+    pub async fn authorize_tool(
+        &self,
+        tool_name: &str,
+        args: &serde_json::Value,
+        entity_id: &str,
+    ) -> Result<()> {
+        let pattern = grant_subject(tool_name, args);
+        if self
+            .permissions
+            .as_ref()
+            .is_some_and(|permissions| permissions.is_granted(tool_name, &pattern))
+        {
+            return Ok(());
+        }
+
+        let request = ApprovalRequest::new(
+            entity_id,
+            ApprovalSubject::ToolCall {
+                tool: tool_name.to_string(),
+                pattern: pattern.clone(),
+            },
+        );
+        match self.decide(&request).await {
+            ApprovalDecision::Approve => Ok(()),
+            ApprovalDecision::ApproveAlways => {
+                self.remember(tool_name, &pattern, entity_id);
+                Ok(())
+            }
+            ApprovalDecision::Deny => Err(ApprovalDenied {
+                summary: request.summary(),
+            }
+            .into()),
+        }
+    }
+
+    /// Ask whether a plan may continue past `label`.
+    pub async fn confirm_checkpoint(&self, label: &str, entity_id: &str) -> bool {
+        let request = ApprovalRequest::new(
+            entity_id,
+            ApprovalSubject::Checkpoint {
+                label: label.to_string(),
+            },
+        );
+        self.decide(&request).await != ApprovalDecision::Deny
+    }
+
+    async fn decide(&self, request: &ApprovalRequest) -> ApprovalDecision {
+        match tokio::time::timeout(self.timeout, self.broker.request_approval(request)).await {
+            Ok(Ok(decision)) => {
+                tracing::info!(
+                    request_id = %request.id,
+                    entity_id = %request.entity_id,
+                    decision = decision.as_str(),
+                    "approval decided: {}",
+                    request.summary()
+                );
+                decision
+            }
+            Ok(Err(error)) => {
+                tracing::warn!(request_id = %request.id, %error, "approval request failed; denying");
+                ApprovalDecision::Deny
+            }
+            Err(_) => {
+                tracing::warn!(
+                    request_id = %request.id,
+                    timeout_secs = self.timeout.as_secs(),
+                    "approval request timed out; denying"
+                );
+                ApprovalDecision::Deny
+            }
+        }
+    }
+
+    fn remember(&self, tool_name: &str, pattern: &str, entity_id: &str) {
+        let Some(permissions) = &self.permissions else {
+            return;
+        };
+        let grant = PermissionGrant {
+            tool: tool_name.to_string(),
+            pattern: exact_pattern(pattern),
+            scope: GrantScope::Permanent,
+        };
+        if let Err(error) = permissions.add_grant(grant, entity_id) {
+            tracing::warn!(%error, tool = tool_name, "failed to persist approval grant");
+        }
+    }
+}
+
+struct PendingApproval {
+    requester: String,
+    reply: oneshot::Sender<ApprovalDecision>,
+}
+
+/// Approval requests waiting for a reply, keyed by request id.
+///
+/// Only the requester that triggered a request (e.g. `telegram:12345`) can
+/// resolve it.
+#[derive(Default)]
+pub struct PendingApprovals {
+    waiting: Mutex<HashMap<String, PendingApproval>>,
+}
+
+impl PendingApprovals {
+    pub fn register(
+        &self,
+        request_id: &str,
+        requester: &str,
+    ) -> oneshot::Receiver<ApprovalDecision> {
+        let (reply, receiver) = oneshot::channel();
+        let mut waiting = self
+            .waiting
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        waiting.retain(|_, pending| !pending.reply.is_closed());
+        waiting.insert(
+            request_id.to_string(),
+            PendingApproval {
+                requester: requester.to_string(),
+                reply,
+            },
+        );
+        receiver
+    }
+
+    /// Deliver a decision. Returns `false` if no matching request is waiting.
+    pub fn resolve(&self, request_id: &str, requester: &str, decision: ApprovalDecision) -> bool {
+        let mut waiting = self
+            .waiting
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        if waiting
+            .get(request_id)
+            .is_none_or(|pending| pending.requester != requester)
+        {
+            return false;
+        }
+        waiting
+            .remove(request_id)
+            .is_some_and(|pending| pending.reply.send(decision).is_ok())
+    }
+
+    /// Resolve a text reply such as `approve 1a2b3c4d` or `/deny 1a2b3c4d`.
+    ///
+    /// The request id is required: a bare "yes" or "ok" in a busy chat is
+    /// too easily meant for something else. Returns `false` if the text is
+    /// not an approval reply.
+    pub fn resolve_reply(&self, requester: &str, text: &str) -> bool {
+        let mut words = text.split_whitespace();
+        let Some(decision) = words
+            .next()
+            .and_then(|word| ApprovalDecision::parse(word.trim_start_matches('/')))
+        else {
+            return false;
+        };
+        match (words.next(), words.next()) {
+            (Some(request_id), None) => self.resolve(request_id, requester, decision),
+            _ => false,
+        }
+    }
+}
+
+/// Denies every request, for callers with nobody to ask. Paired with a
+/// permission store, the gate still admits calls an existing grant covers.
+pub struct GrantsOnlyBroker;
+
+impl ApprovalBroker for GrantsOnlyBroker {
+    fn request_approval<'a>(
+        &'a self,
+        _request: &'a ApprovalRequest,
+    ) -> Pin<Box<dyn Future<Output = Result<ApprovalDecision>> + Send + 'a>> {
+        Box::pin(async { Ok(ApprovalDecision::Deny) })
+    }
+}
+
+/// Prompts on the controlling terminal.
+pub struct CliApprovalBroker;
+
+impl ApprovalBroker for CliApprovalBroker {
+    fn request_approval<'a>(
+        &'a self,
+        request: &'a ApprovalRequest,
+    ) -> Pin<Box<dyn Future<Output = Result<ApprovalDecision>> + Send + 'a>> {
+        let summary = request.summary();
+        let can_remember = request.can_remember();
+        Box::pin(async move {
+            let answer = tokio::task::spawn_blocking(move || -> std::io::Result<String> {
+                use std::io::Write;
+                let options = if can_remember {
+                    "[y]es / [a]lways / [N]o"
+                } else {
+                    "[y]es / [N]o"
+                };
+                eprint!("Approval needed: {summary}\nApprove? {options}: ");
+                std::io::stderr().flush()?;
+                let mut line = String::new();
+                std::io::stdin().read_line(&mut line)?;
+                Ok(line)
+            })
+            .await??;
+
+            let answer = answer.trim();
+            Ok(match answer {
+                "a" | "A" if can_remember => ApprovalDecision::ApproveAlways,
+                _ => match ApprovalDecision::parse(answer) {
+                    Some(ApprovalDecision::ApproveAlways) if !can_remember => {
+                        ApprovalDecision::Approve
+                    }
+                    Some(decision) => decision,
+                    None => ApprovalDecision::Deny,
+                },
+            })
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    struct FixedBroker {
+        decision: ApprovalDecision,
+        asked: Mutex<Vec<String>>,
+    }
+
+    impl FixedBroker {
+        fn new(decision: ApprovalDecision) -> Arc<Self> {
+            Arc::new(Self {
+                decision,
+                asked: Mutex::new(Vec::new()),
+            })
+        }
+    }
+
+    impl ApprovalBroker for FixedBroker {
+        fn request_approval<'a>(
+            &'a self,
+            request: &'a ApprovalRequest,
+        ) -> Pin<Box<dyn Future<Output = Result<ApprovalDecision>> + Send + 'a>> {
+            Box::pin(async move {
+                self.asked.lock().unwrap().push(request.summary());
+                Ok(self.decision)
+            })
+        }
+    }
+
+    struct SilentBroker;
+
+    impl ApprovalBroker for SilentBroker {
+        fn request_approval<'a>(
+            &'a self,
+            _request: &'a ApprovalRequest,
+        ) -> Pin<Box<dyn Future<Output = Result<ApprovalDecision>> + Send + 'a>> {
+            Box::pin(std::future::pending())
+        }
+    }
+
+    #[tokio::test]
+    async fn denied_tool_call_returns_approval_denied_error() {
+        let gate = ApprovalGate::new(FixedBroker::new(ApprovalDecision::Deny));
+        let error = gate
+            .authorize_tool("shell", &json!({"command": "rm -rf build"}), "user")
+            .await
+            .unwrap_err();
+        assert!(error.downcast_ref::<ApprovalDenied>().is_some());
+        assert!(error.to_string().contains("approval denied"));
+    }
+
+    #[tokio::test]
+    async fn approve_always_persists_grant_and_skips_next_prompt() {
+        let tmp = TempDir::new().unwrap();
+        let permissions = Arc::new(PermissionStore::load(tmp.path()));
+        let broker = FixedBroker::new(ApprovalDecision::ApproveAlways);
+        let gate = ApprovalGate::new(broker.clone()).with_permissions(Arc::clone(&permissions));
+        let args = json!({"command": "git status"});
+
+        gate.authorize_tool("shell", &args, "user").await.unwrap();
+        gate.authorize_tool("shell", &args, "user").await.unwrap();
+
+        assert_eq!(broker.asked.lock().unwrap().len(), 1);
+        assert!(PermissionStore::load(tmp.path()).is_granted("shell", "git status"));
+    }
+
+    #[tokio::test]
+    async fn approve_always_grants_only_the_exact_command() {
+        let tmp = TempDir::new().unwrap();
+        let permissions = Arc::new(PermissionStore::load(tmp.path()));
+        let gate = ApprovalGate::new(FixedBroker::new(ApprovalDecision::ApproveAlways))
+            .with_permissions(Arc::clone(&permissions));
+
+        gate.authorize_tool("shell", &json!({"command": "rm -rf build/*"}), "user")
+            .await
+            .unwrap();
+
+        let reloaded = PermissionStore::load(tmp.path());
+        assert!(reloaded.is_granted("shell", "rm -rf build/*"));
+        assert!(!reloaded.is_granted("shell", "rm -rf build/"));
+        assert!(!reloaded.is_granted("shell", "rm -rf build/ ~"));
+    }
+
+    #[tokio::test]
+    async fn unanswered_request_times_out_as_denied() {
+        let gate =
+            ApprovalGate::new(Arc::new(SilentBroker)).with_timeout(Duration::from_millis(20));
+        assert!(!gate.confirm_checkpoint("deploy", "user").await);
+    }
+
+    #[test]
+    fn pending_reply_resolves_only_for_requester() {
+        let pending = PendingApprovals::default();
+        let mut receiver = pending.register("abcd1234", "telegram:42");
+
+        assert!(!pending.resolve_reply("telegram:7", "approve abcd1234"));
+        assert!(!pending.resolve_reply("telegram:42", "hello there"));
+        assert!(pending.resolve_reply("telegram:42", "/deny abcd1234"));
+        assert_eq!(receiver.try_recv().unwrap(), ApprovalDecision::Deny);
+    }
+
+    #[test]
+    fn pending_reply_requires_the_request_id() {
+        let pending = PendingApprovals::default();
+        let mut receiver = pending.register("aaaa0001", "cli:user");
+        assert!(!pending.resolve_reply("cli:user", "yes"));
+        assert!(!pending.resolve_reply("cli:user", "approve"));
+        assert!(pending.resolve_reply("cli:user", "approve aaaa0001"));
+        assert_eq!(receiver.try_recv().unwrap(), ApprovalDecision::Approve);
+    }
+
+    #[test]
+    fn prompt_text_lists_reply_options() {
+        let request = ApprovalRequest::new(
+            "user",
+            ApprovalSubject::Checkpoint {
+                label: "deploy".to_string(),
+            },
+        );
+        let text = request.prompt_text();
+        assert!(text.contains("checkpoint `deploy`"));
+        assert!(text.contains(&format!("`deny {}`", request.id)));
+        assert!(!text.contains("always"));
+    }
+}
@@ -13,3 +13,56 @@ pub enum GrantScope {
     Session,
     Permanent,
 }
+
+/// Grant pattern that matches `subject` and nothing else.
+///
+/// A trailing `*` acts as a wildcard in grant patterns, so it is escaped as
+/// `\*` to keep an approved `rm -rf build/*` from covering every command
+/// under `build/`.
+pub fn exact_pattern(subject: &str) -> String {
+    match subject.strip_suffix('*') {
+        Some(literal) => format!("{literal}\\*"),
+        None => subject.to_string(),
+    }
+}
+
+/// Render the value a grant pattern is matched against for one tool call.
+///
+/// Shell grants match the command line and file grants match the path, so a
+/// `shell` grant with pattern `git *` approves any git invocation. Other
+/// tools match their compact JSON arguments.
+pub fn grant_subject(tool_name: &str, args: &serde_json::Value) -> String {
+    let field = match tool_name {
+        "shell" => "command",
+        "file_read" | "file_write" => "path",
+        _ => return args.to_string(),
+    };
+    args.get(field)
+        .and_then(serde_json::Value::as_str)
+        .unwrap_or_default()
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::grant_subject;
+    use serde_json::json;
+
+    #[test]
+    fn grant_subject_uses_shell_command() {
+        let args = json!({"command": "git status", "timeout": 5});
+        assert_eq!(grant_subject("shell", &args), "git status");
+    }
+
+    #[test]
+    fn grant_subject_uses_file_path() {
+        let args = json!({"path": "notes/today.md", "content": "x"});
+        assert_eq!(grant_subject("file_write", &args), "notes/today.md");
+    }
+
+    #[test]
+    fn grant_subject_falls_back_to_json_args() {
+        let args = json!({"key": "pref"});
+        assert_eq!(grant_subject("memory_store", &args), r#"{"key":"pref"}"#);
+    }
+}
@@ -1,3 +1,4 @@
+pub mod approval;
 pub mod defaults;
 pub mod external_content;
 pub mod grants;
@@ -9,8 +10,12 @@ pub mod secrets;
 pub mod url_validation;
 pub mod writeback_guard;
 
+pub use approval::{
+    ApprovalBroker, ApprovalDecision, ApprovalDenied, ApprovalGate, ApprovalRequest,
+    ApprovalSubject, CliApprovalBroker, GrantsOnlyBroker, PendingApprovals,
+};
 pub use defaults::{default_allowed_commands, default_forbidden_paths};
-pub use grants::{GrantScope, PermissionGrant};
+pub use grants::{GrantScope, PermissionGrant, exact_pattern, grant_subject};
 pub use permissions::PermissionStore;
 pub use policy::{
     ActionPolicyVerdict, AutonomyLevel, EntityRateLimiter, ExternalActionExecution, SecurityPolicy,
@@ -205,6 +205,10 @@ impl PermissionStore {
 
 #[must_use]
 fn pattern_matches(pattern: &str, value: &str) -> bool {
+    // `\*` at the end is a literal star (see `exact_pattern`).
+    if let Some(literal) = pattern.strip_suffix("\\*") {
+        return value.strip_suffix('*') == Some(literal);
+    }
     if pattern == "*" {
         return true;
     }
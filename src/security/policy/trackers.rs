@@ -157,6 +157,16 @@ impl CostTracker {
         true
     }
 
+    /// Count spend that has already happened, even past the daily limit.
+    pub fn add(&self, cents: u32) {
+        let mut state = self
+            .state
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        rollover_day_if_needed(&mut state);
+        state.spent_cents = state.spent_cents.saturating_add(cents);
+    }
+
     pub fn spent_today(&self) -> u32 {
         let mut state = self
             .state
@@ -197,7 +207,7 @@ fn rollover_day_if_needed(state: &mut DailyCostState) {
 
 #[cfg(test)]
 mod tests {
-    use super::{EntityRateLimiter, RateLimitError};
+    use super::{CostTracker, EntityRateLimiter, RateLimitError};
 
     #[test]
     fn entity_rate_limiter_allows_independent_entity_buckets() {
@@ -225,4 +235,13 @@ mod tests {
             Err(RateLimitError::GlobalExhausted)
         ));
     }
+
+    #[test]
+    fn cost_tracker_add_counts_spend_past_the_limit() {
+        let tracker = CostTracker::new();
+        tracker.add(400);
+        tracker.add(200);
+        assert_eq!(tracker.spent_today(), 600);
+        assert!(!tracker.record(0, 500));
+    }
 }
@@ -1,4 +1,4 @@
-use std::net::{IpAddr, ToSocketAddrs};
+use std::net::{IpAddr, SocketAddr, ToSocketAddrs};
 
 /// Returns `true` if the given host string resolves to a private / internal IP.
 #[must_use]
@@ -66,6 +66,37 @@ pub fn validate_url_not_ssrf(url_str: &str) -> anyhow::Result<()> {
     Ok(())
 }
 
+/// Resolve the host of `url` and check every address it resolves to.
+///
+/// Returns the host name and the addresses to connect to. Connecting to
+/// exactly these addresses (e.g. with `reqwest::ClientBuilder::resolve_to_addrs`)
+/// closes the window in which a second lookup could rebind the name to an
+/// internal address after the check.
+pub async fn resolve_public_addrs(url: &url::Url) -> anyhow::Result<(String, Vec<SocketAddr>)> {
+    let host = url
+        .host_str()
+        .ok_or_else(|| anyhow::anyhow!("URL has no host"))?
+        .to_string();
+    if is_private_host(&host) {
+        anyhow::bail!("URL points to private/internal address: {host}");
+    }
+    let port = url
+        .port_or_known_default()
+        .ok_or_else(|| anyhow::anyhow!("URL has no port"))?;
+    let lookup = host.trim_matches('[').trim_matches(']');
+    let addrs: Vec<SocketAddr> = tokio::net::lookup_host((lookup, port)).await?.collect();
+    if addrs.is_empty() {
+        anyhow::bail!("{host} did not resolve to any address");
+    }
+    if let Some(addr) = addrs.iter().find(|addr| is_private_ip(addr.ip())) {
+        anyhow::bail!(
+            "URL points to private/internal address: {host} ({})",
+            addr.ip()
+        );
+    }
+    Ok((host, addrs))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -96,6 +127,20 @@ mod tests {
         assert!(host_resolves_to_private_ip("localhost"));
     }
 
+    #[tokio::test]
+    async fn resolved_addresses_are_checked_before_use() {
+        let loopback = url::Url::parse("http://localhost:8080/").unwrap();
+        assert!(resolve_public_addrs(&loopback).await.is_err());
+
+        let private = url::Url::parse("http://10.1.2.3/").unwrap();
+        assert!(resolve_public_addrs(&private).await.is_err());
+
+        let public = url::Url::parse("https://93.184.215.14/path").unwrap();
+        let (host, addrs) = resolve_public_addrs(&public).await.unwrap();
+        assert_eq!(host, "93.184.215.14");
+        assert_eq!(addrs, ["93.184.215.14:443".parse::<SocketAddr>().unwrap()]);
+    }
+
     #[test]
     fn ssrf_validation_allows_public() {
         assert!(validate_url_not_ssrf("https://example.com").is_ok());
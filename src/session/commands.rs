@@ -0,0 +1,114 @@
+use super::manager::SessionManager;
+use anyhow::Result;
+
+/// Chat commands that act on the conversation history itself.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SessionCommand {
+    /// `/new` — archive the conversation and start over.
+    New,
+    /// `/compact` — summarize older messages now.
+    Compact,
+}
+
+impl SessionCommand {
+    /// Recognize `/new` and `/compact`, including Telegram's `/new@botname`
+    /// form. Anything else is a regular message.
+    pub fn parse(text: &str) -> Option<Self> {
+        let mut words = text.split_whitespace();
+        let command = words.next()?.strip_prefix('/')?;
+        if words.next().is_some() {
+            return None;
+        }
+        let command = command.split_once('@').map_or(command, |(name, _)| name);
+        match command.to_ascii_lowercase().as_str() {
+            "new" | "reset" => Some(Self::New),
+            "compact" => Some(Self::Compact),
+            _ => None,
+        }
+    }
+}
+
+impl SessionManager {
+    /// Run a session command for `(channel, user_id)` and return the reply.
+    pub async fn run_command(
+        &self,
+        channel: &str,
+        user_id: &str,
+        command: SessionCommand,
+    ) -> Result<String> {
+        match command {
+            SessionCommand::New => {
+                self.reset_session(channel, user_id).await?;
+                Ok("Started a new conversation.".to_string())
+            }
+            SessionCommand::Compact => {
+                let session = self.get_or_create(channel, user_id).await?;
+                let result = self.compact_now(&session.id).await?;
+                Ok(if result.compacted {
+                    format!(
+                        "Compacted {} earlier messages into a summary (~{} tokens saved).",
+                        result.messages_removed, result.tokens_saved
+                    )
+                } else {
+                    "Nothing to compact yet.".to_string()
+                })
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session::store::SqliteSessionStore;
+    use crate::session::types::SessionConfig;
+    use sqlx::sqlite::SqlitePoolOptions;
+    use std::sync::Arc;
+
+    #[test]
+    fn parse_recognizes_commands_only_on_their_own() {
+        assert_eq!(SessionCommand::parse("/new"), Some(SessionCommand::New));
+        assert_eq!(
+            SessionCommand::parse("  /compact@iris_bot "),
+            Some(SessionCommand::Compact)
+        );
+        assert_eq!(SessionCommand::parse("/new idea for you"), None);
+        assert_eq!(SessionCommand::parse("new"), None);
+        assert_eq!(SessionCommand::parse("/status"), None);
+    }
+
+    #[tokio::test]
+    async fn new_command_starts_fresh_history_repeatedly() {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        let store = Arc::new(SqliteSessionStore::new(pool).await.unwrap());
+        let manager = SessionManager::new(store, SessionConfig::default());
+
+        let first = manager.get_or_create("telegram", "chat-1").await.unwrap();
+        manager
+            .save(&first.id, "hello", "hi there", None, None)
+            .await
+            .unwrap();
+
+        for _ in 0..2 {
+            let reply = manager
+                .run_command("telegram", "chat-1", SessionCommand::New)
+                .await
+                .unwrap();
+            assert_eq!(reply, "Started a new conversation.");
+        }
+
+        let current = manager.get_or_create("telegram", "chat-1").await.unwrap();
+        assert_ne!(current.id, first.id);
+        assert!(
+            manager
+                .provider_history(&current.id)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+    }
+}
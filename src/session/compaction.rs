@@ -1,6 +1,7 @@
 use super::store::SessionStore;
-use super::types::{MessageRole, SessionState};
+use super::types::{ChatMessage, MessageRole, SessionState};
 use crate::llm::traits::Provider;
+use crate::prompt::{TeraEngine, build_compaction_prompt};
 use crate::utils::text::truncate_with_ellipsis;
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
@@ -50,22 +51,44 @@ pub struct CompactionResult {
     pub messages_removed: usize,
     pub level: Option<CompactionLevel>,
     pub summary_injected: bool,
+    /// Whether the summary was written by the model rather than the
+    /// truncating text fallback.
+    pub summarized_by_model: bool,
+    /// Estimated tokens of the evicted messages minus those of the summary.
+    pub tokens_saved: usize,
 }
 
 impl CompactionResult {
-    fn skipped() -> Self {
+    pub(super) fn skipped() -> Self {
         Self {
             compacted: false,
             messages_removed: 0,
             level: None,
             summary_injected: false,
+            summarized_by_model: false,
+            tokens_saved: 0,
         }
     }
 }
 
+/// The model that writes compaction summaries.
+#[derive(Clone, Copy)]
+pub struct Summarizer<'a> {
+    pub provider: &'a dyn Provider,
+    pub model: &'a str,
+}
+
+const SUMMARIZER_SYSTEM_PROMPT: &str = "You condense conversation transcripts into \
+factual notes that let the assistant continue the conversation later. Write plain \
+prose or bullet points; never address the user.";
+const SUMMARIZER_TEMPERATURE: f64 = 0.2;
+/// Per-message cap when building the summarization prompt, so one huge tool
+/// result cannot crowd out the rest of the span.
+const MAX_PROMPT_MESSAGE_CHARS: usize = 4_000;
+
 /// Estimate total token usage of a session's messages (rough heuristic).
 #[allow(clippy::cast_possible_truncation)]
-fn estimate_tokens(messages: &[super::types::ChatMessage]) -> usize {
+fn estimate_tokens(messages: &[ChatMessage]) -> usize {
     messages
         .iter()
         .map(|m| {
@@ -111,15 +134,84 @@ fn keep_fraction(level: CompactionLevel) -> usize {
     }
 }
 
+/// Summary length to ask the model for, as a percentage of the evicted span.
+fn target_ratio(level: CompactionLevel) -> f64 {
+    match level {
+        CompactionLevel::Light => 30.0,
+        CompactionLevel::Moderate => 20.0,
+        CompactionLevel::Aggressive => 10.0,
+    }
+}
+
+fn role_label(role: MessageRole) -> &'static str {
+    match role {
+        MessageRole::User => "User",
+        MessageRole::Assistant => "Assistant",
+        MessageRole::System => "System",
+    }
+}
+
+/// Approximate size of message text alone, ignoring recorded usage.
+fn estimate_text_tokens(text: &str) -> usize {
+    text.len() / 4
+}
+
+/// The pre-model summary: each message truncated to 200 characters.
+fn text_summary(messages: &[ChatMessage]) -> String {
+    messages
+        .iter()
+        .map(|message| {
+            format!(
+                "{}: {}",
+                role_label(message.role),
+                truncate_with_ellipsis(&message.content, 200)
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+async fn model_summary(
+    summarizer: Summarizer<'_>,
+    messages: &[ChatMessage],
+    level: CompactionLevel,
+) -> Result<String> {
+    let transcript = messages
+        .iter()
+        .map(|message| {
+            format!(
+                "{}: {}",
+                role_label(message.role),
+                truncate_with_ellipsis(&message.content, MAX_PROMPT_MESSAGE_CHARS)
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    let mut engine = TeraEngine::new()?;
+    let prompt = build_compaction_prompt(&mut engine, &transcript, target_ratio(level))?;
+    let summary = summarizer
+        .provider
+        .chat_with_system(
+            Some(SUMMARIZER_SYSTEM_PROMPT),
+            &prompt,
+            summarizer.model,
+            SUMMARIZER_TEMPERATURE,
+        )
+        .await?;
+    let summary = summary.trim();
+    anyhow::ensure!(!summary.is_empty(), "summarizer returned an empty summary");
+    Ok(summary.to_string())
+}
+
 /// Compact a session by summarizing old messages and deleting them.
 ///
-/// Uses tiered thresholds to determine how aggressively to compact.
-/// The `provider` parameter is reserved for future LLM-based summarization;
-/// currently a simple text summary is produced.
+/// Uses tiered thresholds to determine how aggressively to compact. With a
+/// `summarizer` the evicted span is summarized by the model; without one, or
+/// when the model call fails, each message is truncated into a text summary.
 pub async fn compact_session(
     store: &dyn SessionStore,
     session_id: &str,
-    _provider: Option<&dyn Provider>,
+    summarizer: Option<Summarizer<'_>>,
     config: &CompactionConfig,
 ) -> Result<CompactionResult> {
     let message_count = store.count_messages(session_id).await?;
@@ -139,24 +231,26 @@ pub async fn compact_session(
         return Ok(CompactionResult::skipped());
     }
 
-    let mut summary_parts = Vec::with_capacity(to_summarize.len());
-    for message in to_summarize {
-        let role_label = match message.role {
-            MessageRole::User => "User",
-            MessageRole::Assistant => "Assistant",
-            MessageRole::System => "System",
-        };
-        summary_parts.push(format!(
-            "{role_label}: {}",
-            truncate_with_ellipsis(&message.content, 200)
-        ));
-    }
+    let (body, summarized_by_model) = match summarizer {
+        Some(summarizer) => match model_summary(summarizer, to_summarize, level).await {
+            Ok(summary) => (summary, true),
+            Err(error) => {
+                tracing::warn!(%error, session_id, "model compaction failed; using text summary");
+                (text_summary(to_summarize), false)
+            }
+        },
+        None => (text_summary(to_summarize), false),
+    };
 
     let summary = format!(
-        "[Session history summary ({} messages compacted, level={level:?})]\n{}",
-        to_summarize.len(),
-        summary_parts.join("\n")
+        "[Session history summary ({} messages compacted, level={level:?})]\n{body}",
+        to_summarize.len()
     );
+    let evicted_tokens: usize = to_summarize
+        .iter()
+        .map(|message| estimate_text_tokens(&message.content))
+        .sum();
+    let tokens_saved = evicted_tokens.saturating_sub(estimate_text_tokens(&summary));
 
     let messages_removed = to_summarize.len();
 
@@ -172,11 +266,22 @@ pub async fn compact_session(
         .update_session_state(session_id, SessionState::Compacted)
         .await?;
 
+    tracing::info!(
+        session_id,
+        messages_removed,
+        evicted_tokens,
+        tokens_saved,
+        summarized_by_model,
+        "session compacted"
+    );
+
     Ok(CompactionResult {
         compacted: true,
         messages_removed,
         level: Some(level),
         summary_injected: true,
+        summarized_by_model,
+        tokens_saved,
     })
 }
 
@@ -186,6 +291,66 @@ mod tests {
     use crate::session::store::{SessionStore, SqliteSessionStore};
     use crate::session::types::{MessageRole, SessionState};
     use sqlx::sqlite::SqlitePoolOptions;
+    use std::future::Future;
+    use std::pin::Pin;
+
+    /// Answers with a fixed summary, or fails when `summary` is `None`.
+    struct SummaryProvider {
+        summary: Option<&'static str>,
+    }
+
+    impl Provider for SummaryProvider {
+        fn name(&self) -> &str {
+            "summary"
+        }
+
+        fn chat_with_system<'a>(
+            &'a self,
+            _system_prompt: Option<&'a str>,
+            message: &'a str,
+            model: &'a str,
+            _temperature: f64,
+        ) -> Pin<Box<dyn Future<Output = anyhow::Result<String>> + Send + 'a>> {
+            Box::pin(async move {
+                assert_eq!(model, "cheap-model");
+                assert!(message.contains("tool result: deployed v2"));
+                self.summary
+                    .map(str::to_string)
+                    .ok_or_else(|| anyhow::anyhow!("provider unavailable"))
+            })
+        }
+    }
+
+    async fn long_session(store: &SqliteSessionStore) -> String {
+        let session = store.create_session("cli", "u1").await.unwrap();
+        store
+            .append_message(&session.id, MessageRole::User, "please deploy", None, None)
+            .await
+            .unwrap();
+        store
+            .append_message(
+                &session.id,
+                MessageRole::Assistant,
+                &format!("[tool result: deployed v2] {}", "log line ".repeat(100)),
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+        for index in 0..6 {
+            store
+                .append_message(
+                    &session.id,
+                    MessageRole::User,
+                    &format!("msg-{index}"),
+                    None,
+                    None,
+                )
+                .await
+                .unwrap();
+        }
+        session.id
+    }
 
     async fn store() -> SqliteSessionStore {
         let pool = SqlitePoolOptions::new()
@@ -272,4 +437,63 @@ mod tests {
             CompactionLevel::Aggressive
         );
     }
+
+    #[tokio::test]
+    async fn compact_uses_model_summary_and_records_savings() {
+        let store = store().await;
+        let session_id = long_session(&store).await;
+        let provider = SummaryProvider {
+            summary: Some("User asked for a deploy; v2 was deployed."),
+        };
+        let summarizer = Summarizer {
+            provider: &provider,
+            model: "cheap-model",
+        };
+        let config = CompactionConfig {
+            threshold: 4,
+            ..CompactionConfig::default()
+        };
+
+        let result = compact_session(&store, &session_id, Some(summarizer), &config)
+            .await
+            .unwrap();
+
+        assert!(result.summarized_by_model);
+        assert!(result.tokens_saved > 0);
+        let messages = store.get_messages(&session_id, None).await.unwrap();
+        let summary = messages
+            .iter()
+            .find(|m| m.role == MessageRole::System)
+            .unwrap();
+        assert!(summary.content.contains("v2 was deployed"));
+        assert!(!summary.content.contains("log line"));
+    }
+
+    #[tokio::test]
+    async fn compact_falls_back_to_text_summary_when_model_fails() {
+        let store = store().await;
+        let session_id = long_session(&store).await;
+        let provider = SummaryProvider { summary: None };
+        let summarizer = Summarizer {
+            provider: &provider,
+            model: "cheap-model",
+        };
+        let config = CompactionConfig {
+            threshold: 4,
+            ..CompactionConfig::default()
+        };
+
+        let result = compact_session(&store, &session_id, Some(summarizer), &config)
+            .await
+            .unwrap();
+
+        assert!(result.compacted);
+        assert!(!result.summarized_by_model);
+        let messages = store.get_messages(&session_id, None).await.unwrap();
+        assert!(
+            messages
+                .iter()
+                .any(|m| m.content.contains("User: please deploy"))
+        );
+    }
 }
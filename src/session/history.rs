@@ -0,0 +1,211 @@
+use super::types::{ChatMessage, MessageRole};
+use crate::llm::{self, ContentBlock, ImageSource, ProviderMessage};
+use std::collections::HashSet;
+
+/// Plain-text rendering of structured content, stored alongside the blocks
+/// so compaction and listings can work on text alone.
+pub fn render_blocks(blocks: &[ContentBlock]) -> String {
+    let mut parts = Vec::with_capacity(blocks.len());
+    for block in blocks {
+        match block {
+            ContentBlock::Text { text } => parts.push(text.clone()),
+            ContentBlock::ToolUse { name, input, .. } => {
+                parts.push(format!("[tool call: {name} {input}]"));
+            }
+            ContentBlock::ToolResult {
+                content, is_error, ..
+            } => {
+                let label = if *is_error {
+                    "tool error"
+                } else {
+                    "tool result"
+                };
+                parts.push(format!("[{label}: {content}]"));
+            }
+            ContentBlock::Image { .. } => parts.push("[image]".to_string()),
+            ContentBlock::Thinking { .. } => {}
+        }
+    }
+    parts.join("\n")
+}
+
+/// Blocks as they should be persisted: inline image data is replaced with a
+/// placeholder so the session database does not grow with every upload.
+pub fn storable_blocks(blocks: &[ContentBlock]) -> Vec<ContentBlock> {
+    blocks
+        .iter()
+        .map(|block| match block {
+            ContentBlock::Image {
+                source: ImageSource::Base64 { .. },
+            } => ContentBlock::Text {
+                text: "[image]".to_string(),
+            },
+            other => other.clone(),
+        })
+        .collect()
+}
+
+/// Convert stored messages into provider history.
+///
+/// Compaction summaries are moved to the front, and tool calls whose results
+/// were cut off (or results whose calls were) are dropped so providers never
+/// see an unpaired tool exchange.
+pub fn provider_history(messages: &[ChatMessage]) -> Vec<ProviderMessage> {
+    let (summaries, turns): (Vec<_>, Vec<_>) = messages
+        .iter()
+        .partition(|message| message.role == MessageRole::System);
+
+    let mut history: Vec<ProviderMessage> = summaries
+        .into_iter()
+        .chain(turns)
+        .map(to_provider_message)
+        .collect();
+    drop_unpaired_tool_blocks(&mut history);
+    history
+}
+
+fn to_provider_message(message: &ChatMessage) -> ProviderMessage {
+    let role = match message.role {
+        MessageRole::User => llm::MessageRole::User,
+        MessageRole::Assistant => llm::MessageRole::Assistant,
+        MessageRole::System => llm::MessageRole::System,
+    };
+    let content = message.blocks.clone().unwrap_or_else(|| {
+        vec![ContentBlock::Text {
+            text: message.content.clone(),
+        }]
+    });
+    ProviderMessage { role, content }
+}
+
+fn drop_unpaired_tool_blocks(history: &mut Vec<ProviderMessage>) {
+    let answered: Vec<HashSet<String>> = (0..history.len())
+        .map(|index| {
+            history
+                .get(index + 1)
+                .map_or_else(HashSet::new, |next| tool_ids(next, true))
+        })
+        .collect();
+    let requested: Vec<HashSet<String>> = (0..history.len())
+        .map(|index| {
+            index
+                .checked_sub(1)
+                .map_or_else(HashSet::new, |prev| tool_ids(&history[prev], false))
+        })
+        .collect();
+
+    for (index, message) in history.iter_mut().enumerate() {
+        message.content.retain(|block| match block {
+            ContentBlock::ToolUse { id, .. } => answered[index].contains(id),
+            ContentBlock::ToolResult { tool_use_id, .. } => requested[index].contains(tool_use_id),
+            _ => true,
+        });
+    }
+    history.retain(|message| !message.content.is_empty());
+}
+
+fn tool_ids(message: &ProviderMessage, results: bool) -> HashSet<String> {
+    message
+        .content
+        .iter()
+        .filter_map(|block| match block {
+            ContentBlock::ToolResult { tool_use_id, .. } if results => Some(tool_use_id.clone()),
+            ContentBlock::ToolUse { id, .. } if !results => Some(id.clone()),
+            _ => None,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stored(role: MessageRole, content: &str, blocks: Option<Vec<ContentBlock>>) -> ChatMessage {
+        ChatMessage {
+            id: String::new(),
+            session_id: "s".to_string(),
+            role,
+            content: content.to_string(),
+            input_tokens: None,
+            output_tokens: None,
+            blocks,
+            created_at: String::new(),
+        }
+    }
+
+    fn tool_use(id: &str) -> ContentBlock {
+        ContentBlock::ToolUse {
+            id: id.to_string(),
+            name: "shell".to_string(),
+            input: serde_json::json!({"command": "ls"}),
+        }
+    }
+
+    fn tool_result(id: &str) -> ContentBlock {
+        ContentBlock::ToolResult {
+            tool_use_id: id.to_string(),
+            content: "ok".to_string(),
+            is_error: false,
+        }
+    }
+
+    #[test]
+    fn summaries_lead_and_tool_exchanges_survive() {
+        let messages = vec![
+            stored(MessageRole::User, "list files", None),
+            stored(MessageRole::Assistant, "", Some(vec![tool_use("t1")])),
+            stored(MessageRole::User, "", Some(vec![tool_result("t1")])),
+            stored(MessageRole::Assistant, "done", None),
+            stored(MessageRole::System, "[summary]", None),
+        ];
+
+        let history = provider_history(&messages);
+
+        assert_eq!(history.len(), 5);
+        assert_eq!(history[0].role, llm::MessageRole::System);
+        assert!(matches!(
+            history[2].content[0],
+            ContentBlock::ToolUse { .. }
+        ));
+        assert!(matches!(
+            history[3].content[0],
+            ContentBlock::ToolResult { .. }
+        ));
+    }
+
+    #[test]
+    fn unpaired_tool_blocks_are_dropped() {
+        let messages = vec![
+            stored(MessageRole::User, "", Some(vec![tool_result("cut")])),
+            stored(MessageRole::User, "hi", None),
+            stored(
+                MessageRole::Assistant,
+                "checking",
+                Some(vec![
+                    ContentBlock::Text {
+                        text: "checking".to_string(),
+                    },
+                    tool_use("orphan"),
+                ]),
+            ),
+        ];
+
+        let history = provider_history(&messages);
+
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[1].content.len(), 1);
+        assert!(matches!(history[1].content[0], ContentBlock::Text { .. }));
+    }
+
+    #[test]
+    fn storable_blocks_drop_inline_image_data() {
+        let blocks = vec![ContentBlock::Image {
+            source: ImageSource::Base64 {
+                media_type: "image/png".to_string(),
+                data: "AAAA".to_string(),
+            },
+        }];
+        let stored = storable_blocks(&blocks);
+        assert_eq!(render_blocks(&stored), "[image]");
+    }
+}
@@ -1,14 +1,28 @@
-use super::compaction::{self, CompactionConfig, CompactionResult};
-use super::store::SessionStore;
+use super::compaction::{self, CompactionConfig, CompactionResult, Summarizer};
+use super::history::{provider_history, render_blocks, storable_blocks};
+use super::store::{SessionStore, SqliteSessionStore};
 use super::types::{ChatMessage, MessageRole, Session, SessionConfig, SessionState};
+use crate::llm::{self, ContentBlock, Provider, ProviderMessage};
 use anyhow::Result;
-use std::sync::Arc;
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::{Arc, Mutex, PoisonError};
+use tokio::task::JoinHandle;
+
+/// A live session together with the history to send with the next turn.
+pub struct ResumedSession {
+    pub id: String,
+    pub history: Vec<ProviderMessage>,
+}
 
 /// High-level session management wrapping a `SessionStore` + config.
 pub struct SessionManager {
     store: Arc<dyn SessionStore>,
     config: SessionConfig,
     compaction_config: CompactionConfig,
+    summarizer: Option<(Arc<dyn Provider>, String)>,
+    /// One compaction at a time per session.
+    compaction_locks: Mutex<HashMap<String, Arc<tokio::sync::Mutex<()>>>>,
 }
 
 impl SessionManager {
@@ -21,9 +35,59 @@ impl SessionManager {
             store,
             config,
             compaction_config,
+            summarizer: None,
+            compaction_locks: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Open the workspace session database.
+    pub async fn open(workspace_dir: &Path, config: SessionConfig) -> Result<Self> {
+        let store = SqliteSessionStore::open(workspace_dir).await?;
+        Ok(Self::new(Arc::new(store), config))
+    }
+
+    /// Open the workspace sessions when `config.enabled`; a store that fails
+    /// to open is logged and leaves conversations without history.
+    ///
+    /// Compaction summaries are written by `provider` using
+    /// `config.compaction_model`, or `chat_model` when none is set.
+    pub async fn open_if_enabled(
+        workspace_dir: &Path,
+        config: &SessionConfig,
+        provider: Arc<dyn Provider>,
+        chat_model: &str,
+    ) -> Option<Arc<Self>> {
+        if !config.enabled {
+            return None;
+        }
+        let model = config
+            .compaction_model
+            .clone()
+            .unwrap_or_else(|| chat_model.to_string());
+        match Self::open(workspace_dir, config.clone()).await {
+            Ok(manager) => Some(Arc::new(manager.with_summarizer(provider, model))),
+            Err(error) => {
+                tracing::warn!(%error, "session store unavailable; conversations will not keep history");
+                None
+            }
         }
     }
 
+    /// Summarize compacted history with `model` instead of truncating it.
+    pub fn with_summarizer(mut self, provider: Arc<dyn Provider>, model: String) -> Self {
+        self.summarizer = Some((provider, model));
+        self
+    }
+
+    fn summarizer(&self) -> Option<Summarizer<'_>> {
+        self.summarizer
+            .as_ref()
+            .map(|(provider, model)| Summarizer {
+                provider: provider.as_ref(),
+                model,
+            })
+    }
+
     pub fn with_compaction_config(mut self, compaction_config: CompactionConfig) -> Self {
         self.compaction_config = compaction_config;
         self
@@ -58,25 +122,54 @@ impl SessionManager {
         Ok(())
     }
 
+    fn compaction_lock(&self, session_id: &str) -> Arc<tokio::sync::Mutex<()>> {
+        Arc::clone(
+            self.compaction_locks
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .entry(session_id.to_string())
+                .or_default(),
+        )
+    }
+
     /// Compact the session if it exceeds the configured threshold.
     pub async fn compact_if_needed(&self, session_id: &str) -> Result<CompactionResult> {
+        let lock = self.compaction_lock(session_id);
+        let _guard = lock.lock().await;
+        self.compact_locked(session_id).await
+    }
+
+    async fn compact_locked(&self, session_id: &str) -> Result<CompactionResult> {
         if self.config.compaction_threshold == 0 {
-            return Ok(CompactionResult {
-                compacted: false,
-                messages_removed: 0,
-                level: None,
-                summary_injected: false,
-            });
+            return Ok(CompactionResult::skipped());
         }
         compaction::compact_session(
             self.store.as_ref(),
             session_id,
-            None,
+            self.summarizer(),
             &self.compaction_config,
         )
         .await
     }
 
+    /// Compact the session in a background task so the reply is not held
+    /// up by the summarizer. Skipped (`None`) while a compaction of the
+    /// same session is already running; it covers the new messages too.
+    pub fn compact_in_background(self: &Arc<Self>, session_id: &str) -> Option<JoinHandle<()>> {
+        if self.config.compaction_threshold == 0 {
+            return None;
+        }
+        let guard = self.compaction_lock(session_id).try_lock_owned().ok()?;
+        let manager = Arc::clone(self);
+        let session_id = session_id.to_string();
+        Some(tokio::spawn(async move {
+            let _guard = guard;
+            if let Err(error) = manager.compact_locked(&session_id).await {
+                tracing::warn!(%error, session_id, "session compaction failed");
+            }
+        }))
+    }
+
     /// Get message history for a session.
     pub async fn get_history(&self, session_id: &str) -> Result<Vec<ChatMessage>> {
         let limit = if self.config.max_history > 0 {
@@ -87,6 +180,70 @@ impl SessionManager {
         self.store.get_messages(session_id, limit).await
     }
 
+    /// The live session for `(channel, user_id)` and its recent history.
+    pub async fn resume(&self, channel: &str, user_id: &str) -> Result<ResumedSession> {
+        let session = self.get_or_create(channel, user_id).await?;
+        let history = self.provider_history(&session.id).await?;
+        Ok(ResumedSession {
+            id: session.id,
+            history,
+        })
+    }
+
+    /// Recent history for a session, ready to pass to a provider.
+    pub async fn provider_history(&self, session_id: &str) -> Result<Vec<ProviderMessage>> {
+        let messages = self.get_history(session_id).await?;
+        Ok(provider_history(&messages))
+    }
+
+    /// Save a turn as the user saw it followed by every reply the tool loop
+    /// produced (assistant text, tool calls and tool results), then compact
+    /// in the background if the session has grown past the threshold.
+    ///
+    /// `transcript` starts with the user message as sent to the provider;
+    /// it is replaced by `user_message` so injected context is not persisted.
+    pub async fn record_turn(
+        self: &Arc<Self>,
+        session_id: &str,
+        user_message: &str,
+        transcript: &[ProviderMessage],
+    ) -> Result<()> {
+        self.store
+            .append_message(session_id, MessageRole::User, user_message, None, None)
+            .await?;
+        for message in transcript.iter().skip(1) {
+            let role = match message.role {
+                llm::MessageRole::User => MessageRole::User,
+                llm::MessageRole::Assistant => MessageRole::Assistant,
+                llm::MessageRole::System => MessageRole::System,
+            };
+            if let [ContentBlock::Text { text }] = message.content.as_slice() {
+                self.store
+                    .append_message(session_id, role, text, None, None)
+                    .await?;
+            } else {
+                let blocks = storable_blocks(&message.content);
+                self.store
+                    .append_blocks(session_id, role, &render_blocks(&blocks), &blocks)
+                    .await?;
+            }
+        }
+        self.compact_in_background(session_id);
+        Ok(())
+    }
+
+    /// Compact the session now, regardless of the configured threshold.
+    pub async fn compact_now(&self, session_id: &str) -> Result<CompactionResult> {
+        let config = CompactionConfig {
+            threshold: 0,
+            ..self.compaction_config.clone()
+        };
+        let lock = self.compaction_lock(session_id);
+        let _guard = lock.lock().await;
+        compaction::compact_session(self.store.as_ref(), session_id, self.summarizer(), &config)
+            .await
+    }
+
     /// Archive the current active session and create a fresh one.
     pub async fn reset_session(&self, channel: &str, user_id: &str) -> Result<Session> {
         if let Some(existing) = self.find_active_session(channel, user_id).await? {
@@ -116,7 +273,7 @@ impl SessionManager {
         let sessions = self.store.list_sessions(Some(channel)).await?;
         Ok(sessions
             .into_iter()
-            .find(|session| session.user_id == user_id && session.state == SessionState::Active))
+            .find(|session| session.user_id == user_id && session.state != SessionState::Archived))
     }
 }
 
@@ -218,4 +375,98 @@ mod tests {
         let result = manager.compact_if_needed(&session.id).await.unwrap();
         assert!(!result.compacted);
     }
+
+    /// Holds every summary until `release` is notified.
+    struct GatedSummarizer {
+        release: Arc<tokio::sync::Notify>,
+        calls: std::sync::atomic::AtomicUsize,
+    }
+
+    impl Provider for GatedSummarizer {
+        fn name(&self) -> &str {
+            "gated"
+        }
+
+        fn chat_with_system<'a>(
+            &'a self,
+            _system_prompt: Option<&'a str>,
+            _message: &'a str,
+            _model: &'a str,
+            _temperature: f64,
+        ) -> std::pin::Pin<Box<dyn std::future::Future<Output = Result<String>> + Send + 'a>>
+        {
+            Box::pin(async move {
+                self.calls.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                self.release.notified().await;
+                Ok("summary".to_string())
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn record_turn_compacts_in_the_background_one_at_a_time() {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        let store = Arc::new(SqliteSessionStore::new(pool).await.unwrap());
+        let release = Arc::new(tokio::sync::Notify::new());
+        let summarizer = Arc::new(GatedSummarizer {
+            release: Arc::clone(&release),
+            calls: std::sync::atomic::AtomicUsize::new(0),
+        });
+        let config = SessionConfig {
+            compaction_threshold: 4,
+            ..SessionConfig::default()
+        };
+        let manager = Arc::new(
+            SessionManager::new(store, config)
+                .with_summarizer(Arc::clone(&summarizer) as Arc<dyn Provider>, "m".into()),
+        );
+        let session = manager.get_or_create("cli", "u1").await.unwrap();
+        for index in 0..3 {
+            manager
+                .save(&session.id, &format!("q{index}"), "a", None, None)
+                .await
+                .unwrap();
+        }
+
+        // The turn is recorded without waiting for the held summary.
+        let reply = ProviderMessage {
+            role: llm::MessageRole::Assistant,
+            content: vec![ContentBlock::Text {
+                text: "done".into(),
+            }],
+        };
+        tokio::time::timeout(
+            std::time::Duration::from_secs(5),
+            manager.record_turn(&session.id, "last", &[ProviderMessage::user("last"), reply]),
+        )
+        .await
+        .expect("record_turn does not wait for compaction")
+        .unwrap();
+        while summarizer.calls.load(std::sync::atomic::Ordering::SeqCst) == 0 {
+            tokio::task::yield_now().await;
+        }
+        assert!(manager.compact_in_background(&session.id).is_none());
+
+        release.notify_one();
+        let lock = manager.compaction_lock(&session.id);
+        drop(lock.lock().await);
+        let messages = manager
+            .store()
+            .get_messages(&session.id, None)
+            .await
+            .unwrap();
+        let summaries = messages
+            .iter()
+            .filter(|message| message.role == MessageRole::System)
+            .count();
+        assert_eq!(summaries, 1);
+        assert_eq!(
+            summarizer.calls.load(std::sync::atomic::Ordering::SeqCst),
+            1
+        );
+    }
 }
@@ -1,9 +1,14 @@
+pub mod commands;
 pub mod compaction;
+pub mod history;
 pub mod manager;
 pub mod store;
 pub mod types;
 
-pub use compaction::{CompactionConfig, CompactionLevel, CompactionResult, compact_session};
-pub use manager::SessionManager;
+pub use commands::SessionCommand;
+pub use compaction::{
+    CompactionConfig, CompactionLevel, CompactionResult, Summarizer, compact_session,
+};
+pub use manager::{ResumedSession, SessionManager};
 pub use store::{SessionStore, SqliteSessionStore};
 pub use types::{ChatMessage, MessageRole, Session, SessionConfig, SessionState};
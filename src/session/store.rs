@@ -1,9 +1,11 @@
 use super::types::{ChatMessage, MessageRole, Session, SessionState};
+use crate::llm::ContentBlock;
 use anyhow::{Context, Result};
 use chrono::Utc;
 use sqlx::Row;
-use sqlx::sqlite::{SqlitePool, SqliteRow};
+use sqlx::sqlite::{SqlitePool, SqlitePoolOptions, SqliteRow};
 use std::future::Future;
+use std::path::Path;
 use std::pin::Pin;
 use uuid::Uuid;
 
@@ -57,6 +59,16 @@ pub trait SessionStore: Send + Sync {
         output_tokens: Option<u64>,
     ) -> Pin<Box<dyn Future<Output = Result<ChatMessage>> + Send + 'a>>;
 
+    /// Append a message that carries structured content (tool calls, tool
+    /// results). `content` holds its plain-text rendering.
+    fn append_blocks<'a>(
+        &'a self,
+        session_id: &'a str,
+        role: MessageRole,
+        content: &'a str,
+        blocks: &'a [ContentBlock],
+    ) -> Pin<Box<dyn Future<Output = Result<ChatMessage>> + Send + 'a>>;
+
     fn get_messages<'a>(
         &'a self,
         session_id: &'a str,
@@ -86,7 +98,7 @@ CREATE TABLE IF NOT EXISTS session_schema_meta (
     value TEXT NOT NULL
 )";
 const SESSION_SCHEMA_VERSION_KEY: &str = "session_schema_version";
-const SESSION_SCHEMA_VERSION: u32 = 1;
+const SESSION_SCHEMA_VERSION: u32 = 2;
 
 async fn ensure_session_schema_version(pool: &SqlitePool) -> Result<()> {
     sqlx::query(SESSION_SCHEMA_META_TABLE)
@@ -141,6 +153,22 @@ compatibility is disabled; remove session DB and restart."
 }
 
 impl SqliteSessionStore {
+    /// Open (or create) the session database under
+    /// `<workspace>/sessions/sessions.db`.
+    pub async fn open(workspace_dir: &Path) -> Result<Self> {
+        let dir = workspace_dir.join("sessions");
+        tokio::fs::create_dir_all(&dir)
+            .await
+            .context("create sessions directory")?;
+        let url = format!("sqlite://{}?mode=rwc", dir.join("sessions.db").display());
+        let pool = SqlitePoolOptions::new()
+            .max_connections(4)
+            .connect(&url)
+            .await
+            .context("open session database")?;
+        Self::new(pool).await
+    }
+
     /// Create a new store with an existing pool and run migrations.
     pub async fn new(pool: SqlitePool) -> Result<Self> {
         sqlx::query("PRAGMA foreign_keys = ON;")
@@ -158,13 +186,20 @@ impl SqliteSessionStore {
                  model TEXT,
                  metadata TEXT,
                  created_at TEXT NOT NULL,
-                 updated_at TEXT NOT NULL,
-                 UNIQUE(channel, user_id, state)
+                 updated_at TEXT NOT NULL
              )",
         )
         .execute(&pool)
         .await?;
 
+        // Any number of archived sessions, but only one live one per user.
+        sqlx::query(
+            "CREATE UNIQUE INDEX IF NOT EXISTS idx_sessions_live
+                 ON sessions(channel, user_id) WHERE state != 'archived'",
+        )
+        .execute(&pool)
+        .await?;
+
         sqlx::query(
             "CREATE TABLE IF NOT EXISTS chat_messages (
                  id TEXT PRIMARY KEY,
@@ -173,6 +208,7 @@ impl SqliteSessionStore {
                  content TEXT NOT NULL,
                  input_tokens INTEGER,
                  output_tokens INTEGER,
+                 blocks TEXT,
                  created_at TEXT NOT NULL
              )",
         )
@@ -193,6 +229,65 @@ impl SqliteSessionStore {
     pub fn pool(&self) -> &SqlitePool {
         &self.pool
     }
+
+    async fn insert_message(&self, message: NewMessage<'_>) -> Result<ChatMessage> {
+        let message_id = Uuid::new_v4().to_string();
+        let created_at = Utc::now().to_rfc3339();
+        #[allow(clippy::cast_possible_wrap)]
+        let input_tokens_i64 = message.input_tokens.map(|v| v as i64);
+        #[allow(clippy::cast_possible_wrap)]
+        let output_tokens_i64 = message.output_tokens.map(|v| v as i64);
+        let blocks_json = message
+            .blocks
+            .map(serde_json::to_string)
+            .transpose()
+            .context("serialize message blocks")?;
+
+        sqlx::query(
+            "INSERT INTO chat_messages (id, session_id, role, content, input_tokens, output_tokens, blocks, created_at)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8)",
+        )
+        .bind(&message_id)
+        .bind(message.session_id)
+        .bind(role_to_str(message.role))
+        .bind(message.content)
+        .bind(input_tokens_i64)
+        .bind(output_tokens_i64)
+        .bind(blocks_json)
+        .bind(&created_at)
+        .execute(&self.pool)
+        .await?;
+
+        sqlx::query(
+            "UPDATE sessions
+             SET updated_at = $1
+             WHERE id = $2",
+        )
+        .bind(&created_at)
+        .bind(message.session_id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(ChatMessage {
+            id: message_id,
+            session_id: message.session_id.to_string(),
+            role: message.role,
+            content: message.content.to_string(),
+            input_tokens: message.input_tokens,
+            output_tokens: message.output_tokens,
+            blocks: message.blocks.map(<[ContentBlock]>::to_vec),
+            created_at,
+        })
+    }
+}
+
+struct NewMessage<'a> {
+    session_id: &'a str,
+    role: MessageRole,
+    content: &'a str,
+    input_tokens: Option<u64>,
+    output_tokens: Option<u64>,
+    blocks: Option<&'a [ContentBlock]>,
 }
 
 fn state_to_str(state: SessionState) -> &'static str {
@@ -253,6 +348,11 @@ fn map_chat_message_row(row: &SqliteRow) -> Result<ChatMessage> {
     let role_raw: String = row.try_get("role")?;
     let input_tokens: Option<i64> = row.try_get("input_tokens")?;
     let output_tokens: Option<i64> = row.try_get("output_tokens")?;
+    let blocks_raw: Option<String> = row.try_get("blocks")?;
+    let blocks = blocks_raw
+        .map(|value| serde_json::from_str::<Vec<ContentBlock>>(&value))
+        .transpose()
+        .context("deserialize message blocks")?;
 
     Ok(ChatMessage {
         id: row.try_get("id")?,
@@ -263,6 +363,7 @@ fn map_chat_message_row(row: &SqliteRow) -> Result<ChatMessage> {
         input_tokens: input_tokens.map(|v| v as u64),
         #[allow(clippy::cast_sign_loss)]
         output_tokens: output_tokens.map(|v| v as u64),
+        blocks,
         created_at: row.try_get("created_at")?,
     })
 }
@@ -331,7 +432,7 @@ impl SessionStore for SqliteSessionStore {
             let row = sqlx::query(
                 "SELECT id, channel, user_id, state, model, metadata, created_at, updated_at
                  FROM sessions
-                 WHERE channel = $1 AND user_id = $2 AND state = 'active'
+                 WHERE channel = $1 AND user_id = $2 AND state != 'archived'
                  ORDER BY updated_at DESC
                  LIMIT 1",
             )
@@ -441,48 +542,31 @@ impl SessionStore for SqliteSessionStore {
         input_tokens: Option<u64>,
         output_tokens: Option<u64>,
     ) -> Pin<Box<dyn Future<Output = Result<ChatMessage>> + Send + 'a>> {
-        Box::pin(async move {
-            let message_id = Uuid::new_v4().to_string();
-            let created_at = Utc::now().to_rfc3339();
-            #[allow(clippy::cast_possible_wrap)]
-            let input_tokens_i64 = input_tokens.map(|v| v as i64);
-            #[allow(clippy::cast_possible_wrap)]
-            let output_tokens_i64 = output_tokens.map(|v| v as i64);
-
-            sqlx::query(
-                "INSERT INTO chat_messages (id, session_id, role, content, input_tokens, output_tokens, created_at)
-                 VALUES ($1, $2, $3, $4, $5, $6, $7)",
-            )
-            .bind(&message_id)
-            .bind(session_id)
-            .bind(role_to_str(role))
-            .bind(content)
-            .bind(input_tokens_i64)
-            .bind(output_tokens_i64)
-            .bind(&created_at)
-            .execute(&self.pool)
-            .await?;
-
-            sqlx::query(
-                "UPDATE sessions
-                 SET updated_at = $1
-                 WHERE id = $2",
-            )
-            .bind(&created_at)
-            .bind(session_id)
-            .execute(&self.pool)
-            .await?;
+        Box::pin(self.insert_message(NewMessage {
+            session_id,
+            role,
+            content,
+            input_tokens,
+            output_tokens,
+            blocks: None,
+        }))
+    }
 
-            Ok(ChatMessage {
-                id: message_id,
-                session_id: session_id.to_string(),
-                role,
-                content: content.to_string(),
-                input_tokens,
-                output_tokens,
-                created_at,
-            })
-        })
+    fn append_blocks<'a>(
+        &'a self,
+        session_id: &'a str,
+        role: MessageRole,
+        content: &'a str,
+        blocks: &'a [ContentBlock],
+    ) -> Pin<Box<dyn Future<Output = Result<ChatMessage>> + Send + 'a>> {
+        Box::pin(self.insert_message(NewMessage {
+            session_id,
+            role,
+            content,
+            input_tokens: None,
+            output_tokens: None,
+            blocks: Some(blocks),
+        }))
     }
 
     fn get_messages<'a>(
@@ -496,7 +580,7 @@ impl SessionStore for SqliteSessionStore {
                 let limit_i64 = limit_count as i64;
 
                 let rows = sqlx::query(
-                    "SELECT id, session_id, role, content, input_tokens, output_tokens, created_at
+                    "SELECT id, session_id, role, content, input_tokens, output_tokens, blocks, created_at
                      FROM chat_messages
                      WHERE session_id = $1
                      ORDER BY created_at DESC
@@ -515,7 +599,7 @@ impl SessionStore for SqliteSessionStore {
                 Ok(messages)
             } else {
                 let rows = sqlx::query(
-                    "SELECT id, session_id, role, content, input_tokens, output_tokens, created_at
+                    "SELECT id, session_id, role, content, input_tokens, output_tokens, blocks, created_at
                      FROM chat_messages
                      WHERE session_id = $1
                      ORDER BY created_at ASC",
@@ -1,3 +1,4 @@
+use crate::llm::ContentBlock;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -16,6 +17,10 @@ pub struct ChatMessage {
     pub content: String,
     pub input_tokens: Option<u64>,
     pub output_tokens: Option<u64>,
+    /// Structured content for tool calls and results; `content` then holds
+    /// a plain-text rendering.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub blocks: Option<Vec<ContentBlock>>,
     pub created_at: String,
 }
 
@@ -40,10 +45,14 @@ pub struct Session {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct SessionConfig {
     pub enabled: bool,
     pub max_history: usize,
     pub compaction_threshold: usize,
+    /// Model that summarizes compacted history; defaults to the chat model.
+    /// Set a cheaper model here to keep compaction inexpensive.
+    pub compaction_model: Option<String>,
 }
 
 impl Default for SessionConfig {
@@ -52,6 +61,7 @@ impl Default for SessionConfig {
             enabled: true,
             max_history: 100,
             compaction_threshold: 50,
+            compaction_model: None,
         }
     }
 }
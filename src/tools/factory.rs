@@ -2,16 +2,19 @@ use super::file_read::FileReadTool;
 use super::file_write::FileWriteTool;
 use super::memory::{MemoryForgetTool, MemoryGovernanceTool, MemoryRecallTool, MemoryStoreTool};
 use super::shell::ShellTool;
+use super::tool_output::ToolOutputTool;
 use super::traits::Tool;
 use crate::memory::Memory;
 use std::sync::Arc;
 
-/// Create the default set of core tools (shell, `file_read`, `file_write`).
+/// Create the default set of core tools (shell, `file_read`, `file_write`,
+/// `tool_output`).
 pub fn default_tools() -> Vec<Box<dyn Tool>> {
     vec![
         Box::new(ShellTool::new()),
         Box::new(FileReadTool::new()),
         Box::new(FileWriteTool::new()),
+        Box::new(ToolOutputTool::new()),
     ]
 }
 
@@ -46,6 +49,10 @@ pub fn tool_descriptions() -> Vec<(String, String)> {
             "file_write".to_string(),
             "Write file contents. Use when: applying focused edits, scaffolding files, updating docs/code. Don't use when: side effects are unclear or file ownership is uncertain.".to_string(),
         ),
+        (
+            "tool_output".to_string(),
+            "Read a truncated or elided tool output by handle. Use when: a notice says output was cut and the missing part matters. Don't use when: the visible excerpt already answers the question.".to_string(),
+        ),
         (
             "memory_store".to_string(),
             "Save to memory. Use when: preserving durable preferences, decisions, key context. Don't use when: information is transient/noisy/sensitive without need.".to_string(),
@@ -70,13 +77,14 @@ mod tests {
     use super::*;
 
     #[test]
-    fn default_tools_returns_three_core_tools() {
+    fn default_tools_returns_four_core_tools() {
         let tools = default_tools();
-        assert_eq!(tools.len(), 3);
+        assert_eq!(tools.len(), 4);
         let names: Vec<&str> = tools.iter().map(|t| t.name()).collect();
         assert!(names.contains(&"shell"));
         assert!(names.contains(&"file_read"));
         assert!(names.contains(&"file_write"));
+        assert!(names.contains(&"tool_output"));
     }
 
     #[test]
@@ -36,6 +36,10 @@ impl Tool for FileReadTool {
         })
     }
 
+    fn is_parallel_safe(&self) -> bool {
+        true
+    }
+
     fn execute<'a>(
         &'a self,
         args: serde_json::Value,
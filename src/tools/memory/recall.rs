@@ -87,6 +87,10 @@ impl Tool for MemoryRecallTool {
         })
     }
 
+    fn is_parallel_safe(&self) -> bool {
+        true
+    }
+
     fn execute<'a>(
         &'a self,
         args: serde_json::Value,
@@ -1,6 +1,7 @@
 pub use super::traits::{ExecutionContext, MiddlewareDecision, ToolMiddleware};
 use super::types::ToolResult;
 use crate::llm::scrub_secret_patterns;
+use crate::security::approval::ApprovalDenied;
 use crate::security::external_content::{ExternalAction, prepare_external_content};
 use crate::security::policy::{AutonomyLevel, RateLimitError};
 use serde_json::Value;
@@ -23,6 +24,14 @@ fn is_critical_bootstrap_write_target(path: &str) -> bool {
         })
 }
 
+/// Tools that never mutate state and stay available under read-only autonomy.
+pub fn is_read_only_tool(tool_name: &str) -> bool {
+    matches!(
+        tool_name,
+        "file_read" | "memory_recall" | "browser" | "tool_output"
+    )
+}
+
 // ── SecurityMiddleware ──────────────────────────────────────────────
 
 #[derive(Debug)]
@@ -37,15 +46,10 @@ impl ToolMiddleware for SecurityMiddleware {
         ctx: &'a ExecutionContext,
     ) -> Pin<Box<dyn Future<Output = anyhow::Result<MiddlewareDecision>> + Send + 'a>> {
         Box::pin(async move {
-            if ctx.autonomy_level == AutonomyLevel::ReadOnly {
-                match tool_name {
-                    "file_read" | "memory_recall" | "browser" => {}
-                    _ => {
-                        return Ok(MiddlewareDecision::Block(
-                            "blocked by security policy: autonomy is read-only".to_string(),
-                        ));
-                    }
-                }
+            if ctx.autonomy_level == AutonomyLevel::ReadOnly && !is_read_only_tool(tool_name) {
+                return Ok(MiddlewareDecision::Block(
+                    "blocked by security policy: autonomy is read-only".to_string(),
+                ));
             }
 
             if let Some(allowed_tools) = &ctx.allowed_tools
@@ -139,6 +143,50 @@ impl ToolMiddleware for SecurityMiddleware {
     }
 }
 
+// ── ApprovalMiddleware ──────────────────────────────────────────────
+
+/// Pauses supervised tool calls until a human approves them.
+///
+/// Without an approval gate in the context nobody can approve, so such calls
+/// are denied. A denial surfaces as an `approval denied` error, which stops
+/// the tool loop.
+#[derive(Debug)]
+pub struct ApprovalMiddleware;
+
+impl ToolMiddleware for ApprovalMiddleware {
+    fn before_execute<'a>(
+        &'a self,
+        tool_name: &'a str,
+        args: &'a Value,
+        ctx: &'a ExecutionContext,
+    ) -> Pin<Box<dyn Future<Output = anyhow::Result<MiddlewareDecision>> + Send + 'a>> {
+        Box::pin(async move {
+            if ctx.autonomy_level != AutonomyLevel::Supervised || is_read_only_tool(tool_name) {
+                return Ok(MiddlewareDecision::Continue);
+            }
+            match &ctx.approval {
+                Some(gate) => gate.authorize_tool(tool_name, args, &ctx.entity_id).await?,
+                None => {
+                    return Err(ApprovalDenied {
+                        summary: format!("{tool_name} needs approval but no approver is available"),
+                    }
+                    .into());
+                }
+            }
+            Ok(MiddlewareDecision::Continue)
+        })
+    }
+
+    fn after_execute<'a>(
+        &'a self,
+        _tool_name: &'a str,
+        _result: &'a mut ToolResult,
+        _ctx: &'a ExecutionContext,
+    ) -> Pin<Box<dyn Future<Output = ()> + Send + 'a>> {
+        Box::pin(async move {})
+    }
+}
+
 // ── EntityRateLimitMiddleware ───────────────────────────────────────
 
 #[derive(Debug)]
@@ -372,6 +420,7 @@ impl ToolMiddleware for SecretScrubMiddleware {
 pub fn default_middleware_chain() -> Vec<Arc<dyn ToolMiddleware>> {
     vec![
         Arc::new(SecurityMiddleware),
+        Arc::new(ApprovalMiddleware),
         Arc::new(EntityRateLimitMiddleware),
         Arc::new(AuditMiddleware),
         Arc::new(OutputSizeLimitMiddleware),
@@ -8,6 +8,7 @@ pub mod memory;
 pub mod middleware;
 pub mod registry;
 pub mod shell;
+pub mod tool_output;
 pub mod traits;
 pub mod types;
 
@@ -19,5 +20,6 @@ pub use isolation::{NamespaceMiddleware, ToolServer};
 pub use memory::{MemoryForgetTool, MemoryGovernanceTool, MemoryRecallTool, MemoryStoreTool};
 pub use registry::ToolRegistry;
 pub use shell::ShellTool;
+pub use tool_output::{ToolOutputStore, ToolOutputTool};
 pub use traits::{ExecutionContext, MiddlewareDecision, Tool, ToolMiddleware};
 pub use types::{OutputAttachment, ToolResult, ToolSpec};
@@ -1,8 +1,10 @@
 use super::traits::{ExecutionContext, MiddlewareDecision, Tool, ToolMiddleware};
 use super::types::{ToolResult, ToolSpec};
+use crate::runtime::observability::{ObserverEvent, global_observer};
 use serde_json::Value;
 use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 /// Central registry for tool instances and middleware pipeline.
 #[derive(Default)]
@@ -35,6 +37,13 @@ impl ToolRegistry {
         self.tools.get(name)
     }
 
+    /// Whether `name` is a registered tool that may run concurrently.
+    pub fn is_parallel_safe(&self, name: &str) -> bool {
+        self.tools
+            .get(name)
+            .is_some_and(|tool| tool.is_parallel_safe())
+    }
+
     /// Return sorted list of registered tool names.
     pub fn tool_names(&self) -> Vec<&str> {
         let mut names: Vec<&str> = self.tools.keys().map(String::as_str).collect();
@@ -80,6 +89,11 @@ impl ToolRegistry {
             match middleware.before_execute(name, &args, ctx).await? {
                 MiddlewareDecision::Continue => {}
                 MiddlewareDecision::Block(reason) => {
+                    global_observer().record_event(&ObserverEvent::ToolCall {
+                        tool: name.to_string(),
+                        duration: Duration::ZERO,
+                        success: false,
+                    });
                     return Ok(ToolResult {
                         success: false,
                         output: String::new(),
@@ -90,7 +104,14 @@ impl ToolRegistry {
             }
         }
 
-        let mut result = tool.execute(args, ctx).await?;
+        let started = Instant::now();
+        let outcome = tool.execute(args, ctx).await;
+        global_observer().record_event(&ObserverEvent::ToolCall {
+            tool: name.to_string(),
+            duration: started.elapsed(),
+            success: outcome.as_ref().is_ok_and(|result| result.success),
+        });
+        let mut result = outcome?;
 
         for middleware in &self.middleware {
             middleware.after_execute(name, &mut result, ctx).await;
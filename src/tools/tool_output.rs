@@ -0,0 +1,215 @@
+use super::common::failed_tool_result;
+use super::traits::{ExecutionContext, Tool};
+use super::types::ToolResult;
+use crate::security::external_content::sanitize_marker_collision;
+use serde_json::json;
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::time::{Duration, SystemTime};
+
+const OUTPUT_DIR: &str = "tool_outputs";
+/// Saved outputs older than this are removed the next time one is saved.
+const OUTPUT_RETENTION: Duration = Duration::from_hours(24);
+const DEFAULT_READ_CHARS: u64 = 8_000;
+const MAX_READ_CHARS: u64 = 16_000;
+
+/// Full tool outputs that were cut short before reaching the model, kept
+/// under `<workspace>/tool_outputs` so [`ToolOutputTool`] can page through
+/// them by handle.
+#[derive(Debug, Clone)]
+pub struct ToolOutputStore {
+    dir: PathBuf,
+}
+
+impl ToolOutputStore {
+    pub fn new(workspace_dir: &Path) -> Self {
+        Self {
+            dir: workspace_dir.join(OUTPUT_DIR),
+        }
+    }
+
+    /// Save `content` and return the handle it can be read back with.
+    pub async fn save(&self, content: &str) -> std::io::Result<String> {
+        tokio::fs::create_dir_all(&self.dir).await?;
+        self.prune_expired().await;
+
+        let handle = format!("out-{}", &uuid::Uuid::new_v4().simple().to_string()[..12]);
+        tokio::fs::write(self.path(&handle), content).await?;
+        Ok(handle)
+    }
+
+    /// The saved output for `handle`, or `None` when it does not exist.
+    pub async fn load(&self, handle: &str) -> std::io::Result<Option<String>> {
+        if !is_valid_handle(handle) {
+            return Ok(None);
+        }
+        match tokio::fs::read_to_string(self.path(handle)).await {
+            Ok(content) => Ok(Some(content)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    fn path(&self, handle: &str) -> PathBuf {
+        self.dir.join(format!("{handle}.txt"))
+    }
+
+    async fn prune_expired(&self) {
+        let Ok(mut entries) = tokio::fs::read_dir(&self.dir).await else {
+            return;
+        };
+        let cutoff = SystemTime::now() - OUTPUT_RETENTION;
+        while let Ok(Some(entry)) = entries.next_entry().await {
+            let expired = entry
+                .metadata()
+                .await
+                .and_then(|metadata| metadata.modified())
+                .is_ok_and(|modified| modified < cutoff);
+            if expired {
+                let _ = tokio::fs::remove_file(entry.path()).await;
+            }
+        }
+    }
+}
+
+fn is_valid_handle(handle: &str) -> bool {
+    !handle.is_empty()
+        && handle
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-')
+}
+
+/// Read a tool output that was truncated or elided from the conversation.
+pub struct ToolOutputTool;
+
+impl ToolOutputTool {
+    pub const fn new() -> Self {
+        Self
+    }
+}
+
+impl Tool for ToolOutputTool {
+    fn name(&self) -> &str {
+        "tool_output"
+    }
+
+    fn description(&self) -> &str {
+        "Read part of an earlier tool output that was truncated or elided, by its handle"
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "handle": {
+                    "type": "string",
+                    "description": "Handle given in the truncation or elision notice"
+                },
+                "offset": {
+                    "type": "integer",
+                    "description": "Character offset to start reading from (default 0)"
+                },
+                "limit": {
+                    "type": "integer",
+                    "description": format!("Characters to read (default {DEFAULT_READ_CHARS}, max {MAX_READ_CHARS})")
+                }
+            },
+            "required": ["handle"]
+        })
+    }
+
+    fn is_parallel_safe(&self) -> bool {
+        true
+    }
+
+    fn execute<'a>(
+        &'a self,
+        args: serde_json::Value,
+        ctx: &'a ExecutionContext,
+    ) -> Pin<Box<dyn Future<Output = anyhow::Result<ToolResult>> + Send + 'a>> {
+        Box::pin(async move {
+            let handle = args
+                .get("handle")
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| anyhow::anyhow!("Missing 'handle' parameter"))?;
+            let offset = args.get("offset").and_then(serde_json::Value::as_u64);
+            let limit = args
+                .get("limit")
+                .and_then(serde_json::Value::as_u64)
+                .unwrap_or(DEFAULT_READ_CHARS)
+                .clamp(1, MAX_READ_CHARS);
+
+            let store = ToolOutputStore::new(&ctx.workspace_dir);
+            let content = match store.load(handle).await {
+                Ok(Some(content)) => content,
+                Ok(None) => {
+                    return Ok(failed_tool_result(format!(
+                        "No saved tool output for handle '{handle}'"
+                    )));
+                }
+                Err(e) => {
+                    return Ok(failed_tool_result(format!(
+                        "Failed to read tool output: {e}"
+                    )));
+                }
+            };
+
+            Ok(ToolResult {
+                success: true,
+                output: read_window(&content, offset.unwrap_or(0), limit),
+                error: None,
+                attachments: Vec::new(),
+            })
+        })
+    }
+}
+
+/// `limit` characters from `offset`, followed by the position read so the
+/// model knows whether to continue. Saved outputs still carry the
+/// external-content markers they were sent with; those are neutralized so
+/// the re-read does not trip marker-collision sanitization.
+fn read_window(content: &str, offset: u64, limit: u64) -> String {
+    let total = content.chars().count();
+    let start = usize::try_from(offset).unwrap_or(usize::MAX).min(total);
+    let end = start
+        .saturating_add(usize::try_from(limit).unwrap_or(usize::MAX))
+        .min(total);
+    let text: String = content.chars().skip(start).take(end - start).collect();
+    format!(
+        "{}\n[characters {start}..{end} of {total}]",
+        sanitize_marker_collision(&text)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tools::common::test_security_policy;
+
+    #[tokio::test]
+    async fn saved_output_pages_by_offset() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = ToolOutputStore::new(dir.path());
+        let handle = store.save("0123456789").await.unwrap();
+
+        let tool = ToolOutputTool::new();
+        let ctx = ExecutionContext::test_default(test_security_policy(dir.path().to_path_buf()));
+        let result = tool
+            .execute(json!({"handle": handle, "offset": 4, "limit": 3}), &ctx)
+            .await
+            .unwrap();
+
+        assert!(result.success);
+        assert_eq!(result.output, "456\n[characters 4..7 of 10]");
+    }
+
+    #[tokio::test]
+    async fn unknown_or_unsafe_handles_are_not_found() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = ToolOutputStore::new(dir.path());
+
+        assert!(store.load("out-missing").await.unwrap().is_none());
+        assert!(store.load("../secrets").await.unwrap().is_none());
+    }
+}
@@ -7,6 +7,7 @@ use std::pin::Pin;
 use std::sync::Arc;
 
 use crate::security::SecurityPolicy;
+use crate::security::approval::ApprovalGate;
 use crate::security::policy::{AutonomyLevel, EntityRateLimiter, TenantPolicyContext};
 
 /// Core tool trait — implement for any capability
@@ -27,6 +28,12 @@ pub trait Tool: Send + Sync {
         ctx: &'a ExecutionContext,
     ) -> Pin<Box<dyn Future<Output = anyhow::Result<ToolResult>> + Send + 'a>>;
 
+    /// Whether calls have no side effects and may run concurrently with
+    /// other parallel-safe calls from the same response.
+    fn is_parallel_safe(&self) -> bool {
+        false
+    }
+
     /// Get the full spec for LLM registration
     fn spec(&self) -> ToolSpec {
         ToolSpec {
@@ -48,6 +55,10 @@ pub struct ExecutionContext {
     pub allowed_tools: Option<HashSet<String>>,
     pub rate_limiter: Arc<EntityRateLimiter>,
     pub tenant_context: TenantPolicyContext,
+    /// Asks a human before supervised tool calls and plan checkpoints.
+    /// With `None`, anything that needs approval under
+    /// [`AutonomyLevel::Supervised`] is denied.
+    pub approval: Option<Arc<ApprovalGate>>,
 }
 
 impl ExecutionContext {
@@ -61,6 +72,7 @@ impl ExecutionContext {
             allowed_tools: None,
             rate_limiter: Arc::new(EntityRateLimiter::new(100, 20)),
             tenant_context: TenantPolicyContext::disabled(),
+            approval: None,
         }
     }
 }
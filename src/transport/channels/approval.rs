@@ -0,0 +1,129 @@
+//! Routes approval requests to the channel a message came from and matches
+//! replies (text or button presses) back to the waiting run.
+
+use crate::security::approval::{
+    ApprovalBroker, ApprovalDecision, ApprovalRequest, PendingApprovals,
+};
+use anyhow::Result;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+
+use super::traits::{Channel, ChannelMessage};
+
+const CALLBACK_PREFIX: &str = "approval:";
+
+/// Identifies who may answer an approval request raised by `msg`: the user
+/// who wrote it, in the conversation it was written in. In a group chat other
+/// members share the reply address, so the author is part of the key.
+pub fn approval_requester(msg: &ChannelMessage) -> String {
+    match &msg.author {
+        Some(author) => format!("{}:{}:{author}", msg.channel, msg.sender),
+        None => format!("{}:{}", msg.channel, msg.sender),
+    }
+}
+
+/// Button payload for an approval decision, e.g. `approval:approve:1a2b3c4d`.
+pub fn approval_callback_data(decision: ApprovalDecision, request_id: &str) -> String {
+    format!("{CALLBACK_PREFIX}{}:{request_id}", decision.as_str())
+}
+
+/// Turn a button payload back into the equivalent text reply
+/// (`approve 1a2b3c4d`), or `None` if it is not an approval button.
+pub fn approval_callback_reply(data: &str) -> Option<String> {
+    let (decision, request_id) = data.strip_prefix(CALLBACK_PREFIX)?.split_once(':')?;
+    let decision = ApprovalDecision::parse(decision)?;
+    (!request_id.is_empty()).then(|| format!("{} {request_id}", decision.as_str()))
+}
+
+/// Asks for approval in the conversation that triggered the run.
+pub(super) struct ChannelApprovalBroker {
+    channel: Arc<dyn Channel>,
+    recipient: String,
+    requester: String,
+    pending: Arc<PendingApprovals>,
+}
+
+impl ChannelApprovalBroker {
+    pub(super) fn new(
+        channel: Arc<dyn Channel>,
+        msg: &ChannelMessage,
+        pending: Arc<PendingApprovals>,
+    ) -> Self {
+        Self {
+            channel,
+            recipient: msg.sender.clone(),
+            requester: approval_requester(msg),
+            pending,
+        }
+    }
+}
+
+impl ApprovalBroker for ChannelApprovalBroker {
+    fn request_approval<'a>(
+        &'a self,
+        request: &'a ApprovalRequest,
+    ) -> Pin<Box<dyn Future<Output = Result<ApprovalDecision>> + Send + 'a>> {
+        Box::pin(async move {
+            let reply = self.pending.register(&request.id, &self.requester);
+            self.channel
+                .send_approval_request(request, &self.recipient)
+                .await?;
+            reply
+                .await
+                .map_err(|_| anyhow::anyhow!("approval request {} was abandoned", request.id))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn callback_data_round_trips_to_text_reply() {
+        let data = approval_callback_data(ApprovalDecision::ApproveAlways, "1a2b3c4d");
+        assert_eq!(data, "approval:always:1a2b3c4d");
+        assert_eq!(
+            approval_callback_reply(&data).as_deref(),
+            Some("always 1a2b3c4d")
+        );
+    }
+
+    #[test]
+    fn callback_reply_rejects_foreign_payloads() {
+        assert_eq!(approval_callback_reply("menu:open"), None);
+        assert_eq!(approval_callback_reply("approval:maybe:1a2b"), None);
+        assert_eq!(approval_callback_reply("approval:deny:"), None);
+    }
+
+    fn group_message(author: &str, content: &str) -> ChannelMessage {
+        ChannelMessage {
+            id: content.into(),
+            sender: "-100123".into(),
+            author: Some(author.into()),
+            content: content.into(),
+            channel: "telegram".into(),
+            conversation_id: Some("-100123".into()),
+            thread_id: None,
+            reply_to: None,
+            message_id: None,
+            timestamp: 0,
+            attachments: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn only_the_requesting_group_member_can_answer() {
+        let pending = PendingApprovals::default();
+        let request = group_message("42", "deploy it");
+        let mut receiver = pending.register("1a2b3c4d", &approval_requester(&request));
+
+        let bystander = group_message("7", "approve 1a2b3c4d");
+        assert!(!pending.resolve_reply(&approval_requester(&bystander), &bystander.content));
+
+        let requester = group_message("42", "approve 1a2b3c4d");
+        assert!(pending.resolve_reply(&approval_requester(&requester), &requester.content));
+        assert_eq!(receiver.try_recv().unwrap(), ApprovalDecision::Approve);
+    }
+}
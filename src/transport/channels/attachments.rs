@@ -1,8 +1,9 @@
 use crate::llm::types::ContentBlock;
+use crate::media::{MediaProcessor, MediaStore};
 use crate::tools::OutputAttachment;
 use anyhow::Result;
 
-use super::traits::{MediaAttachment, MediaData};
+use super::traits::{Channel, MediaAttachment, MediaData};
 
 pub(crate) fn media_attachment_url(
     url: String,
@@ -70,18 +71,47 @@ pub(crate) fn attachment_to_image_block(attachment: &MediaAttachment) -> Option<
     Some(ContentBlock::Image { source })
 }
 
-pub(crate) async fn load_attachment_bytes(attachment: &MediaAttachment) -> Result<Vec<u8>> {
+/// Load an attachment from its bytes or an unauthenticated URL, failing once
+/// it exceeds `max_bytes`.
+pub(crate) async fn load_attachment_bytes(
+    attachment: &MediaAttachment,
+    max_bytes: usize,
+) -> Result<Vec<u8>> {
     match &attachment.data {
-        MediaData::Bytes(bytes) => Ok(bytes.clone()),
-        MediaData::Url(url) => {
-            let response = reqwest::get(url).await?;
-            let response = response.error_for_status()?;
-            let bytes = response.bytes().await?;
-            Ok(bytes.to_vec())
+        MediaData::Bytes(bytes) => {
+            if bytes.len() > max_bytes {
+                anyhow::bail!("attachment exceeds {max_bytes} bytes");
+            }
+            Ok(bytes.clone())
         }
+        MediaData::Url(url) => download_capped(reqwest::Client::new().get(url), max_bytes).await,
     }
 }
 
+/// Send a download request and read the body, stopping as soon as it grows
+/// past `max_bytes`.
+pub(crate) async fn download_capped(
+    request: reqwest::RequestBuilder,
+    max_bytes: usize,
+) -> Result<Vec<u8>> {
+    let mut response = request.send().await?.error_for_status()?;
+    let too_large = || anyhow::anyhow!("attachment exceeds {max_bytes} bytes");
+    if response
+        .content_length()
+        .is_some_and(|length| usize::try_from(length).map_or(true, |length| length > max_bytes))
+    {
+        return Err(too_large());
+    }
+    let mut bytes = Vec::new();
+    while let Some(chunk) = response.chunk().await? {
+        if bytes.len() + chunk.len() > max_bytes {
+            return Err(too_large());
+        }
+        bytes.extend_from_slice(&chunk);
+    }
+    Ok(bytes)
+}
+
 pub(crate) fn fallback_attachment_description(
     attachment: &MediaAttachment,
     size_bytes: Option<usize>,
@@ -96,6 +126,83 @@ pub(crate) fn fallback_attachment_description(
     )
 }
 
+/// Download (through the receiving `channel`, with its credentials), store
+/// and describe inbound attachments, returning one reference line per
+/// attachment. Without a channel or media store only attachment metadata is
+/// reported.
+pub(crate) async fn describe_inbound_attachments(
+    channel: Option<&dyn Channel>,
+    store: Option<&MediaStore>,
+    processor: &MediaProcessor,
+    attachments: &[MediaAttachment],
+) -> Vec<String> {
+    let mut lines = Vec::with_capacity(attachments.len());
+    for attachment in attachments {
+        let (Some(channel), Some(store)) = (channel, store) else {
+            lines.push(fallback_attachment_description(attachment, None));
+            continue;
+        };
+
+        let max_bytes = usize::try_from(store.max_file_size()).unwrap_or(usize::MAX);
+        let bytes = match channel.fetch_attachment(attachment, max_bytes).await {
+            Ok(bytes) => bytes,
+            Err(error) => {
+                tracing::warn!(
+                    mime_type = %attachment.mime_type,
+                    filename = ?attachment.filename,
+                    error = %error,
+                    "failed to load inbound attachment"
+                );
+                lines.push(fallback_attachment_description(attachment, None));
+                continue;
+            }
+        };
+
+        let file = match store.store(&bytes, attachment.filename.as_deref()).await {
+            Ok(file) => file,
+            Err(error) => {
+                tracing::warn!(
+                    mime_type = %attachment.mime_type,
+                    filename = ?attachment.filename,
+                    error = %error,
+                    "failed to store inbound attachment"
+                );
+                lines.push(fallback_attachment_description(
+                    attachment,
+                    Some(bytes.len()),
+                ));
+                continue;
+            }
+        };
+
+        let description = match processor.describe(&file, &bytes).await {
+            Ok(description) => description,
+            Err(error) => {
+                tracing::debug!(media_id = %file.id, error = %error, "media description failed");
+                fallback_attachment_description(attachment, Some(bytes.len()))
+            }
+        };
+        lines.push(format!(
+            "[Attachment media:{} stored at {}] {description}",
+            file.id, file.storage_path
+        ));
+    }
+    lines
+}
+
+pub(crate) fn append_attachment_context(content: &str, references: &[String]) -> String {
+    if references.is_empty() {
+        return content.to_string();
+    }
+    let mut out = String::from(content);
+    if !out.is_empty() {
+        out.push_str("\n\n");
+    }
+    out.push_str("Attachments:\n");
+    out.push_str(&references.join("\n"));
+    out
+}
+
 pub(crate) async fn output_attachment_to_media_attachment(
     attachment: &OutputAttachment,
 ) -> Option<MediaAttachment> {
@@ -283,8 +390,95 @@ mod tests {
             filename: Some("note.txt".to_string()),
         };
 
-        let loaded = load_attachment_bytes(&attachment).await.unwrap();
+        let loaded = load_attachment_bytes(&attachment, 3).await.unwrap();
         assert_eq!(loaded, vec![7, 8, 9]);
+        assert!(load_attachment_bytes(&attachment, 2).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn downloads_stop_at_the_size_limit() {
+        use wiremock::matchers::{header, method};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(header("authorization", "Bearer secret"))
+            .respond_with(ResponseTemplate::new(200).set_body_bytes(vec![1u8; 64]))
+            .mount(&server)
+            .await;
+        let client = reqwest::Client::new();
+
+        let bytes = download_capped(client.get(server.uri()).bearer_auth("secret"), 64)
+            .await
+            .unwrap();
+        assert_eq!(bytes.len(), 64);
+        let error = download_capped(client.get(server.uri()).bearer_auth("secret"), 63)
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("exceeds 63 bytes"));
+        assert!(download_capped(client.get(server.uri()), 64).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn describe_inbound_attachments_without_store_reports_metadata() {
+        let attachment = MediaAttachment {
+            mime_type: "image/png".to_string(),
+            data: MediaData::Url("https://example.com/a.png".to_string()),
+            filename: Some("a.png".to_string()),
+        };
+
+        let channel = crate::transport::channels::CliChannel::new();
+        let lines = describe_inbound_attachments(
+            Some(&channel),
+            None,
+            &MediaProcessor::new(),
+            &[attachment],
+        )
+        .await;
+        assert_eq!(lines, vec!["[Attachment: a.png (image/png)]".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn describe_inbound_attachments_stores_and_references_media() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let store = MediaStore::new(&crate::media::MediaConfig::default(), temp_dir.path())
+            .await
+            .unwrap();
+        let attachment = MediaAttachment {
+            mime_type: "text/plain".to_string(),
+            data: MediaData::Bytes(b"hello world".to_vec()),
+            filename: Some("note.txt".to_string()),
+        };
+
+        let channel = crate::transport::channels::CliChannel::new();
+        let lines = describe_inbound_attachments(
+            Some(&channel),
+            Some(&store),
+            &MediaProcessor::new(),
+            &[attachment],
+        )
+        .await;
+        assert_eq!(lines.len(), 1);
+        let id = lines[0]
+            .strip_prefix("[Attachment media:")
+            .and_then(|rest| rest.split_whitespace().next())
+            .unwrap();
+        let (file, bytes) = store.retrieve(id).await.unwrap();
+        assert_eq!(bytes, b"hello world");
+        assert!(lines[0].contains(&file.storage_path));
+    }
+
+    #[test]
+    fn append_attachment_context_lists_references() {
+        assert_eq!(append_attachment_context("hi", &[]), "hi");
+        assert_eq!(
+            append_attachment_context("hi", &["[Attachment: a.png (image/png)]".to_string()]),
+            "hi\n\nAttachments:\n[Attachment: a.png (image/png)]"
+        );
+        assert_eq!(
+            append_attachment_context("", &["x".to_string()]),
+            "Attachments:\nx"
+        );
     }
 
     #[tokio::test]
@@ -54,6 +54,7 @@ impl Channel for CliChannel {
                 let msg = ChannelMessage {
                     id: Uuid::new_v4().to_string(),
                     sender: "user".to_string(),
+                    author: None,
                     content: line,
                     channel: "cli".to_string(),
                     conversation_id: None,
@@ -110,6 +111,7 @@ mod tests {
         let msg = ChannelMessage {
             id: "test-id".into(),
             sender: "user".into(),
+            author: None,
             content: "hello".into(),
             channel: "cli".into(),
             conversation_id: None,
@@ -131,6 +133,7 @@ mod tests {
         let msg = ChannelMessage {
             id: "id".into(),
             sender: "s".into(),
+            author: None,
             content: "c".into(),
             channel: "ch".into(),
             conversation_id: None,
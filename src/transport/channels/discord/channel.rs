@@ -1,4 +1,5 @@
 use crate::config::schema::DiscordConfig;
+use crate::security::approval::ApprovalRequest;
 use crate::transport::channels::attachments::media_attachment_url;
 use crate::transport::channels::policy::{AllowlistMatch, is_allowed_user};
 use crate::transport::channels::traits::{Channel, ChannelMessage, MediaAttachment, MediaData};
@@ -8,7 +9,10 @@ use std::pin::Pin;
 use std::sync::Arc;
 use uuid::Uuid;
 
-use super::commands::{build_default_commands, defer_interaction, extract_command_input};
+use super::commands::{
+    acknowledge_component, build_approval_components, build_default_commands, defer_interaction,
+    extract_command_input, extract_component_input,
+};
 use super::gateway::{DiscordGateway, DiscordGatewayState, GatewayEvent};
 use super::http_client::DiscordHttpClient;
 use super::types::{DEFAULT_INTENTS, InteractionType, MAX_MESSAGE_LENGTH};
@@ -214,6 +218,7 @@ impl DiscordChannel {
         let msg = ChannelMessage {
             id: Uuid::new_v4().to_string(),
             sender: author_id.to_string(),
+            author: Some(author_id.to_string()),
             content,
             channel: "discord".to_string(),
             conversation_id: Some(channel_id.to_string()),
@@ -244,8 +249,11 @@ impl DiscordChannel {
             data,
         } = params;
 
-        if InteractionType::from_u64(interaction_type) != Some(InteractionType::ApplicationCommand)
-        {
+        let interaction_type = InteractionType::from_u64(interaction_type);
+        if !matches!(
+            interaction_type,
+            Some(InteractionType::ApplicationCommand | InteractionType::MessageComponent)
+        ) {
             return;
         }
         if !self.is_user_allowed(user_id) {
@@ -255,18 +263,30 @@ impl DiscordChannel {
             return;
         }
 
-        let Some(input) = extract_command_input(data) else {
-            return;
+        let (input, acknowledged) = if interaction_type == Some(InteractionType::MessageComponent) {
+            let Some(input) = extract_component_input(data) else {
+                return;
+            };
+            let acknowledged =
+                acknowledge_component(&self.http, interaction_id, interaction_token).await;
+            (input, acknowledged)
+        } else {
+            let Some(input) = extract_command_input(data) else {
+                return;
+            };
+            let acknowledged =
+                defer_interaction(&self.http, interaction_id, interaction_token).await;
+            (input, acknowledged)
         };
-
-        if let Err(e) = defer_interaction(&self.http, interaction_id, interaction_token).await {
-            tracing::warn!("Discord: failed to defer interaction: {e}");
+        if let Err(e) = acknowledged {
+            tracing::warn!("Discord: failed to acknowledge interaction: {e}");
             return;
         }
 
         let msg = ChannelMessage {
             id: Uuid::new_v4().to_string(),
             sender: user_id.to_string(),
+            author: Some(user_id.to_string()),
             content: input,
             channel: "discord".to_string(),
             conversation_id: Some(channel_id.to_string()),
@@ -308,6 +328,22 @@ impl Channel for DiscordChannel {
         })
     }
 
+    fn send_approval_request<'a>(
+        &'a self,
+        request: &'a ApprovalRequest,
+        channel_id: &'a str,
+    ) -> Pin<Box<dyn Future<Output = anyhow::Result<()>> + Send + 'a>> {
+        Box::pin(async move {
+            self.http
+                .send_components(
+                    channel_id,
+                    &format!("Approval needed: {}", request.summary()),
+                    build_approval_components(request),
+                )
+                .await
+        })
+    }
+
     fn listen<'a>(
         &'a self,
         tx: tokio::sync::mpsc::Sender<ChannelMessage>,
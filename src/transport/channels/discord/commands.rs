@@ -1,6 +1,9 @@
 use anyhow::Result;
 use serde_json::json;
 
+use crate::security::approval::{ApprovalDecision, ApprovalRequest};
+use crate::transport::channels::approval::{approval_callback_data, approval_callback_reply};
+
 use super::http_client::DiscordHttpClient;
 use super::types::InteractionCallbackType;
 
@@ -74,6 +77,53 @@ pub async fn send_interaction_followup(
         .await
 }
 
+/// Action row with approve / always / deny buttons for `request`.
+pub fn build_approval_components(request: &ApprovalRequest) -> Vec<serde_json::Value> {
+    // Button styles: 1 = primary, 3 = success, 4 = danger.
+    let mut buttons = vec![json!({
+        "type": 2,
+        "style": 3,
+        "label": "Approve",
+        "custom_id": approval_callback_data(ApprovalDecision::Approve, &request.id),
+    })];
+    if request.can_remember() {
+        buttons.push(json!({
+            "type": 2,
+            "style": 1,
+            "label": "Always allow",
+            "custom_id": approval_callback_data(ApprovalDecision::ApproveAlways, &request.id),
+        }));
+    }
+    buttons.push(json!({
+        "type": 2,
+        "style": 4,
+        "label": "Deny",
+        "custom_id": approval_callback_data(ApprovalDecision::Deny, &request.id),
+    }));
+    vec![json!({ "type": 1, "components": buttons })]
+}
+
+/// Text reply equivalent to a pressed approval button.
+pub fn extract_component_input(data: &serde_json::Value) -> Option<String> {
+    data.get("custom_id")?
+        .as_str()
+        .and_then(approval_callback_reply)
+}
+
+pub async fn acknowledge_component(
+    http: &DiscordHttpClient,
+    interaction_id: &str,
+    interaction_token: &str,
+) -> Result<()> {
+    http.create_interaction_response(
+        interaction_id,
+        interaction_token,
+        InteractionCallbackType::DeferredUpdateMessage as u8,
+        None,
+    )
+    .await
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -119,4 +169,24 @@ mod tests {
         let data = json!({"name": "ask", "options": []});
         assert_eq!(extract_command_input(&data), None);
     }
+
+    #[test]
+    fn approval_components_round_trip_through_custom_id() {
+        use crate::security::approval::ApprovalSubject;
+        let request = ApprovalRequest::new(
+            "discord:1",
+            ApprovalSubject::Checkpoint {
+                label: "deploy".to_string(),
+            },
+        );
+        let components = build_approval_components(&request);
+        let buttons = components[0]["components"].as_array().unwrap();
+        assert_eq!(buttons.len(), 2);
+
+        let data = json!({"custom_id": buttons[0]["custom_id"]});
+        assert_eq!(
+            extract_component_input(&data),
+            Some(format!("approve {}", request.id))
+        );
+    }
 }
@@ -49,6 +49,21 @@ impl DiscordHttpClient {
             .context("parse Discord send message response JSON")
     }
 
+    pub async fn send_components(
+        &self,
+        channel_id: &str,
+        content: &str,
+        components: Vec<serde_json::Value>,
+    ) -> Result<()> {
+        let url = format!("{API_BASE}/channels/{channel_id}/messages");
+        let body = json!({ "content": content, "components": components });
+        let _response = self
+            .request(Method::POST, &url, Some(body))
+            .await
+            .context("send Discord message with components")?;
+        Ok(())
+    }
+
     pub async fn send_embed(
         &self,
         channel_id: &str,
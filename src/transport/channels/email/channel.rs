@@ -0,0 +1,582 @@
+use anyhow::{Context, Result};
+use lettre::message::header::ContentType;
+use lettre::message::{Attachment, MessageBuilder, MultiPart};
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{Message, SmtpTransport, Transport};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::future::Future;
+use std::net::TcpStream;
+use std::pin::Pin;
+use std::sync::{Mutex, PoisonError};
+use std::time::Duration;
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio::sync::mpsc;
+use tokio::time::sleep;
+use tracing::{error, info, warn};
+
+use super::imap::ImapSession;
+use super::inbound::{self, ThreadRef, parse_mail, parse_reply_address, reply_subject};
+use crate::config::EmailFolderRule;
+use crate::config::schema::EmailConfig;
+use crate::transport::channels::traits::{Channel, ChannelMessage, MediaAttachment, MediaData};
+
+/// Subject for messages that do not answer an email.
+const DEFAULT_SUBJECT: &str = "AsteronIris Message";
+
+/// IDLE is re-issued before servers drop it (RFC 2177 allows 29 minutes).
+const IDLE_RENEW: Duration = Duration::from_mins(25);
+
+/// Threads remembered for replies; the oldest are forgotten first.
+const MAX_TRACKED_THREADS: usize = 1024;
+
+/// Email channel — IMAP IDLE (or polling) for inbound, SMTP for outbound.
+/// Replies are threaded with `In-Reply-To`/`References`.
+pub struct EmailChannel {
+    pub config: EmailConfig,
+    seen_messages: Mutex<HashSet<String>>,
+    threads: Mutex<ThreadBook>,
+    client: reqwest::Client,
+}
+
+/// Reply state by thread root message id.
+#[derive(Default)]
+struct ThreadBook {
+    threads: HashMap<String, ThreadRef>,
+    order: VecDeque<String>,
+}
+
+impl EmailChannel {
+    pub fn new(config: EmailConfig) -> Self {
+        Self {
+            config,
+            seen_messages: Mutex::new(HashSet::new()),
+            threads: Mutex::new(ThreadBook::default()),
+            client: reqwest::Client::new(),
+        }
+    }
+
+    /// Check if a sender email is in the allowlist
+    pub fn is_sender_allowed(&self, email: &str) -> bool {
+        sender_matches(&self.config.allowed_senders, email)
+    }
+
+    /// Strip HTML tags from content (basic)
+    pub fn strip_html(html: &str) -> String {
+        inbound::strip_html(html)
+    }
+
+    /// The watched folders: the configured rules, or `imap_folder` alone.
+    fn folder_rules(&self) -> Vec<EmailFolderRule> {
+        if self.config.folders.is_empty() {
+            vec![EmailFolderRule {
+                folder: self.config.imap_folder.clone(),
+                ..EmailFolderRule::default()
+            }]
+        } else {
+            self.config.folders.clone()
+        }
+    }
+
+    fn is_sender_allowed_in(&self, rule: &EmailFolderRule, email: &str) -> bool {
+        match &rule.allowed_senders {
+            Some(allowed) => sender_matches(allowed, email),
+            None => self.is_sender_allowed(email),
+        }
+    }
+
+    fn remember_thread(&self, root: &str, thread: ThreadRef) {
+        let mut book = self.threads.lock().unwrap_or_else(PoisonError::into_inner);
+        if book.threads.insert(root.to_string(), thread).is_none() {
+            book.order.push_back(root.to_string());
+        }
+        while book.order.len() > MAX_TRACKED_THREADS {
+            if let Some(oldest) = book.order.pop_front() {
+                book.threads.remove(&oldest);
+            }
+        }
+    }
+
+    fn thread(&self, root: &str) -> Option<ThreadRef> {
+        self.threads
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .threads
+            .get(root)
+            .cloned()
+    }
+
+    /// Headers for a message to `recipient`: a reply in its thread when the
+    /// address names one, otherwise a new email.
+    fn compose(&self, recipient: &str, subject: Option<&str>) -> Result<MessageBuilder> {
+        let (address, thread_root) = parse_reply_address(recipient);
+        let thread = thread_root.and_then(|root| self.thread(root));
+        let from = thread
+            .as_ref()
+            .and_then(|thread| thread.from_address.clone())
+            .unwrap_or_else(|| self.config.from_address.clone());
+        let subject = match (subject, &thread) {
+            (Some(subject), _) => subject.to_string(),
+            (None, Some(thread)) => reply_subject(&thread.subject),
+            (None, None) => DEFAULT_SUBJECT.to_string(),
+        };
+        let references = match (thread, thread_root) {
+            (Some(thread), _) => thread.references,
+            (None, Some(root)) => vec![root.to_string()],
+            (None, None) => Vec::new(),
+        };
+
+        let mut builder = Message::builder()
+            .from(from.parse().context("parse email from address")?)
+            .to(address.parse().context("parse email recipient address")?)
+            .subject(subject);
+        if let Some(parent) = references.last() {
+            let references: Vec<String> = references.iter().map(|id| format!("<{id}>")).collect();
+            builder = builder
+                .in_reply_to(format!("<{parent}>"))
+                .references(references.join(" "));
+        }
+        Ok(builder)
+    }
+
+    fn create_smtp_transport(&self) -> Result<SmtpTransport> {
+        let creds = Credentials::new(self.config.username.clone(), self.config.password.clone());
+        let transport = if self.config.smtp_tls {
+            SmtpTransport::relay(&self.config.smtp_host)
+                .context("create SMTP relay connection")?
+                .port(self.config.smtp_port)
+                .credentials(creds)
+                .build()
+        } else {
+            SmtpTransport::builder_dangerous(&self.config.smtp_host)
+                .port(self.config.smtp_port)
+                .credentials(creds)
+                .build()
+        };
+        Ok(transport)
+    }
+
+    /// Watch one folder until the receiver goes away, reconnecting on errors.
+    async fn watch_folder(&self, rule: &EmailFolderRule, tx: &mpsc::Sender<ChannelMessage>) {
+        loop {
+            match self.run_folder_session(rule, tx).await {
+                Ok(()) => return,
+                Err(e) => {
+                    error!("Email watch of {} failed: {e:#}", rule.folder);
+                    sleep(Duration::from_secs(10)).await;
+                }
+            }
+        }
+    }
+
+    async fn run_folder_session(
+        &self,
+        rule: &EmailFolderRule,
+        tx: &mpsc::Sender<ChannelMessage>,
+    ) -> Result<()> {
+        let mut imap = ImapSession::connect(
+            &self.config.imap_host,
+            self.config.imap_port,
+            self.config.max_message_bytes,
+        )
+        .await?;
+        imap.login(&self.config.username, &self.config.password)
+            .await?;
+        imap.select(&rule.folder).await?;
+        let use_idle = self.config.idle && imap.supports("IDLE");
+        info!(
+            "Email watching {} ({})",
+            rule.folder,
+            if use_idle { "IMAP IDLE" } else { "polling" }
+        );
+
+        loop {
+            if !self.deliver_unseen(&mut imap, rule, tx).await? {
+                imap.logout().await;
+                return Ok(());
+            }
+            if use_idle {
+                imap.idle(IDLE_RENEW).await?;
+            } else {
+                sleep(Duration::from_secs(self.config.poll_interval_secs)).await;
+            }
+        }
+    }
+
+    /// Hand unseen messages to the agent. Returns `false` once the receiver
+    /// is gone.
+    async fn deliver_unseen<S: AsyncRead + AsyncWrite + Unpin>(
+        &self,
+        imap: &mut ImapSession<S>,
+        rule: &EmailFolderRule,
+        tx: &mpsc::Sender<ChannelMessage>,
+    ) -> Result<bool> {
+        for uid in imap.search_unseen().await? {
+            if let Some(size) = imap.size(uid).await?
+                && size > self.config.max_message_bytes
+            {
+                warn!(
+                    "Email {uid} in {} is {size} bytes, over max_message_bytes; skipping",
+                    rule.folder
+                );
+                imap.mark_seen(uid).await?;
+                continue;
+            }
+            let Some(raw) = imap.fetch(uid).await? else {
+                continue;
+            };
+            let Some(mail) = parse_mail(&raw) else {
+                warn!("Email {uid} in {} could not be parsed", rule.folder);
+                imap.mark_seen(uid).await?;
+                continue;
+            };
+            if !self.is_sender_allowed_in(rule, &mail.from) {
+                warn!("Blocked email from {}", mail.from);
+                imap.mark_seen(uid).await?;
+                continue;
+            }
+            let is_new = self
+                .seen_messages
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .insert(mail.message.id.clone());
+            if !is_new {
+                imap.mark_seen(uid).await?;
+                continue;
+            }
+
+            self.remember_thread(
+                &mail.thread_root,
+                ThreadRef {
+                    from_address: rule.from_address.clone(),
+                    ..mail.thread
+                },
+            );
+            if tx.send(mail.message).await.is_err() {
+                return Ok(false);
+            }
+            imap.mark_seen(uid).await?;
+            if let Some(target) = &rule.move_to
+                && let Err(e) = imap.move_to(uid, target).await
+            {
+                warn!("Email {uid} could not be moved to {target}: {e:#}");
+            }
+        }
+        Ok(true)
+    }
+}
+
+/// Allowlist match: `*`, full addresses, or domains with or without `@`.
+/// Empty denies everyone.
+fn sender_matches(allowed_senders: &[String], email: &str) -> bool {
+    if allowed_senders.is_empty() {
+        return false; // Empty = deny all
+    }
+    if allowed_senders.iter().any(|a| a == "*") {
+        return true; // Wildcard = allow all
+    }
+    let email_lower = email.to_lowercase();
+    allowed_senders.iter().any(|allowed| {
+        if allowed.starts_with('@') {
+            // Domain match with @ prefix: "@example.com"
+            email_lower.ends_with(&allowed.to_lowercase())
+        } else if allowed.contains('@') {
+            // Full email address match
+            allowed.eq_ignore_ascii_case(email)
+        } else {
+            // Domain match without @ prefix: "example.com"
+            email_lower.ends_with(&format!("@{}", allowed.to_lowercase()))
+        }
+    })
+}
+
+impl Channel for EmailChannel {
+    fn name(&self) -> &str {
+        "email"
+    }
+
+    fn max_message_length(&self) -> usize {
+        usize::MAX
+    }
+
+    fn send<'a>(
+        &'a self,
+        message: &'a str,
+        recipient: &'a str,
+    ) -> Pin<Box<dyn Future<Output = Result<()>> + Send + 'a>> {
+        Box::pin(async move {
+            let (subject, body) = match message.strip_prefix("Subject: ") {
+                Some(rest) => match rest.split_once('\n') {
+                    Some((subject, body)) => (Some(subject.trim()), body.trim()),
+                    None => (None, message),
+                },
+                None => (None, message),
+            };
+
+            let email = self
+                .compose(recipient, subject)?
+                .body(body.to_string())
+                .context("build email message body")?;
+
+            let transport = self.create_smtp_transport()?;
+            transport.send(&email).context("send email via SMTP")?;
+            info!("Email sent to {}", parse_reply_address(recipient).0);
+            Ok(())
+        })
+    }
+
+    fn listen<'a>(
+        &'a self,
+        tx: mpsc::Sender<ChannelMessage>,
+    ) -> Pin<Box<dyn Future<Output = Result<()>> + Send + 'a>> {
+        Box::pin(async move {
+            let rules = self.folder_rules();
+            futures_util::future::join_all(rules.iter().map(|rule| self.watch_folder(rule, &tx)))
+                .await;
+            Ok(())
+        })
+    }
+
+    fn health_check<'a>(&'a self) -> Pin<Box<dyn Future<Output = bool> + Send + 'a>> {
+        Box::pin(async move {
+            let cfg = self.config.clone();
+            tokio::task::spawn_blocking(move || {
+                let tcp = TcpStream::connect((&*cfg.imap_host, cfg.imap_port));
+                tcp.is_ok()
+            })
+            .await
+            .unwrap_or_default()
+        })
+    }
+
+    fn send_media<'a>(
+        &'a self,
+        attachment: &'a MediaAttachment,
+        recipient: &'a str,
+    ) -> Pin<Box<dyn Future<Output = Result<()>> + Send + 'a>> {
+        Box::pin(async move {
+            let bytes = match &attachment.data {
+                MediaData::Url(url) => self
+                    .client
+                    .get(url)
+                    .send()
+                    .await
+                    .context("download email attachment")?
+                    .bytes()
+                    .await
+                    .context("read email attachment bytes")?
+                    .to_vec(),
+                MediaData::Bytes(bytes) => bytes.clone(),
+            };
+            let content_type = ContentType::parse(&attachment.mime_type)
+                .or_else(|_| ContentType::parse("application/octet-stream"))
+                .context("parse email attachment content type")?;
+            let filename = attachment
+                .filename
+                .clone()
+                .unwrap_or_else(|| "attachment".to_string());
+
+            let email = self
+                .compose(recipient, None)?
+                .multipart(
+                    MultiPart::mixed()
+                        .singlepart(Attachment::new(filename).body(bytes, content_type)),
+                )
+                .context("build email attachment message")?;
+
+            let transport = self.create_smtp_transport()?;
+            transport
+                .send(&email)
+                .context("send email attachment via SMTP")?;
+            info!(
+                "Email attachment sent to {}",
+                parse_reply_address(recipient).0
+            );
+            Ok(())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_email_channel(senders: Vec<String>) -> EmailChannel {
+        EmailChannel::new(EmailConfig {
+            allowed_senders: senders,
+            from_address: "bot@example.org".to_string(),
+            ..EmailConfig::default()
+        })
+    }
+
+    // ── is_sender_allowed tests ──────────────────────────────
+
+    #[test]
+    fn is_sender_allowed_denies_when_empty() {
+        let ch = make_email_channel(vec![]);
+        assert!(!ch.is_sender_allowed("anyone@example.com"));
+    }
+
+    #[test]
+    fn is_sender_allowed_wildcard_allows_any() {
+        let ch = make_email_channel(vec!["*".to_string()]);
+        assert!(ch.is_sender_allowed("anyone@anywhere.net"));
+    }
+
+    #[test]
+    fn is_sender_allowed_exact_match() {
+        let ch = make_email_channel(vec!["alice@example.com".to_string()]);
+        assert!(ch.is_sender_allowed("alice@example.com"));
+        assert!(!ch.is_sender_allowed("bob@example.com"));
+    }
+
+    #[test]
+    fn is_sender_allowed_domain_with_at_prefix() {
+        let ch = make_email_channel(vec!["@example.com".to_string()]);
+        assert!(ch.is_sender_allowed("alice@example.com"));
+        assert!(!ch.is_sender_allowed("alice@other.com"));
+    }
+
+    #[test]
+    fn is_sender_allowed_domain_without_at_prefix() {
+        let ch = make_email_channel(vec!["example.com".to_string()]);
+        assert!(ch.is_sender_allowed("alice@example.com"));
+        assert!(!ch.is_sender_allowed("alice@other.com"));
+    }
+
+    #[test]
+    fn is_sender_allowed_case_insensitive() {
+        let ch = make_email_channel(vec!["Alice@Example.COM".to_string()]);
+        assert!(ch.is_sender_allowed("alice@example.com"));
+        assert!(ch.is_sender_allowed("ALICE@EXAMPLE.COM"));
+    }
+
+    #[test]
+    fn folder_rules_override_the_allowlist() {
+        let ch = make_email_channel(vec!["example.com".to_string()]);
+        let open = EmailFolderRule {
+            folder: "Support".to_string(),
+            allowed_senders: Some(vec!["*".to_string()]),
+            ..EmailFolderRule::default()
+        };
+        assert!(ch.is_sender_allowed_in(&open, "anyone@other.com"));
+        let inherited = EmailFolderRule {
+            folder: "INBOX".to_string(),
+            ..EmailFolderRule::default()
+        };
+        assert!(!ch.is_sender_allowed_in(&inherited, "anyone@other.com"));
+    }
+
+    #[test]
+    fn folder_rules_default_to_imap_folder() {
+        let ch = make_email_channel(vec![]);
+        let rules = ch.folder_rules();
+        assert_eq!(rules.len(), 1);
+        assert_eq!(rules[0].folder, "INBOX");
+    }
+
+    // ── reply threading tests ────────────────────────────────
+
+    fn formatted(builder: MessageBuilder) -> String {
+        String::from_utf8(builder.body(String::from("hi")).unwrap().formatted()).unwrap()
+    }
+
+    #[test]
+    fn replies_carry_thread_headers_and_subject() {
+        let ch = make_email_channel(vec![]);
+        ch.remember_thread(
+            "m1@example.com",
+            ThreadRef {
+                subject: "Printer broken".to_string(),
+                references: vec!["m1@example.com".to_string(), "m3@example.com".to_string()],
+                from_address: Some("support@example.org".to_string()),
+            },
+        );
+        let email = formatted(
+            ch.compose("alice@example.com#m1@example.com", None)
+                .unwrap(),
+        );
+        assert!(email.contains("Subject: Re: Printer broken"));
+        assert!(email.contains("In-Reply-To: <m3@example.com>"));
+        assert!(email.contains("References: <m1@example.com> <m3@example.com>"));
+        assert!(email.contains("From: support@example.org"));
+        assert!(email.contains("To: alice@example.com"));
+    }
+
+    #[test]
+    fn unknown_threads_still_reference_the_root() {
+        let ch = make_email_channel(vec![]);
+        let email = formatted(
+            ch.compose("alice@example.com#m1@example.com", Some("Status"))
+                .unwrap(),
+        );
+        assert!(email.contains("Subject: Status"));
+        assert!(email.contains("In-Reply-To: <m1@example.com>"));
+        assert!(email.contains("From: bot@example.org"));
+    }
+
+    #[test]
+    fn bare_addresses_start_new_emails() {
+        let ch = make_email_channel(vec![]);
+        let email = formatted(ch.compose("alice@example.com", None).unwrap());
+        assert!(email.contains("Subject: AsteronIris Message"));
+        assert!(!email.contains("In-Reply-To"));
+    }
+
+    #[test]
+    fn thread_book_forgets_the_oldest_threads() {
+        let ch = make_email_channel(vec![]);
+        for i in 0..=MAX_TRACKED_THREADS {
+            ch.remember_thread(
+                &format!("m{i}"),
+                ThreadRef {
+                    subject: String::new(),
+                    references: Vec::new(),
+                    from_address: None,
+                },
+            );
+        }
+        assert!(ch.thread("m0").is_none());
+        assert!(ch.thread(&format!("m{MAX_TRACKED_THREADS}")).is_some());
+    }
+
+    // ── strip_html tests ─────────────────────────────────────
+
+    #[test]
+    fn strip_html_removes_tags() {
+        assert_eq!(
+            EmailChannel::strip_html("<p>Hello <b>world</b></p>"),
+            "Hello world"
+        );
+    }
+
+    #[test]
+    fn strip_html_nested_tags() {
+        assert_eq!(
+            EmailChannel::strip_html("<div><span>text</span></div>"),
+            "text"
+        );
+    }
+
+    #[test]
+    fn strip_html_collapses_whitespace() {
+        assert_eq!(
+            EmailChannel::strip_html("<p>  hello   world  </p>"),
+            "hello world"
+        );
+    }
+
+    #[test]
+    fn strip_html_empty_input() {
+        assert_eq!(EmailChannel::strip_html(""), "");
+    }
+
+    #[test]
+    fn strip_html_no_tags() {
+        assert_eq!(EmailChannel::strip_html("plain text"), "plain text");
+    }
+
+    #[test]
+    fn strip_html_preserves_entities() {
+        assert_eq!(EmailChannel::strip_html("<p>&amp; &lt;</p>"), "&amp; &lt;");
+    }
+}
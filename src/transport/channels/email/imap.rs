@@ -0,0 +1,376 @@
+//! Minimal async `IMAP4rev1` client with just what the email channel needs:
+//! LOGIN, SELECT, UID SEARCH/FETCH/STORE/MOVE and IDLE.
+//! See: <https://datatracker.ietf.org/doc/html/rfc3501> and
+//! <https://datatracker.ietf.org/doc/html/rfc2177> (IDLE).
+
+use anyhow::{Context, Result, anyhow, bail};
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, BufReader};
+use tokio::net::TcpStream;
+use tokio_rustls::client::TlsStream;
+use tokio_rustls::rustls;
+
+/// Longest wait for a command's tagged response.
+const COMMAND_TIMEOUT: Duration = Duration::from_mins(1);
+
+/// Escape a string for use inside IMAP double-quoted strings (RFC 3501 §4.3).
+/// Backslash and double-quote are the only characters that need escaping.
+pub(super) fn escape_imap_quoted(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for ch in s.chars() {
+        match ch {
+            '\\' | '"' => {
+                out.push('\\');
+                out.push(ch);
+            }
+            '\0' | '\r' | '\n' => {}
+            _ => out.push(ch),
+        }
+    }
+    out
+}
+
+/// One server response with the literals it carried, e.g. the message in
+/// `* 1 FETCH (UID 7 BODY[] {1234}\r\n...)`. `line` holds the text around
+/// the literals.
+#[derive(Debug)]
+pub(super) struct Response {
+    pub line: String,
+    pub literals: Vec<Vec<u8>>,
+}
+
+pub(super) struct ImapSession<S> {
+    stream: BufReader<S>,
+    next_tag: u32,
+    capabilities: HashSet<String>,
+    /// Largest literal (e.g. a fetched message) accepted from the server.
+    max_literal: usize,
+}
+
+impl ImapSession<TlsStream<TcpStream>> {
+    /// Open an implicit-TLS connection (port 993) and read the greeting.
+    /// Literals over `max_literal` bytes are refused.
+    pub async fn connect(host: &str, port: u16, max_literal: usize) -> Result<Self> {
+        let tcp = tokio::time::timeout(COMMAND_TIMEOUT, TcpStream::connect((host, port)))
+            .await
+            .context("connect to IMAP server timed out")?
+            .context("connect to IMAP server")?;
+        let root_store: rustls::RootCertStore =
+            webpki_roots::TLS_SERVER_ROOTS.iter().cloned().collect();
+        let tls_config = rustls::ClientConfig::builder()
+            .with_root_certificates(root_store)
+            .with_no_client_auth();
+        let connector = tokio_rustls::TlsConnector::from(Arc::new(tls_config));
+        let server_name = rustls::pki_types::ServerName::try_from(host.to_string())
+            .context("parse IMAP server name for TLS")?;
+        let tls = connector
+            .connect(server_name, tcp)
+            .await
+            .context("establish TLS connection to IMAP")?;
+        Self::start(tls, max_literal).await
+    }
+}
+
+impl<S: AsyncRead + AsyncWrite + Unpin> ImapSession<S> {
+    /// Wrap a connected stream and read the server greeting.
+    pub async fn start(stream: S, max_literal: usize) -> Result<Self> {
+        let mut session = Self {
+            stream: BufReader::new(stream),
+            next_tag: 1,
+            capabilities: HashSet::new(),
+            max_literal,
+        };
+        let greeting = tokio::time::timeout(COMMAND_TIMEOUT, session.read_response())
+            .await
+            .context("read IMAP server greeting timed out")??;
+        if !greeting.line.starts_with("* OK") && !greeting.line.starts_with("* PREAUTH") {
+            bail!("IMAP server refused connection: {}", greeting.line.trim());
+        }
+        Ok(session)
+    }
+
+    pub async fn login(&mut self, username: &str, password: &str) -> Result<()> {
+        self.command(&format!(
+            "LOGIN \"{}\" \"{}\"",
+            escape_imap_quoted(username),
+            escape_imap_quoted(password)
+        ))
+        .await
+        .context("IMAP login failed")?;
+        let responses = self.command("CAPABILITY").await?;
+        self.capabilities = responses
+            .iter()
+            .filter_map(|response| response.line.strip_prefix("* CAPABILITY "))
+            .flat_map(str::split_whitespace)
+            .map(str::to_ascii_uppercase)
+            .collect();
+        Ok(())
+    }
+
+    pub fn supports(&self, capability: &str) -> bool {
+        self.capabilities.contains(capability)
+    }
+
+    pub async fn select(&mut self, folder: &str) -> Result<()> {
+        self.command(&format!("SELECT \"{}\"", escape_imap_quoted(folder)))
+            .await
+            .with_context(|| format!("select IMAP folder {folder}"))?;
+        Ok(())
+    }
+
+    /// UIDs of messages without the `\Seen` flag.
+    pub async fn search_unseen(&mut self) -> Result<Vec<u32>> {
+        let responses = self.command("UID SEARCH UNSEEN").await?;
+        Ok(responses
+            .iter()
+            .filter_map(|response| response.line.strip_prefix("* SEARCH"))
+            .flat_map(str::split_whitespace)
+            .filter_map(|uid| uid.parse().ok())
+            .collect())
+    }
+
+    /// The message's size in bytes, so oversized mail can be skipped
+    /// without fetching it.
+    pub async fn size(&mut self, uid: u32) -> Result<Option<usize>> {
+        let responses = self
+            .command(&format!("UID FETCH {uid} (RFC822.SIZE)"))
+            .await?;
+        Ok(responses.iter().find_map(|response| {
+            let (_, rest) = response.line.split_once("RFC822.SIZE ")?;
+            rest.split(|c: char| !c.is_ascii_digit())
+                .next()?
+                .parse()
+                .ok()
+        }))
+    }
+
+    /// The full message, fetched without setting `\Seen`.
+    pub async fn fetch(&mut self, uid: u32) -> Result<Option<Vec<u8>>> {
+        let responses = self
+            .command(&format!("UID FETCH {uid} BODY.PEEK[]"))
+            .await?;
+        Ok(responses
+            .into_iter()
+            .filter(|response| response.line.contains(" FETCH "))
+            .find_map(|response| response.literals.into_iter().next()))
+    }
+
+    pub async fn mark_seen(&mut self, uid: u32) -> Result<()> {
+        self.command(&format!("UID STORE {uid} +FLAGS (\\Seen)"))
+            .await?;
+        Ok(())
+    }
+
+    /// Move a message to `folder`, with COPY and EXPUNGE on servers without
+    /// the MOVE extension.
+    pub async fn move_to(&mut self, uid: u32, folder: &str) -> Result<()> {
+        let folder = escape_imap_quoted(folder);
+        if self.supports("MOVE") {
+            self.command(&format!("UID MOVE {uid} \"{folder}\""))
+                .await?;
+        } else {
+            self.command(&format!("UID COPY {uid} \"{folder}\""))
+                .await?;
+            self.command(&format!("UID STORE {uid} +FLAGS (\\Deleted)"))
+                .await?;
+            self.command("EXPUNGE").await?;
+        }
+        Ok(())
+    }
+
+    /// Wait in IDLE until the folder changes or `timeout` passes. Returns
+    /// whether new messages arrived.
+    pub async fn idle(&mut self, timeout: Duration) -> Result<bool> {
+        let tag = self.send_command("IDLE").await?;
+        let continuation = tokio::time::timeout(COMMAND_TIMEOUT, self.read_response())
+            .await
+            .context("IMAP IDLE was not acknowledged")??;
+        if !continuation.line.starts_with('+') {
+            bail!("IMAP IDLE rejected: {}", continuation.line.trim());
+        }
+
+        let deadline = tokio::time::Instant::now() + timeout;
+        let mut new_mail = false;
+        while !new_mail {
+            match tokio::time::timeout_at(deadline, self.read_response()).await {
+                Ok(response) => new_mail = response?.line.trim_end().ends_with(" EXISTS"),
+                Err(_elapsed) => break,
+            }
+        }
+
+        self.write_line("DONE").await?;
+        tokio::time::timeout(COMMAND_TIMEOUT, self.read_tagged(&tag))
+            .await
+            .map_err(|_| anyhow!("IMAP IDLE did not finish"))??;
+        Ok(new_mail)
+    }
+
+    pub async fn logout(&mut self) {
+        let _ = self.command("LOGOUT").await;
+    }
+
+    /// Run a command and return its untagged responses; errors unless the
+    /// server answers `OK`.
+    async fn command(&mut self, command: &str) -> Result<Vec<Response>> {
+        let tag = self.send_command(command).await?;
+        tokio::time::timeout(COMMAND_TIMEOUT, self.read_tagged(&tag))
+            .await
+            .map_err(|_| anyhow!("IMAP command timed out"))?
+    }
+
+    async fn send_command(&mut self, command: &str) -> Result<String> {
+        let tag = format!("A{}", self.next_tag);
+        self.next_tag += 1;
+        self.write_line(&format!("{tag} {command}")).await?;
+        Ok(tag)
+    }
+
+    async fn write_line(&mut self, line: &str) -> Result<()> {
+        let stream = self.stream.get_mut();
+        stream.write_all(line.as_bytes()).await?;
+        stream.write_all(b"\r\n").await?;
+        stream.flush().await?;
+        Ok(())
+    }
+
+    async fn read_tagged(&mut self, tag: &str) -> Result<Vec<Response>> {
+        let mut untagged = Vec::new();
+        loop {
+            let response = self.read_response().await?;
+            let Some(status) = response
+                .line
+                .strip_prefix(tag)
+                .and_then(|rest| rest.strip_prefix(' '))
+            else {
+                untagged.push(response);
+                continue;
+            };
+            if status.starts_with("OK") {
+                return Ok(untagged);
+            }
+            bail!("IMAP server replied: {status}");
+        }
+    }
+
+    async fn read_response(&mut self) -> Result<Response> {
+        let mut line = String::new();
+        let mut literals = Vec::new();
+        loop {
+            let mut raw = Vec::new();
+            if self.stream.read_until(b'\n', &mut raw).await? == 0 {
+                bail!("IMAP connection closed");
+            }
+            let text = String::from_utf8_lossy(&raw);
+            let text = text.trim_end_matches(['\r', '\n']);
+            let literal_len = text
+                .strip_suffix('}')
+                .and_then(|rest| rest.rsplit_once('{'))
+                .and_then(|(_, len)| len.parse::<usize>().ok());
+            line.push_str(text);
+            let Some(len) = literal_len else {
+                return Ok(Response { line, literals });
+            };
+            // The length comes from the server; check it before allocating.
+            if len > self.max_literal {
+                bail!(
+                    "IMAP literal of {len} bytes exceeds the {}-byte message limit",
+                    self.max_literal
+                );
+            }
+            let mut literal = vec![0; len];
+            self.stream.read_exact(&mut literal).await?;
+            literals.push(literal);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{DuplexStream, duplex};
+
+    async fn session(server_script: &'static [u8]) -> (ImapSession<DuplexStream>, DuplexStream) {
+        let (client, mut server) = duplex(64 * 1024);
+        server.write_all(server_script).await.unwrap();
+        (ImapSession::start(client, 1024).await.unwrap(), server)
+    }
+
+    #[test]
+    fn escape_imap_quoted_escapes_quotes_and_drops_newlines() {
+        assert_eq!(escape_imap_quoted(r#"pa"ss\word"#), r#"pa\"ss\\word"#);
+        assert_eq!(escape_imap_quoted("a\r\nb"), "ab");
+    }
+
+    #[tokio::test]
+    async fn login_reads_capabilities() {
+        let (mut imap, mut server) = session(
+            b"* OK ready\r\nA1 OK logged in\r\n* CAPABILITY IMAP4rev1 IDLE MOVE\r\nA2 OK done\r\n",
+        )
+        .await;
+        imap.login("me", "secret").await.unwrap();
+        assert!(imap.supports("IDLE"));
+        assert!(imap.supports("MOVE"));
+
+        let mut sent = vec![0; 64];
+        let n = server.read(&mut sent).await.unwrap();
+        assert!(String::from_utf8_lossy(&sent[..n]).starts_with("A1 LOGIN \"me\" \"secret\""));
+    }
+
+    #[tokio::test]
+    async fn failed_login_is_an_error() {
+        let (mut imap, _server) =
+            session(b"* OK ready\r\nA1 NO [AUTHENTICATIONFAILED] bad\r\n").await;
+        assert!(imap.login("me", "wrong").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn search_and_fetch_read_literals() {
+        let (mut imap, _server) = session(
+            b"* OK ready\r\n* SEARCH 7 9\r\nA1 OK\r\n* 1 FETCH (UID 7 BODY[] {11}\r\nHello\r\nWorl)\r\nA2 OK\r\n",
+        )
+        .await;
+        assert_eq!(imap.search_unseen().await.unwrap(), vec![7, 9]);
+        let body = imap.fetch(7).await.unwrap().unwrap();
+        assert_eq!(body, b"Hello\r\nWorl");
+    }
+
+    #[tokio::test]
+    async fn size_reads_rfc822_size() {
+        let (mut imap, _server) =
+            session(b"* OK ready\r\n* 1 FETCH (UID 7 RFC822.SIZE 52428800)\r\nA1 OK\r\n").await;
+        assert_eq!(imap.size(7).await.unwrap(), Some(52_428_800));
+    }
+
+    #[tokio::test]
+    async fn oversized_literals_are_refused_before_reading() {
+        let (mut imap, _server) =
+            session(b"* OK ready\r\n* 1 FETCH (UID 7 BODY[] {18446744073709551615}\r\n").await;
+        let error = imap.fetch(7).await.unwrap_err();
+        assert!(format!("{error:#}").contains("exceeds the 1024-byte message limit"));
+    }
+
+    #[tokio::test]
+    async fn idle_returns_on_new_mail() {
+        let (mut imap, _server) =
+            session(b"* OK ready\r\n+ idling\r\n* 4 EXISTS\r\nA1 OK IDLE done\r\n").await;
+        assert!(imap.idle(Duration::from_secs(5)).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn idle_times_out_without_changes() {
+        let (mut imap, mut server) = session(b"* OK ready\r\n+ idling\r\n").await;
+        let finish = tokio::spawn(async move {
+            let mut buf = vec![0; 64];
+            let mut received = String::new();
+            while !received.contains("DONE") {
+                let n = server.read(&mut buf).await.unwrap();
+                received.push_str(&String::from_utf8_lossy(&buf[..n]));
+            }
+            server.write_all(b"A1 OK IDLE done\r\n").await.unwrap();
+        });
+        assert!(!imap.idle(Duration::from_millis(50)).await.unwrap());
+        finish.await.unwrap();
+    }
+}
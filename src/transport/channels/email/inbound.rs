@@ -0,0 +1,327 @@
+//! Turns fetched emails into [`ChannelMessage`]s: reply threading, quoted
+//! history and attachments.
+
+use mail_parser::{MessageParser, MimeHeaders};
+use std::time::{SystemTime, UNIX_EPOCH};
+use uuid::Uuid;
+
+use crate::transport::channels::traits::{ChannelMessage, MediaAttachment, MediaData};
+
+/// Attachments larger than this are left out of the message.
+const MAX_ATTACHMENT_BYTES: usize = 10 * 1024 * 1024;
+
+/// What is needed to answer an email as a reply in its thread.
+#[derive(Debug, Clone)]
+pub(super) struct ThreadRef {
+    pub subject: String,
+    /// Message ids of the thread, oldest first; the last one is answered.
+    pub references: Vec<String>,
+    /// Sender for replies when the folder rule overrides `from_address`.
+    pub from_address: Option<String>,
+}
+
+pub(super) struct InboundMail {
+    pub message: ChannelMessage,
+    /// The plain sender address, for the allowlist.
+    pub from: String,
+    /// Message id of the first email in the thread.
+    pub thread_root: String,
+    pub thread: ThreadRef,
+}
+
+/// Reply target for an email: `address#thread_root`. Used as
+/// [`ChannelMessage::sender`] so replies stay in the thread. Domains never
+/// contain `#`.
+pub fn reply_address(address: &str, thread_root: &str) -> String {
+    format!("{address}#{thread_root}")
+}
+
+/// Inverse of [`reply_address`]; a bare address starts a new thread.
+pub fn parse_reply_address(address: &str) -> (&str, Option<&str>) {
+    let domain_start = address.find('@').map_or(0, |at| at + 1);
+    match address[domain_start..].find('#') {
+        Some(pos) => {
+            let split = domain_start + pos;
+            (&address[..split], Some(&address[split + 1..]))
+        }
+        None => (address, None),
+    }
+}
+
+/// `Re: subject`, without stacking prefixes.
+pub(super) fn reply_subject(subject: &str) -> String {
+    let has_prefix = subject
+        .get(..3)
+        .is_some_and(|prefix| prefix.eq_ignore_ascii_case("re:"));
+    if has_prefix {
+        subject.to_string()
+    } else {
+        format!("Re: {subject}")
+    }
+}
+
+pub(super) fn parse_mail(raw: &[u8]) -> Option<InboundMail> {
+    let parsed = MessageParser::default().parse(raw)?;
+    let from = parsed
+        .from()
+        .and_then(|addr| addr.first())
+        .and_then(|a| a.address())
+        .map_or_else(|| "unknown".to_string(), ToString::to_string);
+    let subject = parsed.subject().unwrap_or("(no subject)").to_string();
+    let message_id = parsed.message_id().map_or_else(
+        || format!("gen-{}@asteroniris", Uuid::new_v4()),
+        ToString::to_string,
+    );
+
+    let mut references: Vec<String> = parsed
+        .references()
+        .as_text_list()
+        .unwrap_or_default()
+        .iter()
+        .map(ToString::to_string)
+        .collect();
+    let in_reply_to = parsed.in_reply_to().as_text().map(ToString::to_string);
+    if let Some(parent) = &in_reply_to
+        && !references.contains(parent)
+    {
+        references.push(parent.clone());
+    }
+    let thread_root = references
+        .first()
+        .cloned()
+        .unwrap_or_else(|| message_id.clone());
+    references.push(message_id.clone());
+
+    let body = strip_quoted_reply(&extract_text(&parsed));
+    let timestamp = parsed
+        .date()
+        .and_then(|date| u64::try_from(date.to_timestamp()).ok())
+        .unwrap_or_else(|| {
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs()
+        });
+
+    let message = ChannelMessage {
+        id: message_id.clone(),
+        sender: reply_address(&from, &thread_root),
+        author: Some(from.clone()),
+        content: format!("Subject: {subject}\n\n{body}"),
+        channel: "email".to_string(),
+        conversation_id: Some(thread_root.clone()),
+        thread_id: None,
+        reply_to: in_reply_to,
+        message_id: Some(message_id),
+        timestamp,
+        attachments: extract_attachments(&parsed),
+    };
+    Some(InboundMail {
+        message,
+        from,
+        thread_root,
+        thread: ThreadRef {
+            subject,
+            references,
+            from_address: None,
+        },
+    })
+}
+
+/// Strip HTML tags from content (basic)
+pub(super) fn strip_html(html: &str) -> String {
+    let mut result = String::new();
+    let mut in_tag = false;
+    for ch in html.chars() {
+        match ch {
+            '<' => in_tag = true,
+            '>' => in_tag = false,
+            _ if !in_tag => result.push(ch),
+            _ => {}
+        }
+    }
+    result.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Extract readable text from a parsed email
+fn extract_text(parsed: &mail_parser::Message) -> String {
+    if let Some(text) = parsed.body_text(0) {
+        return text.to_string();
+    }
+    if let Some(html) = parsed.body_html(0) {
+        return strip_html(html.as_ref());
+    }
+    for part in parsed.attachments() {
+        if let Some(ct) = MimeHeaders::content_type(part)
+            && ct.ctype() == "text"
+            && let Ok(text) = std::str::from_utf8(part.contents())
+        {
+            let name = MimeHeaders::attachment_name(part).unwrap_or("file");
+            return format!("[Attachment: {name}]\n{text}");
+        }
+    }
+    "(no readable content)".to_string()
+}
+
+/// Drop the quoted history mail clients append to replies: `>` lines and
+/// everything after an `On ... wrote:` or `Original Message` marker.
+fn strip_quoted_reply(text: &str) -> String {
+    let mut kept = Vec::new();
+    for line in text.lines() {
+        let trimmed = line.trim();
+        let is_attribution = trimmed.starts_with("On ") && trimmed.ends_with("wrote:");
+        if is_attribution || trimmed.starts_with("-----Original Message-----") {
+            break;
+        }
+        if !trimmed.starts_with('>') {
+            kept.push(line);
+        }
+    }
+    let stripped = kept.join("\n").trim().to_string();
+    if stripped.is_empty() {
+        text.trim().to_string()
+    } else {
+        stripped
+    }
+}
+
+fn extract_attachments(parsed: &mail_parser::Message) -> Vec<MediaAttachment> {
+    parsed
+        .attachments()
+        .filter_map(|part| {
+            let filename = MimeHeaders::attachment_name(part).map(ToString::to_string);
+            let contents = part.contents();
+            if contents.len() > MAX_ATTACHMENT_BYTES {
+                tracing::warn!(
+                    "Email attachment {} skipped: {} bytes",
+                    filename.as_deref().unwrap_or("(unnamed)"),
+                    contents.len()
+                );
+                return None;
+            }
+            let mime_type = MimeHeaders::content_type(part).map_or_else(
+                || "application/octet-stream".to_string(),
+                |ct| match ct.subtype() {
+                    Some(subtype) => format!("{}/{subtype}", ct.ctype()),
+                    None => ct.ctype().to_string(),
+                },
+            );
+            Some(MediaAttachment {
+                mime_type,
+                data: MediaData::Bytes(contents.to_vec()),
+                filename,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FIRST: &str = "From: Alice <alice@example.com>\r\n\
+To: support@example.org\r\n\
+Subject: Printer broken\r\n\
+Message-ID: <m1@example.com>\r\n\
+Date: Tue, 1 Jul 2025 10:00:00 +0200\r\n\
+\r\n\
+It jams on every page.\r\n";
+
+    const REPLY: &str = "From: Alice <alice@example.com>\r\n\
+Subject: Re: Printer broken\r\n\
+Message-ID: <m3@example.com>\r\n\
+In-Reply-To: <m2@example.org>\r\n\
+References: <m1@example.com> <m2@example.org>\r\n\
+\r\n\
+Still jams.\r\n\
+\r\n\
+On Tue, 1 Jul 2025, Support wrote:\r\n\
+> Have you tried turning it off?\r\n";
+
+    #[test]
+    fn reply_address_round_trips() {
+        let address = reply_address("alice@example.com", "m1@example.com");
+        assert_eq!(address, "alice@example.com#m1@example.com");
+        assert_eq!(
+            parse_reply_address(&address),
+            ("alice@example.com", Some("m1@example.com"))
+        );
+        assert_eq!(
+            parse_reply_address("alice@example.com"),
+            ("alice@example.com", None)
+        );
+    }
+
+    #[test]
+    fn reply_subject_adds_one_prefix() {
+        assert_eq!(reply_subject("Printer broken"), "Re: Printer broken");
+        assert_eq!(reply_subject("RE: Printer broken"), "RE: Printer broken");
+    }
+
+    #[test]
+    fn new_email_starts_its_own_thread() {
+        let mail = parse_mail(FIRST.as_bytes()).unwrap();
+        assert_eq!(mail.from, "alice@example.com");
+        assert_eq!(mail.thread_root, "m1@example.com");
+        assert_eq!(mail.message.sender, "alice@example.com#m1@example.com");
+        assert_eq!(
+            mail.message.conversation_id.as_deref(),
+            Some("m1@example.com")
+        );
+        assert_eq!(
+            mail.message.content,
+            "Subject: Printer broken\n\nIt jams on every page."
+        );
+        assert_eq!(mail.message.timestamp, 1_751_356_800);
+        assert_eq!(mail.thread.references, vec!["m1@example.com"]);
+    }
+
+    #[test]
+    fn replies_join_the_thread_root_without_quoted_history() {
+        let mail = parse_mail(REPLY.as_bytes()).unwrap();
+        assert_eq!(mail.thread_root, "m1@example.com");
+        assert_eq!(
+            mail.message.conversation_id.as_deref(),
+            Some("m1@example.com")
+        );
+        assert_eq!(mail.message.reply_to.as_deref(), Some("m2@example.org"));
+        assert_eq!(
+            mail.thread.references,
+            vec!["m1@example.com", "m2@example.org", "m3@example.com"]
+        );
+        assert_eq!(
+            mail.message.content,
+            "Subject: Re: Printer broken\n\nStill jams."
+        );
+    }
+
+    #[test]
+    fn attachments_become_media() {
+        let raw = "From: alice@example.com\r\n\
+Subject: Logs\r\n\
+Message-ID: <m4@example.com>\r\n\
+MIME-Version: 1.0\r\n\
+Content-Type: multipart/mixed; boundary=\"b\"\r\n\
+\r\n\
+--b\r\n\
+Content-Type: text/plain\r\n\
+\r\n\
+See attached.\r\n\
+--b\r\n\
+Content-Type: image/png\r\n\
+Content-Disposition: attachment; filename=\"shot.png\"\r\n\
+Content-Transfer-Encoding: base64\r\n\
+\r\n\
+iVBORw0KGgo=\r\n\
+--b--\r\n";
+        let mail = parse_mail(raw.as_bytes()).unwrap();
+        assert_eq!(mail.message.content, "Subject: Logs\n\nSee attached.");
+        assert_eq!(mail.message.attachments.len(), 1);
+        let attachment = &mail.message.attachments[0];
+        assert_eq!(attachment.mime_type, "image/png");
+        assert_eq!(attachment.filename.as_deref(), Some("shot.png"));
+        assert!(
+            matches!(&attachment.data, MediaData::Bytes(bytes) if bytes.starts_with(b"\x89PNG"))
+        );
+    }
+}
@@ -0,0 +1,5 @@
+mod channel;
+mod imap;
+pub mod inbound;
+
+pub use channel::EmailChannel;
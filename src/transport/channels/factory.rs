@@ -7,6 +7,8 @@ use crate::transport::channels::EmailChannel;
 use crate::transport::channels::IMessageChannel;
 #[cfg(feature = "matrix")]
 use crate::transport::channels::MatrixChannel;
+#[cfg(feature = "mattermost")]
+use crate::transport::channels::MattermostChannel;
 #[cfg(feature = "slack")]
 use crate::transport::channels::SlackChannel;
 #[cfg(feature = "telegram")]
@@ -17,6 +19,7 @@ use crate::transport::channels::policy::{ChannelEntry, ChannelPolicy};
 #[cfg(feature = "irc")]
 use crate::transport::channels::{IrcChannel, IrcChannelConfig};
 use std::collections::HashSet;
+use std::path::Path;
 use std::sync::Arc;
 
 fn build_policy(
@@ -29,14 +32,21 @@ fn build_policy(
     }
 }
 
-pub fn build_channels(channels_config: ChannelsConfig) -> Vec<ChannelEntry> {
+/// Build the configured channels. Channels that keep local state (the Matrix
+/// device store) put it under `workspace_dir`.
+#[cfg_attr(not(feature = "matrix"), allow(unused_variables))]
+#[allow(clippy::too_many_lines)]
+pub fn build_channels(channels_config: ChannelsConfig, workspace_dir: &Path) -> Vec<ChannelEntry> {
     let mut channels = Vec::with_capacity(8);
 
     #[cfg(feature = "telegram")]
     if let Some(tg) = channels_config.telegram {
         channels.push(ChannelEntry {
             name: "Telegram",
-            channel: Arc::new(TelegramChannel::new(tg.bot_token, tg.allowed_users)),
+            channel: Arc::new(
+                TelegramChannel::new(tg.bot_token, tg.allowed_users)
+                    .with_webhook(tg.webhook_url, tg.webhook_secret),
+            ),
             policy: build_policy(tg.autonomy_level, tg.tool_allowlist),
         });
     }
@@ -55,11 +65,11 @@ pub fn build_channels(channels_config: ChannelsConfig) -> Vec<ChannelEntry> {
     if let Some(sl) = channels_config.slack {
         channels.push(ChannelEntry {
             name: "Slack",
-            channel: Arc::new(SlackChannel::new(
-                sl.bot_token,
-                sl.channel_id,
-                sl.allowed_users,
-            )),
+            channel: Arc::new(
+                SlackChannel::new(sl.bot_token, sl.channel_id, sl.allowed_users)
+                    .with_app_token(sl.app_token)
+                    .with_signing_secret(sl.signing_secret),
+            ),
             policy: build_policy(sl.autonomy_level, sl.tool_allowlist),
         });
     }
@@ -75,18 +85,36 @@ pub fn build_channels(channels_config: ChannelsConfig) -> Vec<ChannelEntry> {
 
     #[cfg(feature = "matrix")]
     if let Some(mx) = channels_config.matrix {
+        let mut channel =
+            MatrixChannel::new(mx.homeserver, mx.access_token, mx.room_id, mx.allowed_users)
+                .with_auto_join(mx.auto_join);
+        if mx.e2e {
+            channel = channel.with_encryption(
+                workspace_dir.join("state").join("matrix"),
+                mx.store_passphrase,
+            );
+        }
         channels.push(ChannelEntry {
             name: "Matrix",
-            channel: Arc::new(MatrixChannel::new(
-                mx.homeserver,
-                mx.access_token,
-                mx.room_id,
-                mx.allowed_users,
-            )),
+            channel: Arc::new(channel),
             policy: build_policy(mx.autonomy_level, mx.tool_allowlist),
         });
     }
 
+    #[cfg(feature = "mattermost")]
+    if let Some(mm) = channels_config.mattermost {
+        channels.push(ChannelEntry {
+            name: "Mattermost",
+            channel: Arc::new(MattermostChannel::new(
+                mm.url,
+                mm.bot_token,
+                mm.channel_id,
+                mm.allowed_users,
+            )),
+            policy: build_policy(mm.autonomy_level, mm.tool_allowlist),
+        });
+    }
+
     #[cfg(feature = "whatsapp")]
     if let Some(wa) = channels_config.whatsapp {
         channels.push(ChannelEntry {
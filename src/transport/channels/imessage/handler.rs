@@ -108,6 +108,7 @@ end tell"#
                             let msg = ChannelMessage {
                                 id: rowid.to_string(),
                                 sender,
+                                author: None,
                                 content: text,
                                 channel: "imessage".to_string(),
                                 conversation_id: None,
@@ -397,6 +397,7 @@ impl Channel for IrcChannel {
                         let channel_msg = ChannelMessage {
                             id: format!("irc_{}_{seq}", chrono::Utc::now().timestamp_millis()),
                             sender: reply_to,
+                            author: Some(sender_nick.to_string()),
                             content,
                             channel: "irc".to_string(),
                             conversation_id: None,
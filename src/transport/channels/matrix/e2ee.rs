@@ -0,0 +1,281 @@
+//! End-to-end encryption for the Matrix channel: an Olm machine with a
+//! `SQLite` device store, fed by the channel's own sync loop and HTTP client.
+//! See: <https://spec.matrix.org/latest/client-server-api/#end-to-end-encryption>
+
+use anyhow::Context;
+use matrix_sdk_crypto::types::events::room::encrypted::EncryptedEvent;
+use matrix_sdk_crypto::types::requests::{AnyOutgoingRequest, ToDeviceRequest};
+use matrix_sdk_crypto::{
+    DecryptionSettings, EncryptionSettings, EncryptionSyncChanges, OlmMachine, TrustRequirement,
+};
+use matrix_sdk_sqlite::SqliteCryptoStore;
+use reqwest::Client;
+use ruma::api::client::keys::get_keys;
+use ruma::api::client::message::send_message_event;
+use ruma::api::client::sync::sync_events::DeviceLists;
+use ruma::api::client::to_device::send_event_to_device;
+use ruma::api::{IncomingResponse, MatrixVersion, OutgoingRequest, SendAccessToken};
+use ruma::events::{AnyMessageLikeEventContent, EventContent as _};
+use ruma::serde::Raw;
+use ruma::{OneTimeKeyAlgorithm, OwnedDeviceId, OwnedUserId, RoomId, UInt, UserId};
+use serde_json::Value;
+use serde_json::value::to_raw_value;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use super::models::{SyncResponse, TimelineEvent};
+
+pub(super) struct MatrixE2ee {
+    machine: OlmMachine,
+    homeserver: String,
+    access_token: String,
+    client: Client,
+}
+
+impl MatrixE2ee {
+    /// Load (or create) the device identity for `user_id`/`device_id` and
+    /// upload its keys.
+    pub(super) async fn open(
+        store_dir: &Path,
+        passphrase: Option<&str>,
+        user_id: &str,
+        device_id: &str,
+        homeserver: &str,
+        access_token: &str,
+    ) -> anyhow::Result<Self> {
+        let user_id = UserId::parse(user_id).context("parse Matrix user id")?;
+        let device_id = OwnedDeviceId::from(device_id);
+        let store = SqliteCryptoStore::open(store_dir, passphrase)
+            .await
+            .with_context(|| format!("open Matrix crypto store {}", store_dir.display()))?;
+        let machine = OlmMachine::with_store(&user_id, &device_id, store, None)
+            .await
+            .context(
+                "load Matrix device keys (a store created for another device must be removed)",
+            )?;
+        let e2ee = Self {
+            machine,
+            homeserver: homeserver.to_string(),
+            access_token: access_token.to_string(),
+            client: Client::new(),
+        };
+        e2ee.flush_outgoing().await;
+        Ok(e2ee)
+    }
+
+    /// Feed the key material of a sync response to the machine and answer
+    /// what it asks for. Returns whether new room keys arrived.
+    pub(super) async fn receive_sync(&self, sync: &SyncResponse) -> anyhow::Result<bool> {
+        let to_device_events = sync
+            .to_device
+            .events
+            .iter()
+            .filter_map(|event| to_raw_value(event).ok().map(Raw::from_json))
+            .collect();
+        let changed_devices: DeviceLists = from_optional(sync.device_lists.as_ref());
+        let one_time_keys_counts: BTreeMap<OneTimeKeyAlgorithm, UInt> =
+            from_optional(sync.device_one_time_keys_count.as_ref());
+        let unused_fallback_keys: Option<Vec<OneTimeKeyAlgorithm>> = sync
+            .device_unused_fallback_key_types
+            .clone()
+            .and_then(|value| serde_json::from_value(value).ok());
+
+        let (_, room_keys) = self
+            .machine
+            .receive_sync_changes(EncryptionSyncChanges {
+                to_device_events,
+                changed_devices: &changed_devices,
+                one_time_keys_counts: &one_time_keys_counts,
+                unused_fallback_keys: unused_fallback_keys.as_deref(),
+                next_batch_token: Some(sync.next_batch.clone()),
+            })
+            .await
+            .context("process Matrix to-device events")?;
+        self.flush_outgoing().await;
+        Ok(!room_keys.is_empty())
+    }
+
+    pub(super) async fn decrypt(
+        &self,
+        room_id: &str,
+        event: &TimelineEvent,
+    ) -> anyhow::Result<TimelineEvent> {
+        let room_id = RoomId::parse(room_id).context("parse Matrix room id")?;
+        let raw: Raw<EncryptedEvent> = Raw::from_json(to_raw_value(&event.raw)?);
+        let settings = DecryptionSettings {
+            sender_device_trust_requirement: TrustRequirement::Untrusted,
+        };
+        let decrypted = self
+            .machine
+            .decrypt_room_event(&raw, &room_id, &settings)
+            .await?;
+        serde_json::from_str(decrypted.event.json().get()).context("parse decrypted Matrix event")
+    }
+
+    /// Encrypt `content` for the devices of `members`, sharing the room key
+    /// with any that do not have it yet. Returns `m.room.encrypted` content.
+    pub(super) async fn encrypt(
+        &self,
+        room_id: &str,
+        members: &[String],
+        event_type: &str,
+        content: &Value,
+    ) -> anyhow::Result<Value> {
+        let room_id = RoomId::parse(room_id).context("parse Matrix room id")?;
+        let members: Vec<OwnedUserId> = members
+            .iter()
+            .filter_map(|member| UserId::parse(member).ok())
+            .collect();
+
+        self.machine
+            .update_tracked_users(members.iter().map(AsRef::as_ref))
+            .await?;
+        self.flush_outgoing().await;
+        if let Some((txn_id, request)) = self
+            .machine
+            .get_missing_sessions(members.iter().map(AsRef::as_ref))
+            .await?
+        {
+            let response = self.send(request).await?;
+            self.machine
+                .mark_request_as_sent(&txn_id, &response)
+                .await?;
+        }
+        let shares = self
+            .machine
+            .share_room_key(
+                &room_id,
+                members.iter().map(AsRef::as_ref),
+                EncryptionSettings::default(),
+            )
+            .await?;
+        for request in shares {
+            let response = self.send(to_device_request(&request)).await?;
+            self.machine
+                .mark_request_as_sent(&request.txn_id, &response)
+                .await?;
+        }
+
+        let content: Raw<AnyMessageLikeEventContent> = Raw::from_json(to_raw_value(content)?);
+        let encrypted = self
+            .machine
+            .encrypt_room_event_raw(&room_id, event_type, &content)
+            .await?;
+        serde_json::from_str(encrypted.json().get()).context("serialize encrypted Matrix event")
+    }
+
+    /// Send the key uploads, queries, claims and to-device messages the
+    /// machine has queued. Failures are retried on the next call.
+    async fn flush_outgoing(&self) {
+        let requests = match self.machine.outgoing_requests().await {
+            Ok(requests) => requests,
+            Err(error) => {
+                tracing::warn!("Matrix: failed to read queued crypto requests: {error}");
+                return;
+            }
+        };
+        for request in requests {
+            if let Err(error) = self
+                .send_outgoing(request.request_id(), request.request())
+                .await
+            {
+                tracing::warn!("Matrix: crypto request failed: {error:#}");
+            }
+        }
+    }
+
+    async fn send_outgoing(
+        &self,
+        request_id: &ruma::TransactionId,
+        request: &AnyOutgoingRequest,
+    ) -> anyhow::Result<()> {
+        match request {
+            AnyOutgoingRequest::KeysUpload(request) => {
+                let response = self.send(request.clone()).await?;
+                self.machine
+                    .mark_request_as_sent(request_id, &response)
+                    .await?;
+            }
+            AnyOutgoingRequest::KeysQuery(request) => {
+                let mut query = get_keys::v3::Request::new();
+                query.device_keys.clone_from(&request.device_keys);
+                query.timeout = request.timeout;
+                let response = self.send(query).await?;
+                self.machine
+                    .mark_request_as_sent(request_id, &response)
+                    .await?;
+            }
+            AnyOutgoingRequest::KeysClaim(request) => {
+                let response = self.send(request.clone()).await?;
+                self.machine
+                    .mark_request_as_sent(request_id, &response)
+                    .await?;
+            }
+            AnyOutgoingRequest::ToDeviceRequest(request) => {
+                let response = self.send(to_device_request(request)).await?;
+                self.machine
+                    .mark_request_as_sent(request_id, &response)
+                    .await?;
+            }
+            AnyOutgoingRequest::SignatureUpload(request) => {
+                let response = self.send(request.clone()).await?;
+                self.machine
+                    .mark_request_as_sent(request_id, &response)
+                    .await?;
+            }
+            AnyOutgoingRequest::RoomMessage(request) => {
+                let message = send_message_event::v3::Request::new_raw(
+                    request.room_id.clone(),
+                    request.txn_id.clone(),
+                    request.content.event_type(),
+                    Raw::new(&request.content)?,
+                );
+                let response = self.send(message).await?;
+                self.machine
+                    .mark_request_as_sent(request_id, &response)
+                    .await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Send a typed Client-Server API request with the channel's HTTP client.
+    async fn send<R: OutgoingRequest>(&self, request: R) -> anyhow::Result<R::IncomingResponse> {
+        let request = request
+            .try_into_http_request::<Vec<u8>>(
+                &self.homeserver,
+                SendAccessToken::IfRequired(&self.access_token),
+                &[MatrixVersion::V1_1],
+            )
+            .context("build Matrix crypto request")?;
+        let request = reqwest::Request::try_from(request)?;
+        let path = request.url().path().to_string();
+        let response = self
+            .client
+            .execute(request)
+            .await
+            .with_context(|| format!("send Matrix request {path}"))?;
+
+        let mut builder = http::Response::builder().status(response.status());
+        for (name, value) in response.headers() {
+            builder = builder.header(name, value);
+        }
+        let body = response.bytes().await?.to_vec();
+        R::IncomingResponse::try_from_http_response(builder.body(body)?)
+            .map_err(|error| anyhow::anyhow!("Matrix request {path} failed: {error}"))
+    }
+}
+
+fn to_device_request(request: &ToDeviceRequest) -> send_event_to_device::v3::Request {
+    send_event_to_device::v3::Request::new_raw(
+        request.event_type.clone(),
+        request.txn_id.clone(),
+        request.messages.clone(),
+    )
+}
+
+fn from_optional<T: serde::de::DeserializeOwned + Default>(value: Option<&Value>) -> T {
+    value
+        .and_then(|value| serde_json::from_value(value.clone()).ok())
+        .unwrap_or_default()
+}
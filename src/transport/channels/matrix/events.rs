@@ -0,0 +1,354 @@
+//! Turns Matrix room events into [`ChannelMessage`]s and builds the content
+//! of outgoing messages, edits and reactions.
+
+use crate::transport::channels::traits::ChannelMessage;
+use serde_json::Value;
+use uuid::Uuid;
+
+use super::media;
+use super::models::TimelineEvent;
+
+/// Message types answered by the bot.
+const MESSAGE_TYPES: [&str; 6] = [
+    "m.text", "m.notice", "m.image", "m.audio", "m.video", "m.file",
+];
+
+/// Reactions on an approval prompt and the reply each one stands for.
+pub(super) const APPROVAL_REACTIONS: [(&str, &str); 3] =
+    [("✅", "approve"), ("❌", "deny"), ("🔁", "always")];
+
+/// What the listener knows when deciding whether an event is for the bot.
+pub(super) struct InboundContext<'a> {
+    pub homeserver: &'a str,
+    pub user_id: &'a str,
+    pub display_name: Option<&'a str>,
+    /// The configured `room_id`, where every message is answered.
+    pub home_room: Option<&'a str>,
+    /// The room has at most two members.
+    pub is_direct: bool,
+}
+
+/// Reply target for a message: the room, or `room/thread_root` to answer
+/// inside a thread. Used as [`ChannelMessage::sender`] so replies land where
+/// the message was posted.
+pub fn reply_address(room_id: &str, thread_root: Option<&str>) -> String {
+    match thread_root {
+        Some(root) => format!("{room_id}/{root}"),
+        None => room_id.to_string(),
+    }
+}
+
+/// Inverse of [`reply_address`]. Room ids never contain `/`.
+pub fn parse_reply_address(address: &str) -> (&str, Option<&str>) {
+    match address.split_once('/') {
+        Some((room_id, root)) => (room_id, Some(root)),
+        None => (address, None),
+    }
+}
+
+/// The message in a room event, when it is addressed to the bot: anything
+/// in direct messages and the home room, and mentions elsewhere. Mentions
+/// outside threads are answered in a new thread.
+pub(super) fn message_from_event(
+    room_id: &str,
+    event: &TimelineEvent,
+    ctx: &InboundContext<'_>,
+) -> Option<ChannelMessage> {
+    if event.event_type != "m.room.message" || event.sender == ctx.user_id {
+        return None;
+    }
+    let content = &event.content;
+    if !MESSAGE_TYPES.contains(&content.msgtype.as_deref()?) {
+        return None;
+    }
+    let relation = content.relates_to.as_ref();
+    let rel_type = relation.and_then(|rel| rel.rel_type.as_deref());
+    // Edits repeat the original message; answering them would reply twice.
+    if rel_type == Some("m.replace") {
+        return None;
+    }
+    let thread_root = match rel_type {
+        Some("m.thread") => relation.and_then(|rel| rel.event_id.as_deref()),
+        _ => None,
+    };
+
+    let is_home = ctx.home_room == Some(room_id);
+    let thread_root = if ctx.is_direct || is_home {
+        thread_root
+    } else if is_mentioned(event, ctx) {
+        Some(thread_root.or(event.event_id.as_deref())?)
+    } else {
+        return None;
+    };
+
+    let attachments = media::parse_media_attachments(ctx.homeserver, content);
+    let body = content.body.as_deref().unwrap_or("");
+    let text = if attachments.is_empty() {
+        strip_mention(body, ctx)
+    } else {
+        // Media bodies are file names, already carried by the attachment.
+        String::new()
+    };
+    if text.is_empty() && attachments.is_empty() {
+        return None;
+    }
+
+    Some(ChannelMessage {
+        id: Uuid::new_v4().to_string(),
+        sender: reply_address(room_id, thread_root),
+        author: Some(event.sender.clone()),
+        content: text,
+        channel: "matrix".to_string(),
+        conversation_id: Some(room_id.to_string()),
+        thread_id: thread_root.map(ToString::to_string),
+        reply_to: None,
+        message_id: event.event_id.clone(),
+        timestamp: event.origin_server_ts.map_or_else(
+            || {
+                std::time::SystemTime::now()
+                    .duration_since(std::time::UNIX_EPOCH)
+                    .unwrap_or_default()
+                    .as_secs()
+            },
+            |ms| ms / 1000,
+        ),
+        attachments,
+    })
+}
+
+/// The approval reply a reaction stands for, if `event` is a known reaction
+/// on the prompt for `approval_id`.
+pub(super) fn approval_reply(event: &TimelineEvent, approval_id: &str) -> Option<String> {
+    let key = event.content.relates_to.as_ref()?.key.as_deref()?;
+    APPROVAL_REACTIONS
+        .iter()
+        .find(|(emoji, _)| key.trim_end_matches('\u{fe0f}') == *emoji)
+        .map(|(_, reply)| format!("{reply} {approval_id}"))
+}
+
+/// The event a reaction annotates.
+pub(super) fn reaction_target(event: &TimelineEvent) -> Option<&str> {
+    if event.event_type != "m.reaction" {
+        return None;
+    }
+    let relation = event.content.relates_to.as_ref()?;
+    if relation.rel_type.as_deref() != Some("m.annotation") {
+        return None;
+    }
+    relation.event_id.as_deref()
+}
+
+/// `m.room.message` content, threaded under `thread_root` when given.
+pub(super) fn message_content(mut content: Value, thread_root: Option<&str>) -> Value {
+    if let Some(root) = thread_root {
+        content["m.relates_to"] = serde_json::json!({
+            "rel_type": "m.thread",
+            "event_id": root,
+            "is_falling_back": true,
+            "m.in_reply_to": { "event_id": root }
+        });
+    }
+    content
+}
+
+/// Content replacing the text of `event_id`.
+pub(super) fn edit_content(event_id: &str, text: &str) -> Value {
+    serde_json::json!({
+        "msgtype": "m.text",
+        "body": format!("* {text}"),
+        "m.new_content": { "msgtype": "m.text", "body": text },
+        "m.relates_to": { "rel_type": "m.replace", "event_id": event_id }
+    })
+}
+
+pub(super) fn reaction_content(event_id: &str, key: &str) -> Value {
+    serde_json::json!({
+        "m.relates_to": { "rel_type": "m.annotation", "event_id": event_id, "key": key }
+    })
+}
+
+fn is_mentioned(event: &TimelineEvent, ctx: &InboundContext<'_>) -> bool {
+    let content = &event.content;
+    content
+        .mentions
+        .as_ref()
+        .is_some_and(|mentions| mentions.user_ids.iter().any(|id| id == ctx.user_id))
+        || content
+            .body
+            .as_deref()
+            .is_some_and(|body| body.contains(ctx.user_id))
+        || content
+            .formatted_body
+            .as_deref()
+            .is_some_and(|html| html.contains(ctx.user_id))
+}
+
+/// Drop the bot's user id and a leading `Display Name:` pill fallback.
+fn strip_mention(body: &str, ctx: &InboundContext<'_>) -> String {
+    let mut text = body.replace(ctx.user_id, "");
+    if let Some(name) = ctx.display_name.filter(|name| !name.is_empty())
+        && let Some(rest) = text.trim_start().strip_prefix(name)
+    {
+        text = rest.to_string();
+    }
+    text.trim()
+        .trim_start_matches([':', ','])
+        .trim()
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CTX: InboundContext<'static> = InboundContext {
+        homeserver: "https://m.org",
+        user_id: "@bot:m.org",
+        display_name: Some("Bot"),
+        home_room: Some("!home:m.org"),
+        is_direct: false,
+    };
+
+    fn event(json: Value) -> TimelineEvent {
+        serde_json::from_value(json).unwrap()
+    }
+
+    fn text_event(body: &str) -> Value {
+        serde_json::json!({
+            "type": "m.room.message",
+            "sender": "@alice:m.org",
+            "event_id": "$ev1",
+            "origin_server_ts": 1_700_000_000_123_u64,
+            "content": { "msgtype": "m.text", "body": body }
+        })
+    }
+
+    #[test]
+    fn reply_address_round_trips_threads() {
+        assert_eq!(reply_address("!r:m.org", None), "!r:m.org");
+        let threaded = reply_address("!r:m.org", Some("$root"));
+        assert_eq!(parse_reply_address(&threaded), ("!r:m.org", Some("$root")));
+        assert_eq!(parse_reply_address("!r:m.org"), ("!r:m.org", None));
+    }
+
+    #[test]
+    fn home_room_messages_are_answered_inline() {
+        let msg = message_from_event("!home:m.org", &event(text_event("hello")), &CTX).unwrap();
+        assert_eq!(msg.sender, "!home:m.org");
+        assert_eq!(msg.content, "hello");
+        assert_eq!(msg.conversation_id.as_deref(), Some("!home:m.org"));
+        assert_eq!(msg.message_id.as_deref(), Some("$ev1"));
+        assert_eq!(msg.timestamp, 1_700_000_000);
+        assert!(msg.thread_id.is_none());
+    }
+
+    #[test]
+    fn other_rooms_need_a_mention_and_start_a_thread() {
+        assert!(message_from_event("!other:m.org", &event(text_event("hello")), &CTX).is_none());
+
+        let msg = message_from_event(
+            "!other:m.org",
+            &event(text_event("Bot: summarize this")),
+            &CTX,
+        );
+        assert!(msg.is_none(), "display name alone is not a mention");
+
+        let mut mention = text_event("Bot: summarize this");
+        mention["content"]["m.mentions"] = serde_json::json!({ "user_ids": ["@bot:m.org"] });
+        let msg = message_from_event("!other:m.org", &event(mention), &CTX).unwrap();
+        assert_eq!(msg.content, "summarize this");
+        assert_eq!(msg.sender, "!other:m.org/$ev1");
+        assert_eq!(msg.thread_id.as_deref(), Some("$ev1"));
+    }
+
+    #[test]
+    fn direct_rooms_answer_everything() {
+        let ctx = InboundContext {
+            is_direct: true,
+            ..CTX
+        };
+        let msg = message_from_event("!dm:m.org", &event(text_event("hi")), &ctx).unwrap();
+        assert_eq!(msg.sender, "!dm:m.org");
+    }
+
+    #[test]
+    fn thread_replies_keep_the_thread_root() {
+        let mut json = text_event("and then?");
+        json["content"]["m.relates_to"] =
+            serde_json::json!({ "rel_type": "m.thread", "event_id": "$root" });
+        let msg = message_from_event("!home:m.org", &event(json), &CTX).unwrap();
+        assert_eq!(msg.thread_id.as_deref(), Some("$root"));
+        assert_eq!(msg.sender, "!home:m.org/$root");
+    }
+
+    #[test]
+    fn edits_and_own_messages_are_ignored() {
+        let mut edit = text_event("* fixed");
+        edit["content"]["m.relates_to"] =
+            serde_json::json!({ "rel_type": "m.replace", "event_id": "$ev0" });
+        assert!(message_from_event("!home:m.org", &event(edit), &CTX).is_none());
+
+        let mut own = text_event("hello");
+        own["sender"] = "@bot:m.org".into();
+        assert!(message_from_event("!home:m.org", &event(own), &CTX).is_none());
+    }
+
+    #[test]
+    fn media_messages_carry_attachments() {
+        let json = serde_json::json!({
+            "type": "m.room.message",
+            "sender": "@alice:m.org",
+            "content": {
+                "msgtype": "m.image",
+                "body": "cat.png",
+                "url": "mxc://m.org/cat",
+                "info": { "mimetype": "image/png" }
+            }
+        });
+        let msg = message_from_event("!home:m.org", &event(json), &CTX).unwrap();
+        assert!(msg.content.is_empty());
+        assert_eq!(msg.attachments.len(), 1);
+    }
+
+    #[test]
+    fn reactions_map_to_approval_replies() {
+        let reaction = event(serde_json::json!({
+            "type": "m.reaction",
+            "sender": "@alice:m.org",
+            "content": {
+                "m.relates_to": { "rel_type": "m.annotation", "event_id": "$prompt", "key": "✅️" }
+            }
+        }));
+        assert_eq!(reaction_target(&reaction), Some("$prompt"));
+        assert_eq!(
+            approval_reply(&reaction, "ab12").as_deref(),
+            Some("approve ab12")
+        );
+
+        let other = event(serde_json::json!({
+            "type": "m.reaction",
+            "sender": "@alice:m.org",
+            "content": {
+                "m.relates_to": { "rel_type": "m.annotation", "event_id": "$prompt", "key": "🎉" }
+            }
+        }));
+        assert!(approval_reply(&other, "ab12").is_none());
+    }
+
+    #[test]
+    fn outgoing_content_threads_and_edits() {
+        let content = message_content(
+            serde_json::json!({ "msgtype": "m.text", "body": "hi" }),
+            Some("$root"),
+        );
+        assert_eq!(content["m.relates_to"]["rel_type"], "m.thread");
+        assert_eq!(
+            content["m.relates_to"]["m.in_reply_to"]["event_id"],
+            "$root"
+        );
+
+        let edit = edit_content("$ev1", "fixed");
+        assert_eq!(edit["m.new_content"]["body"], "fixed");
+        assert_eq!(edit["m.relates_to"]["rel_type"], "m.replace");
+    }
+}
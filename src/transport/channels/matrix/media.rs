@@ -3,11 +3,14 @@ use crate::transport::channels::traits::MediaAttachment;
 
 use super::models::EventContent;
 
+/// Authenticated media download URL (`/_matrix/client/v1/media`), which
+/// needs the access token; the unauthenticated `/_matrix/media/v3` endpoint
+/// is deprecated and disabled on many homeservers.
 pub(super) fn mxc_to_http(homeserver: &str, mxc_url: &str) -> Option<String> {
     let stripped = mxc_url.strip_prefix("mxc://")?;
     let (server, media_id) = stripped.split_once('/')?;
     Some(format!(
-        "{homeserver}/_matrix/media/v3/download/{server}/{media_id}"
+        "{homeserver}/_matrix/client/v1/media/download/{server}/{media_id}"
     ))
 }
 
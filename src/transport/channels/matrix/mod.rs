@@ -1,28 +1,72 @@
+#[cfg(feature = "matrix-e2e")]
+mod e2ee;
+pub mod events;
 mod media;
 mod models;
 
 #[cfg(test)]
 mod tests;
 
+use crate::config::MatrixAutoJoin;
+use crate::security::approval::ApprovalRequest;
+use crate::transport::channels::attachments::{download_capped, load_attachment_bytes};
 use crate::transport::channels::policy::{AllowlistMatch, is_allowed_user};
 use crate::transport::channels::traits::{Channel, ChannelMessage, MediaAttachment, MediaData};
 use anyhow::Context;
 use reqwest::Client;
+use serde_json::Value;
+use std::collections::HashMap;
 use std::future::Future;
+use std::path::PathBuf;
 use std::pin::Pin;
-use tokio::sync::mpsc;
+use std::sync::{Mutex, PoisonError};
+use tokio::sync::{OnceCell, mpsc};
 
-use self::models::{EventContent, SyncResponse, WhoAmIResponse};
+use self::events::{
+    APPROVAL_REACTIONS, InboundContext, approval_reply, edit_content, message_content,
+    message_from_event, parse_reply_address, reaction_content, reaction_target, reply_address,
+};
+#[cfg(test)]
+use self::models::EventContent;
+use self::models::{SyncResponse, TimelineEvent, WhoAmIResponse};
+
+/// Encrypted events kept for another decryption attempt once their room
+/// key arrives.
+#[cfg(feature = "matrix-e2e")]
+const MAX_UNDECRYPTED_EVENTS: usize = 100;
 
 /// Matrix channel using the Client-Server API (no SDK needed).
 /// Connects to any Matrix homeserver (Element, Synapse, etc.).
-#[derive(Clone)]
 pub struct MatrixChannel {
     homeserver: String,
     access_token: String,
     room_id: String,
     allowed_users: Vec<String>,
+    auto_join: MatrixAutoJoin,
+    /// Device store directory; end-to-end encryption is off without it.
+    store_dir: Option<PathBuf>,
+    store_passphrase: Option<String>,
     client: Client,
+    identity: OnceCell<Identity>,
+    rooms: Mutex<HashMap<String, RoomInfo>>,
+    /// Approval prompts by event id: the request id and where it was sent.
+    approvals: Mutex<HashMap<String, (String, String)>>,
+    #[cfg(feature = "matrix-e2e")]
+    e2ee: OnceCell<Option<e2ee::MatrixE2ee>>,
+}
+
+#[cfg_attr(not(feature = "matrix-e2e"), allow(dead_code))]
+struct Identity {
+    user_id: String,
+    device_id: Option<String>,
+    display_name: Option<String>,
+}
+
+/// What the channel has learned about a room, filled in on demand.
+#[derive(Default)]
+struct RoomInfo {
+    encrypted: Option<bool>,
+    members: Option<Vec<String>>,
 }
 
 impl MatrixChannel {
@@ -42,10 +86,31 @@ impl MatrixChannel {
             access_token,
             room_id,
             allowed_users,
+            auto_join: MatrixAutoJoin::default(),
+            store_dir: None,
+            store_passphrase: None,
             client: Client::new(),
+            identity: OnceCell::new(),
+            rooms: Mutex::new(HashMap::new()),
+            approvals: Mutex::new(HashMap::new()),
+            #[cfg(feature = "matrix-e2e")]
+            e2ee: OnceCell::new(),
         }
     }
 
+    pub fn with_auto_join(mut self, auto_join: MatrixAutoJoin) -> Self {
+        self.auto_join = auto_join;
+        self
+    }
+
+    /// Enable end-to-end encryption with the Olm/Megolm device store kept in
+    /// `store_dir`.
+    pub fn with_encryption(mut self, store_dir: PathBuf, passphrase: Option<String>) -> Self {
+        self.store_dir = Some(store_dir);
+        self.store_passphrase = passphrase.filter(|p| !p.is_empty());
+        self
+    }
+
     fn is_user_allowed(&self, sender: &str) -> bool {
         is_allowed_user(
             &self.allowed_users,
@@ -54,12 +119,48 @@ impl MatrixChannel {
         )
     }
 
-    async fn get_my_user_id(&self) -> anyhow::Result<String> {
-        let url = format!("{}/_matrix/client/v3/account/whoami", self.homeserver);
+    /// Whether an invite from `inviter` is joined automatically. A `*`
+    /// allowlist lets anyone talk to the bot but does not let anyone pull it
+    /// into rooms: `allowed` needs the inviter listed by name.
+    fn accepts_invite(&self, inviter: Option<&str>) -> bool {
+        match self.auto_join {
+            MatrixAutoJoin::Off => false,
+            MatrixAutoJoin::All => true,
+            MatrixAutoJoin::Allowed => inviter.is_some_and(|inviter| {
+                self.allowed_users
+                    .iter()
+                    .any(|user| user != "*" && user.eq_ignore_ascii_case(inviter))
+            }),
+        }
+    }
+
+    fn home_room(&self) -> Option<&str> {
+        Some(self.room_id.as_str()).filter(|room| !room.is_empty())
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!("{}/_matrix/client/v3/{path}", self.homeserver)
+    }
+
+    async fn identity(&self) -> anyhow::Result<&Identity> {
+        self.identity
+            .get_or_try_init(|| async {
+                let who = self.whoami().await?;
+                let display_name = self.display_name(&who.user_id).await;
+                Ok(Identity {
+                    user_id: who.user_id,
+                    device_id: who.device_id,
+                    display_name,
+                })
+            })
+            .await
+    }
+
+    async fn whoami(&self) -> anyhow::Result<WhoAmIResponse> {
         let resp = self
             .client
-            .get(&url)
-            .header("Authorization", format!("Bearer {}", self.access_token))
+            .get(self.url("account/whoami"))
+            .bearer_auth(&self.access_token)
             .send()
             .await
             .context("send Matrix whoami request")?;
@@ -69,8 +170,337 @@ impl MatrixChannel {
             anyhow::bail!("Matrix whoami failed: {err}");
         }
 
-        let who: WhoAmIResponse = resp.json().await.context("parse Matrix whoami response")?;
-        Ok(who.user_id)
+        resp.json().await.context("parse Matrix whoami response")
+    }
+
+    async fn display_name(&self, user_id: &str) -> Option<String> {
+        let resp = self
+            .client
+            .get(self.url(&format!("profile/{}/displayname", path_segment(user_id))))
+            .bearer_auth(&self.access_token)
+            .send()
+            .await
+            .ok()?;
+        let data: Value = resp.json().await.ok()?;
+        data.get("displayname")
+            .and_then(Value::as_str)
+            .map(ToString::to_string)
+    }
+
+    /// The device's Olm machine, opened on first use. `None` when encryption
+    /// is off or the device store cannot be opened.
+    #[cfg(feature = "matrix-e2e")]
+    async fn e2ee(&self) -> Option<&e2ee::MatrixE2ee> {
+        let store_dir = self.store_dir.as_ref()?;
+        let result = self
+            .e2ee
+            .get_or_try_init(|| async {
+                let identity = self.identity().await?;
+                let Some(device_id) = identity.device_id.as_deref() else {
+                    tracing::warn!(
+                        "Matrix: access token is not bound to a device; encrypted rooms stay unreadable"
+                    );
+                    return Ok(None);
+                };
+                let e2ee = e2ee::MatrixE2ee::open(
+                    store_dir,
+                    self.store_passphrase.as_deref(),
+                    &identity.user_id,
+                    device_id,
+                    &self.homeserver,
+                    &self.access_token,
+                )
+                .await?;
+                tracing::info!("Matrix: end-to-end encryption ready for device {device_id}");
+                anyhow::Ok(Some(e2ee))
+            })
+            .await;
+        match result {
+            Ok(e2ee) => e2ee.as_ref(),
+            Err(error) => {
+                tracing::warn!("Matrix: end-to-end encryption unavailable: {error:#}");
+                None
+            }
+        }
+    }
+
+    fn room_info<T>(&self, room_id: &str, f: impl FnOnce(&mut RoomInfo) -> T) -> T {
+        let mut rooms = self.rooms.lock().unwrap_or_else(PoisonError::into_inner);
+        f(rooms.entry(room_id.to_string()).or_default())
+    }
+
+    /// Whether the room has `m.room.encryption` state. Only a 404 means it
+    /// does not; any other failure is an error and is not cached, so nothing
+    /// is sent in plaintext to a room that may be encrypted.
+    async fn is_encrypted(&self, room_id: &str) -> anyhow::Result<bool> {
+        if let Some(encrypted) = self.room_info(room_id, |info| info.encrypted) {
+            return Ok(encrypted);
+        }
+        let resp = self
+            .client
+            .get(self.url(&format!(
+                "rooms/{}/state/m.room.encryption/",
+                path_segment(room_id)
+            )))
+            .bearer_auth(&self.access_token)
+            .send()
+            .await
+            .context("send Matrix room encryption state request")?;
+        let encrypted = match resp.status() {
+            status if status.is_success() => true,
+            reqwest::StatusCode::NOT_FOUND => false,
+            status => anyhow::bail!("Matrix room encryption state request failed: {status}"),
+        };
+        self.room_info(room_id, |info| info.encrypted = Some(encrypted));
+        Ok(encrypted)
+    }
+
+    async fn members(&self, room_id: &str) -> anyhow::Result<Vec<String>> {
+        if let Some(members) = self.room_info(room_id, |info| info.members.clone()) {
+            return Ok(members);
+        }
+        let resp = self
+            .client
+            .get(self.url(&format!("rooms/{}/joined_members", path_segment(room_id))))
+            .bearer_auth(&self.access_token)
+            .send()
+            .await
+            .context("send Matrix joined_members request")?;
+        if !resp.status().is_success() {
+            let err = resp.text().await?;
+            anyhow::bail!("Matrix joined_members failed: {err}");
+        }
+        let data: Value = resp
+            .json()
+            .await
+            .context("parse Matrix joined_members response")?;
+        let members: Vec<String> = data
+            .get("joined")
+            .and_then(Value::as_object)
+            .map(|joined| joined.keys().cloned().collect())
+            .unwrap_or_default();
+        self.room_info(room_id, |info| info.members = Some(members.clone()));
+        Ok(members)
+    }
+
+    /// Send a room event, encrypting it in encrypted rooms. Returns the new
+    /// event id.
+    async fn send_event(
+        &self,
+        room_id: &str,
+        event_type: &str,
+        content: Value,
+    ) -> anyhow::Result<String> {
+        let (event_type, content) = self.seal(room_id, event_type, content).await?;
+        let txn_id = format!("zc_{}", uuid::Uuid::new_v4().simple());
+        let url = self.url(&format!(
+            "rooms/{}/send/{event_type}/{txn_id}",
+            path_segment(room_id)
+        ));
+
+        let resp = self
+            .client
+            .put(&url)
+            .bearer_auth(&self.access_token)
+            .json(&content)
+            .send()
+            .await
+            .context("send Matrix room event")?;
+
+        if !resp.status().is_success() {
+            let err = resp.text().await?;
+            anyhow::bail!("Matrix send failed: {err}");
+        }
+
+        let data: Value = resp.json().await.context("parse Matrix send response")?;
+        Ok(data
+            .get("event_id")
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+            .to_string())
+    }
+
+    #[cfg(feature = "matrix-e2e")]
+    async fn seal(
+        &self,
+        room_id: &str,
+        event_type: &str,
+        content: Value,
+    ) -> anyhow::Result<(String, Value)> {
+        if !self.is_encrypted(room_id).await? {
+            return Ok((event_type.to_string(), content));
+        }
+        let Some(e2ee) = self.e2ee().await else {
+            anyhow::bail!("Matrix room {room_id} is encrypted and encryption is unavailable");
+        };
+        let members = self.members(room_id).await?;
+        let encrypted = e2ee
+            .encrypt(room_id, &members, event_type, &content)
+            .await
+            .context("encrypt Matrix room event")?;
+        Ok(("m.room.encrypted".to_string(), encrypted))
+    }
+
+    #[cfg(not(feature = "matrix-e2e"))]
+    async fn seal(
+        &self,
+        room_id: &str,
+        event_type: &str,
+        content: Value,
+    ) -> anyhow::Result<(String, Value)> {
+        if self.is_encrypted(room_id).await? {
+            anyhow::bail!("Matrix room {room_id} is encrypted; build with the matrix-e2e feature");
+        }
+        Ok((event_type.to_string(), content))
+    }
+
+    /// Room and thread for an outgoing message; anything that is not a
+    /// room address (empty, a user id) goes to the home room.
+    fn target<'a>(&'a self, recipient: &'a str) -> anyhow::Result<(&'a str, Option<&'a str>)> {
+        let (room_id, thread_root) = parse_reply_address(recipient);
+        if room_id.starts_with('!') {
+            return Ok((room_id, thread_root));
+        }
+        self.home_room()
+            .map(|room| (room, None))
+            .context("Matrix message has no room and no room_id is configured")
+    }
+
+    async fn sync(&self, since: Option<&str>) -> anyhow::Result<SyncResponse> {
+        let url = match since {
+            Some(since) => self.url(&format!("sync?since={since}&timeout=30000")),
+            // The first sync only catches up on state and keys; a short
+            // timeline keeps old messages from being answered.
+            None => self.url("sync?timeout=30000&filter={\"room\":{\"timeline\":{\"limit\":1}}}"),
+        };
+        let resp = self
+            .client
+            .get(&url)
+            .bearer_auth(&self.access_token)
+            .send()
+            .await
+            .context("send Matrix sync request")?;
+
+        if !resp.status().is_success() {
+            let err = resp.text().await?;
+            anyhow::bail!("Matrix sync failed: {err}");
+        }
+
+        resp.json().await.context("parse Matrix sync response")
+    }
+
+    /// Join invited rooms the auto-join policy allows.
+    async fn accept_invites(&self, sync: &SyncResponse, user_id: &str) {
+        for (room_id, room) in &sync.rooms.invite {
+            let inviter = room
+                .invite_state
+                .events
+                .iter()
+                .find(|event| {
+                    event.event_type == "m.room.member"
+                        && event.state_key.as_deref() == Some(user_id)
+                })
+                .map(|event| event.sender.as_str());
+            if !self.accepts_invite(inviter) {
+                tracing::info!(
+                    "Matrix: leaving invite to {room_id} from {} pending",
+                    inviter.unwrap_or("unknown")
+                );
+                continue;
+            }
+            let resp = self
+                .client
+                .post(self.url(&format!("join/{}", path_segment(room_id))))
+                .bearer_auth(&self.access_token)
+                .json(&serde_json::json!({}))
+                .send()
+                .await;
+            match resp {
+                Ok(resp) if resp.status().is_success() => {
+                    tracing::info!("Matrix: joined {room_id}");
+                }
+                Ok(resp) => tracing::warn!(
+                    "Matrix: joining {room_id} failed: {}",
+                    resp.text().await.unwrap_or_default()
+                ),
+                Err(error) => tracing::warn!("Matrix: joining {room_id} failed: {error}"),
+            }
+        }
+    }
+
+    /// Keep cached encryption and membership state in step with the sync.
+    fn track_room_state(&self, sync: &SyncResponse) {
+        for (room_id, room) in &sync.rooms.join {
+            for event in room.state.events.iter().chain(&room.timeline.events) {
+                match event.event_type.as_str() {
+                    "m.room.encryption" => {
+                        self.room_info(room_id, |info| info.encrypted = Some(true));
+                    }
+                    "m.room.member" => self.room_info(room_id, |info| info.members = None),
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    /// The message for the agent in a room event, if any.
+    async fn inbound(
+        &self,
+        room_id: &str,
+        event: &TimelineEvent,
+        identity: &Identity,
+    ) -> Option<ChannelMessage> {
+        if event.sender == identity.user_id || !self.is_user_allowed(&event.sender) {
+            return None;
+        }
+        if let Some(target) = reaction_target(event) {
+            return self.approval_from_reaction(target, event);
+        }
+
+        let is_direct = self.home_room() != Some(room_id)
+            && self
+                .members(room_id)
+                .await
+                .is_ok_and(|members| members.len() <= 2);
+        message_from_event(
+            room_id,
+            event,
+            &InboundContext {
+                homeserver: &self.homeserver,
+                user_id: &identity.user_id,
+                display_name: identity.display_name.as_deref(),
+                home_room: self.home_room(),
+                is_direct,
+            },
+        )
+    }
+
+    fn approval_from_reaction(
+        &self,
+        target: &str,
+        event: &TimelineEvent,
+    ) -> Option<ChannelMessage> {
+        let (approval_id, recipient) = self
+            .approvals
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get(target)
+            .cloned()?;
+        let content = approval_reply(event, &approval_id)?;
+        let (room_id, thread_root) = parse_reply_address(&recipient);
+        Some(ChannelMessage {
+            id: uuid::Uuid::new_v4().to_string(),
+            sender: recipient.clone(),
+            author: Some(event.sender.clone()),
+            content,
+            channel: "matrix".to_string(),
+            conversation_id: Some(room_id.to_string()),
+            thread_id: thread_root.map(ToString::to_string),
+            reply_to: None,
+            message_id: event.event_id.clone(),
+            timestamp: event.origin_server_ts.unwrap_or_default() / 1000,
+            attachments: Vec::new(),
+        })
     }
 
     #[cfg(test)]
@@ -78,11 +508,17 @@ impl MatrixChannel {
         media::mxc_to_http(&self.homeserver, mxc_url)
     }
 
+    #[cfg(test)]
     fn parse_media_attachments(&self, content: &EventContent) -> Vec<MediaAttachment> {
         media::parse_media_attachments(&self.homeserver, content)
     }
 }
 
+/// Percent-encode an id (room, event, user) for use as one URL path segment.
+fn path_segment(id: &str) -> String {
+    url::form_urlencoded::byte_serialize(id.as_bytes()).collect()
+}
+
 impl Channel for MatrixChannel {
     fn name(&self) -> &str {
         "matrix"
@@ -95,34 +531,104 @@ impl Channel for MatrixChannel {
     fn send<'a>(
         &'a self,
         message: &'a str,
-        _target: &'a str,
+        target: &'a str,
+    ) -> Pin<Box<dyn Future<Output = anyhow::Result<()>> + Send + 'a>> {
+        Box::pin(async move {
+            let (room_id, thread_root) = self.target(target)?;
+            let content = message_content(
+                serde_json::json!({ "msgtype": "m.text", "body": message }),
+                thread_root,
+            );
+            self.send_event(room_id, "m.room.message", content).await?;
+            Ok(())
+        })
+    }
+
+    /// The prompt also offers reactions: ✅ approve, ❌ deny and, when the
+    /// decision can be remembered, 🔁 always.
+    fn send_approval_request<'a>(
+        &'a self,
+        request: &'a ApprovalRequest,
+        recipient: &'a str,
     ) -> Pin<Box<dyn Future<Output = anyhow::Result<()>> + Send + 'a>> {
         Box::pin(async move {
-            let txn_id = format!("zc_{}", chrono::Utc::now().timestamp_millis());
-            let url = format!(
-                "{}/_matrix/client/v3/rooms/{}/send/m.room.message/{}",
-                self.homeserver, self.room_id, txn_id
+            let (room_id, thread_root) = self.target(recipient)?;
+            let reactions: Vec<&str> = APPROVAL_REACTIONS
+                .iter()
+                .filter(|(_, reply)| *reply != "always" || request.can_remember())
+                .map(|(emoji, _)| *emoji)
+                .collect();
+            let text = format!(
+                "{}\nOr react with {}.",
+                request.prompt_text(),
+                reactions.join(" / ")
+            );
+            let content = message_content(
+                serde_json::json!({ "msgtype": "m.text", "body": text }),
+                thread_root,
             );
+            let event_id = self.send_event(room_id, "m.room.message", content).await?;
+            if event_id.is_empty() {
+                return Ok(());
+            }
+            self.approvals
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .insert(
+                    event_id.clone(),
+                    (request.id.clone(), reply_address(room_id, thread_root)),
+                );
+            for key in reactions {
+                if let Err(error) = self
+                    .send_event(room_id, "m.reaction", reaction_content(&event_id, key))
+                    .await
+                {
+                    tracing::debug!("Matrix: failed to add approval reaction: {error}");
+                }
+            }
+            Ok(())
+        })
+    }
 
-            let body = serde_json::json!({
-                "msgtype": "m.text",
-                "body": message
-            });
+    fn edit_message<'a>(
+        &'a self,
+        channel_id: &'a str,
+        message_id: &'a str,
+        content: &'a str,
+    ) -> Pin<Box<dyn Future<Output = anyhow::Result<()>> + Send + 'a>> {
+        Box::pin(async move {
+            let (room_id, _) = self.target(channel_id)?;
+            self.send_event(room_id, "m.room.message", edit_content(message_id, content))
+                .await?;
+            Ok(())
+        })
+    }
 
+    fn delete_message<'a>(
+        &'a self,
+        channel_id: &'a str,
+        message_id: &'a str,
+    ) -> Pin<Box<dyn Future<Output = anyhow::Result<()>> + Send + 'a>> {
+        Box::pin(async move {
+            let (room_id, _) = self.target(channel_id)?;
+            let txn_id = format!("zc_{}", uuid::Uuid::new_v4().simple());
+            let url = self.url(&format!(
+                "rooms/{}/redact/{}/{txn_id}",
+                path_segment(room_id),
+                path_segment(message_id)
+            ));
             let resp = self
                 .client
                 .put(&url)
-                .header("Authorization", format!("Bearer {}", self.access_token))
-                .json(&body)
+                .bearer_auth(&self.access_token)
+                .json(&serde_json::json!({}))
                 .send()
                 .await
-                .context("send Matrix room message")?;
-
+                .context("send Matrix redaction")?;
             if !resp.status().is_success() {
                 let err = resp.text().await?;
-                anyhow::bail!("Matrix send failed: {err}");
+                anyhow::bail!("Matrix redaction failed: {err}");
             }
-
             Ok(())
         })
     }
@@ -132,119 +638,102 @@ impl Channel for MatrixChannel {
         tx: mpsc::Sender<ChannelMessage>,
     ) -> Pin<Box<dyn Future<Output = anyhow::Result<()>> + Send + 'a>> {
         Box::pin(async move {
-            tracing::info!("Matrix channel listening on room {}...", self.room_id);
-
-            let my_user_id = self
-                .get_my_user_id()
-                .await
-                .context("get Matrix user identity")?;
-
-            // Initial sync to get the since token
-            let url = format!(
-                "{}/_matrix/client/v3/sync?timeout=30000&filter={{\"room\":{{\"timeline\":{{\"limit\":1}}}}}}",
-                self.homeserver
+            let identity = self.identity().await.context("get Matrix user identity")?;
+            tracing::info!(
+                "Matrix channel listening as {} (home room: {})...",
+                identity.user_id,
+                self.home_room().unwrap_or("none")
             );
 
-            let resp = self
-                .client
-                .get(&url)
-                .header("Authorization", format!("Bearer {}", self.access_token))
-                .send()
-                .await
-                .context("send Matrix initial sync request")?;
-
-            if !resp.status().is_success() {
-                let err = resp.text().await?;
-                anyhow::bail!("Matrix initial sync failed: {err}");
+            #[cfg(feature = "matrix-e2e")]
+            let e2ee = self.e2ee().await;
+            #[cfg(not(feature = "matrix-e2e"))]
+            if self.store_dir.is_some() {
+                tracing::warn!(
+                    "Matrix: built without the matrix-e2e feature; encrypted rooms stay unreadable"
+                );
             }
+            #[cfg(feature = "matrix-e2e")]
+            let mut undecrypted: Vec<(String, TimelineEvent)> = Vec::new();
 
-            let sync: SyncResponse = resp
-                .json()
-                .await
-                .context("parse Matrix initial sync response")?;
-            let mut since = sync.next_batch;
-
-            // Long-poll loop
+            let mut since: Option<String> = None;
             loop {
-                let url = format!(
-                    "{}/_matrix/client/v3/sync?since={}&timeout=30000",
-                    self.homeserver, since
-                );
-
-                let resp = self
-                    .client
-                    .get(&url)
-                    .header("Authorization", format!("Bearer {}", self.access_token))
-                    .send()
-                    .await;
-
-                let resp = match resp {
-                    Ok(r) => r,
+                let sync = match self.sync(since.as_deref()).await {
+                    Ok(sync) => sync,
+                    Err(e) if since.is_none() => return Err(e),
                     Err(e) => {
-                        tracing::warn!("Matrix sync error: {e}, retrying...");
+                        tracing::warn!("Matrix sync error: {e:#}, retrying...");
                         tokio::time::sleep(tokio::time::Duration::from_secs(5)).await;
                         continue;
                     }
                 };
+                let catching_up = since.is_none();
+                since = Some(sync.next_batch.clone());
+
+                #[cfg(feature = "matrix-e2e")]
+                let received_keys = match e2ee {
+                    Some(e2ee) => e2ee.receive_sync(&sync).await.unwrap_or_else(|error| {
+                        tracing::warn!("Matrix: {error:#}");
+                        false
+                    }),
+                    None => false,
+                };
 
-                if !resp.status().is_success() {
-                    tokio::time::sleep(tokio::time::Duration::from_secs(5)).await;
+                self.accept_invites(&sync, &identity.user_id).await;
+                self.track_room_state(&sync);
+                if catching_up {
                     continue;
                 }
 
-                let sync: SyncResponse = resp.json().await.context("parse Matrix sync response")?;
-                since = sync.next_batch;
-
-                // Process events from our room
-                if let Some(room) = sync.rooms.join.get(&self.room_id) {
+                let mut messages = Vec::new();
+                for (room_id, room) in &sync.rooms.join {
                     for event in &room.timeline.events {
-                        // Skip our own messages
-                        if event.sender == my_user_id {
-                            continue;
-                        }
-
-                        // Only process text and media messages
-                        if event.event_type != "m.room.message" {
-                            continue;
-                        }
-
-                        let msgtype = event.content.msgtype.as_deref().unwrap_or("");
-                        if !matches!(
-                            msgtype,
-                            "m.text" | "m.image" | "m.audio" | "m.video" | "m.file"
-                        ) {
-                            continue;
-                        }
-
-                        if !self.is_user_allowed(&event.sender) {
-                            continue;
+                        if event.event_type == "m.room.encrypted" {
+                            #[cfg(feature = "matrix-e2e")]
+                            if let Some(e2ee) = e2ee {
+                                match e2ee.decrypt(room_id, event).await {
+                                    Ok(decrypted) => {
+                                        messages.extend(
+                                            self.inbound(room_id, &decrypted, identity).await,
+                                        );
+                                    }
+                                    Err(error) => {
+                                        tracing::debug!(
+                                            "Matrix: cannot decrypt yet in {room_id}: {error}"
+                                        );
+                                        if undecrypted.len() >= MAX_UNDECRYPTED_EVENTS {
+                                            undecrypted.remove(0);
+                                        }
+                                        undecrypted.push((room_id.clone(), event.clone()));
+                                    }
+                                }
+                            }
+                        } else {
+                            messages.extend(self.inbound(room_id, event, identity).await);
                         }
+                    }
+                }
 
-                        let attachments = self.parse_media_attachments(&event.content);
-                        let body = event.content.body.clone().unwrap_or_default();
-                        if body.is_empty() && attachments.is_empty() {
-                            continue;
+                #[cfg(feature = "matrix-e2e")]
+                if let Some(e2ee) = e2ee
+                    && received_keys
+                    && !undecrypted.is_empty()
+                {
+                    let mut still_pending = Vec::new();
+                    for (room_id, event) in undecrypted.drain(..) {
+                        match e2ee.decrypt(&room_id, &event).await {
+                            Ok(decrypted) => {
+                                messages.extend(self.inbound(&room_id, &decrypted, identity).await);
+                            }
+                            Err(_) => still_pending.push((room_id, event)),
                         }
+                    }
+                    undecrypted = still_pending;
+                }
 
-                        let msg = ChannelMessage {
-                            id: format!("mx_{}", chrono::Utc::now().timestamp_millis()),
-                            sender: event.sender.clone(),
-                            content: body,
-                            channel: "matrix".to_string(),
-                            conversation_id: None,
-                            thread_id: None,
-                            reply_to: None,
-                            message_id: None,
-                            timestamp: std::time::SystemTime::now()
-                                .duration_since(std::time::UNIX_EPOCH)
-                                .unwrap_or_default()
-                                .as_secs(),
-                            attachments,
-                        };
-
-                        if tx.send(msg).await.is_err() {
-                            return Ok(());
-                        }
+                for msg in messages {
+                    if tx.send(msg).await.is_err() {
+                        return Ok(());
                     }
                 }
             }
@@ -252,28 +741,36 @@ impl Channel for MatrixChannel {
     }
 
     fn health_check<'a>(&'a self) -> Pin<Box<dyn Future<Output = bool> + Send + 'a>> {
-        Box::pin(async move {
-            let url = format!("{}/_matrix/client/v3/account/whoami", self.homeserver);
-            let Ok(resp) = self
-                .client
-                .get(&url)
-                .header("Authorization", format!("Bearer {}", self.access_token))
-                .send()
-                .await
-            else {
-                return false;
-            };
+        Box::pin(async move { self.whoami().await.is_ok() })
+    }
 
-            resp.status().is_success()
+    /// Homeserver media needs the access token; it is not sent elsewhere.
+    fn fetch_attachment<'a>(
+        &'a self,
+        attachment: &'a MediaAttachment,
+        max_bytes: usize,
+    ) -> Pin<Box<dyn Future<Output = anyhow::Result<Vec<u8>>> + Send + 'a>> {
+        Box::pin(async move {
+            match &attachment.data {
+                MediaData::Url(url) if url.starts_with(&format!("{}/", self.homeserver)) => {
+                    download_capped(
+                        self.client.get(url).bearer_auth(&self.access_token),
+                        max_bytes,
+                    )
+                    .await
+                }
+                _ => load_attachment_bytes(attachment, max_bytes).await,
+            }
         })
     }
 
     fn send_media<'a>(
         &'a self,
         attachment: &'a MediaAttachment,
-        _recipient: &'a str,
+        recipient: &'a str,
     ) -> Pin<Box<dyn Future<Output = anyhow::Result<()>> + Send + 'a>> {
         Box::pin(async move {
+            let (room_id, thread_root) = self.target(recipient)?;
             let bytes = match &attachment.data {
                 MediaData::Url(media_url) => self
                     .client
@@ -292,7 +789,7 @@ impl Channel for MatrixChannel {
             let upload_resp = self
                 .client
                 .post(&upload_url)
-                .header("Authorization", format!("Bearer {}", self.access_token))
+                .bearer_auth(&self.access_token)
                 .header("Content-Type", attachment.mime_type.clone())
                 .body(bytes.clone())
                 .send()
@@ -304,22 +801,14 @@ impl Channel for MatrixChannel {
                 anyhow::bail!("Matrix media upload failed: {err}");
             }
 
-            let upload_data: serde_json::Value = upload_resp
+            let upload_data: Value = upload_resp
                 .json()
                 .await
                 .context("parse Matrix upload response")?;
-            let Some(content_uri) = upload_data
-                .get("content_uri")
-                .and_then(serde_json::Value::as_str)
-            else {
+            let Some(content_uri) = upload_data.get("content_uri").and_then(Value::as_str) else {
                 anyhow::bail!("Matrix upload response missing content_uri");
             };
 
-            let txn_id = format!("zc_{}", chrono::Utc::now().timestamp_millis());
-            let send_url = format!(
-                "{}/_matrix/client/v3/rooms/{}/send/m.room.message/{}",
-                self.homeserver, self.room_id, txn_id
-            );
             let filename = attachment
                 .filename
                 .clone()
@@ -334,30 +823,21 @@ impl Channel for MatrixChannel {
                 "m.file"
             };
 
-            let body = serde_json::json!({
-                "msgtype": msgtype,
-                "body": filename,
-                "url": content_uri,
-                "info": {
-                    "mimetype": attachment.mime_type,
-                    "size": bytes.len()
-                }
-            });
-
-            let send_resp = self
-                .client
-                .put(&send_url)
-                .header("Authorization", format!("Bearer {}", self.access_token))
-                .json(&body)
-                .send()
+            let content = message_content(
+                serde_json::json!({
+                    "msgtype": msgtype,
+                    "body": filename,
+                    "url": content_uri,
+                    "info": {
+                        "mimetype": attachment.mime_type,
+                        "size": bytes.len()
+                    }
+                }),
+                thread_root,
+            );
+            self.send_event(room_id, "m.room.message", content)
                 .await
                 .context("send Matrix media message")?;
-
-            if !send_resp.status().is_success() {
-                let err = send_resp.text().await?;
-                anyhow::bail!("Matrix send media failed: {err}");
-            }
-
             Ok(())
         })
     }
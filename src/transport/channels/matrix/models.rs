@@ -1,22 +1,43 @@
 use serde::Deserialize;
+use serde_json::Value;
 
 #[derive(Debug, Deserialize)]
+#[cfg_attr(not(feature = "matrix-e2e"), allow(dead_code))]
 pub(super) struct SyncResponse {
     pub(super) next_batch: String,
     #[serde(default)]
     pub(super) rooms: Rooms,
+    /// To-device events (Olm-encrypted room keys, key requests).
+    #[serde(default)]
+    pub(super) to_device: EventList,
+    #[serde(default)]
+    pub(super) device_lists: Option<Value>,
+    #[serde(default)]
+    pub(super) device_one_time_keys_count: Option<Value>,
+    #[serde(default)]
+    pub(super) device_unused_fallback_key_types: Option<Value>,
 }
 
 #[derive(Debug, Deserialize, Default)]
 pub(super) struct Rooms {
     #[serde(default)]
     pub(super) join: std::collections::HashMap<String, JoinedRoom>,
+    #[serde(default)]
+    pub(super) invite: std::collections::HashMap<String, InvitedRoom>,
 }
 
 #[derive(Debug, Deserialize)]
 pub(super) struct JoinedRoom {
     #[serde(default)]
     pub(super) timeline: Timeline,
+    #[serde(default)]
+    pub(super) state: Timeline,
+}
+
+#[derive(Debug, Deserialize)]
+pub(super) struct InvitedRoom {
+    #[serde(default)]
+    pub(super) invite_state: Timeline,
 }
 
 #[derive(Debug, Deserialize, Default)]
@@ -25,16 +46,61 @@ pub(super) struct Timeline {
     pub(super) events: Vec<TimelineEvent>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Default)]
+#[cfg_attr(not(feature = "matrix-e2e"), allow(dead_code))]
+pub(super) struct EventList {
+    #[serde(default)]
+    pub(super) events: Vec<Value>,
+}
+
+/// A room event. The original JSON is kept in `raw` so encrypted events can
+/// be handed to the Olm machine unchanged.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(try_from = "Value")]
+#[cfg_attr(not(feature = "matrix-e2e"), allow(dead_code))]
 pub(super) struct TimelineEvent {
-    #[serde(rename = "type")]
     pub(super) event_type: String,
     pub(super) sender: String,
-    #[serde(default)]
     pub(super) content: EventContent,
+    pub(super) event_id: Option<String>,
+    pub(super) origin_server_ts: Option<u64>,
+    pub(super) state_key: Option<String>,
+    pub(super) raw: Value,
 }
 
-#[derive(Debug, Deserialize, Default)]
+impl TryFrom<Value> for TimelineEvent {
+    type Error = serde_json::Error;
+
+    fn try_from(raw: Value) -> Result<Self, Self::Error> {
+        #[derive(Deserialize)]
+        struct Fields {
+            #[serde(rename = "type")]
+            event_type: String,
+            sender: String,
+            #[serde(default)]
+            content: EventContent,
+            #[serde(default)]
+            event_id: Option<String>,
+            #[serde(default)]
+            origin_server_ts: Option<u64>,
+            #[serde(default)]
+            state_key: Option<String>,
+        }
+
+        let fields = Fields::deserialize(&raw)?;
+        Ok(Self {
+            event_type: fields.event_type,
+            sender: fields.sender,
+            content: fields.content,
+            event_id: fields.event_id,
+            origin_server_ts: fields.origin_server_ts,
+            state_key: fields.state_key,
+            raw,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Default)]
 pub(super) struct EventContent {
     #[serde(default)]
     pub(super) body: Option<String>,
@@ -44,15 +110,43 @@ pub(super) struct EventContent {
     pub(super) url: Option<String>,
     #[serde(default)]
     pub(super) info: Option<EventContentInfo>,
+    #[serde(default)]
+    pub(super) formatted_body: Option<String>,
+    #[serde(default, rename = "m.relates_to")]
+    pub(super) relates_to: Option<Relation>,
+    #[serde(default, rename = "m.mentions")]
+    pub(super) mentions: Option<Mentions>,
 }
 
-#[derive(Debug, Deserialize, Default)]
+#[derive(Debug, Clone, Deserialize, Default)]
 pub(super) struct EventContentInfo {
     #[serde(default)]
     pub(super) mimetype: Option<String>,
 }
 
+/// `m.relates_to`: threads (`m.thread`), edits (`m.replace`) and reactions
+/// (`m.annotation`).
+#[derive(Debug, Clone, Deserialize, Default)]
+pub(super) struct Relation {
+    #[serde(default)]
+    pub(super) rel_type: Option<String>,
+    #[serde(default)]
+    pub(super) event_id: Option<String>,
+    /// Reaction key, e.g. `✅`.
+    #[serde(default)]
+    pub(super) key: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, Default)]
+pub(super) struct Mentions {
+    #[serde(default)]
+    pub(super) user_ids: Vec<String>,
+}
+
 #[derive(Debug, Deserialize)]
 pub(super) struct WhoAmIResponse {
     pub(super) user_id: String,
+    /// Absent for access tokens not bound to a device (appservices).
+    #[serde(default)]
+    pub(super) device_id: Option<String>,
 }
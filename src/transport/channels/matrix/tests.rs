@@ -2,6 +2,7 @@ use crate::transport::channels::traits::{Channel, MediaData};
 
 use super::MatrixChannel;
 use super::models::{EventContent, EventContentInfo, SyncResponse, TimelineEvent, WhoAmIResponse};
+use crate::config::MatrixAutoJoin;
 
 fn make_channel() -> MatrixChannel {
     MatrixChannel::new(
@@ -102,12 +103,84 @@ fn empty_allowlist_denies_all() {
     assert!(!ch.is_user_allowed("@anyone:matrix.org"));
 }
 
+#[test]
+fn invites_are_joined_only_from_listed_inviters_by_default() {
+    let ch = make_channel();
+    assert!(ch.accepts_invite(Some("@USER:matrix.org")));
+    assert!(!ch.accepts_invite(Some("@stranger:matrix.org")));
+    assert!(!ch.accepts_invite(None));
+
+    let open = MatrixChannel::new(
+        "https://matrix.org".to_string(),
+        "tok".to_string(),
+        "!r:m".to_string(),
+        vec!["*".to_string()],
+    );
+    assert!(open.is_user_allowed("@stranger:matrix.org"));
+    assert!(!open.accepts_invite(Some("@stranger:matrix.org")));
+    assert!(
+        open.with_auto_join(MatrixAutoJoin::All)
+            .accepts_invite(Some("@stranger:matrix.org"))
+    );
+}
+
 #[test]
 fn name_returns_matrix() {
     let ch = make_channel();
     assert_eq!(ch.name(), "matrix");
 }
 
+#[test]
+fn send_targets_rooms_threads_and_falls_back_to_home_room() {
+    let ch = make_channel();
+    assert_eq!(
+        ch.target("!other:matrix.org/$root").unwrap(),
+        ("!other:matrix.org", Some("$root"))
+    );
+    assert_eq!(ch.target("").unwrap(), ("!room:matrix.org", None));
+    assert_eq!(
+        ch.target("@user:matrix.org").unwrap(),
+        ("!room:matrix.org", None)
+    );
+
+    let no_home = MatrixChannel::new(
+        "https://matrix.org".to_string(),
+        "tok".to_string(),
+        String::new(),
+        vec![],
+    );
+    assert!(no_home.target("").is_err());
+}
+
+#[test]
+fn sync_response_deserializes_invites_and_to_device() {
+    let json = r#"{
+        "next_batch": "s2",
+        "rooms": {
+            "invite": {
+                "!new:matrix.org": {
+                    "invite_state": {
+                        "events": [{
+                            "type": "m.room.member",
+                            "sender": "@user:matrix.org",
+                            "state_key": "@bot:matrix.org",
+                            "content": {"membership": "invite"}
+                        }]
+                    }
+                }
+            }
+        },
+        "to_device": {"events": [{"type": "m.room_key", "sender": "@user:matrix.org", "content": {}}]}
+    }"#;
+    let resp: SyncResponse = serde_json::from_str(json).unwrap();
+    let invite = &resp.rooms.invite["!new:matrix.org"];
+    assert_eq!(
+        invite.invite_state.events[0].state_key.as_deref(),
+        Some("@bot:matrix.org")
+    );
+    assert_eq!(resp.to_device.events.len(), 1);
+}
+
 #[test]
 fn sync_response_deserializes_empty() {
     let json = r#"{"next_batch":"s123","rooms":{"join":{}}}"#;
@@ -208,7 +281,7 @@ fn mxc_to_http_converts_valid_mxc_url() {
     let http = ch.mxc_to_http("mxc://matrix.org/abc123");
     assert_eq!(
         http.as_deref(),
-        Some("https://matrix.org/_matrix/media/v3/download/matrix.org/abc123")
+        Some("https://matrix.org/_matrix/client/v1/media/download/matrix.org/abc123")
     );
 }
 
@@ -228,6 +301,7 @@ fn parse_media_attachments_for_image_event() {
         info: Some(EventContentInfo {
             mimetype: Some("image/png".to_string()),
         }),
+        ..EventContent::default()
     };
 
     let attachments = ch.parse_media_attachments(&content);
@@ -250,6 +324,7 @@ fn parse_media_attachments_for_file_event() {
         info: Some(EventContentInfo {
             mimetype: Some("application/pdf".to_string()),
         }),
+        ..EventContent::default()
     };
 
     let attachments = ch.parse_media_attachments(&content);
@@ -266,6 +341,7 @@ fn parse_media_attachments_text_event_has_no_attachments() {
         msgtype: Some("m.text".to_string()),
         url: None,
         info: None,
+        ..EventContent::default()
     };
 
     let attachments = ch.parse_media_attachments(&content);
@@ -283,6 +359,7 @@ fn parse_media_attachments_requires_url_for_media_msgtypes() {
         info: Some(EventContentInfo {
             mimetype: Some("video/mp4".to_string()),
         }),
+        ..EventContent::default()
     };
 
     assert!(ch.parse_media_attachments(&content).is_empty());
@@ -296,6 +373,7 @@ fn parse_media_attachments_defaults_mime_type() {
         msgtype: Some("m.audio".to_string()),
         url: Some("mxc://matrix.org/audio999".to_string()),
         info: None,
+        ..EventContent::default()
     };
 
     let attachments = ch.parse_media_attachments(&content);
@@ -332,3 +410,33 @@ fn event_content_deserializes_media_fields() {
         Some("image/png")
     );
 }
+
+#[tokio::test]
+async fn encryption_state_errors_fail_closed_and_are_not_cached() {
+    use wiremock::matchers::{method, path_regex};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path_regex(
+            "/rooms/[^/]*flaky[^/]*/state/m.room.encryption/$",
+        ))
+        .respond_with(ResponseTemplate::new(502))
+        .expect(2)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path_regex(
+            "/rooms/[^/]*plain[^/]*/state/m.room.encryption/$",
+        ))
+        .respond_with(ResponseTemplate::new(404))
+        .expect(1)
+        .mount(&server)
+        .await;
+    let ch = MatrixChannel::new(server.uri(), "tok".to_string(), "!r:m".to_string(), vec![]);
+
+    assert!(ch.is_encrypted("!flaky:m").await.is_err());
+    assert!(ch.is_encrypted("!flaky:m").await.is_err());
+    assert!(!ch.is_encrypted("!plain:m").await.unwrap());
+    assert!(!ch.is_encrypted("!plain:m").await.unwrap());
+}
@@ -0,0 +1,612 @@
+use super::events::{
+    Inbound, InboundContext, PostFile, authentication_challenge, message_from_event,
+    parse_reply_address, websocket_url,
+};
+use crate::transport::channels::policy::{AllowlistMatch, is_allowed_user};
+use crate::transport::channels::traits::{Channel, ChannelMessage, MediaAttachment, MediaData};
+use anyhow::Context;
+use futures_util::{SinkExt, StreamExt};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Mutex, PoisonError};
+use tokio_tungstenite::tungstenite::Message;
+
+/// Inbound files larger than this are left out of the message.
+const MAX_INBOUND_FILE_BYTES: u64 = 20 * 1024 * 1024;
+
+/// The bot account the token belongs to.
+#[derive(Debug, Clone)]
+struct BotUser {
+    id: String,
+    username: String,
+}
+
+/// Mattermost channel — receives posts over the server's WebSocket and
+/// answers through the REST API (v4).
+pub struct MattermostChannel {
+    base_url: String,
+    bot_token: String,
+    channel_id: Option<String>,
+    allowed_users: Vec<String>,
+    client: reqwest::Client,
+    bot_user: Mutex<Option<BotUser>>,
+    /// Usernames by user ID, from `GET /users/{id}`.
+    usernames: Mutex<HashMap<String, String>>,
+}
+
+impl MattermostChannel {
+    pub fn new(
+        mut url: String,
+        bot_token: String,
+        channel_id: Option<String>,
+        allowed_users: Vec<String>,
+    ) -> Self {
+        url.truncate(url.trim_end_matches('/').len());
+        Self {
+            base_url: url,
+            bot_token,
+            channel_id: channel_id.filter(|id| !id.trim().is_empty()),
+            allowed_users,
+            client: reqwest::Client::new(),
+            bot_user: Mutex::new(None),
+            usernames: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn api_url(&self, path: &str) -> String {
+        format!("{}/api/v4/{path}", self.base_url)
+    }
+
+    /// Check a Mattermost username or user ID against the allowlist.
+    /// Empty list means deny everyone until explicitly configured.
+    /// `"*"` means allow everyone.
+    fn is_user_allowed(&self, user: &str) -> bool {
+        is_allowed_user(&self.allowed_users, user, AllowlistMatch::Exact)
+    }
+
+    /// The bot's own account, so its posts and mentions can be told apart.
+    /// Looked up once via `GET /users/me`.
+    async fn bot_user(&self) -> anyhow::Result<BotUser> {
+        if let Some(user) = self
+            .bot_user
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+        {
+            return Ok(user);
+        }
+        let me: Value = self
+            .client
+            .get(self.api_url("users/me"))
+            .bearer_auth(&self.bot_token)
+            .send()
+            .await
+            .context("send Mattermost users/me request")?
+            .error_for_status()
+            .context("Mattermost users/me failed")?
+            .json()
+            .await
+            .context("parse Mattermost users/me response")?;
+        let field = |name: &str| {
+            me.get(name)
+                .and_then(Value::as_str)
+                .map(ToString::to_string)
+                .unwrap_or_default()
+        };
+        let user = BotUser {
+            id: field("id"),
+            username: field("username"),
+        };
+        *self.bot_user.lock().unwrap_or_else(PoisonError::into_inner) = Some(user.clone());
+        Ok(user)
+    }
+
+    /// The account username for `user_id`, looked up once via
+    /// `GET /users/{id}` and cached. The event's `sender_name` is not used:
+    /// webhook and bot posts can set it to anything.
+    async fn username(&self, user_id: &str) -> anyhow::Result<String> {
+        if let Some(name) = self
+            .usernames
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get(user_id)
+        {
+            return Ok(name.clone());
+        }
+        let user: Value = self
+            .client
+            .get(self.api_url(&format!("users/{user_id}")))
+            .bearer_auth(&self.bot_token)
+            .send()
+            .await
+            .context("send Mattermost users request")?
+            .error_for_status()
+            .context("Mattermost users lookup failed")?
+            .json()
+            .await
+            .context("parse Mattermost users response")?;
+        let name = user
+            .get("username")
+            .and_then(Value::as_str)
+            .context("Mattermost user has no username")?
+            .to_string();
+        self.usernames
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(user_id.to_string(), name.clone());
+        Ok(name)
+    }
+
+    /// Whether the post's author is allowlisted, by user ID or by the
+    /// username of that account.
+    async fn is_author_allowed(&self, user_id: &str) -> bool {
+        if self.is_user_allowed(user_id) {
+            return true;
+        }
+        match self.username(user_id).await {
+            Ok(name) => {
+                let allowed = self.is_user_allowed(&name);
+                if !allowed {
+                    tracing::warn!(
+                        "Mattermost: ignoring post from unauthorized user: username={name}, user_id={user_id}. \
+ Allowlist the Mattermost username or user ID, then run `asteroniris onboard --channels-only`."
+                    );
+                }
+                allowed
+            }
+            Err(error) => {
+                tracing::warn!(
+                    "Mattermost: ignoring post from user_id={user_id}; username lookup failed: {error:#}"
+                );
+                false
+            }
+        }
+    }
+
+    /// The message for a WebSocket event, if it is a post for the bot from
+    /// an allowed user. Attached files are downloaded with the bot token.
+    async fn inbound_from_event(&self, event: &Value, bot: &BotUser) -> Option<ChannelMessage> {
+        let inbound = message_from_event(
+            event,
+            &InboundContext {
+                bot_user_id: &bot.id,
+                bot_username: &bot.username,
+                home_channel: self.channel_id.as_deref(),
+            },
+        )?;
+        let Inbound {
+            user_id,
+            files,
+            mut message,
+        } = inbound;
+
+        if !self.is_author_allowed(&user_id).await {
+            return None;
+        }
+
+        for file in &files {
+            match self.download_file(file).await {
+                Ok(Some(attachment)) => message.attachments.push(attachment),
+                Ok(None) => {}
+                Err(error) => tracing::warn!("Mattermost: failed to download file: {error:#}"),
+            }
+        }
+        if message.content.is_empty() && message.attachments.is_empty() {
+            return None;
+        }
+        Some(message)
+    }
+
+    async fn download_file(&self, file: &PostFile) -> anyhow::Result<Option<MediaAttachment>> {
+        if file.size > MAX_INBOUND_FILE_BYTES {
+            tracing::debug!(
+                file = file.id,
+                size = file.size,
+                "Mattermost: skipping large file"
+            );
+            return Ok(None);
+        }
+        let bytes = self
+            .client
+            .get(self.api_url(&format!("files/{}", file.id)))
+            .bearer_auth(&self.bot_token)
+            .send()
+            .await
+            .context("send Mattermost file request")?
+            .error_for_status()
+            .context("Mattermost file download failed")?
+            .bytes()
+            .await
+            .context("read Mattermost file bytes")?;
+        Ok(Some(MediaAttachment {
+            mime_type: file
+                .mime_type
+                .clone()
+                .unwrap_or_else(|| "application/octet-stream".to_string()),
+            data: MediaData::Bytes(bytes.to_vec()),
+            filename: file.name.clone(),
+        }))
+    }
+
+    /// Receive events over one WebSocket connection until it closes.
+    /// Returns `Ok` once the receiver is gone; a dropped connection is an
+    /// error so the supervisor reconnects with backoff.
+    async fn listen_websocket(
+        &self,
+        tx: &tokio::sync::mpsc::Sender<ChannelMessage>,
+    ) -> anyhow::Result<()> {
+        let bot = self.bot_user().await?;
+        let (ws, _) = tokio_tungstenite::connect_async(websocket_url(&self.base_url))
+            .await
+            .context("connect to Mattermost WebSocket")?;
+        let (mut write, mut read) = ws.split();
+        write
+            .send(Message::Text(
+                authentication_challenge(&self.bot_token).into(),
+            ))
+            .await
+            .context("authenticate Mattermost WebSocket")?;
+        tracing::info!("Mattermost WebSocket connected as @{}", bot.username);
+
+        while let Some(frame) = read.next().await {
+            let text = match frame.context("read Mattermost WebSocket frame")? {
+                Message::Text(text) => text,
+                Message::Ping(data) => {
+                    write.send(Message::Pong(data)).await?;
+                    continue;
+                }
+                Message::Close(_) => break,
+                _ => continue,
+            };
+            let Ok(event) = serde_json::from_str::<Value>(&text) else {
+                continue;
+            };
+            if event.get("status").and_then(Value::as_str) == Some("FAIL") {
+                anyhow::bail!("Mattermost WebSocket authentication failed: {event}");
+            }
+            if let Some(msg) = self.inbound_from_event(&event, &bot).await
+                && tx.send(msg).await.is_err()
+            {
+                return Ok(());
+            }
+        }
+        anyhow::bail!("Mattermost WebSocket closed")
+    }
+
+    /// `POST /posts`, returning the new post's ID.
+    async fn create_post(&self, mut body: Value, recipient: &str) -> anyhow::Result<String> {
+        let (channel_id, root_id) = parse_reply_address(recipient);
+        body["channel_id"] = Value::String(channel_id.to_string());
+        if let Some(root_id) = root_id {
+            body["root_id"] = Value::String(root_id.to_string());
+        }
+
+        let resp = self
+            .client
+            .post(self.api_url("posts"))
+            .bearer_auth(&self.bot_token)
+            .json(&body)
+            .send()
+            .await
+            .context("send Mattermost create post request")?;
+        let post: Value = Self::checked_json(resp, "create post").await?;
+        post.get("id")
+            .and_then(Value::as_str)
+            .map(ToString::to_string)
+            .ok_or_else(|| anyhow::anyhow!("Mattermost create post returned no id"))
+    }
+
+    async fn checked_json(resp: reqwest::Response, action: &str) -> anyhow::Result<Value> {
+        let status = resp.status();
+        let body = resp
+            .text()
+            .await
+            .unwrap_or_else(|e| format!("<failed to read response body: {e}>"));
+        if !status.is_success() {
+            anyhow::bail!("Mattermost {action} failed ({status}): {body}");
+        }
+        Ok(serde_json::from_str(&body).unwrap_or_default())
+    }
+}
+
+impl Channel for MattermostChannel {
+    fn name(&self) -> &str {
+        "mattermost"
+    }
+
+    fn max_message_length(&self) -> usize {
+        // Servers on older database schemas cap posts at 4000 characters.
+        4000
+    }
+
+    fn send<'a>(
+        &'a self,
+        message: &'a str,
+        recipient: &'a str,
+    ) -> Pin<Box<dyn Future<Output = anyhow::Result<()>> + Send + 'a>> {
+        Box::pin(async move {
+            self.create_post(serde_json::json!({ "message": message }), recipient)
+                .await
+                .map(|_| ())
+        })
+    }
+
+    fn send_draft<'a>(
+        &'a self,
+        message: &'a str,
+        recipient: &'a str,
+    ) -> Pin<Box<dyn Future<Output = anyhow::Result<Option<String>>> + Send + 'a>> {
+        Box::pin(async move {
+            self.create_post(serde_json::json!({ "message": message }), recipient)
+                .await
+                .map(Some)
+        })
+    }
+
+    fn edit_message<'a>(
+        &'a self,
+        _channel_id: &'a str,
+        message_id: &'a str,
+        content: &'a str,
+    ) -> Pin<Box<dyn Future<Output = anyhow::Result<()>> + Send + 'a>> {
+        Box::pin(async move {
+            let resp = self
+                .client
+                .put(self.api_url(&format!("posts/{message_id}/patch")))
+                .bearer_auth(&self.bot_token)
+                .json(&serde_json::json!({ "message": content }))
+                .send()
+                .await
+                .context("send Mattermost patch post request")?;
+            Self::checked_json(resp, "patch post").await.map(|_| ())
+        })
+    }
+
+    fn delete_message<'a>(
+        &'a self,
+        _channel_id: &'a str,
+        message_id: &'a str,
+    ) -> Pin<Box<dyn Future<Output = anyhow::Result<()>> + Send + 'a>> {
+        Box::pin(async move {
+            let resp = self
+                .client
+                .delete(self.api_url(&format!("posts/{message_id}")))
+                .bearer_auth(&self.bot_token)
+                .send()
+                .await
+                .context("send Mattermost delete post request")?;
+            Self::checked_json(resp, "delete post").await.map(|_| ())
+        })
+    }
+
+    fn send_typing<'a>(
+        &'a self,
+        recipient: &'a str,
+    ) -> Pin<Box<dyn Future<Output = anyhow::Result<()>> + Send + 'a>> {
+        Box::pin(async move {
+            let bot = self.bot_user().await?;
+            let (channel_id, root_id) = parse_reply_address(recipient);
+            let resp = self
+                .client
+                .post(self.api_url(&format!("users/{}/typing", bot.id)))
+                .bearer_auth(&self.bot_token)
+                .json(&serde_json::json!({
+                    "channel_id": channel_id,
+                    "parent_id": root_id.unwrap_or(""),
+                }))
+                .send()
+                .await
+                .context("send Mattermost typing request")?;
+            Self::checked_json(resp, "typing").await.map(|_| ())
+        })
+    }
+
+    fn listen<'a>(
+        &'a self,
+        tx: tokio::sync::mpsc::Sender<ChannelMessage>,
+    ) -> Pin<Box<dyn Future<Output = anyhow::Result<()>> + Send + 'a>> {
+        Box::pin(async move { self.listen_websocket(&tx).await })
+    }
+
+    fn health_check<'a>(&'a self) -> Pin<Box<dyn Future<Output = bool> + Send + 'a>> {
+        Box::pin(async move {
+            self.client
+                .get(self.api_url("users/me"))
+                .bearer_auth(&self.bot_token)
+                .send()
+                .await
+                .is_ok_and(|r| r.status().is_success())
+        })
+    }
+
+    fn send_media<'a>(
+        &'a self,
+        attachment: &'a MediaAttachment,
+        recipient: &'a str,
+    ) -> Pin<Box<dyn Future<Output = anyhow::Result<()>> + Send + 'a>> {
+        Box::pin(async move {
+            let bytes = match &attachment.data {
+                MediaData::Url(media_url) => self
+                    .client
+                    .get(media_url)
+                    .send()
+                    .await
+                    .context("download media before Mattermost upload")?
+                    .bytes()
+                    .await
+                    .context("read media bytes")?
+                    .to_vec(),
+                MediaData::Bytes(raw_bytes) => raw_bytes.clone(),
+            };
+
+            let filename = attachment
+                .filename
+                .clone()
+                .unwrap_or_else(|| "attachment".to_string());
+            let file_part = reqwest::multipart::Part::bytes(bytes)
+                .file_name(filename)
+                .mime_str(&attachment.mime_type)
+                .context("invalid attachment MIME type")?;
+            let (channel_id, _) = parse_reply_address(recipient);
+            let form = reqwest::multipart::Form::new()
+                .text("channel_id", channel_id.to_string())
+                .part("files", file_part);
+
+            let resp = self
+                .client
+                .post(self.api_url("files"))
+                .bearer_auth(&self.bot_token)
+                .multipart(form)
+                .send()
+                .await
+                .context("send Mattermost file upload request")?;
+            let uploaded = Self::checked_json(resp, "file upload").await?;
+            let file_ids: Vec<&str> = uploaded
+                .get("file_infos")
+                .and_then(Value::as_array)
+                .map(|infos| {
+                    infos
+                        .iter()
+                        .filter_map(|info| info.get("id").and_then(Value::as_str))
+                        .collect()
+                })
+                .unwrap_or_default();
+            if file_ids.is_empty() {
+                anyhow::bail!("Mattermost file upload returned no file ids");
+            }
+
+            self.create_post(
+                serde_json::json!({ "message": "", "file_ids": file_ids }),
+                recipient,
+            )
+            .await
+            .map(|_| ())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn channel(allowed_users: Vec<String>) -> MattermostChannel {
+        MattermostChannel::new(
+            "http://127.0.0.1:9/".into(),
+            "token".into(),
+            Some("home".into()),
+            allowed_users,
+        )
+    }
+
+    fn bot() -> BotUser {
+        BotUser {
+            id: "botid".into(),
+            username: "asteroniris".into(),
+        }
+    }
+
+    fn dm_event(user_id: &str, sender_name: &str) -> Value {
+        let post = serde_json::json!({
+            "id": "post1",
+            "user_id": user_id,
+            "channel_id": "dm",
+            "root_id": "",
+            "message": "hello",
+            "type": "",
+        });
+        serde_json::json!({
+            "event": "posted",
+            "data": {
+                "channel_type": "D",
+                "sender_name": sender_name,
+                "post": post.to_string(),
+            }
+        })
+    }
+
+    #[test]
+    fn mattermost_channel_name_and_api_url() {
+        let ch = channel(vec![]);
+        assert_eq!(ch.name(), "mattermost");
+        assert_eq!(ch.api_url("users/me"), "http://127.0.0.1:9/api/v4/users/me");
+    }
+
+    #[test]
+    fn blank_channel_id_means_no_home_channel() {
+        let ch = MattermostChannel::new("http://mm".into(), "t".into(), Some(" ".into()), vec![]);
+        assert!(ch.channel_id.is_none());
+    }
+
+    #[test]
+    fn empty_allowlist_denies_everyone() {
+        let ch = channel(vec![]);
+        assert!(!ch.is_user_allowed("alice"));
+        assert!(channel(vec!["*".into()]).is_user_allowed("alice"));
+    }
+
+    fn with_username(ch: MattermostChannel, user_id: &str, name: &str) -> MattermostChannel {
+        ch.usernames
+            .lock()
+            .unwrap()
+            .insert(user_id.into(), name.into());
+        ch
+    }
+
+    #[tokio::test]
+    async fn posts_are_allowed_by_username_or_user_id() {
+        let by_name = with_username(channel(vec!["alice".into()]), "user1", "alice");
+        assert!(
+            by_name
+                .inbound_from_event(&dm_event("user1", "@alice"), &bot())
+                .await
+                .is_some()
+        );
+        let by_id = channel(vec!["user1".into()]);
+        assert!(
+            by_id
+                .inbound_from_event(&dm_event("user1", "@alice"), &bot())
+                .await
+                .is_some()
+        );
+        let by_name = with_username(by_name, "user2", "mallory");
+        assert!(
+            by_name
+                .inbound_from_event(&dm_event("user2", "@mallory"), &bot())
+                .await
+                .is_none()
+        );
+    }
+
+    #[tokio::test]
+    async fn sender_name_cannot_impersonate_an_allowlisted_user() {
+        let ch = with_username(channel(vec!["alice".into()]), "user2", "mallory");
+        assert!(
+            ch.inbound_from_event(&dm_event("user2", "@alice"), &bot())
+                .await
+                .is_none()
+        );
+        // Without a username from the server, only the user ID counts.
+        assert!(
+            ch.inbound_from_event(&dm_event("user3", "@alice"), &bot())
+                .await
+                .is_none()
+        );
+    }
+
+    #[tokio::test]
+    async fn send_media_uses_files_endpoint() {
+        let ch = channel(vec!["*".into()]);
+        let attachment = MediaAttachment {
+            mime_type: "text/plain".to_string(),
+            data: MediaData::Bytes(b"hello".to_vec()),
+            filename: Some("note.txt".to_string()),
+        };
+        let err = ch
+            .send_media(&attachment, "chan:root")
+            .await
+            .expect_err("network failure expected");
+        assert!(format!("{err:#}").contains("Mattermost file upload"));
+    }
+}
@@ -0,0 +1,327 @@
+//! Mattermost WebSocket protocol: the connection URL, the authentication
+//! challenge, and turning `posted` events into [`ChannelMessage`]s.
+//! See: <https://developers.mattermost.com/api-documentation/#/#websocket-events>
+
+use crate::transport::channels::traits::ChannelMessage;
+use serde_json::Value;
+use uuid::Uuid;
+
+/// What the listener knows when deciding whether a post is for the bot.
+pub(super) struct InboundContext<'a> {
+    pub bot_user_id: &'a str,
+    pub bot_username: &'a str,
+    /// The configured `channel_id`, where every post is answered.
+    pub home_channel: Option<&'a str>,
+}
+
+/// A file attached to a post. Downloading it needs the bot token, so the
+/// channel fetches it before handing the message on.
+#[derive(Debug, Clone, PartialEq)]
+pub(super) struct PostFile {
+    pub id: String,
+    pub name: Option<String>,
+    pub mime_type: Option<String>,
+    pub size: u64,
+}
+
+/// A post for the bot together with its author's user ID, for the
+/// allowlist check. The event's `sender_name` is display data that
+/// integrations can override, so it is not carried.
+#[derive(Debug)]
+pub(super) struct Inbound {
+    pub user_id: String,
+    pub files: Vec<PostFile>,
+    pub message: ChannelMessage,
+}
+
+/// Reply target for a post: the channel, or `channel_id:root_id` to answer
+/// inside a thread. Used as [`ChannelMessage::sender`] so replies land where
+/// the post was made.
+pub fn reply_address(channel_id: &str, root_id: Option<&str>) -> String {
+    match root_id {
+        Some(root_id) => format!("{channel_id}:{root_id}"),
+        None => channel_id.to_string(),
+    }
+}
+
+/// Inverse of [`reply_address`].
+pub fn parse_reply_address(address: &str) -> (&str, Option<&str>) {
+    match address.split_once(':') {
+        Some((channel_id, root_id)) => (channel_id, Some(root_id)),
+        None => (address, None),
+    }
+}
+
+/// `ws(s)://<server>/api/v4/websocket` for an `http(s)://` server URL.
+pub(super) fn websocket_url(server_url: &str) -> String {
+    let base = server_url.trim_end_matches('/');
+    let base = if let Some(rest) = base.strip_prefix("https://") {
+        format!("wss://{rest}")
+    } else if let Some(rest) = base.strip_prefix("http://") {
+        format!("ws://{rest}")
+    } else {
+        format!("wss://{base}")
+    };
+    format!("{base}/api/v4/websocket")
+}
+
+/// First frame on a new connection, authenticating it with the bot token.
+pub(super) fn authentication_challenge(token: &str) -> String {
+    serde_json::json!({
+        "seq": 1,
+        "action": "authentication_challenge",
+        "data": { "token": token }
+    })
+    .to_string()
+}
+
+/// The post in a `posted` event, when it is addressed to the bot: direct
+/// messages, anything in the home channel, and mentions elsewhere. Mentions
+/// outside threads are answered in a new thread rooted at the post.
+pub(super) fn message_from_event(event: &Value, ctx: &InboundContext<'_>) -> Option<Inbound> {
+    if event.get("event").and_then(Value::as_str) != Some("posted") {
+        return None;
+    }
+    let data = event.get("data")?;
+    // The post and the mention list arrive as JSON-encoded strings.
+    let post: Value = serde_json::from_str(data.get("post").and_then(Value::as_str)?).ok()?;
+
+    // Join/leave notices and other system posts have a non-empty type.
+    if post
+        .get("type")
+        .and_then(Value::as_str)
+        .is_some_and(|kind| !kind.is_empty())
+    {
+        return None;
+    }
+    let user_id = post.get("user_id").and_then(Value::as_str)?;
+    if user_id == ctx.bot_user_id {
+        return None;
+    }
+    let post_id = post.get("id").and_then(Value::as_str)?;
+    let channel_id = post.get("channel_id").and_then(Value::as_str)?;
+    let root_id = post
+        .get("root_id")
+        .and_then(Value::as_str)
+        .filter(|root| !root.is_empty());
+
+    let is_direct = data.get("channel_type").and_then(Value::as_str) == Some("D");
+    let is_home = ctx.home_channel == Some(channel_id);
+    let text = post.get("message").and_then(Value::as_str).unwrap_or("");
+    let root_id = if is_direct || is_home {
+        root_id
+    } else if mentions_bot(data, text, ctx) {
+        Some(root_id.unwrap_or(post_id))
+    } else {
+        return None;
+    };
+
+    let content = strip_mention(text, ctx.bot_username);
+    let files = parse_files(&post);
+    if content.is_empty() && files.is_empty() {
+        return None;
+    }
+
+    let message = ChannelMessage {
+        id: Uuid::new_v4().to_string(),
+        sender: reply_address(channel_id, root_id),
+        author: Some(user_id.to_string()),
+        content,
+        channel: "mattermost".to_string(),
+        conversation_id: Some(channel_id.to_string()),
+        thread_id: root_id.map(ToString::to_string),
+        reply_to: None,
+        message_id: Some(post_id.to_string()),
+        timestamp: post
+            .get("create_at")
+            .and_then(Value::as_u64)
+            .map_or(0, |millis| millis / 1000),
+        attachments: Vec::new(),
+    };
+    Some(Inbound {
+        user_id: user_id.to_string(),
+        files,
+        message,
+    })
+}
+
+fn mentions_bot(data: &Value, text: &str, ctx: &InboundContext<'_>) -> bool {
+    let mentioned = data
+        .get("mentions")
+        .and_then(Value::as_str)
+        .and_then(|mentions| serde_json::from_str::<Vec<String>>(mentions).ok())
+        .is_some_and(|ids| ids.iter().any(|id| id == ctx.bot_user_id));
+    mentioned || (!ctx.bot_username.is_empty() && text.contains(&format!("@{}", ctx.bot_username)))
+}
+
+pub(super) fn parse_files(post: &Value) -> Vec<PostFile> {
+    post.get("metadata")
+        .and_then(|metadata| metadata.get("files"))
+        .and_then(Value::as_array)
+        .map(|files| {
+            files
+                .iter()
+                .filter_map(|file| {
+                    Some(PostFile {
+                        id: file.get("id").and_then(Value::as_str)?.to_string(),
+                        name: file
+                            .get("name")
+                            .and_then(Value::as_str)
+                            .map(ToString::to_string),
+                        mime_type: file
+                            .get("mime_type")
+                            .and_then(Value::as_str)
+                            .filter(|mime| !mime.is_empty())
+                            .map(ToString::to_string),
+                        size: file.get("size").and_then(Value::as_u64).unwrap_or(0),
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn strip_mention(text: &str, bot_username: &str) -> String {
+    if bot_username.is_empty() {
+        return text.trim().to_string();
+    }
+    text.replace(&format!("@{bot_username}"), "")
+        .trim()
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CTX: InboundContext<'static> = InboundContext {
+        bot_user_id: "botid",
+        bot_username: "asteroniris",
+        home_channel: Some("home"),
+    };
+
+    fn posted(channel_type: &str, post: &Value, mentions: Option<&[&str]>) -> Value {
+        let mut data = serde_json::json!({
+            "channel_type": channel_type,
+            "sender_name": "@alice",
+            "post": post.to_string(),
+        });
+        if let Some(mentions) = mentions {
+            data["mentions"] = serde_json::to_string(mentions).unwrap().into();
+        }
+        serde_json::json!({ "event": "posted", "data": data, "seq": 4 })
+    }
+
+    fn post(channel_id: &str, message: &str) -> Value {
+        serde_json::json!({
+            "id": "post1",
+            "create_at": 1_700_000_000_123_u64,
+            "user_id": "user1",
+            "channel_id": channel_id,
+            "root_id": "",
+            "message": message,
+            "type": "",
+        })
+    }
+
+    fn message(event: &Value) -> Option<ChannelMessage> {
+        message_from_event(event, &CTX).map(|inbound| inbound.message)
+    }
+
+    #[test]
+    fn reply_address_round_trips_threads() {
+        assert_eq!(reply_address("chan", None), "chan");
+        let threaded = reply_address("chan", Some("root"));
+        assert_eq!(parse_reply_address(&threaded), ("chan", Some("root")));
+        assert_eq!(parse_reply_address("chan"), ("chan", None));
+    }
+
+    #[test]
+    fn websocket_url_follows_the_server_scheme() {
+        assert_eq!(
+            websocket_url("https://chat.example.com/"),
+            "wss://chat.example.com/api/v4/websocket"
+        );
+        assert_eq!(
+            websocket_url("http://localhost:8065"),
+            "ws://localhost:8065/api/v4/websocket"
+        );
+    }
+
+    #[test]
+    fn authentication_challenge_carries_the_token() {
+        let frame: Value = serde_json::from_str(&authentication_challenge("tok")).unwrap();
+        assert_eq!(frame["action"], "authentication_challenge");
+        assert_eq!(frame["data"]["token"], "tok");
+    }
+
+    #[test]
+    fn mention_in_other_channel_starts_a_thread_without_the_mention() {
+        let event = posted(
+            "O",
+            &post("town", "@asteroniris summarize this"),
+            Some(&["botid"]),
+        );
+        let inbound = message_from_event(&event, &CTX).unwrap();
+        assert_eq!(inbound.user_id, "user1");
+        let msg = inbound.message;
+        assert_eq!(msg.content, "summarize this");
+        assert_eq!(msg.sender, "town:post1");
+        assert_eq!(msg.conversation_id.as_deref(), Some("town"));
+        assert_eq!(msg.thread_id.as_deref(), Some("post1"));
+        assert_eq!(msg.timestamp, 1_700_000_000);
+    }
+
+    #[test]
+    fn thread_replies_keep_the_root_post() {
+        let mut reply = post("home", "and the follow-up");
+        reply["root_id"] = "root9".into();
+        let msg = message(&posted("O", &reply, None)).unwrap();
+        assert_eq!(msg.sender, "home:root9");
+        assert_eq!(msg.thread_id.as_deref(), Some("root9"));
+    }
+
+    #[test]
+    fn direct_messages_reply_inline() {
+        let msg = message(&posted("D", &post("dm", "hello"), None)).unwrap();
+        assert_eq!(msg.sender, "dm");
+        assert!(msg.thread_id.is_none());
+    }
+
+    #[test]
+    fn unaddressed_own_and_system_posts_are_dropped() {
+        assert!(message(&posted("O", &post("town", "just chatting"), None)).is_none());
+
+        let mut own = post("dm", "my reply");
+        own["user_id"] = "botid".into();
+        assert!(message(&posted("D", &own, None)).is_none());
+
+        let mut system = post("home", "alice joined the channel");
+        system["type"] = "system_join_channel".into();
+        assert!(message(&posted("O", &system, None)).is_none());
+
+        let mut typing = posted("D", &post("dm", "hello"), None);
+        typing["event"] = "typing".into();
+        assert!(message(&typing).is_none());
+    }
+
+    #[test]
+    fn file_only_posts_carry_their_files() {
+        let mut upload = post("dm", "");
+        upload["file_ids"] = serde_json::json!(["file1"]);
+        upload["metadata"] = serde_json::json!({
+            "files": [{ "id": "file1", "name": "report.pdf", "mime_type": "application/pdf", "size": 42 }]
+        });
+        let inbound = message_from_event(&posted("D", &upload, None), &CTX).unwrap();
+        assert_eq!(
+            inbound.files,
+            vec![PostFile {
+                id: "file1".into(),
+                name: Some("report.pdf".into()),
+                mime_type: Some("application/pdf".into()),
+                size: 42,
+            }]
+        );
+        assert!(message(&posted("D", &post("dm", "  "), None)).is_none());
+    }
+}
@@ -0,0 +1,4 @@
+mod channel;
+pub mod events;
+
+pub use channel::MattermostChannel;
@@ -2,14 +2,27 @@ use crate::agent::{
     IntegrationRuntimeTurnOptions, IntegrationTurnParams, LoopStopReason, ToolLoopResult,
     run_main_session_turn_for_runtime_with_policy,
 };
+use crate::cli::commands::{Command, handle_command, parse_command};
+use crate::llm::router::{TurnFeatures, budget_remaining_percent};
 use crate::llm::streaming::{ChannelStreamSink, StreamSink};
+use crate::llm::traits::Provider;
+use crate::process::CompactionLevel;
+use crate::runtime::usage::recorder::today;
+use crate::runtime::usage::report::{build_report, format_usd};
+use crate::runtime::usage::{TurnUsage, UsageGroup, UsageSummary};
+use crate::security::approval::ApprovalGate;
 use crate::security::writeback_guard::enforce_external_autosave_write_policy;
+use crate::session::{ResumedSession, SessionCommand};
 use crate::tools::ExecutionContext;
 use crate::utils::text::truncate_with_ellipsis;
 use anyhow::Result;
 use std::sync::Arc;
 
-use super::attachments::output_attachment_to_media_attachment;
+use super::approval::{ChannelApprovalBroker, approval_requester};
+use super::attachments::{
+    append_attachment_context, describe_inbound_attachments, output_attachment_to_media_attachment,
+};
+use super::chunker::chunk_message;
 use super::ingress_policy::{
     apply_external_ingress_policy, channel_autosave_entity_id, channel_autosave_input,
     channel_runtime_policy_context,
@@ -17,10 +30,20 @@ use super::ingress_policy::{
 use super::policy::min_autonomy;
 use super::startup::ChannelRuntime;
 use super::traits::{Channel, ChannelMessage, MediaAttachment};
+use crate::runtime::observability::trace::{SpanContext, in_turn_span};
+use crate::runtime::observability::{ObserverEvent, global_observer};
 use crate::security::policy::AutonomyLevel;
 use std::collections::HashSet;
+use std::time::{Duration, Instant};
 use tokio::task::JoinHandle;
 
+/// Minimum time between edits of a streamed draft; chat APIs rate-limit
+/// edits per message.
+const STREAM_EDIT_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Forward task of a streamed reply; yields the id of its editable draft.
+type DraftHandle = JoinHandle<Option<String>>;
+
 async fn reply_to_origin(
     channels: &[Arc<dyn Channel>],
     channel_name: &str,
@@ -30,12 +53,28 @@ async fn reply_to_origin(
     for ch in channels {
         if ch.name() == channel_name {
             ch.send_chunked(message, sender).await?;
+            record_channel_message(channel_name, "outbound");
             break;
         }
     }
     Ok(())
 }
 
+/// Turn a message away when the dispatcher has too many pending.
+pub(super) async fn reply_busy(rt: &ChannelRuntime, msg: &ChannelMessage) {
+    let reply = "I'm handling too many messages right now; please try again shortly.";
+    if let Err(error) = reply_to_origin(&rt.channels, &msg.channel, reply, &msg.sender).await {
+        tracing::warn!(%error, "failed to send busy reply");
+    }
+}
+
+fn record_channel_message(channel: &str, direction: &str) {
+    global_observer().record_event(&ObserverEvent::ChannelMessage {
+        channel: channel.to_string(),
+        direction: direction.to_string(),
+    });
+}
+
 async fn send_media_to_origin(
     channels: &[Arc<dyn Channel>],
     channel_name: &str,
@@ -114,7 +153,7 @@ async fn autosave_and_ingest(
 
 async fn build_execution_context(
     rt: &ChannelRuntime,
-    _msg: &ChannelMessage,
+    msg: &ChannelMessage,
     autosave_entity_id: String,
     effective_autonomy: AutonomyLevel,
     tool_allowlist: Option<HashSet<String>>,
@@ -143,6 +182,54 @@ async fn build_execution_context(
         allowed_tools: tool_allowlist,
         rate_limiter: Arc::clone(&rt.rate_limiter),
         tenant_context,
+        approval: channel_approval_gate(rt, msg),
+    }
+}
+
+fn channel_approval_gate(rt: &ChannelRuntime, msg: &ChannelMessage) -> Option<Arc<ApprovalGate>> {
+    let channel = rt.channels.iter().find(|ch| ch.name() == msg.channel)?;
+    let broker = ChannelApprovalBroker::new(Arc::clone(channel), msg, Arc::clone(&rt.approvals));
+    Some(Arc::new(
+        ApprovalGate::new(Arc::new(broker)).with_permissions(Arc::clone(&rt.permissions)),
+    ))
+}
+
+/// Deliver `msg` to a waiting approval request if it is a reply to one.
+pub(super) fn resolve_approval_reply(rt: &ChannelRuntime, msg: &ChannelMessage) -> bool {
+    rt.approvals
+        .resolve_reply(&approval_requester(msg), &msg.content)
+}
+
+async fn report_stop_reason(
+    rt: &ChannelRuntime,
+    msg: &ChannelMessage,
+    stop_reason: &LoopStopReason,
+) {
+    match stop_reason {
+        LoopStopReason::MaxIterations => {
+            tracing::warn!(channel = %msg.channel, sender = %msg.sender, "tool loop hit max iterations");
+        }
+        LoopStopReason::RateLimited => {
+            tracing::warn!(channel = %msg.channel, sender = %msg.sender, "tool loop halted by rate limiting");
+        }
+        LoopStopReason::Completed => {}
+        LoopStopReason::ApprovalDenied => {
+            tracing::info!(channel = %msg.channel, sender = %msg.sender, "tool loop stopped: approval denied");
+            if let Err(error) = reply_to_origin(
+                &rt.channels,
+                &msg.channel,
+                "Stopped: the requested action was not approved.",
+                &msg.sender,
+            )
+            .await
+            {
+                tracing::warn!(%error, "failed to send approval denied reply");
+            }
+        }
+        LoopStopReason::HookBlocked(reason) => {
+            tracing::info!(channel = %msg.channel, sender = %msg.sender, %reason, "tool loop blocked by prompt hook");
+        }
+        LoopStopReason::Error(_) => unreachable!("error stop reason handled by the caller"),
     }
 }
 
@@ -150,15 +237,17 @@ async fn process_tool_loop_result(
     rt: &ChannelRuntime,
     msg: &ChannelMessage,
     result: Result<ToolLoopResult>,
-    stream_forward_handle: Option<JoinHandle<()>>,
+    stream_forward_handle: Option<DraftHandle>,
 ) {
+    let draft = match stream_forward_handle {
+        Some(handle) => handle.await.unwrap_or_else(|error| {
+            tracing::warn!(%error, "stream forward task panicked");
+            None
+        }),
+        None => None,
+    };
     match result {
         Ok(result) => {
-            if let Some(handle) = stream_forward_handle
-                && let Err(error) = handle.await
-            {
-                tracing::warn!(%error, "stream forward task panicked");
-            }
             if let LoopStopReason::Error(error) = &result.stop_reason {
                 eprintln!("  ! channel llm error: {error}");
                 if let Err(error) = reply_to_origin(
@@ -173,26 +262,28 @@ async fn process_tool_loop_result(
                 }
                 return;
             }
-            match result.stop_reason {
-                LoopStopReason::MaxIterations => {
-                    tracing::warn!(channel = %msg.channel, sender = %msg.sender, "tool loop hit max iterations");
-                }
-                LoopStopReason::RateLimited => {
-                    tracing::warn!(channel = %msg.channel, sender = %msg.sender, "tool loop halted by rate limiting");
-                }
-                LoopStopReason::Completed | LoopStopReason::ApprovalDenied => {}
-                LoopStopReason::HookBlocked(reason) => {
-                    tracing::info!(channel = %msg.channel, sender = %msg.sender, %reason, "tool loop blocked by prompt hook");
-                }
-                LoopStopReason::Error(_) => unreachable!("error stop reason handled above"),
-            }
+            report_stop_reason(rt, msg, &result.stop_reason).await;
             println!(
                 "  > channel reply: {}",
                 truncate_with_ellipsis(&result.final_text, 80)
             );
-            if let Err(error) =
-                reply_to_origin(&rt.channels, &msg.channel, &result.final_text, &msg.sender).await
-            {
+            let reply = match draft.as_deref() {
+                Some(draft_id) => {
+                    finish_draft(
+                        &rt.channels,
+                        &msg.channel,
+                        draft_id,
+                        &result.final_text,
+                        &msg.sender,
+                    )
+                    .await
+                }
+                None => {
+                    reply_to_origin(&rt.channels, &msg.channel, &result.final_text, &msg.sender)
+                        .await
+                }
+            };
+            if let Err(error) = reply {
                 eprintln!("  ! channel reply failed ({}): {error}", msg.channel);
             }
             for attachment in &result.attachments {
@@ -230,11 +321,6 @@ async fn process_tool_loop_result(
             }
         }
         Err(error) => {
-            if let Some(handle) = stream_forward_handle
-                && let Err(error) = handle.await
-            {
-                tracing::warn!(%error, "stream forward task panicked");
-            }
             eprintln!("  ! channel llm error: {error}");
             if let Err(error) = reply_to_origin(
                 &rt.channels,
@@ -250,6 +336,371 @@ async fn process_tool_loop_result(
     }
 }
 
+/// Key of the [`Branch`](crate::process::Branch) (and dispatch queue) a
+/// message belongs to.
+pub(super) fn branch_key(msg: &ChannelMessage) -> String {
+    format!("{}:{}", msg.channel, msg.conversation_key())
+}
+
+async fn run_session_command(rt: &ChannelRuntime, msg: &ChannelMessage, command: SessionCommand) {
+    let key = branch_key(msg);
+    let reply = match &rt.sessions {
+        Some(sessions) => {
+            let reply = match sessions
+                .run_command(&msg.channel, &msg.conversation_key(), command)
+                .await
+            {
+                Ok(reply) => reply,
+                Err(error) => {
+                    tracing::warn!(%error, channel = %msg.channel, "session command failed");
+                    format!("! Error: {error}")
+                }
+            };
+            // Reload the new or compacted history on the next message.
+            rt.process.close_branch(&key);
+            reply
+        }
+        None => match command {
+            SessionCommand::New => {
+                rt.process.close_branch(&key);
+                "Started a new conversation.".to_string()
+            }
+            SessionCommand::Compact => {
+                let branch = rt.process.branch(&key);
+                match branch.lock().await.compact(CompactionLevel::Moderate) {
+                    0 => "Nothing to compact yet.".to_string(),
+                    removed => format!("Compacted {removed} earlier messages into a summary."),
+                }
+            }
+        },
+    };
+    if let Err(error) = reply_to_origin(&rt.channels, &msg.channel, &reply, &msg.sender).await {
+        tracing::warn!(%error, "failed to send session command reply");
+    }
+}
+
+/// `/usage`: the sender's recorded usage today and this month, and today's
+/// spend against the daily budget.
+async fn usage_reply(rt: &ChannelRuntime, msg: &ChannelMessage) -> String {
+    let Some(usage) = &rt.usage else {
+        return "Usage tracking is turned off.".to_string();
+    };
+    let entity_id = channel_autosave_entity_id(&msg.channel, &msg.sender);
+    let today = today();
+    let month_start = format!("{}-01", &today[..7]);
+    let report = match build_report(
+        usage.tracker(),
+        &rt.config,
+        UsageGroup::Entity,
+        Some(&month_start),
+        None,
+    )
+    .await
+    {
+        Ok(report) => report,
+        Err(error) => {
+            tracing::warn!(%error, "failed to read usage for /usage");
+            return "Usage is unavailable right now.".to_string();
+        }
+    };
+    let today_summary = usage
+        .tracker()
+        .summarize_by(UsageGroup::Entity, Some(&today), None)
+        .await
+        .unwrap_or_default()
+        .into_iter()
+        .find(|entry| entry.key == entity_id)
+        .map(|entry| entry.summary)
+        .unwrap_or_default();
+    let month_summary = report
+        .breakdown
+        .iter()
+        .find(|entry| entry.key == entity_id)
+        .map(|entry| entry.summary.clone())
+        .unwrap_or_default();
+
+    let line = |label: &str, summary: &UsageSummary| {
+        format!(
+            "{label}: {} turns, {} input / {} output tokens, {}",
+            summary.record_count,
+            summary.total_input_tokens,
+            summary.total_output_tokens,
+            format_usd(summary.total_estimated_cost_micros)
+        )
+    };
+    format!(
+        "{}\n{}\nDaily budget: {} of {} spent ({}%)",
+        line("Today", &today_summary),
+        line("This month", &month_summary),
+        format_usd(report.budget.spent_today_micros),
+        format_usd(i64::from(report.budget.max_cost_per_day_cents) * 10_000),
+        report.budget.percent_used
+    )
+}
+
+/// The message's live session and its stored history.
+async fn resume_session(rt: &ChannelRuntime, msg: &ChannelMessage) -> Option<ResumedSession> {
+    let sessions = rt.sessions.as_ref()?;
+    match sessions.resume(&msg.channel, &msg.conversation_key()).await {
+        Ok(session) => Some(session),
+        Err(error) => {
+            tracing::warn!(%error, channel = %msg.channel, "failed to load conversation history");
+            None
+        }
+    }
+}
+
+async fn record_session_turn(
+    rt: &ChannelRuntime,
+    session: Option<&ResumedSession>,
+    user_message: &str,
+    result: &ToolLoopResult,
+) {
+    let (Some(sessions), Some(session)) = (&rt.sessions, session) else {
+        return;
+    };
+    if let Err(error) = sessions
+        .record_turn(&session.id, user_message, &result.transcript)
+        .await
+    {
+        tracing::warn!(%error, "failed to record conversation turn");
+    }
+}
+
+/// The provider and model for this turn: the router's choice when routing
+/// is enabled, else the runtime defaults. A routed provider that cannot be
+/// built falls back to the defaults.
+fn route_turn(
+    rt: &ChannelRuntime,
+    msg: &ChannelMessage,
+    ctx: &ExecutionContext,
+) -> (Arc<dyn Provider>, String) {
+    let default = || (Arc::clone(&rt.provider), rt.model.clone());
+    let Some(router) = &rt.router else {
+        return default();
+    };
+    let route = router.route(&TurnFeatures {
+        chars: msg.content.chars().count(),
+        has_images: msg
+            .attachments
+            .iter()
+            .any(|attachment| attachment.mime_type.starts_with("image/")),
+        needs_tools: ctx
+            .allowed_tools
+            .as_ref()
+            .is_none_or(|allowed| !allowed.is_empty()),
+        channel: Some(&msg.channel),
+        budget_remaining_percent: budget_remaining_percent(
+            rt.usage
+                .as_ref()
+                .map_or(0, |usage| usage.spent_today_cents()),
+            rt.security.max_cost_per_day_cents,
+        ),
+    });
+    tracing::debug!(
+        channel = %msg.channel,
+        provider = %route.provider,
+        model = %route.model,
+        rule = route.rule.as_deref().unwrap_or("default"),
+        low_budget = route.low_budget,
+        "routed channel turn"
+    );
+    match rt.routed_providers.get(&route.provider) {
+        Ok(provider) => (provider, route.model),
+        Err(error) => {
+            tracing::warn!(%error, provider = %route.provider, "routed provider unavailable; using the default");
+            default()
+        }
+    }
+}
+
+/// Run one turn on the message's branch: history comes from the branch
+/// (seeded from the session store when the branch is new), and the finished
+/// turn is appended to both before the branch is compacted if needed.
+async fn run_branch_turn(
+    rt: &ChannelRuntime,
+    msg: &ChannelMessage,
+    ctx: ExecutionContext,
+    message_input: &str,
+    stream_sink: Option<Arc<dyn StreamSink>>,
+) -> Result<ToolLoopResult> {
+    let branch = rt.process.branch(&branch_key(msg));
+    let mut branch = branch.lock().await;
+    // With a session store the stored history, compaction summaries
+    // included, is the conversation; the branch only carries it into the
+    // turn. Without one the branch keeps and compacts its own history.
+    let mut session = resume_session(rt, msg).await;
+    if let Some(session) = session.as_mut() {
+        branch.set_history(std::mem::take(&mut session.history));
+    }
+
+    let (provider, model) = route_turn(rt, msg, &ctx);
+    let entity_id = ctx.entity_id.clone();
+    let policy_context = ctx.tenant_context.clone();
+    let system_prompt = rt.process.system_prompt();
+    let result = run_main_session_turn_for_runtime_with_policy(
+        IntegrationTurnParams {
+            config: rt.config.as_ref(),
+            security: rt.security.as_ref(),
+            mem: Arc::clone(&rt.mem),
+            answer_provider: provider.as_ref(),
+            reflect_provider: provider.as_ref(),
+            system_prompt: &system_prompt,
+            model_name: &model,
+            temperature: rt.temperature,
+            entity_id: &entity_id,
+            policy_context,
+            user_message: message_input,
+        },
+        IntegrationRuntimeTurnOptions {
+            registry: Arc::clone(&rt.registry),
+            max_tool_iterations: rt.config.autonomy.max_tool_loop_iterations,
+            repeated_tool_call_streak_limit: rt.config.autonomy.repeated_tool_call_streak_limit,
+            execution_context: ctx,
+            stream_sink,
+            conversation_history: branch.history(),
+            image_content: &[],
+            hooks: &[],
+            thinking: branch.thinking(),
+            request_options: None,
+        },
+    )
+    .await;
+
+    if let (Some(usage), Ok(result)) = (&rt.usage, &result) {
+        usage
+            .record_turn(TurnUsage {
+                entity_id: &entity_id,
+                channel: &msg.channel,
+                session_id: session.as_ref().map(|session| session.id.as_str()),
+                provider: provider.name(),
+                model: &model,
+                input_tokens: result.input_tokens,
+                output_tokens: result.output_tokens,
+            })
+            .await;
+    }
+    if let Ok(result) = &result
+        && !matches!(result.stop_reason, LoopStopReason::Error(_))
+    {
+        branch.record_turn(message_input, &result.transcript);
+        if session.is_some() {
+            record_session_turn(rt, session.as_ref(), message_input, result).await;
+        } else {
+            branch.compact_if_needed(rt.process.compaction_thresholds());
+        }
+    }
+    result
+}
+
+async fn inbound_content_with_attachments(rt: &ChannelRuntime, msg: &ChannelMessage) -> String {
+    if msg.attachments.is_empty() {
+        return msg.content.clone();
+    }
+    let channel = rt.channels.iter().find(|ch| ch.name() == msg.channel);
+    let references = describe_inbound_attachments(
+        channel.map(AsRef::as_ref),
+        rt.media_store.as_deref(),
+        &rt.media_processor,
+        &msg.attachments,
+    )
+    .await;
+    append_attachment_context(&msg.content, &references)
+}
+
+/// Forward streamed chunks back to the originating channel as they arrive.
+/// Channels that can edit messages show one draft that grows in place; the
+/// forward task returns its id so the final reply can replace it.
+fn channel_stream_sink(
+    rt: &ChannelRuntime,
+    msg: &ChannelMessage,
+) -> (Option<Arc<dyn StreamSink>>, Option<DraftHandle>) {
+    let mut stream_forward_handle = None;
+    let stream_sink = rt
+        .channels
+        .iter()
+        .find(|channel| channel.name() == msg.channel)
+        .map(|channel| {
+            let (tx, mut rx) = tokio::sync::mpsc::channel::<String>(32);
+            let channel = Arc::clone(channel);
+            let recipient = msg.sender.clone();
+            let channel_name = msg.channel.clone();
+            stream_forward_handle = Some(tokio::spawn(async move {
+                let mut draft: Option<String> = None;
+                let mut editable = true;
+                let mut streamed = String::new();
+                let mut last_edit = Instant::now();
+                while let Some(chunk) = rx.recv().await {
+                    if chunk.is_empty() {
+                        continue;
+                    }
+                    let result = if let Some(draft_id) = draft.as_deref() {
+                        streamed.push_str(&chunk);
+                        // Past the length limit the final reply is split anyway.
+                        if streamed.len() > channel.max_message_length()
+                            || last_edit.elapsed() < STREAM_EDIT_INTERVAL
+                        {
+                            continue;
+                        }
+                        last_edit = Instant::now();
+                        channel.edit_message(&recipient, draft_id, &streamed).await
+                    } else if editable {
+                        channel
+                            .send_draft(&chunk, &recipient)
+                            .await
+                            .map(|draft_id| {
+                                editable = draft_id.is_some();
+                                draft = draft_id;
+                                streamed = chunk;
+                                last_edit = Instant::now();
+                            })
+                    } else {
+                        channel.send(&chunk, &recipient).await
+                    };
+                    if let Err(error) = result {
+                        tracing::warn!(
+                            channel = %channel_name,
+                            recipient = %recipient,
+                            error = %error,
+                            "failed to stream channel chunk"
+                        );
+                        break;
+                    }
+                }
+                draft
+            }));
+            Arc::new(ChannelStreamSink::new(tx, 80)) as Arc<dyn StreamSink>
+        });
+    (stream_sink, stream_forward_handle)
+}
+
+/// Replace a streamed draft with the final reply. Text past the channel's
+/// message limit follows as new messages; if the draft cannot be edited the
+/// reply is sent in full.
+pub(super) async fn finish_draft(
+    channels: &[Arc<dyn Channel>],
+    channel_name: &str,
+    draft_id: &str,
+    message: &str,
+    sender: &str,
+) -> Result<()> {
+    let Some(ch) = channels.iter().find(|ch| ch.name() == channel_name) else {
+        return Ok(());
+    };
+    let mut chunks = chunk_message(message, ch.max_message_length()).into_iter();
+    if let Some(first) = chunks.next()
+        && let Err(error) = ch.edit_message(sender, draft_id, &first).await
+    {
+        tracing::warn!(%error, channel = %channel_name, "failed to finish streamed draft");
+        return reply_to_origin(channels, channel_name, message, sender).await;
+    }
+    for chunk in chunks {
+        ch.send(&chunk, sender).await?;
+    }
+    record_channel_message(channel_name, "outbound");
+    Ok(())
+}
+
 pub(super) async fn handle_channel_message(rt: &ChannelRuntime, msg: &ChannelMessage) {
     println!(
         "  > channel message from {}/{}: {}",
@@ -257,11 +708,67 @@ pub(super) async fn handle_channel_message(rt: &ChannelRuntime, msg: &ChannelMes
         msg.sender,
         truncate_with_ellipsis(&msg.content, 80)
     );
+    if answer_chat_command(rt, msg).await {
+        return;
+    }
+    // The turn's span also covers the messages that start and answer it.
+    in_turn_span(
+        SpanContext::child_of_current(),
+        Box::pin(run_message_turn(rt, msg)),
+    )
+    .await;
+}
+
+/// Answer session and chat commands (`/new`, `/think`, `/usage`, ...) that
+/// need no agent turn. Returns `false` for anything else.
+async fn answer_chat_command(rt: &ChannelRuntime, msg: &ChannelMessage) -> bool {
+    let session_command = SessionCommand::parse(&msg.content);
+    let command = parse_command(&msg.content)
+        .filter(|command| matches!(command, Command::Think { .. } | Command::Usage));
+    if session_command.is_none() && command.is_none() {
+        return false;
+    }
+    record_channel_message(&msg.channel, "inbound");
+
+    if let Some(command) = session_command {
+        run_session_command(rt, msg, command).await;
+        return true;
+    }
+    match command {
+        Some(command @ Command::Think { .. }) => {
+            let branch = rt.process.branch(&branch_key(msg));
+            let mut branch = branch.lock().await;
+            let mut thinking = branch.thinking();
+            let reply = handle_command(&command, &mut thinking);
+            branch.set_thinking(thinking);
+            drop(branch);
+            if let Err(error) =
+                reply_to_origin(&rt.channels, &msg.channel, &reply.text, &msg.sender).await
+            {
+                tracing::warn!(%error, "failed to send /think reply");
+            }
+        }
+        Some(Command::Usage) => {
+            let reply = usage_reply(rt, msg).await;
+            if let Err(error) =
+                reply_to_origin(&rt.channels, &msg.channel, &reply, &msg.sender).await
+            {
+                tracing::warn!(%error, "failed to send /usage reply");
+            }
+        }
+        _ => {}
+    }
+    true
+}
+
+async fn run_message_turn(rt: &ChannelRuntime, msg: &ChannelMessage) {
+    record_channel_message(&msg.channel, "inbound");
 
     let (effective_autonomy, tool_allowlist) = resolve_channel_policy(rt, msg);
 
+    let content = inbound_content_with_attachments(rt, msg).await;
     let source = format!("channel:{}", msg.channel);
-    let ingress = apply_external_ingress_policy(&source, &msg.content);
+    let ingress = apply_external_ingress_policy(&source, &content);
     let autosave_entity_id = channel_autosave_entity_id(&msg.channel, &msg.sender);
 
     autosave_and_ingest(rt, msg, &autosave_entity_id, &ingress.persisted_summary).await;
@@ -292,65 +799,10 @@ pub(super) async fn handle_channel_message(rt: &ChannelRuntime, msg: &ChannelMes
         tool_allowlist,
     )
     .await;
-    // TODO: Port MediaProcessor to v2 for image attachment handling.
-    // For now, use the text content directly without image block conversion.
     let message_input = ingress.model_input;
 
-    let mut stream_forward_handle = None;
-    let stream_sink: Option<Arc<dyn StreamSink>> = rt
-        .channels
-        .iter()
-        .find(|channel| channel.name() == msg.channel)
-        .map(|channel| {
-            let (tx, mut rx) = tokio::sync::mpsc::channel::<String>(32);
-            let channel = Arc::clone(channel);
-            let recipient = msg.sender.clone();
-            let channel_name = msg.channel.clone();
-            stream_forward_handle = Some(tokio::spawn(async move {
-                while let Some(chunk) = rx.recv().await {
-                    if chunk.is_empty() {
-                        continue;
-                    }
-                    if let Err(error) = channel.send(&chunk, &recipient).await {
-                        tracing::warn!(
-                            channel = %channel_name,
-                            recipient = %recipient,
-                            error = %error,
-                            "failed to stream channel chunk"
-                        );
-                        break;
-                    }
-                }
-            }));
-            Arc::new(ChannelStreamSink::new(tx, 80)) as Arc<dyn StreamSink>
-        });
+    let (stream_sink, stream_forward_handle) = channel_stream_sink(rt, msg);
 
-    let entity_id = ctx.entity_id.clone();
-    let policy_context = ctx.tenant_context.clone();
-    let result = run_main_session_turn_for_runtime_with_policy(
-        IntegrationTurnParams {
-            config: rt.config.as_ref(),
-            security: rt.security.as_ref(),
-            mem: Arc::clone(&rt.mem),
-            answer_provider: rt.provider.as_ref(),
-            reflect_provider: rt.provider.as_ref(),
-            system_prompt: &rt.system_prompt,
-            model_name: &rt.model,
-            temperature: rt.temperature,
-            entity_id: &entity_id,
-            policy_context,
-            user_message: &message_input,
-        },
-        IntegrationRuntimeTurnOptions {
-            registry: Arc::clone(&rt.registry),
-            max_tool_iterations: rt.config.autonomy.max_tool_loop_iterations,
-            repeated_tool_call_streak_limit: rt.config.autonomy.repeated_tool_call_streak_limit,
-            execution_context: ctx,
-            stream_sink,
-            conversation_history: &[],
-            hooks: &[],
-        },
-    )
-    .await;
+    let result = run_branch_turn(rt, msg, ctx, &message_input, stream_sink).await;
     process_tool_loop_result(rt, msg, result, stream_forward_handle).await;
 }
@@ -1,3 +1,4 @@
+pub mod approval;
 #[allow(dead_code)]
 mod attachments;
 pub mod chunker;
@@ -15,6 +16,8 @@ pub mod ingress_policy;
 pub mod irc;
 #[cfg(feature = "matrix")]
 pub mod matrix;
+#[cfg(feature = "mattermost")]
+pub mod mattermost;
 mod message_handler;
 pub mod policy;
 pub mod prompt_builder;
@@ -25,6 +28,7 @@ mod startup;
 #[cfg(feature = "telegram")]
 pub mod telegram;
 pub mod traits;
+pub mod webhook_inbox;
 #[cfg(feature = "whatsapp")]
 pub mod whatsapp;
 
@@ -42,6 +46,8 @@ pub use imessage::IMessageChannel;
 pub use irc::{IrcChannel, IrcChannelConfig};
 #[cfg(feature = "matrix")]
 pub use matrix::MatrixChannel;
+#[cfg(feature = "mattermost")]
+pub use mattermost::MattermostChannel;
 #[allow(unused_imports)]
 pub use prompt_builder::{
     SystemPromptOptions, build_system_prompt, build_system_prompt_with_options,
@@ -0,0 +1,646 @@
+use super::events::{
+    Inbound, InboundContext, authorized_bot_user, message_from_event, message_from_slash_command,
+    parse_reply_address,
+};
+use super::socket::{self, SocketFrame};
+use crate::security::approval::ApprovalRequest;
+use crate::transport::channels::attachments::{download_capped, load_attachment_bytes};
+use crate::transport::channels::policy::{AllowlistMatch, is_allowed_user};
+use crate::transport::channels::traits::{Channel, ChannelMessage, MediaAttachment, MediaData};
+use crate::transport::channels::webhook_inbox::webhook_inbox;
+use anyhow::Context;
+use futures_util::{SinkExt, StreamExt};
+use serde_json::Value;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Mutex, PoisonError};
+use tokio_tungstenite::tungstenite::Message;
+
+/// Host serving `url_private` files, the only place the bot token is sent
+/// when downloading attachments.
+const SLACK_FILES_URL: &str = "https://files.slack.com/";
+
+/// Slack channel — receives events over Socket Mode when an app-level token
+/// is configured, otherwise through the gateway's `/slack/events` endpoint
+/// (Events API).
+pub struct SlackChannel {
+    bot_token: String,
+    app_token: Option<String>,
+    signing_secret: Option<String>,
+    channel_id: Option<String>,
+    allowed_users: Vec<String>,
+    client: reqwest::Client,
+    bot_user_id: Mutex<Option<String>>,
+}
+
+impl SlackChannel {
+    pub fn new(bot_token: String, channel_id: Option<String>, allowed_users: Vec<String>) -> Self {
+        Self {
+            bot_token,
+            app_token: None,
+            signing_secret: None,
+            channel_id,
+            allowed_users,
+            client: reqwest::Client::new(),
+            bot_user_id: Mutex::new(None),
+        }
+    }
+
+    /// Listen over Socket Mode with this app-level (`xapp-…`) token.
+    #[must_use]
+    pub fn with_app_token(mut self, app_token: Option<String>) -> Self {
+        self.app_token = app_token.filter(|token| !token.trim().is_empty());
+        self
+    }
+
+    /// Accept Events API requests signed with this secret.
+    #[must_use]
+    pub fn with_signing_secret(mut self, signing_secret: Option<String>) -> Self {
+        self.signing_secret = signing_secret.filter(|secret| !secret.trim().is_empty());
+        self
+    }
+
+    pub fn signing_secret(&self) -> Option<&str> {
+        self.signing_secret.as_deref()
+    }
+
+    /// Check if a Slack user ID is in the allowlist.
+    /// Empty list means deny everyone until explicitly configured.
+    /// `"*"` means allow everyone.
+    fn is_user_allowed(&self, user_id: &str) -> bool {
+        is_allowed_user(&self.allowed_users, user_id, AllowlistMatch::Exact)
+    }
+
+    /// The bot's own user ID, so its messages and mentions can be told
+    /// apart. Looked up once via `auth.test`.
+    async fn bot_user_id(&self) -> String {
+        if let Some(id) = self
+            .bot_user_id
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+        {
+            return id;
+        }
+        let Some(id) = self.fetch_bot_user_id().await else {
+            return String::new();
+        };
+        *self
+            .bot_user_id
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner) = Some(id.clone());
+        id
+    }
+
+    async fn fetch_bot_user_id(&self) -> Option<String> {
+        let resp: serde_json::Value = self
+            .client
+            .get("https://slack.com/api/auth.test")
+            .bearer_auth(&self.bot_token)
+            .send()
+            .await
+            .ok()?
+            .json()
+            .await
+            .ok()?;
+
+        resp.get("user_id")
+            .and_then(|u| u.as_str())
+            .map(String::from)
+    }
+
+    /// The message carried by an `event_callback` payload, if it is
+    /// addressed to the bot and its sender is allowed.
+    pub async fn inbound_from_event(&self, payload: &Value) -> Option<ChannelMessage> {
+        let bot_user_id = match authorized_bot_user(payload) {
+            Some(id) => id.to_string(),
+            None => self.bot_user_id().await,
+        };
+        let inbound = message_from_event(
+            payload,
+            &InboundContext {
+                bot_user_id: &bot_user_id,
+                home_channel: self.channel_id.as_deref(),
+            },
+        )?;
+        self.authorize(inbound)
+    }
+
+    /// The message for a slash command payload, if its sender is allowed.
+    pub fn inbound_from_slash_command(&self, payload: &Value) -> Option<ChannelMessage> {
+        self.authorize(message_from_slash_command(payload)?)
+    }
+
+    /// Fill in `channel` (and `thread_ts` for threaded replies) from a
+    /// reply address.
+    fn address(body: &mut Value, recipient: &str) {
+        let (channel, thread_ts) = parse_reply_address(recipient);
+        body["channel"] = Value::String(channel.to_string());
+        if let Some(thread_ts) = thread_ts {
+            body["thread_ts"] = Value::String(thread_ts.to_string());
+        }
+    }
+
+    fn authorize(&self, inbound: Inbound) -> Option<ChannelMessage> {
+        if self.is_user_allowed(&inbound.user) {
+            Some(inbound.message)
+        } else {
+            tracing::warn!(
+                "Slack: ignoring message from unauthorized user: {}",
+                inbound.user
+            );
+            None
+        }
+    }
+
+    /// Socket Mode: receive envelopes over a WebSocket, acknowledging each
+    /// before handling it and reconnecting whenever Slack asks to. Returns
+    /// once the receiver is gone.
+    async fn listen_socket_mode(
+        &self,
+        app_token: &str,
+        tx: &tokio::sync::mpsc::Sender<ChannelMessage>,
+    ) -> anyhow::Result<()> {
+        loop {
+            let url = socket::open_connection(&self.client, app_token).await?;
+            let (ws, _) = tokio_tungstenite::connect_async(url.as_str())
+                .await
+                .context("connect to Slack Socket Mode")?;
+            let (mut write, mut read) = ws.split();
+
+            while let Some(frame) = read.next().await {
+                let text = match frame.context("read Slack Socket Mode frame")? {
+                    Message::Text(text) => text,
+                    Message::Ping(data) => {
+                        write.send(Message::Pong(data)).await?;
+                        continue;
+                    }
+                    Message::Close(_) => break,
+                    _ => continue,
+                };
+                match socket::parse_frame(&text) {
+                    Some(SocketFrame::Hello) => tracing::info!("Slack Socket Mode connected"),
+                    Some(SocketFrame::Disconnect { reason }) => {
+                        tracing::debug!(reason, "Slack Socket Mode disconnect; reconnecting");
+                        break;
+                    }
+                    Some(SocketFrame::Envelope {
+                        envelope_id,
+                        kind,
+                        payload,
+                    }) => {
+                        write
+                            .send(Message::Text(socket::ack(&envelope_id).into()))
+                            .await
+                            .context("acknowledge Slack envelope")?;
+                        let msg = match kind.as_str() {
+                            "events_api" => self.inbound_from_event(&payload).await,
+                            "slash_commands" => self.inbound_from_slash_command(&payload),
+                            _ => None,
+                        };
+                        if let Some(msg) = msg
+                            && tx.send(msg).await.is_err()
+                        {
+                            return Ok(());
+                        }
+                    }
+                    None => {}
+                }
+            }
+        }
+    }
+
+    async fn post_message(&self, body: Value) -> anyhow::Result<()> {
+        let resp = self
+            .client
+            .post("https://slack.com/api/chat.postMessage")
+            .bearer_auth(&self.bot_token)
+            .json(&body)
+            .send()
+            .await?;
+
+        let status = resp.status();
+        let body = resp
+            .text()
+            .await
+            .unwrap_or_else(|e| format!("<failed to read response body: {e}>"));
+
+        if !status.is_success() {
+            anyhow::bail!("Slack chat.postMessage failed ({status}): {body}");
+        }
+
+        // Slack returns 200 for most app-level errors; check JSON "ok" field
+        let parsed: serde_json::Value = serde_json::from_str(&body).unwrap_or_default();
+        if parsed.get("ok") == Some(&serde_json::Value::Bool(false)) {
+            let err = parsed
+                .get("error")
+                .and_then(|e| e.as_str())
+                .unwrap_or("unknown");
+            anyhow::bail!("Slack chat.postMessage failed: {err}");
+        }
+
+        Ok(())
+    }
+
+    /// Block Kit approval prompt. Replies are typed as text, so approvals work
+    /// without an interactivity endpoint.
+    fn approval_blocks(request: &ApprovalRequest) -> Value {
+        let id = &request.id;
+        let options = if request.can_remember() {
+            format!("`approve {id}` · `always {id}` · `deny {id}`")
+        } else {
+            format!("`approve {id}` · `deny {id}`")
+        };
+        serde_json::json!({
+            "text": request.prompt_text(),
+            "blocks": [
+                {
+                    "type": "section",
+                    "text": {
+                        "type": "mrkdwn",
+                        "text": format!(":raised_hand: *Approval needed*\n{}", request.summary()),
+                    }
+                },
+                {
+                    "type": "context",
+                    "elements": [{ "type": "mrkdwn", "text": format!("Reply {options}") }]
+                }
+            ]
+        })
+    }
+}
+
+impl Channel for SlackChannel {
+    fn name(&self) -> &str {
+        "slack"
+    }
+
+    fn max_message_length(&self) -> usize {
+        3000
+    }
+
+    fn send<'a>(
+        &'a self,
+        message: &'a str,
+        recipient: &'a str,
+    ) -> Pin<Box<dyn Future<Output = anyhow::Result<()>> + Send + 'a>> {
+        Box::pin(async move {
+            let mut body = serde_json::json!({ "text": message });
+            Self::address(&mut body, recipient);
+            self.post_message(body).await
+        })
+    }
+
+    fn send_approval_request<'a>(
+        &'a self,
+        request: &'a ApprovalRequest,
+        recipient: &'a str,
+    ) -> Pin<Box<dyn Future<Output = anyhow::Result<()>> + Send + 'a>> {
+        Box::pin(async move {
+            let mut body = Self::approval_blocks(request);
+            Self::address(&mut body, recipient);
+            self.post_message(body).await
+        })
+    }
+
+    fn listen<'a>(
+        &'a self,
+        tx: tokio::sync::mpsc::Sender<ChannelMessage>,
+    ) -> Pin<Box<dyn Future<Output = anyhow::Result<()>> + Send + 'a>> {
+        Box::pin(async move {
+            if let Some(app_token) = &self.app_token {
+                return self.listen_socket_mode(app_token, &tx).await;
+            }
+
+            // Without an app-level token, Slack pushes events to the
+            // gateway's /slack/events endpoint, which hands them over here.
+            tracing::info!(
+                "Slack channel active (Events API mode). \
+                Point the Slack app's Request URL at your gateway's /slack/events endpoint."
+            );
+            webhook_inbox("slack").drain_into(&tx).await;
+            Ok(())
+        })
+    }
+
+    fn health_check<'a>(&'a self) -> Pin<Box<dyn Future<Output = bool> + Send + 'a>> {
+        Box::pin(async move {
+            self.client
+                .get("https://slack.com/api/auth.test")
+                .bearer_auth(&self.bot_token)
+                .send()
+                .await
+                .is_ok_and(|r| r.status().is_success())
+        })
+    }
+
+    /// `url_private` files need the bot token; it is only sent to Slack's
+    /// file host.
+    fn fetch_attachment<'a>(
+        &'a self,
+        attachment: &'a MediaAttachment,
+        max_bytes: usize,
+    ) -> Pin<Box<dyn Future<Output = anyhow::Result<Vec<u8>>> + Send + 'a>> {
+        Box::pin(async move {
+            match &attachment.data {
+                MediaData::Url(url) if url.starts_with(SLACK_FILES_URL) => {
+                    download_capped(self.client.get(url).bearer_auth(&self.bot_token), max_bytes)
+                        .await
+                }
+                _ => load_attachment_bytes(attachment, max_bytes).await,
+            }
+        })
+    }
+
+    fn send_media<'a>(
+        &'a self,
+        attachment: &'a MediaAttachment,
+        recipient: &'a str,
+    ) -> Pin<Box<dyn Future<Output = anyhow::Result<()>> + Send + 'a>> {
+        Box::pin(async move {
+            let bytes = match &attachment.data {
+                MediaData::Url(media_url) => self
+                    .client
+                    .get(media_url)
+                    .bearer_auth(&self.bot_token)
+                    .send()
+                    .await
+                    .context("download Slack media before upload")?
+                    .bytes()
+                    .await
+                    .context("read Slack media bytes")?
+                    .to_vec(),
+                MediaData::Bytes(raw_bytes) => raw_bytes.clone(),
+            };
+
+            let filename = attachment
+                .filename
+                .clone()
+                .unwrap_or_else(|| "attachment".to_string());
+            let file_part = reqwest::multipart::Part::bytes(bytes).file_name(filename);
+            let (channel, thread_ts) = parse_reply_address(recipient);
+            let mut form = reqwest::multipart::Form::new()
+                .text("channels", channel.to_string())
+                .part("file", file_part);
+            if let Some(thread_ts) = thread_ts {
+                form = form.text("thread_ts", thread_ts.to_string());
+            }
+
+            let resp = self
+                .client
+                .post("https://slack.com/api/files.upload")
+                .bearer_auth(&self.bot_token)
+                .multipart(form)
+                .send()
+                .await
+                .context("send Slack files.upload request")?;
+
+            let status = resp.status();
+            let body = resp
+                .text()
+                .await
+                .unwrap_or_else(|e| format!("<failed to read response body: {e}>"));
+
+            if !status.is_success() {
+                anyhow::bail!("Slack files.upload failed ({status}): {body}");
+            }
+
+            let parsed: Value = serde_json::from_str(&body).unwrap_or_default();
+            if parsed.get("ok") == Some(&Value::Bool(false)) {
+                let err = parsed
+                    .get("error")
+                    .and_then(Value::as_str)
+                    .unwrap_or("unknown");
+                anyhow::bail!("Slack files.upload failed: {err}");
+            }
+
+            Ok(())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::events::parse_files;
+    use super::*;
+
+    #[test]
+    fn slack_channel_name() {
+        let ch = SlackChannel::new("xoxb-fake".into(), None, vec![]);
+        assert_eq!(ch.name(), "slack");
+    }
+
+    #[test]
+    fn slack_channel_with_channel_id() {
+        let ch = SlackChannel::new("xoxb-fake".into(), Some("C12345".into()), vec![]);
+        assert_eq!(ch.channel_id, Some("C12345".to_string()));
+    }
+
+    #[test]
+    fn empty_allowlist_denies_everyone() {
+        let ch = SlackChannel::new("xoxb-fake".into(), None, vec![]);
+        assert!(!ch.is_user_allowed("U12345"));
+        assert!(!ch.is_user_allowed("anyone"));
+    }
+
+    #[test]
+    fn wildcard_allows_everyone() {
+        let ch = SlackChannel::new("xoxb-fake".into(), None, vec!["*".into()]);
+        assert!(ch.is_user_allowed("U12345"));
+    }
+
+    #[test]
+    fn specific_allowlist_filters() {
+        let ch = SlackChannel::new("xoxb-fake".into(), None, vec!["U111".into(), "U222".into()]);
+        assert!(ch.is_user_allowed("U111"));
+        assert!(ch.is_user_allowed("U222"));
+        assert!(!ch.is_user_allowed("U333"));
+    }
+
+    #[test]
+    fn allowlist_exact_match_not_substring() {
+        let ch = SlackChannel::new("xoxb-fake".into(), None, vec!["U111".into()]);
+        assert!(!ch.is_user_allowed("U1111"));
+        assert!(!ch.is_user_allowed("U11"));
+    }
+
+    #[test]
+    fn allowlist_empty_user_id() {
+        let ch = SlackChannel::new("xoxb-fake".into(), None, vec!["U111".into()]);
+        assert!(!ch.is_user_allowed(""));
+    }
+
+    #[test]
+    fn allowlist_case_sensitive() {
+        let ch = SlackChannel::new("xoxb-fake".into(), None, vec!["U111".into()]);
+        assert!(ch.is_user_allowed("U111"));
+        assert!(!ch.is_user_allowed("u111"));
+    }
+
+    #[test]
+    fn allowlist_wildcard_and_specific() {
+        let ch = SlackChannel::new("xoxb-fake".into(), None, vec!["U111".into(), "*".into()]);
+        assert!(ch.is_user_allowed("U111"));
+        assert!(ch.is_user_allowed("anyone"));
+    }
+
+    #[test]
+    fn approval_blocks_include_text_fallback_and_reply_options() {
+        use crate::security::approval::ApprovalSubject;
+        let request = ApprovalRequest::new(
+            "slack:C1",
+            ApprovalSubject::ToolCall {
+                tool: "shell".into(),
+                pattern: "git push".into(),
+            },
+        );
+        let body = SlackChannel::approval_blocks(&request);
+        assert!(body["text"].as_str().unwrap().contains("Approval needed"));
+        let context = body["blocks"][1]["elements"][0]["text"].as_str().unwrap();
+        assert!(context.contains(&format!("`always {}`", request.id)));
+    }
+
+    #[test]
+    fn replies_address_the_channel_and_thread() {
+        let mut body = serde_json::json!({ "text": "hi" });
+        SlackChannel::address(&mut body, "C1:1700000000.000100");
+        assert_eq!(body["channel"], "C1");
+        assert_eq!(body["thread_ts"], "1700000000.000100");
+
+        let mut body = serde_json::json!({ "text": "hi" });
+        SlackChannel::address(&mut body, "D1");
+        assert_eq!(body["channel"], "D1");
+        assert!(body.get("thread_ts").is_none());
+    }
+
+    #[test]
+    fn slash_commands_from_unlisted_users_are_dropped() {
+        let ch = SlackChannel::new("xoxb-fake".into(), None, vec!["U111".into()]);
+        let command = |user: &str| {
+            serde_json::json!({
+                "command": "/ask",
+                "text": "status?",
+                "user_id": user,
+                "channel_id": "C1"
+            })
+        };
+        assert!(ch.inbound_from_slash_command(&command("U111")).is_some());
+        assert!(ch.inbound_from_slash_command(&command("U222")).is_none());
+    }
+
+    #[tokio::test]
+    async fn events_use_the_authorized_bot_user_for_mentions() {
+        let ch = SlackChannel::new("xoxb-fake".into(), None, vec!["*".into()]);
+        let payload = serde_json::json!({
+            "type": "event_callback",
+            "authorizations": [{ "user_id": "UBOT" }],
+            "event": {
+                "type": "app_mention",
+                "user": "U1",
+                "channel": "C9",
+                "text": "<@UBOT> ping",
+                "ts": "1700000000.000100"
+            }
+        });
+        let msg = ch.inbound_from_event(&payload).await.unwrap();
+        assert_eq!(msg.content, "ping");
+        assert_eq!(msg.sender, "C9:1700000000.000100");
+    }
+
+    #[test]
+    fn blank_tokens_do_not_enable_socket_mode_or_events() {
+        let ch = SlackChannel::new("xoxb-fake".into(), None, vec![])
+            .with_app_token(Some("  ".into()))
+            .with_signing_secret(Some(String::new()));
+        assert!(ch.app_token.is_none());
+        assert!(ch.signing_secret().is_none());
+    }
+
+    #[test]
+    fn parse_files_extracts_media_attachment() {
+        let msg = serde_json::json!({
+            "files": [
+                {
+                    "id": "F123",
+                    "name": "report.pdf",
+                    "mimetype": "application/pdf",
+                    "url_private": "https://files.slack.com/files-pri/T/F/report.pdf"
+                }
+            ]
+        });
+
+        let attachments = parse_files(&msg);
+        assert_eq!(attachments.len(), 1);
+        assert_eq!(attachments[0].mime_type, "application/pdf");
+        assert_eq!(attachments[0].filename.as_deref(), Some("report.pdf"));
+        assert!(matches!(
+            &attachments[0].data,
+            MediaData::Url(url) if url.contains("files-pri")
+        ));
+    }
+
+    #[test]
+    fn parse_files_empty_array_returns_none() {
+        let msg = serde_json::json!({ "files": [] });
+        assert!(parse_files(&msg).is_empty());
+    }
+
+    #[test]
+    fn parse_files_missing_field_returns_none() {
+        let msg = serde_json::json!({ "text": "hello" });
+        assert!(parse_files(&msg).is_empty());
+    }
+
+    #[test]
+    fn parse_files_skips_entries_without_private_url() {
+        let msg = serde_json::json!({
+            "files": [
+                {"id": "F1", "name": "no_url.txt", "mimetype": "text/plain"},
+                {
+                    "id": "F2",
+                    "name": "with_url.txt",
+                    "mimetype": "text/plain",
+                    "url_private": "https://files.slack.com/files-pri/T/F/with_url.txt"
+                }
+            ]
+        });
+
+        let attachments = parse_files(&msg);
+        assert_eq!(attachments.len(), 1);
+        assert_eq!(attachments[0].filename.as_deref(), Some("with_url.txt"));
+    }
+
+    #[test]
+    fn parse_files_defaults_mime_type() {
+        let msg = serde_json::json!({
+            "files": [
+                {
+                    "id": "F123",
+                    "name": "blob.bin",
+                    "url_private": "https://files.slack.com/files-pri/T/F/blob.bin"
+                }
+            ]
+        });
+
+        let attachments = parse_files(&msg);
+        assert_eq!(attachments.len(), 1);
+        assert_eq!(attachments[0].mime_type, "application/octet-stream");
+    }
+
+    #[tokio::test]
+    async fn send_media_uses_files_upload_endpoint() {
+        let ch = SlackChannel::new("xoxb-fake".into(), Some("C12345".into()), vec!["*".into()]);
+        let attachment = MediaAttachment {
+            mime_type: "text/plain".to_string(),
+            data: MediaData::Bytes(b"hello".to_vec()),
+            filename: Some("note.txt".to_string()),
+        };
+
+        let err = ch
+            .send_media(&attachment, "C12345")
+            .await
+            .expect_err("network failure expected")
+            .to_string();
+        assert!(err.contains("files.upload") || err.contains("Slack"));
+    }
+}
@@ -0,0 +1,345 @@
+//! Turns Slack event payloads (Socket Mode envelopes and Events API
+//! requests share the same shape) and slash commands into
+//! [`ChannelMessage`]s.
+
+use crate::transport::channels::attachments::media_attachment_url;
+use crate::transport::channels::traits::{ChannelMessage, MediaAttachment};
+use serde_json::Value;
+use uuid::Uuid;
+
+/// Message subtypes that carry user content; edits, joins and deletions
+/// are ignored.
+const CONTENT_SUBTYPES: [&str; 2] = ["file_share", "thread_broadcast"];
+
+/// What the listener knows when deciding whether an event is for the bot.
+pub(super) struct InboundContext<'a> {
+    pub bot_user_id: &'a str,
+    /// The configured `channel_id`, where every message is answered.
+    pub home_channel: Option<&'a str>,
+}
+
+/// A message for the bot together with the Slack user who sent it, for the
+/// allowlist check.
+#[derive(Debug)]
+pub(super) struct Inbound {
+    pub user: String,
+    pub message: ChannelMessage,
+}
+
+/// Reply target for a message: the channel, or `channel:thread_ts` to answer
+/// inside a thread. Used as [`ChannelMessage::sender`] so replies land where
+/// the message was posted.
+pub fn reply_address(channel: &str, thread_ts: Option<&str>) -> String {
+    match thread_ts {
+        Some(thread_ts) => format!("{channel}:{thread_ts}"),
+        None => channel.to_string(),
+    }
+}
+
+/// Inverse of [`reply_address`].
+pub fn parse_reply_address(address: &str) -> (&str, Option<&str>) {
+    match address.split_once(':') {
+        Some((channel, thread_ts)) => (channel, Some(thread_ts)),
+        None => (address, None),
+    }
+}
+
+/// The bot user from an `event_callback` payload's `authorizations`.
+pub(super) fn authorized_bot_user(payload: &Value) -> Option<&str> {
+    payload
+        .get("authorizations")?
+        .as_array()?
+        .iter()
+        .find_map(|auth| auth.get("user_id").and_then(Value::as_str))
+}
+
+/// The message in an `event_callback` payload, when it is addressed to the
+/// bot: direct messages, anything in the home channel, and mentions
+/// elsewhere. Mentions outside threads are answered in a new thread.
+pub(super) fn message_from_event(payload: &Value, ctx: &InboundContext<'_>) -> Option<Inbound> {
+    let event = payload.get("event")?;
+    let kind = event.get("type").and_then(Value::as_str)?;
+    if event.get("bot_id").is_some() {
+        return None;
+    }
+    if let Some(subtype) = event.get("subtype").and_then(Value::as_str)
+        && !CONTENT_SUBTYPES.contains(&subtype)
+    {
+        return None;
+    }
+    let user = event.get("user").and_then(Value::as_str)?;
+    if user == ctx.bot_user_id {
+        return None;
+    }
+    let channel = event.get("channel").and_then(Value::as_str)?;
+    let ts = event.get("ts").and_then(Value::as_str)?;
+    let thread_ts = event.get("thread_ts").and_then(Value::as_str);
+
+    let is_direct =
+        event.get("channel_type").and_then(Value::as_str) == Some("im") || channel.starts_with('D');
+    let is_home = ctx.home_channel == Some(channel);
+    // Mentions in direct messages and the home channel also arrive as plain
+    // `message` events, so only one of the two is taken.
+    let thread_ts = match kind {
+        "message" if is_direct || is_home => thread_ts,
+        "app_mention" if !is_direct && !is_home => Some(thread_ts.unwrap_or(ts)),
+        _ => return None,
+    };
+
+    let text = event.get("text").and_then(Value::as_str).unwrap_or("");
+    let content = strip_mention(text, ctx.bot_user_id);
+    let attachments = parse_files(event);
+    if content.is_empty() && attachments.is_empty() {
+        return None;
+    }
+
+    let message = ChannelMessage {
+        id: Uuid::new_v4().to_string(),
+        sender: reply_address(channel, thread_ts),
+        author: Some(user.to_string()),
+        content,
+        channel: "slack".to_string(),
+        conversation_id: Some(channel.to_string()),
+        thread_id: thread_ts.map(ToString::to_string),
+        reply_to: None,
+        message_id: Some(ts.to_string()),
+        timestamp: ts_seconds(ts),
+        attachments,
+    };
+    Some(Inbound {
+        user: user.to_string(),
+        message,
+    })
+}
+
+/// A slash command invocation. `/ask <text>` sends the text as a plain
+/// message; other commands (`/usage`, `/new`, ...) pass through as chat
+/// commands.
+pub(super) fn message_from_slash_command(payload: &Value) -> Option<Inbound> {
+    let command = payload.get("command").and_then(Value::as_str)?;
+    let user = payload.get("user_id").and_then(Value::as_str)?;
+    let channel = payload.get("channel_id").and_then(Value::as_str)?;
+    let text = payload
+        .get("text")
+        .and_then(Value::as_str)
+        .unwrap_or("")
+        .trim();
+
+    let content = if command == "/ask" {
+        text.to_string()
+    } else {
+        format!("{command} {text}").trim_end().to_string()
+    };
+    if content.is_empty() {
+        return None;
+    }
+
+    let message = ChannelMessage {
+        id: Uuid::new_v4().to_string(),
+        sender: reply_address(channel, None),
+        author: Some(user.to_string()),
+        content,
+        channel: "slack".to_string(),
+        conversation_id: Some(channel.to_string()),
+        thread_id: None,
+        reply_to: None,
+        message_id: None,
+        timestamp: std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs(),
+        attachments: Vec::new(),
+    };
+    Some(Inbound {
+        user: user.to_string(),
+        message,
+    })
+}
+
+pub(super) fn parse_files(msg: &Value) -> Vec<MediaAttachment> {
+    msg.get("files")
+        .and_then(Value::as_array)
+        .map(|files| {
+            files
+                .iter()
+                .filter_map(|file| {
+                    let url = file.get("url_private").and_then(Value::as_str)?;
+                    let mime_type = file.get("mimetype").and_then(Value::as_str);
+                    let filename = file
+                        .get("name")
+                        .and_then(Value::as_str)
+                        .map(ToString::to_string);
+                    Some(media_attachment_url(url.to_string(), mime_type, filename))
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn strip_mention(text: &str, bot_user_id: &str) -> String {
+    if bot_user_id.is_empty() {
+        return text.trim().to_string();
+    }
+    text.replace(&format!("<@{bot_user_id}>"), "")
+        .trim()
+        .to_string()
+}
+
+/// Whole seconds of a Slack `ts` such as `1700000000.000100`.
+fn ts_seconds(ts: &str) -> u64 {
+    ts.split('.')
+        .next()
+        .and_then(|secs| secs.parse().ok())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CTX: InboundContext<'static> = InboundContext {
+        bot_user_id: "UBOT",
+        home_channel: Some("CHOME"),
+    };
+
+    fn message(payload: &Value) -> Option<ChannelMessage> {
+        message_from_event(payload, &CTX).map(|inbound| inbound.message)
+    }
+
+    fn callback(event: Value) -> Value {
+        let mut payload = serde_json::json!({
+            "type": "event_callback",
+            "authorizations": [{ "user_id": "UBOT" }]
+        });
+        payload["event"] = event;
+        payload
+    }
+
+    #[test]
+    fn reply_address_round_trips_threads() {
+        assert_eq!(reply_address("C1", None), "C1");
+        let threaded = reply_address("C1", Some("1700000000.000100"));
+        assert_eq!(
+            parse_reply_address(&threaded),
+            ("C1", Some("1700000000.000100"))
+        );
+        assert_eq!(parse_reply_address("D9"), ("D9", None));
+    }
+
+    #[test]
+    fn authorized_bot_user_reads_authorizations() {
+        assert_eq!(
+            authorized_bot_user(&callback(serde_json::json!({}))),
+            Some("UBOT")
+        );
+        assert_eq!(authorized_bot_user(&serde_json::json!({})), None);
+    }
+
+    #[test]
+    fn mention_in_other_channel_starts_a_thread_without_the_mention() {
+        let payload = callback(serde_json::json!({
+            "type": "app_mention",
+            "user": "U1",
+            "channel": "C2",
+            "text": "<@UBOT> summarize this",
+            "ts": "1700000000.000100"
+        }));
+        let msg = message(&payload).unwrap();
+        assert_eq!(msg.content, "summarize this");
+        assert_eq!(msg.sender, "C2:1700000000.000100");
+        assert_eq!(msg.conversation_id.as_deref(), Some("C2"));
+        assert_eq!(msg.thread_id.as_deref(), Some("1700000000.000100"));
+        assert_eq!(msg.timestamp, 1_700_000_000);
+    }
+
+    #[test]
+    fn thread_replies_keep_the_parent_thread() {
+        let payload = callback(serde_json::json!({
+            "type": "message",
+            "channel_type": "channel",
+            "user": "U1",
+            "channel": "CHOME",
+            "text": "and the follow-up",
+            "ts": "1700000050.000200",
+            "thread_ts": "1700000000.000100"
+        }));
+        let msg = message(&payload).unwrap();
+        assert_eq!(msg.sender, "CHOME:1700000000.000100");
+        assert_eq!(msg.thread_id.as_deref(), Some("1700000000.000100"));
+    }
+
+    #[test]
+    fn direct_messages_reply_inline() {
+        let payload = callback(serde_json::json!({
+            "type": "message",
+            "channel_type": "im",
+            "user": "U1",
+            "channel": "D1",
+            "text": "hello",
+            "ts": "1700000000.000100"
+        }));
+        let msg = message(&payload).unwrap();
+        assert_eq!(msg.sender, "D1");
+        assert!(msg.thread_id.is_none());
+    }
+
+    #[test]
+    fn duplicate_and_unaddressed_events_are_dropped() {
+        let event = |kind: &str, channel: &str| {
+            callback(serde_json::json!({
+                "type": kind,
+                "user": "U1",
+                "channel": channel,
+                "text": "<@UBOT> hi",
+                "ts": "1.0"
+            }))
+        };
+        // Plain chatter in other channels is not for the bot.
+        assert!(message(&event("message", "C2")).is_none());
+        // The home channel's `message` event already covers its mentions.
+        assert!(message(&event("app_mention", "CHOME")).is_none());
+        assert!(message(&event("app_mention", "D1")).is_none());
+    }
+
+    #[test]
+    fn bot_messages_and_edits_are_ignored() {
+        let base = serde_json::json!({
+            "type": "message",
+            "channel_type": "im",
+            "user": "U1",
+            "channel": "D1",
+            "text": "hi",
+            "ts": "1.0"
+        });
+        let with = |key: &str, value: Value| {
+            let mut event = base.clone();
+            event[key] = value;
+            callback(event)
+        };
+        assert!(message(&with("bot_id", "B1".into())).is_none());
+        assert!(message(&with("subtype", "message_changed".into())).is_none());
+        assert!(message(&with("user", "UBOT".into())).is_none());
+        assert!(message(&with("subtype", "file_share".into())).is_some());
+        let inbound = message_from_event(&with("user", "U2".into()), &CTX).unwrap();
+        assert_eq!(inbound.user, "U2");
+    }
+
+    #[test]
+    fn slash_commands_map_to_messages_and_chat_commands() {
+        let command = |command: &str, text: &str| {
+            serde_json::json!({
+                "command": command,
+                "text": text,
+                "user_id": "U1",
+                "channel_id": "C2"
+            })
+        };
+        let ask = message_from_slash_command(&command("/ask", "what's new?")).unwrap();
+        assert_eq!(ask.user, "U1");
+        assert_eq!(ask.message.content, "what's new?");
+        assert_eq!(ask.message.sender, "C2");
+        let usage = message_from_slash_command(&command("/usage", "")).unwrap();
+        assert_eq!(usage.message.content, "/usage");
+        assert!(message_from_slash_command(&command("/ask", "  ")).is_none());
+    }
+}
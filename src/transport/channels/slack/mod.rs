@@ -0,0 +1,5 @@
+mod channel;
+pub mod events;
+mod socket;
+
+pub use channel::SlackChannel;
@@ -0,0 +1,122 @@
+//! Slack Socket Mode protocol: opening a connection URL and reading the
+//! envelopes Slack pushes over the WebSocket.
+//! See: <https://api.slack.com/apis/socket-mode>
+
+use anyhow::Context;
+use serde_json::Value;
+
+/// One frame received over a Socket Mode connection.
+#[derive(Debug, Clone, PartialEq)]
+pub(super) enum SocketFrame {
+    Hello,
+    /// Slack is about to close the connection (refresh, server restart);
+    /// the client should open a new one.
+    Disconnect {
+        reason: String,
+    },
+    /// An event, slash command or interaction that must be acknowledged by
+    /// echoing `envelope_id` within three seconds.
+    Envelope {
+        envelope_id: String,
+        kind: String,
+        payload: Value,
+    },
+}
+
+pub(super) fn parse_frame(text: &str) -> Option<SocketFrame> {
+    let frame: Value = serde_json::from_str(text).ok()?;
+    match frame.get("type").and_then(Value::as_str)? {
+        "hello" => Some(SocketFrame::Hello),
+        "disconnect" => Some(SocketFrame::Disconnect {
+            reason: frame
+                .get("reason")
+                .and_then(Value::as_str)
+                .unwrap_or("unknown")
+                .to_string(),
+        }),
+        kind => Some(SocketFrame::Envelope {
+            envelope_id: frame
+                .get("envelope_id")
+                .and_then(Value::as_str)?
+                .to_string(),
+            kind: kind.to_string(),
+            payload: frame.get("payload").cloned().unwrap_or(Value::Null),
+        }),
+    }
+}
+
+pub(super) fn ack(envelope_id: &str) -> String {
+    serde_json::json!({ "envelope_id": envelope_id }).to_string()
+}
+
+/// A fresh `wss://` URL from `apps.connections.open`. Each URL accepts a
+/// single connection.
+pub(super) async fn open_connection(
+    client: &reqwest::Client,
+    app_token: &str,
+) -> anyhow::Result<String> {
+    let resp: Value = client
+        .post("https://slack.com/api/apps.connections.open")
+        .bearer_auth(app_token)
+        .send()
+        .await
+        .context("send Slack apps.connections.open request")?
+        .json()
+        .await
+        .context("parse Slack apps.connections.open response")?;
+
+    if resp.get("ok") != Some(&Value::Bool(true)) {
+        let err = resp
+            .get("error")
+            .and_then(Value::as_str)
+            .unwrap_or("unknown");
+        anyhow::bail!("Slack apps.connections.open failed: {err}");
+    }
+    resp.get("url")
+        .and_then(Value::as_str)
+        .map(ToString::to_string)
+        .ok_or_else(|| anyhow::anyhow!("Slack apps.connections.open returned no url"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_hello_disconnect_and_envelopes() {
+        assert_eq!(
+            parse_frame(r#"{"type":"hello","num_connections":1}"#),
+            Some(SocketFrame::Hello)
+        );
+        assert_eq!(
+            parse_frame(r#"{"type":"disconnect","reason":"refresh_requested"}"#),
+            Some(SocketFrame::Disconnect {
+                reason: "refresh_requested".into()
+            })
+        );
+        let envelope = parse_frame(
+            r#"{"type":"slash_commands","envelope_id":"e-1","payload":{"command":"/ask"}}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            envelope,
+            SocketFrame::Envelope {
+                envelope_id: "e-1".into(),
+                kind: "slash_commands".into(),
+                payload: serde_json::json!({ "command": "/ask" }),
+            }
+        );
+    }
+
+    #[test]
+    fn envelopes_without_an_id_and_garbage_are_skipped() {
+        assert!(parse_frame(r#"{"type":"events_api","payload":{}}"#).is_none());
+        assert!(parse_frame("not json").is_none());
+    }
+
+    #[test]
+    fn ack_echoes_the_envelope_id() {
+        let ack: Value = serde_json::from_str(&ack("e-7")).unwrap();
+        assert_eq!(ack, serde_json::json!({ "envelope_id": "e-7" }));
+    }
+}
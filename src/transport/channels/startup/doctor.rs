@@ -7,7 +7,7 @@ use super::super::factory;
 use super::super::health::{ChannelHealthState, classify_health_result};
 
 pub async fn doctor_channels(config: Arc<Config>) -> Result<()> {
-    let channels = factory::build_channels(config.channels_config.clone());
+    let channels = factory::build_channels(config.channels_config.clone(), &config.workspace_dir);
 
     if channels.is_empty() {
         println!("No channels configured. Run `asteroniris onboard` to set up channels.");
@@ -1,14 +1,21 @@
 use crate::config::Config;
+use crate::process::{ProcessEvent, run_cortex_loop};
 use anyhow::Result;
+use std::collections::{HashMap, VecDeque};
 use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Semaphore;
+use tokio::task::{Id, JoinHandle, JoinSet};
 
-use super::super::message_handler::handle_channel_message;
+use super::super::message_handler::{
+    branch_key, handle_channel_message, reply_busy, resolve_approval_reply,
+};
 use super::super::runtime::{channel_backoff_settings, spawn_supervised_listener};
 use super::super::traits::ChannelMessage;
-use super::runtime::init_channel_runtime;
+use super::runtime::{ChannelRuntime, init_channel_runtime};
 
 pub async fn start_channels(config: Arc<Config>) -> Result<()> {
-    let rt = init_channel_runtime(&config).await?;
+    let rt = Arc::new(init_channel_runtime(&config).await?);
 
     if rt.channels.is_empty() {
         println!("No channels configured. Run `asteroniris onboard` to set up.");
@@ -53,13 +60,263 @@ pub async fn start_channels(config: Arc<Config>) -> Result<()> {
     }
     drop(tx);
 
-    while let Some(msg) = rx.recv().await {
-        handle_channel_message(&rt, &msg).await;
+    let (shutdown_tx, shutdown_rx) = tokio::sync::watch::channel(false);
+    let background = spawn_process_tasks(&rt, shutdown_rx);
+
+    // Messages for the same conversation run one at a time, in arrival order;
+    // different conversations run concurrently, up to `max_concurrent_turns`.
+    // Approval replies are always resolved immediately so they can reach the
+    // run waiting for them.
+    let turns = Arc::new(Semaphore::new(
+        rt.config.process.max_concurrent_turns.max(1),
+    ));
+    let mut running = JoinSet::new();
+    let mut running_keys: HashMap<Id, String> = HashMap::new();
+    let mut queues = ConversationQueues::new(rt.config.process.max_pending_messages);
+    let mut open = true;
+    loop {
+        tokio::select! {
+            incoming = rx.recv(), if open => match incoming {
+                Some(msg) => {
+                    if resolve_approval_reply(&rt, &msg) {
+                        continue;
+                    }
+                    let key = branch_key(&msg);
+                    match queues.admit(&key, msg) {
+                        Admission::Start(msg) => {
+                            let handle = spawn_handler(&mut running, &rt, &turns, msg);
+                            running_keys.insert(handle, key);
+                        }
+                        Admission::Queued => {}
+                        Admission::Rejected(msg) => {
+                            tracing::warn!(channel = %msg.channel, "channel dispatcher is full; turning a message away");
+                            let rt = Arc::clone(&rt);
+                            tokio::spawn(async move { reply_busy(&rt, &msg).await });
+                        }
+                    }
+                }
+                None => open = false,
+            },
+            Some(done) = running.join_next_with_id() => {
+                let id = match done {
+                    Ok((id, ())) => id,
+                    Err(error) => {
+                        tracing::error!(%error, "channel message handler panicked");
+                        error.id()
+                    }
+                };
+                let Some(key) = running_keys.remove(&id) else {
+                    continue;
+                };
+                if let Some(next) = queues.finish(&key) {
+                    let handle = spawn_handler(&mut running, &rt, &turns, next);
+                    running_keys.insert(handle, key);
+                }
+            },
+            else => break,
+        }
     }
 
+    let _ = shutdown_tx.send(true);
+    for handle in background {
+        let _ = handle.await;
+    }
     for h in handles {
         let _ = h.await;
     }
 
     Ok(())
 }
+
+fn spawn_handler(
+    running: &mut JoinSet<()>,
+    rt: &Arc<ChannelRuntime>,
+    turns: &Arc<Semaphore>,
+    msg: ChannelMessage,
+) -> Id {
+    let rt = Arc::clone(rt);
+    let turns = Arc::clone(turns);
+    running
+        .spawn(async move {
+            let Ok(_permit) = turns.acquire_owned().await else {
+                return;
+            };
+            handle_channel_message(&rt, &msg).await;
+        })
+        .id()
+}
+
+/// What to do with an incoming message.
+enum Admission {
+    /// Its conversation is idle: run it now.
+    Start(ChannelMessage),
+    /// Its conversation is busy: it runs after the messages before it.
+    Queued,
+    /// Too many messages are pending: turn it away.
+    Rejected(ChannelMessage),
+}
+
+/// Per-conversation FIFO queues with a cap on the messages pending across
+/// all of them, counting the one running in each busy conversation.
+struct ConversationQueues {
+    queued: HashMap<String, VecDeque<ChannelMessage>>,
+    pending: usize,
+    limit: usize,
+}
+
+impl ConversationQueues {
+    fn new(limit: usize) -> Self {
+        Self {
+            queued: HashMap::new(),
+            pending: 0,
+            limit: limit.max(1),
+        }
+    }
+
+    fn admit(&mut self, key: &str, msg: ChannelMessage) -> Admission {
+        if self.pending >= self.limit {
+            return Admission::Rejected(msg);
+        }
+        self.pending += 1;
+        if let Some(waiting) = self.queued.get_mut(key) {
+            waiting.push_back(msg);
+            Admission::Queued
+        } else {
+            self.queued.insert(key.to_string(), VecDeque::new());
+            Admission::Start(msg)
+        }
+    }
+
+    /// The running message for `key` is done; returns the next one to run.
+    fn finish(&mut self, key: &str) -> Option<ChannelMessage> {
+        self.pending = self.pending.saturating_sub(1);
+        let next = self.queued.get_mut(key).and_then(VecDeque::pop_front);
+        if next.is_none() {
+            self.queued.remove(key);
+        }
+        next
+    }
+}
+
+/// Start the cortex, idle-branch eviction and process event logging; all
+/// stop when `shutdown` flips to `true`.
+fn spawn_process_tasks(
+    rt: &Arc<ChannelRuntime>,
+    shutdown: tokio::sync::watch::Receiver<bool>,
+) -> Vec<JoinHandle<()>> {
+    let settings = &rt.config.process;
+    let mut handles = Vec::with_capacity(3);
+
+    if settings.cortex_interval_secs > 0 {
+        handles.push(tokio::spawn(run_cortex_loop(
+            rt.process.deps().clone(),
+            rt.process.events().clone(),
+            Duration::from_secs(settings.cortex_interval_secs),
+            shutdown.clone(),
+        )));
+    }
+
+    let max_idle = Duration::from_secs(settings.branch_idle_secs.max(1));
+    let eviction_rt = Arc::clone(rt);
+    let mut eviction_shutdown = shutdown.clone();
+    handles.push(tokio::spawn(async move {
+        let mut ticker = tokio::time::interval((max_idle / 4).max(Duration::from_secs(1)));
+        loop {
+            tokio::select! {
+                _ = ticker.tick() => {
+                    let evicted = eviction_rt.process.evict_idle(max_idle);
+                    if !evicted.is_empty() {
+                        tracing::debug!(count = evicted.len(), "evicted idle conversation branches");
+                    }
+                }
+                _ = eviction_shutdown.changed() => {
+                    if *eviction_shutdown.borrow() { break; }
+                }
+            }
+        }
+    }));
+
+    let mut events = rt.process.events().subscribe();
+    let mut events_shutdown = shutdown;
+    handles.push(tokio::spawn(async move {
+        loop {
+            tokio::select! {
+                event = events.recv() => match event {
+                    Ok(ProcessEvent::Error { entity_id, message }) => {
+                        tracing::warn!(?entity_id, message, "channel process error");
+                    }
+                    Ok(event) => tracing::debug!(?event, "channel process event"),
+                    Err(tokio::sync::broadcast::error::RecvError::Lagged(skipped)) => {
+                        tracing::debug!(skipped, "channel process events lagged");
+                    }
+                    Err(tokio::sync::broadcast::error::RecvError::Closed) => break,
+                },
+                _ = events_shutdown.changed() => {
+                    if *events_shutdown.borrow() { break; }
+                }
+            }
+        }
+    }));
+
+    handles
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(content: &str) -> ChannelMessage {
+        ChannelMessage {
+            id: content.into(),
+            sender: "chat".into(),
+            author: None,
+            content: content.into(),
+            channel: "test".into(),
+            conversation_id: None,
+            thread_id: None,
+            reply_to: None,
+            message_id: None,
+            timestamp: 0,
+            attachments: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn a_conversation_runs_its_messages_in_order() {
+        let mut queues = ConversationQueues::new(8);
+        assert!(matches!(
+            queues.admit("a", message("1")),
+            Admission::Start(_)
+        ));
+        assert!(matches!(queues.admit("a", message("2")), Admission::Queued));
+        assert!(matches!(
+            queues.admit("b", message("3")),
+            Admission::Start(_)
+        ));
+        assert_eq!(queues.finish("a").unwrap().content, "2");
+        assert!(queues.finish("a").is_none());
+        assert!(matches!(
+            queues.admit("a", message("4")),
+            Admission::Start(_)
+        ));
+    }
+
+    #[test]
+    fn messages_past_the_limit_are_rejected_until_some_finish() {
+        let mut queues = ConversationQueues::new(2);
+        assert!(matches!(
+            queues.admit("a", message("1")),
+            Admission::Start(_)
+        ));
+        assert!(matches!(queues.admit("a", message("2")), Admission::Queued));
+        assert!(matches!(
+            queues.admit("b", message("3")),
+            Admission::Rejected(msg) if msg.content == "3"
+        ));
+        assert_eq!(queues.finish("a").unwrap().content, "2");
+        assert!(matches!(
+            queues.admit("b", message("3")),
+            Admission::Start(_)
+        ));
+    }
+}
@@ -1,9 +1,10 @@
 use crate::config::Config;
+use crate::plugins::skills::{enabled_skills, load_skills, skills_to_prompt};
 
 use super::super::prompt_builder::build_system_prompt;
 
 pub(super) fn build_channel_system_prompt(
-    _config: &Config,
+    config: &Config,
     workspace: &std::path::Path,
     model: &str,
 ) -> String {
@@ -12,5 +13,12 @@ pub(super) fn build_channel_system_prompt(
         .iter()
         .map(|(name, description)| (name.as_str(), description.as_str()))
         .collect();
-    build_system_prompt(workspace, model, &prompt_tool_descs)
+    let mut prompt = build_system_prompt(workspace, model, &prompt_tool_descs);
+    let skills = enabled_skills(load_skills(workspace), &config.skills);
+    prompt.push_str(&skills_to_prompt(&skills));
+    #[cfg(feature = "telegram")]
+    if config.channels_config.telegram.is_some() {
+        prompt.push_str(crate::transport::channels::telegram::BUTTONS_PROMPT);
+    }
+    prompt
 }
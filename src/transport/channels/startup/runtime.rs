@@ -1,10 +1,20 @@
-use crate::config::Config;
+use crate::config::{Config, DEFAULT_MODEL};
+use crate::llm::manager::LlmManager;
+use crate::llm::router::{ModelRouter, RoutedProviders};
 use crate::llm::traits::Provider;
+use crate::media::{MediaProcessor, MediaStore};
 use crate::memory::traits::Memory;
+use crate::process::compactor::CompactionThresholds;
+use crate::process::{AgentDeps, ChannelProcess, event_bus};
+use crate::runtime::usage::UsageRecorder;
+use crate::security::approval::PendingApprovals;
+use crate::security::permissions::PermissionStore;
 use crate::security::policy::{EntityRateLimiter, SecurityPolicy};
+use crate::session::SessionManager;
 use crate::tools::middleware::default_middleware_chain;
 use crate::tools::registry::ToolRegistry;
 use anyhow::Result;
+use arc_swap::ArcSwap;
 use std::collections::HashMap;
 use std::sync::Arc;
 
@@ -17,23 +27,34 @@ pub(in super::super) struct ChannelRuntime {
     pub(in super::super) config: Arc<Config>,
     pub(in super::super) security: Arc<SecurityPolicy>,
     pub(in super::super) provider: Arc<dyn Provider>,
+    /// Picks a provider and model per turn; `None` when `[routing]` is off.
+    pub(in super::super) router: Option<ModelRouter>,
+    pub(in super::super) routed_providers: RoutedProviders,
     pub(in super::super) registry: Arc<ToolRegistry>,
     pub(in super::super) rate_limiter: Arc<EntityRateLimiter>,
     pub(in super::super) model: String,
     pub(in super::super) temperature: f64,
     pub(in super::super) mem: Arc<dyn Memory>,
-    pub(in super::super) system_prompt: String,
     pub(in super::super) channels: Vec<Arc<dyn Channel>>,
     pub(in super::super) channel_policies: HashMap<String, ChannelPolicy>,
+    pub(in super::super) media_store: Option<Arc<MediaStore>>,
+    pub(in super::super) media_processor: MediaProcessor,
+    pub(in super::super) approvals: Arc<PendingApprovals>,
+    pub(in super::super) permissions: Arc<PermissionStore>,
+    pub(in super::super) sessions: Option<Arc<SessionManager>>,
+    /// Per-turn token and cost accounting; `None` when `[usage]` is off.
+    pub(in super::super) usage: Option<Arc<UsageRecorder>>,
+    pub(in super::super) process: ChannelProcess,
 }
 
 #[allow(clippy::too_many_lines)]
 pub(super) async fn init_channel_runtime(config: &Arc<Config>) -> Result<ChannelRuntime> {
     let config_api_key = config.api_key.clone();
+    let provider_name = config.default_provider.as_deref().unwrap_or("openrouter");
     let provider: Arc<dyn Provider> = Arc::from(
         crate::llm::factory::create_resilient_provider_with_oauth_recovery(
             config,
-            config.default_provider.as_deref().unwrap_or("openrouter"),
+            provider_name,
             &config.reliability,
             move |name| crate::llm::factory::resolve_api_key(name, config_api_key.as_deref()),
         )?,
@@ -46,12 +67,23 @@ pub(super) async fn init_channel_runtime(config: &Arc<Config>) -> Result<Channel
     let model = config
         .default_model
         .clone()
-        .unwrap_or_else(|| "anthropic/claude-sonnet-4-20250514".into());
+        .unwrap_or_else(|| DEFAULT_MODEL.into());
     let temperature = config.default_temperature;
+    let router = config.routing.enabled.then(|| {
+        ModelRouter::new(
+            &config.routing,
+            &config.usage.pricing,
+            provider_name,
+            &model,
+        )
+    });
+    let routed_providers =
+        RoutedProviders::new(Arc::clone(config), provider_name, Arc::clone(&provider));
     let security = Arc::new(SecurityPolicy::from_config(
         &config.autonomy,
         &config.workspace_dir,
     ));
+    let usage = UsageRecorder::open_if_enabled(config, Arc::clone(&security)).await;
     let rate_limiter = Arc::new(EntityRateLimiter::new(
         config.autonomy.max_actions_per_hour,
         config.autonomy.max_actions_per_entity_per_hour,
@@ -66,18 +98,58 @@ pub(super) async fn init_channel_runtime(config: &Arc<Config>) -> Result<Channel
         .await?,
     );
 
-    let tools = crate::tools::all_tools(Arc::clone(&mem));
+    let mut tools = crate::tools::all_tools(Arc::clone(&mem));
+    tools.extend(crate::plugins::skills::load_skill_tools(
+        &config.workspace_dir,
+        &config.skills,
+    ));
     let mut registry = ToolRegistry::new(default_middleware_chain());
     for tool in tools {
         registry.register(tool);
     }
+    let registry = Arc::new(registry);
 
     let workspace = config.workspace_dir.clone();
     let system_prompt = build_channel_system_prompt(config, &workspace, &model);
 
+    let media_store = if config.media.enabled {
+        match MediaStore::new(&config.media, &config.workspace_dir).await {
+            Ok(store) => Some(Arc::new(store)),
+            Err(error) => {
+                tracing::warn!(%error, "media store unavailable; attachments will not be stored");
+                None
+            }
+        }
+    } else {
+        None
+    };
+    let media_processor = MediaProcessor::with_provider(Arc::clone(&provider), model.clone());
+
+    let sessions = SessionManager::open_if_enabled(
+        &config.workspace_dir,
+        &config.session,
+        Arc::clone(&provider),
+        &model,
+    )
+    .await;
+
+    let deps = AgentDeps::new(
+        Arc::clone(config),
+        Arc::new(LlmManager::new(Arc::new(ArcSwap::new(Arc::clone(config))))),
+        Arc::clone(&mem),
+        Arc::clone(&security),
+        Arc::clone(&registry),
+    );
+    let (events, _) = event_bus(64);
+    let process = ChannelProcess::new(deps, events, system_prompt, model.clone(), temperature)
+        .with_compaction_thresholds(CompactionThresholds {
+            max_context_tokens: config.process.max_context_tokens,
+            ..CompactionThresholds::default()
+        });
+
     let mut channels: Vec<Arc<dyn Channel>> = Vec::new();
     let mut channel_policies = HashMap::new();
-    for entry in factory::build_channels(config.channels_config.clone()) {
+    for entry in factory::build_channels(config.channels_config.clone(), &config.workspace_dir) {
         channel_policies.insert(entry.channel.name().to_string(), entry.policy);
         channels.push(entry.channel);
     }
@@ -86,13 +158,21 @@ pub(super) async fn init_channel_runtime(config: &Arc<Config>) -> Result<Channel
         config: Arc::clone(config),
         security,
         provider,
-        registry: Arc::new(registry),
+        router,
+        routed_providers,
+        registry,
         rate_limiter,
         model,
         temperature,
         mem,
-        system_prompt,
         channels,
         channel_policies,
+        media_store,
+        media_processor,
+        approvals: Arc::new(PendingApprovals::default()),
+        permissions: Arc::new(PermissionStore::load(&config.workspace_dir)),
+        sessions,
+        usage,
+        process,
     })
 }
@@ -1,6 +1,6 @@
-use super::TelegramChannel;
+use super::{TelegramChannel, chat_body, chat_form};
 use anyhow::Context;
-use reqwest::multipart::{Form, Part};
+use reqwest::multipart::Part;
 use std::path::Path;
 
 impl TelegramChannel {
@@ -21,9 +21,7 @@ impl TelegramChannel {
             .context("read file for Telegram document")?;
         let part = Part::bytes(file_bytes).file_name(file_name.to_string());
 
-        let mut form = Form::new()
-            .text("chat_id", chat_id.to_string())
-            .part("document", part);
+        let mut form = chat_form(chat_id).part("document", part);
 
         if let Some(cap) = caption {
             form = form.text("caption", cap.to_string());
@@ -56,9 +54,7 @@ impl TelegramChannel {
     ) -> anyhow::Result<()> {
         let part = Part::bytes(file_bytes).file_name(file_name.to_string());
 
-        let mut form = Form::new()
-            .text("chat_id", chat_id.to_string())
-            .part("document", part);
+        let mut form = chat_form(chat_id).part("document", part);
 
         if let Some(cap) = caption {
             form = form.text("caption", cap.to_string());
@@ -98,9 +94,7 @@ impl TelegramChannel {
             .context("read file for Telegram photo")?;
         let part = Part::bytes(file_bytes).file_name(file_name.to_string());
 
-        let mut form = Form::new()
-            .text("chat_id", chat_id.to_string())
-            .part("photo", part);
+        let mut form = chat_form(chat_id).part("photo", part);
 
         if let Some(cap) = caption {
             form = form.text("caption", cap.to_string());
@@ -133,9 +127,7 @@ impl TelegramChannel {
     ) -> anyhow::Result<()> {
         let part = Part::bytes(file_bytes).file_name(file_name.to_string());
 
-        let mut form = Form::new()
-            .text("chat_id", chat_id.to_string())
-            .part("photo", part);
+        let mut form = chat_form(chat_id).part("photo", part);
 
         if let Some(cap) = caption {
             form = form.text("caption", cap.to_string());
@@ -175,9 +167,7 @@ impl TelegramChannel {
             .context("read file for Telegram video")?;
         let part = Part::bytes(file_bytes).file_name(file_name.to_string());
 
-        let mut form = Form::new()
-            .text("chat_id", chat_id.to_string())
-            .part("video", part);
+        let mut form = chat_form(chat_id).part("video", part);
 
         if let Some(cap) = caption {
             form = form.text("caption", cap.to_string());
@@ -209,9 +199,7 @@ impl TelegramChannel {
     ) -> anyhow::Result<()> {
         let part = Part::bytes(file_bytes).file_name(file_name.to_string());
 
-        let mut form = Form::new()
-            .text("chat_id", chat_id.to_string())
-            .part("video", part);
+        let mut form = chat_form(chat_id).part("video", part);
 
         if let Some(cap) = caption {
             form = form.text("caption", cap.to_string());
@@ -251,9 +239,7 @@ impl TelegramChannel {
             .context("read file for Telegram audio")?;
         let part = Part::bytes(file_bytes).file_name(file_name.to_string());
 
-        let mut form = Form::new()
-            .text("chat_id", chat_id.to_string())
-            .part("audio", part);
+        let mut form = chat_form(chat_id).part("audio", part);
 
         if let Some(cap) = caption {
             form = form.text("caption", cap.to_string());
@@ -285,9 +271,7 @@ impl TelegramChannel {
     ) -> anyhow::Result<()> {
         let part = Part::bytes(file_bytes).file_name(file_name.to_string());
 
-        let mut form = Form::new()
-            .text("chat_id", chat_id.to_string())
-            .part("audio", part);
+        let mut form = chat_form(chat_id).part("audio", part);
 
         if let Some(cap) = caption {
             form = form.text("caption", cap.to_string());
@@ -327,9 +311,7 @@ impl TelegramChannel {
             .context("read file for Telegram voice")?;
         let part = Part::bytes(file_bytes).file_name(file_name.to_string());
 
-        let mut form = Form::new()
-            .text("chat_id", chat_id.to_string())
-            .part("voice", part);
+        let mut form = chat_form(chat_id).part("voice", part);
 
         if let Some(cap) = caption {
             form = form.text("caption", cap.to_string());
@@ -359,10 +341,8 @@ impl TelegramChannel {
         url: &str,
         caption: Option<&str>,
     ) -> anyhow::Result<()> {
-        let mut body = serde_json::json!({
-            "chat_id": chat_id,
-            "document": url
-        });
+        let mut body = chat_body(chat_id);
+        body["document"] = serde_json::Value::String(url.to_string());
 
         if let Some(cap) = caption {
             body["caption"] = serde_json::Value::String(cap.to_string());
@@ -392,10 +372,8 @@ impl TelegramChannel {
         url: &str,
         caption: Option<&str>,
     ) -> anyhow::Result<()> {
-        let mut body = serde_json::json!({
-            "chat_id": chat_id,
-            "photo": url
-        });
+        let mut body = chat_body(chat_id);
+        body["photo"] = serde_json::Value::String(url.to_string());
 
         if let Some(cap) = caption {
             body["caption"] = serde_json::Value::String(cap.to_string());
@@ -424,10 +402,8 @@ impl TelegramChannel {
         url: &str,
         caption: Option<&str>,
     ) -> anyhow::Result<()> {
-        let mut body = serde_json::json!({
-            "chat_id": chat_id,
-            "audio": url
-        });
+        let mut body = chat_body(chat_id);
+        body["audio"] = serde_json::Value::String(url.to_string());
 
         if let Some(cap) = caption {
             body["caption"] = serde_json::Value::String(cap.to_string());
@@ -456,10 +432,8 @@ impl TelegramChannel {
         url: &str,
         caption: Option<&str>,
     ) -> anyhow::Result<()> {
-        let mut body = serde_json::json!({
-            "chat_id": chat_id,
-            "video": url
-        });
+        let mut body = chat_body(chat_id);
+        body["video"] = serde_json::Value::String(url.to_string());
 
         if let Some(cap) = caption {
             body["caption"] = serde_json::Value::String(cap.to_string());
@@ -0,0 +1,123 @@
+//! Reply buttons the agent asks for with `[[Label]]` markup.
+//!
+//! Trailing lines made only of `[[Label]]` items become an inline keyboard,
+//! one row per line. Pressing a button sends its label back as the user's
+//! next message.
+
+use serde_json::Value;
+
+/// System prompt section teaching the agent the markup.
+pub const BUTTONS_PROMPT: &str = "## Telegram Buttons\n\n\
+    To offer choices in Telegram, end the reply with lines of `[[Label]]` items \
+    (e.g. `[[Yes]] [[No]]`); each line becomes a row of buttons. \
+    The label of the pressed button arrives as the user's next message.\n\n";
+
+const CHOICE_PREFIX: &str = "choice:";
+/// Telegram rejects `callback_data` longer than 64 bytes.
+const MAX_CALLBACK_DATA_BYTES: usize = 64;
+
+/// Split `message` into its text and the inline keyboard described by its
+/// trailing `[[Label]]` lines, if any.
+pub(super) fn split_buttons(message: &str) -> (&str, Option<Value>) {
+    let mut rows = Vec::new();
+    let mut text_end = message.trim_end().len();
+    for line in message[..text_end].lines().rev() {
+        let Some(labels) = parse_button_line(line) else {
+            break;
+        };
+        rows.push(labels);
+        text_end = message[..text_end]
+            .trim_end()
+            .strip_suffix(line.trim_end())
+            .map_or(0, str::len);
+    }
+    // Telegram cannot send buttons without text.
+    if rows.is_empty() || text_end == 0 {
+        return (message, None);
+    }
+    rows.reverse();
+    let keyboard: Vec<Value> = rows
+        .into_iter()
+        .map(|labels| {
+            labels
+                .into_iter()
+                .map(|label| {
+                    serde_json::json!({
+                        "text": label,
+                        "callback_data": choice_callback_data(label),
+                    })
+                })
+                .collect()
+        })
+        .collect();
+    (
+        message[..text_end].trim_end(),
+        Some(serde_json::json!({ "inline_keyboard": keyboard })),
+    )
+}
+
+/// The labels of a line like `[[Yes]] [[No]]`; `None` for any other line.
+fn parse_button_line(line: &str) -> Option<Vec<&str>> {
+    let mut rest = line.trim();
+    let mut labels = Vec::new();
+    while !rest.is_empty() {
+        let inner = rest.strip_prefix("[[")?;
+        let (label, after) = inner.split_once("]]")?;
+        let label = label.trim();
+        if label.is_empty() {
+            return None;
+        }
+        labels.push(label);
+        rest = after.trim_start();
+    }
+    (!labels.is_empty()).then_some(labels)
+}
+
+/// `choice:<label>`, cut at a character boundary to fit Telegram's limit.
+fn choice_callback_data(label: &str) -> String {
+    let mut end = label
+        .len()
+        .min(MAX_CALLBACK_DATA_BYTES - CHOICE_PREFIX.len());
+    while !label.is_char_boundary(end) {
+        end -= 1;
+    }
+    format!("{CHOICE_PREFIX}{}", &label[..end])
+}
+
+/// The label of a pressed choice button.
+pub(super) fn choice_callback_label(data: &str) -> Option<&str> {
+    data.strip_prefix(CHOICE_PREFIX)
+        .filter(|label| !label.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn trailing_button_lines_become_keyboard_rows() {
+        let (text, keyboard) = split_buttons("Deploy now?\n\n[[Yes]] [[No]]\n[[Ask me later]]\n");
+        assert_eq!(text, "Deploy now?");
+        let rows = keyboard.unwrap()["inline_keyboard"].clone();
+        assert_eq!(rows[0][0]["text"], "Yes");
+        assert_eq!(rows[0][1]["callback_data"], "choice:No");
+        assert_eq!(rows[1][0]["text"], "Ask me later");
+    }
+
+    #[test]
+    fn buttons_inside_the_text_are_left_alone() {
+        let message = "Write [[Yes]] to confirm.\nThanks";
+        assert_eq!(split_buttons(message), (message, None));
+        let message = "[[Yes]]\nThen continue.";
+        assert_eq!(split_buttons(message), (message, None));
+    }
+
+    #[test]
+    fn long_labels_are_cut_to_the_callback_limit() {
+        let label = "é".repeat(40);
+        let data = choice_callback_data(&label);
+        assert!(data.len() <= MAX_CALLBACK_DATA_BYTES);
+        assert!(choice_callback_label(&data).unwrap().starts_with('é'));
+        assert_eq!(choice_callback_label("approval:approve:1"), None);
+    }
+}
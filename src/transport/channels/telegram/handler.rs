@@ -1,9 +1,16 @@
-use super::TelegramChannel;
+use super::buttons::split_buttons;
+use super::{TelegramChannel, chat_body, parse_topic_address};
+use crate::security::approval::{ApprovalDecision, ApprovalRequest};
+use crate::transport::channels::approval::approval_callback_data;
 use crate::transport::channels::traits::{Channel, ChannelMessage, MediaAttachment, MediaData};
+use crate::transport::channels::webhook_inbox::webhook_inbox;
+use anyhow::Context;
 use serde_json::Value;
 use std::future::Future;
 use std::pin::Pin;
-use uuid::Uuid;
+
+/// Update kinds the bot subscribes to, polled or pushed.
+const ALLOWED_UPDATES: [&str; 2] = ["message", "callback_query"];
 
 impl TelegramChannel {
     pub(crate) fn telegram_file_url(&self, file_path: &str) -> String {
@@ -135,6 +142,98 @@ impl TelegramChannel {
     }
 }
 
+impl TelegramChannel {
+    /// Inline keyboard offering the decisions available for `request`.
+    pub(crate) fn approval_keyboard(request: &ApprovalRequest) -> Value {
+        let mut decisions = vec![ApprovalDecision::Approve];
+        if request.can_remember() {
+            decisions.push(ApprovalDecision::ApproveAlways);
+        }
+        decisions.push(ApprovalDecision::Deny);
+        let buttons: Vec<Value> = decisions
+            .into_iter()
+            .map(|decision| {
+                let label = match decision {
+                    ApprovalDecision::Approve => "Approve",
+                    ApprovalDecision::ApproveAlways => "Always allow",
+                    ApprovalDecision::Deny => "Deny",
+                };
+                serde_json::json!({
+                    "text": label,
+                    "callback_data": approval_callback_data(decision, &request.id),
+                })
+            })
+            .collect();
+        serde_json::json!({ "inline_keyboard": [buttons] })
+    }
+
+    /// Call a Bot API method and return its `result`.
+    async fn post_method(&self, method: &str, body: &Value) -> anyhow::Result<Value> {
+        let resp = self
+            .client
+            .post(self.api_url(method))
+            .json(body)
+            .send()
+            .await?;
+
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let err = resp
+                .text()
+                .await
+                .unwrap_or_else(|e| format!("<failed to read response body: {e}>"));
+            anyhow::bail!("Telegram {method} failed ({status}): {err}");
+        }
+
+        let data: Value = resp.json().await?;
+        Ok(data.get("result").cloned().unwrap_or(Value::Null))
+    }
+
+    /// Like [`Self::post_method`], but resends as plain text when Telegram
+    /// rejects the Markdown (common for model output and partial streams).
+    async fn post_text(&self, method: &str, body: &Value) -> anyhow::Result<Value> {
+        match self.post_method(method, body).await {
+            Err(error)
+                if body.get("parse_mode").is_some()
+                    && error.to_string().contains("can't parse entities") =>
+            {
+                let mut plain = body.clone();
+                if let Some(fields) = plain.as_object_mut() {
+                    fields.remove("parse_mode");
+                }
+                self.post_method(method, &plain).await
+            }
+            result => result,
+        }
+    }
+
+    /// Register the gateway's `/telegram/webhook` and forward the updates it
+    /// receives into `tx`.
+    async fn listen_webhook(
+        &self,
+        url: &str,
+        tx: &tokio::sync::mpsc::Sender<ChannelMessage>,
+    ) -> anyhow::Result<()> {
+        let Some(secret) = self.webhook_secret.as_deref() else {
+            anyhow::bail!(
+                "Telegram webhook mode needs `webhook_secret` so the gateway can verify updates"
+            );
+        };
+        self.post_method(
+            "setWebhook",
+            &serde_json::json!({
+                "url": url,
+                "secret_token": secret,
+                "allowed_updates": ALLOWED_UPDATES,
+            }),
+        )
+        .await?;
+        tracing::info!("Telegram channel active (webhook mode): updates arrive at {url}");
+        webhook_inbox("telegram").drain_into(tx).await;
+        Ok(())
+    }
+}
+
 impl Channel for TelegramChannel {
     fn name(&self) -> &str {
         "telegram"
@@ -150,28 +249,73 @@ impl Channel for TelegramChannel {
         chat_id: &'a str,
     ) -> Pin<Box<dyn Future<Output = anyhow::Result<()>> + Send + 'a>> {
         Box::pin(async move {
-            let body = serde_json::json!({
+            let (text, keyboard) = split_buttons(message);
+            let mut body = chat_body(chat_id);
+            body["text"] = Value::String(text.to_string());
+            body["parse_mode"] = Value::String("Markdown".to_string());
+            if let Some(keyboard) = keyboard {
+                body["reply_markup"] = keyboard;
+            }
+            self.post_text("sendMessage", &body).await?;
+            Ok(())
+        })
+    }
+
+    fn send_draft<'a>(
+        &'a self,
+        message: &'a str,
+        chat_id: &'a str,
+    ) -> Pin<Box<dyn Future<Output = anyhow::Result<Option<String>>> + Send + 'a>> {
+        Box::pin(async move {
+            let mut body = chat_body(chat_id);
+            body["text"] = Value::String(message.to_string());
+            let sent = self.post_method("sendMessage", &body).await?;
+            Ok(sent
+                .get("message_id")
+                .and_then(Value::as_i64)
+                .map(|id| id.to_string()))
+        })
+    }
+
+    fn edit_message<'a>(
+        &'a self,
+        chat_id: &'a str,
+        message_id: &'a str,
+        content: &'a str,
+    ) -> Pin<Box<dyn Future<Output = anyhow::Result<()>> + Send + 'a>> {
+        Box::pin(async move {
+            let (chat_id, _) = parse_topic_address(chat_id);
+            let message_id: i64 = message_id
+                .parse()
+                .with_context(|| format!("invalid Telegram message id: {message_id}"))?;
+            let (text, keyboard) = split_buttons(content);
+            let mut body = serde_json::json!({
                 "chat_id": chat_id,
-                "text": message,
-                "parse_mode": "Markdown"
+                "message_id": message_id,
+                "text": text,
+                "parse_mode": "Markdown",
             });
-
-            let resp = self
-                .client
-                .post(self.api_url("sendMessage"))
-                .json(&body)
-                .send()
-                .await?;
-
-            if !resp.status().is_success() {
-                let status = resp.status();
-                let err = resp
-                    .text()
-                    .await
-                    .unwrap_or_else(|e| format!("<failed to read response body: {e}>"));
-                anyhow::bail!("Telegram sendMessage failed ({status}): {err}");
+            if let Some(keyboard) = keyboard {
+                body["reply_markup"] = keyboard;
             }
+            match self.post_text("editMessageText", &body).await {
+                // Streaming can repeat the text already shown.
+                Err(error) if error.to_string().contains("message is not modified") => Ok(()),
+                result => result.map(|_| ()),
+            }
+        })
+    }
 
+    fn send_approval_request<'a>(
+        &'a self,
+        request: &'a ApprovalRequest,
+        chat_id: &'a str,
+    ) -> Pin<Box<dyn Future<Output = anyhow::Result<()>> + Send + 'a>> {
+        Box::pin(async move {
+            let mut body = chat_body(chat_id);
+            body["text"] = Value::String(format!("Approval needed: {}", request.summary()));
+            body["reply_markup"] = Self::approval_keyboard(request);
+            self.post_method("sendMessage", &body).await?;
             Ok(())
         })
     }
@@ -181,6 +325,15 @@ impl Channel for TelegramChannel {
         tx: tokio::sync::mpsc::Sender<ChannelMessage>,
     ) -> Pin<Box<dyn Future<Output = anyhow::Result<()>> + Send + 'a>> {
         Box::pin(async move {
+            if let Some(url) = &self.webhook_url {
+                return self.listen_webhook(url, &tx).await;
+            }
+
+            // getUpdates is refused while a webhook is registered, e.g.
+            // after switching back from webhook mode.
+            self.post_best_effort("deleteWebhook", &serde_json::json!({}))
+                .await;
+
             let mut offset: i64 = 0;
 
             tracing::info!("Telegram channel listening for messages...");
@@ -190,7 +343,7 @@ impl Channel for TelegramChannel {
                 let body = serde_json::json!({
                     "offset": offset,
                     "timeout": 30,
-                    "allowed_updates": ["message"]
+                    "allowed_updates": ALLOWED_UPDATES
                 });
 
                 let resp = match self.client.post(&url).json(&body).send().await {
@@ -220,71 +373,9 @@ impl Channel for TelegramChannel {
                             offset = uid + 1;
                         }
 
-                        let Some(message) = update.get("message") else {
-                            continue;
-                        };
-
-                        let username_opt = message
-                            .get("from")
-                            .and_then(|f| f.get("username"))
-                            .and_then(|u| u.as_str());
-                        let username = username_opt.unwrap_or("unknown");
-
-                        let user_id = message
-                            .get("from")
-                            .and_then(|f| f.get("id"))
-                            .and_then(serde_json::Value::as_i64);
-                        let user_id_str = user_id.map(|id| id.to_string());
-
-                        let mut identities = vec![username];
-                        if let Some(ref id) = user_id_str {
-                            identities.push(id.as_str());
-                        }
-
-                        if !self.is_any_user_allowed(identities.iter().copied()) {
-                            tracing::warn!(
-                                "Telegram: ignoring message from unauthorized user: username={username}, user_id={}. \
- Allowlist Telegram @username or numeric user ID, then run `asteroniris onboard --channels-only`.",
-                                user_id_str.as_deref().unwrap_or("unknown")
-                            );
-                            continue;
-                        }
-
-                        let text = message
-                            .get("text")
-                            .and_then(serde_json::Value::as_str)
-                            .or_else(|| message.get("caption").and_then(serde_json::Value::as_str))
-                            .unwrap_or("");
-
-                        let attachments = self.parse_telegram_attachments(message).await;
-                        if text.is_empty() && attachments.is_empty() {
-                            continue;
-                        }
-
-                        let chat_id = message
-                            .get("chat")
-                            .and_then(|c| c.get("id"))
-                            .and_then(serde_json::Value::as_i64)
-                            .map(|id| id.to_string())
-                            .unwrap_or_default();
-
-                        let msg = ChannelMessage {
-                            id: Uuid::new_v4().to_string(),
-                            sender: chat_id,
-                            content: text.to_string(),
-                            channel: "telegram".to_string(),
-                            conversation_id: None,
-                            thread_id: None,
-                            reply_to: None,
-                            message_id: None,
-                            timestamp: std::time::SystemTime::now()
-                                .duration_since(std::time::UNIX_EPOCH)
-                                .unwrap_or_default()
-                                .as_secs(),
-                            attachments,
-                        };
-
-                        if tx.send(msg).await.is_err() {
+                        if let Some(msg) = self.inbound_from_update(update).await
+                            && tx.send(msg).await.is_err()
+                        {
                             return Ok(());
                         }
                     }
@@ -299,8 +390,7 @@ impl Channel for TelegramChannel {
                 .get(self.api_url("getMe"))
                 .send()
                 .await
-                .map(|r| r.status().is_success())
-                .unwrap_or(false)
+                .is_ok_and(|r| r.status().is_success())
         })
     }
 
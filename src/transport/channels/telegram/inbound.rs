@@ -0,0 +1,201 @@
+//! Turns Bot API updates into [`ChannelMessage`]s, for both `getUpdates`
+//! polling and the gateway's webhook.
+//! See: <https://core.telegram.org/bots/api#update>
+
+use super::buttons::choice_callback_label;
+use super::{TelegramChannel, topic_address};
+use crate::transport::channels::approval::approval_callback_reply;
+use crate::transport::channels::traits::ChannelMessage;
+use serde_json::Value;
+use uuid::Uuid;
+
+/// The forum topic a message was posted in. Replies in ordinary groups also
+/// carry `message_thread_id`, so only topic messages count.
+fn message_topic(message: &Value) -> Option<i64> {
+    if message.get("is_topic_message").and_then(Value::as_bool) != Some(true) {
+        return None;
+    }
+    message.get("message_thread_id").and_then(Value::as_i64)
+}
+
+fn chat_id(message: &Value) -> Option<String> {
+    message
+        .get("chat")
+        .and_then(|c| c.get("id"))
+        .and_then(Value::as_i64)
+        .map(|id| id.to_string())
+}
+
+fn now_secs() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+impl TelegramChannel {
+    /// The message carried by `update`, if it is one the bot should answer.
+    pub async fn inbound_from_update(&self, update: &Value) -> Option<ChannelMessage> {
+        if let Some(callback) = update.get("callback_query") {
+            return self.callback_message(callback).await;
+        }
+        let message = update.get("message")?;
+
+        let username_opt = message
+            .get("from")
+            .and_then(|f| f.get("username"))
+            .and_then(|u| u.as_str());
+        let username = username_opt.unwrap_or("unknown");
+
+        let user_id = message
+            .get("from")
+            .and_then(|f| f.get("id"))
+            .and_then(Value::as_i64);
+        let user_id_str = user_id.map(|id| id.to_string());
+
+        let mut identities = vec![username];
+        if let Some(ref id) = user_id_str {
+            identities.push(id.as_str());
+        }
+
+        if !self.is_any_user_allowed(identities.iter().copied()) {
+            tracing::warn!(
+                "Telegram: ignoring message from unauthorized user: username={username}, user_id={}. \
+ Allowlist Telegram @username or numeric user ID, then run `asteroniris onboard --channels-only`.",
+                user_id_str.as_deref().unwrap_or("unknown")
+            );
+            return None;
+        }
+
+        let text = message
+            .get("text")
+            .and_then(Value::as_str)
+            .or_else(|| message.get("caption").and_then(Value::as_str))
+            .unwrap_or("");
+
+        let attachments = self.parse_telegram_attachments(message).await;
+        if text.is_empty() && attachments.is_empty() {
+            return None;
+        }
+
+        let chat_id = chat_id(message).unwrap_or_default();
+        let topic = message_topic(message);
+
+        Some(ChannelMessage {
+            id: Uuid::new_v4().to_string(),
+            sender: topic_address(&chat_id, topic),
+            author: user_id_str,
+            content: text.to_string(),
+            channel: "telegram".to_string(),
+            conversation_id: Some(chat_id),
+            thread_id: topic.map(|topic| topic.to_string()),
+            reply_to: None,
+            message_id: message
+                .get("message_id")
+                .and_then(Value::as_i64)
+                .map(|id| id.to_string()),
+            timestamp: now_secs(),
+            attachments,
+        })
+    }
+
+    /// Turn a button press (an approval decision or one of the agent's
+    /// `[[Label]]` choices) into a text reply from its chat.
+    pub(crate) async fn callback_message(&self, callback: &Value) -> Option<ChannelMessage> {
+        let data = callback.get("data").and_then(Value::as_str)?;
+        let approval = approval_callback_reply(data);
+        let choice = choice_callback_label(data);
+        if approval.is_none() && choice.is_none() {
+            return None;
+        }
+
+        let from = callback.get("from");
+        let username = from
+            .and_then(|f| f.get("username"))
+            .and_then(Value::as_str)
+            .unwrap_or("unknown");
+        let user_id = from
+            .and_then(|f| f.get("id"))
+            .and_then(Value::as_i64)
+            .map(|id| id.to_string());
+        let mut identities = vec![username];
+        if let Some(ref id) = user_id {
+            identities.push(id.as_str());
+        }
+        if !self.is_any_user_allowed(identities.iter().copied()) {
+            tracing::warn!("Telegram: ignoring button press from unauthorized user: {username}");
+            return None;
+        }
+
+        if let Some(callback_id) = callback.get("id").and_then(Value::as_str) {
+            self.post_best_effort(
+                "answerCallbackQuery",
+                &serde_json::json!({ "callback_query_id": callback_id }),
+            )
+            .await;
+        }
+
+        let message = callback.get("message")?;
+        let chat_id = chat_id(message)?;
+        let topic = message_topic(message);
+        let message_id = message.get("message_id").and_then(Value::as_i64);
+
+        let content = if let Some(reply) = approval {
+            reply
+        } else {
+            // A choice is made once: drop the keyboard from the question.
+            if let Some(message_id) = message_id {
+                self.post_best_effort(
+                    "editMessageReplyMarkup",
+                    &serde_json::json!({ "chat_id": chat_id, "message_id": message_id }),
+                )
+                .await;
+            }
+            pressed_button_text(message, data)
+                .or(choice)
+                .unwrap_or_default()
+                .to_string()
+        };
+
+        Some(ChannelMessage {
+            id: Uuid::new_v4().to_string(),
+            sender: topic_address(&chat_id, topic),
+            author: user_id,
+            content,
+            channel: "telegram".to_string(),
+            conversation_id: Some(chat_id),
+            thread_id: topic.map(|topic| topic.to_string()),
+            reply_to: message_id.map(|id| id.to_string()),
+            message_id: None,
+            timestamp: now_secs(),
+            attachments: vec![],
+        })
+    }
+
+    /// Bot API call whose failure only affects presentation.
+    pub(super) async fn post_best_effort(&self, method: &str, body: &Value) {
+        if let Err(error) = self
+            .client
+            .post(self.api_url(method))
+            .json(body)
+            .send()
+            .await
+        {
+            tracing::debug!("Telegram {method} failed: {error}");
+        }
+    }
+}
+
+/// Full label of the pressed button; callback data may hold a cut-off copy.
+fn pressed_button_text<'a>(message: &'a Value, data: &str) -> Option<&'a str> {
+    message
+        .get("reply_markup")?
+        .get("inline_keyboard")?
+        .as_array()?
+        .iter()
+        .filter_map(Value::as_array)
+        .flatten()
+        .find(|button| button.get("callback_data").and_then(Value::as_str) == Some(data))?
+        .get("text")
+        .and_then(Value::as_str)
+}
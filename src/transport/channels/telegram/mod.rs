@@ -1,14 +1,20 @@
 pub mod api;
+mod buttons;
 pub mod handler;
+mod inbound;
 use crate::transport::channels::policy::{AllowlistMatch, is_allowed_user};
+pub use buttons::BUTTONS_PROMPT;
 
 #[cfg(test)]
 mod tests;
 
-/// Telegram channel — long-polls the Bot API for updates
+/// Telegram channel — long-polls the Bot API for updates, or receives them
+/// on the gateway's `/telegram/webhook` when a webhook URL is configured
 pub struct TelegramChannel {
     bot_token: String,
     allowed_users: Vec<String>,
+    webhook_url: Option<String>,
+    webhook_secret: Option<String>,
     client: reqwest::Client,
 }
 
@@ -17,10 +23,26 @@ impl TelegramChannel {
         Self {
             bot_token,
             allowed_users,
+            webhook_url: None,
+            webhook_secret: None,
             client: reqwest::Client::new(),
         }
     }
 
+    /// Receive updates on `url` (the gateway's `/telegram/webhook`) instead
+    /// of polling. `secret` is echoed by Telegram in
+    /// `X-Telegram-Bot-Api-Secret-Token`.
+    #[must_use]
+    pub fn with_webhook(mut self, url: Option<String>, secret: Option<String>) -> Self {
+        self.webhook_url = url.filter(|url| !url.trim().is_empty());
+        self.webhook_secret = secret.filter(|secret| !secret.trim().is_empty());
+        self
+    }
+
+    pub fn webhook_secret(&self) -> Option<&str> {
+        self.webhook_secret.as_deref()
+    }
+
     fn api_url(&self, method: &str) -> String {
         format!("https://api.telegram.org/bot{}/{method}", self.bot_token)
     }
@@ -36,3 +58,44 @@ impl TelegramChannel {
         identities.into_iter().any(|id| self.is_user_allowed(id))
     }
 }
+
+/// Reply target for a chat, or for a forum topic in it: `chat_id:topic_id`.
+/// Used as [`ChannelMessage::sender`](crate::transport::channels::traits::ChannelMessage)
+/// so replies land in the topic they answer.
+pub fn topic_address(chat_id: &str, topic_id: Option<i64>) -> String {
+    match topic_id {
+        Some(topic) => format!("{chat_id}:{topic}"),
+        None => chat_id.to_string(),
+    }
+}
+
+/// Inverse of [`topic_address`].
+pub fn parse_topic_address(address: &str) -> (&str, Option<i64>) {
+    match address.rsplit_once(':') {
+        Some((chat, topic)) => match topic.parse() {
+            Ok(topic) => (chat, Some(topic)),
+            Err(_) => (address, None),
+        },
+        None => (address, None),
+    }
+}
+
+/// JSON body addressing `recipient`, with `message_thread_id` for topics.
+fn chat_body(recipient: &str) -> serde_json::Value {
+    let (chat_id, topic) = parse_topic_address(recipient);
+    let mut body = serde_json::json!({ "chat_id": chat_id });
+    if let Some(topic) = topic {
+        body["message_thread_id"] = topic.into();
+    }
+    body
+}
+
+/// Multipart form addressing `recipient`, with `message_thread_id` for topics.
+fn chat_form(recipient: &str) -> reqwest::multipart::Form {
+    let (chat_id, topic) = parse_topic_address(recipient);
+    let form = reqwest::multipart::Form::new().text("chat_id", chat_id.to_string());
+    match topic {
+        Some(topic) => form.text("message_thread_id", topic.to_string()),
+        None => form,
+    }
+}
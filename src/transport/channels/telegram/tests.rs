@@ -494,3 +494,111 @@ fn telegram_file_url_is_constructed_correctly() {
         "https://api.telegram.org/file/bot123:ABC/photos/file.jpg"
     );
 }
+
+#[test]
+fn telegram_approval_keyboard_offers_all_decisions_for_tool_calls() {
+    use crate::security::approval::{ApprovalRequest, ApprovalSubject};
+    let request = ApprovalRequest::new(
+        "telegram:42",
+        ApprovalSubject::ToolCall {
+            tool: "shell".into(),
+            pattern: "git push".into(),
+        },
+    );
+    let keyboard = TelegramChannel::approval_keyboard(&request);
+    let buttons = keyboard["inline_keyboard"][0].as_array().unwrap();
+    assert_eq!(buttons.len(), 3);
+    assert_eq!(
+        buttons[2]["callback_data"],
+        format!("approval:deny:{}", request.id)
+    );
+}
+
+#[tokio::test]
+async fn telegram_approval_callback_ignores_unauthorized_users() {
+    let ch = TelegramChannel::new("t".into(), vec!["alice".into()]);
+    let callback = serde_json::json!({
+        "id": "cb1",
+        "from": {"id": 7, "username": "eve"},
+        "message": {"chat": {"id": 42}},
+        "data": "approval:approve:1a2b3c4d"
+    });
+    assert!(ch.callback_message(&callback).await.is_none());
+}
+
+#[test]
+fn telegram_topic_address_round_trips() {
+    assert_eq!(topic_address("-100123", Some(7)), "-100123:7");
+    assert_eq!(parse_topic_address("-100123:7"), ("-100123", Some(7)));
+    assert_eq!(parse_topic_address("-100123"), ("-100123", None));
+    assert_eq!(parse_topic_address("@channel"), ("@channel", None));
+}
+
+#[test]
+fn telegram_chat_body_targets_the_topic() {
+    let body = chat_body("-100123:7");
+    assert_eq!(body["chat_id"], "-100123");
+    assert_eq!(body["message_thread_id"], 7);
+    assert!(chat_body("42").get("message_thread_id").is_none());
+}
+
+#[tokio::test]
+async fn telegram_topic_messages_map_to_threads() {
+    let ch = TelegramChannel::new("t".into(), vec!["*".into()]);
+    let update = serde_json::json!({
+        "update_id": 1,
+        "message": {
+            "message_id": 10,
+            "message_thread_id": 7,
+            "is_topic_message": true,
+            "from": {"id": 1, "username": "alice"},
+            "chat": {"id": -100_123, "type": "supergroup", "is_forum": true},
+            "text": "status?"
+        }
+    });
+    let msg = ch.inbound_from_update(&update).await.unwrap();
+    assert_eq!(msg.sender, "-100123:7");
+    assert_eq!(msg.conversation_id.as_deref(), Some("-100123"));
+    assert_eq!(msg.thread_id.as_deref(), Some("7"));
+    assert_eq!(msg.conversation_key(), "-100123/7");
+    assert_eq!(msg.message_id.as_deref(), Some("10"));
+}
+
+#[tokio::test]
+async fn telegram_reply_threads_outside_forums_share_the_chat() {
+    let ch = TelegramChannel::new("t".into(), vec!["*".into()]);
+    let update = serde_json::json!({
+        "update_id": 2,
+        "message": {
+            "message_id": 11,
+            "message_thread_id": 3,
+            "from": {"id": 1, "username": "alice"},
+            "chat": {"id": -42, "type": "group"},
+            "text": "hello"
+        }
+    });
+    let msg = ch.inbound_from_update(&update).await.unwrap();
+    assert_eq!(msg.sender, "-42");
+    assert_eq!(msg.thread_id, None);
+}
+
+#[tokio::test]
+async fn telegram_choice_callback_ignores_unauthorized_users() {
+    let ch = TelegramChannel::new("t".into(), vec!["alice".into()]);
+    let callback = serde_json::json!({
+        "id": "cb2",
+        "from": {"id": 7, "username": "eve"},
+        "message": {"message_id": 3, "chat": {"id": 42}},
+        "data": "choice:Yes"
+    });
+    assert!(ch.callback_message(&callback).await.is_none());
+}
+
+#[test]
+fn telegram_webhook_requires_url_and_secret_values() {
+    let ch = TelegramChannel::new("t".into(), vec![]).with_webhook(
+        Some("https://bot.example.com/telegram/webhook".into()),
+        Some(" ".into()),
+    );
+    assert_eq!(ch.webhook_secret(), None);
+}
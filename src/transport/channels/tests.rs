@@ -23,3 +23,65 @@ async fn classify_health_timeout() {
     let state = classify_health_result(&result);
     assert_eq!(state, ChannelHealthState::Timeout);
 }
+
+/// Records sends and edits; messages are limited to 10 bytes.
+#[derive(Default)]
+struct DraftChannel {
+    log: std::sync::Mutex<Vec<String>>,
+}
+
+impl super::traits::Channel for DraftChannel {
+    fn name(&self) -> &str {
+        "draft"
+    }
+
+    fn max_message_length(&self) -> usize {
+        10
+    }
+
+    fn send<'a>(
+        &'a self,
+        message: &'a str,
+        _recipient: &'a str,
+    ) -> std::pin::Pin<Box<dyn Future<Output = anyhow::Result<()>> + Send + 'a>> {
+        Box::pin(async move {
+            self.log.lock().unwrap().push(format!("send {message}"));
+            Ok(())
+        })
+    }
+
+    fn edit_message<'a>(
+        &'a self,
+        _channel_id: &'a str,
+        message_id: &'a str,
+        content: &'a str,
+    ) -> std::pin::Pin<Box<dyn Future<Output = anyhow::Result<()>> + Send + 'a>> {
+        Box::pin(async move {
+            self.log
+                .lock()
+                .unwrap()
+                .push(format!("edit {message_id} {content}"));
+            Ok(())
+        })
+    }
+
+    fn listen<'a>(
+        &'a self,
+        _tx: tokio::sync::mpsc::Sender<super::traits::ChannelMessage>,
+    ) -> std::pin::Pin<Box<dyn Future<Output = anyhow::Result<()>> + Send + 'a>> {
+        Box::pin(async move { Ok(()) })
+    }
+}
+
+#[tokio::test]
+async fn finish_draft_edits_the_draft_and_sends_the_overflow() {
+    let channel = std::sync::Arc::new(DraftChannel::default());
+    let channels: Vec<std::sync::Arc<dyn super::traits::Channel>> = vec![channel.clone()];
+    super::message_handler::finish_draft(&channels, "draft", "7", "first part second", "chat")
+        .await
+        .unwrap();
+    let log = channel.log.lock().unwrap();
+    assert!(log.len() > 1);
+    assert!(log[0].starts_with("edit 7 first"));
+    assert!(log[1..].iter().all(|entry| entry.starts_with("send ")));
+}
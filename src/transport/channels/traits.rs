@@ -1,3 +1,4 @@
+use crate::security::approval::ApprovalRequest;
 use std::future::Future;
 use std::pin::Pin;
 
@@ -16,12 +17,15 @@ pub enum MediaData {
 
 /// A message received from or sent to a channel.
 ///
-/// `sender` identifies the user (e.g. Discord user ID, Telegram user ID).
+/// `sender` is the reply address: a user (e.g. Discord user ID) or a shared
+/// chat, room or thread (e.g. a Telegram group). `author` is the platform
+/// user who wrote the message, when known.
 /// `conversation_id` identifies the conversation context (e.g. Discord channel/thread ID).
 #[derive(Debug, Clone)]
 pub struct ChannelMessage {
     pub id: String,
     pub sender: String,
+    pub author: Option<String>,
     pub content: String,
     pub channel: String,
     pub conversation_id: Option<String>,
@@ -32,6 +36,19 @@ pub struct ChannelMessage {
     pub attachments: Vec<MediaAttachment>,
 }
 
+impl ChannelMessage {
+    /// Key for the persisted conversation this message belongs to: the
+    /// thread within a chat, the chat, or the sender for direct messages.
+    pub fn conversation_key(&self) -> String {
+        match (&self.conversation_id, &self.thread_id) {
+            (Some(conversation), Some(thread)) => format!("{conversation}/{thread}"),
+            (Some(conversation), None) => conversation.clone(),
+            (None, Some(thread)) => thread.clone(),
+            (None, None) => self.sender.clone(),
+        }
+    }
+}
+
 /// Core channel trait — implement for any messaging platform
 pub trait Channel: Send + Sync {
     /// Human-readable channel name
@@ -74,6 +91,29 @@ pub trait Channel: Send + Sync {
         Box::pin(async move { anyhow::bail!("media sending not supported by this channel") })
     }
 
+    /// Download an inbound attachment, failing once it exceeds `max_bytes`.
+    /// Channels whose file URLs need credentials override this.
+    fn fetch_attachment<'a>(
+        &'a self,
+        attachment: &'a MediaAttachment,
+        max_bytes: usize,
+    ) -> Pin<Box<dyn Future<Output = anyhow::Result<Vec<u8>>> + Send + 'a>> {
+        Box::pin(super::attachments::load_attachment_bytes(
+            attachment, max_bytes,
+        ))
+    }
+
+    /// Send the opening part of a streamed reply and return its message id,
+    /// so the rest can be merged in with [`Channel::edit_message`]. Channels
+    /// without editing send it as a normal message and return `None`.
+    fn send_draft<'a>(
+        &'a self,
+        message: &'a str,
+        recipient: &'a str,
+    ) -> Pin<Box<dyn Future<Output = anyhow::Result<Option<String>>> + Send + 'a>> {
+        Box::pin(async move { self.send(message, recipient).await.map(|()| None) })
+    }
+
     fn edit_message<'a>(
         &'a self,
         _channel_id: &'a str,
@@ -91,6 +131,17 @@ pub trait Channel: Send + Sync {
         Box::pin(async move { anyhow::bail!("message deletion not supported by this channel") })
     }
 
+    /// Ask `recipient` to approve a pending action. Channels with interactive
+    /// controls (buttons, blocks) override this; the default sends the plain
+    /// text prompt, answered by replying `approve <id>` or `deny <id>`.
+    fn send_approval_request<'a>(
+        &'a self,
+        request: &'a ApprovalRequest,
+        recipient: &'a str,
+    ) -> Pin<Box<dyn Future<Output = anyhow::Result<()>> + Send + 'a>> {
+        Box::pin(async move { self.send(&request.prompt_text(), recipient).await })
+    }
+
     fn send_chunked<'a>(
         &'a self,
         message: &'a str,
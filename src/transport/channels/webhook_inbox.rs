@@ -0,0 +1,114 @@
+//! Hand-off from gateway webhook routes to channel listeners.
+//!
+//! In webhook mode a channel's updates arrive over HTTP on the gateway
+//! instead of through its own `listen` loop. The route parses each update
+//! and [`WebhookInbox::deliver`]s it; the channel's `listen` drains the inbox
+//! into the same sender polling mode uses, so webhook messages go through the
+//! regular channel pipeline (per-conversation ordering, approvals, branches).
+
+use super::traits::ChannelMessage;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, OnceLock, PoisonError};
+use tokio::sync::mpsc;
+
+/// Updates buffered while no listener is draining, e.g. during a restart.
+const INBOX_CAPACITY: usize = 256;
+
+pub struct WebhookInbox {
+    tx: mpsc::Sender<ChannelMessage>,
+    rx: tokio::sync::Mutex<mpsc::Receiver<ChannelMessage>>,
+}
+
+impl WebhookInbox {
+    fn new() -> Self {
+        let (tx, rx) = mpsc::channel(INBOX_CAPACITY);
+        Self {
+            tx,
+            rx: tokio::sync::Mutex::new(rx),
+        }
+    }
+
+    /// Queue `msg` for the channel listener. Returns `false` when the inbox
+    /// is full and the message was dropped.
+    pub fn deliver(&self, msg: ChannelMessage) -> bool {
+        self.tx.try_send(msg).is_ok()
+    }
+
+    /// Forward queued messages into `tx` until it closes. Only one listener
+    /// drains at a time; a restarted listener picks up where the last left off.
+    pub async fn drain_into(&self, tx: &mpsc::Sender<ChannelMessage>) {
+        let mut rx = self.rx.lock().await;
+        loop {
+            tokio::select! {
+                () = tx.closed() => return,
+                Some(msg) = rx.recv() => {
+                    if tx.send(msg).await.is_err() {
+                        return;
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// The process-wide inbox for `channel`, shared by its gateway route and
+/// its listener.
+pub fn webhook_inbox(channel: &str) -> Arc<WebhookInbox> {
+    static INBOXES: OnceLock<Mutex<HashMap<String, Arc<WebhookInbox>>>> = OnceLock::new();
+    let mut inboxes = INBOXES
+        .get_or_init(|| Mutex::new(HashMap::new()))
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner);
+    Arc::clone(
+        inboxes
+            .entry(channel.to_string())
+            .or_insert_with(|| Arc::new(WebhookInbox::new())),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(content: &str) -> ChannelMessage {
+        ChannelMessage {
+            id: content.into(),
+            sender: "chat".into(),
+            author: None,
+            content: content.into(),
+            channel: "test-inbox".into(),
+            conversation_id: None,
+            thread_id: None,
+            reply_to: None,
+            message_id: None,
+            timestamp: 0,
+            attachments: Vec::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn delivered_messages_reach_the_listener_in_order() {
+        let inbox = webhook_inbox("test-inbox-order");
+        assert!(inbox.deliver(message("first")));
+        assert!(inbox.deliver(message("second")));
+
+        let (tx, mut rx) = mpsc::channel(4);
+        let drain = tokio::spawn({
+            let inbox = Arc::clone(&inbox);
+            async move { inbox.drain_into(&tx).await }
+        });
+        assert_eq!(rx.recv().await.unwrap().content, "first");
+        assert_eq!(rx.recv().await.unwrap().content, "second");
+
+        drop(rx);
+        drain.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn the_same_channel_shares_one_inbox() {
+        let route = webhook_inbox("test-inbox-shared");
+        let listener = webhook_inbox("test-inbox-shared");
+        assert!(Arc::ptr_eq(&route, &listener));
+        assert!(!Arc::ptr_eq(&route, &webhook_inbox("test-inbox-other")));
+    }
+}
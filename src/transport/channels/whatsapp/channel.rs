@@ -121,6 +121,7 @@ impl WhatsAppChannel {
                     messages.push(ChannelMessage {
                         id: Uuid::new_v4().to_string(),
                         sender: normalized_from,
+                        author: None,
                         content,
                         channel: "whatsapp".to_string(),
                         conversation_id: None,
@@ -1,3 +1,4 @@
+use crate::security::approval::{ApprovalDecision, ApprovalRequest, ApprovalSubject};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -10,6 +11,11 @@ pub enum ClientMessage {
     Typing {
         session_id: Option<String>,
     },
+    /// Answer to a `ServerMessage::ApprovalRequest`.
+    ApprovalResponse {
+        id: String,
+        decision: ApprovalDecision,
+    },
     Ping,
 }
 
@@ -32,6 +38,14 @@ pub enum ServerMessage {
     Connected {
         version: String,
     },
+    /// The running turn is paused until the client sends an
+    /// `approval_response` with the same id.
+    ApprovalRequest {
+        id: String,
+        summary: String,
+        subject: ApprovalSubject,
+        can_remember: bool,
+    },
 }
 
 impl ServerMessage {
@@ -61,6 +75,15 @@ impl ServerMessage {
         }
     }
 
+    pub fn approval_request(request: &ApprovalRequest) -> Self {
+        Self::ApprovalRequest {
+            id: request.id.clone(),
+            summary: request.summary(),
+            subject: request.subject.clone(),
+            can_remember: request.can_remember(),
+        }
+    }
+
     pub fn to_json(&self) -> String {
         serde_json::to_string(self)
             .unwrap_or_else(|_| r#"{"type":"error","message":"serialization failed"}"#.to_string())
@@ -69,7 +92,7 @@ impl ServerMessage {
 
 #[cfg(test)]
 mod tests {
-    use super::{ClientMessage, ServerMessage};
+    use super::{ApprovalDecision, ApprovalRequest, ApprovalSubject, ClientMessage, ServerMessage};
 
     #[test]
     fn client_message_chat_roundtrip() {
@@ -142,4 +165,36 @@ mod tests {
 
         assert_eq!(value["type"], "pong");
     }
+
+    #[test]
+    fn client_message_approval_response_deserializes() {
+        let decoded: ClientMessage = serde_json::from_str(
+            r#"{"type":"approval_response","id":"1a2b3c4d","decision":"approve_always"}"#,
+        )
+        .unwrap();
+
+        assert!(matches!(
+            decoded,
+            ClientMessage::ApprovalResponse { id, decision: ApprovalDecision::ApproveAlways }
+                if id == "1a2b3c4d"
+        ));
+    }
+
+    #[test]
+    fn server_message_approval_request_serializes() {
+        let request = ApprovalRequest::new(
+            "gateway:s1",
+            ApprovalSubject::ToolCall {
+                tool: "shell".to_string(),
+                pattern: "git push".to_string(),
+            },
+        );
+        let value = serde_json::to_value(ServerMessage::approval_request(&request)).unwrap();
+
+        assert_eq!(value["type"], "approval_request");
+        assert_eq!(value["id"], request.id);
+        assert_eq!(value["subject"]["kind"], "tool_call");
+        assert_eq!(value["subject"]["tool"], "shell");
+        assert_eq!(value["can_remember"], true);
+    }
 }
@@ -4,6 +4,7 @@ use crate::agent::{
 };
 use crate::llm;
 use crate::persona::channel_person_entity_id;
+use crate::runtime::usage::TurnUsage;
 use crate::security::policy::TenantPolicyContext;
 use crate::security::writeback_guard::enforce_external_autosave_write_policy;
 use crate::tools::ExecutionContext;
@@ -30,8 +31,13 @@ use super::defense::{
     PolicyViolation, apply_external_ingress_policy, must_enforce_auth_violation,
     policy_accounting_response, policy_violation_response,
 };
+use super::pairing::token_client_id;
+use super::sessions::{
+    GatewayConversation, record_session_turn, resume_session, session_command_reply,
+};
 #[cfg(feature = "whatsapp")]
 use super::signature::verify_whatsapp_signature;
+use super::usage_route::record_turn_usage;
 use super::{AppState, WebhookBody};
 
 const ACTION_LIMIT_EXCEEDED_ERROR: &str = "blocked by security policy: action limit exceeded";
@@ -45,7 +51,13 @@ fn bearer_token(headers: &HeaderMap) -> Option<&str> {
         .filter(|token| !token.is_empty())
 }
 
-fn log_tool_loop_stop(source: &str, stop_reason: &LoopStopReason, iterations: u32) {
+/// Who sent a request: a hash-derived id for bearer-token clients, so tokens
+/// never reach entity ids, memory or usage records; `anonymous` otherwise.
+pub(super) fn client_identifier(headers: &HeaderMap, anonymous: &str) -> String {
+    bearer_token(headers).map_or_else(|| anonymous.to_string(), token_client_id)
+}
+
+pub(super) fn log_tool_loop_stop(source: &str, stop_reason: &LoopStopReason, iterations: u32) {
     match stop_reason {
         LoopStopReason::Completed => {}
         LoopStopReason::MaxIterations => {
@@ -66,7 +78,7 @@ fn log_tool_loop_stop(source: &str, stop_reason: &LoopStopReason, iterations: u3
     }
 }
 
-fn policy_accounting_error(error: &anyhow::Error) -> Option<&'static str> {
+pub(super) fn policy_accounting_error(error: &anyhow::Error) -> Option<&'static str> {
     let message = error.to_string();
     if message.contains(ACTION_LIMIT_EXCEEDED_ERROR) {
         Some(ACTION_LIMIT_EXCEEDED_ERROR)
@@ -77,14 +89,19 @@ fn policy_accounting_error(error: &anyhow::Error) -> Option<&'static str> {
     }
 }
 
-async fn run_gateway_tool_loop(
+pub(super) async fn run_gateway_tool_loop(
     state: &AppState,
     system_prompt: Option<&str>,
     user_message: &str,
     model: &str,
     temperature: f64,
     source_identifier: &str,
+    conversation: &GatewayConversation<'_>,
 ) -> anyhow::Result<crate::agent::tool_loop::ToolLoopResult> {
+    let session = resume_session(state, conversation).await;
+    let history = session
+        .as_ref()
+        .map_or(&[][..], |session| session.history.as_slice());
     let full_prompt = system_prompt.unwrap_or(state.system_prompt.as_str());
     let entity_id = channel_person_entity_id("gateway", source_identifier);
     let policy_context = TenantPolicyContext::disabled();
@@ -97,6 +114,7 @@ async fn run_gateway_tool_loop(
         allowed_tools: None,
         rate_limiter: Arc::clone(&state.rate_limiter),
         tenant_context: policy_context.clone(),
+        approval: None,
     };
     let result = run_main_session_turn_for_runtime_with_policy(
         IntegrationTurnParams {
@@ -118,14 +136,31 @@ async fn run_gateway_tool_loop(
             repeated_tool_call_streak_limit: state.repeated_tool_call_streak_limit,
             execution_context: ctx,
             stream_sink: None,
-            conversation_history: &[],
+            conversation_history: history,
+            image_content: &[],
             hooks: &[],
+            thinking: crate::llm::ThinkingLevel::Off,
+            request_options: None,
         },
     )
     .await?;
+    record_turn_usage(
+        state,
+        TurnUsage {
+            entity_id: &entity_id,
+            channel: conversation.channel,
+            session_id: session.as_ref().map(|session| session.id.as_str()),
+            provider: state.provider.name(),
+            model,
+            input_tokens: result.input_tokens,
+            output_tokens: result.output_tokens,
+        },
+    )
+    .await;
     if let LoopStopReason::Error(error) = &result.stop_reason {
         anyhow::bail!("tool loop failed: {error}");
     }
+    record_session_turn(state, session.as_ref(), user_message, &result).await;
     Ok(result)
 }
 
@@ -211,6 +246,12 @@ async fn process_whatsapp_message(
         return;
     }
 
+    let conversation = GatewayConversation::new("whatsapp", sender, None);
+    if let Some(reply) = session_command_reply(state, &conversation, content).await {
+        send_whatsapp_reply_or_log(wa, sender, &reply).await;
+        return;
+    }
+
     match run_gateway_tool_loop(
         state,
         None,
@@ -218,6 +259,7 @@ async fn process_whatsapp_message(
         &state.model,
         state.temperature,
         sender,
+        &conversation,
     )
     .await
     {
@@ -374,8 +416,8 @@ pub(super) async fn handle_webhook(
     let ingress = apply_external_ingress_policy(source, &webhook_body.message);
 
     if state.auto_save {
-        let source_identifier = bearer_token(&headers).unwrap_or("anonymous");
-        let autosave_entity_id = gateway_autosave_entity_id(source_identifier);
+        let source_identifier = client_identifier(&headers, "anonymous");
+        let autosave_entity_id = gateway_autosave_entity_id(&source_identifier);
         let policy_context = gateway_runtime_policy_context();
         if let Err(error) = policy_context.enforce_recall_scope(&autosave_entity_id) {
             tracing::warn!(
@@ -404,14 +446,23 @@ pub(super) async fn handle_webhook(
         return (StatusCode::BAD_REQUEST, Json(err));
     }
 
-    let source_identifier = bearer_token(&headers).unwrap_or("anonymous");
+    let source_identifier = client_identifier(&headers, "anonymous");
+    let client = channel_person_entity_id("gateway", &source_identifier);
+    let conversation =
+        GatewayConversation::new("gateway", &client, webhook_body.conversation_id.as_deref());
+    if let Some(reply) = session_command_reply(&state, &conversation, &webhook_body.message).await {
+        let body = serde_json::json!({"response": reply, "model": state.model});
+        return (StatusCode::OK, Json(body));
+    }
+
     match run_gateway_tool_loop(
         &state,
         None,
         &ingress.model_input,
         &state.model,
         state.temperature,
-        source_identifier,
+        &source_identifier,
+        &conversation,
     )
     .await
     {
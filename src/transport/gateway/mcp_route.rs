@@ -0,0 +1,32 @@
+//! Streamable HTTP MCP endpoint (`/mcp`).
+//!
+//! Exposes the gateway's tool registry to external MCP clients. Requests
+//! authenticate exactly like `/v1/chat/completions`: a paired bearer token or
+//! one of `gateway.openai_compat_api_keys`.
+
+use super::AppState;
+use super::openai_compat_auth::require_gateway_auth;
+use crate::plugins::mcp::server::{http_service, mcp_tool_server};
+use axum::Router;
+use axum::middleware;
+use std::sync::Arc;
+
+pub(super) const MCP_ROUTE: &str = "/mcp";
+
+/// Router serving the MCP streamable HTTP transport behind gateway auth.
+pub(super) fn mcp_router(state: &AppState) -> Router<AppState> {
+    let server = mcp_tool_server(
+        &state.config,
+        Arc::clone(&state.registry),
+        Arc::clone(&state.security),
+        Arc::clone(&state.rate_limiter),
+        "mcp:gateway",
+    );
+
+    Router::new()
+        .route_service(MCP_ROUTE, http_service(server))
+        .route_layer(middleware::from_fn_with_state(
+            state.clone(),
+            require_gateway_auth,
+        ))
+}
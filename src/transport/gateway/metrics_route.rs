@@ -0,0 +1,43 @@
+//! Prometheus scrape endpoint (`/metrics`) and request latency recording.
+//!
+//! `/metrics` authenticates like `/api/usage`: scrapers send the paired
+//! bearer token or an API key (`authorization` in the Prometheus scrape
+//! config). Provider usage is read through the gateway's usage recorder.
+
+use super::AppState;
+use super::openai_compat_auth::require_gateway_auth;
+use crate::runtime::observability::{
+    METRICS_CONTENT_TYPE, ObserverMetric, global_observer, render_metrics,
+};
+use axum::Router;
+use axum::extract::{Request, State};
+use axum::http::header;
+use axum::middleware::{self, Next};
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use std::time::Instant;
+
+/// Router serving `/metrics` behind gateway auth.
+pub(super) fn metrics_router(state: &AppState) -> Router<AppState> {
+    Router::new()
+        .route("/metrics", get(handle_metrics))
+        .route_layer(middleware::from_fn_with_state(
+            state.clone(),
+            require_gateway_auth,
+        ))
+}
+
+/// GET /metrics — text exposition of every observer metric and provider usage
+pub(super) async fn handle_metrics(State(state): State<AppState>) -> Response {
+    let usage = state.usage.as_ref().map(|usage| usage.tracker());
+    let body = render_metrics(usage).await;
+    ([(header::CONTENT_TYPE, METRICS_CONTENT_TYPE)], body).into_response()
+}
+
+/// Records how long each gateway request took to produce a response.
+pub(super) async fn record_request_latency(request: Request, next: Next) -> Response {
+    let started = Instant::now();
+    let response = next.run(request).await;
+    global_observer().record_metric(&ObserverMetric::RequestLatency(started.elapsed()));
+    response
+}
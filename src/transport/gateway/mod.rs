@@ -11,14 +11,28 @@ mod autosave;
 mod defense;
 mod events;
 mod handlers;
+#[cfg(feature = "mcp")]
+mod mcp_route;
+mod metrics_route;
 pub(crate) mod openai_compat_auth;
+pub(crate) mod openai_compat_embeddings;
 pub(crate) mod openai_compat_handler;
+pub(crate) mod openai_compat_messages;
+pub(crate) mod openai_compat_models;
+pub(crate) mod openai_compat_responses;
 pub(crate) mod openai_compat_streaming;
 pub(crate) mod openai_compat_types;
 pub mod pairing;
+mod plans_route;
 mod replay_guard;
 mod server;
+mod sessions;
 mod signature;
+#[cfg(feature = "slack")]
+mod slack_route;
+#[cfg(feature = "telegram")]
+mod telegram_route;
+mod usage_route;
 mod websocket;
 
 // Re-exported for integration tests (tests/persona/scope_regression.rs).
@@ -32,9 +46,15 @@ pub use signature::verify_whatsapp_signature;
 use crate::Config;
 use crate::config::GatewayDefenseMode;
 use crate::llm::Provider;
-use crate::memory::Memory;
+use crate::memory::{EmbeddingProvider, Memory};
+use crate::runtime::usage::UsageRecorder;
 use crate::security::policy::{EntityRateLimiter, SecurityPolicy};
+use crate::session::SessionManager;
 use crate::tools::ToolRegistry;
+#[cfg(feature = "slack")]
+use crate::transport::channels::SlackChannel;
+#[cfg(feature = "telegram")]
+use crate::transport::channels::TelegramChannel;
 #[cfg(feature = "whatsapp")]
 use crate::transport::channels::WhatsAppChannel;
 use pairing::PairingGuard;
@@ -67,6 +87,8 @@ pub struct AppState {
     pub system_prompt: String,
     pub openai_compat_api_keys: Option<Vec<String>>,
     pub mem: Arc<dyn Memory>,
+    /// Backs `/v1/embeddings`; built from the `[memory]` embedding settings.
+    pub embedder: Arc<dyn EmbeddingProvider>,
     pub auto_save: bool,
     pub webhook_secret: Option<Arc<str>>,
     pub pairing: Arc<PairingGuard>,
@@ -75,16 +97,31 @@ pub struct AppState {
     /// `WhatsApp` app secret for webhook signature verification (`X-Hub-Signature-256`)
     #[cfg(feature = "whatsapp")]
     pub whatsapp_app_secret: Option<Arc<str>>,
+    /// Slack Events API receiver; set when a signing secret is configured.
+    #[cfg(feature = "slack")]
+    pub slack: Option<Arc<SlackChannel>>,
+    /// Telegram webhook receiver; set when the channel has a webhook URL.
+    #[cfg(feature = "telegram")]
+    pub telegram: Option<Arc<TelegramChannel>>,
     pub defense_mode: GatewayDefenseMode,
     pub defense_kill_switch: bool,
     pub security: Arc<SecurityPolicy>,
     pub replay_guard: Arc<ReplayGuard>,
+    /// Conversation history for webhook, WebSocket and `WhatsApp` clients;
+    /// `None` when `[session] enabled = false`.
+    pub sessions: Option<Arc<SessionManager>>,
+    /// Per-turn token and cost accounting; `None` when `[usage] enabled = false`.
+    pub usage: Option<Arc<UsageRecorder>>,
 }
 
 /// Webhook request body
 #[derive(serde::Deserialize, serde::Serialize)]
 pub struct WebhookBody {
     pub message: String,
+    /// Keeps separate histories for one client; omitted means the client's
+    /// default conversation.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub conversation_id: Option<String>,
 }
 
 /// `WhatsApp` verification query params
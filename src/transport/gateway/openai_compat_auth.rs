@@ -1,4 +1,9 @@
-use axum::http::HeaderMap;
+use super::AppState;
+use super::openai_compat_handler::bearer_token;
+use axum::extract::{Request, State};
+use axum::http::{HeaderMap, StatusCode};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
 
 pub fn validate_api_key(headers: &HeaderMap, valid_keys: &[String]) -> bool {
     if valid_keys.is_empty() {
@@ -14,6 +19,38 @@ pub fn validate_api_key(headers: &HeaderMap, valid_keys: &[String]) -> bool {
     valid_keys.iter().any(|key| key == token)
 }
 
+/// Middleware for routes that authenticate like `/v1/chat/completions`: a
+/// paired bearer token or one of `gateway.openai_compat_api_keys`.
+pub(super) async fn require_gateway_auth(
+    State(state): State<AppState>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let headers = request.headers();
+    let pairing_active = state.pairing.is_paired() || state.pairing.require_pairing();
+    let api_keys = state.openai_compat_api_keys.as_deref().unwrap_or(&[]);
+
+    if !pairing_active && api_keys.is_empty() {
+        return (
+            StatusCode::FORBIDDEN,
+            "No authentication configured. Enable pairing or configure gateway.openai_compat_api_keys.",
+        )
+            .into_response();
+    }
+
+    let pairing_ok = pairing_active
+        && bearer_token(headers).is_some_and(|token| state.pairing.is_authenticated(token));
+    if !pairing_ok && !validate_api_key(headers, api_keys) {
+        return (
+            StatusCode::UNAUTHORIZED,
+            "Unauthorized — pair first via POST /pair or send a valid API key",
+        )
+            .into_response();
+    }
+
+    next.run(request).await
+}
+
 #[cfg(test)]
 mod tests {
     use super::validate_api_key;
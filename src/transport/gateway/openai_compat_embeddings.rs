@@ -0,0 +1,59 @@
+//! `POST /v1/embeddings` — backed by the memory embedding provider.
+
+use crate::agent::estimate_tokens;
+use crate::transport::gateway::AppState;
+use crate::transport::gateway::openai_compat_handler::{invalid_request, server_error};
+use crate::transport::gateway::openai_compat_types::{
+    EmbeddingEntry, EmbeddingList, EmbeddingRequest, EmbeddingUsage,
+};
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Json, Response};
+
+pub async fn handle_embeddings(
+    State(state): State<AppState>,
+    Json(request): Json<EmbeddingRequest>,
+) -> Response {
+    if state.embedder.dimensions() == 0 {
+        return (
+            StatusCode::NOT_IMPLEMENTED,
+            Json(serde_json::json!({
+                "error": {
+                    "message": "No embedding provider configured. Set memory.embedding_provider.",
+                    "type": "invalid_request_error"
+                }
+            })),
+        )
+            .into_response();
+    }
+
+    let texts = request.input.into_vec();
+    if texts.is_empty() {
+        return invalid_request("input must not be empty");
+    }
+    let inputs = texts.iter().map(String::as_str).collect::<Vec<_>>();
+    let prompt_tokens = inputs.iter().map(|text| estimate_tokens(text)).sum();
+    let vectors = match state.embedder.embed(&inputs).await {
+        Ok(vectors) => vectors,
+        Err(error) => return server_error(&error.to_string()),
+    };
+
+    Json(EmbeddingList {
+        object: "list".to_string(),
+        data: vectors
+            .into_iter()
+            .enumerate()
+            .map(|(index, embedding)| EmbeddingEntry {
+                object: "embedding".to_string(),
+                index,
+                embedding,
+            })
+            .collect(),
+        model: state.config.memory.embedding_model.clone(),
+        usage: EmbeddingUsage {
+            prompt_tokens,
+            total_tokens: prompt_tokens,
+        },
+    })
+    .into_response()
+}
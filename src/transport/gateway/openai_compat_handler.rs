@@ -1,26 +1,57 @@
+use crate::agent::model_output_limit;
 use crate::agent::{
     IntegrationRuntimeTurnOptions, IntegrationTurnParams, LoopStopReason,
     run_main_session_turn_for_runtime_with_policy,
 };
+use crate::llm::{
+    ChannelStreamSink, ChatOptions, ProviderMessage, ProviderStream, StopReason, StreamCollector,
+    StreamEvent, StreamSink,
+};
+use crate::persona::channel_person_entity_id;
+use crate::runtime::usage::TurnUsage;
 use crate::security::policy::TenantPolicyContext;
-use crate::tools::ExecutionContext;
+use crate::tools::{ExecutionContext, ToolSpec};
 use crate::transport::gateway::AppState;
+use crate::transport::gateway::handlers::client_identifier;
 use crate::transport::gateway::openai_compat_auth::validate_api_key;
-use crate::transport::gateway::openai_compat_streaming::build_sse_response;
+use crate::transport::gateway::openai_compat_messages::{
+    UserTurn, convert_messages, request_tool_specs, split_user_turn, tool_calls_from_blocks,
+};
+use crate::transport::gateway::openai_compat_streaming::SseWriter;
 use crate::transport::gateway::openai_compat_types::{
-    ChatCompletion, ChatCompletionRequest, Choice, ChoiceMessage, CompletionUsage, RequestMessage,
+    ChatCompletion, ChatCompletionRequest, Choice, ChoiceMessage, CompletionUsage,
+    RequestResponseFormat, StopSequences, ToolCall,
 };
+use crate::transport::gateway::usage_route::record_turn_usage;
 use axum::extract::State;
 use axum::http::{HeaderMap, StatusCode};
-use axum::response::{IntoResponse, Json};
+use axum::response::{IntoResponse, Json, Response};
 use std::sync::Arc;
+use tokio::sync::mpsc;
+use tokio_stream::StreamExt;
+
+/// Assistant output of one completion.
+pub(super) struct CompletionReply {
+    pub content: Option<String>,
+    pub tool_calls: Vec<ToolCall>,
+    pub finish_reason: &'static str,
+    pub usage: Option<CompletionUsage>,
+}
+
+/// Where a chat completion request is answered.
+enum Route {
+    /// A plain user turn, run through the agent with its own tools.
+    Agent(UserTurn),
+    /// Client tools or trailing tool results, sent straight to the provider.
+    Provider(Vec<ProviderMessage>),
+}
 
 #[allow(clippy::too_many_lines)]
 pub async fn handle_chat_completions(
     State(state): State<AppState>,
     headers: HeaderMap,
     Json(request): Json<ChatCompletionRequest>,
-) -> impl IntoResponse {
+) -> Response {
     let pairing_active = state.pairing.is_paired() || state.pairing.require_pairing();
 
     // Primary auth: pairing bearer token (if pairing is active)
@@ -60,11 +91,159 @@ pub async fn handle_chat_completions(
             .into_response();
     }
 
-    let (system_prompt, user_message) = extract_messages(&request.messages);
+    let converted = convert_messages(&request.messages);
+    let system_prompt = converted.system_prompt.unwrap_or_default();
     let temperature = request.temperature.unwrap_or(state.temperature);
     let model = request.model;
-    let source_identifier = bearer_token(&headers).unwrap_or("openai-compat");
-    let entity_id = format!("gateway:{source_identifier}");
+    let tool_specs = request_tool_specs(request.tools.as_deref(), request.tool_choice.as_ref());
+    let stop = request
+        .stop
+        .map(StopSequences::into_vec)
+        .unwrap_or_default();
+    // `stop` and `max_tokens` are enforced by the provider on both routes.
+    let mut options = ChatOptions::new(temperature).with_stop_sequences(stop);
+    // Client limits are capped at the model's configured output limit.
+    let requested = request
+        .max_tokens
+        .map(|max_tokens| u32::try_from(max_tokens).unwrap_or(u32::MAX));
+    let model_limit = model_output_limit(&state.config.context, &model);
+    if let Some(max_tokens) = match (requested, model_limit) {
+        (Some(requested), Some(limit)) => Some(requested.min(limit)),
+        (requested, limit) => requested.or(limit),
+    } {
+        options = options.with_max_tokens(max_tokens);
+    }
+    if let Some(top_p) = request.top_p {
+        options = options.with_top_p(top_p);
+    }
+    if let Some(seed) = request.seed {
+        options = options.with_seed(seed);
+    }
+    if let Some(format) = request
+        .response_format
+        .and_then(RequestResponseFormat::into_response_format)
+    {
+        options = options.with_response_format(format);
+    }
+
+    // Client tools are executed by the client, so those requests (and
+    // conversations ending in tool results) go straight to the provider.
+    // Plain user turns run through the agent with its own tools and memory.
+    let route = match split_user_turn(converted.history) {
+        Ok(turn) if tool_specs.is_empty() => Route::Agent(turn),
+        Ok(turn) => Route::Provider(turn.into_history()),
+        Err(history) => Route::Provider(history),
+    };
+
+    let completion_id = format!("chatcmpl-{}", uuid::Uuid::new_v4());
+    let created = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_secs());
+
+    if request.stream.unwrap_or(false) {
+        let (writer, response) = SseWriter::new(completion_id, model.clone(), created);
+        match route {
+            Route::Agent(turn) => {
+                tokio::spawn(stream_agent_turn(
+                    state,
+                    headers,
+                    system_prompt,
+                    model,
+                    options,
+                    turn,
+                    writer,
+                ));
+            }
+            Route::Provider(history) => {
+                // Failures before the first event still get a plain error
+                // response rather than an event stream.
+                let stream = match open_provider_stream(
+                    &state,
+                    &system_prompt,
+                    &history,
+                    &tool_specs,
+                    &model,
+                    &options,
+                )
+                .await
+                {
+                    Ok(stream) => stream,
+                    Err(response) => return response,
+                };
+                let entity_id = channel_person_entity_id(
+                    "gateway",
+                    &client_identifier(&headers, "openai-compat"),
+                );
+                tokio::spawn(stream_provider_turn(
+                    state, entity_id, model, stream, writer,
+                ));
+            }
+        }
+        return response;
+    }
+
+    let reply = match route {
+        Route::Agent(turn) => run_agent_turn(
+            &state,
+            &headers,
+            &system_prompt,
+            &model,
+            &options,
+            turn,
+            None,
+        )
+        .await
+        .map_err(|error| server_error(&error)),
+        Route::Provider(history) => {
+            run_provider_turn(
+                &state,
+                &headers,
+                &system_prompt,
+                &history,
+                &tool_specs,
+                &model,
+                &options,
+            )
+            .await
+        }
+    };
+    let reply = match reply {
+        Ok(reply) => reply,
+        Err(response) => return response,
+    };
+
+    Json(ChatCompletion {
+        id: completion_id,
+        object: "chat.completion".to_string(),
+        created,
+        model,
+        choices: vec![Choice {
+            index: 0,
+            message: ChoiceMessage {
+                role: "assistant".to_string(),
+                content: reply.content,
+                tool_calls: reply.tool_calls,
+            },
+            finish_reason: reply.finish_reason.to_string(),
+        }],
+        usage: reply.usage,
+    })
+    .into_response()
+}
+
+/// Runs a user turn through the agent; errors carry the message to report.
+/// `options` reach the provider on every call the turn makes.
+pub(super) async fn run_agent_turn(
+    state: &AppState,
+    headers: &HeaderMap,
+    system_prompt: &str,
+    model: &str,
+    options: &ChatOptions,
+    turn: UserTurn,
+    stream_sink: Option<Arc<dyn StreamSink>>,
+) -> Result<CompletionReply, String> {
+    let entity_id =
+        channel_person_entity_id("gateway", &client_identifier(headers, "openai-compat"));
     let policy_context = TenantPolicyContext::disabled();
     let ctx = ExecutionContext {
         security: Arc::clone(&state.security),
@@ -75,169 +254,290 @@ pub async fn handle_chat_completions(
         allowed_tools: None,
         rate_limiter: Arc::clone(&state.rate_limiter),
         tenant_context: policy_context.clone(),
+        approval: None,
     };
 
-    match run_main_session_turn_for_runtime_with_policy(
+    let result = run_main_session_turn_for_runtime_with_policy(
         IntegrationTurnParams {
             config: state.config.as_ref(),
             security: state.security.as_ref(),
             mem: Arc::clone(&state.mem),
             answer_provider: state.provider.as_ref(),
             reflect_provider: state.provider.as_ref(),
-            system_prompt: system_prompt.as_deref().unwrap_or_default(),
-            model_name: &model,
-            temperature,
+            system_prompt,
+            model_name: model,
+            temperature: options.temperature,
             entity_id: &entity_id,
             policy_context,
-            user_message: &user_message,
+            user_message: &turn.text,
         },
         IntegrationRuntimeTurnOptions {
             registry: Arc::clone(&state.registry),
             max_tool_iterations: state.max_tool_loop_iterations,
             repeated_tool_call_streak_limit: state.repeated_tool_call_streak_limit,
             execution_context: ctx,
-            stream_sink: None,
-            conversation_history: &[],
+            stream_sink,
+            conversation_history: &turn.history,
+            image_content: &turn.images,
             hooks: &[],
+            thinking: crate::llm::ThinkingLevel::Off,
+            request_options: Some(options),
         },
     )
     .await
-    {
-        Ok(result) => {
-            if let LoopStopReason::Error(error) = &result.stop_reason {
-                return (
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    Json(serde_json::json!({
-                        "error": { "message": error, "type": "server_error" }
-                    })),
-                )
-                    .into_response();
-            }
-            if matches!(result.stop_reason, LoopStopReason::MaxIterations) {
-                tracing::warn!("openai compat tool loop hit max iterations");
-            }
-            let completion_id = format!("chatcmpl-{}", uuid::Uuid::new_v4());
-            let created = std::time::SystemTime::now()
-                .duration_since(std::time::UNIX_EPOCH)
-                .map_or(0, |duration| duration.as_secs());
-
-            if request.stream.unwrap_or(false) {
-                build_sse_response(&completion_id, &model, &result.final_text, created)
-                    .into_response()
-            } else {
-                let usage = result.tokens_used.map(|total_tokens| CompletionUsage {
-                    prompt_tokens: 0,
-                    completion_tokens: total_tokens,
-                    total_tokens,
-                });
-
-                Json(ChatCompletion {
-                    id: completion_id,
-                    object: "chat.completion".to_string(),
-                    created,
-                    model,
-                    choices: vec![Choice {
-                        index: 0,
-                        message: ChoiceMessage {
-                            role: "assistant".to_string(),
-                            content: result.final_text,
-                        },
-                        finish_reason: "stop".to_string(),
-                    }],
-                    usage,
-                })
-                .into_response()
-            }
-        }
-        Err(error) => (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(serde_json::json!({
-                "error": { "message": error.to_string(), "type": "server_error" }
-            })),
-        )
-            .into_response(),
+    .map_err(|error| error.to_string())?;
+    record_turn_usage(
+        state,
+        TurnUsage {
+            entity_id: &entity_id,
+            channel: "openai-compat",
+            session_id: None,
+            provider: state.provider.name(),
+            model,
+            input_tokens: result.input_tokens,
+            output_tokens: result.output_tokens,
+        },
+    )
+    .await;
+
+    if let LoopStopReason::Error(error) = &result.stop_reason {
+        return Err(error.clone());
     }
+    if matches!(result.stop_reason, LoopStopReason::MaxIterations) {
+        tracing::warn!("openai compat tool loop hit max iterations");
+    }
+
+    Ok(CompletionReply {
+        content: Some(result.final_text),
+        tool_calls: Vec::new(),
+        finish_reason: if result.output_truncated {
+            "length"
+        } else {
+            "stop"
+        },
+        usage: completion_usage(result.input_tokens, result.output_tokens),
+    })
 }
 
-fn bearer_token(headers: &HeaderMap) -> Option<&str> {
-    headers
-        .get("authorization")
-        .and_then(|value| value.to_str().ok())
-        .and_then(|raw| raw.strip_prefix("Bearer "))
-        .filter(|token| !token.is_empty())
+async fn run_provider_turn(
+    state: &AppState,
+    headers: &HeaderMap,
+    system_prompt: &str,
+    history: &[ProviderMessage],
+    tool_specs: &[ToolSpec],
+    model: &str,
+    options: &ChatOptions,
+) -> Result<CompletionReply, Response> {
+    check_provider_request(state, history, options).map_err(invalid_request)?;
+    let system_prompt = (!system_prompt.is_empty()).then_some(system_prompt);
+    let response = state
+        .provider
+        .chat_with_tools(system_prompt, history, tool_specs, model, options)
+        .await
+        .map_err(|error| server_error(&error.to_string()))?;
+
+    let tool_calls = tool_calls_from_blocks(&response.content_blocks);
+    let finish_reason = provider_finish_reason(!tool_calls.is_empty(), response.stop_reason);
+    let entity_id =
+        channel_person_entity_id("gateway", &client_identifier(headers, "openai-compat"));
+    record_turn_usage(
+        state,
+        TurnUsage {
+            entity_id: &entity_id,
+            channel: "openai-compat",
+            session_id: None,
+            provider: state.provider.name(),
+            model,
+            input_tokens: response.input_tokens,
+            output_tokens: response.output_tokens,
+        },
+    )
+    .await;
+
+    Ok(CompletionReply {
+        content: (!response.text.is_empty() || tool_calls.is_empty()).then_some(response.text),
+        tool_calls,
+        finish_reason,
+        usage: completion_usage(response.input_tokens, response.output_tokens),
+    })
 }
 
-fn extract_messages(messages: &[RequestMessage]) -> (Option<String>, String) {
-    let system = messages
-        .iter()
-        .filter(|message| message.role == "system")
-        .map(|message| message.content.clone())
-        .reduce(|acc, content| format!("{acc}\n{content}"));
-
-    let user_message = messages
-        .iter()
-        .filter(|message| message.role != "system")
-        .map(|message| format!("{}: {}", message.role, message.content))
-        .collect::<Vec<_>>()
-        .join("\n");
-
-    (system, user_message)
+async fn open_provider_stream(
+    state: &AppState,
+    system_prompt: &str,
+    history: &[ProviderMessage],
+    tool_specs: &[ToolSpec],
+    model: &str,
+    options: &ChatOptions,
+) -> Result<ProviderStream, Response> {
+    check_provider_request(state, history, options).map_err(invalid_request)?;
+    let system_prompt = (!system_prompt.is_empty()).then_some(system_prompt);
+    state
+        .provider
+        .chat_with_tools_stream(system_prompt, history, tool_specs, model, options)
+        .await
+        .map_err(|error| server_error(&error.to_string()))
 }
 
-#[cfg(test)]
-mod tests {
-    use super::extract_messages;
-    use crate::transport::gateway::openai_compat_types::RequestMessage;
-
-    #[test]
-    fn extract_messages_with_system_and_user() {
-        let messages = vec![
-            RequestMessage {
-                role: "system".to_string(),
-                content: "You are concise.".to_string(),
-            },
-            RequestMessage {
-                role: "user".to_string(),
-                content: "Hello".to_string(),
-            },
-        ];
+fn check_provider_request(
+    state: &AppState,
+    history: &[ProviderMessage],
+    options: &ChatOptions,
+) -> Result<(), &'static str> {
+    if history.is_empty() {
+        return Err("messages must include a non-system message");
+    }
 
-        let (system, user) = extract_messages(&messages);
-        assert_eq!(system.as_deref(), Some("You are concise."));
-        assert_eq!(user, "user: Hello");
+    let ignored = options.unsupported(state.provider.capabilities().options);
+    if !ignored.is_empty() {
+        tracing::debug!(?ignored, "provider ignores some requested chat options");
     }
+    Ok(())
+}
 
-    #[test]
-    fn extract_messages_with_only_user() {
-        let messages = vec![RequestMessage {
-            role: "user".to_string(),
-            content: "Hello".to_string(),
-        }];
+/// Forwards provider text as it arrives; tool calls follow once complete.
+async fn stream_provider_turn(
+    state: AppState,
+    entity_id: String,
+    model: String,
+    mut stream: ProviderStream,
+    writer: SseWriter,
+) {
+    writer.role().await;
+    let mut collector = StreamCollector::new();
+    while let Some(event) = stream.next().await {
+        let event = match event {
+            Ok(event) => event,
+            Err(error) => {
+                writer.error(&error.to_string()).await;
+                return;
+            }
+        };
+        if let StreamEvent::TextDelta { text } = &event {
+            writer.content(text.clone()).await;
+        }
+        collector.feed(&event);
+    }
+
+    let response = collector.finish();
+    record_turn_usage(
+        &state,
+        TurnUsage {
+            entity_id: &entity_id,
+            channel: "openai-compat",
+            session_id: None,
+            provider: state.provider.name(),
+            model: &model,
+            input_tokens: response.input_tokens,
+            output_tokens: response.output_tokens,
+        },
+    )
+    .await;
 
-        let (system, user) = extract_messages(&messages);
-        assert!(system.is_none());
-        assert_eq!(user, "user: Hello");
+    let tool_calls = tool_calls_from_blocks(&response.content_blocks);
+    let finish_reason = provider_finish_reason(!tool_calls.is_empty(), response.stop_reason);
+    for (index, call) in (0u32..).zip(tool_calls) {
+        writer.tool_call(index, call).await;
     }
+    writer.finish(finish_reason).await;
+}
 
-    #[test]
-    fn extract_messages_with_multiple_system_messages() {
-        let messages = vec![
-            RequestMessage {
-                role: "system".to_string(),
-                content: "Rule one.".to_string(),
-            },
-            RequestMessage {
-                role: "system".to_string(),
-                content: "Rule two.".to_string(),
-            },
-            RequestMessage {
-                role: "user".to_string(),
-                content: "Proceed".to_string(),
-            },
-        ];
+/// Runs an agent turn, forwarding its text to the client as it is produced.
+async fn stream_agent_turn(
+    state: AppState,
+    headers: HeaderMap,
+    system_prompt: String,
+    model: String,
+    options: ChatOptions,
+    turn: UserTurn,
+    writer: SseWriter,
+) {
+    let (sender, mut receiver) = mpsc::channel(64);
+    let sink: Arc<dyn StreamSink> = Arc::new(ChannelStreamSink::new(sender, 1));
+    writer.role().await;
+
+    let mut run = Box::pin(run_agent_turn(
+        &state,
+        &headers,
+        &system_prompt,
+        &model,
+        &options,
+        turn,
+        Some(sink),
+    ));
+    let mut streamed = false;
+    let result = loop {
+        tokio::select! {
+            result = &mut run => break result,
+            Some(text) = receiver.recv() => {
+                streamed = true;
+                writer.content(text).await;
+            }
+        }
+    };
+    while let Ok(text) = receiver.try_recv() {
+        streamed = true;
+        writer.content(text).await;
+    }
 
-        let (system, user) = extract_messages(&messages);
-        assert_eq!(system.as_deref(), Some("Rule one.\nRule two."));
-        assert_eq!(user, "user: Proceed");
+    match result {
+        Ok(reply) => {
+            if !streamed {
+                writer.content(reply.content.unwrap_or_default()).await;
+            }
+            writer.finish(reply.finish_reason).await;
+        }
+        Err(error) => writer.error(&error).await,
     }
 }
+
+fn provider_finish_reason(has_tool_calls: bool, stop_reason: Option<StopReason>) -> &'static str {
+    if has_tool_calls {
+        "tool_calls"
+    } else if stop_reason == Some(StopReason::MaxTokens) {
+        "length"
+    } else {
+        "stop"
+    }
+}
+
+fn completion_usage(input: Option<u64>, output: Option<u64>) -> Option<CompletionUsage> {
+    if input.is_none() && output.is_none() {
+        return None;
+    }
+    let prompt_tokens = input.unwrap_or(0);
+    let completion_tokens = output.unwrap_or(0);
+    Some(CompletionUsage {
+        prompt_tokens,
+        completion_tokens,
+        total_tokens: prompt_tokens + completion_tokens,
+    })
+}
+
+pub(super) fn server_error(message: &str) -> Response {
+    (
+        StatusCode::INTERNAL_SERVER_ERROR,
+        Json(serde_json::json!({
+            "error": { "message": message, "type": "server_error" }
+        })),
+    )
+        .into_response()
+}
+
+pub(super) fn invalid_request(message: &str) -> Response {
+    (
+        StatusCode::BAD_REQUEST,
+        Json(serde_json::json!({
+            "error": { "message": message, "type": "invalid_request_error" }
+        })),
+    )
+        .into_response()
+}
+
+pub(super) fn bearer_token(headers: &HeaderMap) -> Option<&str> {
+    headers
+        .get("authorization")
+        .and_then(|value| value.to_str().ok())
+        .and_then(|raw| raw.strip_prefix("Bearer "))
+        .filter(|token| !token.is_empty())
+}
@@ -0,0 +1,340 @@
+//! Conversion between OpenAI-compatible chat messages and provider messages.
+//!
+//! `system`/`developer` messages become the system prompt; everything else is
+//! kept as structured history so tool calls, tool results and image parts
+//! reach the provider intact.
+
+use crate::llm::{ContentBlock, ImageSource, MessageRole, ProviderMessage};
+use crate::tools::ToolSpec;
+use crate::transport::gateway::openai_compat_types::{
+    ContentPart, FunctionCall, MessageContent, RequestMessage, RequestTool, ToolCall, ToolChoice,
+};
+
+#[derive(Debug, Default)]
+pub(crate) struct ConvertedMessages {
+    pub system_prompt: Option<String>,
+    pub history: Vec<ProviderMessage>,
+}
+
+/// The trailing user turn, split off so it can run as an agent turn.
+#[derive(Debug)]
+pub(crate) struct UserTurn {
+    pub history: Vec<ProviderMessage>,
+    pub text: String,
+    pub images: Vec<ContentBlock>,
+}
+
+impl UserTurn {
+    /// Reassemble the full history, user turn last.
+    pub(crate) fn into_history(self) -> Vec<ProviderMessage> {
+        let mut history = self.history;
+        let mut content = vec![ContentBlock::Text { text: self.text }];
+        content.extend(self.images);
+        history.push(ProviderMessage {
+            role: MessageRole::User,
+            content,
+        });
+        history
+    }
+}
+
+pub(crate) fn convert_messages(messages: &[RequestMessage]) -> ConvertedMessages {
+    let mut converted = ConvertedMessages::default();
+
+    for message in messages {
+        match message.role.as_str() {
+            "system" | "developer" => {
+                let text = content_text(message.content.as_ref());
+                converted.system_prompt = Some(match converted.system_prompt.take() {
+                    Some(existing) => format!("{existing}\n{text}"),
+                    None => text,
+                });
+            }
+            "assistant" => {
+                let mut content = Vec::new();
+                let text = content_text(message.content.as_ref());
+                if !text.is_empty() {
+                    content.push(ContentBlock::Text { text });
+                }
+                for call in message.tool_calls.iter().flatten() {
+                    content.push(ContentBlock::ToolUse {
+                        id: call.id.clone(),
+                        name: call.function.name.clone(),
+                        input: serde_json::from_str(&call.function.arguments)
+                            .unwrap_or_else(|_| serde_json::json!({})),
+                    });
+                }
+                if !content.is_empty() {
+                    converted.history.push(ProviderMessage {
+                        role: MessageRole::Assistant,
+                        content,
+                    });
+                }
+            }
+            "tool" => {
+                let block = ContentBlock::ToolResult {
+                    tool_use_id: message.tool_call_id.clone().unwrap_or_default(),
+                    content: content_text(message.content.as_ref()),
+                    is_error: false,
+                };
+                // Consecutive tool messages answer one assistant turn and are
+                // sent back together.
+                match converted.history.last_mut() {
+                    Some(last)
+                        if last.role == MessageRole::User
+                            && last
+                                .content
+                                .iter()
+                                .all(|block| matches!(block, ContentBlock::ToolResult { .. })) =>
+                    {
+                        last.content.push(block);
+                    }
+                    _ => converted.history.push(ProviderMessage {
+                        role: MessageRole::User,
+                        content: vec![block],
+                    }),
+                }
+            }
+            _ => {
+                let content = user_content(message.content.as_ref());
+                if !content.is_empty() {
+                    converted.history.push(ProviderMessage {
+                        role: MessageRole::User,
+                        content,
+                    });
+                }
+            }
+        }
+    }
+
+    converted
+}
+
+/// Split off the final message when it is a plain user turn (text and images
+/// only). Hands the history back when the conversation ends with anything
+/// else, such as tool results the provider has to answer directly.
+pub(crate) fn split_user_turn(
+    mut history: Vec<ProviderMessage>,
+) -> Result<UserTurn, Vec<ProviderMessage>> {
+    let is_user_turn = history.last().is_some_and(|last| {
+        last.role == MessageRole::User
+            && last.content.iter().all(|block| {
+                matches!(
+                    block,
+                    ContentBlock::Text { .. } | ContentBlock::Image { .. }
+                )
+            })
+    });
+    let Some(last) = history.pop_if(|_| is_user_turn) else {
+        return Err(history);
+    };
+    let mut texts = Vec::new();
+    let mut images = Vec::new();
+    for block in last.content {
+        match block {
+            ContentBlock::Text { text } => texts.push(text),
+            image @ ContentBlock::Image { .. } => images.push(image),
+            _ => {}
+        }
+    }
+
+    Ok(UserTurn {
+        history,
+        text: texts.join("\n"),
+        images,
+    })
+}
+
+/// Tool specs offered to the provider, honouring `tool_choice`.
+pub(crate) fn request_tool_specs(
+    tools: Option<&[RequestTool]>,
+    choice: Option<&ToolChoice>,
+) -> Vec<ToolSpec> {
+    let tools = tools.unwrap_or_default();
+    let forced = match choice {
+        Some(ToolChoice::Mode(mode)) if mode == "none" => return Vec::new(),
+        Some(ToolChoice::Function { function }) => Some(function.name.as_str()),
+        _ => None,
+    };
+
+    tools
+        .iter()
+        .filter(|tool| tool.kind == "function")
+        .filter(|tool| forced.is_none_or(|name| tool.function.name == name))
+        .map(|tool| ToolSpec {
+            name: tool.function.name.clone(),
+            description: tool.function.description.clone().unwrap_or_default(),
+            parameters: tool
+                .function
+                .parameters
+                .clone()
+                .unwrap_or_else(|| serde_json::json!({"type": "object", "properties": {}})),
+        })
+        .collect()
+}
+
+/// `tool_calls` entries for the tool-use blocks of a provider response.
+pub(crate) fn tool_calls_from_blocks(blocks: &[ContentBlock]) -> Vec<ToolCall> {
+    blocks
+        .iter()
+        .filter_map(|block| match block {
+            ContentBlock::ToolUse { id, name, input } => Some(ToolCall {
+                id: id.clone(),
+                kind: "function".to_string(),
+                function: FunctionCall {
+                    name: name.clone(),
+                    arguments: input.to_string(),
+                },
+            }),
+            _ => None,
+        })
+        .collect()
+}
+
+fn content_text(content: Option<&MessageContent>) -> String {
+    match content {
+        None => String::new(),
+        Some(MessageContent::Text(text)) => text.clone(),
+        Some(MessageContent::Parts(parts)) => parts
+            .iter()
+            .filter_map(|part| match part {
+                ContentPart::Text { text } => Some(text.as_str()),
+                _ => None,
+            })
+            .collect::<Vec<_>>()
+            .join("\n"),
+    }
+}
+
+fn user_content(content: Option<&MessageContent>) -> Vec<ContentBlock> {
+    match content {
+        None => Vec::new(),
+        Some(MessageContent::Text(text)) => vec![ContentBlock::Text { text: text.clone() }],
+        Some(MessageContent::Parts(parts)) => parts
+            .iter()
+            .filter_map(|part| match part {
+                ContentPart::Text { text } => Some(ContentBlock::Text { text: text.clone() }),
+                ContentPart::ImageUrl { image_url } => Some(ContentBlock::Image {
+                    source: image_source(&image_url.url),
+                }),
+                ContentPart::Unsupported => None,
+            })
+            .collect(),
+    }
+}
+
+fn image_source(url: &str) -> ImageSource {
+    url.strip_prefix("data:")
+        .and_then(|rest| rest.split_once(";base64,"))
+        .map_or_else(
+            || ImageSource::url(url),
+            |(media_type, data)| ImageSource::base64(media_type, data),
+        )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_messages(value: serde_json::Value) -> Vec<RequestMessage> {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn converts_roles_tool_calls_and_images() {
+        let messages = parse_messages(serde_json::json!([
+            {"role": "system", "content": "Rule one."},
+            {"role": "developer", "content": "Rule two."},
+            {"role": "user", "content": "Find rust docs"},
+            {"role": "assistant", "content": null, "tool_calls": [
+                {"id": "call_1", "type": "function", "function": {"name": "search", "arguments": "{\"q\":\"rust\"}"}},
+                {"id": "call_2", "type": "function", "function": {"name": "search", "arguments": "{\"q\":\"docs\"}"}}
+            ]},
+            {"role": "tool", "tool_call_id": "call_1", "content": "rust-lang.org"},
+            {"role": "tool", "tool_call_id": "call_2", "content": "docs.rs"},
+            {"role": "user", "content": [
+                {"type": "text", "text": "And this?"},
+                {"type": "image_url", "image_url": {"url": "data:image/png;base64,iVBORw0K"}}
+            ]}
+        ]));
+
+        let converted = convert_messages(&messages);
+        assert_eq!(
+            converted.system_prompt.as_deref(),
+            Some("Rule one.\nRule two.")
+        );
+        assert_eq!(converted.history.len(), 4);
+
+        let assistant = &converted.history[1];
+        assert_eq!(assistant.role, MessageRole::Assistant);
+        assert!(matches!(
+            &assistant.content[0],
+            ContentBlock::ToolUse { id, input, .. } if id == "call_1" && input["q"] == "rust"
+        ));
+
+        let results = &converted.history[2];
+        assert_eq!(results.role, MessageRole::User);
+        assert_eq!(results.content.len(), 2);
+
+        assert!(matches!(
+            &converted.history[3].content[1],
+            ContentBlock::Image { source: ImageSource::Base64 { media_type, data } }
+                if media_type == "image/png" && data == "iVBORw0K"
+        ));
+    }
+
+    #[test]
+    fn split_user_turn_separates_text_and_images() {
+        let messages = parse_messages(serde_json::json!([
+            {"role": "user", "content": "Hi"},
+            {"role": "assistant", "content": "Hello!"},
+            {"role": "user", "content": [
+                {"type": "text", "text": "Describe"},
+                {"type": "image_url", "image_url": {"url": "https://example.com/cat.png"}}
+            ]}
+        ]));
+
+        let turn = split_user_turn(convert_messages(&messages).history).unwrap();
+        assert_eq!(turn.history.len(), 2);
+        assert_eq!(turn.text, "Describe");
+        assert!(matches!(
+            &turn.images[0],
+            ContentBlock::Image { source: ImageSource::Url { url } } if url == "https://example.com/cat.png"
+        ));
+    }
+
+    #[test]
+    fn split_user_turn_rejects_trailing_tool_results() {
+        let messages = parse_messages(serde_json::json!([
+            {"role": "user", "content": "Search"},
+            {"role": "assistant", "tool_calls": [
+                {"id": "call_1", "type": "function", "function": {"name": "search", "arguments": "{}"}}
+            ]},
+            {"role": "tool", "tool_call_id": "call_1", "content": "found"}
+        ]));
+
+        let history = split_user_turn(convert_messages(&messages).history).unwrap_err();
+        assert_eq!(history.len(), 3);
+    }
+
+    #[test]
+    fn tool_choice_filters_specs() {
+        let tools: Vec<RequestTool> = serde_json::from_value(serde_json::json!([
+            {"type": "function", "function": {"name": "a"}},
+            {"type": "function", "function": {"name": "b", "description": "B", "parameters": {"type": "object"}}}
+        ]))
+        .unwrap();
+
+        assert_eq!(request_tool_specs(Some(&tools), None).len(), 2);
+        assert!(
+            request_tool_specs(Some(&tools), Some(&ToolChoice::Mode("none".into()))).is_empty()
+        );
+        let forced: ToolChoice = serde_json::from_value(
+            serde_json::json!({"type": "function", "function": {"name": "b"}}),
+        )
+        .unwrap();
+        let specs = request_tool_specs(Some(&tools), Some(&forced));
+        assert_eq!(specs.len(), 1);
+        assert_eq!(specs[0].description, "B");
+    }
+}
@@ -0,0 +1,62 @@
+//! `GET /v1/models` — the models the gateway serves and their provider chain.
+//!
+//! Configured models (the gateway model, then enabled routing rules) come
+//! first, followed by whatever the provider reports through `list_models`.
+
+use crate::llm::factory::provider_chain;
+use crate::transport::gateway::AppState;
+use crate::transport::gateway::openai_compat_types::{ModelEntry, ModelList};
+use axum::extract::State;
+use axum::response::Json;
+use std::time::Duration;
+
+/// How long a provider may take to list its models before only the
+/// configured ones are returned.
+const LIST_MODELS_TIMEOUT: Duration = Duration::from_secs(5);
+
+pub async fn handle_list_models(State(state): State<AppState>) -> Json<ModelList> {
+    let primary = state
+        .config
+        .default_provider
+        .as_deref()
+        .unwrap_or("openrouter");
+    let providers = provider_chain(primary, &state.config.reliability);
+
+    let mut data: Vec<ModelEntry> = Vec::new();
+    let mut add = |id: &str, owned_by: &str, providers: &[String]| {
+        if id.is_empty() || data.iter().any(|entry| entry.id == id) {
+            return;
+        }
+        data.push(ModelEntry {
+            id: id.to_string(),
+            object: "model".to_string(),
+            created: 0,
+            owned_by: owned_by.to_string(),
+            providers: providers.to_vec(),
+        });
+    };
+
+    add(&state.model, primary, &providers);
+    let routing = &state.config.routing;
+    for rule in routing.rules.iter().filter(|_| routing.enabled) {
+        match rule.provider.as_deref() {
+            Some(provider) => add(&rule.model, provider, &[provider.to_string()]),
+            None => add(&rule.model, primary, &providers),
+        }
+    }
+
+    match tokio::time::timeout(LIST_MODELS_TIMEOUT, state.provider.list_models()).await {
+        Ok(Ok(listed)) => {
+            for model in &listed {
+                add(model, primary, &providers);
+            }
+        }
+        Ok(Err(error)) => tracing::debug!(%error, "provider could not list its models"),
+        Err(_) => tracing::debug!("provider took too long to list its models"),
+    }
+
+    Json(ModelList {
+        object: "list".to_string(),
+        data,
+    })
+}
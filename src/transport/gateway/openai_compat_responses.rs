@@ -0,0 +1,177 @@
+//! `POST /v1/responses` — the Responses API mapped onto a main-session turn.
+
+use crate::llm::ChatOptions;
+use crate::transport::gateway::AppState;
+use crate::transport::gateway::openai_compat_handler::{
+    invalid_request, run_agent_turn, server_error,
+};
+use crate::transport::gateway::openai_compat_messages::{convert_messages, split_user_turn};
+use crate::transport::gateway::openai_compat_types::{
+    ContentPart, ImageUrl, MessageContent, RequestMessage, ResponseObject, ResponseOutputMessage,
+    ResponseOutputText, ResponseUsage, ResponsesContent, ResponsesContentPart, ResponsesInput,
+    ResponsesRequest,
+};
+use axum::extract::State;
+use axum::http::HeaderMap;
+use axum::response::{IntoResponse, Json, Response};
+
+pub async fn handle_responses(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(request): Json<ResponsesRequest>,
+) -> Response {
+    if request.stream.unwrap_or(false) {
+        return invalid_request("streaming is not supported on /v1/responses");
+    }
+
+    let mut messages = Vec::new();
+    if let Some(instructions) = request.instructions {
+        messages.push(RequestMessage {
+            role: "system".to_string(),
+            content: Some(MessageContent::Text(instructions)),
+            tool_calls: None,
+            tool_call_id: None,
+        });
+    }
+    messages.extend(input_messages(request.input));
+
+    let converted = convert_messages(&messages);
+    let Ok(turn) = split_user_turn(converted.history) else {
+        return invalid_request("input must end with a user message");
+    };
+    let mut options = ChatOptions::new(request.temperature.unwrap_or(state.temperature));
+    if let Some(max_output_tokens) = request.max_output_tokens {
+        options = options.with_max_tokens(u32::try_from(max_output_tokens).unwrap_or(u32::MAX));
+    }
+    let system_prompt = converted.system_prompt.unwrap_or_default();
+    let reply = match run_agent_turn(
+        &state,
+        &headers,
+        &system_prompt,
+        &request.model,
+        &options,
+        turn,
+        None,
+    )
+    .await
+    {
+        Ok(reply) => reply,
+        Err(error) => return server_error(&error),
+    };
+
+    // `run_agent_turn` reports "length" only when the provider hit the limit.
+    let text = reply.content.unwrap_or_default();
+    let status = if reply.finish_reason == "length" {
+        "incomplete"
+    } else {
+        "completed"
+    };
+    let created_at = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_secs());
+
+    Json(ResponseObject {
+        id: format!("resp_{}", uuid::Uuid::new_v4().simple()),
+        object: "response".to_string(),
+        created_at,
+        model: request.model,
+        status: status.to_string(),
+        output: vec![ResponseOutputMessage {
+            kind: "message".to_string(),
+            id: format!("msg_{}", uuid::Uuid::new_v4().simple()),
+            role: "assistant".to_string(),
+            status: status.to_string(),
+            content: vec![ResponseOutputText {
+                kind: "output_text".to_string(),
+                text: text.clone(),
+                annotations: Vec::new(),
+            }],
+        }],
+        output_text: text,
+        usage: reply.usage.map(|usage| ResponseUsage {
+            input_tokens: usage.prompt_tokens,
+            output_tokens: usage.completion_tokens,
+            total_tokens: usage.total_tokens,
+        }),
+    })
+    .into_response()
+}
+
+/// Rewrite Responses API input as chat messages so both endpoints share one
+/// conversion path.
+fn input_messages(input: ResponsesInput) -> Vec<RequestMessage> {
+    let items = match input {
+        ResponsesInput::Text(text) => {
+            return vec![RequestMessage {
+                role: "user".to_string(),
+                content: Some(MessageContent::Text(text)),
+                tool_calls: None,
+                tool_call_id: None,
+            }];
+        }
+        ResponsesInput::Items(items) => items,
+    };
+
+    items
+        .into_iter()
+        .map(|item| {
+            let content = match item.content {
+                ResponsesContent::Text(text) => MessageContent::Text(text),
+                ResponsesContent::Parts(parts) => MessageContent::Parts(
+                    parts
+                        .into_iter()
+                        .map(|part| match part {
+                            ResponsesContentPart::InputText { text }
+                            | ResponsesContentPart::OutputText { text } => {
+                                ContentPart::Text { text }
+                            }
+                            ResponsesContentPart::InputImage {
+                                image_url: Some(url),
+                            } => ContentPart::ImageUrl {
+                                image_url: ImageUrl { url },
+                            },
+                            ResponsesContentPart::InputImage { image_url: None }
+                            | ResponsesContentPart::Unsupported => ContentPart::Unsupported,
+                        })
+                        .collect(),
+                ),
+            };
+            RequestMessage {
+                role: item.role,
+                content: Some(content),
+                tool_calls: None,
+                tool_call_id: None,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::input_messages;
+    use crate::llm::{ContentBlock, MessageRole};
+    use crate::transport::gateway::openai_compat_messages::{convert_messages, split_user_turn};
+    use crate::transport::gateway::openai_compat_types::ResponsesInput;
+
+    #[test]
+    fn input_items_become_history_and_user_turn() {
+        let input: ResponsesInput = serde_json::from_value(serde_json::json!([
+            {"role": "developer", "content": "Be brief."},
+            {"role": "user", "content": "Hi"},
+            {"role": "assistant", "content": [{"type": "output_text", "text": "Hello!"}]},
+            {"role": "user", "content": [
+                {"type": "input_text", "text": "What is this?"},
+                {"type": "input_image", "image_url": "data:image/jpeg;base64,/9j/"}
+            ]}
+        ]))
+        .unwrap();
+
+        let converted = convert_messages(&input_messages(input));
+        assert_eq!(converted.system_prompt.as_deref(), Some("Be brief."));
+        let turn = split_user_turn(converted.history).unwrap();
+        assert_eq!(turn.history.len(), 2);
+        assert_eq!(turn.history[1].role, MessageRole::Assistant);
+        assert_eq!(turn.text, "What is this?");
+        assert!(matches!(turn.images[0], ContentBlock::Image { .. }));
+    }
+}
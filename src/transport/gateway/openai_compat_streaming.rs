@@ -1,24 +1,117 @@
 use crate::transport::gateway::openai_compat_types::{
-    ChatCompletionChunk, ChunkChoice, ChunkDelta,
+    ChatCompletionChunk, ChunkChoice, ChunkDelta, ChunkToolCall, ToolCall,
 };
 use axum::body::Body;
 use axum::http::{Response, StatusCode, header};
+use tokio::sync::mpsc;
+use tokio_stream::StreamExt;
+use tokio_stream::wrappers::ReceiverStream;
 
-pub fn build_sse_response(
-    completion_id: &str,
-    model: &str,
-    content: &str,
+/// Frames buffered between the producing turn and a slow client.
+const SSE_BUFFER: usize = 64;
+
+/// Writes chat-completion chunks to an SSE response while the turn runs.
+pub struct SseWriter {
+    completion_id: String,
+    model: String,
     created: u64,
-) -> Response<Body> {
-    let chunks = build_chunks(completion_id, model, content, created);
-    let stream = async_stream::stream! {
-        for chunk in chunks {
-            if let Ok(json) = serde_json::to_string(&chunk) {
-                yield Ok::<_, std::convert::Infallible>(format!("data: {json}\n\n"));
-            }
+    sender: mpsc::Sender<String>,
+}
+
+impl SseWriter {
+    /// Creates a writer and the response that streams whatever it writes.
+    pub fn new(completion_id: String, model: String, created: u64) -> (Self, Response<Body>) {
+        let (sender, receiver) = mpsc::channel(SSE_BUFFER);
+        let writer = Self {
+            completion_id,
+            model,
+            created,
+            sender,
+        };
+        (writer, sse_response(receiver))
+    }
+
+    pub async fn role(&self) {
+        self.chunk(
+            ChunkDelta {
+                role: Some("assistant".to_string()),
+                ..ChunkDelta::default()
+            },
+            None,
+        )
+        .await;
+    }
+
+    pub async fn content(&self, text: String) {
+        if text.is_empty() {
+            return;
+        }
+        self.chunk(
+            ChunkDelta {
+                content: Some(text),
+                ..ChunkDelta::default()
+            },
+            None,
+        )
+        .await;
+    }
+
+    pub async fn tool_call(&self, index: u32, call: ToolCall) {
+        self.chunk(
+            ChunkDelta {
+                tool_calls: Some(vec![ChunkToolCall { index, call }]),
+                ..ChunkDelta::default()
+            },
+            None,
+        )
+        .await;
+    }
+
+    /// Sends the closing chunk and ends the stream.
+    pub async fn finish(self, finish_reason: &str) {
+        self.chunk(ChunkDelta::default(), Some(finish_reason)).await;
+        self.done().await;
+    }
+
+    /// Reports a failure after the response has started and ends the stream.
+    pub async fn error(self, message: &str) {
+        let event = serde_json::json!({
+            "error": { "message": message, "type": "server_error" }
+        });
+        self.send(format!("data: {event}\n\n")).await;
+        self.done().await;
+    }
+
+    async fn chunk(&self, delta: ChunkDelta, finish_reason: Option<&str>) {
+        let chunk = ChatCompletionChunk {
+            id: self.completion_id.clone(),
+            object: "chat.completion.chunk".to_string(),
+            created: self.created,
+            model: self.model.clone(),
+            choices: vec![ChunkChoice {
+                index: 0,
+                delta,
+                finish_reason: finish_reason.map(str::to_string),
+            }],
+        };
+        if let Ok(json) = serde_json::to_string(&chunk) {
+            self.send(format!("data: {json}\n\n")).await;
         }
-        yield Ok("data: [DONE]\n\n".to_string());
-    };
+    }
+
+    async fn done(&self) {
+        self.send("data: [DONE]\n\n".to_string()).await;
+    }
+
+    async fn send(&self, frame: String) {
+        // A closed receiver means the client went away; the turn still runs
+        // to completion so its usage is recorded.
+        let _ = self.sender.send(frame).await;
+    }
+}
+
+fn sse_response(receiver: mpsc::Receiver<String>) -> Response<Body> {
+    let stream = ReceiverStream::new(receiver).map(Ok::<_, std::convert::Infallible>);
 
     let mut response = Response::new(Body::from_stream(stream));
     *response.status_mut() = StatusCode::OK;
@@ -36,54 +129,3 @@ pub fn build_sse_response(
     );
     response
 }
-
-fn build_chunks(id: &str, model: &str, content: &str, created: u64) -> Vec<ChatCompletionChunk> {
-    let mut chunks = vec![ChatCompletionChunk {
-        id: id.to_string(),
-        object: "chat.completion.chunk".to_string(),
-        created,
-        model: model.to_string(),
-        choices: vec![ChunkChoice {
-            index: 0,
-            delta: ChunkDelta {
-                role: Some("assistant".to_string()),
-                content: None,
-            },
-            finish_reason: None,
-        }],
-    }];
-
-    for paragraph in content.split("\n\n") {
-        chunks.push(ChatCompletionChunk {
-            id: id.to_string(),
-            object: "chat.completion.chunk".to_string(),
-            created,
-            model: model.to_string(),
-            choices: vec![ChunkChoice {
-                index: 0,
-                delta: ChunkDelta {
-                    role: None,
-                    content: Some(paragraph.to_string()),
-                },
-                finish_reason: None,
-            }],
-        });
-    }
-
-    chunks.push(ChatCompletionChunk {
-        id: id.to_string(),
-        object: "chat.completion.chunk".to_string(),
-        created,
-        model: model.to_string(),
-        choices: vec![ChunkChoice {
-            index: 0,
-            delta: ChunkDelta {
-                role: None,
-                content: None,
-            },
-            finish_reason: Some("stop".to_string()),
-        }],
-    });
-
-    chunks
-}
@@ -1,3 +1,4 @@
+use crate::llm::ResponseFormat;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Deserialize)]
@@ -8,15 +9,164 @@ pub struct ChatCompletionRequest {
     pub temperature: Option<f64>,
     #[serde(default)]
     pub stream: Option<bool>,
-    #[serde(default)]
-    #[allow(dead_code)]
+    #[serde(default, alias = "max_completion_tokens")]
     pub max_tokens: Option<u64>,
+    #[serde(default)]
+    pub stop: Option<StopSequences>,
+    #[serde(default)]
+    pub top_p: Option<f64>,
+    #[serde(default)]
+    pub seed: Option<u64>,
+    #[serde(default)]
+    pub response_format: Option<RequestResponseFormat>,
+    /// Client-defined tools. Calls to them are returned as `tool_calls` for
+    /// the client to execute.
+    #[serde(default)]
+    pub tools: Option<Vec<RequestTool>>,
+    #[serde(default)]
+    pub tool_choice: Option<ToolChoice>,
 }
 
 #[derive(Debug, Deserialize)]
 pub struct RequestMessage {
     pub role: String,
-    pub content: String,
+    #[serde(default)]
+    pub content: Option<MessageContent>,
+    /// Calls made by an earlier assistant turn.
+    #[serde(default)]
+    pub tool_calls: Option<Vec<ToolCall>>,
+    /// Set on `tool` messages: the call this message answers.
+    #[serde(default)]
+    pub tool_call_id: Option<String>,
+}
+
+/// Message content: a plain string or an array of typed parts.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub enum MessageContent {
+    Text(String),
+    Parts(Vec<ContentPart>),
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ContentPart {
+    Text {
+        text: String,
+    },
+    ImageUrl {
+        image_url: ImageUrl,
+    },
+    /// Part types the gateway does not understand (audio, files, ...).
+    #[serde(other)]
+    Unsupported,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ImageUrl {
+    /// `https://...` or a `data:<mime>;base64,<data>` URL.
+    pub url: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub enum StopSequences {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl StopSequences {
+    pub fn into_vec(self) -> Vec<String> {
+        match self {
+            Self::One(stop) => vec![stop],
+            Self::Many(stops) => stops,
+        }
+    }
+}
+
+/// `{"type":"text"}`, `{"type":"json_object"}` or
+/// `{"type":"json_schema","json_schema":{"name":...,"schema":{...}}}`.
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum RequestResponseFormat {
+    Text,
+    JsonObject,
+    JsonSchema { json_schema: JsonSchemaFormat },
+}
+
+#[derive(Debug, Deserialize)]
+pub struct JsonSchemaFormat {
+    pub name: String,
+    #[serde(default)]
+    pub schema: Option<serde_json::Value>,
+}
+
+impl RequestResponseFormat {
+    /// The provider-side format; `None` for plain text.
+    pub fn into_response_format(self) -> Option<ResponseFormat> {
+        match self {
+            Self::Text => None,
+            Self::JsonObject
+            | Self::JsonSchema {
+                json_schema: JsonSchemaFormat { schema: None, .. },
+            } => Some(ResponseFormat::Json),
+            Self::JsonSchema {
+                json_schema:
+                    JsonSchemaFormat {
+                        name,
+                        schema: Some(schema),
+                    },
+            } => Some(ResponseFormat::json_schema(name, schema)),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RequestTool {
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub function: FunctionDefinition,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct FunctionDefinition {
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub parameters: Option<serde_json::Value>,
+}
+
+/// `"none"`, `"auto"`, `"required"` or `{"type":"function","function":{"name":...}}`.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub enum ToolChoice {
+    Mode(String),
+    Function { function: ToolChoiceFunction },
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ToolChoiceFunction {
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ToolCall {
+    pub id: String,
+    #[serde(rename = "type", default = "function_kind")]
+    pub kind: String,
+    pub function: FunctionCall,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FunctionCall {
+    pub name: String,
+    /// JSON-encoded arguments.
+    pub arguments: String,
+}
+
+fn function_kind() -> String {
+    "function".to_string()
 }
 
 #[derive(Debug, Serialize)]
@@ -39,7 +189,9 @@ pub struct Choice {
 #[derive(Debug, Serialize)]
 pub struct ChoiceMessage {
     pub role: String,
-    pub content: String,
+    pub content: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub tool_calls: Vec<ToolCall>,
 }
 
 #[derive(Debug, Serialize)]
@@ -65,19 +217,182 @@ pub struct ChunkChoice {
     pub finish_reason: Option<String>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Default, Serialize)]
 pub struct ChunkDelta {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub role: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub content: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tool_calls: Option<Vec<ChunkToolCall>>,
+}
+
+/// A tool call inside a streamed delta; `index` identifies the call across
+/// chunks.
+#[derive(Debug, Serialize)]
+pub struct ChunkToolCall {
+    pub index: u32,
+    #[serde(flatten)]
+    pub call: ToolCall,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ModelList {
+    pub object: String,
+    pub data: Vec<ModelEntry>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ModelEntry {
+    pub id: String,
+    pub object: String,
+    pub created: u64,
+    pub owned_by: String,
+    /// Provider chain the model is served through, primary first.
+    pub providers: Vec<String>,
+}
+
+/// The requested `model` is ignored; the configured embedding model is used.
+#[derive(Debug, Deserialize)]
+pub struct EmbeddingRequest {
+    pub input: EmbeddingInput,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub enum EmbeddingInput {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl EmbeddingInput {
+    pub fn into_vec(self) -> Vec<String> {
+        match self {
+            Self::One(text) => vec![text],
+            Self::Many(texts) => texts,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct EmbeddingList {
+    pub object: String,
+    pub data: Vec<EmbeddingEntry>,
+    pub model: String,
+    pub usage: EmbeddingUsage,
+}
+
+#[derive(Debug, Serialize)]
+pub struct EmbeddingEntry {
+    pub object: String,
+    pub index: usize,
+    pub embedding: Vec<f32>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct EmbeddingUsage {
+    pub prompt_tokens: u64,
+    pub total_tokens: u64,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ResponsesRequest {
+    pub model: String,
+    pub input: ResponsesInput,
+    #[serde(default)]
+    pub instructions: Option<String>,
+    #[serde(default)]
+    pub temperature: Option<f64>,
+    #[serde(default)]
+    pub max_output_tokens: Option<u64>,
+    #[serde(default)]
+    pub stream: Option<bool>,
+}
+
+/// `input` is either a bare prompt or a list of role-tagged messages.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub enum ResponsesInput {
+    Text(String),
+    Items(Vec<ResponsesInputItem>),
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ResponsesInputItem {
+    pub role: String,
+    pub content: ResponsesContent,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub enum ResponsesContent {
+    Text(String),
+    Parts(Vec<ResponsesContentPart>),
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ResponsesContentPart {
+    InputText {
+        text: String,
+    },
+    OutputText {
+        text: String,
+    },
+    InputImage {
+        #[serde(default)]
+        image_url: Option<String>,
+    },
+    #[serde(other)]
+    Unsupported,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ResponseObject {
+    pub id: String,
+    pub object: String,
+    pub created_at: u64,
+    pub model: String,
+    /// `completed`, or `incomplete` when `max_output_tokens` cut the text.
+    pub status: String,
+    pub output: Vec<ResponseOutputMessage>,
+    pub output_text: String,
+    pub usage: Option<ResponseUsage>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ResponseOutputMessage {
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub id: String,
+    pub role: String,
+    pub status: String,
+    pub content: Vec<ResponseOutputText>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ResponseOutputText {
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub text: String,
+    pub annotations: Vec<serde_json::Value>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ResponseUsage {
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub total_tokens: u64,
 }
 
 #[cfg(test)]
 mod tests {
     use super::{
         ChatCompletion, ChatCompletionChunk, ChatCompletionRequest, Choice, ChoiceMessage,
-        ChunkChoice, ChunkDelta,
+        ChunkChoice, ChunkDelta, ContentPart, EmbeddingInput, EmbeddingRequest, FunctionCall,
+        MessageContent, RequestResponseFormat, ResponseFormat, ResponsesContent,
+        ResponsesContentPart, ResponsesInput, ResponsesRequest, StopSequences, ToolCall,
+        ToolChoice,
     };
 
     #[test]
@@ -101,6 +416,37 @@ mod tests {
         assert_eq!(parsed.max_tokens, Some(128));
     }
 
+    #[test]
+    fn deserializes_sampling_and_response_format() {
+        let parsed: ChatCompletionRequest = serde_json::from_value(serde_json::json!({
+            "model": "gpt-4o-mini",
+            "messages": [{"role": "user", "content": "Hello"}],
+            "top_p": 0.9,
+            "seed": 42,
+            "response_format": {
+                "type": "json_schema",
+                "json_schema": {"name": "answer", "schema": {"type": "object"}}
+            }
+        }))
+        .unwrap();
+        assert_eq!(parsed.top_p, Some(0.9));
+        assert_eq!(parsed.seed, Some(42));
+        assert_eq!(
+            parsed.response_format.unwrap().into_response_format(),
+            Some(ResponseFormat::json_schema(
+                "answer",
+                serde_json::json!({"type": "object"})
+            ))
+        );
+
+        let text: RequestResponseFormat =
+            serde_json::from_value(serde_json::json!({"type": "text"})).unwrap();
+        assert_eq!(text.into_response_format(), None);
+        let json: RequestResponseFormat =
+            serde_json::from_value(serde_json::json!({"type": "json_object"})).unwrap();
+        assert_eq!(json.into_response_format(), Some(ResponseFormat::Json));
+    }
+
     #[test]
     fn serializes_chat_completion() {
         let completion = ChatCompletion {
@@ -112,7 +458,8 @@ mod tests {
                 index: 0,
                 message: ChoiceMessage {
                     role: "assistant".to_string(),
-                    content: "hello".to_string(),
+                    content: Some("hello".to_string()),
+                    tool_calls: Vec::new(),
                 },
                 finish_reason: "stop".to_string(),
             }],
@@ -136,6 +483,7 @@ mod tests {
                 delta: ChunkDelta {
                     role: Some("assistant".to_string()),
                     content: Some("hello".to_string()),
+                    tool_calls: None,
                 },
                 finish_reason: None,
             }],
@@ -146,4 +494,106 @@ mod tests {
         assert_eq!(value["choices"][0]["delta"]["role"], "assistant");
         assert_eq!(value["choices"][0]["delta"]["content"], "hello");
     }
+
+    #[test]
+    fn deserializes_content_parts_tools_and_stop() {
+        let payload = serde_json::json!({
+            "model": "gpt-4o",
+            "messages": [
+                {"role": "user", "content": [
+                    {"type": "text", "text": "What is this?"},
+                    {"type": "image_url", "image_url": {"url": "https://example.com/cat.png", "detail": "low"}},
+                    {"type": "input_audio", "input_audio": {"data": "", "format": "wav"}}
+                ]},
+                {"role": "assistant", "content": null, "tool_calls": [
+                    {"id": "call_1", "type": "function", "function": {"name": "lookup", "arguments": "{}"}}
+                ]},
+                {"role": "tool", "tool_call_id": "call_1", "content": "a cat"}
+            ],
+            "tools": [{"type": "function", "function": {"name": "lookup", "parameters": {"type": "object"}}}],
+            "tool_choice": {"type": "function", "function": {"name": "lookup"}},
+            "stop": "END",
+            "max_completion_tokens": 64
+        });
+
+        let parsed: ChatCompletionRequest = serde_json::from_value(payload).unwrap();
+        let Some(MessageContent::Parts(parts)) = &parsed.messages[0].content else {
+            panic!("expected content parts");
+        };
+        assert!(matches!(parts[1], ContentPart::ImageUrl { .. }));
+        assert!(matches!(parts[2], ContentPart::Unsupported));
+        assert!(parsed.messages[1].content.is_none());
+        assert_eq!(parsed.messages[2].tool_call_id.as_deref(), Some("call_1"));
+        assert_eq!(parsed.tools.as_ref().unwrap()[0].function.name, "lookup");
+        assert!(matches!(
+            parsed.tool_choice,
+            Some(ToolChoice::Function { ref function }) if function.name == "lookup"
+        ));
+        assert!(matches!(parsed.stop, Some(StopSequences::One(ref stop)) if stop == "END"));
+        assert_eq!(parsed.max_tokens, Some(64));
+    }
+
+    #[test]
+    fn serializes_tool_call_message_with_null_content() {
+        let message = ChoiceMessage {
+            role: "assistant".to_string(),
+            content: None,
+            tool_calls: vec![ToolCall {
+                id: "call_1".to_string(),
+                kind: "function".to_string(),
+                function: FunctionCall {
+                    name: "lookup".to_string(),
+                    arguments: "{\"q\":\"rust\"}".to_string(),
+                },
+            }],
+        };
+
+        let value = serde_json::to_value(message).unwrap();
+        assert!(value["content"].is_null());
+        assert_eq!(value["tool_calls"][0]["type"], "function");
+        assert_eq!(value["tool_calls"][0]["function"]["name"], "lookup");
+    }
+
+    #[test]
+    fn deserializes_embedding_input_forms() {
+        let one: EmbeddingRequest =
+            serde_json::from_value(serde_json::json!({"input": "hello"})).unwrap();
+        assert_eq!(one.input.into_vec(), vec!["hello"]);
+
+        let many: EmbeddingRequest = serde_json::from_value(
+            serde_json::json!({"input": ["a", "b"], "model": "text-embedding-3-small"}),
+        )
+        .unwrap();
+        assert!(matches!(many.input, EmbeddingInput::Many(ref texts) if texts.len() == 2));
+    }
+
+    #[test]
+    fn deserializes_responses_input_forms() {
+        let text: ResponsesRequest =
+            serde_json::from_value(serde_json::json!({"model": "m", "input": "Hi"})).unwrap();
+        assert!(matches!(text.input, ResponsesInput::Text(ref input) if input == "Hi"));
+
+        let items: ResponsesRequest = serde_json::from_value(serde_json::json!({
+            "model": "m",
+            "instructions": "Be brief.",
+            "max_output_tokens": 16,
+            "input": [
+                {"role": "user", "content": [
+                    {"type": "input_text", "text": "What is this?"},
+                    {"type": "input_image", "image_url": "https://example.com/cat.png"},
+                    {"type": "input_file", "file_id": "f1"}
+                ]}
+            ]
+        }))
+        .unwrap();
+        let ResponsesInput::Items(items_list) = &items.input else {
+            panic!("expected input items");
+        };
+        let ResponsesContent::Parts(parts) = &items_list[0].content else {
+            panic!("expected content parts");
+        };
+        assert!(matches!(parts[1], ResponsesContentPart::InputImage { .. }));
+        assert!(matches!(parts[2], ResponsesContentPart::Unsupported));
+        assert_eq!(items.max_output_tokens, Some(16));
+    }
 }
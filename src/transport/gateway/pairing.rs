@@ -20,6 +20,12 @@ pub fn hash_token(token: &str) -> String {
     hex::encode(Sha256::digest(token.as_bytes()))
 }
 
+/// Non-secret name for the client holding `token`, for entity ids and usage
+/// records: a prefix of the token's hash.
+pub fn token_client_id(token: &str) -> String {
+    format!("client-{}", &hash_token(token)[..16])
+}
+
 /// Guard that manages gateway pairing (one-time code exchange) and bearer
 /// token validation.
 pub struct PairingGuard {
@@ -0,0 +1,163 @@
+//! Plan inspection and control endpoints (`/api/plans`).
+//!
+//! Mirrors the `asteroniris plan` CLI over HTTP. Requests authenticate like
+//! `/v1/chat/completions`.
+
+use super::AppState;
+use super::openai_compat_auth::require_gateway_auth;
+use crate::planner::{PlanStore, StoredPlan, run_claimed_plan};
+use axum::Router;
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::middleware;
+use axum::response::Json;
+use axum::routing::{get, post};
+
+type JsonResponse = (StatusCode, Json<serde_json::Value>);
+
+/// Router serving the plan endpoints behind gateway auth.
+pub(super) fn plans_router(state: &AppState) -> Router<AppState> {
+    Router::new()
+        .route("/api/plans", get(handle_list_plans))
+        .route("/api/plans/{id}", get(handle_get_plan))
+        .route("/api/plans/{id}/resume", post(handle_resume_plan))
+        .route("/api/plans/{id}/cancel", post(handle_cancel_plan))
+        .route_layer(middleware::from_fn_with_state(
+            state.clone(),
+            require_gateway_auth,
+        ))
+}
+
+/// GET /api/plans — plan summaries, most recently updated first
+pub(super) async fn handle_list_plans(State(state): State<AppState>) -> JsonResponse {
+    let store = match open_store(&state).await {
+        Ok(store) => store,
+        Err(response) => return response,
+    };
+    match store.list(None).await {
+        Ok(plans) => {
+            let plans = plans.iter().map(plan_summary).collect::<Vec<_>>();
+            (StatusCode::OK, Json(serde_json::json!({ "plans": plans })))
+        }
+        Err(error) => internal_error(&error),
+    }
+}
+
+/// GET /api/plans/{id} — full plan with per-step status, output and error
+pub(super) async fn handle_get_plan(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> JsonResponse {
+    let store = match open_store(&state).await {
+        Ok(store) => store,
+        Err(response) => return response,
+    };
+    match store.get(&id).await {
+        Ok(Some(stored)) => (StatusCode::OK, Json(serde_json::json!(stored))),
+        Ok(None) => plan_not_found(&id),
+        Err(error) => internal_error(&error),
+    }
+}
+
+/// POST /api/plans/{id}/resume — resume in the background
+pub(super) async fn handle_resume_plan(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> JsonResponse {
+    let store = match open_store(&state).await {
+        Ok(store) => store,
+        Err(response) => return response,
+    };
+    // Claim before answering so a second resume gets a conflict instead of
+    // running the plan twice.
+    match store.claim(&id).await {
+        Ok(true) => {}
+        Ok(false) => {
+            return match store.status(&id).await {
+                Ok(Some(status)) => (
+                    StatusCode::CONFLICT,
+                    Json(serde_json::json!({"error": format!(
+                        "plan {id} is {}; only failed or interrupted plans can be resumed",
+                        status.as_str()
+                    )})),
+                ),
+                Ok(None) => plan_not_found(&id),
+                Err(error) => internal_error(&error),
+            };
+        }
+        Err(error) => return internal_error(&error),
+    }
+
+    let config = state.config.clone();
+    let plan_id = id.clone();
+    tokio::spawn(async move {
+        if let Err(error) = run_claimed_plan(&config, &store, &plan_id).await {
+            tracing::warn!(plan_id = %plan_id, "plan resume failed: {error}");
+        }
+    });
+
+    (
+        StatusCode::ACCEPTED,
+        Json(serde_json::json!({"plan_id": id, "status": "resuming"})),
+    )
+}
+
+/// POST /api/plans/{id}/cancel — stop dispatching further steps
+pub(super) async fn handle_cancel_plan(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> JsonResponse {
+    let store = match open_store(&state).await {
+        Ok(store) => store,
+        Err(response) => return response,
+    };
+    match store.cancel(&id).await {
+        Ok(true) => (
+            StatusCode::OK,
+            Json(serde_json::json!({"plan_id": id, "status": "cancelled"})),
+        ),
+        Ok(false) => match store.get(&id).await {
+            Ok(Some(_)) => (
+                StatusCode::CONFLICT,
+                Json(serde_json::json!({"error": format!("plan {id} is not running")})),
+            ),
+            Ok(None) => plan_not_found(&id),
+            Err(error) => internal_error(&error),
+        },
+        Err(error) => internal_error(&error),
+    }
+}
+
+async fn open_store(state: &AppState) -> Result<PlanStore, JsonResponse> {
+    PlanStore::open(&state.config.workspace_dir)
+        .await
+        .map_err(|error| internal_error(&error))
+}
+
+fn plan_summary(stored: &StoredPlan) -> serde_json::Value {
+    serde_json::json!({
+        "id": stored.plan.id,
+        "description": stored.plan.description,
+        "status": stored.status,
+        "entity_id": stored.entity_id,
+        "completed_steps": stored.completed_step_count(),
+        "total_steps": stored.plan.steps.len(),
+        "created_at": stored.created_at,
+        "updated_at": stored.updated_at,
+    })
+}
+
+fn plan_not_found(id: &str) -> JsonResponse {
+    (
+        StatusCode::NOT_FOUND,
+        Json(serde_json::json!({"error": format!("plan {id} not found")})),
+    )
+}
+
+fn internal_error(error: &anyhow::Error) -> JsonResponse {
+    tracing::warn!("plan endpoint failed: {error}");
+    (
+        StatusCode::INTERNAL_SERVER_ERROR,
+        Json(serde_json::json!({"error": "plan store unavailable"})),
+    )
+}
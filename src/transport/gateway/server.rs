@@ -1,26 +1,38 @@
 use super::handlers::{handle_health, handle_pair, handle_webhook};
 #[cfg(feature = "whatsapp")]
 use super::handlers::{handle_whatsapp_message, handle_whatsapp_verify};
+use super::openai_compat_auth::require_gateway_auth;
+use super::openai_compat_embeddings::handle_embeddings;
 use super::openai_compat_handler::handle_chat_completions;
+use super::openai_compat_models::handle_list_models;
+use super::openai_compat_responses::handle_responses;
 use super::pairing::PairingGuard;
 use super::replay_guard::ReplayGuard;
 use super::websocket::ws_handler;
 use super::{AppState, MAX_BODY_SIZE, REQUEST_TIMEOUT_SECS};
 
-use crate::config::Config;
+use crate::config::{Config, DEFAULT_MODEL};
 use crate::llm;
 use crate::memory;
 use crate::memory::Memory;
+use crate::plugins::skills;
+use crate::runtime::usage::UsageRecorder;
 use crate::security::policy::{EntityRateLimiter, SecurityPolicy};
+use crate::session::SessionManager;
 use crate::tools;
 use crate::tools::ToolRegistry;
 use crate::tools::middleware::default_middleware_chain;
+#[cfg(feature = "slack")]
+use crate::transport::channels::SlackChannel;
+#[cfg(feature = "telegram")]
+use crate::transport::channels::TelegramChannel;
 #[cfg(feature = "whatsapp")]
 use crate::transport::channels::WhatsAppChannel;
 use anyhow::{Context, Result};
 use axum::{
     Router,
     http::StatusCode,
+    middleware,
     routing::{get, post},
 };
 use std::net::SocketAddr;
@@ -39,7 +51,17 @@ fn is_public_bind(host: &str) -> bool {
 }
 
 /// Run the HTTP gateway using axum with proper HTTP/1.1 compliance.
-pub async fn run_gateway(host: &str, port: u16, config: Arc<Config>) -> Result<()> {
+///
+/// `channel_listeners` is true when channel listeners run in the same
+/// process (the daemon). Webhook routes that hand updates to a listener
+/// through its inbox are only served then; otherwise nothing would process
+/// the acknowledged updates.
+pub async fn run_gateway(
+    host: &str,
+    port: u16,
+    config: Arc<Config>,
+    channel_listeners: bool,
+) -> Result<()> {
     // ── Security: refuse public bind without tunnel or explicit opt-in ──
     if is_public_bind(host) && config.tunnel.provider == "none" && !config.gateway.allow_public_bind
     {
@@ -57,7 +79,7 @@ pub async fn run_gateway(host: &str, port: u16, config: Arc<Config>) -> Result<(
         .await
         .context("bind gateway socket")?;
 
-    run_gateway_with_listener(host, listener, config).await
+    run_gateway_with_listener(host, listener, config, channel_listeners).await
 }
 
 struct GatewayResources {
@@ -65,9 +87,12 @@ struct GatewayResources {
     model: String,
     temperature: f64,
     mem: Arc<dyn Memory>,
+    embedder: Arc<dyn memory::EmbeddingProvider>,
     security: Arc<SecurityPolicy>,
     rate_limiter: Arc<EntityRateLimiter>,
     registry: Arc<ToolRegistry>,
+    sessions: Option<Arc<SessionManager>>,
+    usage: Option<Arc<UsageRecorder>>,
 }
 
 async fn build_gateway_resources(config: &Config) -> Result<GatewayResources> {
@@ -86,10 +111,17 @@ async fn build_gateway_resources(config: &Config) -> Result<GatewayResources> {
     let model = config
         .default_model
         .clone()
-        .unwrap_or_else(|| "anthropic/claude-sonnet-4-20250514".into());
+        .unwrap_or_else(|| DEFAULT_MODEL.into());
     let temperature = config.default_temperature;
 
     let memory_api_key = api_key; // TODO: dedicated memory API key resolution
+    let embedder: Arc<dyn memory::EmbeddingProvider> =
+        Arc::from(memory::create_embedding_provider(
+            &config.memory.embedding_provider,
+            memory_api_key.as_deref(),
+            &config.memory.embedding_model,
+            config.memory.embedding_dimensions,
+        ));
     let mem: Arc<dyn Memory> = Arc::from(
         memory::factory::create_memory(
             &config.memory,
@@ -109,20 +141,36 @@ async fn build_gateway_resources(config: &Config) -> Result<GatewayResources> {
         config.autonomy.max_actions_per_entity_per_hour,
     ));
 
-    let tool_list = tools::all_tools(Arc::clone(&mem));
+    let mut tool_list = tools::all_tools(Arc::clone(&mem));
+    tool_list.extend(skills::load_skill_tools(
+        &config.workspace_dir,
+        &config.skills,
+    ));
     let mut registry = ToolRegistry::new(default_middleware_chain());
     for tool in tool_list {
         registry.register(tool);
     }
 
+    let sessions = SessionManager::open_if_enabled(
+        &config.workspace_dir,
+        &config.session,
+        Arc::clone(&provider),
+        &model,
+    )
+    .await;
+    let usage = UsageRecorder::open_if_enabled(config, Arc::clone(&security)).await;
+
     Ok(GatewayResources {
         provider,
         model,
         temperature,
         mem,
+        embedder,
         security,
         rate_limiter,
         registry: Arc::new(registry),
+        sessions,
+        usage,
     })
 }
 
@@ -135,6 +183,43 @@ fn resolve_webhook_secret(config: &Config) -> Option<Arc<str>> {
         .map(Arc::from)
 }
 
+/// The Slack channel used by `/slack/events`, when a signing secret is
+/// configured. Priority: environment variable > config file.
+#[cfg(feature = "slack")]
+fn build_slack_events_channel(config: &Config) -> Option<Arc<SlackChannel>> {
+    let slack = config.channels_config.slack.as_ref()?;
+    let signing_secret = std::env::var("ASTERONIRIS_SLACK_SIGNING_SECRET")
+        .ok()
+        .or_else(|| slack.signing_secret.clone())?;
+    let channel = SlackChannel::new(
+        slack.bot_token.clone(),
+        slack.channel_id.clone(),
+        slack.allowed_users.clone(),
+    )
+    .with_signing_secret(Some(signing_secret));
+    channel
+        .signing_secret()
+        .is_some()
+        .then(|| Arc::new(channel))
+}
+
+/// The Telegram channel used by `/telegram/webhook`, when webhook mode is
+/// configured with a secret token.
+#[cfg(feature = "telegram")]
+fn build_telegram_webhook_channel(config: &Config) -> Option<Arc<TelegramChannel>> {
+    let telegram = config.channels_config.telegram.as_ref()?;
+    telegram.webhook_url.as_ref()?;
+    let channel = TelegramChannel::new(telegram.bot_token.clone(), telegram.allowed_users.clone())
+        .with_webhook(
+            telegram.webhook_url.clone(),
+            telegram.webhook_secret.clone(),
+        );
+    channel
+        .webhook_secret()
+        .is_some()
+        .then(|| Arc::new(channel))
+}
+
 #[cfg(feature = "whatsapp")]
 fn build_whatsapp_channel(config: &Config) -> Option<Arc<WhatsAppChannel>> {
     config.channels_config.whatsapp.as_ref().map(|whatsapp| {
@@ -152,6 +237,7 @@ fn build_gateway_state(
     resources: GatewayResources,
     pairing: Arc<PairingGuard>,
     webhook_secret: Option<Arc<str>>,
+    channel_listeners: bool,
 ) -> AppState {
     let tool_descs = tools::tool_descriptions();
     let prompt_tool_descs: Vec<(&str, &str)> = tool_descs
@@ -163,6 +249,10 @@ fn build_gateway_state(
         &resources.model,
         &prompt_tool_descs,
     );
+    #[cfg(feature = "telegram")]
+    let telegram = channel_listeners
+        .then(|| build_telegram_webhook_channel(config.as_ref()))
+        .flatten();
     AppState {
         config: Arc::clone(config),
         provider: resources.provider,
@@ -175,6 +265,7 @@ fn build_gateway_state(
         system_prompt,
         openai_compat_api_keys: Some(config.gateway.openai_compat_api_keys.clone()),
         mem: resources.mem,
+        embedder: resources.embedder,
         auto_save: config.memory.auto_save,
         webhook_secret,
         pairing,
@@ -182,18 +273,28 @@ fn build_gateway_state(
         whatsapp: build_whatsapp_channel(config.as_ref()),
         #[cfg(feature = "whatsapp")]
         whatsapp_app_secret: resolve_whatsapp_app_secret(config.as_ref()),
+        #[cfg(feature = "slack")]
+        slack: channel_listeners
+            .then(|| build_slack_events_channel(config.as_ref()))
+            .flatten(),
+        #[cfg(feature = "telegram")]
+        telegram,
         defense_mode: config.gateway.defense_mode,
         defense_kill_switch: config.gateway.defense_kill_switch,
         security: resources.security,
         replay_guard: Arc::new(ReplayGuard::new()),
+        sessions: resources.sessions,
+        usage: resources.usage,
     }
 }
 
-/// Run the HTTP gateway from a pre-bound listener.
+/// Run the HTTP gateway from a pre-bound listener. See [`run_gateway`] for
+/// `channel_listeners`.
 pub async fn run_gateway_with_listener(
     host: &str,
     listener: tokio::net::TcpListener,
     config: Arc<Config>,
+    channel_listeners: bool,
 ) -> Result<()> {
     let actual_port = listener
         .local_addr()
@@ -208,6 +309,25 @@ pub async fn run_gateway_with_listener(
     let whatsapp_enabled = config.channels_config.whatsapp.is_some();
     #[cfg(not(feature = "whatsapp"))]
     let whatsapp_enabled = false;
+    let slack_configured = cfg!(feature = "slack") && config.channels_config.slack.is_some();
+    let slack_enabled = slack_configured && channel_listeners;
+    if slack_configured && !channel_listeners {
+        tracing::warn!(
+            "POST /slack/events is not served: the Slack channel listener only runs under `asteroniris daemon`"
+        );
+    }
+    let telegram_webhook_configured = cfg!(feature = "telegram")
+        && config
+            .channels_config
+            .telegram
+            .as_ref()
+            .is_some_and(|telegram| telegram.webhook_url.is_some());
+    let telegram_webhook_enabled = telegram_webhook_configured && channel_listeners;
+    if telegram_webhook_configured && !channel_listeners {
+        tracing::warn!(
+            "POST /telegram/webhook is not served: the Telegram channel listener only runs under `asteroniris daemon`"
+        );
+    }
 
     let pairing = Arc::new(PairingGuard::new(
         config.gateway.require_pairing,
@@ -218,11 +338,20 @@ pub async fn run_gateway_with_listener(
     print_gateway_banner(
         &display_addr,
         whatsapp_enabled,
+        slack_enabled,
+        telegram_webhook_enabled,
+        mcp_http_enabled(&config),
         &pairing,
         webhook_secret.is_some(),
     );
 
-    let state = build_gateway_state(&config, resources, pairing, webhook_secret);
+    let state = build_gateway_state(
+        &config,
+        resources,
+        pairing,
+        webhook_secret,
+        channel_listeners,
+    );
 
     let app = build_app(state, &config.gateway.cors_origins);
     axum::serve(listener, app)
@@ -253,9 +382,17 @@ fn resolve_whatsapp_app_secret(config: &Config) -> Option<Arc<str>> {
         .map(Arc::from)
 }
 
+fn mcp_http_enabled(config: &Config) -> bool {
+    cfg!(feature = "mcp") && config.mcp.serve.gateway
+}
+
+#[allow(clippy::fn_params_excessive_bools)]
 fn print_gateway_banner(
     display_addr: &str,
     whatsapp_enabled: bool,
+    slack_enabled: bool,
+    telegram_webhook_enabled: bool,
+    mcp_enabled: bool,
     pairing: &PairingGuard,
     webhook_secret_enabled: bool,
 ) {
@@ -267,7 +404,25 @@ fn print_gateway_banner(
         println!("  GET  /whatsapp");
         println!("  POST /whatsapp");
     }
+    if slack_enabled {
+        println!("  POST /slack/events -> Slack Events API");
+    }
+    if telegram_webhook_enabled {
+        println!("  POST /telegram/webhook -> Telegram Bot API");
+    }
+    if mcp_enabled {
+        println!("  POST /mcp -> MCP (streamable HTTP/SSE)");
+    }
+    println!("  POST /v1/chat/completions");
+    println!("  GET  /v1/models");
+    println!("  POST /v1/embeddings");
+    println!("  POST /v1/responses");
+    println!("  GET  /api/plans");
+    println!("  GET  /api/plans/{{id}}");
+    println!("  POST /api/plans/{{id}}/resume");
+    println!("  POST /api/plans/{{id}}/cancel");
     println!("  GET  /health");
+    println!("  GET  /metrics");
     if let Some(code) = pairing.pairing_code() {
         println!();
         println!("  Pairing required:");
@@ -282,21 +437,69 @@ fn print_gateway_banner(
     }
 }
 
-fn build_app(state: AppState, cors_origins: &[String]) -> Router {
+/// OpenAI-compatible endpoints beyond chat completions, behind gateway auth.
+fn openai_compat_router(state: &AppState) -> Router<AppState> {
+    Router::new()
+        .route("/v1/models", get(handle_list_models))
+        .route("/v1/embeddings", post(handle_embeddings))
+        .route("/v1/responses", post(handle_responses))
+        .route_layer(middleware::from_fn_with_state(
+            state.clone(),
+            require_gateway_auth,
+        ))
+}
+
+pub(super) fn build_app(state: AppState, cors_origins: &[String]) -> Router {
     let app = Router::new()
         .route("/health", get(handle_health))
         .route("/pair", post(handle_pair))
         .route("/webhook", post(handle_webhook))
         .route("/ws", get(ws_handler))
-        .route("/v1/chat/completions", post(handle_chat_completions));
+        .route("/v1/chat/completions", post(handle_chat_completions))
+        .merge(openai_compat_router(&state))
+        .merge(super::plans_route::plans_router(&state))
+        .merge(super::usage_route::usage_router(&state))
+        .merge(super::metrics_route::metrics_router(&state));
 
     #[cfg(feature = "whatsapp")]
     let app = app
         .route("/whatsapp", get(handle_whatsapp_verify))
         .route("/whatsapp", post(handle_whatsapp_message));
 
+    // Only registered when a channel listener drains the Slack inbox.
+    #[cfg(feature = "slack")]
+    let app = if state.slack.is_some() {
+        app.route(
+            "/slack/events",
+            post(super::slack_route::handle_slack_events),
+        )
+    } else {
+        app
+    };
+
+    // Only registered when a channel listener drains the Telegram inbox.
+    #[cfg(feature = "telegram")]
+    let app = if state.telegram.is_some() {
+        app.route(
+            "/telegram/webhook",
+            post(super::telegram_route::handle_telegram_webhook),
+        )
+    } else {
+        app
+    };
+
+    #[cfg(feature = "mcp")]
+    let app = if state.config.mcp.serve.gateway {
+        app.merge(super::mcp_route::mcp_router(&state))
+    } else {
+        app
+    };
+
     let mut app = app
         .with_state(state)
+        .layer(middleware::from_fn(
+            super::metrics_route::record_request_latency,
+        ))
         .layer(RequestBodyLimitLayer::new(MAX_BODY_SIZE))
         .layer(TimeoutLayer::with_status_code(
             StatusCode::REQUEST_TIMEOUT,
@@ -0,0 +1,84 @@
+//! Conversation history for gateway clients (webhook, WebSocket, `WhatsApp`).
+
+use crate::agent::{LoopStopReason, ToolLoopResult};
+use crate::session::{ResumedSession, SessionCommand};
+
+use super::AppState;
+
+/// Where a gateway conversation's history is kept.
+pub(super) struct GatewayConversation<'a> {
+    pub channel: &'a str,
+    pub key: String,
+}
+
+impl<'a> GatewayConversation<'a> {
+    /// `client` identifies the caller; `thread` optionally splits its
+    /// history into separate conversations.
+    pub(super) fn new(channel: &'a str, client: &str, thread: Option<&str>) -> Self {
+        let key = match thread {
+            Some(thread) => format!("{client}/{thread}"),
+            None => client.to_string(),
+        };
+        Self { channel, key }
+    }
+}
+
+/// Reply to `/new` or `/compact`; `None` for ordinary messages or when
+/// sessions are disabled.
+pub(super) async fn session_command_reply(
+    state: &AppState,
+    conversation: &GatewayConversation<'_>,
+    text: &str,
+) -> Option<String> {
+    let sessions = state.sessions.as_ref()?;
+    let command = SessionCommand::parse(text)?;
+    Some(
+        match sessions
+            .run_command(conversation.channel, &conversation.key, command)
+            .await
+        {
+            Ok(reply) => reply,
+            Err(error) => {
+                tracing::warn!(%error, "gateway session command failed");
+                format!("! Error: {error}")
+            }
+        },
+    )
+}
+
+pub(super) async fn resume_session(
+    state: &AppState,
+    conversation: &GatewayConversation<'_>,
+) -> Option<ResumedSession> {
+    let sessions = state.sessions.as_ref()?;
+    match sessions
+        .resume(conversation.channel, &conversation.key)
+        .await
+    {
+        Ok(session) => Some(session),
+        Err(error) => {
+            tracing::warn!(%error, "failed to load gateway conversation history");
+            None
+        }
+    }
+}
+
+pub(super) async fn record_session_turn(
+    state: &AppState,
+    session: Option<&ResumedSession>,
+    user_message: &str,
+    result: &ToolLoopResult,
+) {
+    let (Some(sessions), Some(session)) = (&state.sessions, session) else {
+        return;
+    };
+    if matches!(result.stop_reason, LoopStopReason::Error(_)) {
+        return;
+    }
+    if let Err(error) = sessions
+        .record_turn(&session.id, user_message, &result.transcript)
+        .await
+    {
+        tracing::warn!(%error, "failed to record gateway conversation turn");
+    }
+}
@@ -25,3 +25,57 @@ pub fn verify_whatsapp_signature(app_secret: &str, body: &[u8], signature_header
     // Constant-time comparison
     mac.verify_slice(&expected).is_ok()
 }
+
+/// Oldest Slack request timestamp accepted, in seconds, so captured requests
+/// cannot be replayed later.
+#[cfg(feature = "slack")]
+const SLACK_MAX_REQUEST_AGE_SECS: u64 = 300;
+
+/// Verify a Slack request signature (`X-Slack-Signature: v0=<hex>`), an
+/// HMAC-SHA256 of `v0:<X-Slack-Request-Timestamp>:<body>`. Requests whose
+/// timestamp is more than five minutes from `now_secs` are rejected.
+/// See: <https://api.slack.com/authentication/verifying-requests-from-slack>
+#[cfg(feature = "slack")]
+pub fn verify_slack_signature(
+    signing_secret: &str,
+    timestamp: &str,
+    body: &[u8],
+    signature_header: &str,
+    now_secs: u64,
+) -> bool {
+    use hmac::{Hmac, Mac};
+    use sha2::Sha256;
+
+    let Ok(sent_at) = timestamp.parse::<u64>() else {
+        return false;
+    };
+    if now_secs.abs_diff(sent_at) > SLACK_MAX_REQUEST_AGE_SECS {
+        return false;
+    }
+
+    let Some(hex_sig) = signature_header.strip_prefix("v0=") else {
+        return false;
+    };
+    let Ok(expected) = hex::decode(hex_sig) else {
+        return false;
+    };
+
+    let Ok(mut mac) = Hmac::<Sha256>::new_from_slice(signing_secret.as_bytes()) else {
+        return false;
+    };
+    mac.update(b"v0:");
+    mac.update(timestamp.as_bytes());
+    mac.update(b":");
+    mac.update(body);
+
+    mac.verify_slice(&expected).is_ok()
+}
+
+/// Verify the `X-Telegram-Bot-Api-Secret-Token` header Telegram sends with
+/// webhook updates, in constant time.
+/// See: <https://core.telegram.org/bots/api#setwebhook>
+#[cfg(feature = "telegram")]
+pub fn verify_telegram_secret_token(secret: &str, header: &str) -> bool {
+    use subtle::ConstantTimeEq;
+    !secret.is_empty() && bool::from(secret.as_bytes().ct_eq(header.as_bytes()))
+}
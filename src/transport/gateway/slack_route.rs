@@ -0,0 +1,126 @@
+//! Slack Events API receiver (`POST /slack/events`).
+//!
+//! Used when the Slack channel has no app-level token for Socket Mode.
+//! Slack expects an answer within three seconds, so requests are
+//! acknowledged right away and handed to the channel listener through its
+//! webhook inbox; the turn runs in the regular channel pipeline. The route
+//! is therefore only registered when that listener runs in the same process
+//! (`asteroniris daemon`). Slash commands configured with the same request
+//! URL arrive here form-encoded.
+//! See: <https://api.slack.com/apis/events-api>
+
+use super::AppState;
+use super::signature::verify_slack_signature;
+use crate::transport::channels::webhook_inbox::webhook_inbox;
+use crate::utils::text::truncate_with_ellipsis;
+use axum::body::Bytes;
+use axum::extract::State;
+use axum::http::{HeaderMap, StatusCode, header};
+use axum::response::{IntoResponse, Json, Response};
+use serde_json::Value;
+
+/// POST /slack/events -- Events API callbacks and slash commands
+pub(super) async fn handle_slack_events(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Response {
+    let Some(slack) = state.slack.clone() else {
+        return (
+            StatusCode::NOT_FOUND,
+            Json(serde_json::json!({"error": "Slack not configured"})),
+        )
+            .into_response();
+    };
+    let Some(signing_secret) = slack.signing_secret() else {
+        return invalid_signature_response();
+    };
+
+    let header_str = |name: &str| {
+        headers
+            .get(name)
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or("")
+    };
+    let now_secs = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    if !verify_slack_signature(
+        signing_secret,
+        header_str("X-Slack-Request-Timestamp"),
+        &body,
+        header_str("X-Slack-Signature"),
+        now_secs,
+    ) {
+        tracing::warn!("Slack events request signature verification failed");
+        return invalid_signature_response();
+    }
+
+    let is_form =
+        header_str(header::CONTENT_TYPE.as_str()).starts_with("application/x-www-form-urlencoded");
+    let inbound = if is_form {
+        if !state.replay_guard.check_and_record(&body) {
+            tracing::debug!("Slack slash command replay acknowledged");
+            return StatusCode::OK.into_response();
+        }
+        let payload: serde_json::Map<String, Value> = url::form_urlencoded::parse(&body)
+            .map(|(key, value)| (key.into_owned(), Value::String(value.into_owned())))
+            .collect();
+        slack.inbound_from_slash_command(&Value::Object(payload))
+    } else {
+        let Ok(payload) = serde_json::from_slice::<Value>(&body) else {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({"error": "Invalid JSON payload"})),
+            )
+                .into_response();
+        };
+        match payload.get("type").and_then(Value::as_str) {
+            Some("url_verification") => {
+                let challenge = payload.get("challenge").cloned().unwrap_or(Value::Null);
+                return (
+                    StatusCode::OK,
+                    Json(serde_json::json!({ "challenge": challenge })),
+                )
+                    .into_response();
+            }
+            Some("event_callback") => {
+                // Slack retries (`X-Slack-Retry-Num`) deliveries it did not
+                // see acknowledged in time. A retry is only a duplicate when
+                // the original reached us; otherwise it is the first copy.
+                let event_id = payload.get("event_id").and_then(Value::as_str);
+                let key = event_id.map_or(&body[..], str::as_bytes);
+                if !state.replay_guard.check_and_record(key) {
+                    tracing::debug!(
+                        retry = headers.contains_key("X-Slack-Retry-Num"),
+                        "Slack event redelivery acknowledged"
+                    );
+                    return StatusCode::OK.into_response();
+                }
+                slack.inbound_from_event(&payload).await
+            }
+            _ => None,
+        }
+    };
+
+    if let Some(msg) = inbound {
+        tracing::info!(
+            "Slack message in {}: {}",
+            msg.sender,
+            truncate_with_ellipsis(&msg.content, 50)
+        );
+        if !webhook_inbox("slack").deliver(msg) {
+            tracing::warn!("Slack webhook inbox is full; dropping event");
+        }
+    }
+    StatusCode::OK.into_response()
+}
+
+fn invalid_signature_response() -> Response {
+    (
+        StatusCode::UNAUTHORIZED,
+        Json(serde_json::json!({"error": "Invalid signature"})),
+    )
+        .into_response()
+}
@@ -0,0 +1,80 @@
+//! Telegram webhook receiver (`POST /telegram/webhook`).
+//!
+//! Used when the Telegram channel has a `webhook_url`; the channel registers
+//! it with `setWebhook` and this route takes the updates instead of
+//! `getUpdates` polling. Updates are acknowledged right away and handed to
+//! the channel listener through its webhook inbox, so they run through the
+//! same pipeline as polled messages. The route is therefore only registered
+//! when that listener runs in the same process (`asteroniris daemon`).
+//! See: <https://core.telegram.org/bots/api#setwebhook>
+
+use super::AppState;
+use super::signature::verify_telegram_secret_token;
+use crate::transport::channels::webhook_inbox::webhook_inbox;
+use crate::utils::text::truncate_with_ellipsis;
+use axum::body::Bytes;
+use axum::extract::State;
+use axum::http::{HeaderMap, StatusCode};
+use axum::response::{IntoResponse, Json, Response};
+use serde_json::Value;
+
+/// POST /telegram/webhook -- Bot API updates
+pub(super) async fn handle_telegram_webhook(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Response {
+    let Some(telegram) = state.telegram.clone() else {
+        return (
+            StatusCode::NOT_FOUND,
+            Json(serde_json::json!({"error": "Telegram webhook not configured"})),
+        )
+            .into_response();
+    };
+    let Some(secret) = telegram.webhook_secret() else {
+        return invalid_secret_response();
+    };
+
+    let token = headers
+        .get("X-Telegram-Bot-Api-Secret-Token")
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("");
+    if !verify_telegram_secret_token(secret, token) {
+        tracing::warn!("Telegram webhook secret token verification failed");
+        return invalid_secret_response();
+    }
+
+    // Telegram redelivers updates it did not see acknowledged.
+    if !state.replay_guard.check_and_record(&body) {
+        tracing::debug!("Telegram update redelivery acknowledged");
+        return StatusCode::OK.into_response();
+    }
+
+    let Ok(update) = serde_json::from_slice::<Value>(&body) else {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({"error": "Invalid JSON payload"})),
+        )
+            .into_response();
+    };
+
+    if let Some(msg) = telegram.inbound_from_update(&update).await {
+        tracing::info!(
+            "Telegram message in {}: {}",
+            msg.sender,
+            truncate_with_ellipsis(&msg.content, 50)
+        );
+        if !webhook_inbox("telegram").deliver(msg) {
+            tracing::warn!("Telegram webhook inbox is full; dropping update");
+        }
+    }
+    StatusCode::OK.into_response()
+}
+
+fn invalid_secret_response() -> Response {
+    (
+        StatusCode::UNAUTHORIZED,
+        Json(serde_json::json!({"error": "Invalid secret token"})),
+    )
+        .into_response()
+}
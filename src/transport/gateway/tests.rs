@@ -322,6 +322,7 @@ async fn webhook_policy_blocks_when_action_limit_is_exhausted() {
         system_prompt: "test-system".to_string(),
         openai_compat_api_keys: None,
         mem,
+        embedder: Arc::new(crate::memory::NoopEmbedding),
         auto_save: false,
         webhook_secret: Some(Arc::from("test-secret")),
         pairing: Arc::new(PairingGuard::new(false, &[], None)),
@@ -329,6 +330,10 @@ async fn webhook_policy_blocks_when_action_limit_is_exhausted() {
         whatsapp: None,
         #[cfg(feature = "whatsapp")]
         whatsapp_app_secret: None,
+        #[cfg(feature = "slack")]
+        slack: None,
+        #[cfg(feature = "telegram")]
+        telegram: None,
         defense_mode: GatewayDefenseMode::Enforce,
         defense_kill_switch: false,
         security: Arc::new(SecurityPolicy {
@@ -336,6 +341,8 @@ async fn webhook_policy_blocks_when_action_limit_is_exhausted() {
             ..SecurityPolicy::default()
         }),
         replay_guard: Arc::new(ReplayGuard::new()),
+        sessions: None,
+        usage: None,
     };
 
     let mut headers = HeaderMap::new();
@@ -346,6 +353,7 @@ async fn webhook_policy_blocks_when_action_limit_is_exhausted() {
         headers,
         Ok(Json(WebhookBody {
             message: "hello".to_string(),
+            conversation_id: None,
         })),
     )
     .await
@@ -376,6 +384,7 @@ async fn webhook_audit_mode_still_blocks_missing_bearer_when_paired() {
         system_prompt: "test-system".to_string(),
         openai_compat_api_keys: None,
         mem,
+        embedder: Arc::new(crate::memory::NoopEmbedding),
         auto_save: false,
         webhook_secret: None,
         pairing: Arc::new(PairingGuard::new(true, &[hash_token("valid-token")], None)),
@@ -383,10 +392,16 @@ async fn webhook_audit_mode_still_blocks_missing_bearer_when_paired() {
         whatsapp: None,
         #[cfg(feature = "whatsapp")]
         whatsapp_app_secret: None,
+        #[cfg(feature = "slack")]
+        slack: None,
+        #[cfg(feature = "telegram")]
+        telegram: None,
         defense_mode: GatewayDefenseMode::Audit,
         defense_kill_switch: false,
         security: Arc::new(SecurityPolicy::default()),
         replay_guard: Arc::new(ReplayGuard::new()),
+        sessions: None,
+        usage: None,
     };
 
     let response = handle_webhook(
@@ -394,6 +409,7 @@ async fn webhook_audit_mode_still_blocks_missing_bearer_when_paired() {
         HeaderMap::new(),
         Ok(Json(WebhookBody {
             message: "hello".to_string(),
+            conversation_id: None,
         })),
     )
     .await
@@ -424,6 +440,7 @@ async fn webhook_replay_guard_rejects_duplicate_payload() {
         system_prompt: "test-system".to_string(),
         openai_compat_api_keys: None,
         mem,
+        embedder: Arc::new(crate::memory::NoopEmbedding),
         auto_save: false,
         webhook_secret: Some(Arc::from("test-secret")),
         pairing: Arc::new(PairingGuard::new(false, &[], None)),
@@ -431,10 +448,16 @@ async fn webhook_replay_guard_rejects_duplicate_payload() {
         whatsapp: None,
         #[cfg(feature = "whatsapp")]
         whatsapp_app_secret: None,
+        #[cfg(feature = "slack")]
+        slack: None,
+        #[cfg(feature = "telegram")]
+        telegram: None,
         defense_mode: GatewayDefenseMode::Enforce,
         defense_kill_switch: false,
         security: Arc::new(SecurityPolicy::default()),
         replay_guard: Arc::new(ReplayGuard::new()),
+        sessions: None,
+        usage: None,
     };
 
     let mut headers = HeaderMap::new();
@@ -445,6 +468,7 @@ async fn webhook_replay_guard_rejects_duplicate_payload() {
         headers.clone(),
         Ok(Json(WebhookBody {
             message: "same payload".to_string(),
+            conversation_id: None,
         })),
     )
     .await
@@ -456,6 +480,7 @@ async fn webhook_replay_guard_rejects_duplicate_payload() {
         headers,
         Ok(Json(WebhookBody {
             message: "same payload".to_string(),
+            conversation_id: None,
         })),
     )
     .await
@@ -566,6 +591,7 @@ fn effective_defense_mode_kill_switch_forces_audit() {
         system_prompt: "test-system".to_string(),
         openai_compat_api_keys: None,
         mem,
+        embedder: Arc::new(crate::memory::NoopEmbedding),
         auto_save: false,
         webhook_secret: None,
         pairing: Arc::new(PairingGuard::new(false, &[], None)),
@@ -573,10 +599,16 @@ fn effective_defense_mode_kill_switch_forces_audit() {
         whatsapp: None,
         #[cfg(feature = "whatsapp")]
         whatsapp_app_secret: None,
+        #[cfg(feature = "slack")]
+        slack: None,
+        #[cfg(feature = "telegram")]
+        telegram: None,
         defense_mode: GatewayDefenseMode::Enforce,
         defense_kill_switch: true,
         security: Arc::new(SecurityPolicy::default()),
         replay_guard: Arc::new(ReplayGuard::new()),
+        sessions: None,
+        usage: None,
     };
     assert!(matches!(
         defense::effective_defense_mode(&state),
@@ -653,6 +685,7 @@ fn make_test_state(pairing: PairingGuard) -> AppState {
         system_prompt: "test-system".to_string(),
         openai_compat_api_keys: None,
         mem: Arc::new(crate::memory::MarkdownMemory::new(tmp.path())),
+        embedder: Arc::new(crate::memory::NoopEmbedding),
         auto_save: false,
         webhook_secret: None,
         pairing: Arc::new(pairing),
@@ -660,10 +693,16 @@ fn make_test_state(pairing: PairingGuard) -> AppState {
         whatsapp: None,
         #[cfg(feature = "whatsapp")]
         whatsapp_app_secret: None,
+        #[cfg(feature = "slack")]
+        slack: None,
+        #[cfg(feature = "telegram")]
+        telegram: None,
         defense_mode: GatewayDefenseMode::Enforce,
         defense_kill_switch: false,
         security: Arc::new(SecurityPolicy::default()),
         replay_guard: Arc::new(ReplayGuard::new()),
+        sessions: None,
+        usage: None,
     }
 }
 
@@ -713,6 +752,7 @@ async fn pair_then_webhook_accepts_fresh_bearer() {
         webhook_headers,
         Ok(Json(WebhookBody {
             message: "hello after pair".to_string(),
+            conversation_id: None,
         })),
     )
     .await
@@ -751,6 +791,701 @@ async fn handle_health_reflects_paired_when_tokens_exist() {
     assert_eq!(json["paired"], true);
 }
 
+// ---------------------------------------------------------------
+// Plan endpoint tests
+// ---------------------------------------------------------------
+
+fn make_plans_state(workspace: &std::path::Path) -> AppState {
+    let mut state = make_test_state(PairingGuard::new(false, &[], None));
+    let config = Config {
+        workspace_dir: workspace.to_path_buf(),
+        ..Config::default()
+    };
+    state.config = Arc::new(config);
+    state
+}
+
+async fn response_json(response: axum::response::Response) -> serde_json::Value {
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    serde_json::from_slice(&body).unwrap()
+}
+
+#[tokio::test]
+async fn plans_endpoints_list_show_and_cancel() {
+    use super::plans_route::{
+        handle_cancel_plan, handle_get_plan, handle_list_plans, handle_resume_plan,
+    };
+    use axum::extract::Path;
+
+    let tmp = TempDir::new().unwrap();
+    let store = crate::planner::PlanStore::open(tmp.path()).await.unwrap();
+    let plan = crate::planner::PlanParser::parse(
+        r#"{"id":"p1","description":"demo","steps":[{"id":"a","description":"a","action":{"kind":"checkpoint","label":"a"}}]}"#,
+    )
+    .unwrap();
+    let policy = crate::planner::PlanPolicyContext::from_execution_context(
+        &crate::tools::ExecutionContext::test_default(Arc::new(SecurityPolicy::default())),
+    );
+    store.create(&plan, "default", &policy).await.unwrap();
+    let state = make_plans_state(tmp.path());
+
+    let list = handle_list_plans(State(state.clone()))
+        .await
+        .into_response();
+    assert_eq!(list.status(), StatusCode::OK);
+    let json = response_json(list).await;
+    assert_eq!(json["plans"][0]["id"], "p1");
+    assert_eq!(json["plans"][0]["status"], "running");
+    assert_eq!(json["plans"][0]["total_steps"], 1);
+
+    let cancel = handle_cancel_plan(State(state.clone()), Path("p1".to_string()))
+        .await
+        .into_response();
+    assert_eq!(cancel.status(), StatusCode::OK);
+    let again = handle_cancel_plan(State(state.clone()), Path("p1".to_string()))
+        .await
+        .into_response();
+    assert_eq!(again.status(), StatusCode::CONFLICT);
+
+    let cancelled = handle_resume_plan(State(state.clone()), Path("p1".to_string()))
+        .await
+        .into_response();
+    assert_eq!(cancelled.status(), StatusCode::CONFLICT);
+
+    let show = handle_get_plan(State(state.clone()), Path("p1".to_string()))
+        .await
+        .into_response();
+    let json = response_json(show).await;
+    assert_eq!(json["status"], "cancelled");
+    assert_eq!(json["plan"]["steps"][0]["status"], "pending");
+
+    let missing = handle_get_plan(State(state), Path("nope".to_string()))
+        .await
+        .into_response();
+    assert_eq!(missing.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn plan_resume_endpoint_claims_the_plan_once() {
+    use super::plans_route::handle_resume_plan;
+    use axum::extract::Path;
+
+    let tmp = TempDir::new().unwrap();
+    let store = crate::planner::PlanStore::open(tmp.path()).await.unwrap();
+    let plan = crate::planner::PlanParser::parse(
+        r#"{"id":"p1","description":"demo","steps":[{"id":"a","description":"a","action":{"kind":"checkpoint","label":"a"}}]}"#,
+    )
+    .unwrap();
+    let policy = crate::planner::PlanPolicyContext::from_execution_context(
+        &crate::tools::ExecutionContext::test_default(Arc::new(SecurityPolicy::default())),
+    );
+    store.create(&plan, "default", &policy).await.unwrap();
+    store
+        .set_status("p1", crate::planner::PlanRunStatus::Failed)
+        .await
+        .unwrap();
+    let state = make_plans_state(tmp.path());
+
+    let first = handle_resume_plan(State(state.clone()), Path("p1".to_string()))
+        .await
+        .into_response();
+    assert_eq!(first.status(), StatusCode::ACCEPTED);
+    let second = handle_resume_plan(State(state.clone()), Path("p1".to_string()))
+        .await
+        .into_response();
+    assert_eq!(second.status(), StatusCode::CONFLICT);
+
+    let missing = handle_resume_plan(State(state), Path("nope".to_string()))
+        .await
+        .into_response();
+    assert_eq!(missing.status(), StatusCode::NOT_FOUND);
+}
+
+// ---------------------------------------------------------------
+// OpenAI-compatible catalog endpoint tests
+// ---------------------------------------------------------------
+
+/// Reports a fixed model catalog.
+struct CatalogProvider {
+    models: Vec<String>,
+}
+
+impl Provider for CatalogProvider {
+    fn name(&self) -> &str {
+        "catalog-test"
+    }
+
+    fn list_models(
+        &self,
+    ) -> Pin<Box<dyn Future<Output = anyhow::Result<Vec<String>>> + Send + '_>> {
+        Box::pin(async move { Ok(self.models.clone()) })
+    }
+
+    fn chat_with_system<'a>(
+        &'a self,
+        _system_prompt: Option<&'a str>,
+        _message: &'a str,
+        _model: &'a str,
+        _temperature: f64,
+    ) -> Pin<Box<dyn Future<Output = anyhow::Result<String>> + Send + 'a>> {
+        Box::pin(async { Ok("ok".to_string()) })
+    }
+}
+
+#[tokio::test]
+async fn models_endpoint_lists_default_model_and_provider_chain() {
+    use super::openai_compat_models::handle_list_models;
+
+    let mut state = make_test_state(PairingGuard::new(false, &[], None));
+    let mut config = Config {
+        default_provider: Some("anthropic".to_string()),
+        ..Config::default()
+    };
+    config.reliability.fallback_providers = vec!["openai".to_string()];
+    state.config = Arc::new(config);
+
+    let response = handle_list_models(State(state.clone()))
+        .await
+        .into_response();
+    let json = response_json(response).await;
+    assert_eq!(json["object"], "list");
+    assert_eq!(json["data"].as_array().unwrap().len(), 1);
+    assert_eq!(json["data"][0]["id"], state.model);
+    assert_eq!(json["data"][0]["owned_by"], "anthropic");
+    assert_eq!(
+        json["data"][0]["providers"],
+        serde_json::json!(["anthropic", "openai"])
+    );
+}
+
+#[tokio::test]
+async fn models_endpoint_adds_routed_and_provider_listed_models() {
+    use super::openai_compat_models::handle_list_models;
+    use crate::config::RouteRule;
+
+    let mut state = make_test_state(PairingGuard::new(false, &[], None));
+    let mut config = Config {
+        default_provider: Some("ollama".to_string()),
+        ..Config::default()
+    };
+    config.routing.enabled = true;
+    config.routing.rules = vec![
+        RouteRule {
+            provider: Some("anthropic".to_string()),
+            model: "claude-haiku".to_string(),
+            ..RouteRule::default()
+        },
+        RouteRule {
+            model: "llama3:8b".to_string(),
+            ..RouteRule::default()
+        },
+    ];
+    state.config = Arc::new(config);
+    state.provider = Arc::new(CatalogProvider {
+        models: vec!["llama3:8b".to_string(), "qwen2:7b".to_string()],
+    });
+
+    let response = handle_list_models(State(state.clone()))
+        .await
+        .into_response();
+    let json = response_json(response).await;
+    let ids: Vec<&str> = json["data"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|entry| entry["id"].as_str().unwrap())
+        .collect();
+    assert_eq!(
+        ids,
+        [
+            state.model.as_str(),
+            "claude-haiku",
+            "llama3:8b",
+            "qwen2:7b"
+        ]
+    );
+    assert_eq!(json["data"][1]["owned_by"], "anthropic");
+    assert_eq!(
+        json["data"][1]["providers"],
+        serde_json::json!(["anthropic"])
+    );
+    assert_eq!(json["data"][3]["owned_by"], "ollama");
+}
+
+#[tokio::test]
+async fn embeddings_endpoint_requires_configured_provider() {
+    use super::openai_compat_embeddings::handle_embeddings;
+    use super::openai_compat_types::EmbeddingRequest;
+
+    let state = make_test_state(PairingGuard::new(false, &[], None));
+    let request: EmbeddingRequest =
+        serde_json::from_value(serde_json::json!({"input": "hello"})).unwrap();
+
+    let response = handle_embeddings(State(state), Json(request)).await;
+    assert_eq!(response.status(), StatusCode::NOT_IMPLEMENTED);
+}
+
+#[tokio::test]
+async fn metrics_endpoint_serves_text_exposition() {
+    use super::metrics_route::handle_metrics;
+
+    let tmp = TempDir::new().unwrap();
+    let state = make_plans_state(tmp.path());
+
+    let response = handle_metrics(State(state)).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        response.headers()["content-type"],
+        crate::runtime::observability::METRICS_CONTENT_TYPE
+    );
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let text = String::from_utf8(body.to_vec()).unwrap();
+    assert!(text.contains("# TYPE asteroniris_tool_calls_total counter"));
+    assert!(text.contains("# TYPE asteroniris_request_latency_seconds histogram"));
+}
+
+#[tokio::test]
+async fn usage_endpoints_report_breakdowns_and_export_csv() {
+    use super::usage_route::{UsageQuery, handle_usage_export, handle_usage_summary};
+    use crate::runtime::usage::{TurnUsage, UsageRecorder};
+    use axum::extract::Query;
+
+    let tmp = TempDir::new().unwrap();
+    let mut state = make_plans_state(tmp.path());
+    let recorder =
+        UsageRecorder::open(tmp.path(), &state.config.usage, Arc::clone(&state.security))
+            .await
+            .unwrap();
+    for entity_id in ["gateway:alice", "gateway:alice", "gateway:bob"] {
+        recorder
+            .record_turn(TurnUsage {
+                entity_id,
+                channel: "webhook",
+                session_id: None,
+                provider: "openai",
+                model: "gpt-4o-mini",
+                input_tokens: Some(1_000),
+                output_tokens: Some(100),
+            })
+            .await;
+    }
+    state.usage = Some(Arc::new(recorder));
+
+    let summary = handle_usage_summary(
+        State(state.clone()),
+        Query(UsageQuery {
+            by: Some("entity".into()),
+            ..UsageQuery::default()
+        }),
+    )
+    .await;
+    assert_eq!(summary.status(), StatusCode::OK);
+    let json = response_json(summary).await;
+    assert_eq!(json["group"], "entity");
+    assert_eq!(json["total"]["record_count"], 3);
+    assert_eq!(json["breakdown"][0]["key"], "gateway:alice");
+    assert_eq!(json["breakdown"][0]["record_count"], 2);
+
+    let bad = handle_usage_summary(
+        State(state.clone()),
+        Query(UsageQuery {
+            by: Some("planet".into()),
+            ..UsageQuery::default()
+        }),
+    )
+    .await;
+    assert_eq!(bad.status(), StatusCode::BAD_REQUEST);
+
+    let export = handle_usage_export(State(state), Query(UsageQuery::default())).await;
+    assert_eq!(export.status(), StatusCode::OK);
+    assert_eq!(export.headers()["content-type"], "text/csv; charset=utf-8");
+    let body = axum::body::to_bytes(export.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let csv = String::from_utf8(body.to_vec()).unwrap();
+    assert_eq!(csv.lines().count(), 4);
+    assert!(csv.starts_with("created_at,entity_id,channel,"));
+}
+
+#[tokio::test]
+async fn usage_records_never_contain_bearer_tokens() {
+    use super::openai_compat_handler::handle_chat_completions;
+    use super::usage_route::{UsageQuery, handle_usage_export, handle_usage_summary};
+    use crate::runtime::usage::UsageRecorder;
+    use axum::extract::Query;
+    use axum::http::{HeaderValue, header};
+
+    const TOKEN: &str = "sk-secret-gateway-token";
+    let tmp = TempDir::new().unwrap();
+    let mut state = make_plans_state(tmp.path());
+    state.openai_compat_api_keys = Some(vec![TOKEN.to_string()]);
+    state.usage = Some(Arc::new(
+        UsageRecorder::open(tmp.path(), &state.config.usage, Arc::clone(&state.security))
+            .await
+            .unwrap(),
+    ));
+
+    let mut headers = HeaderMap::new();
+    headers.insert(
+        header::AUTHORIZATION,
+        HeaderValue::from_str(&format!("Bearer {TOKEN}")).unwrap(),
+    );
+    let request = serde_json::from_value(serde_json::json!({
+        "model": "gpt-4o-mini",
+        "messages": [{ "role": "user", "content": "hello" }],
+        "tools": [{
+            "type": "function",
+            "function": { "name": "lookup", "parameters": { "type": "object" } }
+        }]
+    }))
+    .unwrap();
+    let response = handle_chat_completions(State(state.clone()), headers, Json(request)).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let summary = handle_usage_summary(
+        State(state.clone()),
+        Query(UsageQuery {
+            by: Some("entity".into()),
+            ..UsageQuery::default()
+        }),
+    )
+    .await;
+    let json = response_json(summary).await;
+    let entity = json["breakdown"][0]["key"].as_str().unwrap().to_string();
+    assert!(entity.starts_with("person:gateway.client-"), "{entity}");
+    assert!(!json.to_string().contains(TOKEN));
+
+    let export = handle_usage_export(State(state), Query(UsageQuery::default())).await;
+    let body = axum::body::to_bytes(export.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let csv = String::from_utf8(body.to_vec()).unwrap();
+    assert!(csv.contains(&entity));
+    assert!(!csv.contains(TOKEN));
+}
+
+/// Streams whatever the test pushes into its channel.
+struct ChannelProvider {
+    events: std::sync::Mutex<
+        Option<tokio::sync::mpsc::Receiver<anyhow::Result<crate::llm::StreamEvent>>>,
+    >,
+    options: std::sync::Mutex<Vec<crate::llm::ChatOptions>>,
+}
+
+impl ChannelProvider {
+    fn new(events: tokio::sync::mpsc::Receiver<anyhow::Result<crate::llm::StreamEvent>>) -> Self {
+        Self {
+            events: std::sync::Mutex::new(Some(events)),
+            options: std::sync::Mutex::new(Vec::new()),
+        }
+    }
+
+    fn seen_options(&self) -> Vec<crate::llm::ChatOptions> {
+        self.options
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .clone()
+    }
+}
+
+impl Provider for ChannelProvider {
+    fn name(&self) -> &str {
+        "channel-test"
+    }
+
+    fn chat_with_system<'a>(
+        &'a self,
+        _system_prompt: Option<&'a str>,
+        _message: &'a str,
+        _model: &'a str,
+        _temperature: f64,
+    ) -> Pin<Box<dyn Future<Output = anyhow::Result<String>> + Send + 'a>> {
+        Box::pin(async { anyhow::bail!("only streaming is supported") })
+    }
+
+    fn chat_with_tools_stream<'a>(
+        &'a self,
+        _system_prompt: Option<&'a str>,
+        _messages: &'a [crate::llm::ProviderMessage],
+        _tools: &'a [crate::tools::ToolSpec],
+        _model: &'a str,
+        options: &'a crate::llm::ChatOptions,
+    ) -> Pin<Box<dyn Future<Output = anyhow::Result<crate::llm::ProviderStream>> + Send + 'a>> {
+        self.options
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .push(options.clone());
+        let events = self
+            .events
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .take()
+            .expect("a single stream per test");
+        Box::pin(async move {
+            Ok(
+                Box::pin(tokio_stream::wrappers::ReceiverStream::new(events))
+                    as crate::llm::ProviderStream,
+            )
+        })
+    }
+}
+
+async fn next_sse_data(body: &mut axum::body::BodyDataStream, pending: &mut String) -> String {
+    use futures_util::StreamExt;
+
+    loop {
+        if let Some(end) = pending.find("\n\n") {
+            let frame = pending[..end].to_string();
+            pending.drain(..end + 2);
+            return frame.trim_start_matches("data: ").to_string();
+        }
+        let bytes = body.next().await.unwrap().unwrap();
+        pending.push_str(std::str::from_utf8(&bytes).unwrap());
+    }
+}
+
+fn completions_headers() -> HeaderMap {
+    use axum::http::{HeaderValue, header};
+
+    let mut headers = HeaderMap::new();
+    headers.insert(
+        header::AUTHORIZATION,
+        HeaderValue::from_static("Bearer sk-test"),
+    );
+    headers
+}
+
+#[tokio::test]
+async fn streamed_completions_forward_text_before_the_provider_finishes() {
+    use super::openai_compat_handler::handle_chat_completions;
+    use crate::llm::StreamEvent;
+
+    let tmp = TempDir::new().unwrap();
+    let (events, receiver) = tokio::sync::mpsc::channel(8);
+    let provider = Arc::new(ChannelProvider::new(receiver));
+    let mut state = make_plans_state(tmp.path());
+    state.provider = provider.clone();
+    state.openai_compat_api_keys = Some(vec!["sk-test".to_string()]);
+
+    let headers = completions_headers();
+    let request = serde_json::from_value(serde_json::json!({
+        "model": "gpt-4o-mini",
+        "stream": true,
+        "stop": ["STOP"],
+        "seed": 3,
+        "messages": [{ "role": "user", "content": "hello" }],
+        "tools": [{
+            "type": "function",
+            "function": { "name": "lookup", "parameters": { "type": "object" } }
+        }]
+    }))
+    .unwrap();
+    let response = handle_chat_completions(State(state), headers, Json(request)).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let mut body = response.into_body().into_data_stream();
+    let mut pending = String::new();
+
+    let role: serde_json::Value =
+        serde_json::from_str(&next_sse_data(&mut body, &mut pending).await).unwrap();
+    assert_eq!(role["choices"][0]["delta"]["role"], "assistant");
+
+    events
+        .send(Ok(StreamEvent::TextDelta {
+            text: "Hello there".into(),
+        }))
+        .await
+        .unwrap();
+    let first: serde_json::Value =
+        serde_json::from_str(&next_sse_data(&mut body, &mut pending).await).unwrap();
+    let first = first["choices"][0]["delta"]["content"]
+        .as_str()
+        .unwrap()
+        .to_string();
+    assert_eq!(first, "Hello there");
+
+    events
+        .send(Ok(StreamEvent::TextDelta {
+            text: ", world".into(),
+        }))
+        .await
+        .unwrap();
+    events
+        .send(Ok(StreamEvent::Done {
+            stop_reason: Some(crate::llm::StopReason::MaxTokens),
+            input_tokens: Some(3),
+            output_tokens: Some(5),
+        }))
+        .await
+        .unwrap();
+    drop(events);
+
+    let mut text = first;
+    let finish_reason = loop {
+        let data = next_sse_data(&mut body, &mut pending).await;
+        let chunk: serde_json::Value = serde_json::from_str(&data).unwrap();
+        let choice = &chunk["choices"][0];
+        if let Some(content) = choice["delta"]["content"].as_str() {
+            text.push_str(content);
+        }
+        if let Some(reason) = choice["finish_reason"].as_str() {
+            break reason.to_string();
+        }
+    };
+    assert_eq!(text, "Hello there, world");
+    assert_eq!(finish_reason, "length");
+    assert_eq!(next_sse_data(&mut body, &mut pending).await, "[DONE]");
+    let options = provider.seen_options();
+    assert_eq!(options[0].stop_sequences, ["STOP"]);
+    assert_eq!(options[0].seed, Some(3));
+}
+
+#[tokio::test]
+async fn agent_completions_send_output_limits_to_the_provider() {
+    use super::openai_compat_handler::handle_chat_completions;
+    use crate::llm::StreamEvent;
+
+    let tmp = TempDir::new().unwrap();
+    let (events, receiver) = tokio::sync::mpsc::channel(8);
+    let provider = Arc::new(ChannelProvider::new(receiver));
+    let mut state = make_plans_state(tmp.path());
+    state.provider = provider.clone();
+    state.openai_compat_api_keys = Some(vec!["sk-test".to_string()]);
+
+    events
+        .send(Ok(StreamEvent::TextDelta {
+            text: "a long answer that the provider cut".into(),
+        }))
+        .await
+        .unwrap();
+    events
+        .send(Ok(StreamEvent::Done {
+            stop_reason: Some(crate::llm::StopReason::MaxTokens),
+            input_tokens: Some(3),
+            output_tokens: Some(2),
+        }))
+        .await
+        .unwrap();
+    drop(events);
+
+    let request = serde_json::from_value(serde_json::json!({
+        "model": "gpt-4o-mini",
+        "stream": true,
+        "max_tokens": 2,
+        "stop": "END",
+        "top_p": 0.5,
+        "seed": 7,
+        "response_format": { "type": "json_object" },
+        "messages": [{ "role": "user", "content": "hello" }]
+    }))
+    .unwrap();
+    let response =
+        handle_chat_completions(State(state), completions_headers(), Json(request)).await;
+    let mut body = response.into_body().into_data_stream();
+    let mut pending = String::new();
+
+    let mut text = String::new();
+    let finish_reason = loop {
+        let data = next_sse_data(&mut body, &mut pending).await;
+        let chunk: serde_json::Value = serde_json::from_str(&data).unwrap();
+        let choice = &chunk["choices"][0];
+        if let Some(content) = choice["delta"]["content"].as_str() {
+            text.push_str(content);
+        }
+        if let Some(reason) = choice["finish_reason"].as_str() {
+            break reason.to_string();
+        }
+    };
+
+    // The provider's own cut is passed through untouched.
+    assert_eq!(text, "a long answer that the provider cut");
+    assert_eq!(finish_reason, "length");
+    let options = provider.seen_options();
+    assert_eq!(options[0].max_tokens, Some(2));
+    assert_eq!(options[0].stop_sequences, ["END"]);
+    assert_eq!(options[0].top_p, Some(0.5));
+    assert_eq!(options[0].seed, Some(7));
+    assert_eq!(
+        options[0].response_format,
+        Some(crate::llm::ResponseFormat::Json)
+    );
+}
+
+/// Answers every tool-loop call as if the output limit was hit.
+#[derive(Default)]
+struct TruncatingProvider {
+    options: std::sync::Mutex<Vec<crate::llm::ChatOptions>>,
+}
+
+impl Provider for TruncatingProvider {
+    fn name(&self) -> &str {
+        "truncating-test"
+    }
+
+    fn chat_with_system<'a>(
+        &'a self,
+        _system_prompt: Option<&'a str>,
+        _message: &'a str,
+        _model: &'a str,
+        _temperature: f64,
+    ) -> Pin<Box<dyn Future<Output = anyhow::Result<String>> + Send + 'a>> {
+        Box::pin(async { Ok(String::new()) })
+    }
+
+    fn chat_with_tools<'a>(
+        &'a self,
+        _system_prompt: Option<&'a str>,
+        _messages: &'a [crate::llm::ProviderMessage],
+        _tools: &'a [crate::tools::ToolSpec],
+        _model: &'a str,
+        options: &'a crate::llm::ChatOptions,
+    ) -> Pin<Box<dyn Future<Output = anyhow::Result<crate::llm::ProviderResponse>> + Send + 'a>>
+    {
+        self.options
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .push(options.clone());
+        Box::pin(async {
+            Ok(crate::llm::ProviderResponse {
+                stop_reason: Some(crate::llm::StopReason::MaxTokens),
+                ..crate::llm::ProviderResponse::text_only("partial".to_string())
+            })
+        })
+    }
+}
+
+#[tokio::test]
+async fn responses_endpoint_sends_max_output_tokens_and_reports_incomplete() {
+    use super::openai_compat_responses::handle_responses;
+
+    let tmp = TempDir::new().unwrap();
+    let provider = Arc::new(TruncatingProvider::default());
+    let mut state = make_plans_state(tmp.path());
+    state.provider = provider.clone();
+    state.openai_compat_api_keys = Some(vec!["sk-test".to_string()]);
+
+    let request = serde_json::from_value(serde_json::json!({
+        "model": "gpt-4o-mini",
+        "input": "hello",
+        "max_output_tokens": 5
+    }))
+    .unwrap();
+    let response = handle_responses(State(state), completions_headers(), Json(request)).await;
+    let json = response_json(response).await;
+
+    assert_eq!(json["status"], "incomplete");
+    assert_eq!(json["output_text"], "partial");
+    let options = provider
+        .options
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner)
+        .clone();
+    assert_eq!(options[0].max_tokens, Some(5));
+}
+
 // ---------------------------------------------------------------
 // WhatsApp verify handler tests
 // ---------------------------------------------------------------
@@ -773,6 +1508,7 @@ fn make_whatsapp_state() -> AppState {
         system_prompt: "test-system".to_string(),
         openai_compat_api_keys: None,
         mem: Arc::new(crate::memory::MarkdownMemory::new(tmp.path())),
+        embedder: Arc::new(crate::memory::NoopEmbedding),
         auto_save: false,
         webhook_secret: None,
         pairing: Arc::new(PairingGuard::new(false, &[], None)),
@@ -783,10 +1519,16 @@ fn make_whatsapp_state() -> AppState {
             vec![],
         ))),
         whatsapp_app_secret: Some(Arc::from("test-app-secret")),
+        #[cfg(feature = "slack")]
+        slack: None,
+        #[cfg(feature = "telegram")]
+        telegram: None,
         defense_mode: GatewayDefenseMode::Enforce,
         defense_kill_switch: false,
         security: Arc::new(SecurityPolicy::default()),
         replay_guard: Arc::new(ReplayGuard::new()),
+        sessions: None,
+        usage: None,
     }
 }
 
@@ -965,3 +1707,376 @@ async fn whatsapp_message_ack_empty_messages() {
     let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
     assert_eq!(json["status"], "ok");
 }
+
+// ---------------------------------------------------------------
+// Slack Events API tests
+// ---------------------------------------------------------------
+
+#[cfg(feature = "slack")]
+fn compute_slack_signature(secret: &str, timestamp: &str, body: &[u8]) -> String {
+    use hmac::{Hmac, Mac};
+    use sha2::Sha256;
+
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).unwrap();
+    mac.update(format!("v0:{timestamp}:").as_bytes());
+    mac.update(body);
+    format!("v0={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+#[cfg(feature = "slack")]
+fn now_secs() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_secs()
+}
+
+#[cfg(feature = "slack")]
+fn make_slack_state() -> AppState {
+    make_slack_state_allowing(vec![])
+}
+
+#[cfg(feature = "slack")]
+fn make_slack_state_allowing(allowed_users: Vec<String>) -> AppState {
+    let mut state = make_test_state(PairingGuard::new(false, &[], None));
+    state.slack = Some(Arc::new(
+        crate::transport::channels::SlackChannel::new("xoxb-test".into(), None, allowed_users)
+            .with_signing_secret(Some("slack-secret".into())),
+    ));
+    state
+}
+
+#[cfg(feature = "slack")]
+fn signed_slack_headers(secret: &str, timestamp: u64, body: &[u8]) -> HeaderMap {
+    let timestamp = timestamp.to_string();
+    let mut headers = HeaderMap::new();
+    headers.insert("X-Slack-Request-Timestamp", timestamp.parse().unwrap());
+    headers.insert(
+        "X-Slack-Signature",
+        compute_slack_signature(secret, &timestamp, body)
+            .parse()
+            .unwrap(),
+    );
+    headers
+}
+
+#[cfg(feature = "slack")]
+#[test]
+fn slack_signature_valid() {
+    let body = b"token=x&command=%2Fask";
+    let signature = compute_slack_signature("secret", "1700000000", body);
+    assert!(super::signature::verify_slack_signature(
+        "secret",
+        "1700000000",
+        body,
+        &signature,
+        1_700_000_010
+    ));
+}
+
+#[cfg(feature = "slack")]
+#[test]
+fn slack_signature_rejects_wrong_secret_and_missing_prefix() {
+    let body = b"{}";
+    let signature = compute_slack_signature("other", "1700000000", body);
+    assert!(!super::signature::verify_slack_signature(
+        "secret",
+        "1700000000",
+        body,
+        &signature,
+        1_700_000_000
+    ));
+    let signature = compute_slack_signature("secret", "1700000000", body);
+    assert!(!super::signature::verify_slack_signature(
+        "secret",
+        "1700000000",
+        body,
+        signature.trim_start_matches("v0="),
+        1_700_000_000
+    ));
+}
+
+#[cfg(feature = "slack")]
+#[test]
+fn slack_signature_rejects_stale_or_malformed_timestamps() {
+    let body = b"{}";
+    let signature = compute_slack_signature("secret", "1700000000", body);
+    assert!(!super::signature::verify_slack_signature(
+        "secret",
+        "1700000000",
+        body,
+        &signature,
+        1_700_000_000 + 301
+    ));
+    let signature = compute_slack_signature("secret", "soon", body);
+    assert!(!super::signature::verify_slack_signature(
+        "secret", "soon", body, &signature, 0
+    ));
+}
+
+#[cfg(feature = "slack")]
+#[tokio::test]
+async fn slack_events_answers_url_verification() {
+    let body = br#"{"type":"url_verification","challenge":"abc123"}"#;
+    let headers = signed_slack_headers("slack-secret", now_secs(), body);
+    let response = super::slack_route::handle_slack_events(
+        State(make_slack_state()),
+        headers,
+        axum::body::Bytes::from_static(body),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(json["challenge"], "abc123");
+}
+
+#[cfg(feature = "slack")]
+#[tokio::test]
+async fn slack_events_rejects_bad_signature() {
+    let body = br#"{"type":"url_verification","challenge":"abc123"}"#;
+    let headers = signed_slack_headers("wrong-secret", now_secs(), body);
+    let response = super::slack_route::handle_slack_events(
+        State(make_slack_state()),
+        headers,
+        axum::body::Bytes::from_static(body),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[cfg(feature = "slack")]
+#[tokio::test]
+async fn slack_events_not_configured_returns_404() {
+    let body = br#"{"type":"url_verification","challenge":"abc123"}"#;
+    let headers = signed_slack_headers("slack-secret", now_secs(), body);
+    let response = super::slack_route::handle_slack_events(
+        State(make_test_state(PairingGuard::new(false, &[], None))),
+        headers,
+        axum::body::Bytes::from_static(body),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+/// Status of `POST {path}` against the full router built from `state`.
+#[cfg(any(feature = "slack", feature = "telegram"))]
+async fn post_to_app(state: AppState, path: &str) -> StatusCode {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let app = super::server::build_app(state, &[]);
+    let server = tokio::spawn(async move { axum::serve(listener, app).await });
+    let status = reqwest::Client::builder()
+        .no_proxy()
+        .build()
+        .unwrap()
+        .post(format!("http://{addr}{path}"))
+        .body("{}")
+        .send()
+        .await
+        .unwrap()
+        .status();
+    server.abort();
+    StatusCode::from_u16(status.as_u16()).unwrap()
+}
+
+#[cfg(feature = "slack")]
+#[tokio::test]
+async fn slack_events_route_is_only_served_with_a_channel_listener() {
+    // The standalone gateway leaves `slack` unset, so the route is absent
+    // rather than acknowledging events nothing would process.
+    let standalone = make_test_state(PairingGuard::new(false, &[], None));
+    assert_eq!(
+        post_to_app(standalone, "/slack/events").await,
+        StatusCode::NOT_FOUND
+    );
+    // Unsigned, so rejected, but the route exists.
+    assert_eq!(
+        post_to_app(make_slack_state(), "/slack/events").await,
+        StatusCode::UNAUTHORIZED
+    );
+}
+
+#[cfg(feature = "slack")]
+#[tokio::test]
+async fn slack_event_retries_are_dropped_only_once_seen() {
+    let state = make_slack_state_allowing(vec!["U1".into()]);
+    let deliver = |body: &'static [u8], retry: Option<&str>| {
+        let mut headers = signed_slack_headers("slack-secret", now_secs(), body);
+        if let Some(retry) = retry {
+            headers.insert("X-Slack-Retry-Num", retry.parse().unwrap());
+        }
+        super::slack_route::handle_slack_events(
+            State(state.clone()),
+            headers,
+            axum::body::Bytes::from_static(body),
+        )
+    };
+    let event = br#"{"type":"event_callback","event_id":"Ev1","authorizations":[{"user_id":"UBOT"}],"event":{"type":"message","channel":"D1","channel_type":"im","user":"U1","ts":"1.0","text":"hello"}}"#;
+    let retry_body = br#"{"type":"event_callback","event_id":"Ev1","authorizations":[{"user_id":"UBOT"}],"event":{"type":"message","channel":"D1","channel_type":"im","user":"U1","ts":"1.0","text":"hello"},"retry":true}"#;
+    let unseen = br#"{"type":"event_callback","event_id":"Ev2","authorizations":[{"user_id":"UBOT"}],"event":{"type":"message","channel":"D1","channel_type":"im","user":"U1","ts":"2.0","text":"lost original"}}"#;
+
+    assert_eq!(deliver(event, None).await.status(), StatusCode::OK);
+    // Same event id: the original was seen, so the retry is dropped.
+    assert_eq!(
+        deliver(retry_body, Some("1")).await.status(),
+        StatusCode::OK
+    );
+    // A retry whose original never arrived is handled.
+    assert_eq!(deliver(unseen, Some("2")).await.status(), StatusCode::OK);
+
+    let (tx, mut rx) = tokio::sync::mpsc::channel(4);
+    let drain = tokio::spawn(async move {
+        crate::transport::channels::webhook_inbox::webhook_inbox("slack")
+            .drain_into(&tx)
+            .await;
+    });
+    let mut received = Vec::new();
+    for _ in 0..2 {
+        let msg = tokio::time::timeout(std::time::Duration::from_secs(5), rx.recv())
+            .await
+            .expect("event reaches the listener")
+            .unwrap();
+        received.push(msg.content);
+    }
+    assert_eq!(received, ["hello", "lost original"]);
+    assert!(
+        tokio::time::timeout(std::time::Duration::from_millis(100), rx.recv())
+            .await
+            .is_err()
+    );
+    drop(rx);
+    drain.await.unwrap();
+}
+
+// ---------------------------------------------------------------
+// Telegram webhook tests
+// ---------------------------------------------------------------
+
+#[cfg(feature = "telegram")]
+fn make_telegram_state() -> AppState {
+    make_telegram_state_allowing(vec![])
+}
+
+#[cfg(feature = "telegram")]
+fn make_telegram_state_allowing(allowed_users: Vec<String>) -> AppState {
+    let mut state = make_test_state(PairingGuard::new(false, &[], None));
+    state.telegram = Some(Arc::new(
+        crate::transport::channels::TelegramChannel::new("123:ABC".into(), allowed_users)
+            .with_webhook(
+                Some("https://bot.example.com/telegram/webhook".into()),
+                Some("tg-secret".into()),
+            ),
+    ));
+    state
+}
+
+#[cfg(feature = "telegram")]
+fn telegram_headers(token: &str) -> HeaderMap {
+    let mut headers = HeaderMap::new();
+    headers.insert("X-Telegram-Bot-Api-Secret-Token", token.parse().unwrap());
+    headers
+}
+
+#[cfg(feature = "telegram")]
+const TELEGRAM_UPDATE: &[u8] = br#"{"update_id":1,"message":{"message_id":5,"from":{"id":7,"username":"eve"},"chat":{"id":42},"text":"hi"}}"#;
+
+#[cfg(feature = "telegram")]
+#[test]
+fn telegram_secret_token_must_match_exactly() {
+    use super::signature::verify_telegram_secret_token;
+    assert!(verify_telegram_secret_token("tg-secret", "tg-secret"));
+    assert!(!verify_telegram_secret_token("tg-secret", "tg-secre"));
+    assert!(!verify_telegram_secret_token("", ""));
+}
+
+#[cfg(feature = "telegram")]
+#[tokio::test]
+async fn telegram_webhook_rejects_wrong_secret_token() {
+    for headers in [telegram_headers("wrong"), HeaderMap::new()] {
+        let response = super::telegram_route::handle_telegram_webhook(
+            State(make_telegram_state()),
+            headers,
+            axum::body::Bytes::from_static(TELEGRAM_UPDATE),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    }
+}
+
+#[cfg(feature = "telegram")]
+#[tokio::test]
+async fn telegram_webhook_not_configured_returns_404() {
+    let response = super::telegram_route::handle_telegram_webhook(
+        State(make_test_state(PairingGuard::new(false, &[], None))),
+        telegram_headers("tg-secret"),
+        axum::body::Bytes::from_static(TELEGRAM_UPDATE),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[cfg(feature = "telegram")]
+#[tokio::test]
+async fn telegram_webhook_route_is_only_served_with_a_channel_listener() {
+    let standalone = make_test_state(PairingGuard::new(false, &[], None));
+    assert_eq!(
+        post_to_app(standalone, "/telegram/webhook").await,
+        StatusCode::NOT_FOUND
+    );
+    // No secret token header, so rejected, but the route exists.
+    assert_eq!(
+        post_to_app(make_telegram_state(), "/telegram/webhook").await,
+        StatusCode::UNAUTHORIZED
+    );
+}
+
+#[cfg(feature = "telegram")]
+#[tokio::test]
+async fn telegram_webhook_acknowledges_verified_updates() {
+    // The sender is not allowlisted, so the update is dropped after the ack.
+    let response = super::telegram_route::handle_telegram_webhook(
+        State(make_telegram_state()),
+        telegram_headers("tg-secret"),
+        axum::body::Bytes::from_static(TELEGRAM_UPDATE),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response = super::telegram_route::handle_telegram_webhook(
+        State(make_telegram_state()),
+        telegram_headers("tg-secret"),
+        axum::body::Bytes::from_static(b"not json"),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+#[cfg(feature = "telegram")]
+#[tokio::test]
+async fn telegram_webhook_hands_updates_to_the_channel_listener() {
+    let response = super::telegram_route::handle_telegram_webhook(
+        State(make_telegram_state_allowing(vec!["eve".into()])),
+        telegram_headers("tg-secret"),
+        axum::body::Bytes::from_static(TELEGRAM_UPDATE),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let (tx, mut rx) = tokio::sync::mpsc::channel(4);
+    let drain = tokio::spawn(async move {
+        crate::transport::channels::webhook_inbox::webhook_inbox("telegram")
+            .drain_into(&tx)
+            .await;
+    });
+    let msg = tokio::time::timeout(std::time::Duration::from_secs(5), rx.recv())
+        .await
+        .expect("update reaches the listener")
+        .unwrap();
+    assert_eq!(msg.channel, "telegram");
+    assert_eq!(msg.content, "hi");
+    drop(rx);
+    drain.await.unwrap();
+}
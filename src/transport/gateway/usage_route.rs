@@ -0,0 +1,153 @@
+//! Usage reporting endpoints (`/api/usage`) and per-turn accounting for
+//! gateway turns.
+//!
+//! Mirrors the `asteroniris usage` CLI over HTTP. Requests authenticate like
+//! `/v1/chat/completions`.
+
+use super::AppState;
+use super::openai_compat_auth::require_gateway_auth;
+use crate::runtime::usage::report::{ReportFormat, breakdown_csv, build_report, records_csv};
+use crate::runtime::usage::{SqliteUsageTracker, TurnUsage, UsageGroup, usage_db_path};
+use axum::Router;
+use axum::extract::{Query, State};
+use axum::http::{StatusCode, header};
+use axum::middleware;
+use axum::response::{IntoResponse, Json, Response};
+use axum::routing::get;
+use serde::Deserialize;
+
+#[derive(Debug, Default, Deserialize)]
+pub(super) struct UsageQuery {
+    pub by: Option<String>,
+    pub since: Option<String>,
+    pub until: Option<String>,
+    pub format: Option<String>,
+}
+
+/// Router serving the usage endpoints behind gateway auth.
+pub(super) fn usage_router(state: &AppState) -> Router<AppState> {
+    Router::new()
+        .route("/api/usage", get(handle_usage_summary))
+        .route("/api/usage/export", get(handle_usage_export))
+        .route_layer(middleware::from_fn_with_state(
+            state.clone(),
+            require_gateway_auth,
+        ))
+}
+
+/// Record a finished gateway turn when usage accounting is enabled.
+pub(super) async fn record_turn_usage(state: &AppState, usage: TurnUsage<'_>) {
+    if let Some(recorder) = &state.usage {
+        recorder.record_turn(usage).await;
+    }
+}
+
+/// GET /api/usage?by=entity&since=2026-09-01 — totals per value of `by`
+/// (default `model`) as JSON, or CSV with `format=csv`
+pub(super) async fn handle_usage_summary(
+    State(state): State<AppState>,
+    Query(query): Query<UsageQuery>,
+) -> Response {
+    let by = query.by.as_deref().unwrap_or("model");
+    let Some(group) = UsageGroup::parse(by) else {
+        return bad_request(&format!(
+            "unknown breakdown '{by}'; use entity, channel, session, model or day"
+        ));
+    };
+    let Some(format) = parse_format(query.format.as_deref(), ReportFormat::Json) else {
+        return bad_request("unknown format; use json or csv");
+    };
+    let tracker = match open_tracker(&state).await {
+        Ok(tracker) => tracker,
+        Err(response) => return response,
+    };
+    match build_report(
+        &tracker,
+        &state.config,
+        group,
+        query.since.as_deref(),
+        query.until.as_deref(),
+    )
+    .await
+    {
+        Ok(report) if format == ReportFormat::Csv => csv_response(breakdown_csv(&report)),
+        Ok(report) => (StatusCode::OK, Json(serde_json::json!(report))).into_response(),
+        Err(error) => internal_error(&error),
+    }
+}
+
+/// GET /api/usage/export?since=…&until=… — every recorded turn as CSV
+/// (default) or JSON
+pub(super) async fn handle_usage_export(
+    State(state): State<AppState>,
+    Query(query): Query<UsageQuery>,
+) -> Response {
+    let Some(format) = parse_format(query.format.as_deref(), ReportFormat::Csv) else {
+        return bad_request("unknown format; use json or csv");
+    };
+    let tracker = match open_tracker(&state).await {
+        Ok(tracker) => tracker,
+        Err(response) => return response,
+    };
+    match tracker
+        .records(query.since.as_deref(), query.until.as_deref())
+        .await
+    {
+        Ok(records) if format == ReportFormat::Json => (
+            StatusCode::OK,
+            Json(serde_json::json!({ "records": records })),
+        )
+            .into_response(),
+        Ok(records) => csv_response(records_csv(&records)),
+        Err(error) => internal_error(&error),
+    }
+}
+
+/// Open the workspace usage database for one request, creating it when the
+/// gateway has not recorded anything yet.
+async fn open_tracker(state: &AppState) -> Result<SqliteUsageTracker, Response> {
+    let db_path = usage_db_path(&state.config.workspace_dir);
+    if let Some(parent) = db_path.parent()
+        && let Err(error) = tokio::fs::create_dir_all(parent).await
+    {
+        return Err(internal_error(&error.into()));
+    }
+    SqliteUsageTracker::new(&db_path)
+        .await
+        .map_err(|error| internal_error(&error))
+}
+
+/// `json` or `csv`; the table format is CLI-only.
+fn parse_format(value: Option<&str>, default: ReportFormat) -> Option<ReportFormat> {
+    match value.map(ReportFormat::parse) {
+        None => Some(default),
+        Some(Some(ReportFormat::Table) | None) => None,
+        Some(format) => format,
+    }
+}
+
+fn csv_response(body: String) -> Response {
+    (
+        StatusCode::OK,
+        [(header::CONTENT_TYPE, "text/csv; charset=utf-8")],
+        body,
+    )
+        .into_response()
+}
+
+fn bad_request(message: &str) -> Response {
+    (
+        StatusCode::BAD_REQUEST,
+        Json(serde_json::json!({ "error": message })),
+    )
+        .into_response()
+}
+
+fn internal_error(error: &anyhow::Error) -> Response {
+    tracing::warn!("usage endpoint failed: {error}");
+    (
+        StatusCode::INTERNAL_SERVER_ERROR,
+        Json(serde_json::json!({ "error": "failed to read usage" })),
+    )
+        .into_response()
+}
@@ -1,16 +1,33 @@
 use super::events::{ClientMessage, ServerMessage};
+use super::handlers::client_identifier;
+use super::sessions::{
+    GatewayConversation, record_session_turn, resume_session, session_command_reply,
+};
+use super::usage_route::record_turn_usage;
 use super::{AppState, MAX_BODY_SIZE};
 use crate::agent::{
     IntegrationRuntimeTurnOptions, IntegrationTurnParams, LoopStopReason,
     run_main_session_turn_for_runtime_with_policy,
 };
+use crate::persona::channel_person_entity_id;
+use crate::runtime::usage::TurnUsage;
+use crate::security::approval::{
+    ApprovalBroker, ApprovalDecision, ApprovalGate, ApprovalRequest, PendingApprovals,
+};
+use crate::security::permissions::PermissionStore;
 use crate::security::policy::TenantPolicyContext;
 use crate::tools::ExecutionContext;
 use axum::extract::State;
 use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
 use axum::http::{HeaderMap, StatusCode};
 use axum::response::IntoResponse;
+use futures_util::stream::SplitStream;
+use futures_util::{SinkExt, StreamExt};
+use std::collections::VecDeque;
+use std::future::Future;
+use std::pin::Pin;
 use std::sync::Arc;
+use tokio::sync::mpsc;
 
 fn bearer_token(headers: &HeaderMap) -> Option<&str> {
     headers
@@ -64,22 +81,154 @@ pub async fn ws_handler(
         return response.into_response();
     }
 
-    ws.on_upgrade(move |socket| handle_socket(socket, state))
+    // The upgrade is authenticated, so this names the token holder.
+    let client = client_identifier(&headers, "websocket");
+    ws.on_upgrade(move |socket| handle_socket(socket, state, client))
         .into_response()
 }
 
-async fn handle_socket(mut socket: WebSocket, state: AppState) {
-    let connected = ServerMessage::connected();
-    if send_message(&mut socket, &connected).await.is_err() {
-        return;
+/// Approval requests on a WebSocket belong to the connection itself.
+const WS_APPROVAL_REQUESTER: &str = "websocket";
+
+/// Outbound half of a connection, shared by the turn and the read loop.
+#[derive(Clone)]
+struct WsConnection {
+    outbound: mpsc::Sender<Message>,
+    approvals: Arc<PendingApprovals>,
+    /// Authenticated caller; session ids from the client are scoped under it.
+    client: String,
+}
+
+impl WsConnection {
+    async fn send(&self, message: &ServerMessage) -> Result<(), ConnectionClosed> {
+        self.outbound
+            .send(Message::Text(message.to_json().into()))
+            .await
+            .map_err(|_| ConnectionClosed)
+    }
+}
+
+#[derive(Debug)]
+struct ConnectionClosed;
+
+/// Sends approval requests to the client as `approval_request` events.
+struct WsApprovalBroker {
+    connection: WsConnection,
+}
+
+impl ApprovalBroker for WsApprovalBroker {
+    fn request_approval<'a>(
+        &'a self,
+        request: &'a ApprovalRequest,
+    ) -> Pin<Box<dyn Future<Output = anyhow::Result<ApprovalDecision>> + Send + 'a>> {
+        Box::pin(async move {
+            let reply = self
+                .connection
+                .approvals
+                .register(&request.id, WS_APPROVAL_REQUESTER);
+            self.connection
+                .send(&ServerMessage::approval_request(request))
+                .await
+                .map_err(|_| anyhow::anyhow!("websocket closed before approval was requested"))?;
+            reply
+                .await
+                .map_err(|_| anyhow::anyhow!("approval request {} was abandoned", request.id))
+        })
+    }
+}
+
+async fn handle_socket(socket: WebSocket, state: AppState, client: String) {
+    let (mut sink, mut stream) = socket.split();
+    let (outbound, mut outbound_rx) = mpsc::channel::<Message>(32);
+    let writer = tokio::spawn(async move {
+        while let Some(message) = outbound_rx.recv().await {
+            if sink.send(message).await.is_err() {
+                break;
+            }
+        }
+    });
+
+    let connection = WsConnection {
+        outbound,
+        approvals: Arc::new(PendingApprovals::default()),
+        client,
+    };
+    if connection.send(&ServerMessage::connected()).await.is_ok() {
+        serve_connection(&connection, &mut stream, &state).await;
     }
 
-    while let Some(result) = socket.recv().await {
+    drop(connection);
+    let _ = writer.await;
+}
+
+/// Read loop. While a chat turn runs, incoming frames keep being read so
+/// approval responses reach the paused turn; other messages wait their turn.
+async fn serve_connection(
+    connection: &WsConnection,
+    stream: &mut SplitStream<WebSocket>,
+    state: &AppState,
+) {
+    let mut queued = VecDeque::new();
+    loop {
+        let client_message = match queued.pop_front() {
+            Some(message) => message,
+            None => match next_client_message(connection, stream).await {
+                Some(message) => message,
+                None => return,
+            },
+        };
+
+        let ClientMessage::Chat {
+            session_id,
+            message,
+        } = client_message
+        else {
+            if handle_client_message(connection, client_message)
+                .await
+                .is_err()
+            {
+                return;
+            }
+            continue;
+        };
+
+        let turn = run_chat_turn(connection, state, session_id, message);
+        tokio::pin!(turn);
+        loop {
+            tokio::select! {
+                result = &mut turn => {
+                    if result.is_err() {
+                        return;
+                    }
+                    break;
+                }
+                incoming = next_client_message(connection, stream) => match incoming {
+                    Some(ClientMessage::ApprovalResponse { id, decision }) => {
+                        if resolve_approval(connection, &id, decision).await.is_err() {
+                            return;
+                        }
+                    }
+                    Some(other) => queued.push_back(other),
+                    // Client went away: dropping the turn cancels it.
+                    None => return,
+                },
+            }
+        }
+    }
+}
+
+/// Next parsed client message, answering pings and malformed frames along
+/// the way. `None` once the connection is closed.
+async fn next_client_message(
+    connection: &WsConnection,
+    stream: &mut SplitStream<WebSocket>,
+) -> Option<ClientMessage> {
+    while let Some(result) = stream.next().await {
         let message = match result {
             Ok(message) => message,
             Err(error) => {
                 tracing::debug!("websocket receive error: {error}");
-                break;
+                return None;
             }
         };
 
@@ -89,127 +238,177 @@ async fn handle_socket(mut socket: WebSocket, state: AppState) {
                     let server_message = ServerMessage::error(format!(
                         "message too large: max {MAX_BODY_SIZE} bytes"
                     ));
-                    if send_message(&mut socket, &server_message).await.is_err() {
-                        break;
-                    }
+                    connection.send(&server_message).await.ok()?;
                     continue;
                 }
 
                 match serde_json::from_str::<ClientMessage>(&text) {
-                    Ok(client_message) => {
-                        if handle_client_message(&mut socket, &state, client_message)
-                            .await
-                            .is_err()
-                        {
-                            break;
-                        }
-                    }
+                    Ok(client_message) => return Some(client_message),
                     Err(error) => {
                         let server_message =
                             ServerMessage::error(format!("invalid message: {error}"));
-                        if send_message(&mut socket, &server_message).await.is_err() {
-                            break;
-                        }
+                        connection.send(&server_message).await.ok()?;
                     }
                 }
             }
-            Message::Close(_) => break,
+            Message::Close(_) => return None,
             Message::Ping(data) => {
-                if socket.send(Message::Pong(data)).await.is_err() {
-                    break;
-                }
+                connection.outbound.send(Message::Pong(data)).await.ok()?;
             }
             _ => {}
         }
     }
+    None
 }
 
-async fn handle_client_message(
-    socket: &mut WebSocket,
+async fn resolve_approval(
+    connection: &WsConnection,
+    id: &str,
+    decision: ApprovalDecision,
+) -> Result<(), ConnectionClosed> {
+    if connection
+        .approvals
+        .resolve(id, WS_APPROVAL_REQUESTER, decision)
+    {
+        return Ok(());
+    }
+    connection
+        .send(&ServerMessage::error(format!(
+            "no pending approval request: {id}"
+        )))
+        .await
+}
+
+#[allow(clippy::too_many_lines)]
+async fn run_chat_turn(
+    connection: &WsConnection,
     state: &AppState,
-    message: ClientMessage,
-) -> Result<(), axum::Error> {
-    match message {
-        ClientMessage::Chat {
-            session_id,
-            message,
-        } => {
-            let typing = ServerMessage::Typing { agent: true };
-            let _ = send_message(socket, &typing).await;
-
-            let source_identifier = session_id.as_deref().unwrap_or("websocket");
-            let entity_id = format!("gateway:{source_identifier}");
-            let policy_context = TenantPolicyContext::disabled();
-            let ctx = ExecutionContext {
-                security: Arc::clone(&state.security),
-                autonomy_level: state.security.autonomy,
-                entity_id: entity_id.clone(),
-                turn_number: 0,
-                workspace_dir: state.security.workspace_dir.clone(),
-                allowed_tools: None,
-                rate_limiter: Arc::clone(&state.rate_limiter),
-                tenant_context: policy_context.clone(),
-            };
-
-            match run_main_session_turn_for_runtime_with_policy(
-                IntegrationTurnParams {
-                    config: state.config.as_ref(),
-                    security: state.security.as_ref(),
-                    mem: Arc::clone(&state.mem),
-                    answer_provider: state.provider.as_ref(),
-                    reflect_provider: state.provider.as_ref(),
-                    system_prompt: state.system_prompt.as_str(),
-                    model_name: &state.model,
-                    temperature: state.temperature,
+    session_id: Option<String>,
+    message: String,
+) -> Result<(), ConnectionClosed> {
+    let typing = ServerMessage::Typing { agent: true };
+    let _ = connection.send(&typing).await;
+
+    let entity_id = channel_person_entity_id("gateway", &connection.client);
+    // Only named sessions keep conversation history, and only within the
+    // authenticated client's own conversations.
+    let conversation = session_id
+        .as_deref()
+        .map(|session_id| GatewayConversation::new("websocket", &entity_id, Some(session_id)));
+    if let Some(conversation) = &conversation
+        && let Some(reply) = session_command_reply(state, conversation, &message).await
+    {
+        let reply = ServerMessage::chat_response(session_id, reply, None, None);
+        return connection.send(&reply).await;
+    }
+    let session = match &conversation {
+        Some(conversation) => resume_session(state, conversation).await,
+        None => None,
+    };
+    let history = session
+        .as_ref()
+        .map_or(&[][..], |session| session.history.as_slice());
+    let policy_context = TenantPolicyContext::disabled();
+    let gate = ApprovalGate::new(Arc::new(WsApprovalBroker {
+        connection: connection.clone(),
+    }))
+    .with_permissions(Arc::new(PermissionStore::load(&state.config.workspace_dir)));
+    let ctx = ExecutionContext {
+        security: Arc::clone(&state.security),
+        autonomy_level: state.security.autonomy,
+        entity_id: entity_id.clone(),
+        turn_number: 0,
+        workspace_dir: state.security.workspace_dir.clone(),
+        allowed_tools: None,
+        rate_limiter: Arc::clone(&state.rate_limiter),
+        tenant_context: policy_context.clone(),
+        approval: Some(Arc::new(gate)),
+    };
+
+    match run_main_session_turn_for_runtime_with_policy(
+        IntegrationTurnParams {
+            config: state.config.as_ref(),
+            security: state.security.as_ref(),
+            mem: Arc::clone(&state.mem),
+            answer_provider: state.provider.as_ref(),
+            reflect_provider: state.provider.as_ref(),
+            system_prompt: state.system_prompt.as_str(),
+            model_name: &state.model,
+            temperature: state.temperature,
+            entity_id: &entity_id,
+            policy_context,
+            user_message: &message,
+        },
+        IntegrationRuntimeTurnOptions {
+            registry: Arc::clone(&state.registry),
+            max_tool_iterations: state.max_tool_loop_iterations,
+            repeated_tool_call_streak_limit: state.repeated_tool_call_streak_limit,
+            execution_context: ctx,
+            stream_sink: None,
+            conversation_history: history,
+            image_content: &[],
+            hooks: &[],
+            thinking: crate::llm::ThinkingLevel::Off,
+            request_options: None,
+        },
+    )
+    .await
+    {
+        Ok(result) => {
+            record_turn_usage(
+                state,
+                TurnUsage {
                     entity_id: &entity_id,
-                    policy_context,
-                    user_message: &message,
-                },
-                IntegrationRuntimeTurnOptions {
-                    registry: Arc::clone(&state.registry),
-                    max_tool_iterations: state.max_tool_loop_iterations,
-                    repeated_tool_call_streak_limit: state.repeated_tool_call_streak_limit,
-                    execution_context: ctx,
-                    stream_sink: None,
-                    conversation_history: &[],
-                    hooks: &[],
+                    channel: "websocket",
+                    session_id: session.as_ref().map(|session| session.id.as_str()),
+                    provider: state.provider.name(),
+                    model: &state.model,
+                    input_tokens: result.input_tokens,
+                    output_tokens: result.output_tokens,
                 },
             )
-            .await
-            {
-                Ok(result) => {
-                    if let LoopStopReason::Error(error) = &result.stop_reason {
-                        let server_message = ServerMessage::error(error);
-                        send_message(socket, &server_message).await?;
-                        return Ok(());
-                    }
-                    if matches!(result.stop_reason, LoopStopReason::MaxIterations) {
-                        tracing::warn!(session_id = ?session_id, "websocket tool loop hit max iterations");
-                    }
-                    let reply = ServerMessage::chat_response(
-                        session_id,
-                        result.final_text,
-                        None,
-                        result.tokens_used,
-                    );
-                    send_message(socket, &reply).await?;
+            .await;
+            if let LoopStopReason::Error(error) = &result.stop_reason {
+                let server_message = ServerMessage::error(error);
+                return connection.send(&server_message).await;
+            }
+            record_session_turn(state, session.as_ref(), &message, &result).await;
+            match result.stop_reason {
+                LoopStopReason::MaxIterations => {
+                    tracing::warn!(session_id = ?session_id, "websocket tool loop hit max iterations");
                 }
-                Err(error) => {
-                    let server_message = ServerMessage::error(error.to_string());
-                    send_message(socket, &server_message).await?;
+                LoopStopReason::ApprovalDenied => {
+                    let server_message =
+                        ServerMessage::error("stopped: the requested action was not approved");
+                    connection.send(&server_message).await?;
                 }
+                _ => {}
             }
+            let reply = ServerMessage::chat_response(
+                session_id,
+                result.final_text,
+                None,
+                result.tokens_used,
+            );
+            connection.send(&reply).await
         }
-        ClientMessage::Typing { .. } => {}
-        ClientMessage::Ping => {
-            send_message(socket, &ServerMessage::Pong).await?;
+        Err(error) => {
+            let server_message = ServerMessage::error(error.to_string());
+            connection.send(&server_message).await
         }
     }
-
-    Ok(())
 }
 
-async fn send_message(socket: &mut WebSocket, message: &ServerMessage) -> Result<(), axum::Error> {
-    let json = message.to_json();
-    socket.send(Message::Text(json.into())).await
+async fn handle_client_message(
+    connection: &WsConnection,
+    message: ClientMessage,
+) -> Result<(), ConnectionClosed> {
+    match message {
+        // Chat turns are driven by `serve_connection`.
+        ClientMessage::Chat { .. } | ClientMessage::Typing { .. } => Ok(()),
+        ClientMessage::ApprovalResponse { id, decision } => {
+            resolve_approval(connection, &id, decision).await
+        }
+        ClientMessage::Ping => connection.send(&ServerMessage::Pong).await,
+    }
 }
@@ -19,7 +19,7 @@ use asteroniris::runtime::observability::traits::{
 };
 use asteroniris::security::SecurityPolicy;
 use asteroniris::security::external_content::{ExternalAction, prepare_external_content};
-use asteroniris::security::policy::TenantPolicyContext;
+use asteroniris::security::policy::{AutonomyLevel, TenantPolicyContext};
 use asteroniris::tools::{ActionIntent, ActionOperator, NoopOperator};
 use std::future::Future;
 use std::pin::Pin;
@@ -189,11 +189,27 @@ async fn autonomy_cycle_reflect_queue_verify_and_intent_seam_stays_bounded() {
         config.autonomy.verify_repair_max_attempts
     );
 
+    // Unattended, the self task's checkpoint has nobody to approve it.
     let (executed, output) =
         asteroniris::platform::cron::scheduler::execute_job_once_for_integration(
             &config, &security, &queued[0],
         )
         .await;
+    assert!(!executed, "{output}");
+    assert!(output.contains("route=agent-planner"), "{output}");
+    assert!(output.contains("success=false"), "{output}");
+
+    let full_security = SecurityPolicy {
+        autonomy: AutonomyLevel::Full,
+        ..security.clone()
+    };
+    let (executed, output) =
+        asteroniris::platform::cron::scheduler::execute_job_once_for_integration(
+            &config,
+            &full_security,
+            &queued[0],
+        )
+        .await;
     assert!(executed, "{output}");
     assert!(output.contains("route=agent-planner"), "{output}");
     assert!(output.contains("success=true"), "{output}");
@@ -3,13 +3,15 @@ use std::sync::{Arc, Mutex};
 
 use anyhow::Result;
 use asteroniris::agent::{LoopStopReason, ToolLoop, ToolLoopRunParams};
+use asteroniris::config::ContextConfig;
+use asteroniris::llm::{ChatOptions, ThinkingLevel};
 use asteroniris::providers::response::{
     ContentBlock, ProviderMessage, ProviderResponse, StopReason,
 };
 use asteroniris::providers::traits::Provider;
 use asteroniris::security::{AutonomyLevel, EntityRateLimiter, SecurityPolicy};
 use asteroniris::tools::middleware::{ExecutionContext, default_middleware_chain};
-use asteroniris::tools::{FileReadTool, ShellTool, ToolRegistry, ToolSpec};
+use asteroniris::tools::{FileReadTool, ShellTool, ToolOutputTool, ToolRegistry, ToolSpec};
 use serde_json::json;
 use std::future::Future;
 use std::pin::Pin;
@@ -20,6 +22,7 @@ struct MockProvider {
     responses: Mutex<VecDeque<ProviderResponse>>,
     seen_system_prompts: Mutex<Vec<Option<String>>>,
     seen_messages: Mutex<Vec<Vec<ProviderMessage>>>,
+    seen_options: Mutex<Vec<ChatOptions>>,
 }
 
 impl MockProvider {
@@ -28,6 +31,7 @@ impl MockProvider {
             responses: Mutex::new(VecDeque::from(responses)),
             seen_system_prompts: Mutex::new(Vec::new()),
             seen_messages: Mutex::new(Vec::new()),
+            seen_options: Mutex::new(Vec::new()),
         }
     }
 
@@ -38,6 +42,13 @@ impl MockProvider {
             .clone()
     }
 
+    fn seen_options(&self) -> Vec<ChatOptions> {
+        self.seen_options
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .clone()
+    }
+
     fn seen_system_prompts(&self) -> Vec<Option<String>> {
         self.seen_system_prompts
             .lock()
@@ -67,9 +78,13 @@ impl Provider for MockProvider {
         messages: &'a [ProviderMessage],
         _tools: &'a [ToolSpec],
         _model: &'a str,
-        _temperature: f64,
+        options: &'a ChatOptions,
     ) -> Pin<Box<dyn Future<Output = Result<ProviderResponse>> + Send + 'a>> {
         Box::pin(async move {
+            self.seen_options
+                .lock()
+                .unwrap_or_else(std::sync::PoisonError::into_inner)
+                .push(options.clone());
             self.seen_system_prompts
                 .lock()
                 .unwrap_or_else(std::sync::PoisonError::into_inner)
@@ -176,6 +191,8 @@ async fn tool_loop_single_call() {
             image_content: &[],
             model: "test-model",
             temperature: 0.0,
+            thinking: ThinkingLevel::Off,
+            request_options: None,
             ctx: &ctx,
             stream_sink: None,
             conversation_history: &[],
@@ -220,6 +237,8 @@ async fn tool_loop_chain() {
             image_content: &[],
             model: "test-model",
             temperature: 0.0,
+            thinking: ThinkingLevel::Off,
+            request_options: None,
             ctx: &ctx,
             stream_sink: None,
             conversation_history: &[],
@@ -256,6 +275,8 @@ async fn tool_loop_max_iterations() {
             image_content: &[],
             model: "test-model",
             temperature: 0.0,
+            thinking: ThinkingLevel::Off,
+            request_options: None,
             ctx: &ctx,
             stream_sink: None,
             conversation_history: &[],
@@ -292,6 +313,8 @@ async fn tool_loop_hard_cap() {
             image_content: &[],
             model: "test-model",
             temperature: 0.0,
+            thinking: ThinkingLevel::Off,
+            request_options: None,
             ctx: &ctx,
             stream_sink: None,
             conversation_history: &[],
@@ -323,6 +346,8 @@ async fn tool_loop_error_recovery() {
             image_content: &[],
             model: "test-model",
             temperature: 0.0,
+            thinking: ThinkingLevel::Off,
+            request_options: None,
             ctx: &ctx,
             stream_sink: None,
             conversation_history: &[],
@@ -365,6 +390,8 @@ async fn tool_loop_no_tools() {
             image_content: &[],
             model: "test-model",
             temperature: 0.0,
+            thinking: ThinkingLevel::Off,
+            request_options: None,
             ctx: &ctx,
             stream_sink: None,
             conversation_history: &[],
@@ -381,3 +408,267 @@ async fn tool_loop_no_tools() {
     let seen_prompts = provider.seen_system_prompts();
     assert_eq!(seen_prompts[0].as_deref(), Some("system"));
 }
+
+/// Sleeps briefly and records how many calls were running at once.
+struct ProbeTool {
+    name: &'static str,
+    parallel_safe: bool,
+    running: Arc<std::sync::atomic::AtomicUsize>,
+    peak: Arc<std::sync::atomic::AtomicUsize>,
+}
+
+impl asteroniris::tools::Tool for ProbeTool {
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    fn description(&self) -> &str {
+        "probe"
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        json!({"type": "object"})
+    }
+
+    fn is_parallel_safe(&self) -> bool {
+        self.parallel_safe
+    }
+
+    fn execute<'a>(
+        &'a self,
+        args: serde_json::Value,
+        _ctx: &'a ExecutionContext,
+    ) -> Pin<Box<dyn Future<Output = Result<asteroniris::tools::ToolResult>> + Send + 'a>> {
+        use std::sync::atomic::Ordering;
+        Box::pin(async move {
+            let now = self.running.fetch_add(1, Ordering::SeqCst) + 1;
+            self.peak.fetch_max(now, Ordering::SeqCst);
+            // Later calls finish first so ordering is not an accident of timing.
+            let delay = 60 - args["n"].as_u64().unwrap_or(0) * 15;
+            tokio::time::sleep(std::time::Duration::from_millis(delay)).await;
+            self.running.fetch_sub(1, Ordering::SeqCst);
+            Ok(asteroniris::tools::ToolResult {
+                success: true,
+                output: format!("{}-{}", self.name, args["n"]),
+                error: None,
+                attachments: Vec::new(),
+            })
+        })
+    }
+}
+
+fn probe_response(name: &str, count: u64) -> ProviderResponse {
+    ProviderResponse {
+        text: String::new(),
+        input_tokens: None,
+        output_tokens: None,
+        model: None,
+        content_blocks: (0..count)
+            .map(|n| ContentBlock::ToolUse {
+                id: format!("toolu_{n}"),
+                name: name.to_string(),
+                input: json!({"n": n}),
+            })
+            .collect(),
+        stop_reason: Some(StopReason::ToolUse),
+    }
+}
+
+async fn run_probe(parallel_safe: bool) -> (usize, Vec<String>) {
+    let (_tmp, _, ctx) = test_registry_and_ctx();
+    let running = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+    let peak = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+    let mut registry = ToolRegistry::new(default_middleware_chain());
+    registry.register(Box::new(ProbeTool {
+        name: "probe",
+        parallel_safe,
+        running,
+        peak: Arc::clone(&peak),
+    }));
+    let provider = MockProvider::new(vec![probe_response("probe", 3), end_turn_text("done")]);
+
+    let result = ToolLoop::new(Arc::new(registry), 8)
+        .run(ToolLoopRunParams {
+            provider: &provider,
+            system_prompt: "system",
+            user_message: "probe",
+            image_content: &[],
+            model: "test-model",
+            temperature: 0.0,
+            thinking: ThinkingLevel::Off,
+            request_options: None,
+            ctx: &ctx,
+            stream_sink: None,
+            conversation_history: &[],
+            hooks: &[],
+        })
+        .await
+        .expect("tool loop should run");
+    assert_eq!(result.stop_reason, LoopStopReason::Completed);
+
+    let result_ids = provider.seen_messages()[1]
+        .iter()
+        .flat_map(|message| &message.content)
+        .filter_map(|block| match block {
+            ContentBlock::ToolResult { tool_use_id, .. } => Some(tool_use_id.clone()),
+            _ => None,
+        })
+        .collect();
+    (peak.load(std::sync::atomic::Ordering::SeqCst), result_ids)
+}
+
+#[tokio::test]
+async fn tool_loop_runs_parallel_safe_calls_concurrently_in_order() {
+    let (peak, result_ids) = run_probe(true).await;
+    assert!(
+        peak > 1,
+        "parallel-safe calls should overlap, peak was {peak}"
+    );
+    assert_eq!(result_ids, vec!["toolu_0", "toolu_1", "toolu_2"]);
+}
+
+#[tokio::test]
+async fn tool_loop_runs_other_calls_one_at_a_time() {
+    let (peak, result_ids) = run_probe(false).await;
+    assert_eq!(peak, 1);
+    assert_eq!(result_ids, vec!["toolu_0", "toolu_1", "toolu_2"]);
+}
+
+#[tokio::test]
+async fn tool_loop_truncates_long_output_behind_a_handle() {
+    let (tmp, mut registry, ctx) = test_registry_and_ctx();
+    Arc::get_mut(&mut registry)
+        .expect("registry is not shared yet")
+        .register(Box::new(ToolOutputTool::new()));
+    let long = format!("{}tail-marker", "line of build output\n".repeat(500));
+    std::fs::write(tmp.path().join("build.log"), &long).expect("write test file");
+    let provider = MockProvider::new(vec![
+        tool_use_response("toolu_1", "file_read", json!({"path": "build.log"})),
+        end_turn_text("done"),
+    ]);
+    let context = ContextConfig {
+        max_tool_output_chars: 1_000,
+        ..ContextConfig::default()
+    };
+
+    let result = ToolLoop::new(Arc::clone(&registry), 8)
+        .with_context_config(context)
+        .run(ToolLoopRunParams {
+            provider: &provider,
+            system_prompt: "system",
+            user_message: "read the log",
+            image_content: &[],
+            model: "test-model",
+            temperature: 0.0,
+            thinking: ThinkingLevel::Off,
+            request_options: None,
+            ctx: &ctx,
+            stream_sink: None,
+            conversation_history: &[],
+            hooks: &[],
+        })
+        .await
+        .expect("tool loop should run");
+    assert_eq!(result.stop_reason, LoopStopReason::Completed);
+    assert!(result.tool_calls[0].result.output.contains(&long));
+
+    let sent = provider.seen_messages()[1]
+        .iter()
+        .flat_map(|message| &message.content)
+        .find_map(|block| match block {
+            ContentBlock::ToolResult { content, .. } => Some(content.clone()),
+            _ => None,
+        })
+        .expect("tool result sent to the model");
+    assert!(sent.len() < 1_500, "sent {} chars", sent.len());
+    assert!(sent.contains("tail-marker"));
+
+    let start = sent.find("handle \"").expect("notice names a handle") + "handle \"".len();
+    let handle = &sent[start..start + sent[start..].find('"').unwrap()];
+    let read = registry
+        .execute(
+            "tool_output",
+            json!({"handle": handle, "offset": 0, "limit": 16_000}),
+            &ctx,
+        )
+        .await
+        .expect("tool_output should run");
+    assert!(read.success, "{:?}", read.error);
+    assert!(read.output.contains(&long));
+}
+
+#[tokio::test]
+async fn tool_loop_sends_thinking_level_and_configured_output_limit() {
+    let (_tmp, registry, ctx) = test_registry_and_ctx();
+    let provider = MockProvider::new(vec![end_turn_text("done")]);
+    let context = ContextConfig {
+        max_output_tokens: [("test-".to_string(), 12_000)].into(),
+        ..ContextConfig::default()
+    };
+
+    ToolLoop::new(registry, 4)
+        .with_context_config(context)
+        .run(ToolLoopRunParams {
+            provider: &provider,
+            system_prompt: "system",
+            user_message: "think hard",
+            image_content: &[],
+            model: "test-model",
+            temperature: 0.0,
+            thinking: ThinkingLevel::High,
+            request_options: None,
+            ctx: &ctx,
+            stream_sink: None,
+            conversation_history: &[],
+            hooks: &[],
+        })
+        .await
+        .expect("tool loop should run");
+
+    let options = provider.seen_options();
+    assert_eq!(options[0].thinking, ThinkingLevel::High);
+    assert_eq!(options[0].max_tokens, Some(12_000));
+}
+
+#[tokio::test]
+async fn tool_loop_forwards_request_options_and_reports_truncation() {
+    let (_tmp, registry, ctx) = test_registry_and_ctx();
+    let provider = MockProvider::new(vec![ProviderResponse {
+        stop_reason: Some(StopReason::MaxTokens),
+        ..end_turn_text("cut")
+    }]);
+    let context = ContextConfig {
+        max_output_tokens: [("test-".to_string(), 100)].into(),
+        ..ContextConfig::default()
+    };
+    let requested = ChatOptions::new(1.5)
+        .with_max_tokens(500)
+        .with_stop_sequences(vec!["END".to_string()])
+        .with_seed(7);
+
+    let result = ToolLoop::new(registry, 4)
+        .with_context_config(context)
+        .run(ToolLoopRunParams {
+            provider: &provider,
+            system_prompt: "system",
+            user_message: "hello",
+            image_content: &[],
+            model: "test-model",
+            temperature: 0.2,
+            thinking: ThinkingLevel::Off,
+            request_options: Some(&requested),
+            ctx: &ctx,
+            stream_sink: None,
+            conversation_history: &[],
+            hooks: &[],
+        })
+        .await
+        .expect("tool loop should run");
+
+    let options = provider.seen_options();
+    assert!((options[0].temperature - 0.2).abs() < f64::EPSILON);
+    assert_eq!(options[0].max_tokens, Some(100));
+    assert_eq!(options[0].stop_sequences, ["END"]);
+    assert_eq!(options[0].seed, Some(7));
+    assert!(result.output_truncated);
+}
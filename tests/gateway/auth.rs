@@ -113,7 +113,7 @@ impl GatewayTestServer {
 
         let host = "127.0.0.1".to_string();
         let handle = tokio::spawn(async move {
-            run_gateway_with_listener(&host, listener, Arc::new(config)).await
+            run_gateway_with_listener(&host, listener, Arc::new(config), false).await
         });
 
         wait_until_gateway_ready(port).await;
@@ -597,6 +597,34 @@ async fn gateway_rejects_expired_persisted_token_from_config_ttl() {
     assert_eq!(body.get("paired"), Some(&Value::Bool(false)));
 }
 
+#[tokio::test]
+async fn gateway_metrics_require_authentication() {
+    let server = GatewayTestServer::start(
+        true,
+        vec![hash_token("token-abc")],
+        "gateway-shared-secret",
+        GatewayDefenseMode::Enforce,
+        false,
+    )
+    .await;
+    let client = reqwest::Client::new();
+
+    let anonymous = client
+        .get(server.url("/metrics"))
+        .send()
+        .await
+        .expect("metrics request should complete");
+    assert_eq!(anonymous.status(), StatusCode::UNAUTHORIZED);
+
+    let paired = client
+        .get(server.url("/metrics"))
+        .header("Authorization", "Bearer token-abc")
+        .send()
+        .await
+        .expect("metrics request should complete");
+    assert_eq!(paired.status(), StatusCode::OK);
+}
+
 struct CountingObserver {
     events: Arc<AtomicUsize>,
     metrics: Arc<AtomicUsize>,
@@ -635,6 +663,7 @@ impl Observer for CountingObserver {
 fn observability_deterministic_factory_and_fanout_path() {
     let cfg = ObservabilityConfig {
         backend: "prometheus".to_string(),
+        ..ObservabilityConfig::default()
     };
     let observer = create_observer(&cfg);
     assert_eq!(observer.name(), "prometheus");
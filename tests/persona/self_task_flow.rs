@@ -9,7 +9,7 @@ use asteroniris::persona::state_persistence::BackendCanonicalStateHeaderPersiste
 use asteroniris::platform::cron::{self, CronJobKind, CronJobOrigin};
 use asteroniris::providers::Provider;
 use asteroniris::security::SecurityPolicy;
-use asteroniris::security::policy::TenantPolicyContext;
+use asteroniris::security::policy::{AutonomyLevel, TenantPolicyContext};
 use std::future::Future;
 use std::pin::Pin;
 use tempfile::TempDir;
@@ -150,11 +150,27 @@ async fn persona_reflect_self_task_flows_through_scheduler_planner_route() {
     );
     assert!(queued[0].command.starts_with("plan:"));
 
+    // Unattended, the self task's checkpoint has nobody to approve it.
     let (executed, output) =
         asteroniris::platform::cron::scheduler::execute_job_once_for_integration(
             &config, &security, &queued[0],
         )
         .await;
+    assert!(!executed, "{output}");
+    assert!(output.contains("route=agent-planner"), "{output}");
+    assert!(output.contains("success=false"), "{output}");
+
+    let full_security = SecurityPolicy {
+        autonomy: AutonomyLevel::Full,
+        ..security.clone()
+    };
+    let (executed, output) =
+        asteroniris::platform::cron::scheduler::execute_job_once_for_integration(
+            &config,
+            &full_security,
+            &queued[0],
+        )
+        .await;
     assert!(executed, "{output}");
     assert!(output.contains("route=agent-planner"), "{output}");
     assert!(output.contains("success=true"), "{output}");
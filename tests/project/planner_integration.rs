@@ -3,6 +3,7 @@ use std::sync::{Arc, Mutex};
 use anyhow::Result;
 use asteroniris::planner::{PlanExecutor, PlanParser, ToolStepRunner};
 use asteroniris::security::SecurityPolicy;
+use asteroniris::security::policy::AutonomyLevel;
 use asteroniris::tools::ToolRegistry;
 use asteroniris::tools::ToolResult;
 use asteroniris::tools::middleware::ExecutionContext;
@@ -69,7 +70,12 @@ impl Tool for FlakyEchoTool {
 }
 
 fn test_runner() -> ToolStepRunner {
-    let security = Arc::new(SecurityPolicy::default());
+    // Checkpoints need an approver under supervised autonomy; these tests
+    // exercise retries, so they run unattended at full autonomy.
+    let security = Arc::new(SecurityPolicy {
+        autonomy: AutonomyLevel::Full,
+        ..SecurityPolicy::default()
+    });
     let ctx = ExecutionContext::from_security(security);
     let mut registry = ToolRegistry::new(vec![]);
     registry.register(Box::new(FlakyEchoTool::new()));
@@ -1,9 +1,12 @@
 use std::collections::HashSet;
+use std::future::Future;
+use std::pin::Pin;
 use std::sync::Arc;
 
 use asteroniris::agent::augment_prompt_with_trust_boundary;
 use asteroniris::security::{
-    AutonomyLevel, EntityRateLimiter, GrantScope, PermissionGrant, PermissionStore, SecurityPolicy,
+    ApprovalBroker, ApprovalDecision, ApprovalGate, ApprovalRequest, AutonomyLevel,
+    EntityRateLimiter, GrantScope, PermissionGrant, PermissionStore, SecurityPolicy,
 };
 use asteroniris::tools::middleware::{ExecutionContext, default_middleware_chain};
 use asteroniris::tools::{FileReadTool, ShellTool, ToolRegistry};
@@ -201,3 +204,54 @@ fn progressive_grant_session() {
     assert!(store.is_granted("shell", "cargo test"));
     assert!(!store.is_granted("shell", "python x"));
 }
+
+struct DenyAll;
+
+impl ApprovalBroker for DenyAll {
+    fn request_approval<'a>(
+        &'a self,
+        _request: &'a ApprovalRequest,
+    ) -> Pin<Box<dyn Future<Output = anyhow::Result<ApprovalDecision>> + Send + 'a>> {
+        Box::pin(async { Ok(ApprovalDecision::Deny) })
+    }
+}
+
+#[tokio::test]
+async fn supervised_tool_call_waits_for_approval() {
+    let (tmp, registry, mut ctx) = test_registry_and_ctx();
+    std::fs::write(tmp.path().join("notes.txt"), "hello").expect("write test file");
+    ctx.autonomy_level = AutonomyLevel::Supervised;
+    ctx.approval = Some(Arc::new(ApprovalGate::new(Arc::new(DenyAll))));
+
+    let error = registry
+        .execute("shell", json!({"command": "ls"}), &ctx)
+        .await
+        .expect_err("denied shell call");
+    assert!(error.to_string().contains("approval denied"));
+
+    let read = registry
+        .execute("file_read", json!({"path": "notes.txt"}), &ctx)
+        .await
+        .expect("read-only tools skip approval");
+    assert!(read.success);
+}
+
+#[tokio::test]
+async fn supervised_tool_call_without_an_approver_is_denied() {
+    let (tmp, registry, mut ctx) = test_registry_and_ctx();
+    std::fs::write(tmp.path().join("notes.txt"), "hello").expect("write test file");
+    ctx.autonomy_level = AutonomyLevel::Supervised;
+    ctx.approval = None;
+
+    let error = registry
+        .execute("shell", json!({"command": "ls"}), &ctx)
+        .await
+        .expect_err("no approver for shell call");
+    assert!(error.to_string().contains("no approver"));
+
+    let read = registry
+        .execute("file_read", json!({"path": "notes.txt"}), &ctx)
+        .await
+        .expect("read-only tools skip approval");
+    assert!(read.success);
+}